@@ -0,0 +1,26 @@
+// Brown-Conrady radial distortion, as seen through an underwater camera port.
+// Each output pixel samples the source at
+//   uv_center + (uv - uv_center) * (1 + k1 * r^2 + k2 * r^4)
+// so positive coefficients give barrel distortion. Corners sample past the frame edge; the
+// clamp-to-edge sampler repeats the border instead of showing black.
+
+@group(0) @binding(0) var src_tex: texture_2d<f32>;
+@group(0) @binding(1) var src_samp: sampler;
+
+struct LensDistortionParams {
+    k1: f32,
+    k2: f32,
+    _pad0: f32,
+    _pad1: f32,
+};
+@group(1) @binding(0) var<uniform> params: LensDistortionParams;
+
+const UV_CENTER: vec2<f32> = vec2<f32>(0.5, 0.5);
+
+@fragment
+fn fragment(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let offset = uv - UV_CENTER;
+    let r2 = dot(offset, offset);
+    let scale = 1.0 + params.k1 * r2 + params.k2 * r2 * r2;
+    return textureSample(src_tex, src_samp, UV_CENTER + offset * scale);
+}
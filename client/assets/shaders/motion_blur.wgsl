@@ -0,0 +1,43 @@
+// Screen-space motion blur driven by the motion-vector prepass.
+// Motion vectors are the per-pixel UV delta between this frame's and last frame's
+// clip-space position (current - previous), so we smear back along -velocity.
+
+@group(0) @binding(0) var src_tex: texture_2d<f32>;
+@group(0) @binding(1) var src_samp: sampler;
+
+@group(1) @binding(0) var velocity_tex: texture_2d<f32>;
+
+struct MotionBlurParams {
+    strength: f32,  // 0..1, fraction of the frame's motion to smear over
+    _pad0: f32,
+    _pad1: f32,
+    _pad2: f32,
+};
+@group(2) @binding(0) var<uniform> params: MotionBlurParams;
+
+const SAMPLES: i32 = 4;
+
+@fragment
+fn fragment(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let base = textureSample(src_tex, src_samp, uv);
+    let velocity = textureSample(velocity_tex, src_samp, uv).rg * params.strength;
+
+    // Skip sub-pixel motion so static views stay sharp
+    let dims = vec2<f32>(textureDimensions(src_tex, 0));
+    if dot(velocity * dims, velocity * dims) < 1.0 {
+        return base;
+    }
+
+    // Center sample plus 4 taps along the velocity, weighted down with distance from center
+    var acc = base.rgb;
+    var weight_total = 1.0;
+    for (var i = 1; i <= SAMPLES; i = i + 1) {
+        let t = f32(i) / f32(SAMPLES);
+        let w = 1.0 - 0.75 * t;
+        let sample_uv = clamp(uv - velocity * t, vec2<f32>(0.0), vec2<f32>(1.0));
+        acc += textureSample(src_tex, src_samp, sample_uv).rgb * w;
+        weight_total += w;
+    }
+
+    return vec4<f32>(acc / weight_total, base.a);
+}
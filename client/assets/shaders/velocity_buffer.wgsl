@@ -0,0 +1,28 @@
+// Camera velocity buffer: reprojects each pixel of the prepass depth into last frame's clip
+// space and writes the UV delta (current - previous), the same convention as Bevy's
+// motion-vector prepass. Mirrors `reproject_velocity` in velocity_buffer.rs.
+
+@group(0) @binding(0) var depth_tex: texture_depth_2d;
+
+struct VelocityUniform {
+    world_from_clip: mat4x4<f32>,
+    prev_clip_from_world: mat4x4<f32>,
+};
+@group(0) @binding(1) var<uniform> velocity_uniform: VelocityUniform;
+
+@fragment
+fn fragment(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let dims = vec2<f32>(textureDimensions(depth_tex));
+    let depth = textureLoad(depth_tex, vec2<i32>(uv * dims), 0);
+    let ndc = vec3<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, depth);
+
+    // Stay homogeneous: far-plane pixels (reverse-Z depth 0) unproject to directions
+    let world = velocity_uniform.world_from_clip * vec4<f32>(ndc, 1.0);
+    let prev_clip = velocity_uniform.prev_clip_from_world * world;
+    if abs(prev_clip.w) < 1e-6 {
+        return vec4<f32>(0.0);
+    }
+    let prev_ndc = prev_clip.xy / prev_clip.w;
+    let velocity = (ndc.xy - prev_ndc) * vec2<f32>(0.5, -0.5);
+    return vec4<f32>(velocity, 0.0, 0.0);
+}
@@ -4,6 +4,7 @@ struct ViewUniform {
     camera_position: vec4<f32>,
     screen_size: vec4<f32>,
     params: vec4<f32>,
+    tuning: vec4<f32>,               // x: distance falloff, y: angular softness, z: extinction
 };
 
 struct ConeUniform {
@@ -261,6 +262,11 @@ const MIN_MARCH_STEPS: u32 = 4u;      // minimum number of samples per ray
 const MAX_MARCH_STEPS: u32 = 64u;     // maximum number of samples per ray
 const TARGET_STEP_LENGTH: f32 = 0.5;  // desired spacing (in metres) between samples
 const EPS: f32 = 1e-6;
+// `debug_mode` of the step-count heat map (`VolumetricLightingMode::DebugHeatmap`); modes 1-5
+// are the inspector's diagnostics below
+const DEBUG_STEP_HEATMAP: u32 = 6u;
+// Step count drawn as full red in the heat map
+const STEP_HEATMAP_FULL_SCALE: f32 = 32.0;
 
 // Diagnostic information returned alongside the accumulated colour.
 struct MarchResult {
@@ -269,6 +275,7 @@ struct MarchResult {
     weight_ratio: f32,            // average angular/radial weight per step
     clamped_length_ratio: f32,    // (depth-clamped length) / (cone range)
     raw_length_ratio: f32,        // (analytic length) / (cone range)
+    steps: u32,                   // march iterations actually run
 }
 
 // Integrate the volumetric lighting along the camera ray inside the cone.
@@ -285,18 +292,18 @@ fn march_cone(
     var t_start = interval.x;
     var t_end   = interval.y;
     if t_end <= t_start + EPS {
-        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, 0.0);
+        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, 0.0, 0u);
     }
 
     let raw_length = max(t_end - t_start, 0.0);
     let raw_length_ratio = clamp(raw_length / cone.range, 0.0, 1.0);
     if raw_length_ratio <= EPS {
-        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, 0.0);
+        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, 0.0, 0u);
     }
 
     t_end = min(t_end, camera_depth);
     if t_end <= t_start + EPS {
-        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, raw_length_ratio);
+        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, raw_length_ratio, 0u);
     }
 
     let desired_steps = clamp(
@@ -307,12 +314,12 @@ fn march_cone(
 
     let clamped_length = clamp(min(camera_depth - t_start, raw_length), 0.0, raw_length);
     if clamped_length <= EPS {
-        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, raw_length_ratio);
+        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, raw_length_ratio, 0u);
     }
 
     let dt = clamped_length / f32(desired_steps);
     if dt <= EPS {
-        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, raw_length_ratio);
+        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, raw_length_ratio, 0u);
     }
 
     let clamped_length_ratio = clamp(clamped_length / cone.range, 0.0, 1.0);
@@ -325,9 +332,11 @@ fn march_cone(
     var weight_sum = 0.0;
     let samples_f = f32(desired_steps);
 
+    var steps_taken = 0u;
     for (var step: u32 = 0u; step < desired_steps; step = step + 1u) {
         let sample_t = t_start + (f32(step) + 0.5) * dt;
         if sample_t > t_start + clamped_length { break; }
+        steps_taken += 1u;
 
         let sample_pos = camera_pos + ray_dir * sample_t;
         let rel  = sample_pos - cone.apex;
@@ -385,9 +394,73 @@ fn march_cone(
         weight_ratio = clamp(weight_sum / samples_f, 0.0, 1.0);
     }
 
-    return MarchResult(accum, hit_ratio, weight_ratio, clamped_length_ratio, raw_length_ratio);
+    return MarchResult(accum, hit_ratio, weight_ratio, clamped_length_ratio, raw_length_ratio, steps_taken);
 }
 
+#ifdef GOD_RAY
+// Sunlight entering through the volume's top face. Shafts come from the rippled surface
+// focusing light, so brightness is modulated by where the sample's sun ray crossed it.
+fn surface_shaft_pattern(xz: vec2<f32>) -> f32 {
+    let a = sin(dot(xz, vec2<f32>(0.37, 0.21)));
+    let b = sin(dot(xz, vec2<f32>(-0.19, 0.43)) + 1.7);
+    let c = sin(dot(xz, vec2<f32>(0.11, -0.29)) + 4.1);
+    return pow(clamp(0.5 + 0.5 * a * b + 0.25 * c, 0.0, 1.0), 3.0);
+}
+
+// Integrate sunlight scattered toward the camera along `[0, t_end]`. Instead of the cone's
+// apex/angle test, a sample is lit when it lies below the horizontal plane through the top
+// face (`cone_uniform.apex`); the light reaching it fades with the path travelled below that
+// plane along the sun direction, using the same extinction as the view ray.
+fn march_god_ray(
+    camera_pos: vec3<f32>,
+    ray_dir: vec3<f32>,
+    t_end: f32,
+    extinction: f32,
+) -> MarchResult {
+    let surface = cone_uniform.apex.xyz;
+    let sun = normalize(cone_uniform.direction_range.xyz);
+    let volume_depth = max(cone_uniform.direction_range.w, 0.001);
+    let base_color = cone_uniform.color_intensity.xyz;
+    let intensity = cone_uniform.color_intensity.w;
+    let density = max(cone_uniform.angles.x, 0.0);
+    // Keep grazing suns from producing unbounded path lengths
+    let sun_down = max(-sun.y, 0.05);
+    if t_end <= EPS {
+        return MarchResult(vec3<f32>(0.0), 0.0, 0.0, 0.0, 0.0, 0u);
+    }
+
+    let steps = clamp(u32(ceil(t_end / TARGET_STEP_LENGTH)), MIN_MARCH_STEPS, MAX_MARCH_STEPS);
+    let dt = t_end / f32(steps);
+    // Light travels along `sun` and scatters back along `-ray_dir`; forward scattering dominates
+    let phase = hg_phase(dot(sun, -ray_dir), 0.6);
+
+    var accum = vec3<f32>(0.0);
+    var transmittance = 1.0;
+    var lit = 0.0;
+    var max_depth = 0.0;
+    var steps_taken = 0u;
+    for (var step: u32 = 0u; step < steps; step = step + 1u) {
+        steps_taken += 1u;
+        let sample_pos = camera_pos + ray_dir * ((f32(step) + 0.5) * dt);
+        let depth_below = surface.y - sample_pos.y;
+        if depth_below >= 0.0 {
+            let light_path = depth_below / sun_down;
+            let entry = sample_pos - sun * light_path;
+            let t_light = exp(-extinction * light_path);
+            let shaft = surface_shaft_pattern(entry.xz);
+            accum += base_color * (intensity * density * phase * t_light * shaft) * transmittance * dt;
+            lit += 1.0;
+            max_depth = max(max_depth, depth_below);
+        }
+        transmittance *= exp(-extinction * dt);
+        if transmittance <= 1e-3 { break; }
+    }
+
+    let lit_ratio = lit / f32(steps);
+    return MarchResult(accum, lit_ratio, lit_ratio, clamp(max_depth / volume_depth, 0.0, 1.0), 1.0, steps_taken);
+}
+#endif
+
 @vertex
 fn vertex(@location(0) position: vec3<f32>) -> VertexOutput {
     let local = vec4<f32>(position, 1.0);
@@ -408,6 +481,37 @@ fn fragment(in: VertexOutput) -> FragmentOutput {
         return FragmentOutput(vec4<f32>(0.0, 0.0, 0.0, 1.0));
     }
 
+#ifdef GOD_RAY
+    // Back faces of the volume: march from the camera to the far face or the scene, whichever
+    // is nearer. Reverse-Z, so a depth of 0 is the far plane.
+    let gr_camera_pos = view_uniform.camera_position.xyz;
+    var gr_ray = in.world_position - gr_camera_pos;
+    let gr_far = length(gr_ray);
+    if gr_far <= EPS {
+        return FragmentOutput(vec4<f32>(0.0, 0.0, 0.0, 1.0));
+    }
+    gr_ray = gr_ray / gr_far;
+    let gr_uv = in.clip_position.xy * view_uniform.screen_size.zw;
+    let gr_ndc = vec2<f32>(gr_uv.x * 2.0 - 1.0, 1.0 - gr_uv.y * 2.0);
+    let gr_pixel = clamp(vec2<i32>(in.clip_position.xy), vec2<i32>(0, 0), vec2<i32>(screen_size) - vec2<i32>(1, 1));
+    let gr_depth = textureLoad(view_depth, gr_pixel, 0);
+    var gr_end = gr_far;
+    if gr_depth > 1e-6 {
+        let scene_world = world_from_ndc(vec3<f32>(gr_ndc, gr_depth));
+        gr_end = min(gr_end, max(dot(scene_world - gr_camera_pos, gr_ray), 0.0));
+    }
+    let gr_result = march_god_ray(gr_camera_pos, gr_ray, gr_end, max(view_uniform.tuning.z, 0.0));
+    let gr_debug = u32(view_uniform.params.y + 0.5);
+    if gr_debug == DEBUG_STEP_HEATMAP {
+        return FragmentOutput(vec4<f32>(f32(gr_result.steps) / STEP_HEATMAP_FULL_SCALE, 0.0, 0.0, 1.0));
+    }
+    if gr_debug == 1u || gr_debug == 2u {
+        let v = pow(gr_result.hit_ratio, 0.2);
+        return FragmentOutput(vec4<f32>(v, 0.2 * (1.0 - v), 1.0 - v, 1.0));
+    }
+    return FragmentOutput(vec4<f32>(gr_result.color, 1.0));
+#else
+
     let scatter_factor = max(view_uniform.params.x, 0.0);
     if scatter_factor <= 0.0 {
         return FragmentOutput(vec4<f32>(0.0, 0.0, 0.0, 1.0));
@@ -446,6 +550,11 @@ fn fragment(in: VertexOutput) -> FragmentOutput {
 
     let result = march_cone(camera_pos, ray_dir, camera_depth, scatter_strength);
 
+    // Heat map of march iterations: black = none, red = STEP_HEATMAP_FULL_SCALE or more
+    if debug_mode == DEBUG_STEP_HEATMAP {
+        return FragmentOutput(vec4<f32>(f32(result.steps) / STEP_HEATMAP_FULL_SCALE, 0.0, 0.0, 1.0));
+    }
+
     var output_color = result.color;
     if debug_mode == 1u {
         let v = pow(result.hit_ratio, 0.2);
@@ -465,4 +574,5 @@ fn fragment(in: VertexOutput) -> FragmentOutput {
     }
 
     return FragmentOutput(vec4<f32>(output_color, 1.0));
+#endif
 }
@@ -0,0 +1,200 @@
+//! CPU draw overhead of 1024 dust motes per frame: one entity and one draw per mote, as
+//! `WaterFxPlugin` used to spawn them, versus `WaterFxPlugin`'s `pack_mote_instances` filling
+//! `MoteInstanceBuffer` for `MoteRenderPlugin`'s single instanced draw.
+//!
+//! No GPU is involved, and mote simulation (`tick_motes`), which costs the same either way,
+//! stays out of both frames. The per-entity frame does the CPU work each mote needed before its
+//! draw could be recorded: the transform write, hierarchy propagation, a sorted transparent
+//! phase item, and a per-draw model matrix plus draw command. The instanced frame runs the
+//! shipped packing system, stages the instance bytes and records one draw. Both run their frame
+//! schedules single-threaded, so task scheduling stays out of the ratio too.
+//!
+//! `cargo bench -p client --bench mote_instancing` runs the criterion group and then fails
+//! unless the instanced frame is at least `GATE_MIN_SPEEDUP` times cheaper.
+
+use std::time::{Duration, Instant};
+
+use bevy::ecs::schedule::{ExecutorKind, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::transform::TransformPlugin;
+use client::scene::render::mote_instancing::MoteInstanceBuffer;
+use client::scene::water::{pack_mote_instances, Bubbles, MoteField};
+use criterion::Criterion;
+
+const MOTES: usize = 1024;
+const GATE_MIN_SPEEDUP: f64 = 10.0;
+
+/// One queued draw: what a transparent phase item and its mesh uniform carry.
+struct DrawItem {
+    entity: Entity,
+    distance: f32,
+    model: Mat4,
+}
+
+#[derive(Resource, Default)]
+struct QueuedDraws(Vec<DrawItem>);
+
+/// What recording the queued draws leaves behind: per-draw uniforms and instance bytes to
+/// upload, and `(entity, instance count)` draw commands.
+#[derive(Resource, Default)]
+struct RecordedFrame {
+    uniforms: Vec<[f32; 16]>,
+    staging: Vec<u8>,
+    draws: Vec<(Entity, u32)>,
+}
+
+fn record_draws(
+    queued: Res<QueuedDraws>,
+    instances: Option<Res<MoteInstanceBuffer>>,
+    mut frame: ResMut<RecordedFrame>,
+) {
+    let frame = &mut *frame;
+    frame.uniforms.clear();
+    frame.staging.clear();
+    frame.draws.clear();
+    let instance_count = match &instances {
+        Some(buffer) => {
+            frame
+                .staging
+                .extend_from_slice(bytemuck::cast_slice(&buffer.instances));
+            buffer.instances.len() as u32
+        }
+        None => 1,
+    };
+    for item in &queued.0 {
+        frame.uniforms.push(item.model.to_cols_array());
+        frame.draws.push((item.entity, instance_count));
+    }
+}
+
+/// A bare app running `Update`, `PostUpdate` and `Last` on one thread, recording draws last.
+fn frame_app() -> App {
+    let mut app = App::new();
+    for label in [Update.intern(), PostUpdate.intern(), Last.intern()] {
+        app.edit_schedule(label, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+    }
+    app.init_resource::<QueuedDraws>()
+        .init_resource::<RecordedFrame>()
+        .add_systems(Last, record_draws);
+    app
+}
+
+fn populated_field() -> MoteField {
+    let mut field = MoteField::default();
+    field.populate(Vec3::new(0.0, -5.0, 0.0), MOTES);
+    field
+}
+
+/// A mote drawn as its own entity, at the position the simulation left it.
+#[derive(Component)]
+struct MoteEntity(Vec3);
+
+fn per_entity_app() -> App {
+    let mut app = frame_app();
+    app.add_plugins(TransformPlugin)
+        .add_systems(Update, sync_mote_entities)
+        .add_systems(
+            PostUpdate,
+            queue_mote_entities.after(TransformSystem::TransformPropagate),
+        );
+    let parent = app.world_mut().spawn(Transform::default()).id();
+    for mote in populated_field().motes() {
+        app.world_mut().spawn((
+            Transform::from_translation(mote.pos),
+            MoteEntity(mote.pos),
+            ChildOf(parent),
+        ));
+    }
+    app.finish();
+    app.cleanup();
+    app
+}
+
+fn sync_mote_entities(mut q: Query<(&mut Transform, &MoteEntity)>) {
+    for (mut transform, mote) in &mut q {
+        transform.translation = mote.0;
+    }
+}
+
+fn queue_mote_entities(
+    q: Query<(Entity, &GlobalTransform), With<MoteEntity>>,
+    mut draws: ResMut<QueuedDraws>,
+) {
+    draws.0.clear();
+    draws.0.extend(q.iter().map(|(entity, global)| DrawItem {
+        entity,
+        distance: global.translation().length_squared(),
+        model: global.compute_matrix(),
+    }));
+    draws.0.sort_by(|a, b| b.distance.total_cmp(&a.distance));
+}
+
+fn instanced_app() -> App {
+    let mut app = frame_app();
+    app.insert_resource(populated_field())
+        .init_resource::<Bubbles>()
+        .init_resource::<MoteInstanceBuffer>()
+        .add_systems(Update, pack_mote_instances)
+        .add_systems(PostUpdate, queue_mote_batch);
+    let batch = app.world_mut().spawn(Transform::default()).id();
+    app.world_mut().insert_resource(BatchEntity(batch));
+    app.finish();
+    app.cleanup();
+    app
+}
+
+#[derive(Resource)]
+struct BatchEntity(Entity);
+
+fn queue_mote_batch(batch: Res<BatchEntity>, mut draws: ResMut<QueuedDraws>) {
+    draws.0.clear();
+    draws.0.push(DrawItem {
+        entity: batch.0,
+        distance: 0.0,
+        model: Mat4::IDENTITY,
+    });
+}
+
+fn frames_per_sec(app: &mut App) -> f64 {
+    let mut frames = 0_u64;
+    let began = Instant::now();
+    while began.elapsed() < Duration::from_secs(2) {
+        app.update();
+        frames += 1;
+    }
+    frames as f64 / began.elapsed().as_secs_f64()
+}
+
+fn main() {
+    let mut per_entity = per_entity_app();
+    let mut instanced = instanced_app();
+    per_entity.update();
+    instanced.update();
+    let draws = |app: &App| app.world().resource::<RecordedFrame>().draws.clone();
+    assert_eq!(draws(&per_entity).len(), MOTES);
+    assert!(draws(&per_entity).iter().all(|&(_, n)| n == 1));
+    assert_eq!(draws(&instanced).len(), 1);
+    assert_eq!(draws(&instanced)[0].1, MOTES as u32);
+
+    let mut c = Criterion::default().configure_from_args();
+    let mut group = c.benchmark_group("mote_frame_1024");
+    group.bench_function("per_entity", |b| b.iter(|| per_entity.update()));
+    group.bench_function("instanced", |b| b.iter(|| instanced.update()));
+    group.finish();
+    c.final_summary();
+
+    // `cargo test --benches` runs each benchmark once without `--bench`; only gate real runs
+    if !std::env::args().any(|arg| arg == "--bench") {
+        return;
+    }
+    let speedup = frames_per_sec(&mut instanced) / frames_per_sec(&mut per_entity);
+    println!(
+        "mote_frame_1024 gate: instanced is {speedup:.1}x cheaper (floor {GATE_MIN_SPEEDUP}x)"
+    );
+    if speedup < GATE_MIN_SPEEDUP {
+        eprintln!("instanced motes are no longer {GATE_MIN_SPEEDUP}x cheaper than one entity each");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,162 @@
+//! `simulate_submarine` predicting sixteen submarines in a headless Bevy app, built with the
+//! `parallel_physics` feature so the subs are stepped through `Query::par_iter_mut`.
+//!
+//! `cargo bench -p client --features parallel_physics --bench parallel_physics` runs the
+//! criterion group on the default compute task pool. On a machine with at least
+//! `GATE_MIN_CORES` cores it then times the same app in a child process whose compute pool has
+//! a single thread, where `par_iter_mut` falls back to walking the query in order, and fails
+//! unless the default pool is more than `GATE_MIN_SPEEDUP` times faster.
+
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use bevy::app::TaskPoolPlugin;
+use bevy::prelude::*;
+use bevy::tasks::available_parallelism;
+use bevy::time::TimeUpdateStrategy;
+use client::campaign::CurrentLevel;
+use client::rollback::InputHistory;
+use client::scene::crash_dump::PhysicsCrashDump;
+use client::scene::submarine::{
+    simulate_submarine, AngularVelocity, ClientPhysicsTiming, SubInputStateComp, SubPhysics,
+    SubStateComp, SubTelemetry, Submarine, Velocity,
+};
+use client::sim_pause::SimPause;
+use criterion::Criterion;
+use levels::subspecs::small_skiff_spec;
+use levels::{SubInputState, SubState};
+
+const SUBS: usize = 16;
+/// Fixed steps per sub per update: a 60 Hz frame's worth at the client's 120 Hz step, times 20
+/// so each update has enough work to outweigh scheduling.
+const STEPS_PER_UPDATE: u32 = 40;
+const GATE_MIN_CORES: usize = 4;
+const GATE_MIN_SPEEDUP: f64 = 2.0;
+/// Set on the child process that times the single-threaded baseline.
+const BASELINE_ENV: &str = "PARALLEL_PHYSICS_BASELINE";
+
+fn sim_app(compute_threads: Option<usize>) -> App {
+    let mut app = App::new();
+    let mut plugins = MinimalPlugins.build();
+    if let Some(threads) = compute_threads {
+        plugins = plugins.set(TaskPoolPlugin {
+            task_pool_options: TaskPoolOptions::with_num_threads(threads),
+        });
+    }
+    let step_dt = ClientPhysicsTiming::default().dt;
+    app.add_plugins(plugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            STEPS_PER_UPDATE as f32 * step_dt,
+        )))
+        .init_resource::<SubTelemetry>()
+        .init_resource::<PhysicsCrashDump>()
+        .init_resource::<SimPause>()
+        .init_resource::<ClientPhysicsTiming>()
+        .init_resource::<CurrentLevel>()
+        .init_resource::<InputHistory>()
+        .add_systems(Update, simulate_submarine);
+    let spec = small_skiff_spec();
+    for state in fleet(&app) {
+        app.world_mut().spawn((
+            Transform::from_translation(state.position),
+            Submarine,
+            Velocity::default(),
+            AngularVelocity::default(),
+            SubPhysics(spec.clone()),
+            SubStateComp(state),
+            SubInputStateComp(SubInputState {
+                thrust: 0.8,
+                yaw: 0.2,
+                ..Default::default()
+            }),
+        ));
+    }
+    // The first update only starts the clock
+    app.update();
+    app
+}
+
+fn fleet(app: &App) -> Vec<SubState> {
+    let start = app.world().resource::<CurrentLevel>().spec().tunnel.pos;
+    let ballast_tanks = small_skiff_spec().ballast_tanks.len();
+    (0..SUBS)
+        .map(|i| SubState {
+            position: start + Vec3::new(i as f32 * 4.0, 0.0, 0.0),
+            velocity: Vec3::new(1.0, 0.0, 0.0),
+            orientation: Quat::IDENTITY,
+            ang_mom: Vec3::ZERO,
+            ballast_fill: vec![0.5; ballast_tanks],
+            pump: Default::default(),
+        })
+        .collect()
+}
+
+/// Put every sub back at its start so long runs don't fly the fleet out of the level.
+fn reset_fleet(app: &mut App) {
+    let start = fleet(app);
+    let mut q = app.world_mut().query::<&mut SubStateComp>();
+    for (mut state, start) in q.iter_mut(app.world_mut()).zip(start) {
+        state.0 = start;
+    }
+}
+
+/// Time `iters` updates, resetting the fleet outside the timed part.
+fn time_updates(app: &mut App, iters: u64) -> Duration {
+    let mut elapsed = Duration::ZERO;
+    for _ in 0..iters {
+        reset_fleet(app);
+        let began = Instant::now();
+        app.update();
+        elapsed += began.elapsed();
+    }
+    elapsed
+}
+
+fn updates_per_second(app: &mut App) -> f64 {
+    let mut updates = 0_u64;
+    let mut elapsed = Duration::ZERO;
+    while elapsed < Duration::from_secs(2) {
+        elapsed += time_updates(app, 1);
+        updates += 1;
+    }
+    updates as f64 / elapsed.as_secs_f64()
+}
+
+fn main() {
+    if std::env::var_os(BASELINE_ENV).is_some() {
+        println!("{}", updates_per_second(&mut sim_app(Some(1))));
+        return;
+    }
+
+    let mut app = sim_app(None);
+    let mut c = Criterion::default().configure_from_args();
+    c.bench_function("simulate_16_subs", |b| {
+        b.iter_custom(|iters| time_updates(&mut app, iters))
+    });
+    c.final_summary();
+
+    // `cargo test --benches` runs each benchmark once without `--bench`; only gate real runs
+    if !std::env::args().any(|arg| arg == "--bench") {
+        return;
+    }
+    let cores = available_parallelism();
+    if cores < GATE_MIN_CORES {
+        println!("simulate_16_subs gate: skipped, {cores} core(s) < {GATE_MIN_CORES}");
+        return;
+    }
+    let parallel = updates_per_second(&mut app);
+    let output = Command::new(std::env::current_exe().expect("bench executable path"))
+        .env(BASELINE_ENV, "1")
+        .output()
+        .expect("run the single-threaded baseline");
+    let sequential: f64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .expect("baseline updates per second");
+    let speedup = parallel / sequential;
+    println!("simulate_16_subs gate: {speedup:.2}x on {cores} cores (floor {GATE_MIN_SPEEDUP}x)");
+    if speedup <= GATE_MIN_SPEEDUP {
+        eprintln!("parallel submarine prediction is no longer {GATE_MIN_SPEEDUP}x faster");
+        std::process::exit(1);
+    }
+}
@@ -1,20 +1,66 @@
 use bevy::prelude::Resource;
 use clap::Parser;
+use protocol::rendezvous::HolePunchConfig;
+use std::path::PathBuf;
+
+use crate::render_settings::GraphicsPreset;
 
 #[derive(Parser, Debug, Resource, Clone)]
 #[command(name = "thalassocracy-client")]
 #[command(about = "Client for Thalassocracy prototype", long_about = None)]
 pub struct Args {
-    /// Server address (ip:port)
+    /// Server address (ip:port), or `rendezvous://host:port/<token>` to find the server
+    /// through a rendezvous service and punch through NAT to it
     #[arg(long, default_value = "127.0.0.1:61234")]
     pub server: String,
     /// Run without window/rendering
     #[arg(long, default_value_t = false)]
     pub headless: bool,
-    /// Optional display name to send in Hello
+    /// Display name to send in Hello; also saved to the identity file
     #[arg(long)]
     pub name: Option<String>,
+    /// Identity file (defaults to ~/.config/thalassocracy/identity.toml)
+    #[arg(long)]
+    pub identity: Option<PathBuf>,
+    /// Use a throwaway identity; no identity file is read or written
+    #[arg(long, default_value_t = false)]
+    pub ephemeral_identity: bool,
+    /// Reach the server through its WebSocket proxy at this address (ip:port); `--server`
+    /// still names the netcode address the server advertises
+    #[cfg(feature = "websocket")]
+    #[arg(long)]
+    pub ws: Option<String>,
+    /// RON campaign file; must match the server's so level ids resolve to the same levels
+    #[arg(long)]
+    pub campaign: Option<PathBuf>,
+    /// Graphics quality preset applied before the first frame
+    #[arg(long, value_enum)]
+    pub quality: Option<GraphicsPreset>,
+    /// List servers broadcasting on the LAN for a few seconds and exit instead of connecting;
+    /// exits 1 if none were found
+    #[arg(long, default_value_t = false)]
+    pub discover: bool,
     /// Seconds to wait for connect before exiting
     #[arg(long, default_value_t = 5)]
     pub connect_timeout_secs: u64,
+    /// Record the pilot's controls to this file, one entry per frame, for `--replay`
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+    /// Fly with the controls recorded by `--record` instead of the pilot's
+    #[arg(long)]
+    pub replay: Option<PathBuf>,
+}
+
+impl Default for Args {
+    /// The command-line defaults, as if the client were started with no arguments.
+    fn default() -> Self {
+        Self::parse_from(["thalassocracy-client"])
+    }
+}
+
+impl Args {
+    /// Hole-punching setup when `--server` is a `rendezvous://` URL.
+    pub fn hole_punch(&self) -> Option<Result<HolePunchConfig, String>> {
+        HolePunchConfig::parse(&self.server)
+    }
 }
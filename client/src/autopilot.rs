@@ -0,0 +1,238 @@
+use bevy::prelude::*;
+use tracing::{info, warn};
+
+use crate::net::HullStatus;
+use crate::scene::submarine::{SubStateComp, Submarine};
+use crate::ThrustInput;
+
+/// Integrator and derivative memory for a single PID loop.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PidState {
+    pub integral: f32,
+    pub prev_error: f32,
+}
+
+/// Active autopilot layer. Writes into `ThrustInput`, so prediction and the server see the same
+/// commands as if the pilot had moved the sliders.
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub enum AutopilotMode {
+    #[default]
+    Off,
+    DepthHold {
+        target_depth_m: f32,
+        pid: PidState,
+    },
+}
+
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct AutopilotConfig {
+    pub kp_depth: f32,
+    pub ki_depth: f32,
+    pub kd_depth: f32,
+    /// Anti-windup bound on the depth integral (m·s).
+    pub integral_limit: f32,
+    /// Autopilot disengages when hull integrity falls below this.
+    pub min_hull_integrity: f32,
+}
+
+impl Default for AutopilotConfig {
+    fn default() -> Self {
+        Self {
+            kp_depth: 0.5,
+            ki_depth: 0.01,
+            kd_depth: 3.5,
+            integral_limit: 5.0,
+            min_hull_integrity: 0.5,
+        }
+    }
+}
+
+/// One PID step for depth hold. Depth is positive downward (`-position.y`).
+/// Returns `(pump_fwd, pump_aft)`; positive fills the tanks and makes the sub sink.
+pub fn depth_hold_command(
+    cfg: &AutopilotConfig,
+    target_depth_m: f32,
+    depth_m: f32,
+    pid: &mut PidState,
+    dt: f32,
+) -> (f32, f32) {
+    if dt <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let error = target_depth_m - depth_m;
+    pid.integral = (pid.integral + error * dt).clamp(-cfg.integral_limit, cfg.integral_limit);
+    let derivative = (error - pid.prev_error) / dt;
+    pid.prev_error = error;
+    let u = cfg.kp_depth * error + cfg.ki_depth * pid.integral + cfg.kd_depth * derivative;
+    // Split evenly across both tanks so trim (pitch) is unaffected
+    let u = u.clamp(-1.0, 1.0);
+    (u, u)
+}
+
+/// Hand the axes the autopilot drives back to the pilot at neutral, so a disengage never leaves
+/// the pumps or planes running on the last command.
+fn release_controls(controls: &mut ThrustInput) {
+    controls.pump_fwd = 0.0;
+    controls.pump_aft = 0.0;
+    controls.plane = 0.0;
+}
+
+pub struct AutopilotPlugin;
+
+impl Plugin for AutopilotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AutopilotMode>()
+            .init_resource::<AutopilotConfig>()
+            .add_systems(
+                Update,
+                (toggle_depth_hold, apply_autopilot)
+                    .chain()
+                    .before(crate::scene::SimSet),
+            );
+    }
+}
+
+/// `H` engages depth hold at the current depth, or disengages it.
+fn toggle_depth_hold(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut mode: ResMut<AutopilotMode>,
+    mut controls: ResMut<ThrustInput>,
+    q_sub: Query<&SubStateComp, With<Submarine>>,
+) {
+    let Some(keys) = keys else {
+        return;
+    };
+    if !keys.just_pressed(KeyCode::KeyH) {
+        return;
+    }
+    *mode = match *mode {
+        AutopilotMode::Off => {
+            let Ok(state) = q_sub.single() else {
+                return;
+            };
+            let target_depth_m = state.0.depth_m();
+            info!(target_depth_m, "Depth hold engaged");
+            AutopilotMode::DepthHold {
+                target_depth_m,
+                pid: PidState::default(),
+            }
+        }
+        AutopilotMode::DepthHold { .. } => {
+            info!("Depth hold disengaged");
+            release_controls(&mut controls);
+            AutopilotMode::Off
+        }
+    };
+}
+
+pub(crate) fn apply_autopilot(
+    time: Res<Time>,
+    cfg: Res<AutopilotConfig>,
+    hull: Option<Res<HullStatus>>,
+    mut mode: ResMut<AutopilotMode>,
+    mut controls: ResMut<ThrustInput>,
+) {
+    let AutopilotMode::DepthHold {
+        target_depth_m,
+        ref mut pid,
+    } = *mode
+    else {
+        return;
+    };
+    if hull.is_some_and(|h| h.integrity < cfg.min_hull_integrity) {
+        warn!("Depth hold disengaged: hull integrity below limit");
+        *mode = AutopilotMode::Off;
+        release_controls(&mut controls);
+        return;
+    }
+    // Last frame's predicted depth, mirrored by `sync_telemetry_to_input`
+    let depth_m = controls.current_depth_m;
+    let (fwd, aft) = depth_hold_command(&cfg, target_depth_m, depth_m, pid, time.delta_secs());
+    controls.pump_fwd = fwd;
+    controls.pump_aft = aft;
+    // Planes follow the depth command, adding pitch authority once the sub is under way
+    controls.plane = (fwd + aft) * 0.5;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use levels::{
+        builtins::greybox_level, step_submarine, subspecs::small_skiff_spec, Quatf, SubInputState,
+        SubState, Vec3f,
+    };
+
+    #[test]
+    fn depth_hold_settles_within_half_a_meter() {
+        let level = greybox_level();
+        let spec = small_skiff_spec();
+        let cfg = AutopilotConfig::default();
+        let mut state = SubState {
+            position: Vec3f::new(0.0, -5.0, 0.0),
+            velocity: Vec3f::new(0.0, 0.0, 0.0),
+            orientation: Quatf::IDENTITY,
+            ang_mom: Vec3f::new(0.0, 0.0, 0.0),
+            ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+            pump: Default::default(),
+        };
+        let target_depth_m = 8.0;
+        let mut pid = PidState {
+            integral: 0.0,
+            prev_error: target_depth_m - 5.0,
+        };
+        let dt = 1.0 / 60.0;
+        let mut t = 0.0f32;
+        let mut worst_late_error = 0.0f32;
+        while t < 30.0 {
+            let depth = state.depth_m();
+            let (pump_fwd, pump_aft) =
+                depth_hold_command(&cfg, target_depth_m, depth, &mut pid, dt);
+            let inputs = SubInputState {
+                pump_fwd,
+                pump_aft,
+                ..Default::default()
+            };
+            step_submarine(&level, &spec, inputs, &mut state, dt, t);
+            t += dt;
+            if t > 25.0 {
+                worst_late_error = worst_late_error.max((target_depth_m - state.depth_m()).abs());
+            }
+        }
+        assert!(
+            worst_late_error < 0.5,
+            "steady-state depth error {worst_late_error:.3} m"
+        );
+    }
+
+    #[test]
+    fn disengaging_releases_pumps_and_planes() {
+        let mut app = App::new();
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(KeyCode::KeyH);
+        app.insert_resource(keys)
+            .insert_resource(AutopilotMode::DepthHold {
+                target_depth_m: 10.0,
+                pid: PidState::default(),
+            })
+            .insert_resource(ThrustInput {
+                value: 0.4,
+                pump_fwd: 0.8,
+                pump_aft: 0.8,
+                plane: 0.8,
+                ..Default::default()
+            })
+            .add_systems(Update, toggle_depth_hold);
+        app.update();
+
+        assert!(matches!(
+            *app.world().resource::<AutopilotMode>(),
+            AutopilotMode::Off
+        ));
+        let controls = app.world().resource::<ThrustInput>();
+        assert_eq!(
+            (controls.pump_fwd, controls.pump_aft, controls.plane),
+            (0.0, 0.0, 0.0)
+        );
+        assert_eq!(controls.value, 0.4, "throttle is not autopilot-owned");
+    }
+}
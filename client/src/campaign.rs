@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use levels::{CampaignSpec, LevelSpec};
+use tracing::warn;
+
+use crate::Args;
+
+/// The campaign `LevelReload` map ids index into, and the level currently being played. Scene
+/// systems rebuild level geometry whenever this changes.
+#[derive(Resource, Debug, Clone)]
+pub struct CurrentLevel {
+    pub campaign: CampaignSpec,
+    pub map_id: usize,
+}
+
+impl CurrentLevel {
+    /// First level of `--campaign`, or of the builtin campaign when unset or unreadable.
+    pub fn from_args(args: &Args) -> Self {
+        let campaign = match &args.campaign {
+            Some(path) => CampaignSpec::from_ron_file(path).unwrap_or_else(|err| {
+                warn!(path = %path.display(), %err, "Falling back to the builtin campaign");
+                CampaignSpec::builtin_campaign()
+            }),
+            None => CampaignSpec::builtin_campaign(),
+        };
+        Self {
+            campaign,
+            map_id: 0,
+        }
+    }
+
+    pub fn spec(&self) -> &LevelSpec {
+        &self.campaign.levels[self.map_id].level_spec
+    }
+}
+
+impl Default for CurrentLevel {
+    fn default() -> Self {
+        Self {
+            campaign: CampaignSpec::builtin_campaign(),
+            map_id: 0,
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use protocol::{ChatMessage, ClientToServer, SendChat, MAX_CHAT_BYTES};
+use uuid::Uuid;
+
+use crate::net::Leaderboard;
+
+/// Messages shown at once; older ones are dropped.
+const MAX_CHAT_LINES: usize = 8;
+/// Seconds a message stays on screen.
+const CHAT_MESSAGE_TTL_S: f32 = 12.0;
+/// Final part of the lifetime over which a message fades out.
+const CHAT_FADE_S: f32 = 3.0;
+const CHAT_TEXT_COLOR: Color = Color::srgb(0.9, 0.95, 1.0);
+
+/// Chat relayed by the server, forwarded by `pump_network`.
+#[derive(Event, Debug, Clone)]
+pub struct ChatReceived(pub ChatMessage);
+
+/// Text the player submitted, sent to the server by `send_outgoing_chat`.
+#[derive(Event, Debug, Clone)]
+pub struct OutgoingChat(pub String);
+
+/// Seconds since a chat line was shown; drives its fade and despawn.
+#[derive(Component, Debug, Default)]
+pub struct ChatMessageAge(pub f32);
+
+/// Line being typed; `Enter` opens and sends it, `Escape` discards it.
+#[derive(Resource, Debug, Default)]
+pub struct ChatInput {
+    pub active: bool,
+    pub text: String,
+}
+
+/// Opacity of a chat line `age_s` seconds after it arrived.
+pub fn chat_line_alpha(age_s: f32) -> f32 {
+    let fade_start = CHAT_MESSAGE_TTL_S - CHAT_FADE_S;
+    let t = ((age_s - fade_start) / CHAT_FADE_S).clamp(0.0, 1.0);
+    1.0_f32.lerp(0.0, t)
+}
+
+/// Sender's leaderboard name, or the start of their id before the first scoreboard arrives.
+fn sender_name(leaderboard: &Leaderboard, sender_id: Uuid) -> String {
+    leaderboard
+        .entries
+        .iter()
+        .find(|e| e.player_id == sender_id)
+        .map(|e| e.display_name.clone())
+        .unwrap_or_else(|| sender_id.simple().to_string()[..8].to_owned())
+}
+
+pub fn send_outgoing_chat(
+    client: Option<ResMut<RenetClient>>,
+    mut outgoing: EventReader<OutgoingChat>,
+) {
+    let Some(mut client) = client.filter(|c| c.is_connected()) else {
+        outgoing.clear();
+        return;
+    };
+    for OutgoingChat(text) in outgoing.read() {
+        let msg = ClientToServer::SendChat(SendChat { text: text.clone() });
+        if let Ok(bytes) = protocol::encode(&msg) {
+            client.send_message(protocol::Channel::Reliable, bytes);
+        }
+    }
+}
+
+#[derive(Component)]
+struct ChatPanel;
+
+#[derive(Component)]
+struct ChatLines;
+
+#[derive(Component)]
+struct ChatInputLine;
+
+/// Recent chat in a bottom-left panel, plus the line being typed.
+pub struct ChatHudPlugin;
+
+impl Plugin for ChatHudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChatInput>()
+            .add_systems(Startup, spawn_chat_panel)
+            .add_systems(
+                Update,
+                (
+                    type_chat,
+                    show_received_chat,
+                    age_chat_lines,
+                    update_chat_panel,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn spawn_chat_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(12.0),
+                left: Val::Px(12.0),
+                width: Val::Px(420.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(6.0)),
+                ..Default::default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.35)),
+            Visibility::Hidden,
+            ChatPanel,
+            Name::new("Chat"),
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                ChatLines,
+            ));
+            panel.spawn((
+                Text::new(String::new()),
+                TextFont {
+                    font_size: 16.0,
+                    ..Default::default()
+                },
+                TextColor(Color::srgb(1.0, 0.9, 0.5)),
+                Visibility::Hidden,
+                ChatInputLine,
+            ));
+        });
+}
+
+fn type_chat(
+    mut keys: EventReader<KeyboardInput>,
+    mut input: ResMut<ChatInput>,
+    mut outgoing: EventWriter<OutgoingChat>,
+) {
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+        if !input.active {
+            if key.logical_key == Key::Enter {
+                input.active = true;
+            }
+            continue;
+        }
+        match &key.logical_key {
+            Key::Enter => {
+                let text = input.text.trim();
+                if !text.is_empty() {
+                    outgoing.write(OutgoingChat(text.to_owned()));
+                }
+                input.text.clear();
+                input.active = false;
+            }
+            Key::Escape => {
+                input.text.clear();
+                input.active = false;
+            }
+            Key::Backspace => {
+                input.text.pop();
+            }
+            _ => {
+                let Some(typed) = &key.text else {
+                    continue;
+                };
+                for c in typed.chars().filter(|c| !c.is_control()) {
+                    if input.text.len() + c.len_utf8() > MAX_CHAT_BYTES {
+                        break;
+                    }
+                    input.text.push(c);
+                }
+            }
+        }
+    }
+}
+
+fn show_received_chat(
+    mut commands: Commands,
+    mut received: EventReader<ChatReceived>,
+    leaderboard: Res<Leaderboard>,
+    q_container: Query<Entity, With<ChatLines>>,
+    q_lines: Query<(Entity, &ChatMessageAge)>,
+) {
+    let new: Vec<&ChatMessage> = received.read().map(|ChatReceived(msg)| msg).collect();
+    if new.is_empty() {
+        return;
+    }
+    let Ok(container) = q_container.single() else {
+        return;
+    };
+    let new = &new[new.len().saturating_sub(MAX_CHAT_LINES)..];
+
+    // Make room, oldest first
+    let mut existing: Vec<(Entity, f32)> = q_lines.iter().map(|(e, age)| (e, age.0)).collect();
+    existing.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let excess = (existing.len() + new.len()).saturating_sub(MAX_CHAT_LINES);
+    for (entity, _) in existing.into_iter().take(excess) {
+        commands.entity(entity).despawn();
+    }
+
+    for msg in new {
+        let name = sender_name(&leaderboard, msg.sender_id);
+        commands.spawn((
+            Text::new(format!("{name}: {}", msg.text)),
+            TextFont {
+                font_size: 16.0,
+                ..Default::default()
+            },
+            TextColor(CHAT_TEXT_COLOR),
+            ChatMessageAge::default(),
+            ChildOf(container),
+        ));
+    }
+}
+
+fn age_chat_lines(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_lines: Query<(Entity, &mut ChatMessageAge, &mut TextColor)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut age, mut color) in &mut q_lines {
+        age.0 += dt;
+        if age.0 >= CHAT_MESSAGE_TTL_S {
+            commands.entity(entity).despawn();
+        } else {
+            color.0.set_alpha(chat_line_alpha(age.0));
+        }
+    }
+}
+
+fn update_chat_panel(
+    input: Res<ChatInput>,
+    q_lines: Query<(), With<ChatMessageAge>>,
+    mut q_panel: Query<&mut Visibility, (With<ChatPanel>, Without<ChatInputLine>)>,
+    mut q_input: Query<(&mut Text, &mut Visibility), With<ChatInputLine>>,
+) {
+    // Hide the backdrop when there is nothing to read or type
+    if let Ok(mut vis) = q_panel.single_mut() {
+        vis.set_if_neq(if input.active || !q_lines.is_empty() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+    if !input.is_changed() {
+        return;
+    }
+    if let Ok((mut text, mut vis)) = q_input.single_mut() {
+        text.0 = format!("> {}_", input.text);
+        *vis = if input.active {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_hold_then_fade_out_before_expiry() {
+        assert_eq!(chat_line_alpha(0.0), 1.0);
+        assert_eq!(chat_line_alpha(CHAT_MESSAGE_TTL_S - CHAT_FADE_S), 1.0);
+        let mid = chat_line_alpha(CHAT_MESSAGE_TTL_S - CHAT_FADE_S / 2.0);
+        assert!((mid - 0.5).abs() < 1e-5);
+        assert_eq!(chat_line_alpha(CHAT_MESSAGE_TTL_S), 0.0);
+    }
+
+    #[test]
+    fn panel_keeps_the_newest_lines_and_names_senders() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<Leaderboard>()
+            .add_event::<KeyboardInput>()
+            .add_event::<ChatReceived>()
+            .add_event::<OutgoingChat>()
+            .add_plugins(ChatHudPlugin);
+        app.update();
+
+        let known = Uuid::from_u128(1);
+        app.world_mut()
+            .resource_mut::<Leaderboard>()
+            .entries
+            .push(protocol::LeaderboardEntry {
+                player_id: known,
+                display_name: "Nemo".into(),
+                credits: 0,
+                mines: 0,
+                docks: 0,
+            });
+        for i in 0..10 {
+            let sender_id = if i == 9 {
+                known
+            } else {
+                Uuid::from_u128(0xabcdef12 << 96)
+            };
+            app.world_mut().send_event(ChatReceived(ChatMessage {
+                sender_id,
+                text: format!("msg {i}"),
+            }));
+        }
+        app.update();
+
+        let mut lines: Vec<String> = app
+            .world_mut()
+            .query_filtered::<&Text, With<ChatMessageAge>>()
+            .iter(app.world())
+            .map(|t| t.0.clone())
+            .collect();
+        lines.sort();
+        assert_eq!(lines.len(), MAX_CHAT_LINES);
+        assert_eq!(lines[0], "Nemo: msg 9");
+        assert_eq!(lines[1], "abcdef12: msg 2");
+    }
+}
@@ -0,0 +1,309 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use protocol::{Channel, ClientToServer};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::desync_metrics::NetClientStats;
+
+/// Samples kept for RTT, ping loss and jitter.
+pub const QUALITY_WINDOW: usize = 16;
+const PING_INTERVAL_S: f32 = 0.5;
+/// A ping unanswered for this long counts as lost.
+const PING_TIMEOUT: Duration = Duration::from_secs(1);
+/// How long the indicator flashes after the grade drops to `Poor` or worse.
+const FLASH_DURATION_S: f32 = 2.0;
+const FLASH_HZ: f32 = 4.0;
+
+/// One `Ping` in flight or answered.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PingSample {
+    pub seq: u32,
+    #[serde(skip)]
+    pub sent: Instant,
+    pub rtt_ms: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum ConnectionGrade {
+    Good,
+    Fair,
+    Poor,
+    #[default]
+    Disconnected,
+}
+
+impl ConnectionGrade {
+    pub fn from_metrics(rtt_ms: f32, loss_pct: f32, jitter_ms: f32) -> Self {
+        if rtt_ms < 60.0 && loss_pct < 2.0 && jitter_ms < 20.0 {
+            Self::Good
+        } else if rtt_ms < 150.0 && loss_pct < 5.0 && jitter_ms < 50.0 {
+            Self::Fair
+        } else {
+            Self::Poor
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Self::Good => Color::srgb(0.2, 0.85, 0.3),
+            Self::Fair => Color::srgb(0.95, 0.8, 0.2),
+            Self::Poor => Color::srgb(0.95, 0.2, 0.15),
+            Self::Disconnected => Color::srgb(0.5, 0.5, 0.5),
+        }
+    }
+}
+
+/// Link health summarized from `NetClientStats` once per frame.
+#[derive(Resource, Debug, Clone, Copy, Default, Serialize)]
+pub struct ConnectionQuality {
+    /// Mean round trip of answered pings in the window.
+    pub rtt_ms: f32,
+    /// Larger of the unanswered-ping share and the gaps in `InputAck` ticks.
+    pub loss_pct: f32,
+    /// Standard deviation of snapshot inter-arrival times.
+    pub jitter_ms: f32,
+    pub grade: ConnectionGrade,
+}
+
+pub struct ConnectionQualityPlugin;
+
+impl Plugin for ConnectionQualityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConnectionQuality>().add_systems(
+            Update,
+            (
+                send_pings,
+                compute_connection_quality.after(crate::net::pump_network),
+            ),
+        );
+    }
+}
+
+fn send_pings(
+    client: Option<ResMut<RenetClient>>,
+    time: Res<Time>,
+    mut stats: ResMut<NetClientStats>,
+    mut since_last: Local<f32>,
+    mut seq: Local<u32>,
+) {
+    let Some(mut client) = client else {
+        return;
+    };
+    if !client.is_connected() {
+        return;
+    }
+    *since_last += time.delta_secs();
+    if *since_last < PING_INTERVAL_S {
+        return;
+    }
+    *since_last = 0.0;
+    *seq = seq.wrapping_add(1);
+    let now = Instant::now();
+    let msg = ClientToServer::Ping(protocol::Ping {
+        seq: *seq,
+        client_send_ms: stats.clock_ms(now),
+    });
+    if let Ok(bytes) = protocol::encode(&msg) {
+        client.send_message(Channel::Input, bytes);
+        stats.record_ping_sent(*seq, now);
+    }
+}
+
+pub fn compute_connection_quality(
+    client: Option<Res<RenetClient>>,
+    stats: Res<NetClientStats>,
+    mut quality: ResMut<ConnectionQuality>,
+) {
+    let connected = client.is_some_and(|c| c.is_connected());
+    let now = Instant::now();
+    let answered: Vec<f32> = stats.pings.iter().filter_map(|p| p.rtt_ms).collect();
+    let rtt_ms = if answered.is_empty() {
+        0.0
+    } else {
+        answered.iter().sum::<f32>() / answered.len() as f32
+    };
+    let loss_pct = ping_loss_pct(&stats.pings, now).max(ack_gap_pct(&stats.acked_ticks));
+    let jitter_ms = std_dev(stats.inter_arrival_ms.iter().copied());
+    let grade = if connected {
+        ConnectionGrade::from_metrics(rtt_ms, loss_pct, jitter_ms)
+    } else {
+        ConnectionGrade::Disconnected
+    };
+    *quality = ConnectionQuality {
+        rtt_ms,
+        loss_pct,
+        jitter_ms,
+        grade,
+    };
+}
+
+/// Share of pings older than `PING_TIMEOUT` that never got a response.
+fn ping_loss_pct(pings: &VecDeque<PingSample>, now: Instant) -> f32 {
+    let settled: Vec<&PingSample> = pings
+        .iter()
+        .filter(|p| p.rtt_ms.is_some() || now.saturating_duration_since(p.sent) >= PING_TIMEOUT)
+        .collect();
+    if settled.is_empty() {
+        return 0.0;
+    }
+    let lost = settled.iter().filter(|p| p.rtt_ms.is_none()).count();
+    100.0 * lost as f32 / settled.len() as f32
+}
+
+/// Share of ticks missing between the oldest and newest `InputAck` seen. Ticks count up by one
+/// per sent `InputTick`, so a gap is an input the server never acknowledged.
+fn ack_gap_pct(acked: &VecDeque<u64>) -> f32 {
+    let (Some(&first), Some(&last)) = (acked.front(), acked.back()) else {
+        return 0.0;
+    };
+    let span = last.saturating_sub(first) + 1;
+    let missing = span.saturating_sub(acked.len() as u64);
+    100.0 * missing as f32 / span as f32
+}
+
+fn std_dev(samples: impl Iterator<Item = f32> + Clone) -> f32 {
+    let n = samples.clone().count();
+    if n < 2 {
+        return 0.0;
+    }
+    let mean = samples.clone().sum::<f32>() / n as f32;
+    let var = samples.map(|s| (s - mean) * (s - mean)).sum::<f32>() / n as f32;
+    var.sqrt()
+}
+
+#[derive(Component)]
+struct QualityIcon;
+
+#[derive(Component)]
+struct QualityLabel;
+
+/// Colored dot plus RTT readout in the top-right corner.
+pub struct ConnectionQualityIndicatorPlugin;
+
+impl Plugin for ConnectionQualityIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_quality_indicator)
+            .add_systems(Update, update_quality_indicator);
+    }
+}
+
+fn spawn_quality_indicator(mut commands: Commands) {
+    let root = commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                top: Val::Px(10.0),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(6.0),
+                ..Default::default()
+            },
+            Name::new("Connection Quality"),
+        ))
+        .id();
+    commands.spawn((
+        Text::new(String::new()),
+        TextFont {
+            font_size: 14.0,
+            ..Default::default()
+        },
+        TextColor(Color::srgb(0.85, 0.9, 0.95)),
+        QualityLabel,
+        ChildOf(root),
+    ));
+    commands.spawn((
+        Node {
+            width: Val::Px(14.0),
+            height: Val::Px(14.0),
+            ..Default::default()
+        },
+        BorderRadius::MAX,
+        BackgroundColor(ConnectionGrade::Disconnected.color()),
+        QualityIcon,
+        ChildOf(root),
+    ));
+}
+
+fn update_quality_indicator(
+    time: Res<Time>,
+    quality: Res<ConnectionQuality>,
+    mut last_grade: Local<ConnectionGrade>,
+    mut flash_remaining: Local<f32>,
+    mut q_icon: Query<&mut BackgroundColor, With<QualityIcon>>,
+    mut q_label: Query<&mut Text, With<QualityLabel>>,
+) {
+    let degraded =
+        |g: ConnectionGrade| matches!(g, ConnectionGrade::Poor | ConnectionGrade::Disconnected);
+    if degraded(quality.grade) && !degraded(*last_grade) {
+        *flash_remaining = FLASH_DURATION_S;
+    }
+    *last_grade = quality.grade;
+    *flash_remaining = (*flash_remaining - time.delta_secs()).max(0.0);
+
+    let mut color = quality.grade.color();
+    if *flash_remaining > 0.0 && (*flash_remaining * FLASH_HZ).fract() < 0.5 {
+        color = Color::srgb(1.0, 0.0, 0.0);
+    } else if *flash_remaining > 0.0 {
+        color = color.with_alpha(0.25);
+    }
+    for mut bg in &mut q_icon {
+        bg.0 = color;
+    }
+    for mut text in &mut q_label {
+        text.0 = match quality.grade {
+            ConnectionGrade::Disconnected => "offline".to_string(),
+            _ => format!(
+                "{:.0} ms  {:.0}% loss  {:.0} ms jitter",
+                quality.rtt_ms, quality.loss_pct, quality.jitter_ms
+            ),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grade_thresholds() {
+        assert_eq!(
+            ConnectionGrade::from_metrics(40.0, 0.0, 5.0),
+            ConnectionGrade::Good
+        );
+        assert_eq!(
+            ConnectionGrade::from_metrics(60.0, 0.0, 5.0),
+            ConnectionGrade::Fair
+        );
+        assert_eq!(
+            ConnectionGrade::from_metrics(40.0, 3.0, 5.0),
+            ConnectionGrade::Fair
+        );
+        assert_eq!(
+            ConnectionGrade::from_metrics(40.0, 0.0, 80.0),
+            ConnectionGrade::Poor
+        );
+    }
+
+    #[test]
+    fn loss_and_jitter_from_samples() {
+        let acked: VecDeque<u64> = [1, 2, 4, 5].into_iter().collect();
+        assert!((ack_gap_pct(&acked) - 20.0).abs() < 1e-4);
+
+        let now = Instant::now();
+        let old = now - Duration::from_secs(2);
+        let pings: VecDeque<PingSample> = [
+            (1, old, Some(30.0)),
+            (2, old, None),
+            (3, now, None), // still in flight
+        ]
+        .into_iter()
+        .map(|(seq, sent, rtt_ms)| PingSample { seq, sent, rtt_ms })
+        .collect();
+        assert!((ping_loss_pct(&pings, now) - 50.0).abs() < 1e-4);
+
+        assert_eq!(std_dev([50.0, 50.0, 50.0].into_iter()), 0.0);
+        assert!((std_dev([40.0, 60.0].into_iter()) - 10.0).abs() < 1e-4);
+    }
+}
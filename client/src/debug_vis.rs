@@ -1,12 +1,23 @@
+use crate::campaign::CurrentLevel;
+use crate::connection_quality::ConnectionQuality;
+use crate::desync_metrics::{CorrectionHistory, DesyncMetrics, NetClientStats};
+use crate::net::FilteredServerState;
+use crate::notifications::NotificationLog;
+use crate::physics_diagnostics;
 use crate::scene::submarine::{SubTelemetry, Submarine, Velocity};
 use crate::scene::SimSet;
+use crate::system_timings::{SystemTimingDiagnosticPlugin, SystemTimings};
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy::pbr::wireframe::WireframeConfig;
 use bevy::prelude::*;
 #[cfg(feature = "windowing")]
 use bevy_inspector_egui::quick::ResourceInspectorPlugin;
 #[cfg(feature = "windowing")]
 use bevy_inspector_egui::InspectorOptions;
-use levels::{builtins::greybox_level, sample_flow_at, Vec3f};
+use levels::{sample_flow_at, SubStepDebug, Vec3f};
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
 
 #[cfg_attr(feature = "windowing", derive(InspectorOptions))]
 #[derive(Resource, Debug, Clone, Reflect)]
@@ -15,10 +26,18 @@ pub struct DebugVis {
     pub labels: bool,
     pub wireframe_global: bool,
     pub flow_arrows: bool,
+    /// Draw every Nth cell of `FlowField::Grid` fields when `flow_arrows` is on.
+    pub flow_grid_density: u32,
     pub overlay: bool,
     pub speed_arrow: bool,
     pub telemetry: bool,
+    /// Physics steps per frame and mean step time under the telemetry block.
+    pub step_timing: bool,
     pub desync_indicator: bool,
+    /// Safe/unsafe heading ring on the HUD when closing on a tunnel wall.
+    pub collision_prediction_enabled: bool,
+    /// Per-system timing table (debug builds only) under the overlay.
+    pub show_system_timings: bool,
 }
 
 impl Default for DebugVis {
@@ -27,10 +46,14 @@ impl Default for DebugVis {
             labels: false,
             wireframe_global: false,
             flow_arrows: false,
+            flow_grid_density: 2,
             overlay: true,
             speed_arrow: false,
             telemetry: true,
+            step_timing: true,
             desync_indicator: true,
+            collision_prediction_enabled: true,
+            show_system_timings: false,
         }
     }
 }
@@ -43,6 +66,7 @@ pub struct DebugVisPlugin;
 impl Plugin for DebugVisPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DebugVis>().register_type::<DebugVis>();
+        app.add_plugins(SystemTimingDiagnosticPlugin);
 
         #[cfg(feature = "windowing")]
         app.add_plugins(ResourceInspectorPlugin::<DebugVis>::default());
@@ -55,6 +79,7 @@ impl Plugin for DebugVisPlugin {
                     apply_label_visibility,
                     apply_overlay_visibility,
                     update_debug_overlay,
+                    dump_telemetry,
                 ),
             )
             .add_systems(Update, draw_speed_arrow.after(SimSet));
@@ -91,7 +116,7 @@ fn spawn_debug_overlay(mut commands: Commands, assets: Res<AssetServer>) {
         Node {
             position_type: PositionType::Absolute,
             right: Val::Px(10.0),
-            top: Val::Px(10.0),
+            top: Val::Px(34.0),
             ..Default::default()
         },
         Text::new(String::new()),
@@ -141,6 +166,12 @@ fn update_debug_overlay(
     telemetry: Option<Res<SubTelemetry>>,
     pause: Option<Res<crate::sim_pause::SimPause>>,
     desync: Option<Res<crate::desync_metrics::DesyncMetrics>>,
+    hull: Option<Res<crate::net::HullStatus>>,
+    autopilot: Option<Res<crate::autopilot::AutopilotMode>>,
+    level: Res<CurrentLevel>,
+    timings: Option<Res<SystemTimings>>,
+    diagnostics: Option<Res<DiagnosticsStore>>,
+    net_stats: Option<Res<NetClientStats>>,
 ) {
     let Ok(mut text) = q_text.single_mut() else {
         return;
@@ -180,9 +211,9 @@ fn update_debug_overlay(
     };
 
     // Flow sample
-    let level = greybox_level();
+    let level = level.spec();
     let (flow, _var) = sample_flow_at(
-        &level,
+        level,
         Vec3f {
             x: p.x,
             y: p.y,
@@ -194,7 +225,27 @@ fn update_debug_overlay(
     let rel = Vec3::new(v.x - flow.x, v.y - flow.y, v.z - flow.z);
     let rel_speed = rel.length();
 
-    let paused = pause.map(|p| p.0).unwrap_or(false);
+    let mut header = String::new();
+    match pause.as_deref() {
+        Some(p) if p.paused => header.push_str("PAUSED \n"),
+        Some(p) if p.is_slow() => header.push_str(&format!("{}× SLOW\n", p.slow_factor)),
+        _ => {}
+    }
+    if let Some(h) = hull.filter(|h| h.integrity < 1.0) {
+        header.push_str(&format!(
+            "HULL {:>3.0}%{}\n",
+            h.integrity * 100.0,
+            if h.alert { "  !! CRITICAL !!" } else { "" }
+        ));
+    }
+    if let Some(crate::autopilot::AutopilotMode::DepthHold { target_depth_m, .. }) =
+        autopilot.as_deref()
+    {
+        header.push_str(&format!(
+            "DEPTH HOLD  tgt {:5.1} m  cur {:5.1} m\n",
+            target_depth_m, -p.y
+        ));
+    }
     // Optional sync indicator line appended to overlay
     let sync_line = if vis.desync_indicator {
         if let Some(d) = desync {
@@ -212,13 +263,17 @@ fn update_debug_overlay(
     } else {
         String::new()
     };
+    let sync_line = match net_stats.filter(|n| n.last_pong_server_ms.is_some()) {
+        Some(n) if vis.desync_indicator => format!("{sync_line}  RTT: {:.0} ms", n.rtt_ewma_ms),
+        _ => sync_line,
+    };
 
     if vis.telemetry {
         if let Some(t) = telemetry {
             let d = &t.0;
             text.0 = format!(
-                "{}POS  {:7.2} {:7.2} {:7.2}\nSPD  {:5.2} m/s  REL {:5.2}\nYAW  {:6.1} deg  dYAW {:6.1} deg/s\nIN   T:{:>5.2}  R:{:>5.2}\nWATER {:5.2} ({:5.2},{:5.2},{:5.2})\n-- TELEMETRY --\nREL u:{:>5.2} v:{:>5.2} w:{:>5.2}\nQ   {:>6.1}  sign_u:{:>+3.0}  fm:{:>4.1}\nTAU ctl:{:>7.1} d_lin:{:>7.1} d_q:{:>7.1} d_v:{:>7.1}\nTAU ws:{:>7.1} beta:{:>7.1}  TOT:{:>7.1}\nERR {:>6.2} deg  ACC {:>6.3} r/s²{}\nRIGHT {:>5.2} {:>5.2} {:>5.2}\nUP {:>5.2} {:>5.2} {:>5.2}",
-                if paused { "PAUSED \n" } else { "" },
+                "{}POS  {:7.2} {:7.2} {:7.2}\nSPD  {:5.2} m/s  REL {:5.2}\nYAW  {:6.1} deg  dYAW {:6.1} deg/s\nIN   T:{:>5.2}  R:{:>5.2}\nWATER {:5.2} ({:5.2},{:5.2},{:5.2})\n-- TELEMETRY --\nREL u:{:>5.2} v:{:>5.2} w:{:>5.2}\nQ   {:>6.1}  sign_u:{:>+3.0}  fm:{:>4.1}\nTAU ctl:{:>7.1} d_lin:{:>7.1} d_q:{:>7.1} d_v:{:>7.1}\nTAU ws:{:>7.1} beta:{:>7.1}  TOT:{:>7.1}\nERR {:>6.2} deg  ACC {:>6.3} r/s²{}\nRIGHT {:>5.2} {:>5.2} {:>5.2}\nUP {:>5.2} {:>5.2} {:>5.2}\nPITCH bal:{:>7.1} cob:{:>7.1} damp:{:>7.1} TOT:{:>7.1}\nROLL  bal:{:>7.1} cob:{:>7.1} damp:{:>7.1} TOT:{:>7.1}\nNOISE {:>5.1} dB",
+                header,
                 p.x, p.y, p.z,
                 speed, rel_speed,
                 yaw.to_degrees(), last.rate.to_degrees(),
@@ -231,12 +286,15 @@ fn update_debug_overlay(
                 d.yaw_err.to_degrees(), d.yaw_acc,
                 sync_line,
                 d.right.x, d.right.y, d.right.z,
-                d.up_b.x, d.up_b.y, d.up_b.z
+                d.up_b.x, d.up_b.y, d.up_b.z,
+                d.tau_pitch_ballast, d.tau_pitch_cob, d.tau_pitch_damp, d.tau_pitch_total,
+                d.tau_roll_ballast, d.tau_roll_cob, d.tau_roll_damp, d.tau_roll_total,
+                d.noise_floor
             );
         } else {
             text.0 = format!(
                 "{}POS  {:7.2} {:7.2} {:7.2}\nSPD  {:5.2} m/s  REL {:5.2}\nYAW  {:6.1} deg  dYAW {:6.1} deg/s\nIN   T:{:>5.2}  R:{:>5.2}\nWATER {:5.2} ({:5.2},{:5.2},{:5.2})\n(telemetry unavailable){}",
-                if paused { "PAUSED \n" } else { "" },
+                header,
                 p.x, p.y, p.z,
                 speed, rel_speed,
                 yaw.to_degrees(), last.rate.to_degrees(),
@@ -248,7 +306,7 @@ fn update_debug_overlay(
     } else {
         text.0 = format!(
             "{}POS  {:7.2} {:7.2} {:7.2}\nSPD  {:5.2} m/s  REL {:5.2}\nYAW  {:6.1} deg  dYAW {:6.1} deg/s\nIN   T:{:>5.2}  R:{:>5.2}\nWATER {:5.2} ({:5.2},{:5.2},{:5.2}){}",
-            if paused { "PAUSED \n" } else { "" },
+            header,
             p.x, p.y, p.z,
             speed, rel_speed,
             yaw.to_degrees(), last.rate.to_degrees(),
@@ -257,6 +315,34 @@ fn update_debug_overlay(
             sync_line,
         );
     }
+    if vis.telemetry && vis.step_timing {
+        let smoothed = |path| {
+            diagnostics
+                .as_deref()
+                .and_then(|d| d.get(path))
+                .and_then(|d| d.smoothed())
+        };
+        if let (Some(steps), Some(step_us)) = (
+            smoothed(&physics_diagnostics::STEP_COUNT),
+            smoothed(&physics_diagnostics::STEP_DURATION_US),
+        ) {
+            text.0
+                .push_str(&format!("\nSTEP {steps:.1}/frame  {step_us:.1} us/step"));
+        }
+    }
+    if vis.show_system_timings {
+        if let Some(frame_ms) = diagnostics
+            .as_deref()
+            .and_then(|d| d.get(&FrameTimeDiagnosticsPlugin::FRAME_TIME))
+            .and_then(|d| d.smoothed())
+        {
+            text.0.push_str(&format!("\nFRAME {frame_ms:.2} ms"));
+        }
+        if let Some(timings) = timings {
+            text.0.push('\n');
+            text.0.push_str(&timings.table());
+        }
+    }
 }
 
 fn draw_speed_arrow(
@@ -264,6 +350,7 @@ fn draw_speed_arrow(
     mut gizmos: Gizmos,
     q_sub: Query<(&Transform, &Velocity), With<Submarine>>,
     time: Res<Time>,
+    level: Res<CurrentLevel>,
 ) {
     let Some(vis) = vis else {
         return;
@@ -284,9 +371,9 @@ fn draw_speed_arrow(
     }
 
     // Also draw water-relative velocity arrow (cyan) for clarity
-    let level = greybox_level();
+    let level = level.spec();
     let (flow, _var) = sample_flow_at(
-        &level,
+        level,
         Vec3f {
             x: p.x,
             y: p.y,
@@ -302,3 +389,99 @@ fn draw_speed_arrow(
         gizmos.arrow(p, end, Color::srgb(0.2, 1.0, 1.0)); // cyan: water-relative velocity
     }
 }
+
+/// Everything the client knows about its sub's simulation and link at one moment, written by
+/// the F9 dump for chasing rare physics anomalies. Missing resources serialize as `null`.
+#[derive(Serialize)]
+pub struct TelemetryDump<'a> {
+    pub unix_ms: u128,
+    pub sub_step: Option<&'a SubStepDebug>,
+    pub filtered_server_state: Option<&'a FilteredServerState>,
+    pub desync: Option<&'a DesyncMetrics>,
+    pub corrections: Option<&'a CorrectionHistory>,
+    pub net_stats: Option<&'a NetClientStats>,
+    pub connection_quality: Option<&'a ConnectionQuality>,
+}
+
+/// F9 writes a `TelemetryDump` to `telemetry_<unix ms>.json` in the working directory. The
+/// JSON is built this frame; the file is written on its own thread.
+#[allow(clippy::too_many_arguments)]
+fn dump_telemetry(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    telemetry: Option<Res<SubTelemetry>>,
+    filtered: Option<Res<FilteredServerState>>,
+    desync: Option<Res<DesyncMetrics>>,
+    corrections: Option<Res<CorrectionHistory>>,
+    net_stats: Option<Res<NetClientStats>>,
+    quality: Option<Res<ConnectionQuality>>,
+    mut log: ResMut<NotificationLog>,
+) {
+    if !keys.is_some_and(|k| k.just_pressed(KeyCode::F9)) {
+        return;
+    }
+    let unix_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    let dump = TelemetryDump {
+        unix_ms,
+        sub_step: telemetry.as_deref().map(|t| &t.0),
+        filtered_server_state: filtered.as_deref(),
+        desync: desync.as_deref(),
+        corrections: corrections.as_deref(),
+        net_stats: net_stats.as_deref(),
+        connection_quality: quality.as_deref(),
+    };
+    let json = match serde_json::to_string_pretty(&dump) {
+        Ok(json) => json,
+        Err(err) => {
+            warn!(?err, "Failed to serialize telemetry");
+            return;
+        }
+    };
+    let path = format!("telemetry_{unix_ms}.json");
+    log.push(format!("Telemetry dumped to {path}"));
+    std::thread::spawn(move || match std::fs::write(&path, json) {
+        Ok(()) => info!(path, "Dumped telemetry"),
+        Err(err) => warn!(?err, path, "Failed to write telemetry dump"),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn telemetry_dump_serializes_every_section() {
+        let mut corrections = CorrectionHistory::default();
+        for i in 0..100 {
+            corrections.record(crate::desync_metrics::CorrectionRecord {
+                server_ms: i,
+                kind: crate::desync_metrics::CorrectionKind::Smooth,
+                pos_err_m: 0.1,
+                ang_err_rad: 0.0,
+                vel_err_mps: 0.0,
+            });
+        }
+        let mut net_stats = NetClientStats::default();
+        net_stats.record_ping_sent(1, std::time::Instant::now());
+        let dump = TelemetryDump {
+            unix_ms: 1,
+            sub_step: Some(&SubStepDebug::default()),
+            filtered_server_state: Some(&FilteredServerState::default()),
+            desync: Some(&DesyncMetrics::default()),
+            corrections: Some(&corrections),
+            net_stats: Some(&net_stats),
+            connection_quality: None,
+        };
+        let value = serde_json::to_value(&dump).unwrap();
+        assert!(value["sub_step"]["mass_eff"].is_number());
+        assert!(value["filtered_server_state"]["pos"].is_array());
+        assert!(value["desync"]["adj_factor_ema"].is_number());
+        // Only the last 64 corrections are kept
+        let kept = value["corrections"].as_array().unwrap();
+        assert_eq!(kept.len(), crate::desync_metrics::CORRECTION_HISTORY_LEN);
+        assert_eq!(kept[0]["server_ms"], 36);
+        assert_eq!(value["net_stats"]["pings"][0]["seq"], 1);
+        assert!(value["connection_quality"].is_null());
+    }
+}
@@ -1,17 +1,104 @@
 use bevy::prelude::*;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
 use std::time::Instant;
+use tracing::{info, warn};
+use uuid::Uuid;
 
+use crate::connection_quality::{PingSample, QUALITY_WINDOW};
 use crate::ThrustInput;
+#[cfg(feature = "windowing")]
+use bevy_egui::EguiPrimaryContextPass;
+#[cfg(feature = "windowing")]
+use bevy_inspector_egui::bevy_egui::EguiContexts;
+use protocol::PingResponse;
+
+/// Weight of each new round trip in `NetClientStats::rtt_ewma_ms`.
+pub const RTT_EWMA_ALPHA: f32 = 0.125;
 
 /// Rolling network client stats updated by net.rs systems.
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Serialize)]
 pub struct NetClientStats {
+    #[serde(skip)]
     pub last_state_instant: Option<Instant>,
     pub inter_arrival_ewma_ms: f32,
     pub last_acked_tick: Option<u64>,
     pub last_server_tick: Option<u64>,
     /// Magnitude of last forced snap (pos error in meters at snap time).
     pub last_snap_magnitude_m: f32,
+    /// Last `QUALITY_WINDOW` snapshot inter-arrival times (ms), oldest first.
+    pub inter_arrival_ms: VecDeque<f32>,
+    /// Last `QUALITY_WINDOW` pings sent, oldest first.
+    pub pings: VecDeque<PingSample>,
+    /// Recently acknowledged `InputTick` ticks, oldest first.
+    pub acked_ticks: VecDeque<u64>,
+    /// Server physics tick rate, from `JoinAck` and any later `TickRateChange`.
+    pub current_tick_hz: Option<u32>,
+    /// Origin of the client clock stamped into `Ping::client_send_ms`.
+    #[serde(skip)]
+    pub clock_origin: Instant,
+    /// `PingSample::rtt_ms` of answered `pings`, smoothed with `RTT_EWMA_ALPHA`; 0 until the
+    /// first response.
+    pub rtt_ewma_ms: f32,
+    /// `PingResponse::server_recv_ms` of the latest response, for one-way delay estimates.
+    pub last_pong_server_ms: Option<u64>,
+}
+
+impl NetClientStats {
+    pub fn record_inter_arrival(&mut self, dt_ms: f32) {
+        push_bounded(&mut self.inter_arrival_ms, dt_ms, QUALITY_WINDOW);
+    }
+
+    pub fn record_ping_sent(&mut self, seq: u32, at: Instant) {
+        let sample = PingSample {
+            seq,
+            sent: at,
+            rtt_ms: None,
+        };
+        push_bounded(&mut self.pings, sample, QUALITY_WINDOW);
+    }
+
+    /// Milliseconds on the client clock at `at`.
+    pub fn clock_ms(&self, at: Instant) -> u64 {
+        at.saturating_duration_since(self.clock_origin).as_millis() as u64
+    }
+
+    /// Fill in the answered ping's `rtt_ms` and fold it into `rtt_ewma_ms`. Responses to pings
+    /// that already aged out of `pings`, or were answered before, are ignored.
+    pub fn record_ping_response(&mut self, pong: &PingResponse, at: Instant) {
+        let Some(ping) = self
+            .pings
+            .iter_mut()
+            .find(|p| p.seq == pong.seq && p.rtt_ms.is_none())
+        else {
+            return;
+        };
+        let rtt_ms = at.saturating_duration_since(ping.sent).as_secs_f32() * 1000.0;
+        ping.rtt_ms = Some(rtt_ms);
+        self.rtt_ewma_ms = if self.last_pong_server_ms.is_none() {
+            rtt_ms
+        } else {
+            self.rtt_ewma_ms + RTT_EWMA_ALPHA * (rtt_ms - self.rtt_ewma_ms)
+        };
+        self.last_pong_server_ms = Some(pong.server_recv_ms);
+    }
+
+    /// Estimated one-way delay, half of `rtt_ewma_ms`.
+    pub fn half_rtt_ms(&self) -> f32 {
+        self.rtt_ewma_ms * 0.5
+    }
+
+    pub fn record_input_ack(&mut self, tick: u64) {
+        self.last_acked_tick = Some(tick);
+        push_bounded(&mut self.acked_ticks, tick, 4 * QUALITY_WINDOW);
+    }
+}
+
+fn push_bounded<T>(queue: &mut VecDeque<T>, value: T, cap: usize) {
+    if queue.len() == cap {
+        queue.pop_front();
+    }
+    queue.push_back(value);
 }
 
 impl Default for NetClientStats {
@@ -22,6 +109,13 @@ impl Default for NetClientStats {
             last_acked_tick: None,
             last_server_tick: None,
             last_snap_magnitude_m: 0.0,
+            inter_arrival_ms: VecDeque::new(),
+            pings: VecDeque::new(),
+            acked_ticks: VecDeque::new(),
+            current_tick_hz: None,
+            clock_origin: Instant::now(),
+            rtt_ewma_ms: 0.0,
+            last_pong_server_ms: None,
         }
     }
 }
@@ -35,7 +129,7 @@ pub struct ReconcileErrors {
 }
 
 /// Aggregated, smoothed indicator of client/server divergence (0..1).
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Serialize)]
 pub struct DesyncMetrics {
     pub adj_factor_ema: f32, // 0 (in sync) .. 1 (very out of sync)
     pub snapshot_age_ms: f32,
@@ -63,6 +157,90 @@ impl Default for DesyncMetrics {
     }
 }
 
+/// Corrections kept in `CorrectionHistory`.
+pub const CORRECTION_HISTORY_LEN: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CorrectionKind {
+    /// Error too large to smooth; the sub was moved onto the server state in one frame.
+    Snap,
+    /// A `ServerCorrection` was started to blend toward the server state.
+    Smooth,
+}
+
+/// One reconciliation of the local sub against a server snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CorrectionRecord {
+    pub server_ms: u64,
+    pub kind: CorrectionKind,
+    pub pos_err_m: f32,
+    pub ang_err_rad: f32,
+    pub vel_err_mps: f32,
+}
+
+/// The local sub's last `CORRECTION_HISTORY_LEN` snaps and corrections, oldest first, filled
+/// by `apply_state_to_sub` for telemetry dumps.
+#[derive(Resource, Debug, Default, Clone, Serialize)]
+pub struct CorrectionHistory(pub VecDeque<CorrectionRecord>);
+
+impl CorrectionHistory {
+    pub fn record(&mut self, record: CorrectionRecord) {
+        push_bounded(&mut self.0, record, CORRECTION_HISTORY_LEN);
+    }
+}
+
+/// Remote position error past which a snapshot counts as a snap, as for the local sub.
+const REMOTE_SNAP_M: f32 = 10.0;
+/// Remote position error past which a snapshot counts as a correction.
+const REMOTE_CORRECTION_M: f32 = 0.08;
+/// File written by the Shift+F11 export, relative to the working directory.
+pub const REMOTE_DESYNC_CSV: &str = "remote_desync.csv";
+
+/// Per-player divergence between a remote sub as displayed and its latest server snapshot.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DesyncEntry {
+    pub last_pos_err_m: f32,
+    pub snap_count: u32,
+    /// Server time of the last snapshot that moved the remote by more than `REMOTE_CORRECTION_M`.
+    pub last_correction_ms: u64,
+}
+
+/// Diagnostic-only desync data for other players' subs, filled by `apply_state_to_sub`. Unlike
+/// `DesyncMetrics` it feeds no indicator, camera effect or notification.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct RemoteDesyncMetrics(pub HashMap<Uuid, DesyncEntry>);
+
+impl RemoteDesyncMetrics {
+    pub fn record(&mut self, id: Uuid, pos_err_m: f32, server_ms: u64) {
+        let entry = self.0.entry(id).or_default();
+        entry.last_pos_err_m = pos_err_m;
+        if pos_err_m > REMOTE_SNAP_M {
+            entry.snap_count += 1;
+        }
+        if pos_err_m > REMOTE_CORRECTION_M {
+            entry.last_correction_ms = server_ms;
+        }
+    }
+
+    /// Entries with the largest position error first.
+    pub fn sorted(&self) -> Vec<(Uuid, DesyncEntry)> {
+        let mut entries: Vec<_> = self.0.iter().map(|(id, e)| (*id, *e)).collect();
+        entries.sort_by(|a, b| b.1.last_pos_err_m.total_cmp(&a.1.last_pos_err_m));
+        entries
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("player_id,last_pos_err_m,snap_count,last_correction_ms\n");
+        for (id, e) in self.sorted() {
+            csv.push_str(&format!(
+                "{id},{:.4},{},{}\n",
+                e.last_pos_err_m, e.snap_count, e.last_correction_ms
+            ));
+        }
+        csv
+    }
+}
+
 pub struct DesyncMetricsPlugin;
 
 impl Plugin for DesyncMetricsPlugin {
@@ -70,13 +248,76 @@ impl Plugin for DesyncMetricsPlugin {
         app.init_resource::<NetClientStats>()
             .init_resource::<ReconcileErrors>()
             .init_resource::<DesyncMetrics>()
+            .init_resource::<RemoteDesyncMetrics>()
             // Compute reconciliation errors once per frame
             .add_systems(Update, sample_reconcile_errors)
             // Aggregate into a single indicator
-            .add_systems(Update, aggregate_desync_metric);
+            .add_systems(Update, aggregate_desync_metric)
+            .add_systems(Update, export_remote_desync_csv);
+
+        #[cfg(feature = "windowing")]
+        {
+            app.add_systems(EguiPrimaryContextPass, ui_remote_desync);
+        }
+    }
+}
+
+/// Shift+F11 dumps `RemoteDesyncMetrics` to `REMOTE_DESYNC_CSV`.
+fn export_remote_desync_csv(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    remote: Res<RemoteDesyncMetrics>,
+) {
+    let Some(keys) = keys else {
+        return;
+    };
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if !shift || !keys.just_pressed(KeyCode::F11) {
+        return;
+    }
+    match std::fs::write(REMOTE_DESYNC_CSV, remote.to_csv()) {
+        Ok(()) => info!(path = REMOTE_DESYNC_CSV, "Exported remote desync metrics"),
+        Err(err) => warn!(?err, "Failed to export remote desync metrics"),
     }
 }
 
+#[cfg(feature = "windowing")]
+fn ui_remote_desync(
+    mut egui_ctx: EguiContexts,
+    remote: Res<RemoteDesyncMetrics>,
+    vis: Option<Res<crate::debug_vis::DebugVis>>,
+) {
+    use bevy_inspector_egui::egui::*;
+    if remote.0.is_empty() || !vis.is_some_and(|v| v.desync_indicator) {
+        return;
+    }
+    let Ok(ctx) = egui_ctx.ctx_mut() else {
+        return;
+    };
+    Window::new("Remote Desync")
+        .anchor(Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+        .resizable(false)
+        .show(ctx, |ui| {
+            Grid::new("remote_desync_grid")
+                .striped(true)
+                .spacing([16.0, 4.0])
+                .show(ui, |ui| {
+                    for header in ["Player", "Pos err", "Snaps", "Last corr"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for (id, e) in remote.sorted() {
+                        let short = id.to_string();
+                        ui.monospace(&short[..8]);
+                        ui.label(format!("{:.2} m", e.last_pos_err_m));
+                        ui.label(e.snap_count.to_string());
+                        ui.label(format!("{} ms", e.last_correction_ms));
+                        ui.end_row();
+                    }
+                });
+            ui.small("Shift+F11 exports CSV");
+        });
+}
+
 /// Read current ServerCorrection (if any) and produce raw errors.
 fn sample_reconcile_errors(
     q_sub: Query<
@@ -192,3 +433,60 @@ fn aggregate_desync_metric(
     let alpha = 1.0 - (-dt / tau).exp();
     out.adj_factor_ema = out.adj_factor_ema + alpha * (adj - out.adj_factor_ema);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_entries_count_snaps_and_sort_by_error() {
+        let (a, b) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let mut remote = RemoteDesyncMetrics::default();
+        remote.record(a, 0.5, 100);
+        remote.record(b, 12.0, 200);
+        remote.record(a, 0.01, 300);
+
+        let sorted = remote.sorted();
+        assert_eq!(sorted[0].0, b);
+        assert_eq!(sorted[0].1.snap_count, 1);
+        // A tiny error is neither a snap nor a correction
+        assert_eq!(
+            sorted[1].1,
+            DesyncEntry {
+                last_pos_err_m: 0.01,
+                snap_count: 0,
+                last_correction_ms: 100,
+            }
+        );
+        assert_eq!(remote.to_csv().lines().count(), 3);
+    }
+
+    #[test]
+    fn rtt_ewma_starts_at_the_first_round_trip_and_smooths_the_rest() {
+        let mut stats = NetClientStats::default();
+        let origin = stats.clock_origin;
+        let pong = |seq, sent_ms, server_recv_ms| PingResponse {
+            seq,
+            client_send_ms: sent_ms,
+            server_recv_ms,
+        };
+        let at = |ms| origin + std::time::Duration::from_millis(ms);
+
+        stats.record_ping_sent(1, at(1000));
+        stats.record_ping_sent(2, at(2000));
+        stats.record_ping_response(&pong(1, 1000, 50), at(1080));
+        assert_eq!(stats.rtt_ewma_ms, 80.0);
+        assert_eq!(stats.pings[0].rtt_ms, Some(80.0));
+        assert_eq!(stats.half_rtt_ms(), 40.0);
+        stats.record_ping_response(&pong(2, 2000, 1050), at(2160));
+        let smoothed = 80.0 + RTT_EWMA_ALPHA * (160.0 - 80.0);
+        assert_eq!(stats.rtt_ewma_ms, smoothed);
+        assert_eq!(stats.last_pong_server_ms, Some(1050));
+
+        // Duplicates and pings never sent leave the estimate alone
+        stats.record_ping_response(&pong(2, 2000, 1100), at(3000));
+        stats.record_ping_response(&pong(9, 2500, 1200), at(3000));
+        assert_eq!(stats.rtt_ewma_ms, smoothed);
+        assert_eq!(stats.last_pong_server_ms, Some(1050));
+    }
+}
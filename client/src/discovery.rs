@@ -0,0 +1,83 @@
+//! LAN server discovery for `--discover` (see `protocol::discovery`).
+
+use std::io;
+use std::net::{Ipv4Addr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use protocol::discovery::ServerBeacon;
+
+/// How long `--discover` listens; beacons go out every `BEACON_INTERVAL`, so this catches each
+/// server at least once.
+pub const DISCOVERY_LISTEN: Duration = Duration::from_secs(3);
+
+/// Listen for beacons on UDP `port` for `listen`.
+pub fn discover(port: u16, listen: Duration) -> io::Result<Vec<ServerBeacon>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port))?;
+    collect_beacons(&socket, listen)
+}
+
+/// Beacons arriving on `socket` within `listen`, one per server address in the order first
+/// heard, each holding the latest player count.
+pub fn collect_beacons(socket: &UdpSocket, listen: Duration) -> io::Result<Vec<ServerBeacon>> {
+    let deadline = Instant::now() + listen;
+    let mut found: Vec<ServerBeacon> = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(found);
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        let n = match socket.recv(&mut buf) {
+            Ok(n) => n,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue
+            }
+            Err(err) => return Err(err),
+        };
+        let Some(beacon) = ServerBeacon::decode(&buf[..n]) else {
+            continue;
+        };
+        match found.iter_mut().find(|b| b.addr == beacon.addr) {
+            Some(known) => *known = beacon,
+            None => found.push(beacon),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beacons_are_deduplicated_by_address() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let beacon = |addr: &str, player_count| ServerBeacon {
+            name: "lan".into(),
+            addr: addr.parse().unwrap(),
+            player_count,
+            max_players: 8,
+        };
+        let to = socket.local_addr().unwrap();
+        for b in [
+            beacon("10.0.0.1:61234", 1),
+            beacon("10.0.0.2:61234", 0),
+            beacon("10.0.0.1:61234", 2),
+        ] {
+            sender.send_to(&b.encode(), to).unwrap();
+        }
+        sender.send_to(b"not a beacon", to).unwrap();
+
+        let found = collect_beacons(&socket, Duration::from_millis(200)).unwrap();
+        assert_eq!(
+            found,
+            vec![beacon("10.0.0.1:61234", 2), beacon("10.0.0.2:61234", 0)]
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Client side of NAT hole punching (see `protocol::rendezvous`): learn the server's address
+//! from the rendezvous, then send it a few throwaway datagrams from the socket netcode will use
+//! so the local NAT already has a mapping when the server's replies arrive.
+
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use protocol::rendezvous::{HolePunchConfig, RendezvousMessage};
+use tracing::info;
+
+/// Wait between queries while the server has not registered yet.
+const QUERY_INTERVAL: Duration = Duration::from_millis(100);
+/// Datagrams sent toward the server before netcode starts; the server drops them.
+const PUNCH_PACKETS: usize = 3;
+const PUNCH_PAYLOAD: &[u8] = b"thalassocracy-punch";
+
+/// Register with the rendezvous in `cfg`, poll it until the server's address is known, and
+/// punch toward that address from `socket`. Blocks for at most `timeout`; leaves `socket`
+/// blocking without a read timeout.
+pub fn punch(
+    socket: &UdpSocket,
+    cfg: &HolePunchConfig,
+    timeout: Duration,
+) -> io::Result<SocketAddr> {
+    let deadline = Instant::now() + timeout;
+    let token = cfg.session_token;
+    let register = RendezvousMessage::Register {
+        token,
+        external_addr: None,
+    }
+    .encode();
+    let query = RendezvousMessage::Query { token }.encode();
+    socket.set_read_timeout(Some(QUERY_INTERVAL))?;
+    let mut buf = [0u8; 512];
+    let peer = 'query: loop {
+        if Instant::now() >= deadline {
+            socket.set_read_timeout(None)?;
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "server never registered with the rendezvous",
+            ));
+        }
+        socket.send_to(&register, cfg.rendezvous_addr)?;
+        socket.send_to(&query, cfg.rendezvous_addr)?;
+        let query_deadline = Instant::now() + QUERY_INTERVAL;
+        while Instant::now() < query_deadline {
+            let (n, from) = match socket.recv_from(&mut buf) {
+                Ok(received) => received,
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::WouldBlock
+                            | io::ErrorKind::TimedOut
+                            | io::ErrorKind::ConnectionReset
+                            | io::ErrorKind::ConnectionRefused
+                    ) =>
+                {
+                    break
+                }
+                Err(err) => return Err(err),
+            };
+            if from != cfg.rendezvous_addr {
+                continue;
+            }
+            if let Some(RendezvousMessage::Peer {
+                token: reply_token,
+                addr: Some(addr),
+            }) = RendezvousMessage::decode(&buf[..n])
+            {
+                if reply_token == token {
+                    break 'query addr;
+                }
+            }
+        }
+    };
+    socket.set_read_timeout(None)?;
+    for _ in 0..PUNCH_PACKETS {
+        socket.send_to(PUNCH_PAYLOAD, peer)?;
+    }
+    info!(?peer, rendezvous = ?cfg.rendezvous_addr, "Hole punched toward server");
+    Ok(peer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gives_up_when_server_never_registers() {
+        let rendezvous = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let cfg = HolePunchConfig {
+            rendezvous_addr: rendezvous.local_addr().unwrap(),
+            session_token: [1; 32],
+        };
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let err = punch(&socket, &cfg, Duration::from_millis(250)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+        // Both the registration and the queries reached the rendezvous
+        let mut buf = [0u8; 512];
+        let (n, _) = rendezvous.recv_from(&mut buf).unwrap();
+        assert!(matches!(
+            RendezvousMessage::decode(&buf[..n]),
+            Some(RendezvousMessage::Register { token: [1, ..], .. })
+        ));
+        let (n, _) = rendezvous.recv_from(&mut buf).unwrap();
+        assert!(matches!(
+            RendezvousMessage::decode(&buf[..n]),
+            Some(RendezvousMessage::Query { token: [1, ..] })
+        ));
+    }
+}
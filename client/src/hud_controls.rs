@@ -1,8 +1,8 @@
 use crate::input::ThrustInput;
-use crate::net::{ConnectStart, TimeSync};
+use crate::net::{ConnectStart, InputBatcher, InputRateLimit, TimeSync};
 use crate::sim_pause::SimPause;
 use bevy::prelude::*;
-use bevy_renet::renet::{DefaultChannel, RenetClient};
+use bevy_renet::renet::RenetClient;
 
 #[cfg(feature = "windowing")]
 use bevy_egui::EguiPrimaryContextPass;
@@ -41,9 +41,9 @@ fn ui_thrust_slider(
             ui.add_space(8.0);
 
             // Pause toggle (client + server via network message)
-            let mut p = paused.0;
+            let mut p = paused.paused;
             if ui.checkbox(&mut p, "Pause").clicked() {
-                paused.0 = p;
+                paused.paused = p;
             }
             ui.add_space(8.0);
 
@@ -91,10 +91,36 @@ fn ui_thrust_slider(
                 thrust.pump_aft = pa;
             }
 
+            ui.separator();
+            ui.label("Planes (+ = dive)");
+            let mut pl = thrust.plane;
+            let slider_pl = Slider::new(&mut pl, -1.0..=1.0)
+                .vertical()
+                .clamping(SliderClamping::Always);
+            ui.add(slider_pl);
+            if (pl - thrust.plane).abs() > f32::EPSILON {
+                thrust.plane = pl;
+            }
+
+            ui.label("Roll trim (+ = stbd)");
+            let mut rt = thrust.roll_trim;
+            let slider_rt = Slider::new(&mut rt, -1.0..=1.0)
+                .vertical()
+                .clamping(SliderClamping::Always);
+            ui.add(slider_rt);
+            if (rt - thrust.roll_trim).abs() > f32::EPSILON {
+                thrust.roll_trim = rt;
+            }
+
             ui.add_space(6.0);
             ui.monospace(format!(
-                "T {:.2} | R {:.2}\nPF {:.2} | PA {:.2}",
-                thrust.value, thrust.yaw, thrust.pump_fwd, thrust.pump_aft
+                "T {:.2} | R {:.2}\nPF {:.2} | PA {:.2}\nDP {:.2} | RT {:.2}",
+                thrust.value,
+                thrust.yaw,
+                thrust.pump_fwd,
+                thrust.pump_aft,
+                thrust.plane,
+                thrust.roll_trim
             ));
         });
 }
@@ -110,30 +136,44 @@ fn send_pause_request(
     if !client.is_connected() {
         return;
     }
-    let cur = paused.0;
+    let cur = paused.paused;
     if last.map(|v| v == cur).unwrap_or(false) {
         return;
     }
     let msg = protocol::ClientToServer::PauseRequest(protocol::PauseRequest { paused: cur });
     if let Ok(bytes) = protocol::encode(&msg) {
-        client.send_message(DefaultChannel::ReliableOrdered, bytes);
+        client.send_message(protocol::Channel::Reliable, bytes);
     }
     *last = Some(cur);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn send_thrust_input(
     client: Option<ResMut<RenetClient>>,
     mut thrust: ResMut<ThrustInput>,
     connect: Option<Res<ConnectStart>>,
     tsync: Option<Res<TimeSync>>,
+    rate_limit: Option<Res<InputRateLimit>>,
+    time: Res<Time>,
+    mut batcher: ResMut<InputBatcher>,
+    mut last_sent_at: Local<Option<std::time::Duration>>,
 ) {
     let Some(mut client) = client else {
         return;
     };
-    // For now, send every frame if connected. Later: send on change or at a fixed input rate.
+    // Send every frame if connected, unless the server has asked us to slow down
     if !client.is_connected() {
+        // Ticks queued for a dropped connection are stale by the next one
+        batcher.pending.clear();
+        *last_sent_at = None;
         return;
     }
+    if let (Some(limit), Some(at)) = (rate_limit, *last_sent_at) {
+        if time.elapsed().saturating_sub(at) < limit.min_interval() {
+            return;
+        }
+    }
+    *last_sent_at = Some(time.elapsed());
     thrust.tick = thrust.tick.wrapping_add(1);
     // Compute server-time stamped event scheduled slightly ahead (30 ms) to reduce timing disagreement
     let ahead_ms: u64 = 30;
@@ -147,22 +187,28 @@ fn send_thrust_input(
             yaw: thrust.yaw,
             pump_fwd: thrust.pump_fwd,
             pump_aft: thrust.pump_aft,
+            pitch: thrust.plane,
+            roll_trim: thrust.roll_trim,
         };
         let msg = protocol::ClientToServer::InputEvent(ev);
         if let Ok(bytes) = protocol::encode(&msg) {
-            client.send_message(DefaultChannel::ReliableOrdered, bytes);
+            client.send_message(protocol::Channel::Reliable, bytes);
         }
     } else {
-        // Fallback: send legacy tick message
-        let msg = protocol::ClientToServer::InputTick(protocol::InputTick {
+        // Fallback: queue legacy tick messages and send them in batches on the input channel
+        let curr = protocol::InputTick {
             tick: thrust.tick,
             thrust: thrust.value,
             yaw: thrust.yaw,
             pump_fwd: thrust.pump_fwd,
             pump_aft: thrust.pump_aft,
-        });
-        if let Ok(bytes) = protocol::encode(&msg) {
-            client.send_message(DefaultChannel::ReliableOrdered, bytes);
+            pitch: thrust.plane,
+            roll_trim: thrust.roll_trim,
+        };
+        if let Some(msg) = batcher.push(curr) {
+            if let Ok(bytes) = protocol::encode(&msg) {
+                client.send_message(protocol::Channel::Input, bytes);
+            }
         }
     }
 }
@@ -17,6 +17,10 @@ pub(super) struct BallastAftFill;
 #[derive(Component)]
 pub(super) struct BallastBuoyText;
 
+/// Turbo pump boost or cooldown; empty for standard pumps.
+#[derive(Component)]
+pub(super) struct BallastPumpText;
+
 pub(super) fn spawn_ballast_hud(mut commands: Commands) {
     // Bottom-right container
     commands
@@ -26,7 +30,7 @@ pub(super) fn spawn_ballast_hud(mut commands: Commands) {
                 bottom: Val::Px(24.0),
                 right: Val::Px(24.0),
                 width: Val::Px(GAUGE_W * 2.0 + GAUGE_GAP + 8.0),
-                height: Val::Px(GAUGE_H + 40.0),
+                height: Val::Px(GAUGE_H + 60.0),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::End,
                 flex_direction: FlexDirection::Column,
@@ -115,6 +119,17 @@ pub(super) fn spawn_ballast_hud(mut commands: Commands) {
                 BallastBuoyText,
                 Name::new("Buoyancy Text"),
             ));
+
+            root.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..Default::default()
+                },
+                TextColor(Color::WHITE),
+                BallastPumpText,
+                Name::new("Pump Turbo Text"),
+            ));
         });
 }
 
@@ -122,7 +137,8 @@ pub(super) fn update_ballast_hud(
     telemetry: Option<Res<crate::scene::submarine::SubTelemetry>>,
     mut q_fwd: Query<&mut Node, (With<BallastFwdFill>, Without<BallastAftFill>)>,
     mut q_aft: Query<&mut Node, (With<BallastAftFill>, Without<BallastFwdFill>)>,
-    mut q_txt: Query<&mut Text, With<BallastBuoyText>>,
+    mut q_txt: Query<&mut Text, (With<BallastBuoyText>, Without<BallastPumpText>)>,
+    mut q_pump: Query<(&mut Text, &mut TextColor), With<BallastPumpText>>,
 ) {
     let Some(t) = telemetry else {
         return;
@@ -152,4 +168,17 @@ pub(super) fn update_ballast_hud(
         };
         txt.0 = format!("Buoyancy: net {b:>7.1} N");
     }
+    if let Ok((mut txt, mut color)) = q_pump.single_mut() {
+        match d.pump_turbo {
+            Some(pump) if pump.is_cooling_down() => {
+                txt.0 = format!("Turbo: cooldown {:>4.1} s", pump.cooldown_remaining_s);
+                color.0 = Color::srgb(1.0, 0.6, 0.2);
+            }
+            Some(pump) => {
+                txt.0 = format!("Turbo: ready {:>4.1} s", pump.boost_remaining_s);
+                color.0 = Color::WHITE;
+            }
+            None => txt.0.clear(),
+        }
+    }
 }
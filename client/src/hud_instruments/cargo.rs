@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use protocol::ResourceType;
+use std::collections::HashMap;
+
+use crate::net::CargoInventory;
+
+#[derive(Component)]
+pub(super) struct CargoHudText;
+
+/// One line per resource aboard, in a fixed order, or a single line for an empty hold.
+pub fn cargo_summary(inventory: &HashMap<ResourceType, u32>) -> String {
+    let lines: Vec<String> = [
+        (ResourceType::Ore, "Ore"),
+        (ResourceType::Crystal, "Crystal"),
+        (ResourceType::RareMineral, "Rare mineral"),
+    ]
+    .into_iter()
+    .filter_map(|(resource_type, label)| {
+        let kg = inventory.get(&resource_type).copied().unwrap_or(0);
+        (kg > 0).then(|| format!("{label:<13}{kg:>5} kg"))
+    })
+    .collect();
+    if lines.is_empty() {
+        "Hold empty".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+pub(super) fn spawn_cargo_hud(mut commands: Commands) {
+    // Top-left, below the debug mode label
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            left: Val::Px(12.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.35)),
+        Text::new(cargo_summary(&HashMap::new())),
+        TextFont {
+            font_size: 14.0,
+            ..Default::default()
+        },
+        TextColor(Color::WHITE),
+        CargoHudText,
+        Name::new("Cargo HUD"),
+    ));
+}
+
+pub(super) fn update_cargo_hud(
+    inventory: Res<CargoInventory>,
+    mut q_txt: Query<&mut Text, With<CargoHudText>>,
+) {
+    if !inventory.is_changed() {
+        return;
+    }
+    if let Ok(mut txt) = q_txt.single_mut() {
+        txt.0 = cargo_summary(&inventory.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_lists_resources_aboard_in_a_fixed_order() {
+        assert_eq!(cargo_summary(&HashMap::new()), "Hold empty");
+        let hold = HashMap::from([
+            (ResourceType::RareMineral, 12),
+            (ResourceType::Crystal, 0),
+            (ResourceType::Ore, 150),
+        ]);
+        assert_eq!(
+            cargo_summary(&hold),
+            "Ore            150 kg\nRare mineral    12 kg"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+use levels::TunnelSpec;
+
+use crate::campaign::CurrentLevel;
+use crate::debug_vis::DebugVis;
+use crate::scene::flow_field::TunnelBounds;
+use crate::scene::submarine::{SubStateComp, Submarine};
+
+pub const HEADING_SECTORS: usize = 16;
+/// Look-ahead used to decide whether a heading is safe.
+const HORIZON_S: f32 = 2.0;
+/// Below this speed there is no meaningful collision risk and the ring hides.
+const MIN_SPEED_M_S: f32 = 1.0;
+
+const RING_SIZE: f32 = 120.0; // px
+const MARK_SIZE: f32 = 10.0; // px
+
+/// Per-sector safety for world headings `i * 360°/16`, measured like `yaw_of` (0 = +Z, +90° = +X).
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct SafeHeadings(pub [bool; HEADING_SECTORS]);
+
+impl Default for SafeHeadings {
+    fn default() -> Self {
+        Self([true; HEADING_SECTORS])
+    }
+}
+
+#[derive(Component)]
+pub(super) struct CompassRoot;
+
+#[derive(Component)]
+pub(super) struct CompassSector(usize);
+
+pub fn sector_heading(i: usize) -> f32 {
+    i as f32 * std::f32::consts::TAU / HEADING_SECTORS as f32
+}
+
+/// Ray-cast horizontally in each sector direction against the tunnel side walls. The tunnel's
+/// end faces open onto the room and chamber and never count as hits; outside the tunnel every
+/// heading is reported safe.
+pub fn safe_heading_sectors(
+    tunnel: &TunnelSpec,
+    pos: Vec3,
+    horizon_m: f32,
+) -> [bool; HEADING_SECTORS] {
+    let mut safe = [true; HEADING_SECTORS];
+    let bounds = TunnelBounds { size: tunnel.size };
+    if !bounds.contains_point(tunnel.pos, pos) {
+        return safe;
+    }
+    let half = tunnel.size * 0.5;
+    let local = pos - tunnel.pos;
+    for (i, s) in safe.iter_mut().enumerate() {
+        let h = sector_heading(i);
+        let dir_z = h.cos();
+        if dir_z.abs() < 1e-4 {
+            continue;
+        }
+        let wall_z = if dir_z > 0.0 { half.z } else { -half.z };
+        let dist = (wall_z - local.z) / dir_z;
+        *s = dist > horizon_m;
+    }
+    safe
+}
+
+pub(super) fn predict_safe_headings(
+    vis: Option<Res<DebugVis>>,
+    q_sub: Query<&SubStateComp, With<Submarine>>,
+    mut safe: ResMut<SafeHeadings>,
+    level: Res<CurrentLevel>,
+) {
+    if vis.is_some_and(|v| !v.collision_prediction_enabled) {
+        return;
+    }
+    let Ok(state) = q_sub.single() else {
+        return;
+    };
+    let speed = state.0.speed();
+    let sectors = safe_heading_sectors(&level.spec().tunnel, state.0.position, speed * HORIZON_S);
+    if safe.0 != sectors {
+        safe.0 = sectors;
+    }
+}
+
+pub(super) fn spawn_compass(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(24.0),
+                right: Val::Px(24.0),
+                width: Val::Px(RING_SIZE),
+                height: Val::Px(RING_SIZE),
+                ..Default::default()
+            },
+            Visibility::Hidden,
+            CompassRoot,
+            Name::new("Compass Root"),
+        ))
+        .with_children(|ring| {
+            for i in 0..HEADING_SECTORS {
+                ring.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(MARK_SIZE),
+                        height: Val::Px(MARK_SIZE),
+                        ..Default::default()
+                    },
+                    BackgroundColor(Color::NONE),
+                    BorderRadius::all(Val::Px(MARK_SIZE * 0.5)),
+                    CompassSector(i),
+                    Name::new(format!("Compass Sector {i}")),
+                ));
+            }
+        });
+}
+
+/// Lay the sectors out around the ring with the sub's heading at the top, and color them.
+pub(super) fn draw_compass(
+    vis: Option<Res<DebugVis>>,
+    safe: Res<SafeHeadings>,
+    q_sub: Query<&SubStateComp, With<Submarine>>,
+    mut q_root: Query<&mut Visibility, With<CompassRoot>>,
+    mut q_sectors: Query<(&CompassSector, &mut Node, &mut BackgroundColor)>,
+) {
+    let Ok(mut root_vis) = q_root.single_mut() else {
+        return;
+    };
+    let enabled = vis.is_none_or(|v| v.collision_prediction_enabled);
+    let state = q_sub.single().ok();
+    let show = enabled && state.is_some_and(|s| s.0.speed() > MIN_SPEED_M_S);
+    let want = if show {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    if *root_vis != want {
+        *root_vis = want;
+    }
+    let Some(state) = state.filter(|_| show) else {
+        return;
+    };
+
+    let yaw = state.0.heading_yaw();
+    let r = RING_SIZE * 0.5 - MARK_SIZE * 0.5;
+    let center = RING_SIZE * 0.5 - MARK_SIZE * 0.5;
+    for (sector, mut node, mut color) in &mut q_sectors {
+        // Increasing yaw turns to port (left), so it maps to -x on screen
+        let rel = sector_heading(sector.0) - yaw;
+        node.left = Val::Px(center - r * rel.sin());
+        node.top = Val::Px(center - r * rel.cos());
+        color.0 = if safe.0[sector.0] {
+            Color::srgba(0.2, 1.0, 0.3, 0.9)
+        } else {
+            Color::srgba(1.0, 0.2, 0.2, 0.9)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headings_toward_near_wall_are_unsafe() {
+        let tunnel = levels::builtins::greybox_level().tunnel;
+        // 2 m from the +Z side wall
+        let pos = tunnel.pos + Vec3::new(0.0, 0.0, tunnel.size.z * 0.5 - 2.0);
+        let safe = safe_heading_sectors(&tunnel, pos, 6.0);
+        assert!(!safe[0], "heading straight at the wall must be unsafe");
+        assert!(
+            safe[HEADING_SECTORS / 2],
+            "heading away from the wall must be safe"
+        );
+        assert!(
+            safe[HEADING_SECTORS / 4],
+            "running along the tunnel must be safe"
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use bevy::prelude::*;
+use levels::{sample_flow_at, TunnelSpec};
+
+use crate::campaign::CurrentLevel;
+use crate::scene::submarine::{SubStateComp, Submarine};
+
+/// Horizontal bands the tunnel's height is split into, shallowest first.
+pub const DEPTH_FLOW_BANDS: usize = 8;
+
+const BAR_H: f32 = 160.0; // px
+const BAR_W: f32 = 72.0; // px
+const BORDER_THICKNESS: f32 = 2.0; // px
+const SHAFT_H: f32 = 4.0; // px
+const SMOOTH_ALPHA: f32 = 0.2; // EMA for band flows
+/// Flow speed (m/s) drawn as a full-width arrow.
+const FULL_SCALE_M_S: f32 = 3.0;
+/// Cross-tunnel flow (m/s along world Z) at which the hue saturates to red or blue.
+const LATERAL_FULL_SCALE_M_S: f32 = 0.5;
+
+#[derive(Component)]
+pub(super) struct DepthFlowRangeText;
+
+#[derive(Component)]
+pub(super) struct DepthFlowBand(usize);
+
+#[derive(Component)]
+pub(super) struct DepthFlowShaft(usize);
+
+#[derive(Component)]
+pub(super) struct DepthFlowHead(usize);
+
+/// Where the sub sits within the tunnel's depth range.
+#[derive(Component)]
+pub(super) struct DepthFlowSubMarker;
+
+/// Smoothed flow at each band's depth below the sub.
+#[derive(Resource, Default, Clone, Copy)]
+pub struct DepthFlowSamples {
+    pub(crate) flows: [Vec3; DEPTH_FLOW_BANDS],
+    /// Sub height within the tunnel: 0 at the ceiling, 1 at the floor.
+    pub(crate) sub_frac: f32,
+}
+
+/// World heights of the band centers, evenly spaced from the tunnel ceiling down.
+pub fn band_sample_heights(tunnel: &TunnelSpec) -> [f32; DEPTH_FLOW_BANDS] {
+    let top = tunnel.pos.y + tunnel.size.y * 0.5;
+    let band_h = tunnel.size.y / DEPTH_FLOW_BANDS as f32;
+    std::array::from_fn(|i| top - (i as f32 + 0.5) * band_h)
+}
+
+/// Green for flow straight down the tunnel, shading to blue for +Z and red for -Z drift.
+pub fn lateral_flow_color(flow: Vec3) -> Color {
+    let k = (flow.z / LATERAL_FULL_SCALE_M_S).clamp(-1.0, 1.0);
+    let k = if k.is_finite() { k } else { 0.0 };
+    Color::hsl(120.0 + 120.0 * k, 0.9, 0.55)
+}
+
+pub(super) fn spawn_depth_flow_bar(mut commands: Commands) {
+    // Right side, below the compass
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(160.0),
+                right: Val::Px(24.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                ..Default::default()
+            },
+            BackgroundColor(Color::NONE),
+            Name::new("Depth Flow Root"),
+        ))
+        .with_children(|root| {
+            // Depth range of the tunnel above the bar
+            root.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 12.0,
+                    ..Default::default()
+                },
+                TextColor(Color::WHITE),
+                DepthFlowRangeText,
+                Name::new("Depth Flow Range Text"),
+            ));
+            root.spawn((
+                Node {
+                    width: Val::Px(BAR_W),
+                    height: Val::Px(BAR_H),
+                    border: UiRect::all(Val::Px(BORDER_THICKNESS)),
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                BorderColor(Color::srgba(1.0, 1.0, 1.0, 0.6)),
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.25)),
+                Name::new("Depth Flow Bar"),
+            ))
+            .with_children(|bar| {
+                for i in 0..DEPTH_FLOW_BANDS {
+                    bar.spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            flex_grow: 1.0,
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        DepthFlowBand(i),
+                        Name::new(format!("Depth Flow Band {i}")),
+                    ))
+                    .with_children(|band| {
+                        band.spawn((
+                            Node {
+                                width: Val::Px(0.0), // updated at runtime
+                                height: Val::Px(SHAFT_H),
+                                ..Default::default()
+                            },
+                            BackgroundColor(Color::WHITE),
+                            DepthFlowShaft(i),
+                        ));
+                        band.spawn((
+                            Text::new(""),
+                            TextFont {
+                                font_size: 12.0,
+                                ..Default::default()
+                            },
+                            TextColor(Color::WHITE),
+                            DepthFlowHead(i),
+                        ));
+                    });
+                }
+                // Sub depth tick (absolute within the bar)
+                bar.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(0.0),
+                        width: Val::Percent(100.0),
+                        height: Val::Px(2.0),
+                        ..Default::default()
+                    },
+                    BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.8)),
+                    Visibility::Hidden,
+                    DepthFlowSubMarker,
+                    Name::new("Depth Flow Sub Marker"),
+                ));
+            });
+        });
+}
+
+pub(super) fn sample_depth_flow(
+    time: Res<Time>,
+    q: Query<&SubStateComp, With<Submarine>>,
+    level: Res<CurrentLevel>,
+    mut samples: ResMut<DepthFlowSamples>,
+) {
+    let Ok(state_comp) = q.single() else {
+        return;
+    };
+    let s = &state_comp.0;
+    let level = level.spec();
+    let t = time.elapsed_secs();
+    for (i, y) in band_sample_heights(&level.tunnel).into_iter().enumerate() {
+        let (flow, _var) = sample_flow_at(level, Vec3::new(s.position.x, y, s.position.z), t);
+        samples.flows[i] = samples.flows[i].lerp(flow, SMOOTH_ALPHA);
+    }
+    let top = level.tunnel.pos.y + level.tunnel.size.y * 0.5;
+    samples.sub_frac = (top - s.position.y) / level.tunnel.size.y;
+}
+
+#[allow(clippy::type_complexity)]
+pub(super) fn draw_depth_flow_bar(
+    samples: Res<DepthFlowSamples>,
+    level: Res<CurrentLevel>,
+    mut q_bands: Query<
+        (&DepthFlowBand, &mut Node),
+        (Without<DepthFlowShaft>, Without<DepthFlowSubMarker>),
+    >,
+    mut q_shafts: Query<
+        (&DepthFlowShaft, &mut Node, &mut BackgroundColor),
+        Without<DepthFlowSubMarker>,
+    >,
+    mut q_heads: Query<(&DepthFlowHead, &mut Text, &mut TextColor), Without<DepthFlowRangeText>>,
+    mut q_marker: Query<(&mut Node, &mut Visibility), With<DepthFlowSubMarker>>,
+    mut q_range: Query<&mut Text, With<DepthFlowRangeText>>,
+) {
+    let tunnel = &level.spec().tunnel;
+    if let Ok(mut txt) = q_range.single_mut() {
+        let ceiling_m = -(tunnel.pos.y + tunnel.size.y * 0.5);
+        let floor_m = -(tunnel.pos.y - tunnel.size.y * 0.5);
+        txt.0 = format!("{ceiling_m:.0}-{floor_m:.0} m");
+    }
+
+    // Arrows point along the flow's world X, the tunnel's axis
+    let inner_w = BAR_W - 2.0 * BORDER_THICKNESS;
+    for (band, mut node) in &mut q_bands {
+        node.flex_direction = if samples.flows[band.0].x < 0.0 {
+            FlexDirection::RowReverse
+        } else {
+            FlexDirection::Row
+        };
+    }
+    for (shaft, mut node, mut color) in &mut q_shafts {
+        let flow = samples.flows[shaft.0];
+        let k = (flow.length() / FULL_SCALE_M_S).clamp(0.0, 1.0);
+        node.width = Val::Px(k * (inner_w - 12.0));
+        *color = BackgroundColor(lateral_flow_color(flow));
+    }
+    for (head, mut txt, mut color) in &mut q_heads {
+        let flow = samples.flows[head.0];
+        txt.0 = if flow.length() < 0.05 {
+            String::new()
+        } else if flow.x < 0.0 {
+            "<".to_string()
+        } else {
+            ">".to_string()
+        };
+        color.0 = lateral_flow_color(flow);
+    }
+    if let Ok((mut node, mut vis)) = q_marker.single_mut() {
+        let inside = (0.0..=1.0).contains(&samples.sub_frac);
+        *vis = if inside {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if inside {
+            let inner_h = BAR_H - 2.0 * BORDER_THICKNESS;
+            node.top = Val::Px(samples.sub_frac * inner_h - 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bands_span_the_tunnel_from_ceiling_to_floor() {
+        let tunnel = levels::builtins::greybox_level().tunnel;
+        let heights = band_sample_heights(&tunnel);
+        let top = tunnel.pos.y + tunnel.size.y * 0.5;
+        let bottom = tunnel.pos.y - tunnel.size.y * 0.5;
+        let band_h = tunnel.size.y / DEPTH_FLOW_BANDS as f32;
+        assert!((heights[0] - (top - 0.5 * band_h)).abs() < 1e-4);
+        assert!((heights[DEPTH_FLOW_BANDS - 1] - (bottom + 0.5 * band_h)).abs() < 1e-4);
+        for pair in heights.windows(2) {
+            assert!((pair[0] - pair[1] - band_h).abs() < 1e-4, "{heights:?}");
+        }
+    }
+
+    #[test]
+    fn hue_follows_lateral_flow() {
+        let hue = |z: f32| Hsla::from(lateral_flow_color(Vec3::new(2.0, 0.0, z))).hue;
+        assert!((hue(0.0) - 120.0).abs() < 1e-3);
+        assert!((hue(1.0) - 240.0).abs() < 1e-3);
+        assert!(hue(-1.0).abs() < 1e-3);
+        assert!(hue(0.1) > 120.0 && hue(0.1) < 240.0);
+    }
+}
@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use levels::DiveDepthEvent;
+use tracing::warn;
+
+use crate::notifications::NotificationLog;
+
+/// Flashes per second inside the warning band; past the limit the banner flashes twice as fast.
+const WARNING_FLASH_HZ: f32 = 1.5;
+const WARNING_RED: Color = Color::srgb(1.0, 0.15, 0.1);
+
+/// Server `DepthWarning`s, forwarded by `pump_network`.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DepthWarningReceived(pub protocol::DepthWarning);
+
+/// Red banner shown while the predicted sub is past its `DiveDepthLimit::warning_depth_m`.
+#[derive(Component)]
+pub struct DepthWarningHud;
+
+/// Banner opacity `t` seconds in: a cosine pulse between 0.25 and 1 at `hz`.
+pub fn depth_warning_alpha(t: f32, hz: f32) -> f32 {
+    0.625 + 0.375 * (std::f32::consts::TAU * hz * t).cos()
+}
+
+/// Note each server warning in the notification log; ejections are only reported this way.
+pub fn log_depth_warnings(
+    mut warnings: EventReader<DepthWarningReceived>,
+    mut log: ResMut<NotificationLog>,
+) {
+    for DepthWarningReceived(w) in warnings.read() {
+        warn!(
+            depth_m = w.depth_m,
+            max_depth_m = w.max_depth_m,
+            exceeded = w.exceeded,
+            ejected = w.ejected,
+            "Depth warning"
+        );
+        log.push(if w.ejected {
+            format!(
+                "Sank past {:.0} m: returned to the nearest spawn point",
+                w.max_depth_m
+            )
+        } else if w.exceeded {
+            format!("Past maximum depth {:.0} m: emergency blow", w.max_depth_m)
+        } else {
+            format!(
+                "Depth {:.0} m: approaching the {:.0} m limit",
+                w.depth_m, w.max_depth_m
+            )
+        });
+    }
+}
+
+pub(super) fn spawn_depth_warning_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(64.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            Name::new("Depth Warning Root"),
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 28.0,
+                    ..Default::default()
+                },
+                TextColor(WARNING_RED),
+                Visibility::Hidden,
+                DepthWarningHud,
+                Name::new("Depth Warning"),
+            ));
+        });
+}
+
+pub(super) fn update_depth_warning_hud(
+    time: Res<Time>,
+    telemetry: Option<Res<crate::scene::submarine::SubTelemetry>>,
+    mut q: Query<(&mut Text, &mut TextColor, &mut Visibility), With<DepthWarningHud>>,
+) {
+    let Ok((mut text, mut color, mut vis)) = q.single_mut() else {
+        return;
+    };
+    let (label, hz) = match telemetry.and_then(|t| t.0.depth_event) {
+        None => {
+            vis.set_if_neq(Visibility::Hidden);
+            return;
+        }
+        Some(DiveDepthEvent::Warning { depth_m }) => {
+            (format!("DEPTH WARNING {depth_m:.0} m"), WARNING_FLASH_HZ)
+        }
+        Some(DiveDepthEvent::Exceeded { depth_m }) => (
+            format!("MAX DEPTH EXCEEDED {depth_m:.0} m - BLOWING BALLAST"),
+            2.0 * WARNING_FLASH_HZ,
+        ),
+    };
+    vis.set_if_neq(Visibility::Inherited);
+    if text.0 != label {
+        text.0 = label;
+    }
+    color
+        .0
+        .set_alpha(depth_warning_alpha(time.elapsed_secs(), hz));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn banner_pulses_without_vanishing() {
+        assert!((depth_warning_alpha(0.0, 2.0) - 1.0).abs() < 1e-6);
+        assert!((depth_warning_alpha(0.25, 2.0) - 0.25).abs() < 1e-5);
+        assert!((depth_warning_alpha(0.5, 2.0) - 1.0).abs() < 1e-5);
+        for i in 0..100 {
+            let a = depth_warning_alpha(i as f32 * 0.013, WARNING_FLASH_HZ);
+            assert!((0.25 - 1e-5..=1.0 + 1e-5).contains(&a), "{a}");
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
-use levels::{builtins::greybox_level, sample_flow_at, Vec3f};
+use levels::sample_flow_at;
+
+use crate::campaign::CurrentLevel;
 
 const INSTR_SIZE: f32 = 140.0; // px
 const RING_THICKNESS: f32 = 2.0; // px
@@ -102,6 +104,7 @@ pub(super) fn update_hud_instr_state(
         ),
         With<crate::scene::submarine::Submarine>,
     >,
+    level: Res<CurrentLevel>,
 ) {
     let Ok((state_comp, mut hud)) = q.single_mut() else {
         return;
@@ -109,16 +112,8 @@ pub(super) fn update_hud_instr_state(
 
     let s = &state_comp.0;
     // Sample flow at sub position
-    let level = greybox_level();
-    let (flow, _var) = sample_flow_at(
-        &level,
-        Vec3f {
-            x: s.position.x,
-            y: s.position.y,
-            z: s.position.z,
-        },
-        time.elapsed_secs(),
-    );
+    let level = level.spec();
+    let (flow, _var) = sample_flow_at(level, s.position, time.elapsed_secs());
     let rel = Vec3::new(
         s.velocity.x - flow.x,
         s.velocity.y - flow.y,
@@ -1,27 +1,51 @@
 use bevy::prelude::*;
 
 pub mod ballast;
+pub mod cargo;
+pub mod compass;
+pub mod depth_flow;
+pub mod depth_warning;
 pub mod flow;
 
+pub use compass::SafeHeadings;
 pub use flow::HudInstrumentState;
 
 pub struct HudInstrumentsPlugin;
 
 impl Plugin for HudInstrumentsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Startup,
-            (flow::spawn_flow_instr, ballast::spawn_ballast_hud),
-        )
-        .add_systems(
-            Update,
-            (
-                sanitize_ui_nodes,
-                flow::update_hud_instr_state,
-                flow::draw_flow_instr,
-                ballast::update_ballast_hud,
-            ),
-        );
+        app.init_resource::<SafeHeadings>()
+            .init_resource::<depth_flow::DepthFlowSamples>()
+            .add_systems(
+                Startup,
+                (
+                    flow::spawn_flow_instr,
+                    ballast::spawn_ballast_hud,
+                    cargo::spawn_cargo_hud,
+                    compass::spawn_compass,
+                    depth_flow::spawn_depth_flow_bar,
+                    depth_warning::spawn_depth_warning_hud,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    sanitize_ui_nodes,
+                    flow::update_hud_instr_state,
+                    flow::draw_flow_instr,
+                    ballast::update_ballast_hud,
+                    cargo::update_cargo_hud,
+                    (
+                        depth_flow::sample_depth_flow,
+                        depth_flow::draw_depth_flow_bar,
+                    )
+                        .chain(),
+                    depth_warning::update_depth_warning_hud.after(crate::scene::SimSet),
+                    (compass::predict_safe_headings, compass::draw_compass)
+                        .chain()
+                        .after(crate::scene::SimSet),
+                ),
+            );
     }
 }
 
@@ -35,11 +59,9 @@ fn sanitize_ui_nodes(mut q: Query<(Entity, &mut Node, Option<&Name>)>) {
                     dirty.push(label);
                 }
             }
-            Val::Percent(p) => {
-                if !p.is_finite() || *p > 1.0e6 || *p < -1.0e6 {
-                    *v = Val::Px(0.0);
-                    dirty.push(label);
-                }
+            Val::Percent(p) if !p.is_finite() || *p > 1.0e6 || *p < -1.0e6 => {
+                *v = Val::Px(0.0);
+                dirty.push(label);
             }
             _ => {}
         }
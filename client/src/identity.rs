@@ -0,0 +1,165 @@
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::Args;
+
+/// Stable player identity persisted between sessions so the server can recognize returning
+/// players.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientIdentity {
+    /// Public id, sent in `ClientHello` and shown to every other player.
+    pub player_uuid: Uuid,
+    /// Empty means anonymous.
+    pub display_name: String,
+    /// Private netcode client id. Only the server sees it, and it restores a departed player's
+    /// state for whoever reconnects with it, so knowing `player_uuid` is not enough. 0 in
+    /// identity files from before it existed, until `load_or_create_identity` fills it in.
+    #[serde(default)]
+    pub secret: u64,
+}
+
+impl ClientIdentity {
+    pub fn new(display_name: impl Into<String>) -> Self {
+        Self {
+            player_uuid: Uuid::new_v4(),
+            display_name: display_name.into(),
+            secret: new_secret(),
+        }
+    }
+
+    /// Netcode client id: the identity's `secret`, never derived from the public UUID.
+    pub fn client_id(&self) -> u64 {
+        self.secret
+    }
+
+    /// Name for `ClientHello`; `None` when anonymous.
+    pub fn hello_name(&self) -> Option<String> {
+        (!self.display_name.is_empty()).then(|| self.display_name.clone())
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let s = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&s)?)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// `$XDG_CONFIG_HOME/thalassocracy/identity.toml`, falling back to `~/.config`.
+pub fn default_identity_path() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("thalassocracy").join("identity.toml"))
+}
+
+/// Load the identity file, or create and save a new identity if there is none. `--name`
+/// overrides (and updates) the saved name; without it, a new identity asks on stdin when run
+/// from a terminal. With `--ephemeral-identity` nothing is read or written.
+pub fn load_or_create_identity(args: &Args) -> ClientIdentity {
+    if args.ephemeral_identity {
+        return ClientIdentity::new(args.name.clone().unwrap_or_default());
+    }
+    let Some(path) = args.identity.clone().or_else(default_identity_path) else {
+        warn!("No config directory; using a throwaway identity");
+        return ClientIdentity::new(args.name.clone().unwrap_or_default());
+    };
+
+    let mut identity = match ClientIdentity::load(&path) {
+        Ok(identity) => identity,
+        Err(err) => {
+            if path.exists() {
+                warn!(path = %path.display(), %err, "Unreadable identity file; replacing it");
+            }
+            let name = args.name.clone().unwrap_or_else(prompt_display_name);
+            let identity = ClientIdentity::new(name);
+            save_identity(&identity, &path);
+            info!(player_uuid = %identity.player_uuid, "Created new player identity");
+            return identity;
+        }
+    };
+    let mut changed = false;
+    if let Some(name) = args.name.as_ref().filter(|n| **n != identity.display_name) {
+        identity.display_name = name.clone();
+        changed = true;
+    }
+    if identity.secret == 0 {
+        identity.secret = new_secret();
+        changed = true;
+    }
+    if changed {
+        save_identity(&identity, &path);
+    }
+    identity
+}
+
+/// 63 random bits, so the secret fits a TOML integer; never 0.
+fn new_secret() -> u64 {
+    (Uuid::new_v4().as_u64_pair().0 >> 1).max(1)
+}
+
+fn save_identity(identity: &ClientIdentity, path: &Path) {
+    if let Err(err) = identity.save(path) {
+        warn!(path = %path.display(), %err, "Failed to save player identity");
+    }
+}
+
+fn prompt_display_name() -> String {
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        return String::new();
+    }
+    print!("Display name: ");
+    let _ = std::io::stdout().flush();
+    let mut line = String::new();
+    if stdin.lock().read_line(&mut line).is_err() {
+        return String::new();
+    }
+    line.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_round_trips_through_file() {
+        let path = std::env::temp_dir()
+            .join(format!("thalassocracy-{}", Uuid::new_v4()))
+            .join("identity.toml");
+        let identity = ClientIdentity::new("Nemo");
+        identity.save(&path).unwrap();
+        let loaded = ClientIdentity::load(&path).unwrap();
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(loaded, identity);
+        assert_eq!(loaded.client_id(), identity.client_id());
+    }
+
+    #[test]
+    fn client_id_is_not_derived_from_the_public_uuid() {
+        let identity = ClientIdentity::new("Nemo");
+        let uuid = identity.player_uuid.as_bytes();
+        assert_ne!(
+            identity.client_id(),
+            u64::from_le_bytes(uuid[0..8].try_into().unwrap())
+        );
+        // Identity files from before the secret read as 0; `load_or_create_identity` replaces it
+        let old: ClientIdentity = toml::from_str(&format!(
+            "player_uuid = \"{}\"\ndisplay_name = \"\"",
+            identity.player_uuid
+        ))
+        .unwrap();
+        assert_eq!(old.secret, 0);
+    }
+}
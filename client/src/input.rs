@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use levels::{SubInputState, SubInputs, SubPhysicsSpec, SubState};
 
 /// Shared resource for client thrust inputs.
 #[derive(Resource, Debug, Clone)]
@@ -10,7 +11,20 @@ pub struct ThrustInput {
     pub pump_fwd: f32,
     /// Aft ballast pump speed in [-1,1]. +1 pumps water in, -1 pumps out.
     pub pump_aft: f32,
+    /// Dive planes in [-1,1]. +1 = dive. Sent to the server as `pitch`.
+    pub plane: f32,
+    /// Roll trim in [-1,1]. +1 = starboard side down.
+    pub roll_trim: f32,
     pub tick: u64,
+    /// Last predicted state of the local sub, copied in by `sync_telemetry_to_input` after
+    /// each simulation step so tools without ECS queries can read it.
+    pub current_speed_m_s: f32,
+    /// Positive downward, measured below y = 0.
+    pub current_depth_m: f32,
+    /// Degrees in [0, 360), 0 = +Z, 90 = +X.
+    pub current_heading_deg: f32,
+    /// Mean ballast fill across all tanks, 0..1.
+    pub current_ballast_avg: f32,
 }
 
 impl Default for ThrustInput {
@@ -20,7 +34,114 @@ impl Default for ThrustInput {
             yaw: 0.0,
             pump_fwd: 0.0,
             pump_aft: 0.0,
+            plane: 0.0,
+            roll_trim: 0.0,
             tick: 0,
+            current_speed_m_s: 0.0,
+            current_depth_m: 0.0,
+            current_heading_deg: 0.0,
+            current_ballast_avg: 0.0,
         }
     }
 }
+
+impl ThrustInput {
+    pub fn as_sub_inputs(&self) -> SubInputs {
+        SubInputs {
+            thrust: self.value,
+            yaw: self.yaw,
+            pump_fwd: self.pump_fwd,
+            pump_aft: self.pump_aft,
+            plane: self.plane,
+            roll_trim: self.roll_trim,
+        }
+    }
+
+    /// Copy the telemetry fields from a simulated sub state.
+    pub fn sync_from_state(&mut self, state: &SubState) {
+        self.current_speed_m_s = state.speed();
+        self.current_depth_m = state.depth_m();
+        self.current_heading_deg = state.heading_yaw().to_degrees().rem_euclid(360.0);
+        self.current_ballast_avg = state.ballast_avg();
+    }
+}
+
+/// `SubInputs::from_raw`: `ThrustInput` lives in the client, so the helper is a trait here
+/// rather than an inherent method in `levels`.
+pub trait FromRawInput {
+    /// Ramp `prev` toward the raw controls over `dt` with the spec's time constants.
+    fn from_raw(
+        raw: &ThrustInput,
+        prev: &SubInputState,
+        dt: f32,
+        spec: &SubPhysicsSpec,
+    ) -> SubInputState;
+}
+
+impl FromRawInput for SubInputs {
+    fn from_raw(
+        raw: &ThrustInput,
+        prev: &SubInputState,
+        dt: f32,
+        spec: &SubPhysicsSpec,
+    ) -> SubInputState {
+        SubInputState::ramped(prev, raw.as_sub_inputs(), dt, spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use levels::subspecs::small_skiff_spec;
+    use levels::Vec3f;
+
+    #[test]
+    fn from_raw_ramps_keyboard_thrust_over_three_time_constants() {
+        let spec = small_skiff_spec();
+        let raw = ThrustInput {
+            value: 1.0,
+            ..Default::default()
+        };
+        let dt = 1.0 / 60.0;
+        let mut state = SubInputState::default();
+        // 0.45 s, three `thrust_tau_s` of 0.15 s
+        for _ in 0..27 {
+            let next = SubInputs::from_raw(&raw, &state, dt, &spec);
+            assert!(next.thrust > state.thrust && next.thrust - state.thrust < 0.15);
+            state = next;
+        }
+        assert!(
+            state.thrust > 0.93 && state.thrust < 0.97,
+            "thrust at 0.45 s = {}",
+            state.thrust
+        );
+    }
+
+    #[test]
+    fn telemetry_matches_sub_state() {
+        let state = SubState {
+            position: Vec3f::new(4.0, -12.5, 1.0),
+            velocity: Vec3f::new(3.0, 0.0, 4.0),
+            orientation: Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2),
+            ang_mom: Vec3f::ZERO,
+            ballast_fill: vec![0.25, 0.75, 0.5],
+            pump: Default::default(),
+        };
+        let mut input = ThrustInput::default();
+        input.sync_from_state(&state);
+        assert_eq!(input.current_speed_m_s, 5.0);
+        assert_eq!(input.current_depth_m, 12.5);
+        // Nose along -X
+        assert!((input.current_heading_deg - 270.0).abs() < 1e-3);
+        assert_eq!(input.current_ballast_avg, 0.5);
+
+        let empty = SubState {
+            ballast_fill: Vec::new(),
+            orientation: Quat::IDENTITY,
+            ..state
+        };
+        input.sync_from_state(&empty);
+        assert_eq!(input.current_heading_deg, 0.0);
+        assert_eq!(input.current_ballast_avg, 0.0);
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+#[cfg(feature = "windowing")]
+use crate::net::{Leaderboard, MyPlayerId, TeamRoster};
+#[cfg(feature = "windowing")]
+use crate::scene::remote_players::team_color;
+#[cfg(feature = "windowing")]
+use bevy_egui::EguiPrimaryContextPass;
+#[cfg(feature = "windowing")]
+use bevy_inspector_egui::bevy_egui::EguiContexts;
+
+/// Visibility of the scoreboard window, toggled with `L`.
+#[derive(Resource, Debug, Default)]
+pub struct LeaderboardOverlay {
+    pub visible: bool,
+}
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LeaderboardOverlay>()
+            .add_systems(Update, toggle_leaderboard);
+
+        #[cfg(feature = "windowing")]
+        {
+            app.add_systems(EguiPrimaryContextPass, ui_leaderboard);
+        }
+    }
+}
+
+fn toggle_leaderboard(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut overlay: ResMut<LeaderboardOverlay>,
+) {
+    if keys.is_some_and(|k| k.just_pressed(KeyCode::KeyL)) {
+        overlay.visible = !overlay.visible;
+    }
+}
+
+#[cfg(feature = "windowing")]
+fn ui_leaderboard(
+    mut egui_ctx: EguiContexts,
+    overlay: Res<LeaderboardOverlay>,
+    leaderboard: Res<Leaderboard>,
+    my_id: Res<MyPlayerId>,
+    roster: Res<TeamRoster>,
+) {
+    use bevy_inspector_egui::egui::*;
+    if !overlay.visible {
+        return;
+    }
+    let Ok(ctx) = egui_ctx.ctx_mut() else {
+        return;
+    };
+
+    let team_color32 = |team_id: u8| {
+        let c = team_color(team_id).to_srgba();
+        Color32::from_rgb(
+            (c.red * 255.0) as u8,
+            (c.green * 255.0) as u8,
+            (c.blue * 255.0) as u8,
+        )
+    };
+    let mut entries: Vec<_> = leaderboard.entries.iter().collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.credits));
+
+    Window::new("Leaderboard")
+        .anchor(Align2::CENTER_TOP, [0.0, 40.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            if let Some(win) = leaderboard.last_win {
+                ui.colored_label(
+                    team_color32(win.team_id),
+                    format!(
+                        "Team {} won the last round ({})",
+                        win.team_id + 1,
+                        win.score
+                    ),
+                );
+            }
+            if !leaderboard.team_scores.is_empty() {
+                ui.horizontal(|ui| {
+                    for t in &leaderboard.team_scores {
+                        ui.colored_label(
+                            team_color32(t.team_id),
+                            format!("Team {}: {}", t.team_id + 1, t.score),
+                        );
+                    }
+                });
+                ui.separator();
+            }
+            if entries.is_empty() {
+                ui.label("Waiting for scores...");
+                return;
+            }
+            Grid::new("leaderboard_grid")
+                .striped(true)
+                .spacing([16.0, 4.0])
+                .show(ui, |ui| {
+                    for header in ["#", "Team", "Player", "Credits", "Mines", "Docks"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for (rank, e) in entries.iter().enumerate() {
+                        let color = if my_id.0 == Some(e.player_id) {
+                            Color32::from_rgb(255, 210, 90)
+                        } else {
+                            ui.visuals().text_color()
+                        };
+                        ui.colored_label(color, format!("{}", rank + 1));
+                        match roster.0.get(&e.player_id) {
+                            Some(&team_id) => {
+                                ui.colored_label(team_color32(team_id), format!("{}", team_id + 1))
+                            }
+                            None => ui.label("-"),
+                        };
+                        ui.colored_label(color, &e.display_name);
+                        ui.colored_label(color, e.credits.to_string());
+                        ui.colored_label(color, e.mines.to_string());
+                        ui.colored_label(color, e.docks.to_string());
+                        ui.end_row();
+                    }
+                });
+        });
+}
@@ -8,30 +8,51 @@ use bevy::render::RenderPlugin;
 use bevy_renet::{netcode::NetcodeClientPlugin, RenetClientPlugin};
 
 pub mod args;
+pub mod autopilot;
+pub mod campaign;
+pub mod chat_hud;
+pub mod connection_quality;
 pub mod debug_vis;
 pub mod desync_metrics;
+pub mod discovery;
+pub mod hole_punch;
 pub mod hud_controls;
 pub mod hud_instruments;
+pub mod identity;
 pub mod input;
 pub mod labels;
+pub mod leaderboard;
+pub mod missions;
 pub mod net;
+pub mod notifications;
+pub mod physics_diagnostics;
+pub mod reconnect;
 pub mod render_settings;
+pub mod replay;
+pub mod rollback;
 pub mod scene;
 pub mod sim_pause;
+pub mod system_timings;
+#[cfg(feature = "websocket")]
+pub mod ws_transport;
 
 pub use args::Args;
 use debug_vis::DebugVisPlugin;
-use desync_metrics::{DesyncMetricsPlugin, NetClientStats};
+use desync_metrics::{CorrectionHistory, DesyncMetricsPlugin, NetClientStats, RemoteDesyncMetrics};
 #[cfg(feature = "windowing")]
 use hud_controls::HudControlsPlugin;
 #[cfg(feature = "windowing")]
 use hud_instruments::HudInstrumentsPlugin;
 pub use input::ThrustInput;
 use labels::LabelPlugin;
+#[cfg(feature = "windowing")]
+use leaderboard::LeaderboardPlugin;
 use net::{
-    client_connect, crash_on_disconnect, enforce_connect_timeout, HelloSent, LatestStateDelta,
-    MyPlayerId, NetSet,
+    client_connect, crash_on_disconnect, enforce_connect_timeout, HelloSent, HullStatus,
+    InputBatcher, Inventory, LatestStateDelta, Leaderboard, MyPlayerId, NetSet, RequestedFeatures,
+    TeamRoster,
 };
+use reconnect::{attempt_reconnect, ReconnectOverlayPlugin, ReconnectPolicy};
 use scene::{
     submarine::{ClientPhysicsTiming, SubTelemetry},
     ScenePlugin, SimSet,
@@ -82,6 +103,10 @@ fn build_client_app_with_config(args: Args, config: ClientAppConfig) -> App {
     let mut app = App::new();
 
     if config.include_rendering {
+        // Inserted ahead of `RenderSettingsPlugin`, whose `init_resource` then keeps it
+        if let Some(preset) = args.quality {
+            app.insert_resource(render_settings::RenderSettings::from_preset(preset));
+        }
         app.add_plugins((DefaultPlugins
             .set(AssetPlugin {
                 file_path: "assets".into(),
@@ -104,20 +129,43 @@ fn build_client_app_with_config(args: Args, config: ClientAppConfig) -> App {
             app.add_plugins(WorldInspectorPlugin::default());
             app.add_plugins(HudControlsPlugin);
             app.add_plugins(HudInstrumentsPlugin);
+            app.add_plugins(LeaderboardPlugin);
+            app.add_plugins(missions::MissionTrackerPlugin);
             app.add_plugins(render_settings::RenderSettingsPlugin);
         }
     } else {
         app.add_plugins(MinimalPlugins);
     }
 
-    app.insert_resource(args.clone())
+    app.insert_resource(campaign::CurrentLevel::from_args(&args))
+        .insert_resource(args.clone())
         .init_resource::<HelloSent>()
         .init_resource::<MyPlayerId>()
         .init_resource::<LatestStateDelta>()
         .init_resource::<SimPause>()
         .init_resource::<NetClientStats>()
+        .init_resource::<RemoteDesyncMetrics>()
+        .init_resource::<CorrectionHistory>()
         .init_resource::<SubTelemetry>()
-        .init_resource::<ClientPhysicsTiming>();
+        .init_resource::<ClientPhysicsTiming>()
+        .init_resource::<ReconnectPolicy>()
+        .init_resource::<HullStatus>()
+        .init_resource::<Inventory>()
+        .init_resource::<net::CargoInventory>()
+        .init_resource::<Leaderboard>()
+        .init_resource::<missions::MissionTracker>()
+        .init_resource::<TeamRoster>()
+        .init_resource::<InputBatcher>()
+        .init_resource::<rollback::InputHistory>()
+        .init_resource::<RequestedFeatures>()
+        .init_resource::<scene::ore::PendingOreSpawns>()
+        .init_resource::<notifications::NotificationLog>()
+        .add_event::<scene::torpedo::TorpedoEvent>()
+        .add_event::<missions::MissionMessage>()
+        .add_event::<chat_hud::ChatReceived>()
+        .add_event::<chat_hud::OutgoingChat>()
+        .add_event::<hud_instruments::depth_warning::DepthWarningReceived>()
+        .add_event::<rollback::AckCorrection>();
 
     if !config.include_ui && !app.world().contains_resource::<ThrustInput>() {
         app.world_mut().insert_resource(ThrustInput::default());
@@ -131,7 +179,26 @@ fn build_client_app_with_config(args: Args, config: ClientAppConfig) -> App {
             Update,
             (net::pump_network, net::apply_state_to_sub).in_set(NetSet),
         )
-        .add_systems(Update, (crash_on_disconnect, enforce_connect_timeout));
+        .add_systems(
+            Update,
+            (
+                // After `NetSet`, so a `Disconnect` notice read this frame is already in place
+                crash_on_disconnect.after(NetSet),
+                enforce_connect_timeout,
+                attempt_reconnect,
+                sim_pause::slow_motion_keys.before(SimSet),
+                notifications::expire_notifications,
+                missions::apply_mission_messages.after(NetSet),
+                hud_instruments::depth_warning::log_depth_warnings.after(NetSet),
+                chat_hud::send_outgoing_chat,
+                rollback::rollback_on_correction
+                    .after(NetSet)
+                    .before(SimSet),
+            ),
+        );
+    app.add_plugins(connection_quality::ConnectionQualityPlugin);
+    #[cfg(feature = "websocket")]
+    app.add_plugins(ws_transport::WsClientTransportPlugin);
 
     if config.include_debug {
         app.add_plugins(WireframePlugin::default());
@@ -141,10 +208,17 @@ fn build_client_app_with_config(args: Args, config: ClientAppConfig) -> App {
 
     if config.include_rendering {
         app.add_plugins(LabelPlugin);
+        app.add_plugins(ReconnectOverlayPlugin);
+        app.add_plugins(notifications::NotificationOverlayPlugin);
+        app.add_plugins(connection_quality::ConnectionQualityIndicatorPlugin);
+        app.add_plugins(chat_hud::ChatHudPlugin);
+        app.add_plugins(physics_diagnostics::SubPhysicsDiagnosticsPlugin);
     }
 
     if config.include_scene {
         app.add_plugins(ScenePlugin);
+        app.add_plugins(autopilot::AutopilotPlugin);
+        app.add_plugins(replay::ReplayPlugin::from_args(&args));
     }
 
     app
@@ -1,7 +1,10 @@
 use anyhow::Result;
+use bevy::app::AppExit;
 use clap::Parser;
 
+use client::discovery::{discover, DISCOVERY_LISTEN};
 use client::{build_client_app, Args};
+use protocol::discovery::DISCOVERY_PORT;
 
 fn main() -> Result<()> {
     tracing_subscriber::fmt()
@@ -9,7 +12,23 @@ fn main() -> Result<()> {
         .init();
 
     let args = Args::parse();
+    if args.discover {
+        let servers = discover(DISCOVERY_PORT, DISCOVERY_LISTEN)?;
+        for server in &servers {
+            println!(
+                "{}\t{}\t{}/{} players",
+                server.addr, server.name, server.player_count, server.max_players
+            );
+        }
+        if servers.is_empty() {
+            eprintln!("No servers found on the LAN");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
     let mut app = build_client_app(args);
-    app.run();
+    if let AppExit::Error(code) = app.run() {
+        std::process::exit(code.get().into());
+    }
     Ok(())
 }
@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+use levels::Mission;
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::net::Inventory;
+use crate::notifications::NotificationLog;
+#[cfg(feature = "windowing")]
+use bevy_egui::EguiPrimaryContextPass;
+#[cfg(feature = "windowing")]
+use bevy_inspector_egui::bevy_egui::EguiContexts;
+
+/// Mission messages forwarded by `pump_network`.
+#[derive(Event, Debug, Clone)]
+pub enum MissionMessage {
+    Update(protocol::MissionUpdate),
+    Complete(protocol::MissionComplete),
+}
+
+/// This player's progress as last reported by the server, with descriptions looked up in the
+/// shared mission list.
+#[derive(Resource, Debug)]
+pub struct MissionTracker {
+    pub missions: Vec<Mission>,
+    /// Mission id -> (progress, total).
+    pub progress: HashMap<u32, (u32, u32)>,
+}
+
+impl Default for MissionTracker {
+    fn default() -> Self {
+        Self {
+            missions: levels::builtin_missions(),
+            progress: HashMap::new(),
+        }
+    }
+}
+
+impl MissionTracker {
+    pub fn description(&self, mission_id: u32) -> String {
+        self.missions
+            .iter()
+            .find(|m| m.id == mission_id)
+            .map(|m| m.description.clone())
+            .unwrap_or_else(|| format!("Mission {mission_id}"))
+    }
+}
+
+pub fn apply_mission_messages(
+    mut events: EventReader<MissionMessage>,
+    mut tracker: ResMut<MissionTracker>,
+    mut log: ResMut<NotificationLog>,
+    mut inventory: ResMut<Inventory>,
+) {
+    for event in events.read() {
+        match event {
+            MissionMessage::Update(update) => {
+                tracker
+                    .progress
+                    .insert(update.mission_id, (update.progress, update.total));
+                if update.progress < update.total {
+                    log.push(format!(
+                        "{}: {}/{}",
+                        tracker.description(update.mission_id),
+                        update.progress,
+                        update.total
+                    ));
+                }
+            }
+            MissionMessage::Complete(complete) => {
+                info!(
+                    mission_id = complete.mission_id,
+                    reward = complete.reward,
+                    "Mission complete"
+                );
+                inventory.credits += complete.reward;
+                log.push(format!(
+                    "Mission complete: {} (+{} credits)",
+                    tracker.description(complete.mission_id),
+                    complete.reward
+                ));
+            }
+        }
+    }
+}
+
+/// Visibility of the mission tracker window, toggled with `J`.
+#[derive(Resource, Debug, Default)]
+pub struct MissionTrackerOverlay {
+    pub visible: bool,
+}
+
+pub struct MissionTrackerPlugin;
+
+impl Plugin for MissionTrackerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MissionTrackerOverlay>()
+            .add_systems(Update, toggle_mission_tracker);
+
+        #[cfg(feature = "windowing")]
+        {
+            app.add_systems(EguiPrimaryContextPass, ui_mission_tracker);
+        }
+    }
+}
+
+fn toggle_mission_tracker(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut overlay: ResMut<MissionTrackerOverlay>,
+) {
+    if keys.is_some_and(|k| k.just_pressed(KeyCode::KeyJ)) {
+        overlay.visible = !overlay.visible;
+    }
+}
+
+#[cfg(feature = "windowing")]
+fn ui_mission_tracker(
+    mut egui_ctx: EguiContexts,
+    overlay: Res<MissionTrackerOverlay>,
+    tracker: Res<MissionTracker>,
+) {
+    use bevy_inspector_egui::egui::*;
+    if !overlay.visible {
+        return;
+    }
+    let Ok(ctx) = egui_ctx.ctx_mut() else {
+        return;
+    };
+
+    Window::new("Missions")
+        .anchor(Align2::LEFT_TOP, [10.0, 40.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            Grid::new("missions_grid").striped(true).show(ui, |ui| {
+                for mission in &tracker.missions {
+                    let (progress, total) = tracker
+                        .progress
+                        .get(&mission.id)
+                        .copied()
+                        .unwrap_or((0, mission.objective.total()));
+                    ui.label(&mission.description);
+                    ui.label(format!("{progress}/{total}"));
+                    ui.label(format!("{} cr", mission.reward_credits));
+                    if progress >= total {
+                        ui.colored_label(Color32::from_rgb(80, 220, 120), "done");
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completion_pays_reward_and_logs() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<MissionTracker>()
+            .init_resource::<NotificationLog>()
+            .init_resource::<Inventory>()
+            .add_event::<MissionMessage>()
+            .add_systems(Update, apply_mission_messages);
+
+        app.world_mut()
+            .send_event(MissionMessage::Update(protocol::MissionUpdate {
+                mission_id: 1,
+                progress: 3,
+                total: 3,
+            }));
+        app.world_mut()
+            .send_event(MissionMessage::Complete(protocol::MissionComplete {
+                mission_id: 1,
+                reward: 100,
+            }));
+        app.update();
+
+        let tracker = app.world().resource::<MissionTracker>();
+        assert_eq!(tracker.progress.get(&1), Some(&(3, 3)));
+        assert_eq!(app.world().resource::<Inventory>().credits, 100);
+        let log: Vec<_> = app
+            .world()
+            .resource::<NotificationLog>()
+            .entries()
+            .map(|n| n.text.clone())
+            .collect();
+        assert_eq!(
+            log,
+            ["Mission complete: Dock at the station 3 times (+100 credits)"]
+        );
+    }
+}
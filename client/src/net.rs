@@ -1,18 +1,36 @@
 use bevy::prelude::*;
 use bevy_renet::netcode::{ClientAuthentication, NetcodeClientTransport};
-use bevy_renet::renet::{ConnectionConfig, DefaultChannel, RenetClient};
+use bevy_renet::renet::RenetClient;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::net::UdpSocket;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{info, warn};
 
-use crate::desync_metrics::NetClientStats;
+use crate::campaign::CurrentLevel;
+use crate::chat_hud::ChatReceived;
+use crate::desync_metrics::{
+    CorrectionHistory, CorrectionKind, CorrectionRecord, NetClientStats, RemoteDesyncMetrics,
+};
+use crate::hud_instruments::depth_warning::DepthWarningReceived;
+use crate::identity::{load_or_create_identity, ClientIdentity};
+use crate::missions::MissionMessage;
+use crate::reconnect::{schedule_reconnect, ReconnectPending, ReconnectPolicy};
+use crate::rollback::{AckCorrection, InputHistory};
+use crate::scene::ore::{FadeOutAnim, OreNode, PendingOreSpawns, SpawnInAnim};
+use crate::scene::remote_players::RemotePlayer;
 use crate::scene::submarine::ClientPhysicsTiming;
-use crate::scene::submarine::{NetControlled, ServerCorrection, Submarine, Velocity};
-use levels::SubInputState;
+use crate::scene::submarine::{
+    NetControlled, ServerCorrection, SubStateHistory, Submarine, Velocity,
+};
+use crate::scene::torpedo::{TorpedoControls, TorpedoEvent};
+use levels::{PumpCooldownState, SubInputState, SubState};
 
 use crate::Args;
 use protocol::{
-    ClientHello, ClientToServer, ServerToClient, StateDelta, NETCODE_PROTOCOL_ID, PROTOCOL_VERSION,
+    Channel, ClientHello, ClientToServer, DisconnectReason, FeatureFlags, InputTick,
+    LeaderboardEntry, ServerToClient, StateDelta, TeamScore, TeamWin, MAX_INPUT_BATCH,
+    NETCODE_PROTOCOL_ID, PROTOCOL_VERSION,
 };
 
 #[derive(Resource, Default)]
@@ -27,27 +45,132 @@ pub struct ConnectStart {
 #[derive(Resource, Default)]
 pub struct MyPlayerId(pub Option<uuid::Uuid>);
 
+/// Newest snapshot, with its quantized players already expanded into `players`.
 #[derive(Resource, Default)]
 pub struct LatestStateDelta(pub Option<StateDelta>);
 
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct NetSet;
 
+/// Latest scoreboard from the server's periodic `LeaderboardUpdate`.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+    pub team_scores: Vec<TeamScore>,
+    /// Winner of the most recent round, if any has ended since joining.
+    pub last_win: Option<TeamWin>,
+}
+
+/// Why the server last told us to disconnect, from its `Disconnect` message.
+#[derive(Resource, Debug, Clone)]
+pub struct ServerDisconnect(pub DisconnectReason);
+
+/// Input send rate the server asked for in its last `RateLimit` message.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputRateLimit {
+    pub allowed_hz: u32,
+}
+
+impl InputRateLimit {
+    /// Shortest gap between two input messages that stays within `allowed_hz`.
+    pub fn min_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs_f64(1.0 / self.allowed_hz.max(1) as f64)
+    }
+}
+
+/// Features this client asks for in its `ClientHello`: the ones it has paths for.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestedFeatures(pub FeatureFlags);
+
+impl Default for RequestedFeatures {
+    fn default() -> Self {
+        Self(FeatureFlags::DELTA_STATE | FeatureFlags::TORPEDO | FeatureFlags::TEAMS)
+    }
+}
+
+/// Features the server granted in its last `JoinAck`; absent until joined.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerFeatures(pub FeatureFlags);
+
+/// Input ticks waiting to go out together as one `InputTickBatch`, so a 120 Hz client sends
+/// at most 60 input datagrams a second at the default interval.
+#[derive(Resource, Debug, Clone)]
+pub struct InputBatcher {
+    pub pending: Vec<InputTick>,
+    /// Ticks per batch; clamped to `1..=MAX_INPUT_BATCH`.
+    pub flush_interval_ticks: u32,
+}
+
+impl Default for InputBatcher {
+    fn default() -> Self {
+        Self {
+            pending: Vec::with_capacity(MAX_INPUT_BATCH),
+            flush_interval_ticks: 2,
+        }
+    }
+}
+
+impl InputBatcher {
+    /// Queue `tick`; returns the batch to send once `flush_interval_ticks` are pending.
+    pub fn push(&mut self, tick: InputTick) -> Option<ClientToServer> {
+        self.pending.push(tick);
+        let interval = (self.flush_interval_ticks as usize).clamp(1, MAX_INPUT_BATCH);
+        (self.pending.len() >= interval).then(|| {
+            let batch = std::mem::replace(&mut self.pending, Vec::with_capacity(MAX_INPUT_BATCH));
+            ClientToServer::InputTickBatch(InputTick::encode_batch(&batch))
+        })
+    }
+}
+
+/// Team of every known player, from `TeamAssignment` messages.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct TeamRoster(pub HashMap<uuid::Uuid, u8>);
+
+/// Local player's banked credits, as of the last `DockAck`.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct Inventory {
+    pub credits: u64,
+}
+
+/// Local player's hold in kg per resource, from the last `MineAck` or `InventoryUpdate`.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct CargoInventory(pub HashMap<protocol::ResourceType, u32>);
+
+/// Local player's hull integrity as reported by the server.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct HullStatus {
+    pub integrity: f32,
+    /// Set by `HullAlert`; cleared once integrity recovers above the alert level.
+    pub alert: bool,
+}
+
+impl Default for HullStatus {
+    fn default() -> Self {
+        Self {
+            integrity: 1.0,
+            alert: false,
+        }
+    }
+}
+
 #[derive(Resource, Default, Debug, Clone, Copy)]
 pub struct TimeSync {
     pub offset_ms: f32,
     pub last_server_ms: u64,
 }
 
-#[derive(Resource, Debug, Clone)]
+#[derive(Resource, Debug, Clone, Serialize)]
 pub struct FilteredServerState {
     pub initialized: bool,
+    /// Player the filter is tracking; a different `MyPlayerId` restarts it from scratch.
+    pub last_known_player_id: Option<uuid::Uuid>,
     pub pos: Vec3,
     pub rot: Quat,
     pub body_rot: Quat,
     pub vel: Vec3,
     pub ang_mom: Vec3,
     pub ballast_fill: Vec<f32>,
+    pub pump: PumpCooldownState,
     pub input_state: SubInputState,
 }
 
@@ -55,99 +178,210 @@ impl Default for FilteredServerState {
     fn default() -> Self {
         Self {
             initialized: false,
+            last_known_player_id: None,
             pos: Vec3::ZERO,
             rot: Quat::IDENTITY,
             body_rot: Quat::IDENTITY,
             vel: Vec3::ZERO,
             ang_mom: Vec3::ZERO,
             ballast_fill: Vec::new(),
+            pump: PumpCooldownState::default(),
             input_state: SubInputState::default(),
         }
     }
 }
 
-pub fn client_connect(mut commands: Commands, args: Res<Args>) {
-    let server_addr: std::net::SocketAddr = args.server.parse().expect("invalid server addr");
+impl FilteredServerState {
+    /// The filtered physics state (body frame, +Z forward).
+    pub fn body_state(&self) -> SubState {
+        SubState {
+            position: self.pos,
+            velocity: self.vel,
+            orientation: self.body_rot,
+            ang_mom: self.ang_mom,
+            ballast_fill: self.ballast_fill.clone(),
+            pump: self.pump,
+        }
+    }
+
+    fn set_body_state(&mut self, state: SubState) {
+        self.pos = state.position;
+        self.vel = state.velocity;
+        self.body_rot = state.orientation;
+        self.ang_mom = state.ang_mom;
+        self.ballast_fill = state.ballast_fill;
+        self.pump = state.pump;
+    }
+}
+
+pub fn client_connect(
+    mut commands: Commands,
+    args: Res<Args>,
+    identity: Option<Res<ClientIdentity>>,
+) {
+    let connect_timeout = Duration::from_secs(args.connect_timeout_secs);
+    // Punch from the socket netcode will use, so the NAT mapping it opens is the one replies hit
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).expect("failed to bind UDP socket");
+    let (server_addr, punched) = match args.hole_punch() {
+        Some(punch) => {
+            let punch = punch.expect("invalid rendezvous URL");
+            match crate::hole_punch::punch(&socket, &punch, connect_timeout) {
+                Ok(addr) => (addr, true),
+                Err(err) => {
+                    warn!(rendezvous = ?punch.rendezvous_addr, %err, "Hole punching failed");
+                    (punch.rendezvous_addr, false)
+                }
+            }
+        }
+        None => (args.server.parse().expect("invalid server addr"), true),
+    };
 
     // Unsecure prototype setup
-    let client = RenetClient::new(ConnectionConfig::default());
-    // Generate a non-zero client id (derive from UUID bytes for simplicity)
-    let uuid = uuid::Uuid::new_v4();
-    let bytes = uuid.as_bytes();
-    let client_id = u64::from_le_bytes(bytes[0..8].try_into().expect("uuid slice to u64"));
+    let client = RenetClient::new(protocol::renet_config::connection_config(1.0));
+    // Stable across sessions so the server can correlate a returning player
+    let identity = match identity {
+        Some(identity) => identity.clone(),
+        None => {
+            let identity = load_or_create_identity(&args);
+            commands.insert_resource(identity.clone());
+            identity
+        }
+    };
+    let client_id = identity.client_id();
     let auth = ClientAuthentication::Unsecure {
         protocol_id: NETCODE_PROTOCOL_ID,
         client_id,
         server_addr,
         user_data: None,
     };
-    let socket = UdpSocket::bind(("0.0.0.0", 0)).expect("failed to bind UDP socket");
-    let transport = NetcodeClientTransport::new(
-        SystemTime::now().duration_since(UNIX_EPOCH).unwrap(),
-        auth,
-        socket,
-    )
-    .expect("failed to create client transport");
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+
+    #[cfg(feature = "websocket")]
+    let over_ws = connect_ws_transport(&mut commands, &args, now, &auth, connect_timeout);
+    #[cfg(not(feature = "websocket"))]
+    let over_ws = false;
+    // A failed punch leaves no transport; the connect timeout then handles it like a lost
+    // handshake
+    if !over_ws && punched {
+        let transport = NetcodeClientTransport::new(now, auth, socket)
+            .expect("failed to create client transport");
+        commands.insert_resource(transport);
+    }
 
     commands.insert_resource(client);
-    commands.insert_resource(transport);
     commands.insert_resource(ConnectStart {
         at: Instant::now(),
-        timeout: Duration::from_secs(args.connect_timeout_secs),
+        timeout: connect_timeout,
     });
-    commands.init_resource::<TimeSync>();
-    commands.init_resource::<FilteredServerState>();
+    // Fresh per-session state; a reconnect must not inherit the old filter or time offset.
+    commands.insert_resource(TimeSync::default());
+    commands.insert_resource(FilteredServerState::default());
+    commands.insert_resource(TeamRoster::default());
 
     info!(?server_addr, "Client created and connecting");
 }
 
+/// With `--ws`, connect through the server's WebSocket proxy instead of UDP. A failed
+/// WebSocket connect leaves no transport; the connect timeout then handles it like a lost
+/// handshake. Returns false when `--ws` is not set.
+#[cfg(feature = "websocket")]
+fn connect_ws_transport(
+    commands: &mut Commands,
+    args: &Args,
+    now: Duration,
+    auth: &ClientAuthentication,
+    connect_timeout: Duration,
+) -> bool {
+    let Some(ws) = args.ws.as_deref() else {
+        return false;
+    };
+    let ws_addr: std::net::SocketAddr = ws.parse().expect("invalid ws addr");
+    match crate::ws_transport::WsClientTransport::new(now, auth.clone(), ws_addr, connect_timeout) {
+        Ok(transport) => {
+            info!(?ws_addr, "Connecting over WebSocket");
+            commands.insert_resource(transport);
+        }
+        Err(err) => warn!(?ws_addr, %err, "WebSocket connect failed"),
+    }
+    true
+}
+
+/// Step client prediction at the server's physics rate, from `JoinAck` or a later
+/// `TickRateChange`.
+fn set_tick_rate(client_tick: &mut ClientPhysicsTiming, net_stats: &mut NetClientStats, hz: u32) {
+    let hz = hz.max(1);
+    client_tick.dt = 1.0 / hz as f32;
+    net_stats.current_tick_hz = Some(hz);
+    info!(
+        tick_hz = hz,
+        dt = client_tick.dt,
+        "Configured client fixed-step dt"
+    );
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn pump_network(
     client: Option<ResMut<RenetClient>>,
-    args: Res<Args>,
+    (identity, requested): (Option<Res<ClientIdentity>>, Res<RequestedFeatures>),
     mut hello_sent: ResMut<HelloSent>,
     mut my_id: ResMut<MyPlayerId>,
     mut latest: ResMut<LatestStateDelta>,
     mut paused: ResMut<crate::sim_pause::SimPause>,
     mut net_stats: ResMut<NetClientStats>,
     mut client_tick: ResMut<ClientPhysicsTiming>,
+    mut reconnect: ResMut<ReconnectPolicy>,
+    mut hull: ResMut<HullStatus>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut roster: ResMut<TeamRoster>,
+    mut commands: Commands,
+    mut pending_ore: ResMut<PendingOreSpawns>,
+    q_ore: Query<(Entity, &OreNode)>,
+    mut level: ResMut<CurrentLevel>,
 ) {
+    #[cfg(debug_assertions)]
+    let _timer = crate::system_timings::ScopeTimer::start(crate::system_timings::TimedSystem::Pump);
     let Some(mut client) = client else {
         return;
     };
 
     // Send Hello once after connection established
-    if client.is_connected() && !hello_sent.0 {
+    if let Some(identity) = identity.filter(|_| client.is_connected() && !hello_sent.0) {
         let hello = ClientToServer::Hello(ClientHello {
             protocol: PROTOCOL_VERSION,
-            display_name: args.name.clone(),
+            player_id: identity.player_uuid,
+            display_name: identity.hello_name(),
+            requested_features: requested.0,
         });
         if let Ok(bytes) = protocol::encode(&hello) {
-            client.send_message(DefaultChannel::ReliableOrdered, bytes);
+            client.send_message(Channel::Reliable, bytes);
         }
         hello_sent.0 = true;
     }
 
     // Read reliable messages
-    while let Some(bytes) = client.receive_message(DefaultChannel::ReliableOrdered) {
+    while let Some(bytes) = client.receive_message(Channel::Reliable) {
         match protocol::decode::<ServerToClient>(bytes.as_ref()) {
             Ok(ServerToClient::JoinAck(ack)) => {
                 info!(player_id = ?ack.player_id, "Received JoinAck");
                 my_id.0 = Some(ack.player_id);
+                if reconnect.current_attempt != 0 {
+                    reconnect.current_attempt = 0;
+                }
                 // Configure client fixed-step dt from server tick rate
-                let hz = ack.tick_hz.max(1) as f32;
-                client_tick.dt = 1.0 / hz;
-                info!(
-                    tick_hz = ack.tick_hz,
-                    dt = client_tick.dt,
-                    "Configured client fixed-step dt"
-                );
+                set_tick_rate(&mut client_tick, &mut net_stats, ack.tick_hz);
+                info!(features = ack.features.0, "Server granted features");
+                if ack.features.contains(FeatureFlags::TORPEDO) {
+                    commands.insert_resource(TorpedoControls::default());
+                } else {
+                    commands.remove_resource::<TorpedoControls>();
+                }
+                commands.insert_resource(ServerFeatures(ack.features));
             }
             Ok(ServerToClient::StateDelta(delta)) => {
                 // For compatibility in case server still sends reliable.
                 let latest_tick = latest.0.as_ref().map(|d| d.tick).unwrap_or(0);
                 if delta.tick > latest_tick {
-                    latest.0 = Some(delta);
+                    latest.0 = Some(delta.into_decompressed());
                     let now = Instant::now();
                     if let Some(prev) = net_stats.last_state_instant {
                         let dt_ms = now.saturating_duration_since(prev).as_secs_f32() * 1000.0;
@@ -159,6 +393,7 @@ pub fn pump_network(
                             net_stats.inter_arrival_ewma_ms
                                 + alpha * (dt_ms - net_stats.inter_arrival_ewma_ms)
                         };
+                        net_stats.record_inter_arrival(dt_ms);
                     }
                     net_stats.last_state_instant = Some(now);
                     // Time sync handled in apply_state_to_sub where ConnectStart is available
@@ -166,10 +401,110 @@ pub fn pump_network(
                 }
             }
             Ok(ServerToClient::PauseState(state)) => {
-                paused.0 = state.paused;
+                paused.paused = state.paused;
+            }
+            Ok(ServerToClient::TickRateChange(change)) => {
+                set_tick_rate(&mut client_tick, &mut net_stats, change.new_hz);
             }
             Ok(ServerToClient::InputAck(ack)) => {
-                net_stats.last_acked_tick = Some(ack.tick);
+                net_stats.record_input_ack(ack.tick);
+                if let Some(player) = ack.correction {
+                    commands.send_event(AckCorrection {
+                        tick: ack.correction_tick,
+                        player,
+                    });
+                }
+            }
+            Ok(ServerToClient::HullAlert(alert)) => {
+                warn!(integrity = alert.integrity, "Hull integrity critical");
+                hull.integrity = alert.integrity;
+                hull.alert = true;
+            }
+            Ok(ServerToClient::LeaderboardUpdate(update)) => {
+                leaderboard.entries = update.entries;
+                leaderboard.team_scores = update.team_scores;
+            }
+            Ok(ServerToClient::TeamAssignment(assignment)) => {
+                roster.0.insert(assignment.player_id, assignment.team_id);
+            }
+            Ok(ServerToClient::TeamWin(win)) => {
+                info!(team_id = win.team_id, score = win.score, "Round won");
+                leaderboard.last_win = Some(win);
+            }
+            Ok(ServerToClient::EntityDespawn(despawn)) => {
+                // A respawn that has not played yet is superseded
+                pending_ore.0.remove(&despawn.node_id);
+                for (entity, node) in &q_ore {
+                    if node.node_id == despawn.node_id {
+                        commands
+                            .entity(entity)
+                            .remove::<SpawnInAnim>()
+                            .insert(FadeOutAnim::default());
+                    }
+                }
+            }
+            Ok(ServerToClient::EntitySpawn(spawn)) => {
+                pending_ore.0.insert(spawn.node_id, SpawnInAnim::default());
+            }
+            Ok(ServerToClient::LevelReload(reload)) => {
+                let map_id = reload.map_id as usize;
+                if map_id >= level.campaign.levels.len() {
+                    warn!(map_id, "Server loaded a level missing from our campaign");
+                } else if map_id != level.map_id {
+                    info!(map_id, "Loading campaign level");
+                    // Only touch the resource on a real change; scene systems rebuild on it
+                    level.map_id = map_id;
+                    pending_ore.0.clear();
+                }
+            }
+            Ok(ServerToClient::TorpedoSpawned(spawn)) => {
+                commands.send_event(TorpedoEvent::Spawned(spawn));
+            }
+            Ok(ServerToClient::TorpedoDetonation(det)) => {
+                commands.send_event(TorpedoEvent::Detonation(det));
+            }
+            Ok(ServerToClient::MissionUpdate(update)) => {
+                commands.send_event(MissionMessage::Update(update));
+            }
+            Ok(ServerToClient::MissionComplete(complete)) => {
+                commands.send_event(MissionMessage::Complete(complete));
+            }
+            Ok(ServerToClient::MineAck(ack)) => {
+                if ack.success {
+                    info!(resource = ?ack.resource_type, kg = ack.amount, "Mined");
+                }
+                commands.insert_resource(CargoInventory(ack.inventory_after));
+            }
+            Ok(ServerToClient::InventoryUpdate(update)) => {
+                commands.insert_resource(CargoInventory(update.inventory));
+            }
+            Ok(ServerToClient::DockAck(ack)) => {
+                info!(credits = ack.credits_after, "Docked");
+                commands.insert_resource(Inventory {
+                    credits: ack.credits_after,
+                });
+            }
+            Ok(ServerToClient::Disconnect(reason)) => {
+                // `crash_on_disconnect` exits on the transport drop that follows instead of
+                // reconnecting
+                warn!(?reason, "Server is disconnecting us");
+                commands.insert_resource(ServerDisconnect(reason));
+            }
+            Ok(ServerToClient::ChatMessage(msg)) => {
+                commands.send_event(ChatReceived(msg));
+            }
+            Ok(ServerToClient::DepthWarning(warning)) => {
+                commands.send_event(DepthWarningReceived(warning));
+            }
+            Ok(ServerToClient::RateLimit(limit)) => {
+                warn!(
+                    allowed_hz = limit.allowed_hz,
+                    dropped = limit.excess_inputs_dropped,
+                    "Server is rate limiting our inputs"
+                );
+                commands.insert_resource(InputRateLimit {
+                    allowed_hz: limit.allowed_hz,
+                });
             }
             Ok(other) => {
                 warn!(?other, "Unhandled server message");
@@ -179,12 +514,12 @@ pub fn pump_network(
     }
 
     // Read unreliable messages (snapshots)
-    while let Some(bytes) = client.receive_message(DefaultChannel::Unreliable) {
+    while let Some(bytes) = client.receive_message(Channel::State) {
         match protocol::decode::<ServerToClient>(bytes.as_ref()) {
             Ok(ServerToClient::StateDelta(delta)) => {
                 let latest_tick = latest.0.as_ref().map(|d| d.tick).unwrap_or(0);
                 if delta.tick > latest_tick {
-                    latest.0 = Some(delta);
+                    latest.0 = Some(delta.into_decompressed());
                     let now = Instant::now();
                     if let Some(prev) = net_stats.last_state_instant {
                         let dt_ms = now.saturating_duration_since(prev).as_secs_f32() * 1000.0;
@@ -196,6 +531,7 @@ pub fn pump_network(
                             net_stats.inter_arrival_ewma_ms
                                 + alpha * (dt_ms - net_stats.inter_arrival_ewma_ms)
                         };
+                        net_stats.record_inter_arrival(dt_ms);
                     }
                     net_stats.last_state_instant = Some(now);
                     // Update time sync (simple): offset = server_ms - local_ms
@@ -206,6 +542,9 @@ pub fn pump_network(
                     net_stats.last_server_tick = latest.0.as_ref().map(|d| d.tick);
                 }
             }
+            Ok(ServerToClient::PingResponse(pong)) => {
+                net_stats.record_ping_response(&pong, Instant::now());
+            }
             Ok(other) => {
                 // Ignore other kinds on unreliable for now.
                 warn!(?other, "Unhandled unreliable server message");
@@ -215,7 +554,7 @@ pub fn pump_network(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn apply_state_to_sub(
     my_id: Res<MyPlayerId>,
     latest: Res<LatestStateDelta>,
@@ -226,6 +565,7 @@ pub fn apply_state_to_sub(
             &mut Transform,
             &mut Velocity,
             Option<&mut ServerCorrection>,
+            Option<&SubStateHistory>,
         ),
         With<Submarine>,
     >,
@@ -235,17 +575,51 @@ pub fn apply_state_to_sub(
     mut filtered: ResMut<FilteredServerState>,
     connect: Option<Res<ConnectStart>>,
     mut tsync: ResMut<TimeSync>,
+    mut hull: ResMut<HullStatus>,
+    q_remote: Query<(&RemotePlayer, &Transform), Without<Submarine>>,
+    mut remote_metrics: ResMut<RemoteDesyncMetrics>,
+    mut corrections: ResMut<CorrectionHistory>,
+    mut client_tick: ResMut<ClientPhysicsTiming>,
+    predicted: Res<InputHistory>,
 ) {
+    #[cfg(debug_assertions)]
+    let _timer =
+        crate::system_timings::ScopeTimer::start(crate::system_timings::TimedSystem::NetApply);
     let Some(my_id) = my_id.0 else {
         return;
     };
+    // After a respawn the filter still holds the old sub's state, which may be across the map;
+    // start over from the new player's first snapshot instead of blending from it
+    if filtered.last_known_player_id != Some(my_id) {
+        filtered.initialized = false;
+        filtered.last_known_player_id = Some(my_id);
+    }
     let Some(delta) = latest.0.as_ref() else {
         return;
     };
+    // Remotes play back a snapshot interval behind, so the gap is how far each trails the one
+    // just received. Diagnostic only; nothing else reads it.
+    if latest.is_changed() {
+        remote_metrics
+            .0
+            .retain(|id, _| delta.players.iter().any(|p| p.id == *id));
+        for p in delta.players.iter().filter(|p| p.id != my_id) {
+            if let Some((_, t)) = q_remote.iter().find(|(r, _)| r.id == p.id) {
+                let err = t.translation.distance(Vec3::from_array(p.position));
+                remote_metrics.record(p.id, err, delta.server_ms);
+            }
+        }
+    }
     let Some(me) = delta.players.iter().find(|p| p.id == my_id) else {
         return;
     };
-    if let Ok((entity, mut t, mut v, corr_opt)) = q_sub.single_mut() {
+    if hull.integrity != me.hull_integrity {
+        hull.integrity = me.hull_integrity;
+        if hull.integrity >= 0.3 {
+            hull.alert = false;
+        }
+    }
+    if let Ok((entity, mut t, mut v, corr_opt, history)) = q_sub.single_mut() {
         // Update time sync from delta.server_ms vs local monotonic
         if let Some(connect) = connect {
             let local_ms = connect.at.elapsed().as_millis() as u64;
@@ -274,39 +648,36 @@ pub fn apply_state_to_sub(
             yaw: me.input_state.yaw,
             pump_fwd: me.input_state.pump_fwd,
             pump_aft: me.input_state.pump_aft,
+            plane: me.input_state.pitch,
+            roll_trim: me.input_state.roll_trim,
+        };
+        let server_state = SubState {
+            position: target_pos_raw,
+            velocity: target_vel_raw,
+            orientation: target_rot_raw,
+            ang_mom: server_ang_mom,
+            ballast_fill: me.ballast_fill.clone(),
+            pump: crate::rollback::pump_from_net(me.pump),
         };
-        let server_ballast = me.ballast_fill.clone();
 
         // Initialize or low-pass filter the authoritative target to remove HF jitter
         if !filtered.initialized {
-            filtered.pos = target_pos_raw;
             // Initialize in the same frame (mesh space) used for comparisons/corrections
             filtered.rot = target_rot;
-            filtered.body_rot = target_rot_raw;
-            filtered.vel = target_vel_raw;
-            filtered.ang_mom = server_ang_mom;
-            filtered.ballast_fill = server_ballast.clone();
+            filtered.set_body_state(server_state);
             filtered.input_state = server_input;
             filtered.initialized = true;
+            // Prediction restarts from this snapshot, so number its steps in server ticks
+            client_tick.tick = delta.tick;
         } else {
             let dt = time.delta_secs().max(1e-3);
             // Adapt smoothing: track server more tightly while the player is steering
             let yaw_in_mag = controls.as_ref().map(|c| c.yaw.abs()).unwrap_or(0.0);
             let tau = if yaw_in_mag > 0.05 { 0.035 } else { 0.10 }; // ~35ms when steering, 100ms otherwise
             let alpha = 1.0 - (-dt / tau).exp();
-            filtered.pos = filtered.pos.lerp(target_pos_raw, alpha);
             filtered.rot = filtered.rot.slerp(target_rot, alpha);
-            filtered.body_rot = filtered.body_rot.slerp(target_rot_raw, alpha);
-            // Blend velocity toward server but also consider current sim velocity to avoid buzz
-            filtered.vel = filtered.vel.lerp(target_vel_raw, alpha);
-            filtered.ang_mom = filtered.ang_mom.lerp(server_ang_mom, alpha);
-            if filtered.ballast_fill.len() != server_ballast.len() {
-                filtered.ballast_fill = server_ballast.clone();
-            } else {
-                for (f, target) in filtered.ballast_fill.iter_mut().zip(server_ballast.iter()) {
-                    *f += alpha * (target - *f);
-                }
-            }
+            let blended = SubState::interpolate(&filtered.body_state(), &server_state, alpha);
+            filtered.set_body_state(blended);
             filtered.input_state.thrust +=
                 alpha * (server_input.thrust - filtered.input_state.thrust);
             filtered.input_state.yaw += alpha * (server_input.yaw - filtered.input_state.yaw);
@@ -316,9 +687,19 @@ pub fn apply_state_to_sub(
                 alpha * (server_input.pump_aft - filtered.input_state.pump_aft);
         }
 
-        let target_pos = filtered.pos;
-        let target_rot = filtered.rot;
-        let target_vel = filtered.vel;
+        // The snapshot is for a past tick; when that tick's prediction is still held, carry the
+        // server's offset from it forward onto the current prediction instead of pulling the sub
+        // back toward a filtered state that trails it by the snapshot age. A snapshot the
+        // prediction hasn't reached yet is already as current as anything the client has.
+        let (target_pos, target_rot, target_vel) = match predicted.at(delta.tick) {
+            Some(then) => (
+                t.translation + (target_pos_raw - then.state.position),
+                target_rot_raw * then.state.orientation.inverse() * t.rotation,
+                **v + (target_vel_raw - then.state.velocity),
+            ),
+            None if delta.tick > client_tick.tick => (target_pos_raw, target_rot, target_vel_raw),
+            None => (filtered.pos, filtered.rot, filtered.vel),
+        };
         // If the error is huge (teleport), snap immediately; otherwise smooth via ServerCorrection
         let raw_pos_err = t.translation.distance(target_pos_raw);
         let raw_ang_err = t.rotation.angle_between(target_rot);
@@ -338,7 +719,15 @@ pub fn apply_state_to_sub(
         let enter_ang = if steering { 0.10 } else { 0.05 };
         let enter_vel = if steering { 0.20 } else { 0.08 };
         let need_corr = pos_err > enter_pos || ang_err > enter_ang || vel_err > enter_vel;
+        let record = |kind, pos_err_m, ang_err_rad| CorrectionRecord {
+            server_ms: delta.server_ms,
+            kind,
+            pos_err_m,
+            ang_err_rad,
+            vel_err_mps: vel_err,
+        };
         if snap_now {
+            corrections.record(record(CorrectionKind::Snap, raw_pos_err, raw_ang_err));
             t.translation = target_pos;
             t.rotation = target_rot;
             **v = target_vel;
@@ -361,6 +750,16 @@ pub fn apply_state_to_sub(
                 corr.elapsed = 0.2;
             }
         } else if need_corr {
+            corrections.record(record(CorrectionKind::Smooth, pos_err, ang_err));
+            if let Some(predicted) = history.and_then(|h| h.at(delta.tick)) {
+                tracing::debug!(
+                    tick = delta.tick,
+                    predicted = ?predicted.position,
+                    server = ?me.position,
+                    err_m = predicted.position.distance(target_pos_raw),
+                    "Server correction vs predicted state at the same tick"
+                );
+            }
             commands.entity(entity).insert(ServerCorrection {
                 target_pos,
                 target_rot,
@@ -374,20 +773,89 @@ pub fn apply_state_to_sub(
     }
 }
 
-pub fn crash_on_disconnect(transport: Option<Res<NetcodeClientTransport>>) {
-    if let Some(transport) = transport {
-        if let Some(reason) = transport.disconnect_reason() {
-            eprintln!("Network disconnect: {reason:?}. Exiting.");
-            std::process::exit(1);
+/// On a dropped connection, tear down the session and hand off to the reconnect policy.
+/// Exits only once `ReconnectPolicy::max_attempts` have been spent, or straight away when the
+/// server sent a `Disconnect` saying why: it would refuse or not answer a reconnect anyway.
+#[allow(clippy::too_many_arguments)]
+pub fn crash_on_disconnect(
+    mut commands: Commands,
+    transport: Option<Res<NetcodeClientTransport>>,
+    #[cfg(feature = "websocket")] ws_transport: Option<Res<crate::ws_transport::WsClientTransport>>,
+    pending: Option<Res<ReconnectPending>>,
+    server_disconnect: Option<Res<ServerDisconnect>>,
+    mut exit: EventWriter<AppExit>,
+    mut policy: ResMut<ReconnectPolicy>,
+    mut hello_sent: ResMut<HelloSent>,
+    mut my_id: ResMut<MyPlayerId>,
+    mut latest: ResMut<LatestStateDelta>,
+) {
+    if pending.is_some() {
+        return;
+    }
+    let reason = transport.and_then(|t| t.disconnect_reason());
+    #[cfg(feature = "websocket")]
+    let reason = reason.or_else(|| ws_transport.and_then(|t| t.disconnect_reason()));
+    let Some(reason) = reason else {
+        return;
+    };
+    warn!(?reason, "Network disconnect");
+    commands.remove_resource::<RenetClient>();
+    commands.remove_resource::<NetcodeClientTransport>();
+    #[cfg(feature = "websocket")]
+    commands.remove_resource::<crate::ws_transport::WsClientTransport>();
+    commands.remove_resource::<ConnectStart>();
+    hello_sent.0 = false;
+    my_id.0 = None;
+    latest.0 = None;
+    if let Some(ServerDisconnect(reason)) = server_disconnect.as_deref() {
+        eprintln!(
+            "Disconnected by the server: {}",
+            describe_disconnect(reason)
+        );
+        exit.write(match reason {
+            DisconnectReason::ServerShutdown => AppExit::Success,
+            DisconnectReason::Kicked
+            | DisconnectReason::IncompatibleProtocol { .. }
+            | DisconnectReason::IdentityInUse => AppExit::error(),
+        });
+        return;
+    }
+    schedule_reconnect(&mut commands, &mut policy);
+}
+
+fn describe_disconnect(reason: &DisconnectReason) -> String {
+    match reason {
+        DisconnectReason::IncompatibleProtocol { server, client } => {
+            format!("incompatible protocol (server v{server}, client v{client})")
+        }
+        DisconnectReason::Kicked => "kicked".to_string(),
+        DisconnectReason::ServerShutdown => "server shutting down".to_string(),
+        DisconnectReason::IdentityInUse => {
+            "another session is already playing as this identity".to_string()
         }
     }
 }
 
-pub fn enforce_connect_timeout(client: Option<Res<RenetClient>>, start: Option<Res<ConnectStart>>) {
+pub fn enforce_connect_timeout(
+    mut commands: Commands,
+    client: Option<Res<RenetClient>>,
+    start: Option<Res<ConnectStart>>,
+    mut policy: ResMut<ReconnectPolicy>,
+) {
     let (Some(client), Some(start)) = (client, start) else {
         return;
     };
     if !client.is_connected() && start.at.elapsed() >= start.timeout {
+        // A stalled reconnect counts as a failed attempt rather than a hard exit.
+        if policy.current_attempt > 0 {
+            commands.remove_resource::<RenetClient>();
+            commands.remove_resource::<NetcodeClientTransport>();
+            #[cfg(feature = "websocket")]
+            commands.remove_resource::<crate::ws_transport::WsClientTransport>();
+            commands.remove_resource::<ConnectStart>();
+            schedule_reconnect(&mut commands, &mut policy);
+            return;
+        }
         eprintln!(
             "Connection timeout after {}s without establishing a session. Exiting.",
             start.timeout.as_secs()
@@ -395,3 +863,133 @@ pub fn enforce_connect_timeout(client: Option<Res<RenetClient>>, start: Option<R
         std::process::exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{NetInputState, NetPlayer};
+    use uuid::Uuid;
+
+    fn snapshot(id: Uuid, position: Vec3) -> StateDelta {
+        StateDelta {
+            tick: 0,
+            server_ms: 0,
+            players: vec![NetPlayer {
+                id,
+                position: position.to_array(),
+                velocity: [0.0; 3],
+                orientation: [0.0, 0.0, 0.0, 1.0],
+                ang_mom: [0.0; 3],
+                angular_velocity: [0.0; 3],
+                ballast_fill: Vec::new(),
+                input_state: NetInputState {
+                    thrust: 0.0,
+                    yaw: 0.0,
+                    pump_fwd: 0.0,
+                    pump_aft: 0.0,
+                    pitch: 0.0,
+                    roll_trim: 0.0,
+                },
+                hull_integrity: 1.0,
+                team_id: 0,
+                pump: Default::default(),
+            }],
+            compressed_players: Vec::new(),
+            snapshot_origin: [0.0; 3],
+            obstacles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn respawn_under_new_id_does_not_blend_from_old_position() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<MyPlayerId>()
+            .init_resource::<LatestStateDelta>()
+            .init_resource::<NetClientStats>()
+            .init_resource::<FilteredServerState>()
+            .init_resource::<TimeSync>()
+            .init_resource::<HullStatus>()
+            .init_resource::<RemoteDesyncMetrics>()
+            .init_resource::<CorrectionHistory>()
+            .init_resource::<ClientPhysicsTiming>()
+            .init_resource::<InputHistory>()
+            .add_systems(Update, apply_state_to_sub);
+        app.world_mut()
+            .spawn((Submarine, Transform::default(), Velocity(Vec3::ZERO)));
+
+        let (old_id, new_id) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        app.insert_resource(MyPlayerId(Some(old_id)));
+        app.insert_resource(LatestStateDelta(Some(snapshot(old_id, Vec3::ZERO))));
+        for _ in 0..5 {
+            app.update();
+        }
+
+        // Respawned far away under a fresh id
+        let spawn = Vec3::new(300.0, 4.0, 0.0);
+        app.insert_resource(MyPlayerId(Some(new_id)));
+        app.insert_resource(LatestStateDelta(Some(snapshot(new_id, spawn))));
+        for frame in 0..5 {
+            app.update();
+            let filtered = app.world().resource::<FilteredServerState>();
+            assert!(
+                filtered.pos.distance(spawn) < 0.01,
+                "frame {frame}: filtered target at {:?}",
+                filtered.pos
+            );
+            assert_eq!(filtered.last_known_player_id, Some(new_id));
+            let mut q = app
+                .world_mut()
+                .query_filtered::<&Transform, With<Submarine>>();
+            let sub = q.single(app.world()).unwrap();
+            assert!(
+                sub.translation.distance(spawn) < 0.01,
+                "frame {frame}: sub at {:?}",
+                sub.translation
+            );
+        }
+    }
+
+    #[test]
+    fn input_batcher_flushes_every_interval_and_caps_the_batch() {
+        let tick = |tick| InputTick {
+            tick,
+            thrust: 1.0,
+            yaw: 0.0,
+            pump_fwd: 0.0,
+            pump_aft: 0.0,
+            pitch: 0.0,
+            roll_trim: 0.0,
+        };
+        let batch_ticks = |msg: Option<ClientToServer>| match msg {
+            Some(ClientToServer::InputTickBatch(batch)) => Some(
+                InputTick::decode_batch(&batch)
+                    .iter()
+                    .map(|t| t.tick)
+                    .collect::<Vec<_>>(),
+            ),
+            None => None,
+            Some(other) => panic!("unexpected message {other:?}"),
+        };
+
+        let mut batcher = InputBatcher::default();
+        let sent: Vec<_> = (1..=5)
+            .map(|t| batch_ticks(batcher.push(tick(t))))
+            .collect();
+        assert_eq!(
+            sent,
+            vec![None, Some(vec![1, 2]), None, Some(vec![3, 4]), None]
+        );
+        assert_eq!(batcher.pending.len(), 1);
+
+        let mut batcher = InputBatcher {
+            flush_interval_ticks: 100,
+            ..Default::default()
+        };
+        let flushed: Vec<_> = (1..=2 * MAX_INPUT_BATCH as u64)
+            .filter_map(|t| batch_ticks(batcher.push(tick(t))))
+            .collect();
+        assert_eq!(flushed.len(), 2);
+        assert!(flushed.iter().all(|b| b.len() == MAX_INPUT_BATCH));
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+/// How long a notification stays on screen.
+const NOTIFICATION_TTL_S: f32 = 4.0;
+/// Oldest entries are dropped beyond this.
+const MAX_NOTIFICATIONS: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub text: String,
+    pub remaining_s: f32,
+}
+
+/// Short-lived status messages (spec reloads, etc.) shown under the top edge of the screen.
+#[derive(Resource, Debug, Default)]
+pub struct NotificationLog {
+    entries: VecDeque<Notification>,
+}
+
+impl NotificationLog {
+    pub fn push(&mut self, text: impl Into<String>) {
+        if self.entries.len() == MAX_NOTIFICATIONS {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Notification {
+            text: text.into(),
+            remaining_s: NOTIFICATION_TTL_S,
+        });
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Notification> {
+        self.entries.iter()
+    }
+}
+
+pub fn expire_notifications(time: Res<Time>, mut log: ResMut<NotificationLog>) {
+    if log.entries.is_empty() {
+        return;
+    }
+    let dt = time.delta_secs();
+    for n in log.entries.iter_mut() {
+        n.remaining_s -= dt;
+    }
+    log.entries.retain(|n| n.remaining_s > 0.0);
+}
+
+#[derive(Component)]
+struct NotificationText;
+
+pub struct NotificationOverlayPlugin;
+
+impl Plugin for NotificationOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_notification_overlay)
+            .add_systems(Update, update_notification_overlay);
+    }
+}
+
+fn spawn_notification_overlay(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Percent(35.0),
+            ..Default::default()
+        },
+        Text::new(String::new()),
+        TextFont {
+            font_size: 18.0,
+            ..Default::default()
+        },
+        TextColor(Color::srgb(0.9, 0.95, 1.0)),
+        NotificationText,
+        Name::new("Notifications"),
+    ));
+}
+
+fn update_notification_overlay(
+    log: Res<NotificationLog>,
+    mut q: Query<&mut Text, With<NotificationText>>,
+) {
+    if !log.is_changed() {
+        return;
+    }
+    let Ok(mut text) = q.single_mut() else {
+        return;
+    };
+    text.0 = log
+        .entries()
+        .map(|n| n.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+}
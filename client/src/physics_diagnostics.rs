@@ -0,0 +1,84 @@
+//! Bevy diagnostics for local submarine prediction: how many physics steps each frame ran
+//! and how long one took on average.
+//!
+//! `simulate_submarine` times its `step_submarine_dbg` calls through [`ProfiledPhysicsStep`]
+//! when the resource exists. Only windowed clients add [`SubPhysicsDiagnosticsPlugin`], so
+//! headless and minimal (integration test) clients step untimed.
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Instant;
+
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::prelude::*;
+
+pub const STEP_COUNT: DiagnosticPath = DiagnosticPath::const_new("submarine/step_count");
+pub const STEP_DURATION_US: DiagnosticPath =
+    DiagnosticPath::const_new("submarine/step_duration_us");
+
+/// Physics steps timed since the last `push_sub_physics_diagnostics`. Atomic so subs stepped
+/// in parallel (`parallel_physics`) can share it.
+#[derive(Resource, Debug, Default)]
+pub struct ProfiledPhysicsStep {
+    steps: AtomicU32,
+    total_ns: AtomicU64,
+}
+
+impl ProfiledPhysicsStep {
+    /// Run one physics step (a `step_submarine_dbg` call) and add its wall-clock time to this
+    /// frame's sample.
+    pub fn measure<R>(&self, step: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let out = step();
+        let ns = start.elapsed().as_nanos().min(u64::MAX as u128) as u64;
+        self.steps.fetch_add(1, Ordering::Relaxed);
+        self.total_ns.fetch_add(ns, Ordering::Relaxed);
+        out
+    }
+
+    /// Steps since the last call and their mean duration in microseconds, if any ran.
+    pub fn take_sample(&self) -> (u32, Option<f64>) {
+        let steps = self.steps.swap(0, Ordering::Relaxed);
+        let total_ns = self.total_ns.swap(0, Ordering::Relaxed);
+        let mean_us = (steps > 0).then(|| total_ns as f64 / steps as f64 / 1000.0);
+        (steps, mean_us)
+    }
+}
+
+pub struct SubPhysicsDiagnosticsPlugin;
+
+impl Plugin for SubPhysicsDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProfiledPhysicsStep>()
+            .register_diagnostic(Diagnostic::new(STEP_COUNT))
+            .register_diagnostic(Diagnostic::new(STEP_DURATION_US).with_suffix("us"))
+            .add_systems(Last, push_sub_physics_diagnostics);
+    }
+}
+
+/// Frames without a step record a zero count but no duration, so the duration average only
+/// covers frames that stepped.
+fn push_sub_physics_diagnostics(mut diagnostics: Diagnostics, profiled: Res<ProfiledPhysicsStep>) {
+    let (steps, mean_us) = profiled.take_sample();
+    diagnostics.add_measurement(&STEP_COUNT, || steps as f64);
+    if let Some(mean_us) = mean_us {
+        diagnostics.add_measurement(&STEP_DURATION_US, || mean_us);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_averages_steps_and_resets() {
+        let profiled = ProfiledPhysicsStep::default();
+        assert_eq!(profiled.take_sample(), (0, None));
+        for _ in 0..3 {
+            profiled.measure(|| std::thread::sleep(std::time::Duration::from_millis(1)));
+        }
+        let (steps, mean_us) = profiled.take_sample();
+        assert_eq!(steps, 3);
+        assert!(mean_us.unwrap() >= 1000.0, "{mean_us:?}");
+        assert_eq!(profiled.take_sample(), (0, None));
+    }
+}
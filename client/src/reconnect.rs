@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::identity::ClientIdentity;
+use crate::net::client_connect;
+use crate::Args;
+
+/// How the client recovers from a dropped connection before giving up.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ReconnectPolicy {
+    pub max_attempts: u32,
+    pub delay_s: f32,
+    /// Attempts spent since the last successful join; reset on JoinAck.
+    pub current_attempt: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            delay_s: 2.0,
+            current_attempt: 0,
+        }
+    }
+}
+
+/// Present while waiting to open a new connection after a drop.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ReconnectPending {
+    pub until: Instant,
+    pub attempt: u32,
+}
+
+/// Schedule the next reconnect attempt, or exit once the policy is exhausted.
+pub fn schedule_reconnect(commands: &mut Commands, policy: &mut ReconnectPolicy) {
+    if policy.current_attempt >= policy.max_attempts {
+        eprintln!(
+            "Reconnect failed after {} attempts. Exiting.",
+            policy.max_attempts
+        );
+        std::process::exit(1);
+    }
+    policy.current_attempt += 1;
+    warn!(
+        attempt = policy.current_attempt,
+        max = policy.max_attempts,
+        delay_s = policy.delay_s,
+        "Scheduling reconnect"
+    );
+    commands.insert_resource(ReconnectPending {
+        until: Instant::now() + Duration::from_secs_f32(policy.delay_s.max(0.0)),
+        attempt: policy.current_attempt,
+    });
+}
+
+pub fn attempt_reconnect(
+    mut commands: Commands,
+    args: Res<Args>,
+    identity: Option<Res<ClientIdentity>>,
+    pending: Option<Res<ReconnectPending>>,
+) {
+    let Some(pending) = pending else {
+        return;
+    };
+    if Instant::now() < pending.until {
+        return;
+    }
+    info!(attempt = pending.attempt, "Reconnecting");
+    commands.remove_resource::<ReconnectPending>();
+    client_connect(commands, args, identity);
+}
+
+#[derive(Component)]
+struct ReconnectOverlayNode;
+
+/// Centered "Reconnecting (n/m)..." banner shown while a reconnect is pending or in flight.
+pub struct ReconnectOverlayPlugin;
+
+impl Plugin for ReconnectOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_reconnect_overlay)
+            .add_systems(Update, update_reconnect_overlay);
+    }
+}
+
+fn spawn_reconnect_overlay(mut commands: Commands, assets: Res<AssetServer>) {
+    let font: Handle<Font> = assets.load("fonts/FiraSans-Bold.ttf");
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(40.0),
+            top: Val::Percent(45.0),
+            ..Default::default()
+        },
+        Text::new(String::new()),
+        TextFont {
+            font,
+            font_size: 28.0,
+            ..Default::default()
+        },
+        TextColor(Color::srgb(1.0, 0.8, 0.3)),
+        Visibility::Hidden,
+        ReconnectOverlayNode,
+        Name::new("Reconnect Overlay"),
+    ));
+}
+
+fn update_reconnect_overlay(
+    policy: Res<ReconnectPolicy>,
+    mut q: Query<(&mut Text, &mut Visibility), With<ReconnectOverlayNode>>,
+) {
+    if !policy.is_changed() {
+        return;
+    }
+    let Ok((mut text, mut vis)) = q.single_mut() else {
+        return;
+    };
+    if policy.current_attempt == 0 {
+        *vis = Visibility::Hidden;
+        return;
+    }
+    *vis = Visibility::Visible;
+    text.0 = format!(
+        "Reconnecting ({}/{})...",
+        policy.current_attempt, policy.max_attempts
+    );
+}
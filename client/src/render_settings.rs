@@ -1,13 +1,46 @@
 use bevy::prelude::*;
 #[cfg(feature = "windowing")]
+use bevy_egui::EguiPrimaryContextPass;
+#[cfg(feature = "windowing")]
+use bevy_inspector_egui::bevy_egui::EguiContexts;
+#[cfg(feature = "windowing")]
 use bevy_inspector_egui::quick::ResourceInspectorPlugin;
 #[cfg(feature = "windowing")]
 use bevy_inspector_egui::InspectorOptions;
 
+/// Coarse quality tiers, selectable with `--quality` or from the Graphics window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect, clap::ValueEnum)]
+pub enum GraphicsPreset {
+    /// No volumetrics, water post-process, motion blur or lens distortion.
+    Low,
+    /// Faint volumetric cones and the water post-process.
+    Medium,
+    /// Every post-process effect at its tuned default.
+    #[default]
+    High,
+    /// As High, with full-strength volumetric scattering.
+    Ultra,
+}
+
+impl GraphicsPreset {
+    pub const ALL: [GraphicsPreset; 4] = [Self::Low, Self::Medium, Self::High, Self::Ultra];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+            Self::Ultra => "Ultra",
+        }
+    }
+}
+
 #[cfg_attr(feature = "windowing", derive(InspectorOptions))]
 #[derive(Resource, Debug, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct RenderSettings {
+    /// Last preset applied; individual fields may have been tweaked since.
+    pub preset: GraphicsPreset,
     pub volumetric_cones: bool,
     #[cfg_attr(feature = "windowing", inspector(min = 0.0, max = 5.0))]
     pub volumetric_cone_intensity: f32,
@@ -21,11 +54,29 @@ pub struct RenderSettings {
     #[cfg_attr(feature = "windowing", inspector(min = 0.0, max = 5.0))]
     pub water_post_strength: f32,
     pub water_post_debug: bool,
+    /// Fade the water post-process out as the camera nears the surface.
+    pub depth_adaptive_water: bool,
+    /// Multiplier on `water_post_strength`, written each frame by `water_depth_adapter`.
+    #[cfg_attr(feature = "windowing", inspector(min = 0.0, max = 1.0))]
+    pub water_post_depth_scale: f32,
+    pub motion_blur_enabled: bool,
+    #[cfg_attr(feature = "windowing", inspector(min = 0.0, max = 1.0))]
+    pub motion_blur_strength: f32,
+    /// Camera velocity texture (`VelocityTexture`) for passes that want it alongside the
+    /// motion-vector prepass.
+    pub velocity_buffer_enabled: bool,
+    pub lens_distortion_enabled: bool,
+    /// Brown-Conrady radial coefficients; positive values bow the image outward (barrel).
+    #[cfg_attr(feature = "windowing", inspector(min = -0.5, max = 0.5))]
+    pub lens_distort_k1: f32,
+    #[cfg_attr(feature = "windowing", inspector(min = -0.5, max = 0.5))]
+    pub lens_distort_k2: f32,
 }
 
 impl Default for RenderSettings {
     fn default() -> Self {
         Self {
+            preset: GraphicsPreset::High,
             volumetric_cones: true,
             volumetric_cone_intensity: 0.3,
             volumetric_cone_distance_falloff: 0.12,
@@ -34,6 +85,71 @@ impl Default for RenderSettings {
             water_post: true,
             water_post_strength: 1.0,
             water_post_debug: false,
+            depth_adaptive_water: true,
+            water_post_depth_scale: 1.0,
+            motion_blur_enabled: true,
+            motion_blur_strength: 0.5,
+            velocity_buffer_enabled: true,
+            lens_distortion_enabled: true,
+            lens_distort_k1: 0.05,
+            lens_distort_k2: 0.01,
+        }
+    }
+}
+
+impl RenderSettings {
+    pub fn from_preset(preset: GraphicsPreset) -> Self {
+        let mut settings = Self::default();
+        settings.apply_preset(preset);
+        settings
+    }
+
+    /// Overwrites the effect toggles and their strengths with the preset's values. Debug
+    /// switches are left alone.
+    pub fn apply_preset(&mut self, preset: GraphicsPreset) {
+        let defaults = Self::default();
+        self.preset = preset;
+        match preset {
+            GraphicsPreset::Low => {
+                self.volumetric_cones = false;
+                self.water_post = false;
+                self.motion_blur_enabled = false;
+                self.velocity_buffer_enabled = false;
+                self.lens_distortion_enabled = false;
+            }
+            GraphicsPreset::Medium => {
+                self.volumetric_cones = true;
+                self.volumetric_cone_intensity = 0.15;
+                self.water_post = true;
+                self.water_post_strength = defaults.water_post_strength;
+                self.motion_blur_enabled = false;
+                self.velocity_buffer_enabled = false;
+                self.lens_distortion_enabled = false;
+            }
+            GraphicsPreset::High => {
+                self.volumetric_cones = true;
+                self.volumetric_cone_intensity = defaults.volumetric_cone_intensity;
+                self.water_post = true;
+                self.water_post_strength = defaults.water_post_strength;
+                self.motion_blur_enabled = true;
+                self.velocity_buffer_enabled = true;
+                self.motion_blur_strength = defaults.motion_blur_strength;
+                self.lens_distortion_enabled = true;
+                self.lens_distort_k1 = defaults.lens_distort_k1;
+                self.lens_distort_k2 = defaults.lens_distort_k2;
+            }
+            GraphicsPreset::Ultra => {
+                self.volumetric_cones = true;
+                self.volumetric_cone_intensity = 0.6;
+                self.water_post = true;
+                self.water_post_strength = defaults.water_post_strength;
+                self.motion_blur_enabled = true;
+                self.velocity_buffer_enabled = true;
+                self.motion_blur_strength = defaults.motion_blur_strength;
+                self.lens_distortion_enabled = true;
+                self.lens_distort_k1 = defaults.lens_distort_k1;
+                self.lens_distort_k2 = defaults.lens_distort_k2;
+            }
         }
     }
 }
@@ -52,13 +168,75 @@ impl Plugin for RenderSettingsPlugin {
             .register_type::<VolumetricConeShaderDebugSettings>();
 
         #[cfg(feature = "windowing")]
-        app.add_plugins(ResourceInspectorPlugin::<VolumetricConeShaderDebugSettings>::default());
+        app.add_plugins(ResourceInspectorPlugin::<VolumetricConeShaderDebugSettings>::default())
+            .add_systems(EguiPrimaryContextPass, ui_graphics_preset);
     }
 }
+
+#[cfg(feature = "windowing")]
+fn ui_graphics_preset(mut egui_ctx: EguiContexts, mut settings: ResMut<RenderSettings>) {
+    use bevy_inspector_egui::egui::*;
+    let Ok(ctx) = egui_ctx.ctx_mut() else {
+        return;
+    };
+
+    Window::new("Graphics")
+        .anchor(Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+        .resizable(false)
+        .default_open(false)
+        .show(ctx, |ui| {
+            let mut preset = settings.preset;
+            ComboBox::from_label("Quality")
+                .selected_text(preset.label())
+                .show_ui(ui, |ui| {
+                    for option in GraphicsPreset::ALL {
+                        ui.selectable_value(&mut preset, option, option.label());
+                    }
+                });
+            if preset != settings.preset {
+                settings.apply_preset(preset);
+            }
+            ui.separator();
+            ui.checkbox(&mut settings.lens_distortion_enabled, "Lens distortion");
+            ui.add_enabled_ui(settings.lens_distortion_enabled, |ui| {
+                ui.add(Slider::new(&mut settings.lens_distort_k1, -0.5..=0.5).text("k1"));
+                ui.add(Slider::new(&mut settings.lens_distort_k2, -0.5..=0.5).text("k2"));
+            });
+        });
+}
+
 #[cfg_attr(feature = "windowing", derive(InspectorOptions))]
 #[derive(Resource, Debug, Clone, Reflect, Default)]
 #[reflect(Resource)]
 pub struct VolumetricConeShaderDebugSettings {
-    #[cfg_attr(feature = "windowing", inspector(min = 0, max = 5))]
+    #[cfg_attr(feature = "windowing", inspector(min = 0, max = 6))]
     pub debug_mode: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn low_preset_disables_heavy_effects() {
+        let settings = RenderSettings::from_preset(GraphicsPreset::Low);
+        assert_eq!(settings.preset, GraphicsPreset::Low);
+        assert!(!settings.volumetric_cones);
+        assert!(!settings.water_post);
+        assert!(!settings.motion_blur_enabled);
+        assert!(!settings.lens_distortion_enabled);
+    }
+
+    #[test]
+    fn high_preset_restores_defaults_after_low() {
+        let mut settings = RenderSettings::from_preset(GraphicsPreset::Low);
+        settings.apply_preset(GraphicsPreset::High);
+        let defaults = RenderSettings::default();
+        assert!(settings.volumetric_cones && settings.water_post && settings.motion_blur_enabled);
+        assert!(settings.lens_distortion_enabled);
+        assert_eq!(
+            settings.volumetric_cone_intensity,
+            defaults.volumetric_cone_intensity
+        );
+    }
+}
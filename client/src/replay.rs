@@ -0,0 +1,369 @@
+//! Input recording and playback. `--record <file>` appends the pilot's controls once per frame;
+//! `--replay <file>` writes them back into `ThrustInput` in place of the pilot, keyed by
+//! `ClientPhysicsTiming::tick`, so a flight can be reproduced step for step.
+//!
+//! File layout: `THALSREP`, four zero bytes and `PROTOCOL_VERSION` as a u32 LE (16 bytes), then
+//! one `ReplayEntry` per frame as a u32 LE length followed by its `protocol::encode` bytes.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context};
+use bevy::prelude::*;
+use protocol::{InputTick, WireMessage, PROTOCOL_VERSION};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::scene::submarine::{self, ClientPhysicsTiming};
+use crate::{Args, ThrustInput};
+
+pub const REPLAY_MAGIC: [u8; 8] = *b"THALSREP";
+pub const REPLAY_HEADER_LEN: usize = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayEntry {
+    /// `ClientPhysicsTiming::tick` the input was applied from.
+    pub server_tick: u64,
+    /// Milliseconds since the Unix epoch when it was recorded.
+    pub wall_ms: u64,
+    pub input: InputTick,
+}
+
+impl WireMessage for ReplayEntry {
+    /// Replay files hold nothing else.
+    fn msg_type(&self) -> u8 {
+        0
+    }
+}
+
+pub fn replay_header() -> [u8; REPLAY_HEADER_LEN] {
+    let mut header = [0; REPLAY_HEADER_LEN];
+    header[..8].copy_from_slice(&REPLAY_MAGIC);
+    header[12..].copy_from_slice(&u32::from(PROTOCOL_VERSION).to_le_bytes());
+    header
+}
+
+/// `entry` framed for the replay file: its length, then its encoding.
+pub fn frame_entry(entry: &ReplayEntry) -> anyhow::Result<Vec<u8>> {
+    let bytes = protocol::encode(entry)?;
+    let mut frame = Vec::with_capacity(4 + bytes.len());
+    frame.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&bytes);
+    Ok(frame)
+}
+
+/// Parse a whole replay file, rejecting other protocol versions.
+pub fn read_replay(bytes: &[u8]) -> anyhow::Result<Vec<ReplayEntry>> {
+    let Some((header, mut rest)) = bytes.split_first_chunk::<REPLAY_HEADER_LEN>() else {
+        bail!("replay file is shorter than its header");
+    };
+    if header[..8] != REPLAY_MAGIC {
+        bail!("not a replay file");
+    }
+    let version = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
+    if version != u32::from(PROTOCOL_VERSION) {
+        bail!("replay recorded under protocol {version}, this client speaks {PROTOCOL_VERSION}");
+    }
+    let mut entries = Vec::new();
+    while let Some((len, tail)) = rest.split_first_chunk::<4>() {
+        let len = u32::from_le_bytes(*len) as usize;
+        let Some(bytes) = tail.get(..len) else {
+            bail!("replay entry {} is truncated", entries.len());
+        };
+        entries.push(
+            protocol::decode(bytes).with_context(|| format!("replay entry {}", entries.len()))?,
+        );
+        rest = &tail[len..];
+    }
+    if !rest.is_empty() {
+        bail!("replay ends in {} stray bytes", rest.len());
+    }
+    Ok(entries)
+}
+
+/// Open `--record` file; every frame's controls are appended to it.
+#[derive(Resource, Debug)]
+pub struct ReplayRecorder {
+    file: File,
+}
+
+impl ReplayRecorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut file = File::create(path)?;
+        file.write_all(&replay_header())?;
+        Ok(Self { file })
+    }
+}
+
+/// Entries of the `--replay` file not yet reached, and the input currently replayed.
+#[derive(Resource, Debug, Default)]
+pub struct ReplayPlayback {
+    pub entries: VecDeque<ReplayEntry>,
+    /// Local tick minus recorded tick, fixed by the first frame played back.
+    tick_offset: Option<i64>,
+    current: Option<InputTick>,
+    finished: bool,
+}
+
+impl ReplayPlayback {
+    pub fn new(entries: Vec<ReplayEntry>) -> Self {
+        Self {
+            entries: entries.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Ok(Self::new(read_replay(&bytes)?))
+    }
+}
+
+fn input_tick(controls: &ThrustInput, tick: u64) -> InputTick {
+    InputTick {
+        tick,
+        thrust: controls.value,
+        yaw: controls.yaw,
+        pump_fwd: controls.pump_fwd,
+        pump_aft: controls.pump_aft,
+        pitch: controls.plane,
+        roll_trim: controls.roll_trim,
+    }
+}
+
+fn apply_input(controls: &mut ThrustInput, input: &InputTick) {
+    controls.value = input.thrust;
+    controls.yaw = input.yaw;
+    controls.pump_fwd = input.pump_fwd;
+    controls.pump_aft = input.pump_aft;
+    controls.plane = input.pitch;
+    controls.roll_trim = input.roll_trim;
+}
+
+pub fn record_inputs(
+    mut commands: Commands,
+    recorder: Option<ResMut<ReplayRecorder>>,
+    controls: Res<ThrustInput>,
+    timing: Res<ClientPhysicsTiming>,
+) {
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+    let entry = ReplayEntry {
+        server_tick: timing.tick,
+        wall_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64),
+        input: input_tick(&controls, timing.tick),
+    };
+    let written = frame_entry(&entry).and_then(|frame| Ok(recorder.file.write_all(&frame)?));
+    if let Err(err) = written {
+        warn!(%err, "Stopped recording inputs");
+        commands.remove_resource::<ReplayRecorder>();
+    }
+}
+
+/// Overwrite the pilot's controls with the newest recorded input at or before this tick. Once
+/// the file runs out, its last input is held.
+pub fn play_back_inputs(
+    playback: Option<ResMut<ReplayPlayback>>,
+    mut controls: ResMut<ThrustInput>,
+    timing: Res<ClientPhysicsTiming>,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+    let playback = &mut *playback;
+    let Some(offset) = playback.tick_offset.or_else(|| {
+        let first = playback.entries.front()?;
+        Some(timing.tick as i64 - first.server_tick as i64)
+    }) else {
+        return;
+    };
+    playback.tick_offset = Some(offset);
+    while let Some(entry) = playback
+        .entries
+        .front()
+        .filter(|e| e.server_tick as i64 + offset <= timing.tick as i64)
+    {
+        playback.current = Some(entry.input.clone());
+        playback.entries.pop_front();
+    }
+    if let Some(input) = &playback.current {
+        apply_input(&mut controls, input);
+    }
+    if playback.entries.is_empty() && !playback.finished {
+        playback.finished = true;
+        info!(tick = timing.tick, "Replay finished");
+    }
+}
+
+/// Records and/or replays the local pilot's controls for `--record` and `--replay`.
+#[derive(Default)]
+pub struct ReplayPlugin {
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+}
+
+impl ReplayPlugin {
+    pub fn from_args(args: &Args) -> Self {
+        Self {
+            record: args.record.clone(),
+            replay: args.replay.clone(),
+        }
+    }
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = &self.record {
+            match ReplayRecorder::create(path) {
+                Ok(recorder) => {
+                    info!(path = %path.display(), "Recording inputs");
+                    app.insert_resource(recorder);
+                }
+                Err(err) => warn!(path = %path.display(), %err, "Not recording inputs"),
+            }
+        }
+        if let Some(path) = &self.replay {
+            match ReplayPlayback::load(path) {
+                Ok(playback) => {
+                    info!(path = %path.display(), entries = playback.entries.len(), "Replaying inputs");
+                    app.insert_resource(playback);
+                }
+                Err(err) => warn!(path = %path.display(), %err, "Not replaying inputs"),
+            }
+        }
+        // After everything that writes the pilot's controls, before they are ramped into the sub
+        app.add_systems(
+            Update,
+            (play_back_inputs, record_inputs)
+                .chain()
+                .after(crate::autopilot::apply_autopilot)
+                .before(submarine::ramp_inputs),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::campaign::CurrentLevel;
+    use crate::rollback::InputHistory;
+    use crate::scene::crash_dump::PhysicsCrashDump;
+    use crate::scene::submarine::{
+        AngularVelocity, SubInputStateComp, SubPhysics, SubStateComp, SubTelemetry, Submarine,
+        Velocity,
+    };
+    use crate::sim_pause::SimPause;
+    use bevy::time::TimeUpdateStrategy;
+    use levels::subspecs::small_skiff_spec;
+    use std::time::Duration;
+
+    const TICKS: u64 = 300;
+
+    /// Scripted controls that change over the run, including pump reversals.
+    fn pilot(timing: Res<ClientPhysicsTiming>, mut controls: ResMut<ThrustInput>) {
+        let t = timing.tick as f32;
+        controls.value = 1.0;
+        controls.yaw = (t / 40.0).sin();
+        controls.pump_fwd = if timing.tick < 120 { 0.6 } else { -0.4 };
+        controls.plane = (t / 70.0).cos() * 0.5;
+    }
+
+    /// A pilot the replay has to override.
+    fn wrong_pilot(mut controls: ResMut<ThrustInput>) {
+        controls.value = -1.0;
+        controls.yaw = 1.0;
+    }
+
+    fn sim_app(replay: ReplayPlugin) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                1.0 / 60.0,
+            )))
+            .init_resource::<ThrustInput>()
+            .init_resource::<SubTelemetry>()
+            .init_resource::<PhysicsCrashDump>()
+            .init_resource::<SimPause>()
+            .init_resource::<ClientPhysicsTiming>()
+            .init_resource::<CurrentLevel>()
+            .init_resource::<InputHistory>()
+            .add_plugins(replay)
+            .add_systems(
+                Update,
+                (submarine::ramp_inputs, submarine::simulate_submarine).chain(),
+            );
+        let start = app.world().resource::<CurrentLevel>().spec().tunnel.pos;
+        app.world_mut().spawn((
+            Transform::from_translation(start),
+            Submarine,
+            Velocity::default(),
+            AngularVelocity::default(),
+            SubPhysics(small_skiff_spec()),
+            SubStateComp(levels::SubState {
+                position: start,
+                velocity: Vec3::ZERO,
+                orientation: Quat::IDENTITY,
+                ang_mom: Vec3::ZERO,
+                ballast_fill: Vec::new(),
+                pump: Default::default(),
+            }),
+            SubInputStateComp::default(),
+        ));
+        app
+    }
+
+    fn fly(app: &mut App) -> Vec3 {
+        while app.world().resource::<ClientPhysicsTiming>().tick < TICKS {
+            app.update();
+        }
+        let mut q = app.world_mut().query::<&SubStateComp>();
+        q.single(app.world()).unwrap().0.position
+    }
+
+    #[test]
+    fn recorded_inputs_replay_to_the_same_position() {
+        let path = std::env::temp_dir().join(format!("thalasso-replay-{}.bin", std::process::id()));
+
+        let mut recording = sim_app(ReplayPlugin {
+            record: Some(path.clone()),
+            replay: None,
+        });
+        recording.add_systems(Update, pilot.before(play_back_inputs));
+        let recorded = fly(&mut recording);
+        drop(recording);
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes[..REPLAY_HEADER_LEN], replay_header());
+        assert_eq!(&bytes[..12], b"THALSREP\0\0\0\0");
+        let entries = read_replay(&bytes).unwrap();
+        assert!(entries.last().unwrap().server_tick >= TICKS - 2);
+
+        let mut replaying = sim_app(ReplayPlugin {
+            record: None,
+            replay: Some(path.clone()),
+        });
+        replaying.add_systems(Update, wrong_pilot.before(play_back_inputs));
+        let replayed = fly(&mut replaying);
+        let start = CurrentLevel::default().spec().tunnel.pos;
+        assert!(
+            recorded.distance(start) > 1.0,
+            "the sub barely moved: {recorded}"
+        );
+        assert!(
+            recorded.distance(replayed) < 0.01,
+            "recorded {recorded}, replayed {replayed}"
+        );
+
+        // Another protocol's recording is refused
+        let mut stale = bytes;
+        stale[12..16].copy_from_slice(&(u32::from(PROTOCOL_VERSION) - 1).to_le_bytes());
+        assert!(read_replay(&stale).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
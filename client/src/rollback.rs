@@ -0,0 +1,278 @@
+use bevy::prelude::*;
+use levels::{
+    step_submarine_dbg, LevelSpec, PumpCooldownState, SubInputState, SubPhysicsSpec, SubState,
+};
+use std::collections::VecDeque;
+use tracing::debug;
+
+use crate::campaign::CurrentLevel;
+use crate::scene::crash_dump::PhysicsDiverged;
+use crate::scene::submarine::{ClientPhysicsTiming, SubPhysics, SubStateComp, Submarine};
+
+/// Predicted steps `InputHistory` keeps (~1 s at 120 Hz).
+pub const INPUT_HISTORY_LEN: usize = 128;
+/// Prediction error at an acked tick that triggers a rollback.
+pub const ROLLBACK_POSITION_ERROR_M: f32 = 0.5;
+pub const ROLLBACK_ANGLE_ERROR_RAD: f32 = 5.0 * std::f32::consts::PI / 180.0;
+pub const ROLLBACK_PUMP_ERROR_S: f32 = 0.25;
+
+/// One predicted fixed step: the inputs it was stepped with, the flow time it sampled and the
+/// state it ended in.
+#[derive(Debug, Clone)]
+pub struct InputHistoryEntry {
+    pub tick: u64,
+    pub inputs: SubInputState,
+    pub time: f32,
+    pub state: SubState,
+}
+
+/// The local sub's recent predicted steps, keyed by `ClientPhysicsTiming::tick`, so a server
+/// correction for a past tick can be replayed forward through the inputs since.
+#[derive(Resource, Debug, Default)]
+pub struct InputHistory {
+    pub entries: VecDeque<InputHistoryEntry>,
+}
+
+impl InputHistory {
+    pub fn push(&mut self, entry: InputHistoryEntry) {
+        // A restart from a snapshot renumbers ticks; anything at or past it is stale
+        while self.entries.back().is_some_and(|e| e.tick >= entry.tick) {
+            self.entries.pop_back();
+        }
+        if self.entries.len() >= INPUT_HISTORY_LEN {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub fn at(&self, tick: u64) -> Option<&InputHistoryEntry> {
+        self.entries.iter().rev().find(|e| e.tick == tick)
+    }
+
+    /// Restart from `state` at `tick` and re-step every later entry with its recorded inputs,
+    /// overwriting their states. Returns the newest state, or `None` if `tick` isn't held.
+    pub fn replay(
+        &mut self,
+        tick: u64,
+        state: SubState,
+        level: &LevelSpec,
+        spec: &SubPhysicsSpec,
+        dt: f32,
+    ) -> Option<SubState> {
+        let start = self.entries.iter().position(|e| e.tick == tick)?;
+        self.entries[start].state = state.clone();
+        let mut state = state;
+        for entry in self.entries.iter_mut().skip(start + 1) {
+            step_submarine_dbg(level, spec, entry.inputs, &mut state, dt, entry.time, None);
+            entry.state = state.clone();
+        }
+        Some(state)
+    }
+}
+
+/// Server state for a past tick, from an `InputAck` correction.
+#[derive(Event, Debug, Clone)]
+pub struct AckCorrection {
+    pub tick: u64,
+    pub player: protocol::NetPlayer,
+}
+
+pub fn sub_state_from_net(p: &protocol::NetPlayer) -> SubState {
+    SubState {
+        position: Vec3::from_array(p.position),
+        velocity: Vec3::from_array(p.velocity),
+        orientation: Quat::from_array(p.orientation),
+        ang_mom: Vec3::from_array(p.ang_mom),
+        ballast_fill: p.ballast_fill.clone(),
+        pump: pump_from_net(p.pump),
+    }
+}
+
+pub fn pump_from_net(pump: protocol::NetPumpState) -> PumpCooldownState {
+    PumpCooldownState {
+        boost_remaining_s: pump.boost_remaining_s,
+        cooldown_remaining_s: pump.cooldown_remaining_s,
+    }
+}
+
+pub fn prediction_diverged(predicted: &SubState, server: &SubState) -> bool {
+    predicted.position.distance(server.position) > ROLLBACK_POSITION_ERROR_M
+        || predicted.orientation.angle_between(server.orientation) > ROLLBACK_ANGLE_ERROR_RAD
+        || (predicted.pump.boost_remaining_s - server.pump.boost_remaining_s).abs()
+            > ROLLBACK_PUMP_ERROR_S
+        || (predicted.pump.cooldown_remaining_s - server.pump.cooldown_remaining_s).abs()
+            > ROLLBACK_PUMP_ERROR_S
+}
+
+/// Check each acked correction against what was predicted for its tick; past the thresholds,
+/// rewind the local sub to the server's state and replay the inputs since.
+#[allow(clippy::type_complexity)]
+pub fn rollback_on_correction(
+    mut corrections: EventReader<AckCorrection>,
+    mut history: ResMut<InputHistory>,
+    timing: Res<ClientPhysicsTiming>,
+    level: Res<CurrentLevel>,
+    mut q_sub: Query<(&mut SubStateComp, &SubPhysics), (With<Submarine>, Without<PhysicsDiverged>)>,
+) {
+    let Some(correction) = corrections.read().last() else {
+        return;
+    };
+    if correction.tick >= timing.tick {
+        return;
+    }
+    let Ok((mut state, spec)) = q_sub.single_mut() else {
+        return;
+    };
+    let Some(predicted) = history.at(correction.tick) else {
+        return;
+    };
+    let server = sub_state_from_net(&correction.player);
+    if !prediction_diverged(&predicted.state, &server) {
+        return;
+    }
+    let error_m = predicted.state.position.distance(server.position);
+    if let Some(replayed) =
+        history.replay(correction.tick, server, level.spec(), &spec.0, timing.dt)
+    {
+        debug!(
+            tick = correction.tick,
+            replayed_to = timing.tick,
+            error_m,
+            "Rolled back prediction"
+        );
+        state.0 = replayed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use levels::{builtins::greybox_level, subspecs::small_skiff_spec, SubInputs};
+
+    fn predict(history: &mut InputHistory, start: SubState, steps: u64, dt: f32) -> SubState {
+        let level = greybox_level();
+        let spec = small_skiff_spec();
+        let mut inputs = SubInputState::default();
+        let mut state = start;
+        for tick in 1..=steps {
+            let target = SubInputs {
+                thrust: 1.0,
+                ..Default::default()
+            };
+            inputs.ramp_toward(target, dt, &spec);
+            let time = tick as f32 * dt;
+            step_submarine_dbg(&level, &spec, inputs, &mut state, dt, time, None);
+            history.push(InputHistoryEntry {
+                tick,
+                inputs,
+                time,
+                state: state.clone(),
+            });
+        }
+        state
+    }
+
+    fn start_state() -> SubState {
+        let spec = small_skiff_spec();
+        SubState {
+            position: Vec3::new(140.0, 4.0, 0.0),
+            velocity: Vec3::ZERO,
+            orientation: Quat::IDENTITY,
+            ang_mom: Vec3::ZERO,
+            ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+            pump: Default::default(),
+        }
+    }
+
+    #[test]
+    fn history_is_capped_and_drops_renumbered_ticks() {
+        let mut history = InputHistory::default();
+        predict(&mut history, start_state(), 200, 1.0 / 60.0);
+        assert_eq!(history.entries.len(), INPUT_HISTORY_LEN);
+        assert_eq!(history.entries.front().unwrap().tick, 200 - 127);
+
+        // Prediction restarted from an older snapshot tick
+        let restart = history.at(150).unwrap().clone();
+        history.push(restart);
+        assert_eq!(history.entries.back().unwrap().tick, 150);
+        assert!(history.at(151).is_none());
+    }
+
+    #[test]
+    fn replay_from_corrected_state_matches_fresh_prediction() {
+        let dt = 1.0 / 60.0;
+        let level = greybox_level();
+        let spec = small_skiff_spec();
+        let mut history = InputHistory::default();
+        predict(&mut history, start_state(), 60, dt);
+
+        // The server saw the sub 1 m further along at tick 20
+        let mut server = history.at(20).unwrap().state.clone();
+        server.position.x += 1.0;
+        assert!(prediction_diverged(&history.at(20).unwrap().state, &server));
+        let replayed = history
+            .replay(20, server.clone(), &level, &spec, dt)
+            .unwrap();
+
+        // Same as stepping ticks 21..=60 from the server state with the recorded inputs
+        let mut expected = server;
+        let mut reference = InputHistory::default();
+        predict(&mut reference, start_state(), 60, dt);
+        for entry in reference.entries.iter().filter(|e| e.tick > 20) {
+            step_submarine_dbg(
+                &level,
+                &spec,
+                entry.inputs,
+                &mut expected,
+                dt,
+                entry.time,
+                None,
+            );
+        }
+        assert_eq!(replayed, expected);
+        assert_eq!(history.at(60).unwrap().state, replayed);
+        let drift = replayed.position.x - reference.at(60).unwrap().state.position.x;
+        assert!(drift > 0.5, "correction was lost in replay: {drift}");
+
+        // Small errors are left to the snapshot filter
+        let mut close = history.at(30).unwrap().state.clone();
+        close.position.y += 0.1;
+        assert!(!prediction_diverged(&history.at(30).unwrap().state, &close));
+    }
+
+    #[test]
+    fn pump_budget_is_restored_from_the_server() {
+        let player = protocol::NetPlayer {
+            id: uuid::Uuid::nil(),
+            position: [140.0, 4.0, 0.0],
+            velocity: [2.0, 0.0, 0.0],
+            orientation: Quat::IDENTITY.to_array(),
+            ang_mom: [0.0; 3],
+            angular_velocity: [0.0; 3],
+            ballast_fill: vec![0.5; 2],
+            input_state: protocol::NetInputState {
+                thrust: 1.0,
+                yaw: 0.0,
+                pump_fwd: 1.0,
+                pump_aft: 0.0,
+                pitch: 0.0,
+                roll_trim: 0.0,
+            },
+            hull_integrity: 1.0,
+            team_id: 0,
+            pump: protocol::NetPumpState {
+                boost_remaining_s: 1.5,
+                cooldown_remaining_s: 0.0,
+            },
+        };
+        let server = sub_state_from_net(&player);
+        assert_eq!(server.pump.boost_remaining_s, 1.5);
+
+        // Same pose, but the server has burnt more of the turbo boost than was predicted
+        let predicted = SubState {
+            pump: PumpCooldownState::default(),
+            ..server.clone()
+        };
+        assert!(prediction_diverged(&predicted, &server));
+    }
+}
@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+use levels::ao::{decode_ao_maps, AoMap};
+
+/// Baked AO for the greybox level, written by `levels`' `bake_ao` binary.
+pub const GREYBOX_AO_PATH: &str = "baked_ao/greybox.ao.bincode";
+
+/// Per-mesh AO factors for the current level, keyed by mesh (entity) name.
+#[derive(Asset, TypePath, Debug)]
+pub struct BakedAo(pub Vec<AoMap>);
+
+#[derive(Default)]
+struct BakedAoLoader;
+
+impl bevy::asset::AssetLoader for BakedAoLoader {
+    type Asset = BakedAo;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn bevy::asset::io::Reader,
+        _settings: &(),
+        _load_context: &mut bevy::asset::LoadContext<'_>,
+    ) -> Result<BakedAo, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        decode_ao_maps(&bytes)
+            .map(BakedAo)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ao.bincode"]
+    }
+}
+
+/// The level's baked AO, if a bake was shipped for it.
+#[derive(Resource)]
+pub struct LevelAo(pub Handle<BakedAo>);
+
+/// Static mesh whose vertex colors take the AO map matching its `Name`. The mesh must have the
+/// vertex layout the bake used (`Plane3d` with `STATIC_PLANE_SUBDIVISIONS`).
+#[derive(Component)]
+pub struct StaticAoMesh;
+
+/// Resolve `path` under the asset root and return it only if the file exists, so optional assets
+/// (AO bakes, photo textures) fall back quietly instead of logging a load error.
+pub fn asset_if_present(path: &str) -> Option<&str> {
+    let full = bevy::asset::io::file::FileAssetReader::get_base_path()
+        .join("assets")
+        .join(path);
+    full.exists().then_some(path)
+}
+
+pub struct BakedAoPlugin;
+
+impl Plugin for BakedAoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BakedAo>()
+            .init_asset_loader::<BakedAoLoader>()
+            .add_systems(Update, apply_baked_ao);
+        #[cfg(debug_assertions)]
+        app.init_asset::<LevelSpecSource>()
+            .init_asset_loader::<LevelSpecSourceLoader>()
+            .add_systems(Startup, load_level_spec_source)
+            .add_systems(Update, rebake_ao_on_level_change);
+    }
+}
+
+/// Write AO into the vertex colors of every `StaticAoMesh`; `StandardMaterial` multiplies
+/// them into the albedo (`base_color = albedo * ao`). Re-runs when the bake (re)loads.
+fn apply_baked_ao(
+    mut events: EventReader<AssetEvent<BakedAo>>,
+    level_ao: Option<Res<LevelAo>>,
+    baked: Res<Assets<BakedAo>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    q_new: Query<(), Added<StaticAoMesh>>,
+    q_static: Query<(&Name, &Mesh3d), With<StaticAoMesh>>,
+) {
+    let Some(level_ao) = level_ao else {
+        return;
+    };
+    let id = level_ao.0.id();
+    let reloaded = events
+        .read()
+        .any(|e| e.is_loaded_with_dependencies(id) || e.is_modified(id));
+    if !reloaded && q_new.is_empty() {
+        return;
+    }
+    let Some(BakedAo(maps)) = baked.get(id) else {
+        return;
+    };
+    for (name, mesh3d) in &q_static {
+        let Some(map) = maps.iter().find(|m| m.mesh_name == name.as_str()) else {
+            continue;
+        };
+        let Some(mesh) = meshes.get_mut(&mesh3d.0) else {
+            continue;
+        };
+        if mesh.count_vertices() != map.ao.len() {
+            tracing::warn!(
+                mesh = name.as_str(),
+                vertices = mesh.count_vertices(),
+                baked = map.ao.len(),
+                "Baked AO does not match mesh; re-run bake_ao"
+            );
+            continue;
+        }
+        let colors: Vec<[f32; 4]> = map.ao.iter().map(|&a| [a, a, a, 1.0]).collect();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    }
+}
+
+#[cfg(debug_assertions)]
+#[derive(Asset, TypePath, Debug)]
+pub struct LevelSpecSource(pub String);
+
+#[cfg(debug_assertions)]
+#[derive(Default)]
+struct LevelSpecSourceLoader;
+
+#[cfg(debug_assertions)]
+impl bevy::asset::AssetLoader for LevelSpecSourceLoader {
+    type Asset = LevelSpecSource;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn bevy::asset::io::Reader,
+        _settings: &(),
+        _load_context: &mut bevy::asset::LoadContext<'_>,
+    ) -> Result<LevelSpecSource, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        String::from_utf8(bytes)
+            .map(LevelSpecSource)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+#[cfg(debug_assertions)]
+#[derive(Resource)]
+struct LevelSpecHandle(Handle<LevelSpecSource>);
+
+#[cfg(debug_assertions)]
+fn load_level_spec_source(mut commands: Commands, assets: Res<AssetServer>) {
+    commands.insert_resource(LevelSpecHandle(assets.load("levels/greybox.level.ron")));
+}
+
+/// Debug-only: re-bake AO in-process when `assets/levels/greybox.level.ron` is saved. Blocks
+/// the frame for the bake (about a second in debug builds); the on-disk bake is not touched.
+#[cfg(debug_assertions)]
+fn rebake_ao_on_level_change(
+    mut events: EventReader<AssetEvent<LevelSpecSource>>,
+    handle: Option<Res<LevelSpecHandle>>,
+    sources: Res<Assets<LevelSpecSource>>,
+    level_ao: Option<Res<LevelAo>>,
+    mut baked: ResMut<Assets<BakedAo>>,
+    mut log: ResMut<crate::notifications::NotificationLog>,
+) {
+    let (Some(handle), Some(level_ao)) = (handle, level_ao) else {
+        return;
+    };
+    for event in events.read() {
+        // Only edits count; the initial load mirrors the shipped bake
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        if *id != handle.0.id() {
+            continue;
+        }
+        let Some(source) = sources.get(*id) else {
+            continue;
+        };
+        match levels::LevelSpec::from_ron_str(&source.0) {
+            Ok(level) => {
+                let maps = levels::ao::bake_level_ao(&level);
+                if let Some(ao) = baked.get_mut(&level_ao.0) {
+                    ao.0 = maps;
+                } else {
+                    baked.insert(&level_ao.0, BakedAo(maps));
+                }
+                tracing::info!("Level spec changed; static AO re-baked");
+                log.push("AO REBAKED");
+            }
+            Err(err) => {
+                tracing::warn!(%err, "Rejected level spec reload; keeping previous AO");
+                log.push(format!("LEVEL INVALID: {err}"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use levels::ao::{bake_level_ao, static_surfaces, STATIC_PLANE_SUBDIVISIONS};
+    use levels::builtins::greybox_level;
+
+    #[test]
+    fn baked_surfaces_match_bevy_plane_vertex_order() {
+        for surface in static_surfaces(&greybox_level()) {
+            let mesh = Plane3d::default()
+                .mesh()
+                .size(surface.size[0], surface.size[1])
+                .subdivisions(STATIC_PLANE_SUBDIVISIONS)
+                .build();
+            let transform =
+                Transform::from_translation(surface.center).with_rotation(surface.rotation);
+            let Some(bevy::render::mesh::VertexAttributeValues::Float32x3(positions)) =
+                mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            else {
+                panic!("plane mesh has positions");
+            };
+            let baked = surface.vertices(STATIC_PLANE_SUBDIVISIONS);
+            assert_eq!(positions.len(), baked.len(), "{}", surface.name);
+            for (p, b) in positions.iter().zip(&baked) {
+                let world = transform.transform_point(Vec3::from_array(*p));
+                assert!(
+                    world.distance(*b) < 1e-3,
+                    "{}: {world} vs {b}",
+                    surface.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn shipped_level_and_bake_match_builtin_greybox() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let text = std::fs::read_to_string(dir.join("levels/greybox.level.ron")).unwrap();
+        let shipped = levels::LevelSpec::from_ron_str(&text).expect("level asset parses");
+        assert_eq!(
+            format!("{shipped:?}"),
+            format!("{:?}", greybox_level()),
+            "assets/levels/greybox.level.ron drifted from greybox_level()"
+        );
+
+        let bytes = std::fs::read(dir.join(GREYBOX_AO_PATH)).unwrap();
+        let maps = decode_ao_maps(&bytes).expect("baked AO decodes");
+        let fresh = bake_level_ao(&greybox_level());
+        assert_eq!(maps.len(), fresh.len());
+        for (m, f) in maps.iter().zip(&fresh) {
+            assert_eq!(m.mesh_name, f.mesh_name);
+            assert_eq!(
+                m.ao.len(),
+                f.ao.len(),
+                "{}: stale bake, re-run bake_ao",
+                m.mesh_name
+            );
+            let max_diff =
+                m.ao.iter()
+                    .zip(&f.ao)
+                    .map(|(a, b)| (a - b).abs())
+                    .fold(0.0, f32::max);
+            assert!(
+                max_diff < 1e-3,
+                "{}: stale bake, re-run bake_ao",
+                m.mesh_name
+            );
+        }
+    }
+}
@@ -23,6 +23,33 @@ pub struct FollowCam {
     pub distance: f32,
     pub height: f32,
     pub stiffness: f32, // larger = snappier follow
+    /// Sideways offset along the sub's starboard axis (m); negative swings to port.
+    pub lateral_offset: f32,
+    /// Raises the look-at point above the sub so the camera tilts down onto it less (deg).
+    pub vertical_pitch_deg: f32,
+}
+
+/// `lateral_offset` values cycled by [Z]: trailing, starboard, port.
+pub const FOLLOW_CAM_SIDE_OFFSETS: [f32; 3] = [0.0, 3.0, -3.0];
+
+/// Follow-camera position and look-at point for a sub at `sub_pos` heading along `dir`,
+/// with `right` its starboard axis.
+pub fn follow_cam_targets(cam: &FollowCam, sub_pos: Vec3, dir: Vec3, right: Vec3) -> (Vec3, Vec3) {
+    let desired_pos =
+        sub_pos - dir * cam.distance + Vec3::Y * cam.height + right * cam.lateral_offset;
+    let look_target = sub_pos + Vec3::Y * cam.vertical_pitch_deg.to_radians().tan() * cam.distance;
+    (desired_pos, look_target)
+}
+
+/// The offset after `current` in `FOLLOW_CAM_SIDE_OFFSETS`, wrapping; unknown values restart.
+pub fn next_side_offset(current: f32) -> f32 {
+    let n = FOLLOW_CAM_SIDE_OFFSETS.len();
+    FOLLOW_CAM_SIDE_OFFSETS
+        .iter()
+        .position(|o| (o - current).abs() < 1e-3)
+        .map_or(FOLLOW_CAM_SIDE_OFFSETS[0], |i| {
+            FOLLOW_CAM_SIDE_OFFSETS[(i + 1) % n]
+        })
 }
 
 #[derive(Component)]
@@ -46,6 +73,8 @@ pub fn update_game_camera(
     };
     let sub_pos = sub_t.translation;
     let orient_dir = (sub_t.rotation * Vec3::X).normalize_or_zero();
+    // Mesh space has +X forward, so starboard is -Z
+    let right = sub_t.rotation * Vec3::NEG_Z;
 
     for (mut cam_t, cam, mut state, mode) in &mut q_cam {
         match *mode {
@@ -56,12 +85,12 @@ pub fn update_game_camera(
                     state.last_dir
                 };
                 state.last_dir = dir;
-                let desired_pos = sub_pos - dir * cam.distance + Vec3::Y * cam.height;
+                let (desired_pos, look_target) = follow_cam_targets(cam, sub_pos, dir, right);
                 let stiffness = cam.stiffness.max(0.0);
                 let dt = time.delta_secs();
                 let lerp = 1.0 - (-stiffness * dt).exp();
                 cam_t.translation = cam_t.translation.lerp(desired_pos, lerp);
-                cam_t.look_at(sub_pos, Vec3::Y);
+                cam_t.look_at(look_target, Vec3::Y);
             }
             CamMode::FirstPerson => {
                 // Lock camera to the submarine with an orientation offset:
@@ -85,7 +114,9 @@ pub fn switch_cameras_keys(
     let Ok(mut mode) = q.single_mut() else {
         return;
     };
-    if keys.just_pressed(KeyCode::F11) {
+    // Shift+F11 is the remote desync CSV export
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keys.just_pressed(KeyCode::F11) && !shift {
         *mode = if *mode == CamMode::Follow {
             CamMode::FirstPerson
         } else {
@@ -101,6 +132,19 @@ pub fn switch_cameras_keys(
     }
 }
 
+/// [Z] swings the follow camera between trailing and the two side views.
+pub fn follow_cam_side_key(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut q: Query<&mut FollowCam, With<GameCamera>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyZ) {
+        return;
+    }
+    for mut cam in &mut q {
+        cam.lateral_offset = next_side_offset(cam.lateral_offset);
+    }
+}
+
 pub fn free_fly_camera(
     time: Res<Time>,
     keys: Res<ButtonInput<KeyCode>>,
@@ -164,3 +208,48 @@ pub fn free_fly_camera(
         t.translation += dir.normalize() * speed * time.delta_secs();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cam(lateral_offset: f32, vertical_pitch_deg: f32) -> FollowCam {
+        FollowCam {
+            distance: 8.0,
+            height: 2.0,
+            stiffness: 8.0,
+            lateral_offset,
+            vertical_pitch_deg,
+        }
+    }
+
+    #[test]
+    fn side_offset_moves_camera_along_starboard_axis() {
+        // Sub turned 90 deg: mesh forward +X now points at -Z, starboard -Z at -X
+        let rot = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        let sub_pos = Vec3::new(5.0, -10.0, 1.0);
+        let dir = rot * Vec3::X;
+        let right = rot * Vec3::NEG_Z;
+
+        let (trail, look) = follow_cam_targets(&cam(0.0, 0.0), sub_pos, dir, right);
+        assert!(
+            trail.abs_diff_eq(Vec3::new(5.0, -8.0, 9.0), 1e-4),
+            "{trail}"
+        );
+        assert_eq!(look, sub_pos);
+
+        let (side, _) = follow_cam_targets(&cam(3.0, 0.0), sub_pos, dir, right);
+        assert!(side.abs_diff_eq(Vec3::new(2.0, -8.0, 9.0), 1e-4), "{side}");
+
+        let (_, look) = follow_cam_targets(&cam(0.0, 45.0), sub_pos, dir, right);
+        assert!(look.abs_diff_eq(sub_pos + Vec3::Y * 8.0, 1e-4), "{look}");
+    }
+
+    #[test]
+    fn side_key_cycles_offsets() {
+        assert_eq!(next_side_offset(0.0), 3.0);
+        assert_eq!(next_side_offset(3.0), -3.0);
+        assert_eq!(next_side_offset(-3.0), 0.0);
+        assert_eq!(next_side_offset(1.5), 0.0);
+    }
+}
@@ -0,0 +1,125 @@
+//! Post-mortem dump of the last physics steps when client prediction diverges.
+
+use std::collections::VecDeque;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use levels::{SubState, SubStepDebug};
+
+/// Steps of `SubStepDebug` history kept for a dump.
+pub const CRASH_DUMP_STEPS: usize = 32;
+/// Speed (m/s) past which the integrator is treated as having blown up.
+pub const DIVERGENCE_SPEED: f32 = 1e6;
+
+/// Marks a submarine whose prediction diverged; `simulate_submarine` skips it so NaNs don't
+/// spread, and server corrections keep it moving.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PhysicsDiverged;
+
+/// Ring buffer of the most recent prediction steps, written out when a step diverges.
+#[derive(Resource, Debug, Clone)]
+pub struct PhysicsCrashDump {
+    steps: VecDeque<SubStepDebug>,
+    /// Directory dumps are written to.
+    pub dir: PathBuf,
+}
+
+impl Default for PhysicsCrashDump {
+    fn default() -> Self {
+        Self {
+            steps: VecDeque::with_capacity(CRASH_DUMP_STEPS),
+            dir: PathBuf::from("."),
+        }
+    }
+}
+
+impl PhysicsCrashDump {
+    pub fn push(&mut self, step: SubStepDebug) {
+        if self.steps.len() == CRASH_DUMP_STEPS {
+            self.steps.pop_front();
+        }
+        self.steps.push_back(step);
+    }
+
+    /// Serialize the buffered steps, oldest first, to `physics_crash_<unix ms>.json` in `dir`.
+    pub fn write(&self) -> io::Result<PathBuf> {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let steps: Vec<_> = self.steps.iter().collect();
+        let json = serde_json::to_string_pretty(&steps).map_err(io::Error::other)?;
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("physics_crash_{millis}.json"));
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+}
+
+/// NaN position or a runaway velocity: the state can't be stepped any further.
+pub fn is_divergent(state: &SubState) -> bool {
+    !state.position.is_finite() || !state.velocity.is_finite() || state.speed() > DIVERGENCE_SPEED
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use levels::builtins::greybox_level;
+    use levels::subspecs::small_skiff_spec;
+    use levels::{step_submarine_dbg, Quatf, SubInputState, Vec3f};
+
+    #[test]
+    fn degenerate_spec_produces_a_crash_dump() {
+        let level = greybox_level();
+        let mut spec = small_skiff_spec();
+        spec.xu = 0.0;
+        spec.yv = 0.0;
+        spec.zw = 0.0;
+        spec.cxd = 0.0;
+        spec.cyd = 0.0;
+        spec.czd = 0.0;
+        spec.t_max = 1e12;
+        let mut state = SubState {
+            position: level.tunnel.pos,
+            velocity: Vec3f::ZERO,
+            orientation: Quatf::IDENTITY,
+            ang_mom: Vec3f::ZERO,
+            ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+            pump: Default::default(),
+        };
+        let inputs = SubInputState {
+            thrust: 1.0,
+            ..Default::default()
+        };
+        let mut dump = PhysicsCrashDump {
+            dir: std::env::temp_dir().join(format!("thalassocracy-crash-{}", std::process::id())),
+            ..Default::default()
+        };
+
+        let mut written = None;
+        for i in 0..1000 {
+            let mut dbg = SubStepDebug::default();
+            step_submarine_dbg(
+                &level,
+                &spec,
+                inputs,
+                &mut state,
+                1.0 / 60.0,
+                i as f32 / 60.0,
+                Some(&mut dbg),
+            );
+            dump.push(dbg);
+            if is_divergent(&state) {
+                written = Some(dump.write().unwrap());
+                break;
+            }
+        }
+
+        let path = written.expect("degenerate spec never diverged");
+        let steps: Vec<serde_json::Value> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let _ = std::fs::remove_dir_all(&dump.dir);
+        assert!(!steps.is_empty() && steps.len() <= CRASH_DUMP_STEPS);
+    }
+}
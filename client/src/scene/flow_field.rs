@@ -8,6 +8,14 @@ pub enum FlowField {
     /// Uniform flow across space; `flow` is a 3D vector in world units/sec.
     /// `variance` encodes short-term stochastic deviation magnitude.
     Uniform { flow: Vec3, variance: f32 },
+    /// Per-cell flow vectors on an axis-aligned grid of `dims` cubes of side `cell_size`,
+    /// starting at the `origin` corner; `cells` is indexed x fastest, then y, then z.
+    Grid {
+        origin: Vec3,
+        cell_size: f32,
+        dims: UVec3,
+        cells: Vec<Vec3>,
+    },
 }
 
 impl FlowField {
@@ -16,10 +24,28 @@ impl FlowField {
     }
 
     /// Sample the flow vector and variance at a world position and time.
-    /// For Uniform, returns the same values regardless of `pos`/`time`.
-    pub fn sample(&self, _pos: Vec3, _time: f32) -> (Vec3, f32) {
-        match *self {
-            FlowField::Uniform { flow, variance } => (flow, variance),
+    /// For Uniform, returns the same values regardless of `pos`/`time`; Grid returns the
+    /// containing cell's vector (zero outside the grid) and no variance.
+    pub fn sample(&self, pos: Vec3, _time: f32) -> (Vec3, f32) {
+        match self {
+            FlowField::Uniform { flow, variance } => (*flow, *variance),
+            FlowField::Grid {
+                origin,
+                cell_size,
+                dims,
+                cells,
+            } => {
+                let cell = ((pos - *origin) / *cell_size).floor();
+                if cell.cmplt(Vec3::ZERO).any() || cell.cmpge(dims.as_vec3()).any() {
+                    return (Vec3::ZERO, 0.0);
+                }
+                let cell = cell.as_uvec3();
+                let index = cell.x + dims.x * (cell.y + dims.y * cell.z);
+                (
+                    cells.get(index as usize).copied().unwrap_or(Vec3::ZERO),
+                    0.0,
+                )
+            }
         }
     }
 }
@@ -34,10 +60,62 @@ pub struct TunnelBounds {
     pub size: Vec3, // X length, Y height, Z width (local space)
 }
 
+impl TunnelBounds {
+    /// Whether `p` is inside these bounds placed axis-aligned at `center`, walls included.
+    pub fn contains_point(&self, center: Vec3, p: Vec3) -> bool {
+        levels::aabb_contains(center, self.size, p)
+    }
+
+    /// Like `contains_point`, for bounds rotated and scaled with the tunnel's `transform`.
+    pub fn contains_point_rotated(&self, transform: &GlobalTransform, p: Vec3) -> bool {
+        let local = transform.affine().inverse().transform_point3(p);
+        self.contains_point(Vec3::ZERO, local)
+    }
+}
+
+/// Blue at rest through red at `max_speed`.
+fn speed_color(speed: f32, max_speed: f32) -> Color {
+    let t = if max_speed > 0.0 {
+        (speed / max_speed).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    Color::srgb(t, 0.0, 1.0 - t)
+}
+
+/// Conservative frustum test: false only when all eight corners of the box are outside the
+/// same clip plane. Bevy's reverse-Z puts the near plane at z = w and infinity at z = 0.
+pub fn aabb_in_frustum(clip_from_world: Mat4, min: Vec3, max: Vec3) -> bool {
+    let corners = (0..8).map(|i| {
+        let corner = Vec3::new(
+            if i & 1 == 0 { min.x } else { max.x },
+            if i & 2 == 0 { min.y } else { max.y },
+            if i & 4 == 0 { min.z } else { max.z },
+        );
+        clip_from_world * corner.extend(1.0)
+    });
+    let mut outside = [true; 6];
+    for c in corners {
+        let planes = [
+            c.x < -c.w,
+            c.x > c.w,
+            c.y < -c.w,
+            c.y > c.w,
+            c.z < 0.0,
+            c.z > c.w,
+        ];
+        for (all_out, out) in outside.iter_mut().zip(planes) {
+            *all_out &= out;
+        }
+    }
+    !outside.iter().any(|&o| o)
+}
+
 pub fn draw_flow_gizmos(
     vis: Option<Res<crate::debug_vis::DebugVis>>,
     mut gizmos: Gizmos,
     q: Query<(&GlobalTransform, &FlowField, &TunnelBounds), With<Tunnel>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
     time: Res<Time>,
 ) {
     let Some(vis) = vis else {
@@ -47,7 +125,45 @@ pub fn draw_flow_gizmos(
         return;
     }
 
+    // World-to-clip for the active 3D camera; without one nothing is culled
+    let clip_from_world = q_camera
+        .iter()
+        .find(|(camera, _)| camera.is_active)
+        .map(|(camera, transform)| camera.clip_from_view() * transform.compute_matrix().inverse());
+
     for (transform, field, bounds) in &q {
+        if let FlowField::Grid {
+            origin,
+            cell_size,
+            dims,
+            cells,
+        } = field
+        {
+            let max_speed = cells.iter().map(|c| c.length()).fold(0.0, f32::max);
+            let step = vis.flow_grid_density.max(1) as usize;
+            for z in (0..dims.z).step_by(step) {
+                for y in (0..dims.y).step_by(step) {
+                    for x in (0..dims.x).step_by(step) {
+                        let min = *origin + UVec3::new(x, y, z).as_vec3() * *cell_size;
+                        let max = min + Vec3::splat(*cell_size);
+                        if clip_from_world.is_some_and(|m| !aabb_in_frustum(m, min, max)) {
+                            continue;
+                        }
+                        let index = (x + dims.x * (y + dims.y * z)) as usize;
+                        let flow = cells.get(index).copied().unwrap_or(Vec3::ZERO);
+                        let speed = flow.length();
+                        if speed <= 1e-3 {
+                            continue;
+                        }
+                        let center = (min + max) * 0.5;
+                        let tip = center + flow / speed * *cell_size * 0.8;
+                        gizmos.arrow(center, tip, speed_color(speed, max_speed));
+                    }
+                }
+            }
+            continue;
+        }
+
         // For now, assume axis-aligned tunnel (no rotation or non-uniform scale).
         let center = transform.translation();
         let half = bounds.size * 0.5;
@@ -82,3 +198,75 @@ pub fn draw_flow_gizmos(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::camera::CameraProjection;
+
+    #[test]
+    fn tunnel_bounds_contain_points_up_to_the_walls() {
+        let bounds = TunnelBounds {
+            size: Vec3::new(10.0, 4.0, 2.0),
+        };
+        let center = Vec3::new(100.0, -5.0, 3.0);
+        assert!(bounds.contains_point(center, center));
+        // Faces and corners count as inside
+        assert!(bounds.contains_point(center, center + Vec3::new(5.0, 0.0, 0.0)));
+        assert!(bounds.contains_point(center, center + Vec3::new(-5.0, 2.0, -1.0)));
+        assert!(!bounds.contains_point(center, center + Vec3::new(5.01, 0.0, 0.0)));
+        assert!(!bounds.contains_point(center, center + Vec3::new(0.0, -2.01, 0.0)));
+        assert!(!bounds.contains_point(center, center + Vec3::new(0.0, 0.0, 1.01)));
+    }
+
+    #[test]
+    fn rotated_tunnel_bounds_test_points_in_local_space() {
+        let bounds = TunnelBounds {
+            size: Vec3::new(10.0, 4.0, 2.0),
+        };
+        // Long axis turned from +X onto +Z
+        let transform = GlobalTransform::from(
+            Transform::from_xyz(1.0, 2.0, 3.0)
+                .with_rotation(Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2)),
+        );
+        let center = transform.translation();
+        // A quarter turn isn't exact in f32, so stay a hair inside the rotated faces
+        assert!(bounds.contains_point_rotated(&transform, center + Vec3::new(0.0, 0.0, 4.99)));
+        assert!(bounds.contains_point_rotated(&transform, center + Vec3::new(0.99, -1.99, -4.99)));
+        assert!(!bounds.contains_point_rotated(&transform, center + Vec3::new(0.0, 0.0, 5.01)));
+        // Inside the unrotated box, but past the rotated tunnel's side wall
+        assert!(!bounds.contains_point_rotated(&transform, center + Vec3::new(4.0, 0.0, 0.0)));
+        assert!(bounds.contains_point(center, center + Vec3::new(4.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn grid_samples_the_containing_cell() {
+        let field = FlowField::Grid {
+            origin: Vec3::new(-2.0, 0.0, 0.0),
+            cell_size: 2.0,
+            dims: UVec3::new(2, 1, 1),
+            cells: vec![Vec3::X, Vec3::Y],
+        };
+        assert_eq!(field.sample(Vec3::new(-1.0, 1.0, 1.0), 0.0).0, Vec3::X);
+        assert_eq!(field.sample(Vec3::new(1.0, 1.0, 1.0), 0.0).0, Vec3::Y);
+        assert_eq!(field.sample(Vec3::new(3.0, 1.0, 1.0), 0.0).0, Vec3::ZERO);
+    }
+
+    #[test]
+    fn cells_behind_or_beside_the_camera_are_culled() {
+        let projection = PerspectiveProjection::default();
+        let view = Transform::from_xyz(0.0, 0.0, 0.0).looking_to(Vec3::NEG_Z, Vec3::Y);
+        let clip_from_world = projection.get_clip_from_view() * view.compute_matrix().inverse();
+        let cell = |center: Vec3| (center - Vec3::splat(0.5), center + Vec3::splat(0.5));
+
+        let (min, max) = cell(Vec3::new(0.0, 0.0, -10.0));
+        assert!(aabb_in_frustum(clip_from_world, min, max));
+        let (min, max) = cell(Vec3::new(0.0, 0.0, 10.0));
+        assert!(!aabb_in_frustum(clip_from_world, min, max));
+        let (min, max) = cell(Vec3::new(50.0, 0.0, -10.0));
+        assert!(!aabb_in_frustum(clip_from_world, min, max));
+        // Straddling the camera still counts as visible
+        let (min, max) = cell(Vec3::ZERO);
+        assert!(aabb_in_frustum(clip_from_world, min, max));
+    }
+}
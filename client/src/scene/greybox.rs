@@ -6,18 +6,22 @@ use bevy::math::{Affine2, Vec2};
 use bevy::pbr::{MeshMaterial3d, StandardMaterial};
 use bevy::prelude::*;
 
+use levels::ao::STATIC_PLANE_SUBDIVISIONS;
 use levels::subspecs::small_skiff_spec;
-use levels::{builtins::greybox_level, LevelSpec, Vec3f};
+use levels::{LevelSpec, Vec3f};
 
+use crate::campaign::CurrentLevel;
 use crate::scene::submarine::make_swivel_clip;
 
+use super::baked_ao::{asset_if_present, LevelAo, StaticAoMesh, GREYBOX_AO_PATH};
 use super::camera::{CamMode, FollowCam, FollowCamState, FreeFlyState, GameCamera};
 use super::flow_field::{FlowField, Tunnel, TunnelBounds};
-use super::light_bulb::{BlinkingLight, LightBulb};
+use super::light_bulb::{BlinkingLight, LightBulb, SpeedModulated};
 use super::proctex::ProcTexAssets;
 use super::setup::spawn_box;
 use super::submarine::{
-    make_rudder_prism_mesh, AngularVelocity, Rudder, SubPhysics, Submarine, Velocity,
+    make_dive_plane_mesh, make_rudder_prism_mesh, AngularVelocity, DivePlane, Rudder, SubPhysics,
+    SubStateComp, Submarine, Velocity,
 };
 use bevy::render::render_resource::{Face, TextureUsages};
 
@@ -32,14 +36,255 @@ pub struct Chamber;
 #[derive(Component)]
 pub struct DockPad;
 
+/// Root of static level geometry, despawned when the campaign moves to another level.
+#[derive(Component)]
+pub struct LevelGeometry;
+
 pub fn spawn_greybox(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
-    proc_tex: Option<Res<ProcTexAssets>>,
+    level: Res<CurrentLevel>,
     clips: ResMut<Assets<AnimationClip>>,
     graphs: ResMut<Assets<AnimationGraph>>,
+) {
+    // Baked AO for the tunnel/chamber shell (see `levels::ao`), darkening creases via vertex colors
+    if let Some(path) = asset_if_present(GREYBOX_AO_PATH) {
+        commands.insert_resource(LevelAo(asset_server.load(path)));
+    }
+
+    // Spawn a submarine (parent) with child hull and child rudder
+    {
+        // Place near the -X end of the tunnel, centered in YZ
+        let start = sub_start(level.spec());
+
+        let sub_root = commands
+            .spawn((
+                Transform::from_translation(start),
+                GlobalTransform::default(),
+                Visibility::default(),
+                Submarine,
+                Velocity::default(),
+                AngularVelocity::default(),
+                SubPhysics(small_skiff_spec()),
+                crate::hud_instruments::HudInstrumentState::default(),
+                // Initialize persistent physics state; fill is set in simulate on first tick
+                SubStateComp(levels::SubState {
+                    position: levels::Vec3f::new(start.x, start.y, start.z),
+                    velocity: levels::Vec3f::ZERO,
+                    orientation: Quat::IDENTITY,
+                    ang_mom: levels::Vec3f::ZERO,
+                    ballast_fill: Vec::new(),
+                    pump: Default::default(),
+                }),
+                super::submarine::SubInputStateComp(levels::SubInputState::default()),
+                super::submarine::SubStateHistory::default(),
+                Name::new("SubmarineRoot"),
+            ))
+            .id();
+
+        // Hull (prolate spheroid) as child
+        let sub_radius = 0.6;
+        let sub_scale = Vec3::new(2.2, 0.8, 0.8); // prolate along +X
+        let hull_mesh = meshes.add(Mesh::from(Sphere::new(sub_radius)));
+        let hull_material = materials.add(StandardMaterial {
+            base_color: Color::from(Srgba::new(0.75, 0.8, 0.85, 1.0)),
+            perceptual_roughness: 0.4,
+            metallic: 0.1,
+            ..Default::default()
+        });
+        let hull = commands
+            .spawn((
+                Mesh3d(hull_mesh),
+                MeshMaterial3d(hull_material),
+                Transform::from_scale(sub_scale),
+                GlobalTransform::default(),
+                Name::new("SubmarineHull"),
+            ))
+            .id();
+        commands.entity(hull).insert(ChildOf(sub_root));
+
+        // Rudder as child (triangular prism thickness)
+        let rudder_mesh = make_rudder_prism_mesh(1.0, 1.2, 0.12);
+        let rudder_mesh = meshes.add(rudder_mesh);
+        let rudder_material = materials.add(StandardMaterial {
+            base_color: Color::from(Srgba::new(0.9, 0.1, 0.1, 1.0)),
+            cull_mode: Some(Face::Back),
+            ..Default::default()
+        });
+        let rudder_local = Transform::from_translation(Vec3::new(-1.6, 0.0, 0.0));
+        let rudder = commands
+            .spawn((
+                Mesh3d(rudder_mesh),
+                MeshMaterial3d(rudder_material.clone()),
+                rudder_local,
+                GlobalTransform::default(),
+                Rudder,
+                Name::new("Rudder"),
+            ))
+            .id();
+        commands.entity(rudder).insert(ChildOf(sub_root));
+
+        // Dive planes: fore and aft pairs just below the centerline at the hull ends, root at
+        // the hull side. Mesh frame +Z is port, so the starboard plate spans toward -Z.
+        let spec = small_skiff_spec();
+        let beam_half = spec.diameter * 0.5;
+        let (plane_span, plane_chord, plane_taper) = (0.45, 0.4, 0.6);
+        for (station, x) in [
+            (DivePlane::Fore, 0.5 * spec.length),
+            (DivePlane::Aft, -0.5 * spec.length),
+        ] {
+            for (side, z, span) in [
+                ("Port", beam_half, plane_span),
+                ("Starboard", -beam_half, -plane_span),
+            ] {
+                let plane = commands
+                    .spawn((
+                        Mesh3d(meshes.add(make_dive_plane_mesh(span, plane_chord, plane_taper))),
+                        MeshMaterial3d(rudder_material.clone()),
+                        Transform::from_translation(Vec3::new(x, -0.1, z)),
+                        GlobalTransform::default(),
+                        station,
+                        Name::new(format!("Dive Plane {station:?} {side}")),
+                    ))
+                    .id();
+                commands.entity(plane).insert(ChildOf(sub_root));
+            }
+        }
+
+        // Forward floodlight as a child (spotlight)
+        let light_pos = Vec3::new(0.04, 0.5, 0.0);
+        let light_transform = Transform::from_translation(light_pos);
+
+        let floodlight_name = Name::new("Sub Floodlight");
+        let floodlight_entity = commands
+            .spawn((
+                SpotLight {
+                    color: Color::srgb(1.0, 1.0, 1.0),
+                    intensity: 1_200_000_000.0, // brighter for longer throw
+                    range: 100.0,
+                    inner_angle: 0.04,
+                    outer_angle: 0.08,
+                    shadows_enabled: true,
+                    ..Default::default()
+                },
+                floodlight_name.clone(),
+                light_transform,
+            ))
+            .insert(ChildOf(sub_root))
+            .id();
+        make_swivel_clip(
+            &mut commands,
+            floodlight_entity,
+            &floodlight_name,
+            clips,
+            graphs,
+        );
+
+        let tail_point_light_pos = Vec3::new(-1.1, 0.27, 0.0);
+        let tail_root = commands
+            .spawn((
+                Transform::from_translation(tail_point_light_pos),
+                Name::from("Sub Tail-light Root"),
+                ChildOf(sub_root),
+            ))
+            .id();
+        // Replace tail-light with blinking LightBulb
+        let _tail_bulb = commands.spawn((
+            LightBulb {
+                color: Color::srgb(1.0, 0.1, 0.1),
+                strength: 0.0,
+            },
+            // Blinks faster the faster this sub moves
+            BlinkingLight {
+                on_fraction: 0.4,
+                on_intensity: 2.8,
+                off_intensity: 0.0,
+                speed_modulated: Some(SpeedModulated {
+                    base_period: 0.9,
+                    speed_factor: 0.15,
+                }),
+                affects_sub_bulbs_only: true,
+                ..Default::default()
+            },
+            super::light_bulb::LightShadowOverride(false),
+            Transform::IDENTITY,
+            GlobalTransform::default(),
+            Name::from("Sub Tail LightBulb"),
+            ChildOf(tail_root),
+        ));
+
+        // Unified game camera (single camera). Initialize near the bow; mode = FirstPerson
+        let fp_world = start + Vec3::new(1.0, 0.0, 0.0);
+        let fp_t = Transform::from_translation(fp_world).looking_at(fp_world + Vec3::X, Vec3::Y);
+        commands.spawn((
+            Camera3d {
+                depth_texture_usages: (TextureUsages::RENDER_ATTACHMENT
+                    | TextureUsages::TEXTURE_BINDING)
+                    .into(),
+                ..Default::default()
+            },
+            Camera {
+                hdr: true,
+                is_active: true,
+                ..Default::default()
+            },
+            bevy::core_pipeline::bloom::Bloom {
+                intensity: 0.02,
+                low_frequency_boost: 0.7,
+                low_frequency_boost_curvature: 0.95,
+                high_pass_frequency: 1.0,
+                prefilter: BloomPrefilter {
+                    threshold: 0.6,
+                    threshold_softness: 0.2,
+                },
+                composite_mode: bevy::core_pipeline::bloom::BloomCompositeMode::Additive,
+                max_mip_dimension: 512,
+                scale: Vec2::ONE,
+            },
+            bevy::core_pipeline::tonemapping::Tonemapping::TonyMcMapface,
+            bevy::pbr::DistanceFog {
+                color: Color::srgb(0.04, 0.11, 0.12),
+                falloff: bevy::pbr::FogFalloff::Exponential { density: 0.10 },
+                ..Default::default()
+            },
+            // Motion blur samples along the prepass velocity buffer and requires MSAA off
+            Msaa::Off,
+            bevy::core_pipeline::prepass::MotionVectorPrepass,
+            fp_t,
+            GlobalTransform::default(),
+            GameCamera,
+            CamMode::FirstPerson,
+            FollowCam {
+                distance: 8.0,
+                height: 2.0,
+                stiffness: 8.0,
+                lateral_offset: 0.0,
+                vertical_pitch_deg: 0.0,
+            },
+            FollowCamState {
+                last_dir: Vec3::NEG_X,
+            },
+            FreeFlyState {
+                yaw: 0.0,
+                pitch: 0.0,
+                speed: 8.0,
+            },
+            Name::new("Game Camera"),
+        ));
+    }
+}
+
+/// Station room, dock pad, tunnel, chamber and side tunnels for `level`; every root is a
+/// `LevelGeometry`.
+fn spawn_level_geometry(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    asset_server: &AssetServer,
+    proc_tex: Option<&ProcTexAssets>,
+    level: &LevelSpec,
 ) {
     // Convert helpers
     fn v(v: Vec3f) -> Vec3 {
@@ -53,8 +298,6 @@ pub fn spawn_greybox(
     let chamber_color: Color = Color::from(Srgba::new(0.30, 0.32, 0.34, 1.0));
     let dock_emissive: LinearRgba = LinearRgba::from(Srgba::new(0.0, 0.8, 0.9, 1.0));
 
-    // Load level spec from shared crate
-    let level: LevelSpec = greybox_level();
     let room_w = level.room.size.x;
     let room_h = level.room.size.y;
     let room_d = level.room.size.z;
@@ -62,61 +305,63 @@ pub fn spawn_greybox(
 
     // Floor
     let e_floor = spawn_box(
-        &mut commands,
-        &mut meshes,
-        &mut materials,
+        commands,
+        meshes,
+        materials,
         Vec3::new(room_w, wall_thick, room_d),
         Vec3::new(0.0, -wall_thick * 0.5, 0.0),
         floor_color,
     );
-    commands.entity(e_floor).insert(Name::new("Station Floor"));
+    commands
+        .entity(e_floor)
+        .insert((Name::new("Station Floor"), LevelGeometry));
     // Walls
     // +X wall
     let wall_e = spawn_box(
-        &mut commands,
-        &mut meshes,
-        &mut materials,
+        commands,
+        meshes,
+        materials,
         Vec3::new(wall_thick, room_h, room_d),
         Vec3::new(room_w * 0.5, room_h * 0.5 - wall_thick, 0.0),
         wall_color,
     );
-    commands.entity(wall_e).insert(StationRoom);
+    commands.entity(wall_e).insert((StationRoom, LevelGeometry));
     // -X wall
     let e_wall_negx = spawn_box(
-        &mut commands,
-        &mut meshes,
-        &mut materials,
+        commands,
+        meshes,
+        materials,
         Vec3::new(wall_thick, room_h, room_d),
         Vec3::new(-room_w * 0.5, room_h * 0.5 - wall_thick, 0.0),
         wall_color,
     );
     commands
         .entity(e_wall_negx)
-        .insert(Name::new("Station Wall -X"));
+        .insert((Name::new("Station Wall -X"), LevelGeometry));
     // +Z wall
     let e_wall_posz = spawn_box(
-        &mut commands,
-        &mut meshes,
-        &mut materials,
+        commands,
+        meshes,
+        materials,
         Vec3::new(room_w, room_h, wall_thick),
         Vec3::new(0.0, room_h * 0.5 - wall_thick, room_d * 0.5),
         wall_color,
     );
     commands
         .entity(e_wall_posz)
-        .insert(Name::new("Station Wall +Z"));
+        .insert((Name::new("Station Wall +Z"), LevelGeometry));
     // -Z wall
     let e_wall_negz = spawn_box(
-        &mut commands,
-        &mut meshes,
-        &mut materials,
+        commands,
+        meshes,
+        materials,
         Vec3::new(room_w, room_h, wall_thick),
         Vec3::new(0.0, room_h * 0.5 - wall_thick, -room_d * 0.5),
         wall_color,
     );
     commands
         .entity(e_wall_negz)
-        .insert(Name::new("Station Wall -Z"));
+        .insert((Name::new("Station Wall -Z"), LevelGeometry));
 
     // Docking pad in the station
     {
@@ -133,6 +378,7 @@ pub fn spawn_greybox(
             Transform::from_translation(v(level.room.dock_pos)),
             GlobalTransform::default(),
             DockPad,
+            LevelGeometry,
             Name::new("Dock Pad"),
         ));
     }
@@ -144,37 +390,42 @@ pub fn spawn_greybox(
         level.tunnel.size.z,
     );
     let tunnel_pos = Vec3::new(level.tunnel.pos.x, level.tunnel.pos.y, level.tunnel.pos.z);
-    let tunnel_entity = {
+    {
         // Parent holds the field and bounds. Children are the shell meshes.
         let parent = commands
             .spawn((
                 Transform::from_translation(tunnel_pos),
                 GlobalTransform::default(),
                 Tunnel,
+                LevelGeometry,
                 TunnelBounds { size: tunnel_size },
                 Visibility::default(),
                 // Flow field from spec
-                match level.tunnel.flow {
-                    levels::FlowFieldSpec::Uniform { flow, variance } => {
-                        FlowField::uniform(v(flow), variance)
-                    }
+                {
+                    let (flow, variance) = level.tunnel.flow.mean();
+                    FlowField::uniform(v(flow), variance)
                 },
                 Name::new("Tunnel"),
             ))
             .id();
 
-        // Use the provided rock albedo; disable depth_map for now to avoid sampler type mismatch from 16-bit PNG
-        let tex_albedo: Handle<Image> = asset_server.load_with_settings(
-            "textures/rock_face_03_diff_4k.jpg",
-            |settings: &mut ImageLoaderSettings| {
-                settings.sampler = bevy::image::ImageSampler::Descriptor(ImageSamplerDescriptor {
-                    address_mode_u: ImageAddressMode::Repeat,
-                    address_mode_v: ImageAddressMode::Repeat,
-                    address_mode_w: ImageAddressMode::Repeat,
-                    ..default()
-                });
-            },
-        );
+        // Rock albedo: procedural until the photo texture loads (see `ProcTexAssets`); disable
+        // depth_map for now to avoid sampler type mismatch from 16-bit PNG
+        let tex_albedo: Handle<Image> = match proc_tex {
+            Some(p) => p.stone_albedo.clone(),
+            None => asset_server.load_with_settings(
+                super::proctex::ROCK_ALBEDO_PATH,
+                |settings: &mut ImageLoaderSettings| {
+                    settings.sampler =
+                        bevy::image::ImageSampler::Descriptor(ImageSamplerDescriptor {
+                            address_mode_u: ImageAddressMode::Repeat,
+                            address_mode_v: ImageAddressMode::Repeat,
+                            address_mode_w: ImageAddressMode::Repeat,
+                            ..default()
+                        });
+                },
+            ),
+        };
 
         let tex_normal: Handle<Image> = asset_server.load_with_settings(
             "textures/rock_face_03_nor_gl_4k_zip.exr",
@@ -227,13 +478,19 @@ pub fn spawn_greybox(
         // Helper to spawn a single textured plane as a child (avoids cuboid UV issues)
         let mut spawn_plane =
             |size: Vec2, local: Vec3, rot: Quat, name: &str, mat: Handle<StandardMaterial>| {
-                let mesh = meshes.add(Plane3d::default().mesh().size(size.x, size.y));
+                let mesh = meshes.add(
+                    Plane3d::default()
+                        .mesh()
+                        .size(size.x, size.y)
+                        .subdivisions(STATIC_PLANE_SUBDIVISIONS),
+                );
                 let child = commands
                     .spawn((
                         Mesh3d(mesh),
                         MeshMaterial3d(mat),
                         Transform::from_translation(local).with_rotation(rot),
                         GlobalTransform::default(),
+                        StaticAoMesh,
                         Name::new(name.to_string()),
                     ))
                     .id();
@@ -298,6 +555,7 @@ pub fn spawn_greybox(
                             on_fraction: 0.35,
                             on_intensity: 3.8,
                             off_intensity: 0.0,
+                            ..Default::default()
                         },
                         Transform::from_translation(pos),
                         GlobalTransform::default(),
@@ -325,8 +583,7 @@ pub fn spawn_greybox(
                 ))
                 .insert(ChildOf(parent));
         }
-        parent
-    };
+    }
 
     // Mining chamber as a hollow shell with an open entrance toward the tunnel (remove -X wall)
     let chamber_size = Vec3::new(
@@ -346,6 +603,7 @@ pub fn spawn_greybox(
                 GlobalTransform::default(),
                 Visibility::default(),
                 Chamber,
+                LevelGeometry,
                 Name::new("Chamber"),
             ))
             .id();
@@ -376,13 +634,19 @@ pub fn spawn_greybox(
         let half = chamber_size * 0.5;
         // Helper to spawn a plane as a child of the chamber
         let mut spawn_plane = |size: Vec2, local: Vec3, rot: Quat, name: &str| {
-            let mesh = meshes.add(Plane3d::default().mesh().size(size.x, size.y));
+            let mesh = meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(size.x, size.y)
+                    .subdivisions(STATIC_PLANE_SUBDIVISIONS),
+            );
             let child = commands
                 .spawn((
                     Mesh3d(mesh),
                     MeshMaterial3d(chamber_mat.clone()),
                     Transform::from_translation(local).with_rotation(rot),
                     GlobalTransform::default(),
+                    StaticAoMesh,
                     Name::new(name.to_string()),
                 ))
                 .id();
@@ -427,188 +691,120 @@ pub fn spawn_greybox(
         // Intentionally omit -X wall to create an open entrance from the tunnel
     }
 
-    // Spawn a submarine (parent) with child hull and child rudder
-    {
-        // Place near the -X end of the tunnel, centered in YZ
-        let tb = TunnelBounds { size: tunnel_size };
-        let start = tunnel_pos + Vec3::new(-tb.size.x * 0.5 + 6.0, 0.0, 0.0);
-
-        let sub_root = commands
-            .spawn((
-                Transform::from_translation(start),
-                GlobalTransform::default(),
-                Visibility::default(),
-                Submarine,
-                Velocity::default(),
-                AngularVelocity::default(),
-                SubPhysics(small_skiff_spec()),
-                crate::hud_instruments::HudInstrumentState::default(),
-                // Initialize persistent physics state; fill is set in simulate on first tick
-                super::submarine::SubStateComp(levels::SubState {
-                    position: levels::Vec3f::new(start.x, start.y, start.z),
-                    velocity: levels::Vec3f::ZERO,
-                    orientation: Quat::IDENTITY,
-                    ang_mom: levels::Vec3f::ZERO,
-                    ballast_fill: Vec::new(),
-                }),
-                super::submarine::SubInputStateComp(levels::SubInputState::default()),
-                Name::new("SubmarineRoot"),
-            ))
-            .id();
-
-        // Hull (prolate spheroid) as child
-        let sub_radius = 0.6;
-        let sub_scale = Vec3::new(2.2, 0.8, 0.8); // prolate along +X
-        let hull_mesh = meshes.add(Mesh::from(Sphere::new(sub_radius)));
-        let hull_material = materials.add(StandardMaterial {
-            base_color: Color::from(Srgba::new(0.75, 0.8, 0.85, 1.0)),
-            perceptual_roughness: 0.4,
-            metallic: 0.1,
+    // Side tunnels: plain shells open toward the main tunnel, capped at the dead end. The main
+    // tunnel's side walls are not cut, so branch mouths read as doorways drawn over them.
+    if !level.side_tunnels.is_empty() {
+        let branch_mat = materials.add(StandardMaterial {
+            base_color: chamber_color,
+            perceptual_roughness: 0.95,
+            metallic: 0.02,
+            cull_mode: None,
+            double_sided: true,
             ..Default::default()
         });
-        let hull = commands
-            .spawn((
-                Mesh3d(hull_mesh),
-                MeshMaterial3d(hull_material),
-                Transform::from_scale(sub_scale),
-                GlobalTransform::default(),
-                Name::new("SubmarineHull"),
-            ))
-            .id();
-        commands.entity(hull).insert(ChildOf(sub_root));
-
-        // Rudder as child (triangular prism thickness)
-        let rudder_mesh = make_rudder_prism_mesh(1.0, 1.2, 0.12);
-        let rudder_mesh = meshes.add(rudder_mesh);
-        let rudder_material = materials.add(StandardMaterial {
-            base_color: Color::from(Srgba::new(0.9, 0.1, 0.1, 1.0)),
-            cull_mode: Some(Face::Back),
-            ..Default::default()
-        });
-        let rudder_local = Transform::from_translation(Vec3::new(-1.6, 0.0, 0.0));
-        let rudder = commands
-            .spawn((
-                Mesh3d(rudder_mesh),
-                MeshMaterial3d(rudder_material),
-                rudder_local,
-                GlobalTransform::default(),
-                Rudder,
-                Name::new("Rudder"),
-            ))
-            .id();
-        commands.entity(rudder).insert(ChildOf(sub_root));
-
-        // Forward floodlight as a child (spotlight)
-        let light_pos = Vec3::new(0.04, 0.5, 0.0);
-        let light_transform = Transform::from_translation(light_pos);
-
-        let floodlight_name = Name::new("Sub Floodlight");
-        let floodlight_entity = commands
-            .spawn((
-                SpotLight {
-                    color: Color::srgb(1.0, 1.0, 1.0),
-                    intensity: 1_200_000_000.0, // brighter for longer throw
-                    range: 100.0,
-                    inner_angle: 0.04,
-                    outer_angle: 0.08,
-                    shadows_enabled: true,
-                    ..Default::default()
-                },
-                floodlight_name.clone(),
-                light_transform,
-            ))
-            .insert(ChildOf(sub_root))
-            .id();
-        make_swivel_clip(
-            &mut commands,
-            floodlight_entity,
-            &floodlight_name,
-            clips,
-            graphs,
-        );
-
-        let tail_point_light_pos = Vec3::new(-1.1, 0.27, 0.0);
-        let tail_root = commands
-            .spawn((
-                Transform::from_translation(tail_point_light_pos),
-                Name::from("Sub Tail-light Root"),
-                ChildOf(sub_root),
-            ))
-            .id();
-        // Replace tail-light with blinking LightBulb
-        let _tail_bulb = commands.spawn((
-            LightBulb {
-                color: Color::srgb(1.0, 0.1, 0.1),
-                strength: 0.0,
-            },
-            BlinkingLight {
-                period: 0.9,
-                on_fraction: 0.4,
-                on_intensity: 2.8,
-                off_intensity: 0.0,
-            },
-            super::light_bulb::LightShadowOverride(false),
-            Transform::IDENTITY,
-            GlobalTransform::default(),
-            Name::from("Sub Tail LightBulb"),
-            ChildOf(tail_root),
-        ));
+        for (i, branch) in level.side_tunnels.iter().enumerate() {
+            let size = v(branch.size);
+            let half = size * 0.5;
+            let dead_end = (branch.pos.z - level.tunnel.pos.z).signum() * half.z;
+            let parent = commands
+                .spawn((
+                    Transform::from_translation(v(branch.pos)),
+                    GlobalTransform::default(),
+                    Visibility::default(),
+                    LevelGeometry,
+                    Name::new(format!("Side Tunnel {i}")),
+                ))
+                .id();
+            let faces = [
+                (
+                    Vec2::new(size.x, size.z),
+                    Vec3::new(0.0, -half.y, 0.0),
+                    Quat::IDENTITY,
+                ),
+                (
+                    Vec2::new(size.x, size.z),
+                    Vec3::new(0.0, half.y, 0.0),
+                    Quat::from_rotation_x(std::f32::consts::PI),
+                ),
+                (
+                    Vec2::new(size.y, size.z),
+                    Vec3::new(half.x, 0.0, 0.0),
+                    Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+                ),
+                (
+                    Vec2::new(size.y, size.z),
+                    Vec3::new(-half.x, 0.0, 0.0),
+                    Quat::from_rotation_z(-std::f32::consts::FRAC_PI_2),
+                ),
+                (
+                    Vec2::new(size.x, size.y),
+                    Vec3::new(0.0, 0.0, dead_end),
+                    Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+                ),
+            ];
+            for (face_size, local, rot) in faces {
+                commands.spawn((
+                    Mesh3d(meshes.add(Plane3d::default().mesh().size(face_size.x, face_size.y))),
+                    MeshMaterial3d(branch_mat.clone()),
+                    Transform::from_translation(local).with_rotation(rot),
+                    ChildOf(parent),
+                ));
+            }
+        }
+    }
+}
 
-        // Unified game camera (single camera). Initialize near the bow; mode = FirstPerson
-        let fp_world = start + Vec3::new(1.0, 0.0, 0.0);
-        let fp_t = Transform::from_translation(fp_world).looking_at(fp_world + Vec3::X, Vec3::Y);
-        commands.spawn((
-            Camera3d {
-                depth_texture_usages: (TextureUsages::RENDER_ATTACHMENT
-                    | TextureUsages::TEXTURE_BINDING)
-                    .into(),
-                ..Default::default()
-            },
-            Camera {
-                hdr: true,
-                is_active: true,
-                ..Default::default()
-            },
-            bevy::core_pipeline::bloom::Bloom {
-                intensity: 0.02,
-                low_frequency_boost: 0.7,
-                low_frequency_boost_curvature: 0.95,
-                high_pass_frequency: 1.0,
-                prefilter: BloomPrefilter {
-                    threshold: 0.6,
-                    threshold_softness: 0.2,
-                },
-                composite_mode: bevy::core_pipeline::bloom::BloomCompositeMode::Additive,
-                max_mip_dimension: 512,
-                scale: Vec2::ONE,
-            },
-            bevy::core_pipeline::tonemapping::Tonemapping::TonyMcMapface,
-            bevy::pbr::DistanceFog {
-                color: Color::srgb(0.04, 0.11, 0.12),
-                falloff: bevy::pbr::FogFalloff::Exponential { density: 0.10 },
-                ..Default::default()
-            },
-            Msaa::Off,
-            fp_t,
-            GlobalTransform::default(),
-            GameCamera,
-            CamMode::FirstPerson,
-            FollowCam {
-                distance: 8.0,
-                height: 2.0,
-                stiffness: 8.0,
-            },
-            FollowCamState {
-                last_dir: Vec3::NEG_X,
-            },
-            FreeFlyState {
-                yaw: 0.0,
-                pitch: 0.0,
-                speed: 8.0,
-            },
-            Name::new("Game Camera"),
-        ));
+/// Local sub start: near the -X end of the tunnel, centered in YZ.
+fn sub_start(level: &LevelSpec) -> Vec3 {
+    let t = &level.tunnel;
+    Vec3::new(t.pos.x - t.size.x * 0.5 + 6.0, t.pos.y, t.pos.z)
+}
 
-        let _ = tunnel_entity; // ensure it exists (unused var otherwise)
+/// Build the level geometry on the first frame and rebuild it whenever the server loads another
+/// campaign level; on a reload the local sub also restarts at the new tunnel entrance.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn respawn_level_geometry(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    proc_tex: Option<Res<ProcTexAssets>>,
+    level: Res<CurrentLevel>,
+    q_geometry: Query<Entity, With<LevelGeometry>>,
+    mut q_sub: Query<
+        (
+            &mut Transform,
+            &mut SubStateComp,
+            &mut Velocity,
+            &mut AngularVelocity,
+        ),
+        With<Submarine>,
+    >,
+) {
+    for entity in &q_geometry {
+        commands.entity(entity).despawn();
+    }
+    spawn_level_geometry(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &asset_server,
+        proc_tex.as_deref(),
+        level.spec(),
+    );
+    if level.is_added() {
+        return;
+    }
+    // The shipped bake only matches the greybox meshes
+    commands.remove_resource::<LevelAo>();
+    let start = sub_start(level.spec());
+    for (mut transform, mut state, mut vel, mut ang_vel) in &mut q_sub {
+        *transform = Transform::from_translation(start);
+        state.0.position = Vec3f::new(start.x, start.y, start.z);
+        state.0.velocity = Vec3f::ZERO;
+        state.0.orientation = Quat::IDENTITY;
+        state.0.ang_mom = Vec3f::ZERO;
+        vel.0 = Vec3::ZERO;
+        ang_vel.0 = Vec3::ZERO;
     }
 }
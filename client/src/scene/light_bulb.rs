@@ -2,6 +2,8 @@ use bevy::math::primitives::Sphere;
 use bevy::pbr::{MeshMaterial3d, NotShadowCaster, StandardMaterial};
 use bevy::prelude::*;
 
+use super::submarine::{Submarine, Velocity};
+
 #[derive(Resource, Default)]
 struct LightBulbAssets {
     sphere_mesh: Handle<Mesh>,
@@ -35,6 +37,7 @@ impl Plugin for LightBulbPlugin {
         app.init_resource::<LightBulbAssets>()
             .register_type::<LightBulb>()
             .register_type::<BlinkingLight>()
+            .register_type::<SpeedModulated>()
             .register_type::<LightShadowOverride>()
             .add_systems(Startup, setup_assets)
             .add_systems(
@@ -57,8 +60,18 @@ fn setup_assets(mut meshes: ResMut<Assets<Mesh>>, mut assets: ResMut<LightBulbAs
 #[reflect(Component)]
 pub struct LightShadowOverride(pub bool);
 
+/// Shortest blink period speed modulation may reach; faster reads as strobing.
+pub const MIN_BLINK_PERIOD_S: f32 = 0.05;
+
+/// Blink timing. By default a light blinks with a fixed `period`. With `speed_modulated` set,
+/// it blinks faster as the nearest submarine moves faster, and ignores `period`. The
+/// effective period is `base_period / (1 + speed * speed_factor)`, clamped to at least
+/// `MIN_BLINK_PERIOD_S`. With `affects_sub_bulbs_only`, only a light mounted on a submarine
+/// is modulated, and only by that sub's own speed. Any other light keeps `base_period`, so a
+/// tunnel warning light never picks up the pace of a passing sub.
 #[derive(Component, Reflect, Clone, Copy)]
 #[reflect(Component)]
+#[require(BlinkPhase)]
 pub struct BlinkingLight {
     /// total period in seconds (e.g. 1.0 = 1 Hz)
     pub period: f32,
@@ -68,6 +81,11 @@ pub struct BlinkingLight {
     pub on_intensity: f32,
     /// LightBulb.strength when OFF (usually 0.0)
     pub off_intensity: f32,
+    /// Speed-driven timing replacing `period`, if any
+    pub speed_modulated: Option<SpeedModulated>,
+    /// Only modulate by the speed of the submarine this light is mounted on; unmounted lights
+    /// keep `base_period`
+    pub affects_sub_bulbs_only: bool,
 }
 
 impl Default for BlinkingLight {
@@ -77,15 +95,72 @@ impl Default for BlinkingLight {
             on_fraction: 0.2,
             on_intensity: 1.0,
             off_intensity: 0.0,
+            speed_modulated: None,
+            affects_sub_bulbs_only: false,
         }
     }
 }
 
-fn tick_blinking_lights(time: Res<Time>, mut q: Query<(&BlinkingLight, &mut LightBulb)>) {
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct SpeedModulated {
+    /// Period in seconds at rest
+    pub base_period: f32,
+    /// How strongly each m/s of speed shortens the period
+    pub speed_factor: f32,
+}
+
+impl SpeedModulated {
+    pub fn effective_period(&self, speed: f32) -> f32 {
+        let speedup = 1.0 + speed.max(0.0) * self.speed_factor.max(0.0);
+        (self.base_period / speedup).max(MIN_BLINK_PERIOD_S)
+    }
+}
+
+/// Accumulated blink phase (0..1) of a speed-modulated light, so a changing period speeds the
+/// blink up instead of jumping to another point in the cycle.
+#[derive(Component, Default, Clone, Copy)]
+struct BlinkPhase(f32);
+
+#[allow(clippy::type_complexity)]
+fn tick_blinking_lights(
+    time: Res<Time>,
+    mut q: Query<(
+        Entity,
+        &BlinkingLight,
+        &mut BlinkPhase,
+        &mut LightBulb,
+        &GlobalTransform,
+    )>,
+    subs: Query<(Entity, &GlobalTransform, &Velocity), With<Submarine>>,
+    parents: Query<&ChildOf>,
+) {
     let t = time.elapsed_secs();
-    for (blink, mut bulb) in &mut q {
-        let period = blink.period.max(1e-3);
-        let phase = (t % period) / period; // 0..1
+    for (entity, blink, mut phase, mut bulb, xf) in &mut q {
+        let phase = match blink.speed_modulated {
+            Some(modulated) => {
+                let speed = if blink.affects_sub_bulbs_only {
+                    parents
+                        .iter_ancestors(entity)
+                        .find_map(|ancestor| subs.get(ancestor).ok())
+                        .map_or(0.0, |(_, _, vel)| vel.length())
+                } else {
+                    subs.iter()
+                        .min_by(|a, b| {
+                            let da = a.1.translation().distance_squared(xf.translation());
+                            let db = b.1.translation().distance_squared(xf.translation());
+                            da.total_cmp(&db)
+                        })
+                        .map_or(0.0, |(_, _, vel)| vel.length())
+                };
+                let period = modulated.effective_period(speed);
+                phase.0 = (phase.0 + time.delta_secs() / period).fract();
+                phase.0
+            }
+            None => {
+                let period = blink.period.max(1e-3);
+                (t % period) / period // 0..1
+            }
+        };
         let on_frac = blink.on_fraction.clamp(0.0, 1.0);
         let target = if phase < on_frac {
             blink.on_intensity
@@ -187,3 +262,23 @@ fn update_bulb_properties(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speed_shortens_the_period_down_to_the_floor() {
+        let tail = SpeedModulated {
+            base_period: 0.9,
+            speed_factor: 0.15,
+        };
+        assert_eq!(tail.effective_period(0.0), 0.9);
+        // 0.9 / (1 + 10 * 0.15)
+        assert!((tail.effective_period(10.0) - 0.36).abs() < 1e-6);
+        assert!(tail.effective_period(10.0) < tail.effective_period(5.0));
+        assert_eq!(tail.effective_period(1e4), MIN_BLINK_PERIOD_S);
+        // Reversing is still speed, never a longer period
+        assert_eq!(tail.effective_period(-3.0), 0.9);
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use levels::HoloIcon;
+
+use crate::campaign::CurrentLevel;
+use crate::notifications::NotificationLog;
+
+use super::submarine::Submarine;
+
+/// Side length of a holo marker quad.
+const HOLO_SIZE_M: f32 = 3.0;
+
+/// Glowing slab whose text is logged once each time the local sub enters its trigger sphere.
+#[derive(Component, Debug)]
+pub struct LorePlaque {
+    pub text: String,
+    pub trigger_radius_m: f32,
+    inside: bool,
+}
+
+/// Icon quad turned to face the active camera every frame.
+#[derive(Component, Debug)]
+pub struct HoloMarker;
+
+pub fn holo_icon_path(icon: HoloIcon) -> &'static str {
+    match icon {
+        HoloIcon::Warning => "textures/holo/warning.png",
+        HoloIcon::Objective => "textures/holo/objective.png",
+        HoloIcon::Poi => "textures/holo/poi.png",
+    }
+}
+
+pub struct LorePlugin;
+
+impl Plugin for LorePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_lore.run_if(resource_changed::<CurrentLevel>),
+                trigger_lore_plaques,
+                face_holo_markers,
+            ),
+        );
+    }
+}
+
+/// Replace all plaques and holo markers with the current level's.
+#[allow(clippy::type_complexity)]
+fn spawn_lore(
+    mut commands: Commands,
+    level: Res<CurrentLevel>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_old: Query<Entity, Or<(With<LorePlaque>, With<HoloMarker>)>>,
+) {
+    for entity in &q_old {
+        commands.entity(entity).despawn();
+    }
+    let level = level.spec();
+    if !level.lore_plaques.is_empty() {
+        let slab = meshes.add(Cuboid::new(2.0, 1.2, 0.2));
+        let material = materials.add(StandardMaterial {
+            base_color: Color::srgb(0.15, 0.3, 0.35),
+            emissive: LinearRgba::rgb(0.4, 1.6, 1.8),
+            perceptual_roughness: 0.6,
+            ..Default::default()
+        });
+        for (i, plaque) in level.lore_plaques.iter().enumerate() {
+            let p = plaque.position;
+            commands.spawn((
+                Mesh3d(slab.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(p.x, p.y, p.z),
+                LorePlaque {
+                    text: plaque.text.clone(),
+                    trigger_radius_m: plaque.trigger_radius_m,
+                    inside: false,
+                },
+                Name::new(format!("Lore Plaque {i}")),
+            ));
+        }
+    }
+    if !level.holo_markers.is_empty() {
+        let quad = meshes.add(Rectangle::new(HOLO_SIZE_M, HOLO_SIZE_M));
+        for (i, marker) in level.holo_markers.iter().enumerate() {
+            let [r, g, b] = marker.color;
+            let p = marker.position;
+            commands.spawn((
+                Mesh3d(quad.clone()),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: Color::linear_rgb(r, g, b),
+                    base_color_texture: Some(asset_server.load(holo_icon_path(marker.icon))),
+                    emissive: LinearRgba::rgb(r, g, b),
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    double_sided: true,
+                    cull_mode: None,
+                    ..Default::default()
+                })),
+                Transform::from_xyz(p.x, p.y, p.z),
+                HoloMarker,
+                Name::new(format!("Holo Marker {i}")),
+            ));
+        }
+    }
+}
+
+fn trigger_lore_plaques(
+    mut log: ResMut<NotificationLog>,
+    q_sub: Query<&Transform, With<Submarine>>,
+    mut q_plaques: Query<(&Transform, &mut LorePlaque), Without<Submarine>>,
+) {
+    let Some(sub) = q_sub.iter().next() else {
+        return;
+    };
+    for (transform, mut plaque) in &mut q_plaques {
+        let inside = transform.translation.distance(sub.translation) <= plaque.trigger_radius_m;
+        if inside && !plaque.inside {
+            log.push(plaque.text.clone());
+        }
+        plaque.inside = inside;
+    }
+}
+
+fn face_holo_markers(
+    q_cameras: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    mut q_markers: Query<&mut Transform, With<HoloMarker>>,
+) {
+    let Some((_, camera)) = q_cameras.iter().find(|(c, _)| c.is_active) else {
+        return;
+    };
+    // Match the camera's rotation so the quad's +Z face points back at it
+    let rotation = camera.compute_transform().rotation;
+    for mut transform in &mut q_markers {
+        transform.rotation = rotation;
+    }
+}
@@ -1,15 +1,25 @@
 use bevy::prelude::*;
 
+use crate::campaign::CurrentLevel;
+use crate::net::NetSet;
+
+pub mod baked_ao;
 pub mod camera;
+pub mod crash_dump;
 pub mod flow_field;
 pub mod greybox;
 pub mod light_bulb;
+pub mod lore;
+pub mod obstacles;
 pub mod ore;
 pub mod postprocess;
 pub mod proctex;
+pub mod remote_players;
 pub mod render;
 pub mod setup;
 pub mod submarine;
+pub mod thermal_vent;
+pub mod torpedo;
 pub mod water;
 
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
@@ -22,8 +32,11 @@ impl Plugin for ScenePlugin {
         use submarine::{ClientPhysicsTiming, SubTelemetry};
 
         app.register_type::<flow_field::FlowField>()
+            .register_type::<levels::SubStepDebug>()
+            .register_type::<SubTelemetry>()
             .init_resource::<SubTelemetry>()
             .init_resource::<ClientPhysicsTiming>()
+            .init_resource::<crash_dump::PhysicsCrashDump>()
             .add_plugins(proctex::ProcTexPlugin)
             .add_plugins(light_bulb::LightBulbPlugin)
             .add_systems(Startup, (setup::setup_scene, greybox::spawn_greybox))
@@ -31,13 +44,19 @@ impl Plugin for ScenePlugin {
                 Update,
                 (
                     camera::switch_cameras_keys,
+                    camera::follow_cam_side_key,
                     camera::free_fly_camera,
                     flow_field::draw_flow_gizmos,
-                    submarine::update_sub_input_state,
+                    submarine::ramp_inputs.before(SimSet),
                     submarine::simulate_submarine.in_set(SimSet),
-                    submarine::apply_server_corrections,
+                    submarine::sync_telemetry_to_input.after(SimSet),
+                    submarine::apply_server_corrections
+                        .after(NetSet)
+                        .before(SimSet),
                     camera::update_game_camera.after(SimSet),
                     submarine::animate_rudder,
+                    submarine::animate_dive_planes,
+                    greybox::respawn_level_geometry.run_if(resource_changed::<CurrentLevel>),
                 ),
             );
 
@@ -45,6 +64,17 @@ impl Plugin for ScenePlugin {
         app.add_plugins(render::volumetric_floodlights::VolumetricFloodlightsPlugin);
         app.add_plugins(water::WaterFxPlugin);
         app.add_plugins(postprocess::WaterPostProcessPlugin);
+        app.add_plugins(render::velocity_buffer::VelocityBufferPlugin);
+        app.add_plugins(postprocess::MotionBlurPlugin);
+        app.add_plugins(postprocess::LensDistortionPlugin);
+        app.add_plugins(baked_ao::BakedAoPlugin);
         app.add_plugins(ore::OrePlugin);
+        app.add_plugins(obstacles::ObstaclePlugin);
+        app.add_plugins(thermal_vent::ThermalVentPlugin);
+        app.add_plugins(remote_players::RemotePlayersPlugin);
+        app.add_plugins(torpedo::TorpedoPlugin);
+        app.add_plugins(lore::LorePlugin);
+        #[cfg(debug_assertions)]
+        app.add_plugins(submarine::SubSpecHotReloadPlugin);
     }
 }
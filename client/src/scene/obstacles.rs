@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+
+use crate::campaign::CurrentLevel;
+use crate::net::{LatestStateDelta, NetSet};
+
+/// Box for one of the level's moving obstacles. The server owns the motion; each snapshot
+/// retargets the box, which then glides from where it was to the new position over roughly one
+/// snapshot interval.
+#[derive(Component, Debug)]
+pub struct MovingObstacle {
+    /// `MovingObstacleSpec::id`, matched against `NetObstacle::id`.
+    pub id: u32,
+    from: Vec3,
+    to: Vec3,
+    elapsed_s: f32,
+}
+
+impl MovingObstacle {
+    fn new(id: u32, position: Vec3) -> Self {
+        Self {
+            id,
+            from: position,
+            to: position,
+            elapsed_s: 0.0,
+        }
+    }
+
+    /// Start gliding from `current` to a freshly received snapshot position.
+    fn retarget(&mut self, current: Vec3, to: Vec3) {
+        self.from = current;
+        self.to = to;
+        self.elapsed_s = 0.0;
+    }
+
+    /// Position `dt` seconds further along the glide, which lasts `interval_s`.
+    fn advance(&mut self, dt: f32, interval_s: f32) -> Vec3 {
+        self.elapsed_s += dt;
+        let t = if interval_s > 0.0 {
+            (self.elapsed_s / interval_s).min(1.0)
+        } else {
+            1.0
+        };
+        self.from.lerp(self.to, t)
+    }
+}
+
+/// Smoothed time between snapshots, used as the glide duration.
+#[derive(Resource, Debug)]
+struct ObstacleSnapshotInterval {
+    interval_s: f32,
+    last_snapshot_s: Option<f32>,
+}
+
+impl Default for ObstacleSnapshotInterval {
+    fn default() -> Self {
+        Self {
+            // Server default snapshot rate until real arrivals are measured
+            interval_s: 1.0 / 20.0,
+            last_snapshot_s: None,
+        }
+    }
+}
+
+pub struct ObstaclePlugin;
+
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ObstacleSnapshotInterval>().add_systems(
+            Update,
+            (
+                respawn_level_obstacles.run_if(resource_changed::<CurrentLevel>),
+                retarget_obstacles.after(NetSet),
+                glide_obstacles,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Replace all obstacle boxes with the current level's, parked at their first waypoint.
+fn respawn_level_obstacles(
+    mut commands: Commands,
+    level: Res<CurrentLevel>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_old: Query<Entity, With<MovingObstacle>>,
+) {
+    for entity in &q_old {
+        commands.entity(entity).despawn();
+    }
+    let obstacles = &level.spec().moving_obstacles;
+    if obstacles.is_empty() {
+        return;
+    }
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.45, 0.32, 0.18),
+        emissive: LinearRgba::rgb(0.6, 0.25, 0.05),
+        perceptual_roughness: 0.8,
+        ..Default::default()
+    });
+    for spec in obstacles {
+        let start = spec.waypoints.first().copied().unwrap_or_default();
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::from_size(spec.mesh_size))),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(start),
+            MovingObstacle::new(spec.id, start),
+            Name::new(format!("Moving Obstacle {}", spec.id)),
+        ));
+    }
+}
+
+fn retarget_obstacles(
+    time: Res<Time>,
+    latest: Res<LatestStateDelta>,
+    mut interval: ResMut<ObstacleSnapshotInterval>,
+    mut q: Query<(&mut MovingObstacle, &Transform)>,
+) {
+    if !latest.is_changed() {
+        return;
+    }
+    let Some(delta) = latest.0.as_ref() else {
+        return;
+    };
+    let now = time.elapsed_secs();
+    if let Some(last) = interval.last_snapshot_s.replace(now) {
+        interval.interval_s += 0.2 * ((now - last) - interval.interval_s);
+    }
+    for (mut obstacle, transform) in &mut q {
+        if let Some(net) = delta.obstacles.iter().find(|o| o.id == obstacle.id) {
+            obstacle.retarget(transform.translation, Vec3::from_array(net.position));
+        }
+    }
+}
+
+fn glide_obstacles(
+    time: Res<Time>,
+    interval: Res<ObstacleSnapshotInterval>,
+    mut q: Query<(&mut MovingObstacle, &mut Transform)>,
+) {
+    let dt = time.delta_secs();
+    for (mut obstacle, mut transform) in &mut q {
+        transform.translation = obstacle.advance(dt, interval.interval_s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glide_reaches_snapshot_after_one_interval() {
+        let mut obstacle = MovingObstacle::new(0, Vec3::ZERO);
+        obstacle.retarget(Vec3::ZERO, Vec3::new(2.0, 0.0, 0.0));
+        assert_eq!(obstacle.advance(0.025, 0.05), Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(obstacle.advance(0.05, 0.05), Vec3::new(2.0, 0.0, 0.0));
+
+        // A late snapshot starts the next glide from wherever the box is now
+        obstacle.retarget(Vec3::new(2.0, 0.0, 0.0), Vec3::new(2.0, 4.0, 0.0));
+        assert_eq!(obstacle.advance(0.0, 0.05), Vec3::new(2.0, 0.0, 0.0));
+        assert_eq!(obstacle.advance(0.05, 0.05), Vec3::new(2.0, 4.0, 0.0));
+    }
+}
@@ -1,53 +1,187 @@
 use bevy::math::primitives::{Cuboid, Sphere};
 use bevy::prelude::*;
-use levels::builtins::greybox_level;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use levels::mesh::{procedural_rock, RockMesh};
+use levels::ResourceType;
+use std::collections::HashMap;
 
+use crate::campaign::CurrentLevel;
+
+/// Root of a client-side ore node; `node_id` indexes `LevelSpec::ore_nodes`.
+#[derive(Component)]
+pub struct OreNode {
+    pub node_id: u32,
+}
+
+/// Scale an ore node shrinks to before it is despawned, and grows back from on respawn.
+const ORE_MIN_SCALE: f32 = 0.1;
+
+/// Mined-out ore node shrinking and dimming to nothing; despawned when `elapsed >= duration`.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FadeOutAnim {
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl Default for FadeOutAnim {
+    fn default() -> Self {
+        Self {
+            elapsed: 0.0,
+            duration: 2.0,
+        }
+    }
+}
+
+impl FadeOutAnim {
+    /// Emissive multiplier, 1 -> 0.
+    fn glow(&self) -> f32 {
+        1.0 - (self.elapsed / self.duration).clamp(0.0, 1.0)
+    }
+
+    fn scale(&self) -> f32 {
+        ORE_MIN_SCALE + (1.0 - ORE_MIN_SCALE) * self.glow()
+    }
+}
+
+/// Respawned ore node growing back to full size and glow.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SpawnInAnim {
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl Default for SpawnInAnim {
+    fn default() -> Self {
+        Self {
+            elapsed: 0.0,
+            duration: 1.5,
+        }
+    }
+}
+
+impl SpawnInAnim {
+    /// Emissive multiplier, 0 -> 1.
+    fn glow(&self) -> f32 {
+        (self.elapsed / self.duration).clamp(0.0, 1.0)
+    }
+
+    fn scale(&self) -> f32 {
+        ORE_MIN_SCALE + (1.0 - ORE_MIN_SCALE) * self.glow()
+    }
+}
+
+/// Respawns announced by `EntitySpawn`, keyed by node id. Held until the node's fade-out (if
+/// any) has finished, then spawned with the stored animation.
+#[derive(Resource, Debug, Default)]
+pub struct PendingOreSpawns(pub HashMap<u32, SpawnInAnim>);
+
+/// Host rock around an ore node; excluded from the emissive pulse.
 #[derive(Component)]
-pub struct OreNode;
+struct OreRock;
 
 #[derive(Component)]
 struct OrePulse {
     phase: f32,
     amp: f32,
+    /// Core emissive at full glow, from `ore_glow`.
+    glow: LinearRgba,
+}
+
+/// Core emissive for a node of `resource_type`: gold ore, blue crystal, violet rare mineral.
+pub fn ore_glow(resource_type: ResourceType) -> LinearRgba {
+    match resource_type {
+        ResourceType::Ore => LinearRgba::rgb(25.0, 18.0, 4.0),
+        ResourceType::Crystal => LinearRgba::rgb(4.0, 14.0, 25.0),
+        ResourceType::RareMineral => LinearRgba::rgb(20.0, 4.0, 25.0),
+    }
 }
 
 pub struct OrePlugin;
 
 impl Plugin for OrePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_demo_ore)
-            .add_systems(Update, pulse_ore_emissive);
+        app.add_systems(
+            Update,
+            (
+                respawn_level_ore.run_if(resource_changed::<CurrentLevel>),
+                tick_ore_anims,
+                pulse_ore_emissive,
+            )
+                .chain(),
+        );
     }
 }
 
-fn spawn_demo_ore(
+/// Replace all ore nodes with the current level's, at full size.
+fn respawn_level_ore(
     mut commands: Commands,
+    level: Res<CurrentLevel>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    q_nodes: Query<Entity, With<OreNode>>,
 ) {
-    let level = greybox_level();
-    // Place ore somewhere visible in the chamber, off-center a bit
-    let p = level.chamber.pos;
-    let pos = Vec3::new(p.x + 6.0, p.y - 17.0, p.z + 5.0);
+    for entity in &q_nodes {
+        commands.entity(entity).despawn();
+    }
+    for (node_id, ore) in level.spec().ore_nodes.iter().enumerate() {
+        spawn_ore_node(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            node_id as u32,
+            ore.position,
+            ore_glow(ore.primary_resource()),
+        );
+    }
+}
+
+fn spawn_ore_node(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    node_id: u32,
+    pos: Vec3,
+    glow: LinearRgba,
+) -> Entity {
     let root = commands
         .spawn((
             Transform::from_translation(pos),
             GlobalTransform::default(),
             Visibility::default(),
-            OreNode,
+            OreNode { node_id },
             OrePulse {
                 phase: 0.0,
                 amp: 1.0,
+                glow,
             },
             Name::new("Ore Node"),
         ))
         .id();
 
+    // Host rock the crystals grow out of
+    let rock_mesh = meshes.add(rock_to_mesh(&procedural_rock(17, 2)));
+    let rock_mat = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.22, 0.2, 0.19),
+        perceptual_roughness: 0.95,
+        ..Default::default()
+    });
+    commands.spawn((
+        Mesh3d(rock_mesh),
+        MeshMaterial3d(rock_mat),
+        Transform::from_translation(Vec3::new(0.0, -0.35, 0.0))
+            .with_scale(Vec3::new(0.9, 0.6, 0.8)),
+        GlobalTransform::default(),
+        OreRock,
+        Name::new("Ore Rock"),
+        ChildOf(root),
+    ));
+
     // Core glow sphere
     let core_mesh = meshes.add(Mesh::from(Sphere::new(0.3)));
     let core_mat = materials.add(StandardMaterial {
         base_color: Color::srgb(1.0, 0.9, 0.7),
-        emissive: LinearRgba::rgb(25.0, 18.0, 4.0),
+        emissive: glow,
         metallic: 0.9,
         perceptual_roughness: 0.35,
         ..Default::default()
@@ -74,7 +208,7 @@ fn spawn_demo_ore(
         let shard_t = Transform::from_translation(off).with_rotation(rot);
         let mat = materials.add(StandardMaterial {
             base_color: Color::srgb(1.0, 0.95, 0.85),
-            emissive: LinearRgba::rgb(15.0, 11.0, 3.0),
+            emissive: glow * 0.6,
             metallic: 1.0,
             perceptual_roughness: 0.28,
             reflectance: 0.9,
@@ -105,23 +239,109 @@ fn spawn_demo_ore(
         ))
         .id();
     let _ = bulb;
+    root
+}
+
+/// Advance fade-out/spawn-in scaling, despawn faded nodes, and spawn pending respawns once
+/// their node is gone. Glow is applied by `pulse_ore_emissive`.
+#[allow(clippy::type_complexity)]
+fn tick_ore_anims(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut pending: ResMut<PendingOreSpawns>,
+    level: Res<CurrentLevel>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q_nodes: Query<(
+        Entity,
+        &OreNode,
+        &mut Transform,
+        Option<&mut FadeOutAnim>,
+        Option<&mut SpawnInAnim>,
+    )>,
+) {
+    let dt = time.delta_secs();
+    let mut fading = Vec::new();
+    for (entity, node, mut transform, fade, spawn) in &mut q_nodes {
+        if let Some(mut fade) = fade {
+            fade.elapsed += dt;
+            if fade.elapsed >= fade.duration {
+                commands.entity(entity).despawn();
+            } else {
+                transform.scale = Vec3::splat(fade.scale());
+                fading.push(node.node_id);
+            }
+        } else if let Some(mut spawn) = spawn {
+            spawn.elapsed += dt;
+            transform.scale = Vec3::splat(spawn.scale());
+            if spawn.elapsed >= spawn.duration {
+                commands.entity(entity).remove::<SpawnInAnim>();
+            }
+        } else {
+            // Already present and idle (e.g. a duplicate EntitySpawn): nothing to play
+            pending.0.remove(&node.node_id);
+        }
+    }
+
+    if pending.0.is_empty() {
+        return;
+    }
+    let level = level.spec();
+    pending.0.retain(|&node_id, anim| {
+        if fading.contains(&node_id) {
+            return true;
+        }
+        let Some(ore) = level.ore_nodes.get(node_id as usize) else {
+            return false;
+        };
+        let root = spawn_ore_node(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            node_id,
+            ore.position,
+            ore_glow(ore.primary_resource()),
+        );
+        commands.entity(root).insert((
+            *anim,
+            Transform::from_translation(ore.position).with_scale(Vec3::splat(anim.scale())),
+        ));
+        false
+    });
 }
 
+#[allow(clippy::type_complexity)]
 fn pulse_ore_emissive(
     time: Res<Time>,
-    q_roots: Query<(&OrePulse, &Children), With<OreNode>>,
-    mut q_mat: Query<&mut MeshMaterial3d<StandardMaterial>>,
+    q_roots: Query<
+        (
+            &OrePulse,
+            &Children,
+            Option<&FadeOutAnim>,
+            Option<&SpawnInAnim>,
+        ),
+        With<OreNode>,
+    >,
+    mut q_mat: Query<&mut MeshMaterial3d<StandardMaterial>, Without<OreRock>>,
     mut mats: ResMut<Assets<StandardMaterial>>,
     mut q_lights: Query<&mut PointLight>,
 ) {
     let t = time.elapsed_secs();
-    for (pulse, children) in &q_roots {
+    for (pulse, children, fade, spawn) in &q_roots {
+        let glow = fade
+            .map(FadeOutAnim::glow)
+            .or(spawn.map(SpawnInAnim::glow))
+            .unwrap_or(1.0);
         // Compute a gentle pulse
-        let s = 0.75 + 0.25 * (t * 1.3 + pulse.phase).sin() * pulse.amp.max(0.0);
+        let s = (0.75 + 0.25 * (t * 1.3 + pulse.phase).sin() * pulse.amp.max(0.0)) * glow;
         for c in children.iter() {
             if let Ok(mh) = q_mat.get_mut(c) {
                 if let Some(m) = mats.get_mut(&mh.0) {
-                    m.emissive = LinearRgba::rgb(25.0 * s, 18.0 * s, 4.0 * s);
+                    m.emissive = LinearRgba::rgb(
+                        pulse.glow.red * s,
+                        pulse.glow.green * s,
+                        pulse.glow.blue * s,
+                    );
                 }
             } else if let Ok(mut pl) = q_lights.get_mut(c) {
                 pl.intensity = 20_000.0 * s * s;
@@ -129,3 +349,30 @@ fn pulse_ore_emissive(
         }
     }
 }
+
+pub fn rock_to_mesh(rock: &RockMesh) -> Mesh {
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, rock.positions.clone())
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, rock.normals.clone())
+    .with_inserted_indices(Indices::U32(rock.indices.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fade_out_and_spawn_in_meet_at_min_scale() {
+        let mut fade = FadeOutAnim::default();
+        let mut spawn = SpawnInAnim::default();
+        assert_eq!((fade.scale(), fade.glow()), (1.0, 1.0));
+        assert_eq!((spawn.scale(), spawn.glow()), (ORE_MIN_SCALE, 0.0));
+        fade.elapsed = fade.duration;
+        spawn.elapsed = spawn.duration;
+        assert_eq!((fade.scale(), fade.glow()), (ORE_MIN_SCALE, 0.0));
+        assert_eq!((spawn.scale(), spawn.glow()), (1.0, 1.0));
+    }
+}
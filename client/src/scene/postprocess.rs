@@ -5,13 +5,13 @@ use bevy::render::render_graph::{
     NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNodeRunner
 };
 use bevy::render::render_resource::binding_types::{
-    sampler, texture_2d, texture_depth_2d, uniform_buffer,
+    sampler, texture_2d, uniform_buffer,
 };
 use bevy::render::render_resource::*;
 use bevy::render::renderer::{RenderContext, RenderDevice};
 use bevy::render::view::{ViewDepthTexture, ViewTarget, ViewUniformOffset, ViewUniforms};
 // no Extract import needed
-use bevy::asset::{Handle, LoadState};
+use bevy::asset::Handle;
 use bevy::ecs::query::QueryItem;
 use bevy::prelude::Shader;
 use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
@@ -21,6 +21,7 @@ use bevy::render::RenderSet;
 
 use bevy::core_pipeline::core_3d::graph::{Core3d, Node3d};
 use bevy::core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state;
+use bevy::core_pipeline::prepass::ViewPrepassTextures;
 
 use crate::scene::render::volumetric_floodlights::FloodlightPassLabel;
 
@@ -36,7 +37,8 @@ pub struct WaterPostProcessPlugin;
 impl BevyPlugin for WaterPostProcessPlugin {
     fn build(&self, app: &mut App) {
         // Extract debug toggles into the render world
-        app.add_plugins(ExtractResourcePlugin::<RenderVisToggles>::default());
+        app.add_plugins(ExtractResourcePlugin::<RenderVisToggles>::default())
+            .add_systems(Update, water_depth_adapter);
 
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
@@ -72,7 +74,14 @@ impl BevyPlugin for WaterPostProcessPlugin {
 pub struct RenderVisToggles {
     pub water_post: bool,
     pub strength: f32,
+    pub depth_scale: f32,
     pub debug: bool,
+    pub motion_blur: bool,
+    pub motion_blur_strength: f32,
+    pub velocity_buffer: bool,
+    pub lens_distortion: bool,
+    pub lens_distort_k1: f32,
+    pub lens_distort_k2: f32,
 }
 
 impl ExtractResource for RenderVisToggles {
@@ -81,11 +90,63 @@ impl ExtractResource for RenderVisToggles {
         Self {
             water_post: source.water_post,
             strength: source.water_post_strength.max(0.0),
+            depth_scale: source.water_post_depth_scale.clamp(0.0, 1.0),
             debug: source.water_post_debug,
+            motion_blur: source.motion_blur_enabled,
+            motion_blur_strength: source.motion_blur_strength.clamp(0.0, 1.0),
+            velocity_buffer: source.velocity_buffer_enabled,
+            lens_distortion: source.lens_distortion_enabled,
+            lens_distort_k1: source.lens_distort_k1,
+            lens_distort_k2: source.lens_distort_k2,
         }
     }
 }
 
+// Depth band (camera y, metres) over which the water effect fades in when diving.
+const WATER_FULL_STRENGTH_Y: f32 = -5.0;
+const WATER_FADE_OUT_Y: f32 = -2.0;
+// Time constant for fading out when resurfacing; diving applies the new scale immediately.
+const WATER_RESURFACE_TAU_S: f32 = 2.0;
+
+/// Target `water_post_depth_scale` for a camera at `camera_y`: 1 below -5 m, 0 above -2 m.
+fn water_depth_scale(camera_y: f32) -> f32 {
+    let t = ((camera_y - WATER_FULL_STRENGTH_Y) / (WATER_FADE_OUT_Y - WATER_FULL_STRENGTH_Y))
+        .clamp(0.0, 1.0);
+    1.0 - t * t * (3.0 - 2.0 * t)
+}
+
+/// Step `current` toward `target`, easing down with `WATER_RESURFACE_TAU_S` so surfacing
+/// doesn't pop the effect off.
+fn blend_water_depth_scale(current: f32, target: f32, dt: f32) -> f32 {
+    if target >= current {
+        return target;
+    }
+    target + (current - target) * (-dt / WATER_RESURFACE_TAU_S).exp()
+}
+
+fn water_depth_adapter(
+    time: Res<Time>,
+    mut settings: ResMut<crate::render_settings::RenderSettings>,
+    q_cam: Query<(&GlobalTransform, &Camera), With<Camera3d>>,
+) {
+    if !settings.depth_adaptive_water {
+        if settings.water_post_depth_scale != 1.0 {
+            settings.water_post_depth_scale = 1.0;
+        }
+        return;
+    }
+    let Some((cam_t, _)) = q_cam.iter().find(|(_, cam)| cam.is_active) else {
+        return;
+    };
+    let target = water_depth_scale(cam_t.translation().y);
+    let scale =
+        blend_water_depth_scale(settings.water_post_depth_scale, target, time.delta_secs());
+    // Only write on change so RenderSettings change detection stays quiet at depth
+    if scale != settings.water_post_depth_scale {
+        settings.water_post_depth_scale = scale;
+    }
+}
+
 #[derive(Resource)]
 pub struct WaterPostPipeline {
     resources: Option<WaterPostPipelineResources>,
@@ -107,7 +168,7 @@ impl FromWorld for WaterPostPipeline {
             .load(POSTPROCESS_SHADER_PATH);
         Self {
             resources: None,
-            shader: shader,
+            shader,
         }
     }
 }
@@ -322,7 +383,7 @@ impl bevy::render::render_graph::ViewNode for WaterPostNode {
                 CachedPipelineState::Queued => {
                     tracing::debug!("post: pipeline queued");
                 }
-                CachedPipelineState::Creating(status) => {
+                CachedPipelineState::Creating(_) => {
                     tracing::debug!("water_post: pipeline not ready, skipping frame");
                 }
                 CachedPipelineState::Err(e) => {
@@ -391,7 +452,7 @@ impl bevy::render::render_graph::ViewNode for WaterPostNode {
 
         // Create or update params bind group
         let params_data = [
-            toggles.strength,
+            toggles.strength * toggles.depth_scale,
             if toggles.debug { 1.0 } else { 0.0 },
             0.0,
             0.0,
@@ -439,3 +500,507 @@ impl bevy::render::render_graph::ViewNode for WaterPostNode {
         Ok(())
     }
 }
+
+// Motion blur: smears each pixel along its screen-space velocity, taken from the camera's
+// motion-vector prepass (current minus previous frame clip position). Runs after the water
+// post so the wobble gets blurred too. Depends on `WaterPostProcessPlugin` for the extracted
+// toggles and its graph node.
+
+const MOTION_BLUR_SHADER_PATH: &str = "shaders/motion_blur.wgsl";
+
+#[derive(Debug, Clone, Copy, RenderLabel, Hash, PartialEq, Eq)]
+pub struct MotionBlurRenderLabel;
+pub struct MotionBlurPlugin;
+
+impl BevyPlugin for MotionBlurPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<SpecializedRenderPipelines<MotionBlurPipeline>>()
+            .add_systems(
+                Render,
+                prepare_motion_blur_pipelines.in_set(RenderSet::Prepare),
+            )
+            .add_render_graph_node::<ViewNodeRunner<MotionBlurNode>>(Core3d, MotionBlurRenderLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    WaterPostRenderLabel,
+                    MotionBlurRenderLabel,
+                    Node3d::EndMainPass,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<MotionBlurPipeline>();
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct MotionBlurPipeline {
+    color_bind_group_layout: BindGroupLayout,
+    velocity_bind_group_layout: BindGroupLayout,
+    params_bind_group_layout: BindGroupLayout,
+    sampler: Sampler,
+    shader: Handle<Shader>,
+}
+
+impl FromWorld for MotionBlurPipeline {
+    fn from_world(render_world: &mut World) -> Self {
+        let device = render_world.resource::<RenderDevice>();
+        let color_bind_group_layout = device.create_bind_group_layout(
+            "motion_blur_color_bgl",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                ),
+            ),
+        );
+        let velocity_bind_group_layout = device.create_bind_group_layout(
+            "motion_blur_velocity_bgl",
+            &BindGroupLayoutEntries::single(
+                ShaderStages::FRAGMENT,
+                texture_2d(TextureSampleType::Float { filterable: true }),
+            ),
+        );
+        let params_bind_group_layout = device.create_bind_group_layout(
+            "motion_blur_params_bgl",
+            &BindGroupLayoutEntries::single(
+                ShaderStages::FRAGMENT,
+                uniform_buffer::<[f32; 4]>(false),
+            ),
+        );
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("motion_blur_sampler"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+        let shader = render_world
+            .resource::<AssetServer>()
+            .load(MOTION_BLUR_SHADER_PATH);
+        Self {
+            color_bind_group_layout,
+            velocity_bind_group_layout,
+            params_bind_group_layout,
+            sampler,
+            shader,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct CameraMotionBlurPipeline {
+    pub pipeline_id: CachedRenderPipelineId,
+}
+
+impl SpecializedRenderPipeline for MotionBlurPipeline {
+    // Same key as the water post: only the target format varies
+    type Key = WaterPostPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("motion_blur".into()),
+            layout: vec![
+                self.color_bind_group_layout.clone(),
+                self.velocity_bind_group_layout.clone(),
+                self.params_bind_group_layout.clone(),
+            ],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: key.format,
+                    blend: Some(BlendState::REPLACE),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+pub fn prepare_motion_blur_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<MotionBlurPipeline>>,
+    pipe: Res<MotionBlurPipeline>,
+    toggles: Option<Res<RenderVisToggles>>,
+    views: Query<
+        (Entity, &bevy::render::view::ExtractedView, Option<&Msaa>),
+        Without<CameraMotionBlurPipeline>,
+    >,
+) {
+    if !toggles.is_some_and(|t| t.motion_blur) {
+        return;
+    }
+    for (entity, view, msaa) in &views {
+        // The velocity texture is single-sampled; blurring a multisampled target would need a
+        // resolve first and smear across the MSAA edges anyway.
+        assert!(
+            msaa.is_none_or(|m| *m == Msaa::Off),
+            "motion blur is incompatible with MSAA; set Msaa::Off on the camera"
+        );
+        let fmt = if view.hdr {
+            ViewTarget::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
+        };
+        let id = pipelines.specialize(
+            &pipeline_cache,
+            &pipe,
+            WaterPostPipelineKey {
+                format: fmt,
+                hdr: view.hdr,
+            },
+        );
+        commands
+            .entity(entity)
+            .insert(CameraMotionBlurPipeline { pipeline_id: id });
+    }
+}
+
+#[derive(Default)]
+pub struct MotionBlurNode;
+
+impl bevy::render::render_graph::ViewNode for MotionBlurNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        Option<&'static ViewPrepassTextures>,
+        &'static CameraMotionBlurPipeline,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (target, prepass, pipeline): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let Some(toggles) = world.get_resource::<RenderVisToggles>() else {
+            return Ok(());
+        };
+        if !toggles.motion_blur || toggles.motion_blur_strength <= 0.0 {
+            return Ok(());
+        }
+        // Cameras without a MotionVectorPrepass have no velocity buffer to blur along
+        let Some(velocity) = prepass.and_then(|p| p.motion_vectors_view()) else {
+            return Ok(());
+        };
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(render_pipeline) = pipeline_cache.get_render_pipeline(pipeline.pipeline_id) else {
+            tracing::debug!("motion_blur: pipeline not ready, skipping frame");
+            return Ok(());
+        };
+        let blur_pipe = world.resource::<MotionBlurPipeline>();
+
+        let pp = target.post_process_write();
+        let device = render_context.render_device();
+        let color_bg = device.create_bind_group(
+            Some("motion_blur_color_bg"),
+            &blur_pipe.color_bind_group_layout,
+            &BindGroupEntries::sequential((pp.source, &blur_pipe.sampler)),
+        );
+        let velocity_bg = device.create_bind_group(
+            Some("motion_blur_velocity_bg"),
+            &blur_pipe.velocity_bind_group_layout,
+            &BindGroupEntries::single(velocity),
+        );
+        let params_data = [toggles.motion_blur_strength, 0.0, 0.0, 0.0];
+        let params_buffer = device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("motion_blur_params"),
+            contents: bytemuck::cast_slice(&params_data),
+            usage: BufferUsages::UNIFORM,
+        });
+        let params_bg = device.create_bind_group(
+            Some("motion_blur_params_bg"),
+            &blur_pipe.params_bind_group_layout,
+            &BindGroupEntries::single(params_buffer.as_entire_binding()),
+        );
+
+        let mut pass = render_context
+            .command_encoder()
+            .begin_render_pass(&RenderPassDescriptor {
+                label: Some("motion_blur_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: pp.destination,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+        pass.set_pipeline(render_pipeline);
+        pass.set_bind_group(0, &color_bg, &[]);
+        pass.set_bind_group(1, &velocity_bg, &[]);
+        pass.set_bind_group(2, &params_bg, &[]);
+        pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
+
+// Lens distortion: Brown-Conrady barrel warp of the camera port. Runs on the lit scene before
+// the volumetric floodlights are composited, so the beams stay straight. Depends on
+// `WaterPostProcessPlugin` for the extracted toggles and on `VolumetricFloodlightsPlugin` for
+// its graph node.
+
+const LENS_DISTORTION_SHADER_PATH: &str = "shaders/lens_distortion.wgsl";
+
+#[derive(Debug, Clone, Copy, RenderLabel, Hash, PartialEq, Eq)]
+pub struct LensDistortionRenderLabel;
+pub struct LensDistortionPlugin;
+
+impl BevyPlugin for LensDistortionPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<SpecializedRenderPipelines<LensDistortionPipeline>>()
+            .add_systems(
+                Render,
+                prepare_lens_distortion_pipelines.in_set(RenderSet::Prepare),
+            )
+            .add_render_graph_node::<ViewNodeRunner<LensDistortionNode>>(
+                Core3d,
+                LensDistortionRenderLabel,
+            )
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::MainTransparentPass,
+                    LensDistortionRenderLabel,
+                    FloodlightPassLabel,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<LensDistortionPipeline>();
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct LensDistortionPipeline {
+    color_bind_group_layout: BindGroupLayout,
+    params_bind_group_layout: BindGroupLayout,
+    sampler: Sampler,
+    shader: Handle<Shader>,
+}
+
+impl FromWorld for LensDistortionPipeline {
+    fn from_world(render_world: &mut World) -> Self {
+        let device = render_world.resource::<RenderDevice>();
+        let color_bind_group_layout = device.create_bind_group_layout(
+            "lens_distortion_color_bgl",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                ),
+            ),
+        );
+        let params_bind_group_layout = device.create_bind_group_layout(
+            "lens_distortion_params_bgl",
+            &BindGroupLayoutEntries::single(
+                ShaderStages::FRAGMENT,
+                uniform_buffer::<[f32; 4]>(false),
+            ),
+        );
+        // Distorted corners sample outside [0, 1]; repeat the border rather than going black
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("lens_distortion_sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+        let shader = render_world
+            .resource::<AssetServer>()
+            .load(LENS_DISTORTION_SHADER_PATH);
+        Self {
+            color_bind_group_layout,
+            params_bind_group_layout,
+            sampler,
+            shader,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct CameraLensDistortionPipeline {
+    pub pipeline_id: CachedRenderPipelineId,
+}
+
+impl SpecializedRenderPipeline for LensDistortionPipeline {
+    type Key = WaterPostPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("lens_distortion".into()),
+            layout: vec![
+                self.color_bind_group_layout.clone(),
+                self.params_bind_group_layout.clone(),
+            ],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: key.format,
+                    blend: Some(BlendState::REPLACE),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+pub fn prepare_lens_distortion_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<LensDistortionPipeline>>,
+    pipe: Res<LensDistortionPipeline>,
+    toggles: Option<Res<RenderVisToggles>>,
+    views: Query<
+        (Entity, &bevy::render::view::ExtractedView),
+        Without<CameraLensDistortionPipeline>,
+    >,
+) {
+    if !toggles.is_some_and(|t| t.lens_distortion) {
+        return;
+    }
+    for (entity, view) in &views {
+        let fmt = if view.hdr {
+            ViewTarget::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
+        };
+        let id = pipelines.specialize(
+            &pipeline_cache,
+            &pipe,
+            WaterPostPipelineKey {
+                format: fmt,
+                hdr: view.hdr,
+            },
+        );
+        commands
+            .entity(entity)
+            .insert(CameraLensDistortionPipeline { pipeline_id: id });
+    }
+}
+
+#[derive(Default)]
+pub struct LensDistortionNode;
+
+impl bevy::render::render_graph::ViewNode for LensDistortionNode {
+    type ViewQuery = (&'static ViewTarget, &'static CameraLensDistortionPipeline);
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (target, pipeline): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let Some(toggles) = world.get_resource::<RenderVisToggles>() else {
+            return Ok(());
+        };
+        if !toggles.lens_distortion
+            || (toggles.lens_distort_k1 == 0.0 && toggles.lens_distort_k2 == 0.0)
+        {
+            return Ok(());
+        }
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(render_pipeline) = pipeline_cache.get_render_pipeline(pipeline.pipeline_id) else {
+            tracing::debug!("lens_distortion: pipeline not ready, skipping frame");
+            return Ok(());
+        };
+        let lens_pipe = world.resource::<LensDistortionPipeline>();
+
+        let pp = target.post_process_write();
+        let device = render_context.render_device();
+        let color_bg = device.create_bind_group(
+            Some("lens_distortion_color_bg"),
+            &lens_pipe.color_bind_group_layout,
+            &BindGroupEntries::sequential((pp.source, &lens_pipe.sampler)),
+        );
+        let params_data = [toggles.lens_distort_k1, toggles.lens_distort_k2, 0.0, 0.0];
+        let params_buffer = device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("lens_distortion_params"),
+            contents: bytemuck::cast_slice(&params_data),
+            usage: BufferUsages::UNIFORM,
+        });
+        let params_bg = device.create_bind_group(
+            Some("lens_distortion_params_bg"),
+            &lens_pipe.params_bind_group_layout,
+            &BindGroupEntries::single(params_buffer.as_entire_binding()),
+        );
+
+        let mut pass = render_context
+            .command_encoder()
+            .begin_render_pass(&RenderPassDescriptor {
+                label: Some("lens_distortion_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: pp.destination,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+        pass.set_pipeline(render_pipeline);
+        pass.set_bind_group(0, &color_bg, &[]);
+        pass.set_bind_group(1, &params_bg, &[]);
+        pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn water_depth_scale_ramps_between_surface_and_depth() {
+        assert_eq!(water_depth_scale(-20.0), 1.0);
+        assert_eq!(water_depth_scale(-5.0), 1.0);
+        assert_eq!(water_depth_scale(-2.0), 0.0);
+        assert_eq!(water_depth_scale(1.0), 0.0);
+        assert!((water_depth_scale(-3.5) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn resurfacing_fades_slowly_but_diving_is_immediate() {
+        assert_eq!(blend_water_depth_scale(0.0, 1.0, 0.016), 1.0);
+        let after_tau = blend_water_depth_scale(1.0, 0.0, WATER_RESURFACE_TAU_S);
+        assert!((after_tau - (-1.0f32).exp()).abs() < 1e-6);
+    }
+}
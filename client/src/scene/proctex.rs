@@ -1,32 +1,99 @@
-use bevy::image::{ImageAddressMode, ImageFilterMode, ImageSampler, ImageSamplerDescriptor};
+use bevy::asset::LoadState;
+use bevy::image::{
+    ImageAddressMode, ImageFilterMode, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor,
+};
 use bevy::prelude::*;
 use bevy::render::render_asset::RenderAssetUsages;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
 
+use super::baked_ao::asset_if_present;
+
+/// Photo rock albedo that replaces the procedural one once it loads.
+pub const ROCK_ALBEDO_PATH: &str = "textures/rock_face_03_diff_4k.jpg";
+
 #[derive(Resource, Default)]
 pub struct ProcTexAssets {
+    /// Rock albedo for the tunnel and chamber: procedural at startup, then the contents of
+    /// `ROCK_ALBEDO_PATH` once that loads. The handle itself never changes, so materials built
+    /// before the load pick up the photo texture too.
     pub stone_albedo: Handle<Image>,
 }
 
+/// The `ROCK_ALBEDO_PATH` load still in flight.
+#[derive(Resource)]
+struct PendingRockAlbedo(Handle<Image>);
+
 pub struct ProcTexPlugin;
 
 impl Plugin for ProcTexPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ProcTexAssets>()
-            .add_systems(Startup, generate_stone_texture);
+            .add_systems(Startup, generate_stone_texture)
+            .add_systems(
+                Update,
+                adopt_rock_albedo_file.run_if(resource_exists::<PendingRockAlbedo>),
+            );
+    }
+}
+
+fn generate_stone_texture(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut out: ResMut<ProcTexAssets>,
+    asset_server: Option<Res<AssetServer>>,
+) {
+    out.stone_albedo = images.add(generate_rock_albedo(512, 0x00C0_FFEE));
+    // Without the file (CI, trimmed demo checkouts) the procedural texture simply stays
+    let Some((server, path)) = asset_server.zip(asset_if_present(ROCK_ALBEDO_PATH)) else {
+        return;
+    };
+    let file = server.load_with_settings(path, |settings: &mut ImageLoaderSettings| {
+        settings.sampler = repeat_sampler();
+    });
+    commands.insert_resource(PendingRockAlbedo(file));
+}
+
+fn adopt_rock_albedo_file(
+    mut commands: Commands,
+    pending: Res<PendingRockAlbedo>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    out: Res<ProcTexAssets>,
+) {
+    match asset_server.load_state(&pending.0) {
+        LoadState::Loaded => {
+            if let Some(image) = images.get(&pending.0).cloned() {
+                images.insert(&out.stone_albedo, image);
+            }
+        }
+        LoadState::Failed(err) => {
+            tracing::debug!(?err, "Rock albedo unavailable, keeping procedural")
+        }
+        _ => return,
     }
+    commands.remove_resource::<PendingRockAlbedo>();
+}
+
+fn repeat_sampler() -> ImageSampler {
+    ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        address_mode_w: ImageAddressMode::Repeat,
+        mag_filter: ImageFilterMode::Linear,
+        min_filter: ImageFilterMode::Linear,
+        mipmap_filter: ImageFilterMode::Linear,
+        ..Default::default()
+    })
 }
 
-fn generate_stone_texture(mut images: ResMut<Assets<Image>>, mut out: ResMut<ProcTexAssets>) {
-    // Small, tileable grayscale noise composed into an sRGB RGBA image
-    let w: u32 = 512;
-    let h: u32 = 512;
-    let seed: u32 = 0x00C0_FFEE;
-    let data = make_improved_rock_rgba(w as usize, h as usize, seed);
+/// Tileable `size`×`size` sRGB rock albedo built from 4-octave value noise; the same `seed`
+/// always gives the same pixels.
+pub fn generate_rock_albedo(size: u32, seed: u32) -> Image {
+    let data = make_improved_rock_rgba(size as usize, size as usize, seed);
     let mut image = Image::new(
         Extent3d {
-            width: w,
-            height: h,
+            width: size,
+            height: size,
             depth_or_array_layers: 1,
         },
         TextureDimension::D2,
@@ -35,16 +102,8 @@ fn generate_stone_texture(mut images: ResMut<Assets<Image>>, mut out: ResMut<Pro
         RenderAssetUsages::RENDER_WORLD,
     );
     image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST;
-    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
-        address_mode_u: ImageAddressMode::Repeat,
-        address_mode_v: ImageAddressMode::Repeat,
-        address_mode_w: ImageAddressMode::Repeat,
-        mag_filter: ImageFilterMode::Linear,
-        min_filter: ImageFilterMode::Linear,
-        mipmap_filter: ImageFilterMode::Linear,
-        ..Default::default()
-    });
-    out.stone_albedo = images.add(image);
+    image.sampler = repeat_sampler();
+    image
 }
 
 // ---------------------- noise helpers ----------------------
@@ -73,39 +132,25 @@ fn hash2(ix: i32, iy: i32, seed: u32) -> u32 {
     h
 }
 
-fn grad(ix: i32, iy: i32, seed: u32) -> (f32, f32) {
-    let h = hash2(ix, iy, seed);
-    // Map to angle [0, 2pi)
-    let a = (h as f32 / u32::MAX as f32) * std::f32::consts::TAU;
-    (a.cos(), a.sin())
+fn lattice_value(ix: i32, iy: i32, seed: u32) -> f32 {
+    // Map the hash to [-1, 1]
+    hash2(ix, iy, seed) as f32 / u32::MAX as f32 * 2.0 - 1.0
 }
 
-fn perlin2_periodic(x: f32, y: f32, period_x: i32, period_y: i32, seed: u32) -> f32 {
-    // Periodic gradient noise over integer lattice with wrapping periods
+fn value2_periodic(x: f32, y: f32, period_x: i32, period_y: i32, seed: u32) -> f32 {
+    // Periodic value noise over integer lattice with wrapping periods
     let xi = x.floor() as i32;
     let yi = y.floor() as i32;
-    let xf = x - xi as f32;
-    let yf = y - yi as f32;
-    let u = fade(xf);
-    let v = fade(yf);
+    let u = fade(x - xi as f32);
+    let v = fade(y - yi as f32);
 
     let x0 = xi.rem_euclid(period_x);
     let y0 = yi.rem_euclid(period_y);
     let x1 = (xi + 1).rem_euclid(period_x);
     let y1 = (yi + 1).rem_euclid(period_y);
 
-    let (gx00, gy00) = grad(x0, y0, seed);
-    let (gx10, gy10) = grad(x1, y0, seed);
-    let (gx01, gy01) = grad(x0, y1, seed);
-    let (gx11, gy11) = grad(x1, y1, seed);
-
-    let n00 = gx00 * xf + gy00 * yf;
-    let n10 = gx10 * (xf - 1.0) + gy10 * yf;
-    let n01 = gx01 * xf + gy01 * (yf - 1.0);
-    let n11 = gx11 * (xf - 1.0) + gy11 * (yf - 1.0);
-
-    let nx0 = lerp(n00, n10, u);
-    let nx1 = lerp(n01, n11, u);
+    let nx0 = lerp(lattice_value(x0, y0, seed), lattice_value(x1, y0, seed), u);
+    let nx1 = lerp(lattice_value(x0, y1, seed), lattice_value(x1, y1, seed), u);
     lerp(nx0, nx1, v)
 }
 
@@ -115,7 +160,7 @@ fn fbm2_tileable(x: f32, y: f32, base_period: i32, octaves: i32, seed: u32) -> f
     let mut freq = 1.0;
     for o in 0..octaves {
         let p = (base_period as f32 / freq).round().max(1.0) as i32;
-        let n = perlin2_periodic(
+        let n = value2_periodic(
             x * freq,
             y * freq,
             p,
@@ -160,7 +205,7 @@ fn make_improved_rock_rgba(w: usize, h: usize, seed: u32) -> Vec<u8> {
             let ny = ny0 + wy * warp_amp;
 
             // Ridge/turbulence base
-            let base = fbm2_tileable(nx * 2.0, ny * 2.0, base_period, 5, seed ^ 0x9E37_79B9);
+            let base = fbm2_tileable(nx * 2.0, ny * 2.0, base_period, 4, seed ^ 0x9E37_79B9);
             let ridge = (base.abs()).powf(0.75);
 
             // Veins: periodic sin stripes with warped phase
@@ -199,7 +244,7 @@ fn make_improved_rock_rgba(w: usize, h: usize, seed: u32) -> Vec<u8> {
             let b = lum * (warm.2 * (1.0 - tint_t) + cool.2 * tint_t);
 
             // Minor speckle for grain
-            let speck = perlin2_periodic(
+            let speck = value2_periodic(
                 nx * 12.0,
                 ny * 12.0,
                 base_period,
@@ -220,3 +265,24 @@ fn make_improved_rock_rgba(w: usize, h: usize, seed: u32) -> Vec<u8> {
     }
     data
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rock_albedo_has_requested_size_and_finite_pixels() {
+        let image = generate_rock_albedo(512, 42);
+        assert_eq!(image.size(), UVec2::splat(512));
+        let data = image.data.as_ref().expect("pixel data");
+        assert_eq!(data.len(), 512 * 512 * 4);
+        for y in (0..512).step_by(37) {
+            for x in (0..512).step_by(41) {
+                let color = image.get_color_at(x, y).unwrap().to_linear();
+                assert!(color.to_f32_array().iter().all(|c| c.is_finite()));
+            }
+        }
+        // Not a flat fill
+        assert!(data.chunks(4).any(|p| p[0] != data[0]));
+    }
+}
@@ -0,0 +1,341 @@
+use bevy::prelude::*;
+use protocol::NetPlayer;
+use uuid::Uuid;
+
+use crate::debug_vis::LabelNode;
+use crate::labels::{LabelFont, TracksEntity};
+use crate::net::{LatestStateDelta, Leaderboard, MyPlayerId, NetSet};
+use crate::scene::submarine::ClientPhysicsTiming;
+
+/// Stand-in hull for another player's sub, placed at its latest server snapshot.
+#[derive(Component)]
+pub struct RemotePlayer {
+    pub id: Uuid,
+    pub team_id: u8,
+    material: Handle<StandardMaterial>,
+}
+
+/// Palette for team-colored hulls, name tags and the leaderboard; wraps for larger team ids.
+pub fn team_color(team_id: u8) -> Color {
+    const PALETTE: [Color; 4] = [
+        Color::srgb(0.25, 0.6, 1.0),
+        Color::srgb(1.0, 0.35, 0.25),
+        Color::srgb(0.35, 0.9, 0.4),
+        Color::srgb(0.95, 0.8, 0.2),
+    ];
+    PALETTE[team_id as usize % PALETTE.len()]
+}
+
+/// How far past its newest snapshot a remote is extrapolated before it freezes in place.
+pub const REMOTE_EXTRAPOLATION_LIMIT_S: f32 = 0.2;
+
+/// A remote's two newest snapshots and the clock `interpolate_remote_players` renders them
+/// by. Playback runs on the server tick timeline, a snapshot interval behind the newest sample.
+#[derive(Component, Debug, Clone)]
+pub struct RemoteSnapshots {
+    /// Oldest first.
+    samples: [Option<(u64, NetPlayer)>; 2],
+    /// Seconds since server tick 0.
+    playback_s: f64,
+}
+
+impl RemoteSnapshots {
+    pub fn new(tick: u64, player: NetPlayer, tick_dt: f32) -> Self {
+        Self {
+            samples: [None, Some((tick, player))],
+            playback_s: tick as f64 * tick_dt as f64,
+        }
+    }
+
+    /// Add the sample from a newer snapshot. Playback outside the two samples held restarts
+    /// at the older one, so a remote that starved gets its one-interval buffer back instead
+    /// of starving again on every snapshot.
+    pub fn push(&mut self, tick: u64, player: NetPlayer, tick_dt: f32) {
+        if self.samples[1].as_ref().is_some_and(|(t, _)| tick <= *t) {
+            return;
+        }
+        self.samples[0] = self.samples[1].take();
+        self.samples[1] = Some((tick, player));
+        let t1 = tick as f64 * tick_dt as f64;
+        let t0 = self.samples[0]
+            .as_ref()
+            .map_or(t1, |(t, _)| *t as f64 * tick_dt as f64);
+        if !(t0..=t1).contains(&self.playback_s) {
+            self.playback_s = t0;
+        }
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        self.playback_s += dt as f64;
+    }
+
+    /// Position and orientation at the playback time: blended between the two samples, or
+    /// carried along the newest one's velocity and turn rate once playback has run past it.
+    pub fn pose(&self, tick_dt: f32) -> Option<(Vec3, Quat)> {
+        let (newest_tick, newest) = self.samples[1].as_ref()?;
+        let t1 = *newest_tick as f64 * tick_dt as f64;
+        let position = Vec3::from_array(newest.position);
+        let rotation = Quat::from_array(newest.orientation);
+        match &self.samples[0] {
+            Some((older_tick, older)) if self.playback_s < t1 => {
+                let t0 = *older_tick as f64 * tick_dt as f64;
+                let alpha = ((self.playback_s - t0) / (t1 - t0)).clamp(0.0, 1.0) as f32;
+                Some((
+                    Vec3::from_array(older.position).lerp(position, alpha),
+                    Quat::from_array(older.orientation)
+                        .slerp(rotation, alpha)
+                        .normalize(),
+                ))
+            }
+            _ => {
+                // Starved: no sample ahead of the playback clock
+                let ahead =
+                    ((self.playback_s - t1).max(0.0) as f32).min(REMOTE_EXTRAPOLATION_LIMIT_S);
+                Some((
+                    position + Vec3::from_array(newest.velocity) * ahead,
+                    integrate_orientation(
+                        rotation,
+                        Vec3::from_array(newest.angular_velocity),
+                        ahead,
+                    ),
+                ))
+            }
+        }
+    }
+}
+
+/// Turn `rotation` by the body-frame rate `ang_vel` for `dt`.
+pub fn integrate_orientation(rotation: Quat, ang_vel: Vec3, dt: f32) -> Quat {
+    let rate = ang_vel.length();
+    if rate <= f32::EPSILON || dt <= 0.0 {
+        return rotation;
+    }
+    // Body-frame delta, so post-multiply
+    (rotation * Quat::from_axis_angle(ang_vel / rate, rate * dt)).normalize()
+}
+
+pub struct RemotePlayersPlugin;
+
+impl Plugin for RemotePlayersPlugin {
+    fn build(&self, app: &mut App) {
+        // After `apply_state_to_sub` has measured how far each remote is about to jump
+        app.add_systems(
+            Update,
+            (sync_remote_players, interpolate_remote_players)
+                .chain()
+                .after(NetSet),
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn sync_remote_players(
+    mut commands: Commands,
+    my_id: Res<MyPlayerId>,
+    latest: Res<LatestStateDelta>,
+    leaderboard: Res<Leaderboard>,
+    timing: Res<ClientPhysicsTiming>,
+    font: Option<Res<LabelFont>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q_remote: Query<(Entity, &mut RemotePlayer, &mut RemoteSnapshots)>,
+    mut q_tags: Query<(Entity, &TracksEntity, &mut Text, &mut TextColor)>,
+) {
+    if !latest.is_changed() {
+        return;
+    }
+    let Some(delta) = latest.0.as_ref() else {
+        return;
+    };
+    // Names arrive with the (slower) leaderboard; tags start anonymous and fill in later
+    let name_of = |id: Uuid| {
+        leaderboard
+            .entries
+            .iter()
+            .find(|e| e.player_id == id)
+            .map(|e| e.display_name.clone())
+            .unwrap_or_else(|| "(anon)".to_string())
+    };
+    let others: Vec<_> = delta
+        .players
+        .iter()
+        .filter(|p| Some(p.id) != my_id.0)
+        .collect();
+
+    // Update or despawn existing remotes
+    for (entity, mut remote, mut snapshots) in &mut q_remote {
+        let Some(p) = others.iter().find(|p| p.id == remote.id) else {
+            commands.entity(entity).despawn();
+            for (tag, tracks, _, _) in &q_tags {
+                if tracks.0 == entity {
+                    commands.entity(tag).despawn();
+                }
+            }
+            continue;
+        };
+        snapshots.push(delta.tick, (*p).clone(), timing.dt);
+        if remote.team_id != p.team_id {
+            remote.team_id = p.team_id;
+            if let Some(mat) = materials.get_mut(&remote.material) {
+                mat.base_color = team_color(p.team_id);
+            }
+        }
+        for (_, tracks, mut text, mut color) in &mut q_tags {
+            if tracks.0 != entity {
+                continue;
+            }
+            color.0 = team_color(p.team_id);
+            let name = name_of(p.id);
+            if text.0 != name {
+                text.0 = name;
+            }
+        }
+    }
+
+    // Spawn newcomers
+    for p in others {
+        if q_remote.iter().any(|(_, r, _)| r.id == p.id) {
+            continue;
+        }
+        let material = materials.add(StandardMaterial {
+            base_color: team_color(p.team_id),
+            perceptual_roughness: 0.6,
+            ..Default::default()
+        });
+        // Capsule long axis along body +Z (forward)
+        let mesh = Capsule3d::new(0.6, 3.0)
+            .mesh()
+            .build()
+            .rotated_by(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2));
+        let entity = commands
+            .spawn((
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(Vec3::from_array(p.position))
+                    .with_rotation(Quat::from_array(p.orientation)),
+                RemotePlayer {
+                    id: p.id,
+                    team_id: p.team_id,
+                    material,
+                },
+                RemoteSnapshots::new(delta.tick, p.clone(), timing.dt),
+                Name::new(format!("Remote Player {}", p.id)),
+            ))
+            .id();
+        if let Some(font) = font.as_ref() {
+            let name = name_of(p.id);
+            commands.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    ..Default::default()
+                },
+                Text::new(name.clone()),
+                TextFont {
+                    font: font.0.clone(),
+                    font_size: 14.0,
+                    ..Default::default()
+                },
+                TextColor(team_color(p.team_id)),
+                TracksEntity(entity),
+                LabelNode,
+                Name::new(format!("Label: {name}")),
+            ));
+        }
+    }
+}
+
+/// Move each remote along its snapshots by this frame's time.
+fn interpolate_remote_players(
+    time: Res<Time>,
+    timing: Res<ClientPhysicsTiming>,
+    mut q_remote: Query<(&mut Transform, &mut RemoteSnapshots), With<RemotePlayer>>,
+) {
+    let dt = time.delta_secs();
+    for (mut transform, mut snapshots) in &mut q_remote {
+        snapshots.advance(dt);
+        if let Some((position, rotation)) = snapshots.pose(timing.dt) {
+            transform.translation = position;
+            transform.rotation = rotation;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orientation_follows_body_rate() {
+        let start = Quat::from_rotation_y(0.3);
+        assert_eq!(integrate_orientation(start, Vec3::ZERO, 0.1), start);
+
+        // 0.5 rad/s of yaw for 2 s turns the nose a further radian
+        let turned = integrate_orientation(start, Vec3::new(0.0, 0.5, 0.0), 2.0);
+        assert!(turned.angle_between(Quat::from_rotation_y(1.3)) < 1e-4);
+
+        // Body-frame rate: pitching after a yaw pitches about the yawed axis
+        let pitched = integrate_orientation(start, Vec3::new(0.2, 0.0, 0.0), 1.0);
+        let expected = start * Quat::from_rotation_x(0.2);
+        assert!(pitched.angle_between(expected) < 1e-4);
+    }
+
+    fn sample(x: f32, yaw: f32, velocity: Vec3) -> NetPlayer {
+        NetPlayer {
+            id: Uuid::nil(),
+            position: [x, 0.0, 0.0],
+            velocity: velocity.to_array(),
+            orientation: Quat::from_rotation_y(yaw).to_array(),
+            ang_mom: [0.0; 3],
+            angular_velocity: [0.0; 3],
+            ballast_fill: Vec::new(),
+            input_state: protocol::NetInputState {
+                thrust: 0.0,
+                yaw: 0.0,
+                pump_fwd: 0.0,
+                pump_aft: 0.0,
+                pitch: 0.0,
+                roll_trim: 0.0,
+            },
+            hull_integrity: 1.0,
+            team_id: 0,
+            pump: Default::default(),
+        }
+    }
+
+    #[test]
+    fn playback_blends_between_the_two_newest_snapshots() {
+        let dt = 0.1;
+        let mut snaps = RemoteSnapshots::new(10, sample(0.0, 0.0, Vec3::ZERO), dt);
+        snaps.push(13, sample(3.0, 0.6, Vec3::ZERO), dt);
+        // A repeated or older tick is ignored
+        snaps.push(12, sample(99.0, 0.0, Vec3::ZERO), dt);
+
+        // Playback starts at the older sample and runs a third of the way in 0.1 s
+        let (pos, _) = snaps.pose(dt).unwrap();
+        assert!(pos.abs_diff_eq(Vec3::ZERO, 1e-5));
+        snaps.advance(0.1);
+        let (pos, rot) = snaps.pose(dt).unwrap();
+        assert!(pos.abs_diff_eq(Vec3::new(1.0, 0.0, 0.0), 1e-4));
+        assert!(rot.angle_between(Quat::from_rotation_y(0.2)) < 1e-4);
+    }
+
+    #[test]
+    fn starved_playback_extrapolates_briefly_then_freezes() {
+        let dt = 0.1;
+        let vel = Vec3::new(2.0, 0.0, 0.0);
+        let mut snaps = RemoteSnapshots::new(10, sample(0.0, 0.0, vel), dt);
+        snaps.push(11, sample(0.2, 0.0, vel), dt);
+        snaps.advance(0.2);
+        let (pos, _) = snaps.pose(dt).unwrap();
+        assert!(pos.abs_diff_eq(Vec3::new(0.4, 0.0, 0.0), 1e-4));
+        snaps.advance(1.0);
+        let (pos, _) = snaps.pose(dt).unwrap();
+        let frozen = 0.2 + 2.0 * REMOTE_EXTRAPOLATION_LIMIT_S;
+        assert!(pos.abs_diff_eq(Vec3::new(frozen, 0.0, 0.0), 1e-4));
+
+        // The next snapshot pulls playback back to its older sample instead of running ahead
+        snaps.push(12, sample(0.4, 0.0, vel), dt);
+        let (pos, _) = snaps.pose(dt).unwrap();
+        assert!(pos.abs_diff_eq(Vec3::new(0.2, 0.0, 0.0), 1e-4));
+    }
+}
@@ -1 +1,3 @@
+pub mod mote_instancing;
+pub mod velocity_buffer;
 pub mod volumetric_floodlights;
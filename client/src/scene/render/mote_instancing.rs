@@ -0,0 +1,325 @@
+//! Draws every dust mote and bubble in one instanced call. The simulation in `water.rs` packs
+//! particles into `MoteInstanceBuffer` each frame; the render world copies them into a
+//! persistent vertex buffer (grown on demand, refilled through the queue's staging belt) and
+//! draws a single sphere mesh once per instance.
+
+use bevy::core_pipeline::core_3d::Transparent3d;
+use bevy::ecs::system::{lifetimeless::*, SystemParamItem};
+use bevy::pbr::{
+    MeshPipeline, MeshPipelineKey, RenderMeshInstances, SetMeshViewBindGroup, ViewKeyCache,
+};
+use bevy::prelude::*;
+use bevy::render::{
+    extract_component::{ExtractComponent, ExtractComponentPlugin},
+    extract_resource::{ExtractResource, ExtractResourcePlugin},
+    mesh::{allocator::MeshAllocator, MeshVertexBufferLayoutRef, RenderMesh, RenderMeshBufferInfo},
+    render_asset::RenderAssets,
+    render_phase::{
+        AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+        RenderCommandResult, SetItemPipeline, TrackedRenderPass, ViewSortedRenderPhases,
+    },
+    render_resource::*,
+    renderer::{RenderDevice, RenderQueue},
+    sync_world::MainEntity,
+    view::{ExtractedView, NoFrustumCulling},
+    Render, RenderApp, RenderSet,
+};
+use bytemuck::{Pod, Zeroable};
+
+pub const MOTE_SHADER_PATH: &str = "shaders/motes.wgsl";
+
+/// One particle as the GPU sees it: `[x, y, z, scale, r, g, b, a]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct MoteInstanceData {
+    pub position: [f32; 3],
+    pub scale: f32,
+    /// Linear RGBA.
+    pub color: [f32; 4],
+}
+
+impl MoteInstanceData {
+    pub fn new(position: Vec3, scale: f32, color: LinearRgba) -> Self {
+        Self {
+            position: position.to_array(),
+            scale,
+            color: color.to_f32_array(),
+        }
+    }
+}
+
+/// Particles to draw this frame, rebuilt by the simulation every `Update`.
+#[derive(Resource, Clone, Debug, Default, ExtractResource)]
+pub struct MoteInstanceBuffer {
+    pub instances: Vec<MoteInstanceData>,
+}
+
+/// Carrier entity for the instanced draw; its `Mesh3d` is the unit particle mesh.
+#[derive(Component, Clone, Copy, Debug, Default, ExtractComponent)]
+pub struct MoteBatch;
+
+pub struct MoteRenderPlugin;
+
+impl Plugin for MoteRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MoteInstanceBuffer>()
+            .add_plugins((
+                ExtractResourcePlugin::<MoteInstanceBuffer>::default(),
+                ExtractComponentPlugin::<MoteBatch>::default(),
+            ))
+            .add_systems(Startup, spawn_mote_batch);
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<MoteGpuBuffer>()
+            .init_resource::<SpecializedMeshPipelines<MotePipeline>>()
+            .add_render_command::<Transparent3d, DrawMotes>()
+            .add_systems(
+                Render,
+                (
+                    queue_motes.in_set(RenderSet::QueueMeshes),
+                    prepare_mote_buffer.in_set(RenderSet::PrepareResources),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<MotePipeline>();
+        }
+    }
+}
+
+fn spawn_mote_batch(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(1.0).mesh().ico(1).unwrap())),
+        Transform::default(),
+        MoteBatch,
+        // Instances carry their own world positions; the carrier's bounds mean nothing
+        NoFrustumCulling,
+        Name::new("Mote Batch"),
+    ));
+}
+
+/// GPU copy of `MoteInstanceBuffer`, reused across frames while it is large enough.
+#[derive(Resource, Default)]
+struct MoteGpuBuffer {
+    buffer: Option<Buffer>,
+    capacity: usize,
+    length: usize,
+}
+
+fn prepare_mote_buffer(
+    instances: Res<MoteInstanceBuffer>,
+    mut gpu: ResMut<MoteGpuBuffer>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    let len = instances.instances.len();
+    gpu.length = len;
+    if len == 0 {
+        return;
+    }
+    if gpu.buffer.is_none() || gpu.capacity < len {
+        let capacity = len.next_power_of_two();
+        gpu.buffer = Some(render_device.create_buffer(&BufferDescriptor {
+            label: Some("mote instance buffer"),
+            size: (capacity * size_of::<MoteInstanceData>()) as u64,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+        gpu.capacity = capacity;
+    }
+    if let Some(buffer) = &gpu.buffer {
+        render_queue.write_buffer(buffer, 0, bytemuck::cast_slice(&instances.instances));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn queue_motes(
+    draw_functions: Res<DrawFunctions<Transparent3d>>,
+    mote_pipeline: Res<MotePipeline>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<MotePipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    meshes: Res<RenderAssets<RenderMesh>>,
+    render_mesh_instances: Res<RenderMeshInstances>,
+    view_key_cache: Res<ViewKeyCache>,
+    gpu: Res<MoteGpuBuffer>,
+    batches: Query<(Entity, &MainEntity), With<MoteBatch>>,
+    mut phases: ResMut<ViewSortedRenderPhases<Transparent3d>>,
+    views: Query<&ExtractedView>,
+) {
+    if gpu.length == 0 {
+        return;
+    }
+    let draw_motes = draw_functions.read().id::<DrawMotes>();
+    for view in &views {
+        let Some(phase) = phases.get_mut(&view.retained_view_entity) else {
+            continue;
+        };
+        let Some(view_key) = view_key_cache.get(&view.retained_view_entity) else {
+            continue;
+        };
+        let view_key = *view_key - MeshPipelineKey::BLEND_RESERVED_BITS;
+        for (entity, main_entity) in &batches {
+            let Some(mesh_instance) = render_mesh_instances.render_mesh_queue_data(*main_entity)
+            else {
+                continue;
+            };
+            let Some(mesh) = meshes.get(mesh_instance.mesh_asset_id) else {
+                continue;
+            };
+            let key = view_key
+                | MeshPipelineKey::BLEND_ALPHA
+                | MeshPipelineKey::from_primitive_topology(mesh.primitive_topology());
+            let Ok(pipeline) =
+                pipelines.specialize(&pipeline_cache, &mote_pipeline, key, &mesh.layout)
+            else {
+                continue;
+            };
+            phase.add(Transparent3d {
+                entity: (entity, *main_entity),
+                pipeline,
+                draw_function: draw_motes,
+                // Motes surround the camera, so blend them over all farther transparent geometry
+                distance: 0.0,
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::None,
+                indexed: true,
+            });
+        }
+    }
+}
+
+#[derive(Resource)]
+struct MotePipeline {
+    shader: Handle<Shader>,
+    mesh_pipeline: MeshPipeline,
+}
+
+impl FromWorld for MotePipeline {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            shader: world.load_asset(MOTE_SHADER_PATH),
+            mesh_pipeline: world.resource::<MeshPipeline>().clone(),
+        }
+    }
+}
+
+impl SpecializedMeshPipeline for MotePipeline {
+    type Key = MeshPipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayoutRef,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut descriptor = self.mesh_pipeline.specialize(key, layout)?;
+        // The shader reads only the view; positions come from the instance buffer, not the
+        // per-mesh uniform, so drop the mesh bind group
+        descriptor.layout.truncate(1);
+        descriptor.vertex.shader = self.shader.clone();
+        descriptor.vertex.buffers.push(VertexBufferLayout {
+            array_stride: size_of::<MoteInstanceData>() as u64,
+            step_mode: VertexStepMode::Instance,
+            attributes: vec![
+                // Locations 0-2 are the mesh's position, normal and UV
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 0,
+                    shader_location: 3,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: VertexFormat::Float32x4.size(),
+                    shader_location: 4,
+                },
+            ],
+        });
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            fragment.shader = self.shader.clone();
+        }
+        Ok(descriptor)
+    }
+}
+
+type DrawMotes = (SetItemPipeline, SetMeshViewBindGroup<0>, DrawMoteInstances);
+
+struct DrawMoteInstances;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawMoteInstances {
+    type Param = (
+        SRes<RenderAssets<RenderMesh>>,
+        SRes<RenderMeshInstances>,
+        SRes<MeshAllocator>,
+        SRes<MoteGpuBuffer>,
+    );
+    type ViewQuery = ();
+    type ItemQuery = ();
+
+    fn render<'w>(
+        item: &P,
+        _view: (),
+        _entity: Option<()>,
+        (meshes, render_mesh_instances, mesh_allocator, gpu): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let mesh_allocator = mesh_allocator.into_inner();
+        let gpu = gpu.into_inner();
+        let Some(mesh_instance) = render_mesh_instances.render_mesh_queue_data(item.main_entity())
+        else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(gpu_mesh) = meshes.into_inner().get(mesh_instance.mesh_asset_id) else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(instances) = gpu.buffer.as_ref() else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(vertex_slice) = mesh_allocator.mesh_vertex_slice(&mesh_instance.mesh_asset_id)
+        else {
+            return RenderCommandResult::Skip;
+        };
+        let instance_range = 0..gpu.length as u32;
+
+        pass.set_vertex_buffer(0, vertex_slice.buffer.slice(..));
+        pass.set_vertex_buffer(1, instances.slice(..));
+        match &gpu_mesh.buffer_info {
+            RenderMeshBufferInfo::Indexed {
+                index_format,
+                count,
+            } => {
+                let Some(index_slice) =
+                    mesh_allocator.mesh_index_slice(&mesh_instance.mesh_asset_id)
+                else {
+                    return RenderCommandResult::Skip;
+                };
+                pass.set_index_buffer(index_slice.buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(
+                    index_slice.range.start..(index_slice.range.start + count),
+                    vertex_slice.range.start as i32,
+                    instance_range,
+                );
+            }
+            RenderMeshBufferInfo::NonIndexed => {
+                pass.draw(vertex_slice.range, instance_range);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_layout_is_eight_floats() {
+        assert_eq!(size_of::<MoteInstanceData>(), size_of::<[f32; 8]>());
+        let data = MoteInstanceData::new(Vec3::new(1.0, 2.0, 3.0), 0.5, LinearRgba::RED);
+        let floats: [f32; 8] = bytemuck::cast(data);
+        assert_eq!(floats, [1.0, 2.0, 3.0, 0.5, 1.0, 0.0, 0.0, 1.0]);
+    }
+}
@@ -0,0 +1,323 @@
+//! Screen-space velocity buffer for motion blur and TAA. Between the prepasses and the main
+//! pass, a fullscreen pass reprojects every pixel of the prepass depth through the view's
+//! `PrevViewProj` and writes `current - previous` as a UV-space delta to an `Rg16Float`
+//! texture per view. The convention matches Bevy's motion-vector prepass, so consumers can bind
+//! either. Reading depth instead of rasterizing the scene a second time means the buffer holds
+//! camera motion only; moving meshes still need the `MotionVectorPrepass`. Cameras need a
+//! prepass (depth or motion vectors) and a `TEXTURE_BINDING` depth texture.
+
+use bevy::core_pipeline::core_3d::graph::{Core3d, Node3d};
+use bevy::core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state;
+use bevy::core_pipeline::prepass::ViewPrepassTextures;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy::render::render_graph::{
+    NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+};
+use bevy::render::render_resource::binding_types::{texture_depth_2d, uniform_buffer_sized};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::texture::{CachedTexture, TextureCache};
+use bevy::render::view::{ExtractedView, ViewDepthTexture};
+use bevy::render::{Render, RenderApp, RenderSet};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::scene::postprocess::RenderVisToggles;
+
+pub const VELOCITY_SHADER_PATH: &str = "shaders/velocity_buffer.wgsl";
+pub const VELOCITY_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rg16Float;
+
+#[derive(Debug, Clone, Copy, RenderLabel, Hash, PartialEq, Eq)]
+pub struct VelocityBufferLabel;
+
+/// Adds the velocity pass. Depends on `WaterPostProcessPlugin` for the extracted toggles.
+pub struct VelocityBufferPlugin;
+
+impl Plugin for VelocityBufferPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<VelocityTexture>()
+            .add_systems(
+                Render,
+                prepare_velocity_buffers.in_set(RenderSet::PrepareResources),
+            )
+            .add_render_graph_node::<ViewNodeRunner<VelocityBufferNode>>(
+                Core3d,
+                VelocityBufferLabel,
+            )
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::EndPrepasses,
+                    VelocityBufferLabel,
+                    Node3d::StartMainPass,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<VelocityBufferPipeline>();
+        }
+    }
+}
+
+/// The view's `clip_from_world` as of the previous frame; equal to the current one on the
+/// first frame a view is seen, so it starts with zero velocity.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct PrevViewProj(pub Mat4);
+
+/// Uniform for one view's pass: unproject with this frame's matrix, reproject with last's.
+#[repr(C)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct VelocityUniform {
+    pub world_from_clip: Mat4,
+    pub prev_clip_from_world: Mat4,
+}
+
+/// This frame's velocity target for a view.
+#[derive(Component)]
+pub struct ViewVelocityTexture(pub CachedTexture);
+
+/// Velocity targets by render-world view entity, rebuilt every frame. Downstream passes look up
+/// their view here and bind the texture after `VelocityBufferLabel` has run.
+#[derive(Resource, Default)]
+pub struct VelocityTexture {
+    pub views: EntityHashMap<TextureView>,
+}
+
+/// UV-space motion of a pixel at `ndc` (x, y, and reverse-Z depth), current minus previous.
+/// Kept in homogeneous coordinates so far-plane pixels (depth 0) reproject as directions.
+pub fn reproject_velocity(uniform: &VelocityUniform, ndc: Vec3) -> Vec2 {
+    let world = uniform.world_from_clip * ndc.extend(1.0);
+    let prev_clip = uniform.prev_clip_from_world * world;
+    if prev_clip.w.abs() < 1e-6 {
+        return Vec2::ZERO;
+    }
+    let prev_ndc = prev_clip.truncate().truncate() / prev_clip.w;
+    (ndc.truncate() - prev_ndc) * Vec2::new(0.5, -0.5)
+}
+
+#[allow(clippy::type_complexity)]
+pub fn prepare_velocity_buffers(
+    mut commands: Commands,
+    device: Res<RenderDevice>,
+    mut texture_cache: ResMut<TextureCache>,
+    mut velocity: ResMut<VelocityTexture>,
+    toggles: Option<Res<RenderVisToggles>>,
+    views: Query<(
+        Entity,
+        &ExtractedView,
+        Option<&ViewDepthTexture>,
+        Has<ViewPrepassTextures>,
+        Option<&PrevViewProj>,
+    )>,
+) {
+    velocity.views.clear();
+    if !toggles.is_some_and(|t| t.velocity_buffer) {
+        return;
+    }
+    for (entity, view, depth, has_prepass, prev) in &views {
+        let clip_from_world = view.clip_from_world.unwrap_or_else(|| {
+            view.clip_from_view * view.world_from_view.compute_matrix().inverse()
+        });
+        let prev_clip_from_world = prev.map_or(clip_from_world, |p| p.0);
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(PrevViewProj(clip_from_world));
+        // Needs prepass depth the shader can sample; other views have nothing to reproject
+        let Some(depth) = depth
+            .filter(|d| has_prepass && d.texture.usage().contains(TextureUsages::TEXTURE_BINDING))
+        else {
+            entity_commands.remove::<(VelocityUniform, ViewVelocityTexture)>();
+            continue;
+        };
+        let size = depth.texture.size();
+        let texture = texture_cache.get(
+            &device,
+            TextureDescriptor {
+                label: Some("velocity_buffer"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: VELOCITY_TEXTURE_FORMAT,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+        velocity.views.insert(entity, texture.default_view.clone());
+        entity_commands.insert((
+            VelocityUniform {
+                world_from_clip: clip_from_world.inverse(),
+                prev_clip_from_world,
+            },
+            ViewVelocityTexture(texture),
+        ));
+    }
+}
+
+#[derive(Resource)]
+pub struct VelocityBufferPipeline {
+    layout: BindGroupLayout,
+    pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for VelocityBufferPipeline {
+    fn from_world(render_world: &mut World) -> Self {
+        let device = render_world.resource::<RenderDevice>();
+        let layout = device.create_bind_group_layout(
+            "velocity_buffer_bgl",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (texture_depth_2d(), uniform_buffer_sized(false, None)),
+            ),
+        );
+        let shader = render_world
+            .resource::<AssetServer>()
+            .load(VELOCITY_SHADER_PATH);
+        let pipeline_id = render_world
+            .resource_mut::<PipelineCache>()
+            .queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some("velocity_buffer".into()),
+                layout: vec![layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader,
+                    shader_defs: vec![],
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format: VELOCITY_TEXTURE_FORMAT,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                push_constant_ranges: vec![],
+                zero_initialize_workgroup_memory: false,
+            });
+        Self {
+            layout,
+            pipeline_id,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct VelocityBufferNode;
+
+impl ViewNode for VelocityBufferNode {
+    type ViewQuery = (
+        &'static ViewDepthTexture,
+        &'static VelocityUniform,
+        &'static ViewVelocityTexture,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (depth, uniform, target): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        if !world
+            .get_resource::<RenderVisToggles>()
+            .is_some_and(|t| t.velocity_buffer)
+        {
+            return Ok(());
+        }
+        let velocity_pipe = world.resource::<VelocityBufferPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(render_pipeline) = pipeline_cache.get_render_pipeline(velocity_pipe.pipeline_id)
+        else {
+            tracing::debug!("velocity_buffer: pipeline not ready, skipping frame");
+            return Ok(());
+        };
+
+        let device = render_context.render_device();
+        let uniform_buffer = device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("velocity_buffer_uniform"),
+            contents: bytemuck::bytes_of(uniform),
+            usage: BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(
+            Some("velocity_buffer_bg"),
+            &velocity_pipe.layout,
+            &BindGroupEntries::sequential((depth.view(), uniform_buffer.as_entire_binding())),
+        );
+
+        let mut pass = render_context
+            .command_encoder()
+            .begin_render_pass(&RenderPassDescriptor {
+                label: Some("velocity_buffer_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &target.0.default_view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(Default::default()),
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+        pass.set_pipeline(render_pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uniform(prev_view: Transform, view: Transform) -> (VelocityUniform, Mat4) {
+        let proj = Mat4::perspective_infinite_reverse_rh(1.0, 16.0 / 9.0, 0.1);
+        let clip_from_world = proj * view.compute_matrix().inverse();
+        let uniform = VelocityUniform {
+            world_from_clip: clip_from_world.inverse(),
+            prev_clip_from_world: proj * prev_view.compute_matrix().inverse(),
+        };
+        (uniform, clip_from_world)
+    }
+
+    #[test]
+    fn static_camera_has_zero_velocity() {
+        let view =
+            Transform::from_xyz(1.0, 2.0, 3.0).looking_at(Vec3::new(0.0, 0.0, -10.0), Vec3::Y);
+        let (uniform, _) = uniform(view, view);
+        for ndc in [
+            Vec3::new(0.3, -0.2, 0.5),
+            Vec3::new(-0.9, 0.9, 0.01),
+            Vec3::new(0.0, 0.0, 0.0),
+        ] {
+            assert!(reproject_velocity(&uniform, ndc).length() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn strafing_right_moves_near_points_left_and_leaves_the_horizon() {
+        let prev = Transform::IDENTITY;
+        let view = Transform::from_xyz(0.5, 0.0, 0.0);
+        let (uniform, clip_from_world) = uniform(prev, view);
+
+        let near = clip_from_world.project_point3(Vec3::new(0.0, 0.0, -5.0));
+        let velocity = reproject_velocity(&uniform, near);
+        // Point drifts toward -x on screen: current UV x is smaller than last frame's
+        assert!(velocity.x < -0.01, "{velocity}");
+        assert!(velocity.y.abs() < 1e-5);
+
+        // Far-plane pixels are directions; a pure translation doesn't move them
+        let horizon = reproject_velocity(&uniform, Vec3::new(0.2, 0.1, 0.0));
+        assert!(horizon.length() < 1e-5, "{horizon}");
+    }
+}
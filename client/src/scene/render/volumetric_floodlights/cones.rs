@@ -9,6 +9,8 @@ use super::VolumetricConeDebugMaterial;
 #[derive(Resource, Default, Clone)]
 pub struct VolumetricConeAssets {
     pub mesh: Option<Handle<Mesh>>,
+    /// Unit cube scaled to each `GodRaySpec` volume.
+    pub god_ray_mesh: Option<Handle<Mesh>>,
     pub debug_material: Option<Handle<VolumetricConeDebugMaterial>>,
 }
 
@@ -16,6 +18,7 @@ pub(super) fn register(app: &mut App) {
     app.add_plugins(MaterialPlugin::<VolumetricConeDebugMaterial>::default())
         .init_resource::<VolumetricConeAssets>()
         .register_type::<VolumetricCone>()
+        .register_type::<GodRaySpec>()
         .add_systems(Startup, setup_volumetric_cone_assets)
         .add_systems(Update, sync_spotlight_cones);
 }
@@ -34,11 +37,26 @@ fn setup_volumetric_cone_assets(
 
     assets.debug_material = Some(debug_handle);
     assets.mesh = Some(mesh_handle);
+    assets.god_ray_mesh = Some(meshes.add(Cuboid::from_size(Vec3::ONE)));
 }
 
-#[derive(Component, Debug, Reflect)]
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
 #[reflect(Component)]
-pub struct VolumetricCone;
+pub struct VolumetricCone {
+    /// Always draw the cone's back faces without a depth test, as when the camera is inside it.
+    /// Views inside the cone get this variant regardless.
+    pub render_inside: bool,
+}
+
+/// Box of water lit by the sun from above, centered on the entity's transform. Light enters
+/// through the top face and fades with depth below it; the sun direction and strength come from
+/// `VolumetricLightingState::god_rays`.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct GodRaySpec {
+    pub half_extents: Vec3,
+    pub color: Color,
+}
 
 #[allow(clippy::type_complexity)]
 fn sync_spotlight_cones(
@@ -98,7 +116,7 @@ fn sync_spotlight_cones(
                     Mesh3d(base_mesh.clone()),
                     cone_transform,
                     GlobalTransform::default(),
-                    VolumetricCone,
+                    VolumetricCone::default(),
                     NotShadowCaster,
                     Name::new("VolumetricCone"),
                     Visibility::Inherited,
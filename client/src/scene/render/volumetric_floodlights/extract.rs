@@ -7,9 +7,11 @@ use bevy::render::{mesh::Mesh3d, view::ViewVisibility, Extract};
 use crate::render_settings::{RenderSettings, VolumetricConeShaderDebugSettings};
 
 use super::{
-    pipeline::{ExtractedConeLights, RenderConeLight},
-    ExtractedVolumetricDebugSettings, ExtractedVolumetricSettings, RenderVolumetricLightingMode,
-    VolumetricCone, VolumetricLightingMode, VolumetricLightingState,
+    cones::VolumetricConeAssets,
+    pipeline::{ExtractedConeLights, ExtractedGodRays, RenderConeLight, RenderGodRayVolume},
+    ExtractedVolumetricDebugSettings, ExtractedVolumetricSettings, GodRaySpec,
+    RenderVolumetricLightingMode, VolumetricCone, VolumetricLightingMode, VolumetricLightingState,
+    DEBUG_STEP_HEATMAP_MODE,
 };
 
 pub(super) fn extract_volumetric_mode(
@@ -49,11 +51,16 @@ pub(super) fn extract_volumetric_settings(
 
 pub(super) fn extract_volumetric_debug_settings(
     mut commands: Commands,
+    state: Extract<Res<VolumetricLightingState>>,
     settings: Extract<Res<VolumetricConeShaderDebugSettings>>,
 ) {
-    commands.insert_resource(ExtractedVolumetricDebugSettings {
-        debug_mode: settings.debug_mode,
-    });
+    // The [V] heat map takes precedence over the inspector's diagnostic
+    let debug_mode = if state.mode == VolumetricLightingMode::DebugHeatmap {
+        DEBUG_STEP_HEATMAP_MODE
+    } else {
+        settings.debug_mode
+    };
+    commands.insert_resource(ExtractedVolumetricDebugSettings { debug_mode });
 }
 
 #[allow(clippy::type_complexity)]
@@ -70,17 +77,28 @@ pub(super) fn extract_cone_lights(
         )>,
     >,
     cones_query: Extract<
-        Query<(Entity, &GlobalTransform, &Mesh3d, Option<&ViewVisibility>), With<VolumetricCone>>,
+        Query<(
+            Entity,
+            &VolumetricCone,
+            &GlobalTransform,
+            &Mesh3d,
+            Option<&ViewVisibility>,
+        )>,
     >,
 ) {
     let mut cones = Vec::new();
-    if matches!(state.mode, VolumetricLightingMode::RaymarchCones) {
-        let mut cone_data: HashMap<Entity, (Handle<Mesh>, Mat4, bool)> = HashMap::default();
-        for (entity, transform, mesh, visibility) in cones_query.iter() {
+    if state.mode.raymarches() {
+        let mut cone_data: HashMap<Entity, (Handle<Mesh>, Mat4, bool, bool)> = HashMap::default();
+        for (entity, cone, transform, mesh, visibility) in cones_query.iter() {
             let visible = visibility.is_none_or(|v| v.get());
             cone_data.insert(
                 entity,
-                (mesh.0.clone(), transform.compute_matrix(), visible),
+                (
+                    mesh.0.clone(),
+                    transform.compute_matrix(),
+                    visible,
+                    cone.render_inside,
+                ),
             );
         }
 
@@ -106,13 +124,15 @@ pub(super) fn extract_cone_lights(
             let mut mesh_and_model = None;
             if let Some(children) = children {
                 for child in children.iter() {
-                    if let Some((mesh, model, cone_visible)) = cone_data.get(&child) {
-                        mesh_and_model = Some((mesh.clone(), *model, *cone_visible));
+                    if let Some((mesh, model, cone_visible, render_inside)) = cone_data.get(&child)
+                    {
+                        mesh_and_model =
+                            Some((mesh.clone(), *model, *cone_visible, *render_inside));
                         break;
                     }
                 }
             }
-            let Some((mesh, model, cone_visible)) = mesh_and_model else {
+            let Some((mesh, model, cone_visible, render_inside)) = mesh_and_model else {
                 continue;
             };
             if !cone_visible {
@@ -148,9 +168,54 @@ pub(super) fn extract_cone_lights(
                 cos_outer,
                 mesh,
                 model,
+                render_inside,
             });
         }
     }
 
     commands.insert_resource(ExtractedConeLights { cones });
 }
+
+pub(super) fn extract_god_rays(
+    mut commands: Commands,
+    state: Extract<Res<VolumetricLightingState>>,
+    settings: Extract<Res<RenderSettings>>,
+    assets: Extract<Res<VolumetricConeAssets>>,
+    specs: Extract<Query<(&GodRaySpec, &GlobalTransform, Option<&ViewVisibility>)>>,
+) {
+    let god_rays = state.god_rays;
+    let sun_direction = god_rays.sun_direction.normalize_or_zero();
+    let active = state.mode.raymarches()
+        && settings.volumetric_cones
+        && god_rays.enabled
+        && god_rays.intensity > 0.0
+        && sun_direction != Vec3::ZERO;
+
+    let mut volumes = Vec::new();
+    if let (true, Some(mesh)) = (active, assets.god_ray_mesh.as_ref()) {
+        for (spec, transform, visibility) in specs.iter() {
+            if visibility.is_some_and(|v| !v.get()) {
+                continue;
+            }
+            let size = spec.half_extents * 2.0;
+            if size.min_element() <= 0.0 || !size.is_finite() {
+                continue;
+            }
+            let model = transform.compute_matrix() * Mat4::from_scale(size);
+            volumes.push(RenderGodRayVolume {
+                surface: transform.transform_point(Vec3::Y * spec.half_extents.y),
+                depth: size.y,
+                color: spec.color.into(),
+                mesh: mesh.clone(),
+                model,
+            });
+        }
+    }
+
+    commands.insert_resource(ExtractedGodRays {
+        sun_direction,
+        intensity: god_rays.intensity.max(0.0),
+        density: god_rays.density.max(0.0),
+        volumes,
+    });
+}
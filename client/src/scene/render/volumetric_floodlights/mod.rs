@@ -5,6 +5,8 @@ use bevy::render::{
     render_resource::SpecializedRenderPipelines,
     ExtractSchedule, Render, RenderApp, RenderSet,
 };
+#[cfg(feature = "windowing")]
+use bevy_egui::EguiPrimaryContextPass;
 
 pub mod debug_material;
 pub use debug_material::VolumetricConeDebugMaterial;
@@ -15,7 +17,7 @@ mod pipeline;
 mod render_node;
 mod ui;
 
-pub use cones::VolumetricCone;
+pub use cones::{GodRaySpec, VolumetricCone};
 pub use render_node::FloodlightPassLabel;
 
 pub const CONE_VOLUME_SHADER_PATH: &str = "shaders/volumetric_floodlights/volumetric_cones.wgsl";
@@ -24,17 +26,55 @@ pub const CONE_VOLUME_SHADER_PATH: &str = "shaders/volumetric_floodlights/volume
 pub enum VolumetricLightingMode {
     Disabled,
     RaymarchCones,
+    /// Raymarch, but draw each pixel's march step count as a heat map (black = 0, red = 32)
+    /// instead of the lighting.
+    DebugHeatmap,
+}
+
+/// `debug_mode` the cone shader reads as its step-count heat map; 1-5 are the inspector's
+/// `VolumetricConeShaderDebugSettings` diagnostics.
+pub const DEBUG_STEP_HEATMAP_MODE: u32 = 6;
+
+impl VolumetricLightingMode {
+    /// Whether the cone pass runs at all in this mode.
+    pub fn raymarches(self) -> bool {
+        matches!(self, Self::RaymarchCones | Self::DebugHeatmap)
+    }
+}
+
+/// Sunlight shafts drawn inside every `GodRaySpec` volume.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GodRaySettings {
+    /// Direction the sunlight travels; normalized on extraction.
+    pub sun_direction: Vec3,
+    pub intensity: f32,
+    /// Scattering per meter of lit water along the view ray.
+    pub density: f32,
+    pub enabled: bool,
+}
+
+impl Default for GodRaySettings {
+    fn default() -> Self {
+        Self {
+            sun_direction: Vec3::new(0.25, -1.0, 0.15),
+            intensity: 0.5,
+            density: 0.3,
+            enabled: true,
+        }
+    }
 }
 
 #[derive(Resource, Debug, Clone, Copy)]
 pub struct VolumetricLightingState {
     pub mode: VolumetricLightingMode,
+    pub god_rays: GodRaySettings,
 }
 
 impl Default for VolumetricLightingState {
     fn default() -> Self {
         Self {
             mode: VolumetricLightingMode::RaymarchCones,
+            god_rays: GodRaySettings::default(),
         }
     }
 }
@@ -82,6 +122,8 @@ impl Plugin for VolumetricFloodlightsPlugin {
             .add_systems(Update, ui::toggle_volumetric_mode)
             .add_systems(Startup, ui::spawn_mode_label)
             .add_systems(Update, ui::update_mode_label);
+        #[cfg(feature = "windowing")]
+        app.add_systems(EguiPrimaryContextPass, ui::ui_god_rays);
 
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
@@ -89,6 +131,7 @@ impl Plugin for VolumetricFloodlightsPlugin {
                 .init_resource::<pipeline::ConeVolumePipeline>()
                 .init_resource::<SpecializedRenderPipelines<pipeline::ConeVolumePipeline>>()
                 .init_resource::<pipeline::ExtractedConeLights>()
+                .init_resource::<pipeline::ExtractedGodRays>()
                 .init_resource::<ExtractedVolumetricSettings>()
                 .init_resource::<ExtractedVolumetricDebugSettings>()
                 .add_systems(ExtractSchedule, extract::extract_volumetric_mode)
@@ -105,6 +148,10 @@ impl Plugin for VolumetricFloodlightsPlugin {
                     ExtractSchedule,
                     extract::extract_cone_lights.after(extract::extract_volumetric_debug_settings),
                 )
+                .add_systems(
+                    ExtractSchedule,
+                    extract::extract_god_rays.after(extract::extract_volumetric_settings),
+                )
                 .add_systems(
                     Render,
                     pipeline::prepare_view_cone_lights.in_set(RenderSet::Queue),
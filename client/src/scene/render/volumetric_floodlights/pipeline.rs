@@ -26,7 +26,7 @@ use bytemuck::{Pod, Zeroable};
 
 use super::{
     ExtractedVolumetricDebugSettings, ExtractedVolumetricSettings, RenderVolumetricLightingMode,
-    VolumetricLightingMode, CONE_VOLUME_SHADER_PATH,
+    CONE_VOLUME_SHADER_PATH,
 };
 
 #[derive(Resource)]
@@ -180,6 +180,37 @@ pub(super) struct ConeVolumePipelineKey {
     format: TextureFormat,
     sample_count: u32,
     vertex_layout: MeshVertexBufferLayoutRef,
+    /// Sunlight volume instead of a spotlight cone: the shader swaps the cone test for a
+    /// half-space test below the volume's top face.
+    god_ray: bool,
+    /// Camera inside the cone: draw its back faces and skip the depth test.
+    render_inside: bool,
+}
+
+/// Face culling and depth test for a volume variant. The camera is usually inside a god-ray
+/// volume, and sometimes inside a cone; its front faces are then behind the camera, so draw the
+/// back faces and let every one through. The shader clamps the march to the scene depth.
+fn cull_and_depth(god_ray: bool, render_inside: bool) -> (Face, CompareFunction) {
+    if god_ray || render_inside {
+        (Face::Front, CompareFunction::Always)
+    } else {
+        (Face::Back, CompareFunction::GreaterEqual)
+    }
+}
+
+/// CPU twin of the shader's `is_point_inside_cone`.
+fn cone_contains_point(cone: &RenderConeLight, point: Vec3) -> bool {
+    const EPS: f32 = 1e-4;
+    let rel = point - cone.apex;
+    let dist = rel.length();
+    if dist <= EPS {
+        return true;
+    }
+    let axial = rel.dot(cone.direction);
+    if axial < -EPS || axial > cone.range + EPS {
+        return false;
+    }
+    axial / dist + EPS >= cone.cos_outer
 }
 
 impl SpecializedRenderPipeline for ConeVolumePipeline {
@@ -194,8 +225,17 @@ impl SpecializedRenderPipeline for ConeVolumePipeline {
 
         let resources = self.resources();
 
+        let (label, shader_defs) = if key.god_ray {
+            ("god_ray_raymarch", vec!["GOD_RAY".into()])
+        } else if key.render_inside {
+            ("cone_volume_raymarch_inside", vec![])
+        } else {
+            ("cone_volume_raymarch", vec![])
+        };
+        let (cull_mode, depth_compare) = cull_and_depth(key.god_ray, key.render_inside);
+
         RenderPipelineDescriptor {
-            label: Some("cone_volume_raymarch".into()),
+            label: Some(label.into()),
             layout: vec![
                 resources.global_layout.clone(),
                 resources.view_layout.clone(),
@@ -204,13 +244,13 @@ impl SpecializedRenderPipeline for ConeVolumePipeline {
             ],
             vertex: VertexState {
                 shader: self.shader.clone(),
-                shader_defs: vec![],
+                shader_defs: shader_defs.clone(),
                 entry_point: "vertex".into(),
                 buffers: vec![vertex_layout],
             },
             fragment: Some(FragmentState {
                 shader: self.shader.clone(),
-                shader_defs: vec![],
+                shader_defs,
                 entry_point: "fragment".into(),
                 targets: vec![Some(ColorTargetState {
                     format: key.format,
@@ -230,13 +270,13 @@ impl SpecializedRenderPipeline for ConeVolumePipeline {
                 })],
             }),
             primitive: PrimitiveState {
-                cull_mode: Some(Face::Back),
+                cull_mode: Some(cull_mode),
                 ..Default::default()
             },
             depth_stencil: Some(DepthStencilState {
                 format: TextureFormat::Depth32Float,
                 depth_write_enabled: false,
-                depth_compare: CompareFunction::GreaterEqual,
+                depth_compare,
                 stencil: StencilState::default(),
                 bias: DepthBiasState::default(),
             }),
@@ -263,6 +303,8 @@ pub(super) struct RenderConeLight {
     pub cos_outer: f32,
     pub mesh: Handle<Mesh>,
     pub model: Mat4,
+    /// `VolumetricCone::render_inside`: use the inside variant for every view.
+    pub render_inside: bool,
 }
 
 #[derive(Resource, Default, Clone)]
@@ -270,16 +312,40 @@ pub(super) struct ExtractedConeLights {
     pub cones: Vec<RenderConeLight>,
 }
 
+#[derive(Clone, Debug)]
+pub(super) struct RenderGodRayVolume {
+    /// Center of the volume's top face, where sunlight enters the water.
+    pub surface: Vec3,
+    pub depth: f32,
+    pub color: LinearRgba,
+    pub mesh: Handle<Mesh>,
+    pub model: Mat4,
+}
+
+#[derive(Resource, Default, Clone)]
+pub(super) struct ExtractedGodRays {
+    pub sun_direction: Vec3,
+    pub intensity: f32,
+    pub density: f32,
+    pub volumes: Vec<RenderGodRayVolume>,
+}
+
 #[derive(Component)]
 pub(super) struct ViewConeRenderData {
-    pub(super) pipeline_id: CachedRenderPipelineId,
     pub(super) global: BindGroup,
     pub(super) view: BindGroup,
     pub(super) _view_uniform: Buffer,
-    pub(super) draws: Vec<ConeDraw>,
+    /// Spotlight cones (outside, then inside), then god-ray volumes; each has its own pipeline
+    /// variant.
+    pub(super) batches: Vec<ConeDrawBatch>,
     pub(super) fog: Option<BindGroup>,
 }
 
+pub(super) struct ConeDrawBatch {
+    pub pipeline_id: CachedRenderPipelineId,
+    pub draws: Vec<ConeDraw>,
+}
+
 pub(super) struct ConeDraw {
     pub bind_group: BindGroup,
     pub _uniform_buffer: Buffer,
@@ -320,6 +386,7 @@ pub(super) fn prepare_view_cone_lights(
     )>,
     fog_meta: Res<FogMeta>,
     cones: Res<ExtractedConeLights>,
+    god_rays: Res<ExtractedGodRays>,
     mode: Res<RenderVolumetricLightingMode>,
     settings: Res<ExtractedVolumetricSettings>,
     debug: Res<ExtractedVolumetricDebugSettings>,
@@ -329,10 +396,13 @@ pub(super) fn prepare_view_cone_lights(
     render_device: Res<RenderDevice>,
     mesh_assets: Res<RenderAssets<RenderMesh>>,
 ) {
-    let raymarch = matches!(mode.0, VolumetricLightingMode::RaymarchCones);
+    #[cfg(debug_assertions)]
+    let _timer =
+        crate::system_timings::ScopeTimer::start(crate::system_timings::TimedSystem::ConePrep);
+    let raymarch = mode.0.raymarches();
     for (entity, view, depth_texture, fog_offset, msaa) in &views {
         let mut entity_commands = commands.entity(entity);
-        if !raymarch || cones.cones.is_empty() {
+        if !raymarch || (cones.cones.is_empty() && god_rays.volumes.is_empty()) {
             entity_commands.remove::<ViewConeRenderData>();
             continue;
         }
@@ -342,17 +412,7 @@ pub(super) fn prepare_view_cone_lights(
             continue;
         };
 
-        let Some(first_cone) = cones.cones.first() else {
-            entity_commands.remove::<ViewConeRenderData>();
-            continue;
-        };
-        let Some(render_mesh) = mesh_assets.get(&first_cone.mesh) else {
-            entity_commands.remove::<ViewConeRenderData>();
-            continue;
-        };
-
         pipeline.ensure_initialized(&render_device);
-        let resources = pipeline.resources();
 
         let format = if view.hdr {
             ViewTarget::TEXTURE_FORMAT_HDR
@@ -360,12 +420,41 @@ pub(super) fn prepare_view_cone_lights(
             TextureFormat::bevy_default()
         };
         let sample_count = msaa.map(|m| m.samples()).unwrap_or(1);
-        let key = ConeVolumePipelineKey {
-            format,
-            sample_count,
-            vertex_layout: render_mesh.layout.clone(),
+        let camera_position = view.world_from_view.translation();
+        let mut specialize = |mesh: &Handle<Mesh>, god_ray: bool, render_inside: bool| {
+            let render_mesh = mesh_assets.get(mesh)?;
+            let key = ConeVolumePipelineKey {
+                format,
+                sample_count,
+                vertex_layout: render_mesh.layout.clone(),
+                god_ray,
+                render_inside,
+            };
+            Some(pipelines.specialize(&pipeline_cache, &pipeline, key))
         };
-        let pipeline_id = pipelines.specialize(&pipeline_cache, &pipeline, key);
+        // Outside cones first, then any the camera is inside of
+        let cone_inside: Vec<bool> = cones
+            .cones
+            .iter()
+            .map(|cone| cone.render_inside || cone_contains_point(cone, camera_position))
+            .collect();
+        let cone_pipeline_ids = [false, true].map(|inside| {
+            cones
+                .cones
+                .iter()
+                .zip(&cone_inside)
+                .find(|(_, &i)| i == inside)
+                .and_then(|(cone, _)| specialize(&cone.mesh, false, inside))
+        });
+        let god_ray_pipeline_id = god_rays
+            .volumes
+            .first()
+            .and_then(|volume| specialize(&volume.mesh, true, false));
+        if cone_pipeline_ids.iter().all(Option::is_none) && god_ray_pipeline_id.is_none() {
+            entity_commands.remove::<ViewConeRenderData>();
+            continue;
+        }
+        let resources = pipeline.resources();
 
         let world_from_view = view.world_from_view.compute_matrix();
         let view_from_world = world_from_view.inverse();
@@ -373,7 +462,6 @@ pub(super) fn prepare_view_cone_lights(
             .clip_from_world
             .unwrap_or(view.clip_from_view * view_from_world);
         let inv_view_proj = clip_from_world.inverse();
-        let camera_position = view.world_from_view.translation();
         let viewport = view.viewport;
         let screen_width = viewport.z.max(1) as f32;
         let screen_height = viewport.w.max(1) as f32;
@@ -464,86 +552,181 @@ pub(super) fn prepare_view_cone_lights(
             )
         });
 
-        let mut draws = Vec::new();
-        for cone in &cones.cones {
-            let Some(_render_mesh) = mesh_assets.get(&cone.mesh) else {
+        let mut batches = Vec::new();
+        for (inside, pipeline_id) in [false, true].into_iter().zip(cone_pipeline_ids) {
+            let Some(pipeline_id) = pipeline_id else {
                 continue;
             };
+            let mut draws = Vec::new();
+            for (cone, _) in cones.cones.iter().zip(&cone_inside).filter(|(_, &i)| i == inside) {
+                let Some(_render_mesh) = mesh_assets.get(&cone.mesh) else {
+                    continue;
+                };
+
+                debug_assert!(
+                    cone.range.is_finite() && cone.range > 0.0,
+                    "Cone range invalid: {:?}",
+                    cone.range
+                );
+                debug_assert!(
+                    cone.intensity.is_finite() && cone.intensity >= 0.0,
+                    "Cone intensity invalid: {:?}",
+                    cone.intensity
+                );
+                debug_assert!(
+                    (cone.direction.length_squared() - 1.0).abs() < 1e-3,
+                    "Cone direction not normalized: {:?}",
+                    cone.direction
+                );
+                debug_assert!(
+                    cone.cos_inner >= cone.cos_outer - 1e-3,
+                    "Cone cos_inner < cos_outer: {:?} < {:?}",
+                    cone.cos_inner,
+                    cone.cos_outer
+                );
+
+                let cone_uniform = ConeVolumePerConeUniform {
+                    model: cone.model,
+                    apex: Vec4::new(cone.apex.x, cone.apex.y, cone.apex.z, 1.0),
+                    direction_range: Vec4::new(
+                        cone.direction.x,
+                        cone.direction.y,
+                        cone.direction.z,
+                        cone.range,
+                    ),
+                    color_intensity: Vec4::new(
+                        cone.color.red,
+                        cone.color.green,
+                        cone.color.blue,
+                        cone.intensity,
+                    ),
+                    angles: Vec4::new(cone.cos_inner, cone.cos_outer, 0.0, 0.0),
+                };
+                draws.push(make_cone_draw(
+                    &render_device,
+                    &resources.cone_layout,
+                    &cone_uniform,
+                    cone.mesh.clone(),
+                ));
+            }
+            if !draws.is_empty() {
+                batches.push(ConeDrawBatch { pipeline_id, draws });
+            }
+        }
 
-            debug_assert!(
-                cone.range.is_finite() && cone.range > 0.0,
-                "Cone range invalid: {:?}",
-                cone.range
-            );
-            debug_assert!(
-                cone.intensity.is_finite() && cone.intensity >= 0.0,
-                "Cone intensity invalid: {:?}",
-                cone.intensity
-            );
-            debug_assert!(
-                (cone.direction.length_squared() - 1.0).abs() < 1e-3,
-                "Cone direction not normalized: {:?}",
-                cone.direction
-            );
-            debug_assert!(
-                cone.cos_inner >= cone.cos_outer - 1e-3,
-                "Cone cos_inner < cos_outer: {:?} < {:?}",
-                cone.cos_inner,
-                cone.cos_outer
-            );
-
-            let cone_uniform = ConeVolumePerConeUniform {
-                model: cone.model,
-                apex: Vec4::new(cone.apex.x, cone.apex.y, cone.apex.z, 1.0),
-                direction_range: Vec4::new(
-                    cone.direction.x,
-                    cone.direction.y,
-                    cone.direction.z,
-                    cone.range,
-                ),
-                color_intensity: Vec4::new(
-                    cone.color.red,
-                    cone.color.green,
-                    cone.color.blue,
-                    cone.intensity,
-                ),
-                angles: Vec4::new(cone.cos_inner, cone.cos_outer, 0.0, 0.0),
-            };
-
-            let uniform_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
-                label: Some("cone_volume_cone_uniform"),
-                contents: bytemuck::bytes_of(&cone_uniform),
-                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-            });
-
-            let cone_bind_group = render_device.create_bind_group(
-                Some("cone_volume_cone_bg"),
-                &resources.cone_layout,
-                &[BindGroupEntry {
-                    binding: 0,
-                    resource: uniform_buffer.as_entire_binding(),
-                }],
-            );
-
-            draws.push(ConeDraw {
-                bind_group: cone_bind_group,
-                _uniform_buffer: uniform_buffer,
-                mesh: cone.mesh.clone(),
-            });
+        if let Some(pipeline_id) = god_ray_pipeline_id {
+            let sun = god_rays.sun_direction;
+            let mut draws = Vec::new();
+            for volume in &god_rays.volumes {
+                let Some(_render_mesh) = mesh_assets.get(&volume.mesh) else {
+                    continue;
+                };
+                // Same layout as a cone: the top face stands in for the apex, the sun
+                // direction for the axis, and `angles.x` carries the scattering density
+                let uniform = ConeVolumePerConeUniform {
+                    model: volume.model,
+                    apex: volume.surface.extend(1.0),
+                    direction_range: sun.extend(volume.depth),
+                    color_intensity: Vec4::new(
+                        volume.color.red,
+                        volume.color.green,
+                        volume.color.blue,
+                        god_rays.intensity,
+                    ),
+                    angles: Vec4::new(god_rays.density, 0.0, 0.0, 0.0),
+                };
+                draws.push(make_cone_draw(
+                    &render_device,
+                    &resources.cone_layout,
+                    &uniform,
+                    volume.mesh.clone(),
+                ));
+            }
+            if !draws.is_empty() {
+                batches.push(ConeDrawBatch { pipeline_id, draws });
+            }
         }
 
-        if draws.is_empty() {
+        if batches.is_empty() {
             entity_commands.remove::<ViewConeRenderData>();
             continue;
         }
 
         entity_commands.insert(ViewConeRenderData {
-            pipeline_id,
             global: global_bind_group,
             view: view_bind_group,
             _view_uniform: view_uniform_buffer,
-            draws,
+            batches,
             fog: fog_bind_group,
         });
     }
 }
+
+fn make_cone_draw(
+    render_device: &RenderDevice,
+    layout: &BindGroupLayout,
+    uniform: &ConeVolumePerConeUniform,
+    mesh: Handle<Mesh>,
+) -> ConeDraw {
+    let uniform_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("cone_volume_cone_uniform"),
+        contents: bytemuck::bytes_of(uniform),
+        usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+    });
+
+    let bind_group = render_device.create_bind_group(
+        Some("cone_volume_cone_bg"),
+        layout,
+        &[BindGroupEntry {
+            binding: 0,
+            resource: uniform_buffer.as_entire_binding(),
+        }],
+    );
+
+    ConeDraw {
+        bind_group,
+        _uniform_buffer: uniform_buffer,
+        mesh,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cone() -> RenderConeLight {
+        RenderConeLight {
+            light_entity: Entity::PLACEHOLDER,
+            apex: Vec3::new(0.0, 2.0, 0.0),
+            direction: Vec3::NEG_Z,
+            range: 10.0,
+            intensity: 1.0,
+            color: LinearRgba::WHITE,
+            cos_inner: 0.95,
+            cos_outer: 0.9,
+            mesh: Handle::default(),
+            model: Mat4::IDENTITY,
+            render_inside: false,
+        }
+    }
+
+    #[test]
+    fn camera_at_apex_uses_the_inside_variant() {
+        let cone = cone();
+        assert!(cone_contains_point(&cone, cone.apex));
+        assert!(cone_contains_point(&cone, cone.apex + Vec3::NEG_Z * 5.0));
+        assert!(!cone_contains_point(&cone, cone.apex + Vec3::Z));
+        assert!(!cone_contains_point(&cone, cone.apex + Vec3::new(3.0, 0.0, -1.0)));
+        assert!(!cone_contains_point(&cone, cone.apex + Vec3::NEG_Z * 11.0));
+
+        // Inside: back faces only, never depth-rejected, so the march still gets fragments
+        assert_eq!(
+            cull_and_depth(false, true),
+            (Face::Front, CompareFunction::Always)
+        );
+        assert_eq!(
+            cull_and_depth(false, false),
+            (Face::Back, CompareFunction::GreaterEqual)
+        );
+    }
+}
@@ -14,7 +14,7 @@ use bevy::render::{
     view::{ViewDepthTexture, ViewTarget},
 };
 
-use super::{pipeline::ViewConeRenderData, RenderVolumetricLightingMode, VolumetricLightingMode};
+use super::{pipeline::ViewConeRenderData, RenderVolumetricLightingMode};
 
 #[derive(RenderLabel, Debug, Clone, Hash, PartialEq, Eq)]
 pub struct FloodlightPassLabel;
@@ -42,21 +42,18 @@ impl ViewNode for FloodlightViewNode {
         world: &World,
     ) -> Result<(), NodeRunError> {
         let mode = world.resource::<RenderVolumetricLightingMode>();
-        if mode.0 != VolumetricLightingMode::RaymarchCones {
+        if !mode.0.raymarches() {
             return Ok(());
         }
 
         let Some(render_data) = render_data else {
             return Ok(());
         };
-        if render_data.draws.is_empty() {
+        if render_data.batches.is_empty() {
             return Ok(());
         }
 
         let pipeline_cache = world.resource::<PipelineCache>();
-        let Some(pipeline) = pipeline_cache.get_render_pipeline(render_data.pipeline_id) else {
-            return Ok(());
-        };
 
         let Some(depth_texture) = depth_texture else {
             return Ok(());
@@ -88,49 +85,55 @@ impl ViewNode for FloodlightViewNode {
             render_pass.set_camera_viewport(viewport);
         }
 
-        render_pass.set_render_pipeline(pipeline);
-        render_pass.set_bind_group(0, &render_data.global, &[]); //Global shadow atlas
-        render_pass.set_bind_group(1, &render_data.view, &[]); //depth-stencil texture
-        if let (Some(fog_bg), Some(fog_offset)) = (&render_data.fog, fog_offset) {
-            render_pass.set_bind_group(3, fog_bg, &[fog_offset.offset]); // DistanceFog GPU uniform
-        }
-        for draw in &render_data.draws {
-            let Some(render_mesh) = mesh_assets.get(&draw.mesh) else {
-                continue;
-            };
-            let Some(vertex_slice) = mesh_allocator.mesh_vertex_slice(&draw.mesh.id()) else {
+        for batch in &render_data.batches {
+            let Some(pipeline) = pipeline_cache.get_render_pipeline(batch.pipeline_id) else {
                 continue;
             };
-
-            render_pass.set_bind_group(2, &draw.bind_group, &[]);
-            render_pass.set_vertex_buffer(0, vertex_slice.buffer.slice(..));
-
-            match &render_mesh.buffer_info {
-                RenderMeshBufferInfo::Indexed {
-                    index_format,
-                    count,
-                } => {
-                    let Some(index_slice) = mesh_allocator.mesh_index_slice(&draw.mesh.id()) else {
-                        continue;
-                    };
-                    let index_stride = match index_format {
-                        IndexFormat::Uint16 => 2u64,
-                        IndexFormat::Uint32 => 4u64,
-                    };
-                    let offset = index_slice.range.start as u64 * index_stride;
-                    render_pass.set_index_buffer(
-                        index_slice.buffer.slice(..),
-                        offset,
-                        *index_format,
-                    );
-                    render_pass.draw_indexed(
-                        index_slice.range.start..(index_slice.range.start + count),
-                        vertex_slice.range.start as i32,
-                        0..1,
-                    );
-                }
-                RenderMeshBufferInfo::NonIndexed => {
-                    render_pass.draw(vertex_slice.range.clone(), 0..1);
+            render_pass.set_render_pipeline(pipeline);
+            render_pass.set_bind_group(0, &render_data.global, &[]); //Global shadow atlas
+            render_pass.set_bind_group(1, &render_data.view, &[]); //depth-stencil texture
+            if let (Some(fog_bg), Some(fog_offset)) = (&render_data.fog, fog_offset) {
+                render_pass.set_bind_group(3, fog_bg, &[fog_offset.offset]); // DistanceFog GPU uniform
+            }
+            for draw in &batch.draws {
+                let Some(render_mesh) = mesh_assets.get(&draw.mesh) else {
+                    continue;
+                };
+                let Some(vertex_slice) = mesh_allocator.mesh_vertex_slice(&draw.mesh.id()) else {
+                    continue;
+                };
+
+                render_pass.set_bind_group(2, &draw.bind_group, &[]);
+                render_pass.set_vertex_buffer(0, vertex_slice.buffer.slice(..));
+
+                match &render_mesh.buffer_info {
+                    RenderMeshBufferInfo::Indexed {
+                        index_format,
+                        count,
+                    } => {
+                        let Some(index_slice) = mesh_allocator.mesh_index_slice(&draw.mesh.id())
+                        else {
+                            continue;
+                        };
+                        let index_stride = match index_format {
+                            IndexFormat::Uint16 => 2u64,
+                            IndexFormat::Uint32 => 4u64,
+                        };
+                        let offset = index_slice.range.start as u64 * index_stride;
+                        render_pass.set_index_buffer(
+                            index_slice.buffer.slice(..),
+                            offset,
+                            *index_format,
+                        );
+                        render_pass.draw_indexed(
+                            index_slice.range.start..(index_slice.range.start + count),
+                            vertex_slice.range.start as i32,
+                            0..1,
+                        );
+                    }
+                    RenderMeshBufferInfo::NonIndexed => {
+                        render_pass.draw(vertex_slice.range.clone(), 0..1);
+                    }
                 }
             }
         }
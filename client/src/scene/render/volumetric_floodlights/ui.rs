@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+#[cfg(feature = "windowing")]
+use bevy_inspector_egui::bevy_egui::EguiContexts;
 
 use super::{VolumetricLightingMode, VolumetricLightingState};
 
@@ -11,16 +13,19 @@ pub(super) fn toggle_volumetric_mode(
 ) {
     if keys.just_pressed(KeyCode::KeyV) {
         state.mode = match state.mode {
-            VolumetricLightingMode::RaymarchCones => VolumetricLightingMode::Disabled,
             VolumetricLightingMode::Disabled => VolumetricLightingMode::RaymarchCones,
+            VolumetricLightingMode::RaymarchCones => VolumetricLightingMode::DebugHeatmap,
+            VolumetricLightingMode::DebugHeatmap => VolumetricLightingMode::Disabled,
         };
-        println!(
-            "Volumetric mode: {}",
-            match state.mode {
-                VolumetricLightingMode::RaymarchCones => "Raymarch",
-                VolumetricLightingMode::Disabled => "Disabled",
-            }
-        );
+        println!("Volumetric mode: {}", mode_name(state.mode));
+    }
+}
+
+fn mode_name(mode: VolumetricLightingMode) -> &'static str {
+    match mode {
+        VolumetricLightingMode::RaymarchCones => "raymarch",
+        VolumetricLightingMode::DebugHeatmap => "step heatmap",
+        VolumetricLightingMode::Disabled => "off",
     }
 }
 
@@ -50,11 +55,37 @@ pub(super) fn update_mode_label(
     if !state.is_changed() {
         return;
     }
-    let text = match state.mode {
-        VolumetricLightingMode::RaymarchCones => "Volumetrics: raymarch [V]",
-        VolumetricLightingMode::Disabled => "Volumetrics: off [V]",
-    };
+    let text = format!("Volumetrics: {} [V]", mode_name(state.mode));
     for mut t in &mut q {
-        *t = Text::new(text);
+        *t = Text::new(text.clone());
+    }
+}
+
+#[cfg(feature = "windowing")]
+pub(super) fn ui_god_rays(mut egui_ctx: EguiContexts, mut state: ResMut<VolumetricLightingState>) {
+    use bevy_inspector_egui::egui::*;
+    let Ok(ctx) = egui_ctx.ctx_mut() else {
+        return;
+    };
+
+    // Edit a copy so merely drawing the window does not mark the state changed
+    let mut god_rays = state.god_rays;
+    Window::new("God Rays")
+        .anchor(Align2::RIGHT_BOTTOM, [-10.0, -60.0])
+        .resizable(false)
+        .default_open(false)
+        .show(ctx, |ui| {
+            ui.checkbox(&mut god_rays.enabled, "Enabled");
+            ui.add_enabled_ui(god_rays.enabled, |ui| {
+                ui.add(Slider::new(&mut god_rays.intensity, 0.0..=5.0).text("Intensity"));
+                ui.add(Slider::new(&mut god_rays.density, 0.0..=2.0).text("Density"));
+                ui.label("Sun direction");
+                ui.add(Slider::new(&mut god_rays.sun_direction.x, -1.0..=1.0).text("x"));
+                ui.add(Slider::new(&mut god_rays.sun_direction.y, -1.0..=-0.05).text("y"));
+                ui.add(Slider::new(&mut god_rays.sun_direction.z, -1.0..=1.0).text("z"));
+            });
+        });
+    if god_rays != state.god_rays {
+        state.god_rays = god_rays;
     }
 }
@@ -3,6 +3,8 @@ use bevy::pbr::{MeshMaterial3d, StandardMaterial};
 use bevy::prelude::Mesh3d;
 use bevy::prelude::*;
 
+use super::render::volumetric_floodlights::GodRaySpec;
+
 // Cameras are spawned in world.rs (single unified camera)
 
 pub fn spawn_box(
@@ -53,5 +55,16 @@ pub fn setup_scene(mut commands: Commands) {
         Name::new("Sun Light"),
     ));
 
+    // Sunlight shafts filling the station room, entering through its ceiling at y = 48
+    commands.spawn((
+        GodRaySpec {
+            half_extents: Vec3::new(120.0, 24.0, 120.0),
+            color: Color::srgb(0.55, 0.8, 0.85),
+        },
+        Transform::from_xyz(0.0, 24.0, 0.0),
+        Visibility::default(),
+        Name::new("God Rays"),
+    ));
+
     // No camera spawned here; world::spawn_greybox creates the single GameCamera.
 }
@@ -1,13 +1,21 @@
 use bevy::animation::{animated_field, AnimationTarget, AnimationTargetId};
+use bevy::ecs::query::QueryItem;
 use bevy::prelude::*;
 use bevy::render::mesh::Indices;
 use bevy::render::render_resource::PrimitiveTopology;
+use bevy::utils::Parallel;
+use std::collections::VecDeque;
 
-use levels::{builtins::greybox_level, SubInputState, SubInputs, SubState, SubStepDebug};
 use levels::{step_submarine_dbg, SubPhysicsSpec};
+use levels::{SubInputState, SubState, SubStepDebug};
 
+use super::crash_dump::{is_divergent, PhysicsCrashDump, PhysicsDiverged};
+use crate::campaign::CurrentLevel;
 use crate::net::FilteredServerState;
-use crate::sim_pause::SimPause;
+use crate::physics_diagnostics::ProfiledPhysicsStep;
+use crate::reconnect::{ReconnectPending, ReconnectPolicy};
+use crate::rollback::{InputHistory, InputHistoryEntry};
+use crate::sim_pause::{is_reconnecting, SimPause};
 
 #[derive(Component)]
 pub struct Submarine;
@@ -21,6 +29,16 @@ pub struct AngularVelocity(pub Vec3);
 #[derive(Component)]
 pub struct Rudder;
 
+/// Dive plane hinged at its quarter chord; one per side at each station.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivePlane {
+    Fore,
+    Aft,
+}
+
+/// Full-input dive plane deflection (radians, ~20 degrees).
+pub const MAX_DIVE_PLANE_ANGLE: f32 = 0.35;
+
 #[allow(dead_code)]
 #[derive(Component, Clone)]
 pub struct SubPhysics(pub SubPhysicsSpec);
@@ -31,6 +49,34 @@ pub struct SubStateComp(pub SubState);
 #[derive(Component, Debug, Clone, Default)]
 pub struct SubInputStateComp(pub SubInputState);
 
+/// Most predicted states `SubStateHistory` keeps (~4 s at 120 Hz).
+pub const SUB_STATE_HISTORY_LEN: usize = 512;
+
+/// Predicted state after each fixed step, keyed by `ClientPhysicsTiming::tick`, so a server
+/// correction can be logged next to what the client believed at the same tick. Only filled in
+/// debug builds.
+#[derive(Component, Debug, Clone, Default)]
+pub struct SubStateHistory {
+    pub entries: VecDeque<(u64, SubState)>,
+}
+
+impl SubStateHistory {
+    pub fn push(&mut self, tick: u64, state: SubState) {
+        if self.entries.len() >= SUB_STATE_HISTORY_LEN {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((tick, state));
+    }
+
+    pub fn at(&self, tick: u64) -> Option<&SubState> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(t, _)| *t == tick)
+            .map(|(_, s)| s)
+    }
+}
+
 #[derive(Component, Debug, Clone)]
 #[allow(dead_code)]
 pub struct ServerCorrection {
@@ -45,13 +91,17 @@ pub struct ServerCorrection {
 #[derive(Component)]
 pub struct NetControlled;
 
-#[derive(Resource, Debug, Clone, Default)]
+#[derive(Resource, Debug, Clone, Default, Reflect)]
+#[reflect(Resource)]
 pub struct SubTelemetry(pub SubStepDebug);
 
 #[derive(Resource, Debug, Clone, Copy)]
 pub struct ClientPhysicsTiming {
     pub acc: f32,
     pub dt: f32,
+    /// Fixed steps predicted so far, in server ticks: `apply_state_to_sub` aligns it with the
+    /// snapshot tick whenever it (re)starts from the server's state.
+    pub tick: u64,
 }
 
 impl Default for ClientPhysicsTiming {
@@ -60,72 +110,99 @@ impl Default for ClientPhysicsTiming {
         Self {
             acc: 0.0,
             dt: 1.0 / 120.0,
+            tick: 0,
         }
     }
 }
 
-pub fn update_sub_input_state(
+/// Ramp the commanded input state toward the raw controls so keyboard steps
+/// (0 -> 1) don't reach the physics as instantaneous thrust changes.
+pub fn ramp_inputs(
+    time: Res<Time>,
     controls: Option<Res<crate::ThrustInput>>,
     filtered: Option<Res<FilteredServerState>>,
-    mut q: Query<&mut SubInputStateComp, With<Submarine>>,
+    mut q: Query<(&mut SubInputStateComp, &SubPhysics), With<Submarine>>,
 ) {
     let inputs = controls
         .as_ref()
-        .map(|c| SubInputs {
-            thrust: c.value,
-            yaw: c.yaw,
-            pump_fwd: c.pump_fwd,
-            pump_aft: c.pump_aft,
-        })
+        .map(|c| c.as_sub_inputs())
         .unwrap_or_default();
     let server_input = filtered
         .as_ref()
         .filter(|f| f.initialized)
         .map(|f| f.input_state);
-    for mut state in &mut q {
-        let mut desired = SubInputState::from_inputs(inputs);
+    let dt = time.delta_secs();
+    for (mut state, spec) in &mut q {
+        let mut target = inputs;
         if let Some(server) = server_input {
             let alpha = 0.25_f32;
-            desired.thrust += alpha * (server.thrust - desired.thrust);
-            desired.yaw += alpha * (server.yaw - desired.yaw);
-            desired.pump_fwd += alpha * (server.pump_fwd - desired.pump_fwd);
-            desired.pump_aft += alpha * (server.pump_aft - desired.pump_aft);
+            target.thrust += alpha * (server.thrust - target.thrust);
+            target.yaw += alpha * (server.yaw - target.yaw);
+            target.pump_fwd += alpha * (server.pump_fwd - target.pump_fwd);
+            target.pump_aft += alpha * (server.pump_aft - target.pump_aft);
         }
-        state.0 = desired;
+        state.0.ramp_toward(target, dt, &spec.0);
     }
 }
 // Quatf is the same type as Bevy's Quat (re-exported from bevy_math).
 
-#[allow(clippy::type_complexity)]
+type SimulatedSubData = (
+    Entity,
+    &'static mut Transform,
+    &'static mut Velocity,
+    &'static mut SubStateComp,
+    &'static SubPhysics,
+    Option<&'static ServerCorrection>,
+    &'static mut AngularVelocity,
+    Option<&'static NetControlled>,
+    &'static SubInputStateComp,
+    Option<&'static mut SubStateHistory>,
+);
+type SimulatedSub<'a> = QueryItem<'a, SimulatedSubData>;
+
+/// One submarine's steps this frame, merged into the shared resources once every sub has run.
+struct SubPrediction {
+    entity: Entity,
+    history: Vec<InputHistoryEntry>,
+    steps: Vec<SubStepDebug>,
+    diverged: bool,
+}
+
+/// Predict every local submarine forward by the whole fixed steps accumulated this frame.
+///
+/// With the `parallel_physics` feature the subs are stepped on the compute task pool. Each
+/// sub only touches its own components and queues its history, debug steps and divergence
+/// on a thread-local list; those are merged into `InputHistory`, `PhysicsCrashDump` and
+/// `SubTelemetry` in entity order after the loop, so nothing shared is locked per step.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn simulate_submarine(
     time: Res<Time>,
-    mut q_sub: Query<
-        (
-            &mut Transform,
-            &mut Velocity,
-            &mut SubStateComp,
-            &SubPhysics,
-            Option<&ServerCorrection>,
-            &mut AngularVelocity,
-            Option<&NetControlled>,
-            &SubInputStateComp,
-        ),
-        With<Submarine>,
-    >,
+    mut commands: Commands,
+    mut q_sub: Query<SimulatedSubData, (With<Submarine>, Without<PhysicsDiverged>)>,
     controls: Option<Res<crate::ThrustInput>>,
     mut telemetry: ResMut<SubTelemetry>,
+    mut crash_dump: ResMut<PhysicsCrashDump>,
     paused: Res<SimPause>,
     mut timing: ResMut<ClientPhysicsTiming>,
+    reconnect_pending: Option<Res<ReconnectPending>>,
+    reconnect_policy: Option<Res<ReconnectPolicy>>,
+    level: Res<CurrentLevel>,
+    mut input_history: ResMut<InputHistory>,
+    profiled: Option<Res<ProfiledPhysicsStep>>,
 ) {
+    #[cfg(debug_assertions)]
+    let _timer =
+        crate::system_timings::ScopeTimer::start(crate::system_timings::TimedSystem::Simulate);
     let frame_dt = time.delta_secs();
     if frame_dt <= 0.0 {
         return;
     }
-    if paused.0 {
+    if paused.paused {
         timing.acc = 0.0; // avoid catch-up on resume
         return;
     }
-    timing.acc += frame_dt;
+    let reconnecting = is_reconnecting(reconnect_pending.as_deref(), reconnect_policy.as_deref());
+    timing.acc += paused.scaled_dt(frame_dt, reconnecting);
     let step_dt = timing.dt.max(1e-4);
     let mut steps: u32 = 0;
     while timing.acc >= step_dt {
@@ -135,22 +212,19 @@ pub fn simulate_submarine(
     if steps == 0 {
         return;
     }
+    let tick0 = timing.tick;
+    timing.tick += steps as u64;
 
-    // Build a transient LevelSpec identical to what's spawned (use the builtin for now)
-    let level = greybox_level();
+    // Predict against the same level the server simulates
+    let level = level.spec();
 
-    let raw_inputs = if let Some(c) = controls {
-        SubInputs {
-            thrust: c.value,
-            yaw: c.yaw,
-            pump_fwd: c.pump_fwd,
-            pump_aft: c.pump_aft,
-        }
-    } else {
-        SubInputs::default()
-    };
+    let raw_inputs = controls.map(|c| c.as_sub_inputs()).unwrap_or_default();
+    let t0 = time.elapsed_secs() - (timing.acc + steps as f32 * step_dt);
+    let mut predictions = Parallel::<Vec<SubPrediction>>::default();
+    let profiled = profiled.as_deref();
 
-    for (
+    let predict = |(
+        entity,
         mut transform,
         mut vel,
         mut state_comp,
@@ -159,8 +233,8 @@ pub fn simulate_submarine(
         mut ang_vel_comp,
         _net,
         input_state,
-    ) in &mut q_sub
-    {
+        _history,
+    ): SimulatedSub| {
         // Map visual mesh (+X forward) to physics body (+Z forward): yaw +90 deg
         let body_from_mesh = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
         let mesh_from_body = body_from_mesh.conjugate();
@@ -174,36 +248,61 @@ pub fn simulate_submarine(
                 ),
                 velocity: levels::Vec3f::new(vel.x, vel.y, vel.z),
                 orientation: transform.rotation * body_from_mesh,
-                ang_mom: {
-                    let ixx = spec.0.ixx.max(0.0);
-                    let iyy = spec.0.iyy.max(0.0);
-                    let izz = spec.0.izz.max(0.0);
-                    levels::Vec3f::new(
-                        ang_vel_comp.x * ixx,
-                        ang_vel_comp.y * iyy,
-                        ang_vel_comp.z * izz,
-                    )
-                },
+                ang_mom: spec.0.rotational_inertia().max(levels::Vec3f::ZERO) * **ang_vel_comp,
                 ballast_fill: vec![0.5; spec.0.ballast_tanks.len()],
+                pump: Default::default(),
             };
         }
         let mut state = state_comp.0.clone();
+        #[cfg(debug_assertions)]
+        let mut history = _history;
+        let mut prediction = SubPrediction {
+            entity,
+            history: Vec::with_capacity(steps as usize),
+            steps: Vec::with_capacity(steps as usize),
+            diverged: false,
+        };
         // Fixed-step loop; advance time parameter for flow sampling consistently
-        let t0 = time.elapsed_secs() - (timing.acc + steps as f32 * step_dt);
         for i in 0..steps {
             let mut dbg = SubStepDebug::default();
             let t_sub = t0 + (i + 1) as f32 * step_dt;
-            step_submarine_dbg(
-                &level,
-                &spec.0,
-                input_state.0,
-                &mut state,
-                step_dt,
-                t_sub,
-                Some(&mut dbg),
-            );
+            let mut step = || {
+                step_submarine_dbg(
+                    level,
+                    &spec.0,
+                    input_state.0,
+                    &mut state,
+                    step_dt,
+                    t_sub,
+                    Some(&mut dbg),
+                )
+            };
+            match profiled {
+                Some(profiled) => profiled.measure(step),
+                None => step(),
+            };
             dbg.raw_inputs = Some(raw_inputs);
-            telemetry.0 = dbg; // store last step's diagnostics
+            prediction.history.push(InputHistoryEntry {
+                tick: tick0 + i as u64 + 1,
+                inputs: input_state.0,
+                time: t_sub,
+                state: state.clone(),
+            });
+            #[cfg(debug_assertions)]
+            if let Some(history) = history.as_mut() {
+                history.push(tick0 + i as u64 + 1, state.clone());
+            }
+            prediction.steps.push(dbg);
+            if is_divergent(&state) {
+                // Keep the last good state and leave this sub to server corrections
+                prediction.diverged = true;
+                break;
+            }
+        }
+        let diverged = prediction.diverged;
+        predictions.borrow_local_mut().push(prediction);
+        if diverged {
+            return;
         }
         // Persist state back to component
         state_comp.0 = state.clone();
@@ -213,25 +312,63 @@ pub fn simulate_submarine(
 
         **vel = Vec3::new(state.velocity.x, state.velocity.y, state.velocity.z);
         // Update client-side rates from body angular momentum
-        let wx = if spec.0.ixx > 0.0 {
-            state.ang_mom.x / spec.0.ixx
+        let inertia = spec.0.rotational_inertia();
+        let wx = if inertia.x > 0.0 {
+            state.ang_mom.x / inertia.x
         } else {
             0.0
         };
-        let wy = if spec.0.iyy > 0.0 {
-            state.ang_mom.y / spec.0.iyy
+        let wy = if inertia.y > 0.0 {
+            state.ang_mom.y / inertia.y
         } else {
             0.0
         };
-        let wz = if spec.0.izz > 0.0 {
-            state.ang_mom.z / spec.0.izz
+        let wz = if inertia.z > 0.0 {
+            state.ang_mom.z / inertia.z
         } else {
             0.0
         };
         **ang_vel_comp = Vec3::new(wx, wy, wz);
+    };
+
+    #[cfg(feature = "parallel_physics")]
+    q_sub.par_iter_mut().for_each(predict);
+    #[cfg(not(feature = "parallel_physics"))]
+    q_sub.iter_mut().for_each(predict);
+
+    let mut merged = Vec::new();
+    predictions.drain_into(&mut merged);
+    // Parallel tasks finish in any order; merge the way the sequential loop would have run
+    merged.sort_unstable_by_key(|p| p.entity);
+    for prediction in merged {
+        for entry in prediction.history {
+            input_history.push(entry);
+        }
+        for dbg in prediction.steps {
+            crash_dump.push(dbg);
+            telemetry.0 = dbg;
+        }
+        if prediction.diverged {
+            match crash_dump.write() {
+                Ok(path) => tracing::error!(?path, "Physics divergence detected, wrote crash dump"),
+                Err(err) => tracing::error!(?err, "Physics divergence detected, crash dump failed"),
+            }
+            commands.entity(prediction.entity).insert(PhysicsDiverged);
+        }
     }
 }
 
+/// Mirror the local sub's predicted state into `ThrustInput` for HUDs and headless tools.
+pub fn sync_telemetry_to_input(
+    controls: Option<ResMut<crate::ThrustInput>>,
+    q_sub: Query<&SubStateComp, With<Submarine>>,
+) {
+    let (Some(mut controls), Ok(state)) = (controls, q_sub.single()) else {
+        return;
+    };
+    controls.sync_from_state(&state.0);
+}
+
 pub fn apply_server_corrections(
     time: Res<Time>,
     mut commands: Commands,
@@ -246,18 +383,12 @@ pub fn apply_server_corrections(
         With<Submarine>,
     >,
     controls: Option<Res<crate::ThrustInput>>,
-    filtered: Option<Res<FilteredServerState>>,
 ) {
     let dt = time.delta_secs();
     if dt <= 0.0 {
         return;
     }
 
-    let filtered_state = filtered
-        .as_ref()
-        .filter(|f| f.initialized)
-        .map(|f| (**f).clone());
-
     for (e, mut t, mut v, mut state_comp, mut corr) in &mut q {
         let yaw_input_mag = controls.as_ref().map(|c| c.yaw.abs()).unwrap_or(0.0);
         let steering = yaw_input_mag > 0.05;
@@ -274,23 +405,13 @@ pub fn apply_server_corrections(
         t.rotation = t.rotation.slerp(corr.target_rot, alpha_rot);
         **v = (**v).lerp(corr.target_vel, alpha_vel);
 
-        if let Some(filtered) = filtered_state.as_ref() {
-            state_comp.0.position =
-                levels::Vec3f::new(filtered.pos.x, filtered.pos.y, filtered.pos.z);
-            state_comp.0.velocity =
-                levels::Vec3f::new(filtered.vel.x, filtered.vel.y, filtered.vel.z);
-            state_comp.0.orientation = filtered.body_rot;
-            state_comp.0.ang_mom =
-                levels::Vec3f::new(filtered.ang_mom.x, filtered.ang_mom.y, filtered.ang_mom.z);
-            state_comp.0.ballast_fill = filtered.ballast_fill.clone();
-        } else {
-            let current_pos = t.translation;
-            let current_vel = **v;
-            state_comp.0.position = levels::Vec3f::new(current_pos.x, current_pos.y, current_pos.z);
-            state_comp.0.velocity = levels::Vec3f::new(current_vel.x, current_vel.y, current_vel.z);
-            let body_from_mesh = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
-            state_comp.0.orientation = t.rotation * body_from_mesh;
-        }
+        // Nudge the prediction itself; the targets are already at the current tick
+        let current_pos = t.translation;
+        let current_vel = **v;
+        state_comp.0.position = levels::Vec3f::new(current_pos.x, current_pos.y, current_pos.z);
+        state_comp.0.velocity = levels::Vec3f::new(current_vel.x, current_vel.y, current_vel.z);
+        let body_from_mesh = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        state_comp.0.orientation = t.rotation * body_from_mesh;
 
         corr.elapsed += dt;
         let pos_err = t.translation.distance(corr.target_pos);
@@ -393,6 +514,90 @@ pub fn make_rudder_prism_mesh(length: f32, height: f32, thickness: f32) -> Mesh
     mesh
 }
 
+pub fn animate_dive_planes(
+    controls: Option<Res<crate::ThrustInput>>,
+    mut q_planes: Query<(&DivePlane, &mut Transform)>,
+) {
+    let input = controls
+        .as_ref()
+        .map(|c| c.plane)
+        .unwrap_or(0.0)
+        .clamp(-1.0, 1.0);
+    for (plane, mut t) in &mut q_planes {
+        // Mesh frame is +X forward, so +Z rotation lifts the leading edge (trailing edge down).
+        // Diving pushes the bow down and the stern up: fore trailing edges up, aft down.
+        let sign = match plane {
+            DivePlane::Fore => -1.0,
+            DivePlane::Aft => 1.0,
+        };
+        t.rotation = Quat::from_rotation_z(sign * input * MAX_DIVE_PLANE_ANGLE);
+    }
+}
+
+/// Thin trapezoidal plate in the XZ plane, hinged at the origin (quarter chord of the root).
+/// The root sits on z = 0 and the tip at z = `span`; a negative `span` builds the mirror-image
+/// plate for the other side. The tip chord is `chord * taper`.
+pub fn make_dive_plane_mesh(span: f32, chord: f32, taper: f32) -> Mesh {
+    let thickness = chord * 0.06;
+    let tip_chord = chord * taper;
+    // Leading edge ahead of the hinge (+X), trailing edge behind it
+    let outline = [
+        Vec3::new(chord * 0.25, 0.0, 0.0),
+        Vec3::new(tip_chord * 0.25, 0.0, span),
+        Vec3::new(-tip_chord * 0.75, 0.0, span),
+        Vec3::new(-chord * 0.75, 0.0, 0.0),
+    ];
+    let center = outline.iter().copied().sum::<Vec3>() / outline.len() as f32;
+    let top = outline.map(|p| p + Vec3::Y * thickness * 0.5);
+    let bottom = outline.map(|p| p - Vec3::Y * thickness * 0.5);
+
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    // Counter-clockwise winding around the outward normal `n`, whichever way `span` points
+    let mut add_tri = |v0: Vec3, v1: Vec3, v2: Vec3, n: Vec3| {
+        let (v1, v2) = if (v1 - v0).cross(v2 - v0).dot(n) < 0.0 {
+            (v2, v1)
+        } else {
+            (v1, v2)
+        };
+        let base = positions.len() as u32;
+        for v in [v0, v1, v2] {
+            positions.push(v.to_array());
+            normals.push(n.to_array());
+            uvs.push([(v.x + chord * 0.75) / chord, v.z / span]);
+        }
+        indices.extend_from_slice(&[base, base + 1, base + 2]);
+    };
+
+    // Top and bottom faces
+    add_tri(top[0], top[1], top[2], Vec3::Y);
+    add_tri(top[0], top[2], top[3], Vec3::Y);
+    add_tri(bottom[0], bottom[1], bottom[2], -Vec3::Y);
+    add_tri(bottom[0], bottom[2], bottom[3], -Vec3::Y);
+
+    // Edge faces, normals pointing away from the plate center
+    for i in 0..outline.len() {
+        let j = (i + 1) % outline.len();
+        let edge = outline[j] - outline[i];
+        let mut n = edge.cross(Vec3::Y).normalize_or_zero();
+        if n.dot((outline[i] + outline[j]) * 0.5 - center) < 0.0 {
+            n = -n;
+        }
+        add_tri(top[i], top[j], bottom[j], n);
+        add_tri(top[i], bottom[j], bottom[i], n);
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
 const SWIVEL_PERIOD: f32 = 4.0f32;
 const SWIVEL_DEG: f32 = 15.0f32;
 const SAMPLES: u32 = 40;
@@ -442,3 +647,178 @@ pub fn make_swivel_clip(
             player: light_entity,
         });
 }
+
+/// Raw text of a `specs/*.ron` tuning file; parsed on use so a bad edit can be reported
+/// instead of failing the asset load.
+#[cfg(debug_assertions)]
+#[derive(Asset, TypePath, Debug)]
+pub struct SubSpecSource(pub String);
+
+#[cfg(debug_assertions)]
+#[derive(Default)]
+struct SubSpecSourceLoader;
+
+#[cfg(debug_assertions)]
+impl bevy::asset::AssetLoader for SubSpecSourceLoader {
+    type Asset = SubSpecSource;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn bevy::asset::io::Reader,
+        _settings: &(),
+        _load_context: &mut bevy::asset::LoadContext<'_>,
+    ) -> Result<SubSpecSource, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        String::from_utf8(bytes)
+            .map(SubSpecSource)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["sub.ron"]
+    }
+}
+
+#[cfg(debug_assertions)]
+#[derive(Resource)]
+struct SubSpecHandle(Handle<SubSpecSource>);
+
+/// Debug-only: re-apply `assets/specs/small_skiff.sub.ron` to the local sub whenever the file
+/// changes on disk, so physics tuning does not need a recompile.
+#[cfg(debug_assertions)]
+pub struct SubSpecHotReloadPlugin;
+
+#[cfg(debug_assertions)]
+impl Plugin for SubSpecHotReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SubSpecSource>()
+            .init_asset_loader::<SubSpecSourceLoader>()
+            .add_systems(Startup, load_sub_spec_source)
+            .add_systems(Update, apply_reloaded_sub_spec);
+    }
+}
+
+#[cfg(debug_assertions)]
+fn load_sub_spec_source(mut commands: Commands, assets: Res<AssetServer>) {
+    commands.insert_resource(SubSpecHandle(assets.load("specs/small_skiff.sub.ron")));
+}
+
+#[cfg(debug_assertions)]
+fn apply_reloaded_sub_spec(
+    mut events: EventReader<AssetEvent<SubSpecSource>>,
+    handle: Option<Res<SubSpecHandle>>,
+    sources: Res<Assets<SubSpecSource>>,
+    mut log: ResMut<crate::notifications::NotificationLog>,
+    mut q_sub: Query<&mut SubPhysics, With<Submarine>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        // Only edits count; the initial load mirrors the built-in spec
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        if *id != handle.0.id() {
+            continue;
+        }
+        let Some(source) = sources.get(*id) else {
+            continue;
+        };
+        let result = SubPhysicsSpec::from_ron_str(&source.0)
+            .map_err(|e| e.to_string())
+            .and_then(|spec| spec.validate().map(|_| spec));
+        match result {
+            Ok(spec) => {
+                for mut physics in &mut q_sub {
+                    physics.0 = spec.clone();
+                }
+                tracing::info!("Sub physics spec reloaded");
+                log.push("SPEC RELOADED");
+            }
+            Err(err) => {
+                tracing::warn!(%err, "Rejected sub physics spec reload; keeping previous spec");
+                log.push(format!("SPEC INVALID: {err}"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::mesh::VertexAttributeValues;
+
+    #[test]
+    fn dive_plane_faces_wind_outward_on_both_sides() {
+        for span in [0.45, -0.45] {
+            let mesh = make_dive_plane_mesh(span, 0.4, 0.6);
+            let Some(VertexAttributeValues::Float32x3(positions)) =
+                mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            else {
+                panic!("mesh has positions");
+            };
+            let Some(VertexAttributeValues::Float32x3(normals)) =
+                mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+            else {
+                panic!("mesh has normals");
+            };
+            let Some(Indices::U32(indices)) = mesh.indices() else {
+                panic!("mesh has u32 indices");
+            };
+            for tri in indices.chunks_exact(3) {
+                let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| Vec3::from(positions[i as usize]));
+                let n = Vec3::from(normals[tri[0] as usize]);
+                assert!(
+                    (b - a).cross(c - a).dot(n) > 0.0,
+                    "span {span}: triangle {tri:?} winds against its normal"
+                );
+            }
+            let max_z = positions
+                .iter()
+                .map(|p| p[2] * span.signum())
+                .fold(0.0, f32::max);
+            assert!((max_z - span.abs()).abs() < 1e-5, "tip at z = span");
+        }
+    }
+
+    #[test]
+    fn shipped_spec_asset_matches_builtin_skiff() {
+        let text = include_str!("../../assets/specs/small_skiff.sub.ron");
+        let spec = SubPhysicsSpec::from_ron_str(text).expect("asset parses");
+        spec.validate().expect("asset validates");
+        assert_eq!(spec, levels::subspecs::small_skiff_spec());
+    }
+
+    #[test]
+    fn state_history_stays_capped_under_rapid_stepping() {
+        let level = levels::builtins::greybox_level();
+        let spec = levels::subspecs::small_skiff_spec();
+        let inputs = SubInputState {
+            thrust: 1.0,
+            yaw: 0.5,
+            ..Default::default()
+        };
+        let mut state = SubState {
+            position: levels::Vec3f::ZERO,
+            velocity: levels::Vec3f::ZERO,
+            orientation: Quat::IDENTITY,
+            ang_mom: levels::Vec3f::ZERO,
+            ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+            pump: Default::default(),
+        };
+        let mut history = SubStateHistory::default();
+        for tick in 1..=2000u64 {
+            levels::step_submarine(&level, &spec, inputs, &mut state, 1.0 / 120.0, 0.0);
+            history.push(tick, state.clone());
+            assert!(history.entries.len() <= SUB_STATE_HISTORY_LEN);
+        }
+        assert_eq!(history.entries.len(), SUB_STATE_HISTORY_LEN);
+        assert_eq!(history.at(2000), Some(&state));
+        assert!(history.at(2000 - SUB_STATE_HISTORY_LEN as u64).is_none());
+        assert!(history.at(2001 - SUB_STATE_HISTORY_LEN as u64).is_some());
+    }
+}
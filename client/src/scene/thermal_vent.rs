@@ -0,0 +1,137 @@
+use bevy::image::{ImageAddressMode, ImageFilterMode, ImageSampler, ImageSamplerDescriptor};
+use bevy::math::Affine2;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::campaign::CurrentLevel;
+
+/// Visual for a thermal vent column; the material's UVs scroll upward to read as rising heat.
+#[derive(Component)]
+pub struct ThermalVent {
+    material: Handle<StandardMaterial>,
+    scroll_speed: f32,
+}
+
+pub struct ThermalVentPlugin;
+
+impl Plugin for ThermalVentPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_thermal_vents.run_if(resource_changed::<CurrentLevel>),
+                animate_thermal_vents,
+            ),
+        );
+    }
+}
+
+/// Replace all vent columns with the current level's.
+fn spawn_thermal_vents(
+    mut commands: Commands,
+    level: Res<CurrentLevel>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    q_vents: Query<Entity, With<ThermalVent>>,
+) {
+    for entity in &q_vents {
+        commands.entity(entity).despawn();
+    }
+    let level = level.spec();
+    if level.thermal_vents.is_empty() {
+        return;
+    }
+    let streaks = images.add(make_heat_streak_image(64, 256));
+    for (i, vent) in level.thermal_vents.iter().enumerate() {
+        let material = materials.add(StandardMaterial {
+            base_color: Color::srgba(1.0, 0.55, 0.25, 0.35),
+            base_color_texture: Some(streaks.clone()),
+            emissive: LinearRgba::rgb(0.9, 0.35, 0.1),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            double_sided: true,
+            cull_mode: None,
+            // Tile the streaks a few times around the circumference and once per ~8 m of height
+            uv_transform: Affine2::from_scale(Vec2::new(3.0, (vent.height_m / 8.0).max(1.0))),
+            ..Default::default()
+        });
+        let center = Vec3::new(
+            vent.position.x,
+            vent.position.y + vent.height_m * 0.5,
+            vent.position.z,
+        );
+        commands.spawn((
+            Mesh3d(meshes.add(Cylinder::new(vent.radius_m, vent.height_m))),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(center),
+            ThermalVent {
+                material,
+                scroll_speed: vent.upwelling_m_s / 8.0,
+            },
+            Name::new(format!("Thermal Vent {i}")),
+        ));
+    }
+}
+
+fn animate_thermal_vents(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q: Query<(&ThermalVent, &mut Transform)>,
+) {
+    let t = time.elapsed_secs();
+    for (vent, mut transform) in &mut q {
+        if let Some(mat) = materials.get_mut(&vent.material) {
+            // Scroll upward and wobble sideways a little for a shimmering look
+            mat.uv_transform.translation = Vec2::new(
+                0.03 * (t * 1.7).sin(),
+                -(t * vent.scroll_speed).rem_euclid(1.0),
+            );
+        }
+        let wobble = 1.0 + 0.03 * (t * 2.3).sin();
+        transform.scale = Vec3::new(wobble, 1.0, 2.0 - wobble);
+    }
+}
+
+/// Tileable vertical streaks with soft alpha, brighter toward the column base (v = 1).
+fn make_heat_streak_image(w: usize, h: usize) -> Image {
+    let mut data = vec![0u8; w * h * 4];
+    for y in 0..h {
+        let v = y as f32 / h as f32;
+        for x in 0..w {
+            let u = x as f32 / w as f32;
+            let tau = std::f32::consts::TAU;
+            // Sum of periodic waves keeps the image tileable in both directions
+            let streak = 0.5
+                + 0.25 * (tau * (u * 5.0 + 0.3 * (tau * v * 2.0).sin())).sin()
+                + 0.25 * (tau * (u * 11.0 - 0.2 * (tau * v * 3.0).cos())).sin();
+            let lum = streak.clamp(0.0, 1.0);
+            let alpha = (0.25 + 0.75 * lum * lum).clamp(0.0, 1.0);
+            let i = (y * w + x) * 4;
+            data[i] = (255.0 * (0.8 + 0.2 * lum)) as u8;
+            data[i + 1] = (255.0 * (0.5 + 0.4 * lum)) as u8;
+            data[i + 2] = (255.0 * (0.3 + 0.3 * lum)) as u8;
+            data[i + 3] = (255.0 * alpha) as u8;
+        }
+    }
+    let mut image = Image::new(
+        Extent3d {
+            width: w as u32,
+            height: h as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        mag_filter: ImageFilterMode::Linear,
+        min_filter: ImageFilterMode::Linear,
+        ..Default::default()
+    });
+    image
+}
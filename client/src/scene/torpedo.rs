@@ -0,0 +1,211 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use protocol::{Channel, ClientToServer, FireTorpedo};
+
+use super::submarine::{SubPhysics, Submarine};
+
+const TORPEDO_RADIUS_M: f32 = 0.12;
+const TORPEDO_LENGTH_M: f32 = 1.6;
+/// Time for an explosion to grow to its full radius; it fades out over the same span.
+const EXPLOSION_DURATION_S: f32 = 0.5;
+
+/// Torpedo lifecycle messages forwarded by `pump_network`.
+#[derive(Event, Debug, Clone)]
+pub enum TorpedoEvent {
+    Spawned(protocol::TorpedoSpawned),
+    Detonation(protocol::TorpedoDetonation),
+}
+
+/// Client-side copy of a server torpedo, flown straight and slowed by `protocol::torpedo_drag`.
+#[derive(Component, Debug)]
+pub struct Torpedo {
+    pub id: u32,
+    pub direction: Vec3,
+    pub speed_m_s: f32,
+}
+
+#[derive(Component, Debug)]
+pub struct ExplosionVfx {
+    pub radius_m: f32,
+    pub elapsed_s: f32,
+    material: Handle<StandardMaterial>,
+}
+
+impl ExplosionVfx {
+    /// Current sphere scale and alpha: grows linearly to `radius_m` while fading out.
+    pub fn scale_and_alpha(&self) -> (f32, f32) {
+        let t = (self.elapsed_s / EXPLOSION_DURATION_S).clamp(0.0, 1.0);
+        (self.radius_m * t, 1.0 - t)
+    }
+}
+
+/// Fire controls, present only while the server has granted `FeatureFlags::TORPEDO`.
+#[derive(Resource, Debug, Default)]
+pub struct TorpedoControls {
+    /// Tube the next shot leaves from; alternates between the sub's tubes.
+    pub next_tube: u8,
+}
+
+pub struct TorpedoPlugin;
+
+impl Plugin for TorpedoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                fire_torpedo_key,
+                handle_torpedo_events,
+                move_torpedoes,
+                animate_explosions,
+            ),
+        );
+    }
+}
+
+/// F fires straight ahead, alternating between the sub's tubes, once the server allows it.
+fn fire_torpedo_key(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    client: Option<ResMut<RenetClient>>,
+    controls: Option<ResMut<TorpedoControls>>,
+    q_sub: Query<&SubPhysics, With<Submarine>>,
+) {
+    let (Some(keys), Some(mut client), Some(mut controls)) = (keys, client, controls) else {
+        return;
+    };
+    if !keys.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let Some(tubes) = q_sub.iter().next().map(|p| p.0.torpedo_tubes) else {
+        return;
+    };
+    if tubes == 0 {
+        return;
+    }
+    let tube_id = controls.next_tube % tubes;
+    controls.next_tube = (tube_id + 1) % tubes;
+    let msg = ClientToServer::FireTorpedo(FireTorpedo {
+        tube_id,
+        target_bearing_deg: 0.0,
+        speed_mps: 0.0,
+        heading_override: None,
+    });
+    if let Ok(bytes) = protocol::encode(&msg) {
+        client.send_message(Channel::Reliable, bytes);
+    }
+}
+
+fn handle_torpedo_events(
+    mut commands: Commands,
+    mut events: EventReader<TorpedoEvent>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_torpedoes: Query<(Entity, &Torpedo)>,
+) {
+    for event in events.read() {
+        match event {
+            TorpedoEvent::Spawned(spawn) => {
+                let direction = Vec3::from_array(spawn.direction).normalize_or(Vec3::Z);
+                commands
+                    .spawn((
+                        Mesh3d(meshes.add(Cylinder::new(TORPEDO_RADIUS_M, TORPEDO_LENGTH_M))),
+                        MeshMaterial3d(materials.add(StandardMaterial {
+                            base_color: Color::srgb(0.3, 0.32, 0.35),
+                            metallic: 0.6,
+                            perceptual_roughness: 0.4,
+                            ..Default::default()
+                        })),
+                        Transform::from_translation(Vec3::from_array(spawn.origin))
+                            .with_rotation(Quat::from_rotation_arc(Vec3::Y, direction)),
+                        Torpedo {
+                            id: spawn.id,
+                            direction,
+                            speed_m_s: spawn.speed_m_s,
+                        },
+                        Name::new(format!("Torpedo {}", spawn.id)),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(PointLight {
+                            color: Color::srgb(1.0, 0.75, 0.4),
+                            intensity: 20_000.0,
+                            range: 8.0,
+                            ..Default::default()
+                        });
+                    });
+            }
+            TorpedoEvent::Detonation(det) => {
+                for (entity, torpedo) in &q_torpedoes {
+                    if torpedo.id == det.id {
+                        commands.entity(entity).despawn();
+                    }
+                }
+                let material = materials.add(StandardMaterial {
+                    base_color: Color::srgb(1.0, 0.6, 0.25),
+                    emissive: LinearRgba::rgb(4.0, 2.0, 0.6),
+                    alpha_mode: AlphaMode::Add,
+                    unlit: true,
+                    ..Default::default()
+                });
+                commands.spawn((
+                    Mesh3d(meshes.add(Sphere::new(1.0))),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_translation(Vec3::from_array(det.position))
+                        .with_scale(Vec3::ZERO),
+                    ExplosionVfx {
+                        radius_m: det.radius_m,
+                        elapsed_s: 0.0,
+                        material,
+                    },
+                    Name::new(format!("Explosion {}", det.id)),
+                ));
+            }
+        }
+    }
+}
+
+/// Extrapolate along the launch direction; the server only reports spawn and detonation.
+fn move_torpedoes(time: Res<Time>, mut q: Query<(&mut Torpedo, &mut Transform)>) {
+    let dt = time.delta_secs();
+    for (mut torpedo, mut transform) in &mut q {
+        torpedo.speed_m_s = protocol::torpedo_drag(torpedo.speed_m_s, dt);
+        transform.translation += torpedo.direction * torpedo.speed_m_s * dt;
+    }
+}
+
+fn animate_explosions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q: Query<(Entity, &mut ExplosionVfx, &mut Transform)>,
+) {
+    for (entity, mut vfx, mut transform) in &mut q {
+        vfx.elapsed_s += time.delta_secs();
+        if vfx.elapsed_s >= EXPLOSION_DURATION_S {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let (scale, alpha) = vfx.scale_and_alpha();
+        transform.scale = Vec3::splat(scale);
+        if let Some(mat) = materials.get_mut(&vfx.material) {
+            mat.base_color.set_alpha(alpha);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explosion_grows_to_radius_and_fades() {
+        let mut vfx = ExplosionVfx {
+            radius_m: 6.0,
+            elapsed_s: 0.0,
+            material: Handle::default(),
+        };
+        assert_eq!(vfx.scale_and_alpha(), (0.0, 1.0));
+        vfx.elapsed_s = EXPLOSION_DURATION_S * 0.5;
+        assert_eq!(vfx.scale_and_alpha(), (3.0, 0.5));
+        vfx.elapsed_s = EXPLOSION_DURATION_S;
+        assert_eq!(vfx.scale_and_alpha(), (6.0, 0.0));
+    }
+}
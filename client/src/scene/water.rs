@@ -1,7 +1,6 @@
-use bevy::pbr::{MeshMaterial3d, NotShadowCaster, StandardMaterial};
-use bevy::prelude::AlphaMode;
 use bevy::prelude::*;
 
+use super::render::mote_instancing::{MoteInstanceBuffer, MoteInstanceData, MoteRenderPlugin};
 use super::submarine::Submarine;
 use crate::scene::flow_field::{FlowField, Tunnel, TunnelBounds};
 
@@ -11,72 +10,52 @@ pub struct WaterFxPlugin;
 
 impl Plugin for WaterFxPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<UnderwaterAssets>()
+        app.add_plugins(MoteRenderPlugin)
         .init_resource::<UnderwaterSettings>()
-        .add_systems(Startup, setup_underwater_assets)
+        .init_resource::<MoteField>()
+        .init_resource::<Bubbles>()
         .add_systems(
             Update,
             (
                 tune_camera_underwater,
-                ensure_mote_field,
-                tick_motes,
-                ensure_bubble_emitter,
-                spawn_bubbles,
-                tick_bubbles,
+                (
+                    tick_motes,
+                    ensure_bubble_emitter,
+                    spawn_bubbles,
+                    tick_bubbles,
+                    pack_mote_instances,
+                )
+                    .chain(),
             ),
         );
     }
 }
 
-// ---------- Assets / Materials ----------
-
-#[derive(Resource, Default)]
-pub struct UnderwaterAssets {
-    mote_mesh: Handle<Mesh>,
-    mote_mat: Handle<StandardMaterial>,
-    bubble_mesh: Handle<Mesh>,
-    bubble_mat: Handle<StandardMaterial>,
-}
+// ---------- Settings ----------
 
 /// Runtime toggles for underwater FX.
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct UnderwaterSettings {
     /// Leave bubbles off by default for now.
     pub bubbles_enabled: bool,
+    /// Dust motes kept around the camera; all of them render in a single instanced draw.
+    pub mote_count: usize,
 }
 
-fn setup_underwater_assets(
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut assets: ResMut<UnderwaterAssets>,
-) {
-    // Tiny unlit sphere for dust motes
-    let mote_mesh = meshes.add(Mesh::from(bevy::math::primitives::Sphere::new(0.02)));
-    let mote_mat = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.65, 0.85, 0.9).with_alpha(0.2),
-        unlit: true,
-        alpha_mode: AlphaMode::Blend,
-        ..Default::default()
-    });
+impl Default for UnderwaterSettings {
+    fn default() -> Self {
+        Self {
+            bubbles_enabled: false,
+            mote_count: 160,
+        }
+    }
+}
 
-    // Slightly larger transparent sphere for bubbles
-    let bubble_mesh = meshes.add(Mesh::from(bevy::math::primitives::Sphere::new(0.03)));
-    let bubble_mat = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.85, 0.95, 1.0).with_alpha(0.4),
-        unlit: true,
-        alpha_mode: AlphaMode::Blend,
-        // Fresnel-like hack: non-shadowing and double-sided feel via cull off
-        cull_mode: None,
-        ..Default::default()
-    });
+const MOTE_RADIUS_M: f32 = 0.02;
+const MOTE_COLOR: LinearRgba = LinearRgba::new(0.38, 0.69, 0.79, 0.2);
+const BUBBLE_RADIUS_M: f32 = 0.03;
+const BUBBLE_COLOR: LinearRgba = LinearRgba::new(0.69, 0.89, 1.0, 0.4);
 
-    *assets = UnderwaterAssets {
-        mote_mesh,
-        mote_mat,
-        bubble_mesh,
-        bubble_mat,
-    };
-}
 // ---------- Camera tuning ----------
 
 #[derive(Component)]
@@ -108,129 +87,104 @@ fn tune_camera_underwater(
 
 // ---------- Dust motes ----------
 
-#[derive(Component)]
-struct MoteField {
-    radius: f32,
+pub struct Mote {
+    pub pos: Vec3,
+    vel: Vec3,
 }
 
-#[derive(Component)]
-struct UnderwaterMote {
-    vel: Vec3,
+/// Motes drifting in a sphere that trails the active camera.
+#[derive(Resource)]
+pub struct MoteField {
+    center: Vec3,
+    radius: f32,
+    motes: Vec<Mote>,
 }
 
-#[allow(clippy::type_complexity)]
-fn ensure_mote_field(
-    mut commands: Commands,
-    q_field: Query<Entity, With<MoteField>>,
-    q_cam: Query<(Entity, &Transform, &Camera), (With<Camera3d>, Without<MoteField>)>,
-    assets: Res<UnderwaterAssets>,
-) {
-    if q_field.iter().next().is_some() {
-        return;
-    }
-    // Choose the active camera
-    let mut active: Option<Transform> = None;
-    for (_e, t, cam) in &q_cam {
-        if cam.is_active {
-            active = Some(*t);
-            break;
+impl Default for MoteField {
+    fn default() -> Self {
+        Self {
+            center: Vec3::ZERO,
+            radius: 8.0,
+            motes: Vec::new(),
         }
     }
-    let Some(cam_t) = active else {
-        return;
-    };
+}
 
-    let radius = 8.0_f32;
-    let count = 160_usize;
-
-    let field_e = commands
-        .spawn((
-            Transform::from_translation(cam_t.translation),
-            GlobalTransform::default(),
-            Visibility::default(),
-            MoteField { radius },
-            Name::new("MoteField"),
-        ))
-        .id();
-
-    let mut rng_seed = 0x1234_5678_u32;
-    let mut frand = || {
-        // xorshift32
-        rng_seed ^= rng_seed << 13;
-        rng_seed ^= rng_seed >> 17;
-        rng_seed ^= rng_seed << 5;
-        (rng_seed as f32 / u32::MAX as f32) * 2.0 - 1.0
-    };
+impl MoteField {
+    /// Scatter `count` motes around `center`; seeded so every run looks the same.
+    pub fn populate(&mut self, center: Vec3, count: usize) {
+        let mut rng_seed = 0x1234_5678_u32;
+        let mut frand = || {
+            // xorshift32
+            rng_seed ^= rng_seed << 13;
+            rng_seed ^= rng_seed >> 17;
+            rng_seed ^= rng_seed << 5;
+            (rng_seed as f32 / u32::MAX as f32) * 2.0 - 1.0
+        };
+        let radius = self.radius;
+        self.center = center;
+        self.motes = (0..count)
+            .map(|_| {
+                let pos = center
+                    + Vec3::new(frand(), frand(), frand()).normalize_or_zero()
+                        * (radius * 0.9 * frand().abs());
+                let vel = Vec3::new(frand() * 0.05, 0.05 + frand() * 0.02, frand() * 0.05);
+                Mote { pos, vel }
+            })
+            .collect();
+    }
 
-    for _ in 0..count {
-        let pos = cam_t.translation
-            + Vec3::new(frand(), frand(), frand()).normalize_or_zero()
-                * (radius * 0.9 * frand().abs());
-        let vel = Vec3::new(frand() * 0.05, 0.05 + frand() * 0.02, frand() * 0.05);
-        commands.spawn((
-            Mesh3d(assets.mote_mesh.clone()),
-            MeshMaterial3d(assets.mote_mat.clone()),
-            Transform::from_translation(pos),
-            GlobalTransform::default(),
-            UnderwaterMote { vel },
-            NotShadowCaster,
-            Name::new("Mote"),
-            ChildOf(field_e),
-        ));
+    pub fn motes(&self) -> &[Mote] {
+        &self.motes
     }
 }
 
-#[allow(clippy::type_complexity)]
 fn tick_motes(
     time: Res<Time>,
-    mut q_field: Query<(&mut Transform, &MoteField)>,
-    q_cam: Query<(&Transform, &Camera), (With<Camera3d>, Without<MoteField>)>,
-    mut q_motes: Query<
-        (&mut Transform, &mut UnderwaterMote),
-        (Without<Camera3d>, Without<MoteField>),
-    >,
+    settings: Res<UnderwaterSettings>,
+    mut field: ResMut<MoteField>,
+    q_cam: Query<(&Transform, &Camera), With<Camera3d>>,
     q_flow: Query<(&GlobalTransform, &FlowField, &TunnelBounds), With<Tunnel>>,
 ) {
-    let mut cam_opt: Option<Transform> = None;
-    for (t, cam) in &q_cam {
-        if cam.is_active {
-            cam_opt = Some(*t);
-            break;
-        }
-    }
-    let Some(cam_t) = cam_opt else {
+    let Some((cam_t, _)) = q_cam.iter().find(|(_, cam)| cam.is_active) else {
         return;
     };
+    if field.motes.len() != settings.mote_count {
+        field.populate(cam_t.translation, settings.mote_count);
+    }
     let dt = time.delta_secs().clamp(0.0, 0.05);
 
-    if let Ok((mut field_t, field)) = q_field.single_mut() {
-        // Keep field centered on camera smoothly
-        let lerp = 1.0 - (-4.0 * dt).exp();
-        field_t.translation = field_t.translation.lerp(cam_t.translation, lerp);
-
-        // Sample first flow field if available
-        let flow = if let Ok((_gt, ff, _tb)) = q_flow.single() {
-            let (v, variance) = ff.sample(field_t.translation, time.elapsed_secs());
-            v + Vec3::new(0.0, 0.05 + variance * 0.02, 0.0)
-        } else {
-            Vec3::new(0.0, 0.06, 0.0)
-        };
+    // Keep field centered on camera smoothly
+    let lerp = 1.0 - (-4.0 * dt).exp();
+    field.center = field.center.lerp(cam_t.translation, lerp);
+
+    // Sample first flow field if available
+    let flow = if let Ok((_gt, ff, _tb)) = q_flow.single() {
+        let (v, variance) = ff.sample(field.center, time.elapsed_secs());
+        v + Vec3::new(0.0, 0.05 + variance * 0.02, 0.0)
+    } else {
+        Vec3::new(0.0, 0.06, 0.0)
+    };
 
-        for (mut t, mut mote) in &mut q_motes {
-            let jitter = Vec3::new(
-                (time.elapsed_secs() * 0.9 + t.translation.x).sin() * 0.01,
-                (time.elapsed_secs() * 1.1 + t.translation.y).cos() * 0.01,
-                (time.elapsed_secs() * 1.3 + t.translation.z).sin() * 0.01,
-            );
-            mote.vel = mote.vel.lerp(flow + jitter, 0.1);
-            t.translation += mote.vel * dt;
-
-            // Recycle motes far outside the sphere
-            let d = (t.translation - field_t.translation).length();
-            if d > field.radius {
-                let dir = (t.translation - field_t.translation).normalize_or_zero();
-                t.translation = field_t.translation - dir * (field.radius * 0.9);
-            }
+    let MoteField {
+        center,
+        radius,
+        motes,
+    } = &mut *field;
+    for mote in motes.iter_mut() {
+        let jitter = Vec3::new(
+            (time.elapsed_secs() * 0.9 + mote.pos.x).sin() * 0.01,
+            (time.elapsed_secs() * 1.1 + mote.pos.y).cos() * 0.01,
+            (time.elapsed_secs() * 1.3 + mote.pos.z).sin() * 0.01,
+        );
+        mote.vel = mote.vel.lerp(flow + jitter, 0.1);
+        mote.pos += mote.vel * dt;
+
+        // Recycle motes far outside the sphere
+        let d = (mote.pos - *center).length();
+        if d > *radius {
+            let dir = (mote.pos - *center).normalize_or_zero();
+            mote.pos = *center - dir * (*radius * 0.9);
         }
     }
 }
@@ -242,12 +196,16 @@ struct BubbleEmitter {
     cooldown: f32,
 }
 
-#[derive(Component)]
 struct Bubble {
+    pos: Vec3,
+    scale: f32,
     ttl: f32,
     rise: f32,
 }
 
+#[derive(Resource, Default)]
+pub struct Bubbles(Vec<Bubble>);
+
 fn ensure_bubble_emitter(
     mut commands: Commands,
     q_emit: Query<Entity, With<BubbleEmitter>>,
@@ -266,12 +224,11 @@ fn ensure_bubble_emitter(
 
 fn spawn_bubbles(
     time: Res<Time>,
-    mut commands: Commands,
+    mut bubbles: ResMut<Bubbles>,
     mut q_emit: Query<(&mut BubbleEmitter, &GlobalTransform), With<Submarine>>,
-    assets: Res<UnderwaterAssets>,
-    settings: Option<Res<UnderwaterSettings>>,
+    settings: Res<UnderwaterSettings>,
 ) {
-    if !settings.map(|s| s.bubbles_enabled).unwrap_or(false) {
+    if !settings.bubbles_enabled {
         return;
     }
     let Ok((mut em, gt)) = q_emit.single_mut() else {
@@ -292,43 +249,80 @@ fn spawn_bubbles(
 
     for i in 0..3 {
         let f = i as f32 * 0.37;
-        let pos = stern + right * (f.sin() * 0.05) + up * (f.cos() * 0.04);
-        commands.spawn((
-            Mesh3d(assets.bubble_mesh.clone()),
-            MeshMaterial3d(assets.bubble_mat.clone()),
-            Transform::from_translation(pos),
-            GlobalTransform::default(),
-            Bubble {
-                ttl: 1.8,
-                rise: 0.9,
-            },
-            NotShadowCaster,
-            Name::new("Bubble"),
-        ));
+        bubbles.0.push(Bubble {
+            pos: stern + right * (f.sin() * 0.05) + up * (f.cos() * 0.04),
+            scale: 1.0,
+            ttl: 1.8,
+            rise: 0.9,
+        });
     }
 }
 
-fn tick_bubbles(
-    time: Res<Time>,
-    mut commands: Commands,
-    mut q: Query<(Entity, &mut Transform, &mut Bubble)>,
-    settings: Option<Res<UnderwaterSettings>>,
-) {
-    if !settings.map(|s| s.bubbles_enabled).unwrap_or(false) {
+fn tick_bubbles(time: Res<Time>, mut bubbles: ResMut<Bubbles>, settings: Res<UnderwaterSettings>) {
+    if !settings.bubbles_enabled {
+        bubbles.0.clear();
         return;
     }
     let dt = time.delta_secs();
-    for (e, mut t, mut b) in &mut q {
+    bubbles.0.retain_mut(|b| {
         b.ttl -= dt;
         if b.ttl <= 0.0 {
-            commands.entity(e).despawn();
-            continue;
+            return false;
         }
         // Rise and drift
-        let s = 1.0 + (1.8 - b.ttl) * 0.1;
-        t.translation += Vec3::new(0.0, b.rise * dt, 0.0);
-        t.translation.x += (time.elapsed_secs() * 2.3 + t.translation.y).sin() * 0.01;
-        t.translation.z += (time.elapsed_secs() * 1.9 + t.translation.x).cos() * 0.01;
-        t.scale = Vec3::splat(s);
+        b.scale = 1.0 + (1.8 - b.ttl) * 0.1;
+        b.pos.y += b.rise * dt;
+        b.pos.x += (time.elapsed_secs() * 2.3 + b.pos.y).sin() * 0.01;
+        b.pos.z += (time.elapsed_secs() * 1.9 + b.pos.x).cos() * 0.01;
+        true
+    });
+}
+
+// ---------- Instancing ----------
+
+/// Rebuild the per-frame instance list that `MoteRenderPlugin` draws in one call.
+pub fn pack_mote_instances(
+    field: Res<MoteField>,
+    bubbles: Res<Bubbles>,
+    mut buffer: ResMut<MoteInstanceBuffer>,
+) {
+    buffer.instances.clear();
+    buffer.instances.extend(
+        field
+            .motes
+            .iter()
+            .map(|m| MoteInstanceData::new(m.pos, MOTE_RADIUS_M, MOTE_COLOR)),
+    );
+    buffer.instances.extend(
+        bubbles
+            .0
+            .iter()
+            .map(|b| MoteInstanceData::new(b.pos, BUBBLE_RADIUS_M * b.scale, BUBBLE_COLOR)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn populated_field_packs_one_instance_per_mote() {
+        let mut app = App::new();
+        app.init_resource::<MoteInstanceBuffer>()
+            .init_resource::<Bubbles>()
+            .insert_resource(MoteField::default())
+            .add_systems(Update, pack_mote_instances);
+        app.world_mut()
+            .resource_mut::<MoteField>()
+            .populate(Vec3::new(0.0, -5.0, 0.0), 1024);
+        app.update();
+
+        let buffer = app.world().resource::<MoteInstanceBuffer>();
+        assert_eq!(buffer.instances.len(), 1024);
+        for instance in &buffer.instances {
+            let p = Vec3::from_array(instance.position);
+            assert!(p.distance(Vec3::new(0.0, -5.0, 0.0)) <= 8.0 * 0.9 + 1e-4);
+            assert_eq!(instance.scale, MOTE_RADIUS_M);
+        }
     }
 }
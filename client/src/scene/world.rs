@@ -7,7 +7,7 @@ use bevy::math::{Affine2, Vec2};
 use bevy::render::render_resource::TextureUsages;
 
 use crate::debug_vis::DebugVis;
-use levels::{builtins::greybox_level, FlowFieldSpec, LevelSpec, Vec3f};
+use levels::{builtins::greybox_level, LevelSpec, Vec3f};
 use levels::subspecs::small_skiff_spec;
 
 use super::setup::spawn_box;
@@ -183,10 +183,9 @@ pub fn spawn_greybox(
                 TunnelBounds { size: tunnel_size },
                 Visibility::default(),
                 // Flow field from spec
-                match level.tunnel.flow {
-                    FlowFieldSpec::Uniform { flow, variance } => {
-                        FlowField::uniform(v(flow), variance)
-                    }
+                {
+                    let (flow, variance) = level.tunnel.flow.mean();
+                    FlowField::uniform(v(flow), variance)
                 },
                 Name::new("Tunnel"),
             ))
@@ -530,7 +529,7 @@ pub fn spawn_greybox(
             GlobalTransform::default(),
             GameCamera,
             CamMode::FirstPerson,
-            FollowCam { distance: 8.0, height: 2.0, stiffness: 8.0 },
+            FollowCam { distance: 8.0, height: 2.0, stiffness: 8.0, lateral_offset: 0.0, vertical_pitch_deg: 0.0 },
             FollowCamState { last_dir: Vec3::NEG_X },
             FreeFlyState { yaw: 0.0, pitch: 0.0, speed: 8.0 },
             Name::new("Game Camera"),
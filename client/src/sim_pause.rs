@@ -1,4 +1,97 @@
 use bevy::prelude::*;
 
-#[derive(Resource, Default, Debug, Clone, Copy)]
-pub struct SimPause(pub bool);
+use crate::reconnect::{ReconnectPending, ReconnectPolicy};
+
+/// Slow-motion factor bound to `,`; `.` restores real time.
+pub const SLOW_MOTION_FACTOR: f32 = 0.25;
+
+/// Client simulation clock controls. `paused` is mirrored to the server; `slow_factor` is local
+/// only and scales client prediction for close physics observation.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct SimPause {
+    pub paused: bool,
+    pub slow_factor: f32,
+}
+
+impl Default for SimPause {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            slow_factor: 1.0,
+        }
+    }
+}
+
+impl SimPause {
+    /// Simulation time to accumulate for a frame of `frame_dt` real seconds. Slow motion is
+    /// suppressed while reconnecting so prediction does not have to catch up afterwards.
+    pub fn scaled_dt(&self, frame_dt: f32, reconnecting: bool) -> f32 {
+        if self.paused {
+            return 0.0;
+        }
+        if reconnecting {
+            return frame_dt;
+        }
+        frame_dt * self.slow_factor.clamp(0.0, 1.0)
+    }
+
+    pub fn is_slow(&self) -> bool {
+        self.slow_factor < 1.0
+    }
+}
+
+/// `,` switches to slow motion, `.` back to real time.
+pub fn slow_motion_keys(keys: Option<Res<ButtonInput<KeyCode>>>, mut pause: ResMut<SimPause>) {
+    let Some(keys) = keys else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Comma) {
+        pause.slow_factor = SLOW_MOTION_FACTOR;
+    }
+    if keys.just_pressed(KeyCode::Period) {
+        pause.slow_factor = 1.0;
+    }
+}
+
+/// True while a dropped connection is being re-established.
+pub fn is_reconnecting(
+    pending: Option<&ReconnectPending>,
+    policy: Option<&ReconnectPolicy>,
+) -> bool {
+    pending.is_some() || policy.is_some_and(|p| p.current_attempt > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarter_speed_advances_quarter_second_per_real_second() {
+        let pause = SimPause {
+            paused: false,
+            slow_factor: 0.25,
+        };
+        let frame_dt = 1.0 / 60.0;
+        let step_dt = 1.0 / 60.0;
+        let mut acc = 0.0f32;
+        let mut steps = 0u32;
+        for _ in 0..60 {
+            acc += pause.scaled_dt(frame_dt, false);
+            while acc >= step_dt {
+                acc -= step_dt;
+                steps += 1;
+            }
+        }
+        let sim_time = steps as f32 * step_dt + acc;
+        assert!((sim_time - 0.25).abs() < 1e-4, "sim advanced {sim_time} s");
+        assert!((14..=15).contains(&steps), "took {steps} fixed steps");
+
+        // Reconnecting and paused override slow motion
+        assert_eq!(pause.scaled_dt(frame_dt, true), frame_dt);
+        let paused = SimPause {
+            paused: true,
+            ..pause
+        };
+        assert_eq!(paused.scaled_dt(frame_dt, false), 0.0);
+    }
+}
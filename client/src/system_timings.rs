@@ -0,0 +1,124 @@
+//! Wall-clock timings of a few hot systems for the debug overlay.
+//!
+//! The timed systems open a [`ScopeTimer`] under `#[cfg(debug_assertions)]`, so release builds
+//! carry no measurement code. Samples go through process-wide atomics rather than a resource
+//! because `prepare_view_cone_lights` runs in the render world.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Instant;
+
+use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
+use bevy::prelude::*;
+
+/// EWMA weight of each new frame's sample in [`SystemTimings`].
+pub const EWMA_ALPHA: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedSystem {
+    Simulate,
+    NetApply,
+    Pump,
+    ConePrep,
+}
+
+impl TimedSystem {
+    pub const COUNT: usize = 4;
+}
+
+/// Microseconds spent in each system since the last `collect_system_timings`.
+static PENDING_US: [AtomicU32; TimedSystem::COUNT] = [
+    AtomicU32::new(0),
+    AtomicU32::new(0),
+    AtomicU32::new(0),
+    AtomicU32::new(0),
+];
+
+/// Adds the time between `start` and drop to its system's pending sample.
+pub struct ScopeTimer {
+    system: TimedSystem,
+    start: Instant,
+}
+
+impl ScopeTimer {
+    pub fn start(system: TimedSystem) -> Self {
+        Self {
+            system,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for ScopeTimer {
+    fn drop(&mut self) {
+        let us = self.start.elapsed().as_micros().min(u32::MAX as u128) as u32;
+        PENDING_US[self.system as usize].fetch_add(us, Ordering::Relaxed);
+    }
+}
+
+/// Smoothed per-frame milliseconds, indexed by `TimedSystem`.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SystemTimings {
+    pub ms: [f32; TimedSystem::COUNT],
+}
+
+impl SystemTimings {
+    pub fn get(&self, system: TimedSystem) -> f32 {
+        self.ms[system as usize]
+    }
+
+    pub fn record(&mut self, sample_ms: [f32; TimedSystem::COUNT]) {
+        for (ms, sample) in self.ms.iter_mut().zip(sample_ms) {
+            *ms += EWMA_ALPHA * (sample - *ms);
+        }
+    }
+
+    /// Overlay table; the systems only report in debug builds.
+    pub fn table(&self) -> String {
+        if !cfg!(debug_assertions) {
+            return "SYSTEMS (ms): debug builds only".to_string();
+        }
+        format!(
+            "SYSTEMS (ms):\n simulate: {:.2}\n net_apply: {:.2}\n pump: {:.2}\n cone_prep: {:.2}",
+            self.get(TimedSystem::Simulate),
+            self.get(TimedSystem::NetApply),
+            self.get(TimedSystem::Pump),
+            self.get(TimedSystem::ConePrep),
+        )
+    }
+}
+
+pub struct SystemTimingDiagnosticPlugin;
+
+impl Plugin for SystemTimingDiagnosticPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+        }
+        app.init_resource::<SystemTimings>()
+            .add_systems(Last, collect_system_timings);
+    }
+}
+
+fn collect_system_timings(mut timings: ResMut<SystemTimings>) {
+    let sample_ms =
+        std::array::from_fn(|i| PENDING_US[i].swap(0, Ordering::Relaxed) as f32 / 1000.0);
+    timings.record(sample_ms);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ewma_converges_without_jumping() {
+        let mut timings = SystemTimings::default();
+        timings.record([1.0, 0.0, 0.0, 0.0]);
+        assert!((timings.get(TimedSystem::Simulate) - 0.1).abs() < 1e-6);
+        for _ in 0..100 {
+            timings.record([1.0, 0.0, 2.0, 0.0]);
+        }
+        assert!((timings.get(TimedSystem::Simulate) - 1.0).abs() < 1e-3);
+        assert!((timings.get(TimedSystem::Pump) - 2.0).abs() < 1e-3);
+        assert_eq!(timings.get(TimedSystem::NetApply), 0.0);
+    }
+}
@@ -0,0 +1,228 @@
+//! Netcode client transport over a WebSocket instead of a UDP socket, for networks (and, later,
+//! browser builds) where only TCP gets through. The server side is `server::ws_proxy`, which
+//! unwraps each message into a datagram for the regular netcode socket, so everything above
+//! the transport (renet channels, `protocol::encode`/`decode`) is unchanged.
+
+use std::io;
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_renet::netcode::{ClientAuthentication, NetcodeTransportError};
+use bevy_renet::renet::RenetClient;
+use bevy_renet::{RenetClientPlugin, RenetReceive, RenetSend};
+use renetcode::{DisconnectReason, NetcodeClient, NetcodeError};
+use tungstenite::{Error as WsError, Message, WebSocket};
+
+/// Client WebSocket, non-blocking after the handshake so it fits the frame-driven transport.
+#[derive(Debug)]
+struct WsStream(WebSocket<TcpStream>);
+
+impl WsStream {
+    fn connect(addr: SocketAddr, timeout: Duration) -> io::Result<Self> {
+        let stream = TcpStream::connect_timeout(&addr, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        stream.set_nodelay(true)?;
+        let (ws, _) = tungstenite::client(format!("ws://{addr}/"), stream)
+            .map_err(|err| io::Error::new(io::ErrorKind::ConnectionRefused, err.to_string()))?;
+        ws.get_ref().set_nonblocking(true)?;
+        Ok(Self(ws))
+    }
+
+    /// Queue `payload` as one binary message and write as much as the socket takes; the rest
+    /// goes out on a later call.
+    fn send(&mut self, payload: &[u8]) -> io::Result<()> {
+        match self.0.send(Message::binary(payload.to_vec())) {
+            Ok(()) => Ok(()),
+            Err(err) => ignore_would_block(err),
+        }
+    }
+
+    /// Next binary message, if one has arrived. Returns `ConnectionAborted` once the server
+    /// has closed the connection.
+    fn recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        loop {
+            match self.0.read() {
+                Ok(Message::Binary(data)) => return Ok(Some(data.to_vec())),
+                // Pings are answered by tungstenite; text is not part of the tunnel
+                Ok(_) => {}
+                Err(err) => return ignore_would_block(err).map(|()| None),
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        let _ = self.0.close(None);
+        let _ = self.0.flush();
+    }
+}
+
+fn ignore_would_block(err: WsError) -> io::Result<()> {
+    match err {
+        WsError::Io(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+        WsError::Io(err) => Err(err),
+        WsError::ConnectionClosed | WsError::AlreadyClosed => {
+            Err(io::ErrorKind::ConnectionAborted.into())
+        }
+        err => Err(io::Error::new(io::ErrorKind::InvalidData, err.to_string())),
+    }
+}
+
+/// Drop-in for `NetcodeClientTransport` with the same `update`/`send_packets` cycle.
+#[derive(Resource, Debug)]
+pub struct WsClientTransport {
+    ws: WsStream,
+    netcode_client: NetcodeClient,
+    /// Set when the WebSocket itself closes, which netcode would only notice on timeout.
+    closed: Option<DisconnectReason>,
+}
+
+impl WsClientTransport {
+    /// Open the WebSocket to `ws_addr`. `authentication` still names the server's netcode
+    /// (UDP) address, which is what its connect token is checked against.
+    pub fn new(
+        current_time: Duration,
+        authentication: ClientAuthentication,
+        ws_addr: SocketAddr,
+        connect_timeout: Duration,
+    ) -> Result<Self, NetcodeTransportError> {
+        let ws = WsStream::connect(ws_addr, connect_timeout)?;
+        let netcode_client = NetcodeClient::new(current_time, authentication)?;
+        Ok(Self {
+            ws,
+            netcode_client,
+            closed: None,
+        })
+    }
+
+    pub fn client_id(&self) -> u64 {
+        self.netcode_client.client_id()
+    }
+
+    pub fn disconnect(&mut self) {
+        if self.netcode_client.is_disconnected() {
+            return;
+        }
+        if let Ok((_, packet)) = self.netcode_client.disconnect() {
+            let _ = self.ws.send(packet);
+        }
+        self.ws.close();
+    }
+
+    pub fn disconnect_reason(&self) -> Option<DisconnectReason> {
+        self.netcode_client.disconnect_reason().or(self.closed)
+    }
+
+    pub fn send_packets(&mut self, client: &mut RenetClient) -> Result<(), NetcodeTransportError> {
+        if let Some(reason) = self.disconnect_reason() {
+            return Err(NetcodeError::Disconnected(reason).into());
+        }
+        for packet in client.get_packets_to_send() {
+            let (_, payload) = self.netcode_client.generate_payload_packet(&packet)?;
+            self.ws.send(payload)?;
+        }
+        Ok(())
+    }
+
+    pub fn update(
+        &mut self,
+        duration: Duration,
+        client: &mut RenetClient,
+    ) -> Result<(), NetcodeTransportError> {
+        if let Some(reason) = self.disconnect_reason() {
+            client.disconnect_due_to_transport();
+            return Err(NetcodeError::Disconnected(reason).into());
+        }
+        if let Some(error) = client.disconnect_reason() {
+            let (_, packet) = self.netcode_client.disconnect()?;
+            self.ws.send(packet)?;
+            self.ws.close();
+            return Err(error.into());
+        }
+
+        if self.netcode_client.is_connected() {
+            client.set_connected();
+        } else if self.netcode_client.is_connecting() {
+            client.set_connecting();
+        }
+
+        loop {
+            let mut message = match self.ws.recv() {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                Err(err) => {
+                    self.closed = Some(if err.kind() == io::ErrorKind::ConnectionAborted {
+                        DisconnectReason::DisconnectedByServer
+                    } else {
+                        DisconnectReason::ConnectionTimedOut
+                    });
+                    client.disconnect_due_to_transport();
+                    return Err(err.into());
+                }
+            };
+            if let Some(payload) = self.netcode_client.process_packet(&mut message) {
+                client.process_packet(payload);
+            }
+        }
+
+        if let Some((packet, _)) = self.netcode_client.update(duration) {
+            self.ws.send(packet)?;
+        }
+        Ok(())
+    }
+}
+
+/// Drives `WsClientTransport` the way bevy_renet's `NetcodeClientPlugin` drives the UDP one.
+pub struct WsClientTransportPlugin;
+
+impl Plugin for WsClientTransportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            update_ws_transport
+                .in_set(RenetReceive)
+                .run_if(resource_exists::<WsClientTransport>)
+                .run_if(resource_exists::<RenetClient>)
+                .after(RenetClientPlugin::update_system),
+        )
+        .add_systems(
+            PostUpdate,
+            send_ws_packets
+                .in_set(RenetSend)
+                .run_if(resource_exists::<WsClientTransport>)
+                .run_if(resource_exists::<RenetClient>),
+        )
+        .add_systems(
+            Last,
+            disconnect_ws_on_exit.run_if(resource_exists::<WsClientTransport>),
+        );
+    }
+}
+
+fn update_ws_transport(
+    mut transport: ResMut<WsClientTransport>,
+    mut client: ResMut<RenetClient>,
+    time: Res<Time>,
+    mut transport_errors: EventWriter<NetcodeTransportError>,
+) {
+    if let Err(e) = transport.update(time.delta(), &mut client) {
+        transport_errors.write(e);
+    }
+}
+
+fn send_ws_packets(
+    mut transport: ResMut<WsClientTransport>,
+    mut client: ResMut<RenetClient>,
+    mut transport_errors: EventWriter<NetcodeTransportError>,
+) {
+    if let Err(e) = transport.send_packets(&mut client) {
+        transport_errors.write(e);
+    }
+}
+
+fn disconnect_ws_on_exit(exit: EventReader<AppExit>, mut transport: ResMut<WsClientTransport>) {
+    if !exit.is_empty() {
+        transport.disconnect();
+    }
+}
@@ -4,19 +4,47 @@ mod integration {
     use std::time::Duration;
 
     use anyhow::Result;
-    use bevy_app::{App, Startup, Update};
+    use bevy_app::{App, AppExit, Startup, Update};
     use bevy_ecs::prelude::*;
-    use bevy_renet::renet::{DefaultChannel, RenetClient};
-    use bevy_time::Time;
+    use bevy_renet::netcode::{ClientAuthentication, NetcodeClientTransport};
+    use bevy_renet::renet::RenetClient;
+    use bevy_time::TimeUpdateStrategy;
     use bevy_transform::components::{GlobalTransform, Transform};
-    use client::net::FilteredServerState;
+    use client::campaign::CurrentLevel;
+    use client::discovery::{collect_beacons, DISCOVERY_LISTEN};
+    use client::identity::ClientIdentity;
+    use client::missions::MissionTracker;
+    use client::net::{
+        FilteredServerState, InputBatcher, InputRateLimit, Inventory, LatestStateDelta,
+        Leaderboard, MyPlayerId, NetSet, RequestedFeatures, ServerDisconnect, ServerFeatures,
+        TeamRoster,
+    };
+    use client::reconnect::{ReconnectPending, ReconnectPolicy};
+    use client::rollback::InputHistory;
+    use client::scene::crash_dump::PhysicsCrashDump;
     use client::scene::submarine::{
-        AngularVelocity, SubInputStateComp, SubPhysics, SubStateComp, Submarine, Velocity,
+        self, AngularVelocity, SubInputStateComp, SubPhysics, SubStateComp, Submarine, Velocity,
+    };
+    use client::scene::torpedo::TorpedoControls;
+    use client::scene::SimSet;
+    use client::ws_transport::WsClientTransport;
+    use client::{build_minimal_client_app, Args as ClientArgs, ThrustInput};
+    use levels::{
+        builtins::greybox_level, subspecs::small_skiff_spec, Quatf, ResourceType, SubState, Vec3f,
+    };
+    use protocol::discovery::ServerBeacon;
+    use protocol::rendezvous::HolePunchConfig;
+    use protocol::renet_config::connection_config;
+    use protocol::{
+        Channel, ClientHello, ClientToServer, DisconnectReason, FeatureFlags, ServerToClient,
+        LEGACY_PROTOCOL_VERSION, NETCODE_PROTOCOL_ID, PROTOCOL_VERSION,
+    };
+    use server::{
+        build_server_app, build_server_app_with_level, CampaignRes, CargoHold, Config,
+        DepartedPlayers, GrantedFeatures, HullIntegrity, InputBuffer, MovingObstacle, Player,
+        PlayerScore, ServerAddresses, ShutdownSignal, SubStateComp as ServerSubStateComp, Team,
+        Torpedo, TorpedoCooldown, TorpedoTubes,
     };
-    use client::{build_minimal_client_app, Args as ClientArgs};
-    use levels::{subspecs::small_skiff_spec, Quatf, SubInputState, SubState, Vec3f};
-    use protocol::ClientToServer;
-    use server::{build_server_app, Config, ServerAddresses, SubStateComp as ServerSubStateComp};
 
     const HARD_THRESHOLD: f32 = 0.2;
     const SOFT_THRESHOLD: f32 = 0.1;
@@ -24,8 +52,11 @@ mod integration {
     const SIM_DT: f32 = 1.0 / 30.0;
     const HANDSHAKE_STEPS: usize = 600;
     const WARMUP_STEPS: usize = 120;
-    const SIM_STEPS: usize = 10_000;
+    /// Full throttle from the dock covers ~0.14 m a step, so this stops ~20 m short of the
+    /// greybox tunnel's sliding gate at x = 240.
+    const SIM_STEPS: usize = 600;
     const IGNORE_STEPS: usize = 128;
+    const TWO_CLIENT_STEPS: usize = 500;
 
     #[derive(Resource, Default)]
     struct TestThrottleState {
@@ -41,9 +72,9 @@ mod integration {
     }
 
     fn advance_app(app: &mut App, dt: f32) {
-        if let Some(mut time) = app.world_mut().get_resource_mut::<Time>() {
-            time.advance_by(Duration::from_secs_f32(dt));
-        }
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            dt,
+        )));
         app.update();
     }
 
@@ -63,13 +94,34 @@ mod integration {
                 orientation: Quatf::IDENTITY,
                 ang_mom: Vec3f::new(0.0, 0.0, 0.0),
                 ballast_fill: ballast,
+                pump: Default::default(),
             }),
+            SubInputStateComp::default(),
         ));
     }
 
+    /// Run the scene's prediction systems on a headless client, which otherwise only filters
+    /// server snapshots.
+    fn add_local_prediction(app: &mut App) {
+        app.init_resource::<PhysicsCrashDump>().add_systems(
+            Update,
+            (
+                submarine::ramp_inputs.before(SimSet),
+                submarine::simulate_submarine.in_set(SimSet),
+                submarine::apply_server_corrections
+                    .after(NetSet)
+                    .before(SimSet),
+            ),
+        );
+    }
+
+    /// Full throttle every frame, batched through the client's `InputBatcher` like
+    /// `send_thrust_input`.
     fn drive_full_throttle(
         client: Option<ResMut<RenetClient>>,
         mut throttle: ResMut<TestThrottleState>,
+        mut batcher: ResMut<InputBatcher>,
+        mut controls: ResMut<ThrustInput>,
     ) {
         let Some(mut client) = client else {
             return;
@@ -77,17 +129,81 @@ mod integration {
         if !client.is_connected() {
             return;
         }
+        // Local prediction reads the same throttle the server is sent
+        controls.value = 1.0;
 
         throttle.tick = throttle.tick.wrapping_add(1);
-        let msg = ClientToServer::InputTick(protocol::InputTick {
+        let tick = protocol::InputTick {
             tick: throttle.tick,
             thrust: 1.0,
             yaw: 0.0,
             pump_fwd: 0.0,
             pump_aft: 0.0,
+            pitch: 0.0,
+            roll_trim: 0.0,
+        };
+        if let Some(msg) = batcher.push(tick) {
+            if let Ok(bytes) = protocol::encode(&msg) {
+                client.send_message(Channel::Input, bytes);
+            }
+        }
+    }
+
+    /// Full-throttle ticks per frame sent by `flood_inputs`; 200 Hz at `FLOOD_DT`.
+    const FLOOD_INPUTS_PER_FRAME: usize = 4;
+    const FLOOD_DT: f32 = 1.0 / 50.0;
+
+    fn flood_inputs(client: Option<ResMut<RenetClient>>, mut throttle: ResMut<TestThrottleState>) {
+        let Some(mut client) = client else {
+            return;
+        };
+        if !client.is_connected() {
+            return;
+        }
+        for _ in 0..FLOOD_INPUTS_PER_FRAME {
+            throttle.tick = throttle.tick.wrapping_add(1);
+            let msg = ClientToServer::InputTick(protocol::InputTick {
+                tick: throttle.tick,
+                thrust: 1.0,
+                yaw: 0.0,
+                pump_fwd: 0.0,
+                pump_aft: 0.0,
+                pitch: 0.0,
+                roll_trim: 0.0,
+            });
+            if let Ok(bytes) = protocol::encode(&msg) {
+                client.send_message(Channel::Reliable, bytes);
+            }
+        }
+    }
+
+    /// Forward pump command re-sent every frame by `drive_pumps`; thrust and yaw stay at zero.
+    #[derive(Resource, Default)]
+    struct TestPumpState {
+        tick: u64,
+        pump_fwd: f32,
+    }
+
+    fn drive_pumps(client: Option<ResMut<RenetClient>>, mut pumps: ResMut<TestPumpState>) {
+        let Some(mut client) = client else {
+            return;
+        };
+        if !client.is_connected() {
+            return;
+        }
+
+        pumps.tick = pumps.tick.wrapping_add(1);
+        let msg = ClientToServer::InputTick(protocol::InputTick {
+            tick: pumps.tick,
+            thrust: 0.0,
+            yaw: 0.0,
+            pump_fwd: pumps.pump_fwd,
+            pump_aft: 0.0,
+            pitch: 0.0,
+            roll_trim: 0.0,
         });
         if let Ok(bytes) = protocol::encode(&msg) {
-            client.send_message(DefaultChannel::ReliableOrdered, bytes);
+            client.send_message(Channel::Reliable, bytes);
         }
     }
 
@@ -146,13 +262,12 @@ mod integration {
 
         let client_args = ClientArgs {
             server: server_addr.to_string(),
-            headless: true,
-            name: Some("integration-test".to_string()),
-            connect_timeout_secs: 5,
+            ..client_args(port, "integration-test")
         };
 
         let mut client_app = build_minimal_client_app(client_args);
         client_app.add_systems(Startup, spawn_test_submarine);
+        add_local_prediction(&mut client_app);
         client_app.insert_resource(TestThrottleState::default());
         client_app.add_systems(Update, drive_full_throttle);
 
@@ -195,31 +310,1640 @@ mod integration {
             advance_app(&mut client_app, SIM_DT);
         }
 
-        let mut max_delta = 0.0f32;
+        let mut max_err = 0.0f32;
         for step in 0..SIM_STEPS {
             advance_app(&mut server_app, SIM_DT);
             advance_app(&mut client_app, SIM_DT);
 
-            if let (Some(server_pos), Some(filtered_pos)) = (
-                server_sub_position(&server_app),
-                client_latest_position(&client_app),
-            ) {
-                let delta = distance(server_pos, filtered_pos);
-                if step >= IGNORE_STEPS {
-                    max_delta = max_delta.max(delta);
-                    assert!(
-                        delta < HARD_THRESHOLD,
-                        "predicted divergence {delta:.5} exceeded hard limit"
-                    );
-                }
+            let err = client_prediction_error(&client_app)
+                .expect("no predicted state at the snapshot tick");
+            if step >= IGNORE_STEPS {
+                max_err = max_err.max(err);
+                assert!(
+                    err < HARD_THRESHOLD,
+                    "prediction is {err:.5} m from the server at step {step}"
+                );
+            }
+        }
+
+        assert!(
+            max_err < SOFT_THRESHOLD,
+            "max prediction error {max_err:.5} exceeded target {SOFT_THRESHOLD}"
+        );
+
+        Ok(())
+    }
+
+    fn client_player_id(app: &App) -> Option<uuid::Uuid> {
+        app.world().get_resource::<MyPlayerId>().and_then(|id| id.0)
+    }
+
+    /// Headless client for the server on `port`, with a throwaway identity.
+    fn client_args(port: u16, name: &str) -> ClientArgs {
+        ClientArgs {
+            server: format!("127.0.0.1:{port}"),
+            headless: true,
+            name: Some(name.to_string()),
+            ephemeral_identity: true,
+            ..Default::default()
+        }
+    }
+
+    /// Step the server and `clients` together until every client has been given a player id.
+    fn join_all(server_app: &mut App, clients: &mut [App]) -> Vec<uuid::Uuid> {
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(server_app, HANDSHAKE_DT);
+            for client_app in clients.iter_mut() {
+                advance_app(client_app, HANDSHAKE_DT);
+            }
+            if clients.iter().all(|c| client_player_id(c).is_some()) {
+                break;
+            }
+        }
+        clients
+            .iter()
+            .map(|c| client_player_id(c).expect("client never joined"))
+            .collect()
+    }
+
+    fn join_server(server_app: &mut App, client_app: &mut App) -> uuid::Uuid {
+        join_all(server_app, std::slice::from_mut(client_app))[0]
+    }
+
+    /// A headless client called `name` that has joined the server on `port`.
+    fn connect_client(server_app: &mut App, port: u16, name: &str) -> App {
+        let mut client_app = build_minimal_client_app(client_args(port, name));
+        join_server(server_app, &mut client_app);
+        client_app
+    }
+
+    #[test]
+    fn client_reconnects_after_disconnect() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = build_minimal_client_app(client_args(port, "reconnect-test"));
+        client_app.insert_resource(ReconnectPolicy {
+            delay_s: 0.0,
+            ..ReconnectPolicy::default()
+        });
+
+        let first_id = join_server(&mut server_app, &mut client_app);
+
+        // Leave the sub mid-tunnel and damaged; both should survive the reconnect
+        let parked = greybox_level().tunnel.pos;
+        place_server_sub(&mut server_app, parked);
+        let mut q = server_app.world_mut().query::<&mut HullIntegrity>();
+        q.single_mut(server_app.world_mut()).expect("server hull").0 = 0.5;
+        // Score and team come back from `DepartedPlayers`; team 1 is not the default assignment
+        let mut q = server_app
+            .world_mut()
+            .query::<(&mut PlayerScore, &mut Team)>();
+        let (mut score, mut team) = q.single_mut(server_app.world_mut()).expect("server score");
+        score.credits = 750;
+        score.docks = 2;
+        *team = Team(1);
+        let first_entity = server_player_entity(&mut server_app, first_id);
+
+        client_app
+            .world_mut()
+            .resource_mut::<NetcodeClientTransport>()
+            .disconnect();
+
+        let mut saw_pending = false;
+        let mut second_id = None;
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            saw_pending |= client_app.world().contains_resource::<ReconnectPending>();
+            second_id = client_player_id(&client_app);
+            if second_id.is_some() {
+                break;
+            }
+        }
+
+        assert!(saw_pending, "disconnect did not schedule a reconnect");
+        let second_id = second_id.expect("client never rejoined after disconnect");
+        assert_eq!(
+            first_id, second_id,
+            "rejoin should keep the client's stable identity"
+        );
+        assert_eq!(
+            client_app
+                .world()
+                .resource::<ReconnectPolicy>()
+                .current_attempt,
+            0,
+            "successful rejoin should reset the attempt counter"
+        );
+        // `MyPlayerId` outlives the disconnect, so wait for the server to respawn the sub
+        for _ in 0..HANDSHAKE_STEPS {
+            let entity = server_player_entity(&mut server_app, second_id);
+            if entity.is_some() && entity != first_entity {
+                break;
+            }
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+        }
+        assert_ne!(
+            server_player_entity(&mut server_app, second_id),
+            first_entity,
+            "server never respawned the rejoined sub"
+        );
+        let restored = server_player_position(&mut server_app, second_id).expect("rejoined sub");
+        assert!(
+            distance(restored, parked.to_array()) < 1.0,
+            "rejoined at {restored:?}, left at {parked:?}"
+        );
+        let mut q = server_app.world_mut().query::<&HullIntegrity>();
+        let hull = q.single(server_app.world()).expect("server hull").0;
+        assert_eq!(hull, 0.5, "hull integrity should survive the reconnect");
+        let mut q = server_app.world_mut().query::<(&PlayerScore, &Team)>();
+        let (score, team) = q.single(server_app.world()).expect("server score");
+        assert_eq!(
+            (score.credits, score.docks),
+            (750, 2),
+            "score should survive the reconnect"
+        );
+        assert_eq!(*team, Team(1), "team should survive the reconnect");
+        Ok(())
+    }
+
+    #[test]
+    fn kicked_client_is_refused_when_it_reconnects() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = connect_client(&mut server_app, port, "kick-test");
+        let player_id = client_player_id(&client_app).expect("client never joined");
+        let identity = client_app.world().resource::<ClientIdentity>().clone();
+        set_server_credits(&mut server_app, 750);
+
+        // Hold the sub in the middle of the station room, well over its plausible speed, until
+        // the anti-cheat kicks it. The room leaves space for the overshoot of a tick at that speed.
+        let room_centre = Vec3f::new(0.0, 0.0, -60.0);
+        for _ in 0..HANDSHAKE_STEPS {
+            let mut q = server_app.world_mut().query::<&mut ServerSubStateComp>();
+            for mut state in q.iter_mut(server_app.world_mut()) {
+                state.0.position = room_centre;
+                state.0.velocity = Vec3f::new(0.0, 0.0, 100.0);
+            }
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            if client_app.should_exit().is_some() {
+                break;
+            }
+        }
+        assert!(
+            matches!(
+                client_app.world().get_resource::<ServerDisconnect>(),
+                Some(ServerDisconnect(DisconnectReason::Kicked))
+            ),
+            "client was never told it was kicked"
+        );
+        assert_eq!(client_app.should_exit(), Some(AppExit::error()));
+        for _ in 0..HANDSHAKE_STEPS {
+            if server_player_entity(&mut server_app, player_id).is_none() {
+                break;
+            }
+            advance_app(&mut server_app, HANDSHAKE_DT);
+        }
+        assert!(
+            !server_app
+                .world()
+                .resource::<DepartedPlayers>()
+                .0
+                .values()
+                .any(|back| back.player_id == player_id),
+            "a kicked player's state was kept for its return"
+        );
+
+        // The same identity coming back is refused rather than restored
+        let mut rejoin_app = build_minimal_client_app(client_args(port, "kick-test"));
+        rejoin_app.insert_resource(identity);
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut rejoin_app, HANDSHAKE_DT);
+            if rejoin_app.should_exit().is_some() {
+                break;
+            }
+        }
+        assert_eq!(
+            client_player_id(&rejoin_app),
+            None,
+            "kicked player rejoined"
+        );
+        assert!(
+            matches!(
+                rejoin_app.world().get_resource::<ServerDisconnect>(),
+                Some(ServerDisconnect(DisconnectReason::Kicked))
+            ),
+            "rejoin was not refused as kicked"
+        );
+        assert!(server_player_entity(&mut server_app, player_id).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn known_player_id_alone_does_not_restore_a_departed_player() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = connect_client(&mut server_app, port, "owner");
+        let player_id = client_player_id(&client_app).expect("client never joined");
+        let owner = client_app.world().resource::<ClientIdentity>().clone();
+        set_server_credits(&mut server_app, 750);
+        client_app
+            .world_mut()
+            .resource_mut::<NetcodeClientTransport>()
+            .disconnect();
+        drop(client_app);
+        for _ in 0..HANDSHAKE_STEPS {
+            if server_player_entity(&mut server_app, player_id).is_none() {
+                break;
+            }
+            advance_app(&mut server_app, HANDSHAKE_DT);
+        }
+
+        // The player id is public; without the owner's secret it starts from scratch
+        let mut impostor_app = build_minimal_client_app(client_args(port, "impostor"));
+        impostor_app.insert_resource(ClientIdentity {
+            secret: ClientIdentity::new("impostor").secret,
+            ..owner.clone()
+        });
+        join_server(&mut server_app, &mut impostor_app);
+        let mut q = server_app.world_mut().query::<&PlayerScore>();
+        let credits = q.single(server_app.world()).expect("impostor score").credits;
+        assert_eq!(credits, 0, "an impostor got the departed player's score");
+        assert!(
+            server_app
+                .world()
+                .resource::<DepartedPlayers>()
+                .0
+                .contains_key(&owner.client_id()),
+            "the owner's state should still wait for them"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn second_session_for_an_active_player_is_refused() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = connect_client(&mut server_app, port, "first");
+        let player_id = client_player_id(&client_app).expect("client never joined");
+        let identity = client_app.world().resource::<ClientIdentity>().clone();
+
+        let mut second_app = build_minimal_client_app(client_args(port, "second"));
+        second_app.insert_resource(ClientIdentity {
+            secret: ClientIdentity::new("second").secret,
+            ..identity
+        });
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            advance_app(&mut second_app, HANDSHAKE_DT);
+            if second_app.should_exit().is_some() {
+                break;
             }
         }
+        assert!(
+            matches!(
+                second_app.world().get_resource::<ServerDisconnect>(),
+                Some(ServerDisconnect(DisconnectReason::IdentityInUse))
+            ),
+            "the second session was not refused"
+        );
+        assert_eq!(client_player_id(&second_app), None);
+        assert_eq!(server_player_count(&mut server_app), 1);
+        assert!(server_player_entity(&mut server_app, player_id).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn injected_level_sets_the_spawn_point() -> Result<()> {
+        // Shorten the tunnel from the room end: its entrance, where subs spawn, moves 40 m in
+        let mut level = greybox_level();
+        level.tunnel.pos.x += 20.0;
+        level.tunnel.size.x -= 40.0;
+        let t = &level.tunnel;
+        let spawn = [t.pos.x - t.size.x * 0.5 + 6.0, t.pos.y, t.pos.z];
+        let builtin = greybox_level().tunnel;
+        let builtin_spawn = [
+            builtin.pos.x - builtin.size.x * 0.5 + 6.0,
+            builtin.pos.y,
+            builtin.pos.z,
+        ];
+        assert!(distance(spawn, builtin_spawn) > 30.0);
+
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app_with_level(
+            Config {
+                port,
+                ..Config::default()
+            },
+            level,
+        );
+        advance_app(&mut server_app, HANDSHAKE_DT);
 
+        let mut client_app = build_minimal_client_app(client_args(port, "level-injection-test"));
+
+        let mut first_seen = None;
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            first_seen = client_player_id(&client_app).and_then(|id| {
+                let delta = client_app
+                    .world()
+                    .resource::<LatestStateDelta>()
+                    .0
+                    .as_ref()?;
+                let me = delta.players.iter().find(|p| p.id == id)?;
+                Some(me.position)
+            });
+            if first_seen.is_some() {
+                break;
+            }
+        }
+        let first_seen = first_seen.expect("client never received its sub");
+        // A few ticks of drift in the tunnel flow at most
         assert!(
-            max_delta < SOFT_THRESHOLD,
-            "max divergence {max_delta:.5} exceeded target {SOFT_THRESHOLD}"
+            distance(first_seen, spawn) < 0.5,
+            "client first saw its sub at {first_seen:?}, spawn is {spawn:?}"
+        );
+        let campaign = server_app.world().resource::<CampaignRes>();
+        assert_eq!(campaign.spec.levels.len(), 1);
+        Ok(())
+    }
+
+    fn client_team(app: &App) -> Option<u8> {
+        let id = client_player_id(app)?;
+        app.world()
+            .get_resource::<TeamRoster>()
+            .and_then(|roster| roster.0.get(&id).copied())
+    }
+
+    #[test]
+    fn team_deathmatch_splits_two_clients() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            team_deathmatch: true,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut clients: Vec<App> = ["alpha", "bravo"]
+            .iter()
+            .map(|name| build_minimal_client_app(client_args(port, name)))
+            .collect();
+
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            for client_app in clients.iter_mut() {
+                advance_app(client_app, HANDSHAKE_DT);
+            }
+            if clients.iter().all(|c| client_team(c).is_some()) {
+                break;
+            }
+        }
+
+        let teams: Vec<u8> = clients
+            .iter()
+            .map(|c| client_team(c).expect("client never received its team"))
+            .collect();
+        assert_ne!(
+            teams[0], teams[1],
+            "both clients landed on team {}",
+            teams[0]
         );
+        Ok(())
+    }
+
+    #[test]
+    fn shutdown_notifies_every_client_before_exit() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut clients: Vec<App> = ["alpha", "bravo"]
+            .iter()
+            .map(|name| build_minimal_client_app(client_args(port, name)))
+            .collect();
+        join_all(&mut server_app, &mut clients);
+
+        *server_app.world_mut().resource_mut::<ShutdownSignal>() = ShutdownSignal {
+            requested: true,
+            countdown_ticks: 6,
+        };
+        let mut exited = false;
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            for client_app in clients.iter_mut() {
+                advance_app(client_app, HANDSHAKE_DT);
+            }
+            if server_app.should_exit().is_some() {
+                exited = true;
+                break;
+            }
+        }
+        assert!(exited, "server never exited after the countdown");
+
+        for client_app in &clients {
+            let notice = client_app.world().get_resource::<ServerDisconnect>();
+            assert!(
+                matches!(
+                    notice,
+                    Some(ServerDisconnect(DisconnectReason::ServerShutdown))
+                ),
+                "client missed the shutdown notice: {notice:?}"
+            );
+        }
+
+        // The notice stops the reconnect policy: each client exits cleanly once the transport drops
+        for _ in 0..HANDSHAKE_STEPS {
+            for client_app in clients.iter_mut() {
+                advance_app(client_app, HANDSHAKE_DT);
+                assert!(
+                    !client_app.world().contains_resource::<ReconnectPending>(),
+                    "client tried to reconnect to a server that shut down"
+                );
+            }
+            if clients.iter().all(|c| c.should_exit().is_some()) {
+                break;
+            }
+        }
+        for client_app in &clients {
+            assert_eq!(
+                client_app.should_exit(),
+                Some(AppExit::Success),
+                "client did not exit cleanly after the shutdown"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn flooding_client_is_told_to_slow_down() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, FLOOD_DT);
+
+        let mut client_app = build_minimal_client_app(client_args(port, "flooder"));
+        client_app.insert_resource(TestThrottleState::default());
+        client_app.add_systems(Update, flood_inputs);
 
+        let mut limit = None;
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, FLOOD_DT);
+            advance_app(&mut client_app, FLOOD_DT);
+            limit = client_app.world().get_resource::<InputRateLimit>().copied();
+            if limit.is_some() {
+                break;
+            }
+        }
+        assert_eq!(
+            limit,
+            Some(InputRateLimit {
+                allowed_hz: Config::default().max_inputs_per_sec,
+            }),
+            "client never received a RateLimit notice"
+        );
         Ok(())
     }
+
+    #[test]
+    fn batched_input_ticks_reach_the_server() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = build_minimal_client_app(client_args(port, "batcher"));
+        client_app.insert_resource(TestThrottleState::default());
+        client_app.add_systems(Update, drive_full_throttle);
+
+        let mut last_tick = None;
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            let sent = client_app.world().resource::<TestThrottleState>().tick;
+            last_tick = server_app
+                .world_mut()
+                .query::<&InputBuffer>()
+                .iter(server_app.world())
+                .next()
+                .and_then(InputBuffer::newest)
+                .map(|(tick, inputs)| (tick, inputs.thrust));
+            if sent >= 60 && last_tick.is_some() {
+                break;
+            }
+        }
+        let (server_tick, thrust) = last_tick.expect("server never received a batched input");
+        let sent = client_app.world().resource::<TestThrottleState>().tick;
+        let interval = InputBatcher::default().flush_interval_ticks as u64;
+        // Up to one partial batch may still be queued, plus one in flight
+        assert!(
+            sent - server_tick < 2 * interval + 1,
+            "server at tick {server_tick}, client sent {sent}"
+        );
+        assert_eq!(thrust, 1.0);
+        Ok(())
+    }
+
+    #[test]
+    fn server_grants_only_enabled_features() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            enabled_features: FeatureFlags(FeatureFlags::ALL.0 & !FeatureFlags::TORPEDO.0),
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = build_minimal_client_app(client_args(port, "negotiator"));
+        client_app.insert_resource(RequestedFeatures(
+            FeatureFlags::SONAR | FeatureFlags::TORPEDO,
+        ));
+
+        let mut granted = None;
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            granted = client_app.world().get_resource::<ServerFeatures>().copied();
+            if granted.is_some() {
+                break;
+            }
+        }
+        assert_eq!(granted, Some(ServerFeatures(FeatureFlags::SONAR)));
+        assert!(
+            !client_app.world().contains_resource::<TorpedoControls>(),
+            "torpedo controls set up without the TORPEDO feature"
+        );
+
+        advance_app(&mut server_app, HANDSHAKE_DT);
+        let mut q = server_app.world_mut().query::<&GrantedFeatures>();
+        let on_server: Vec<_> = q.iter(server_app.world()).copied().collect();
+        assert_eq!(on_server, vec![GrantedFeatures(FeatureFlags::SONAR)]);
+        Ok(())
+    }
+
+    fn server_player_entity(app: &mut App, id: uuid::Uuid) -> Option<Entity> {
+        let mut q = app.world_mut().query::<(Entity, &Player)>();
+        q.iter(app.world())
+            .find(|(_, player)| player.id == id)
+            .map(|(entity, _)| entity)
+    }
+
+    fn server_player_position(app: &mut App, id: uuid::Uuid) -> Option<[f32; 3]> {
+        let mut q = app.world_mut().query::<(&Player, &ServerSubStateComp)>();
+        q.iter(app.world())
+            .find(|(player, _)| player.id == id)
+            .map(|(_, state)| {
+                let pos = state.0.position;
+                [pos.x, pos.y, pos.z]
+            })
+    }
+
+    fn client_latest_rotation(app: &App) -> Option<Quatf> {
+        app.world()
+            .get_resource::<FilteredServerState>()
+            .filter(|filtered| filtered.initialized)
+            .map(|filtered| filtered.body_rot)
+    }
+
+    /// How far the client's predicted sub was, at the tick of its latest snapshot, from where
+    /// that snapshot puts it.
+    fn client_prediction_error(app: &App) -> Option<f32> {
+        let world = app.world();
+        let id = client_player_id(app)?;
+        let delta = world.resource::<LatestStateDelta>().0.as_ref()?;
+        let me = delta.players.iter().find(|p| p.id == id)?;
+        let predicted = world.resource::<InputHistory>().at(delta.tick)?;
+        Some(distance(predicted.state.position.to_array(), me.position))
+    }
+
+    /// One recorded step of `run_two_clients`: each client's filtered position and body
+    /// orientation, and how far its prediction was from the server at the snapshot tick.
+    struct TwoClientSample {
+        clients: [([f32; 3], Quatf); 2],
+        prediction_err: [Option<f32>; 2],
+    }
+
+    /// One server and two headless clients at full throttle; records `TWO_CLIENT_STEPS` steps
+    /// after warmup.
+    fn run_two_clients() -> Vec<TwoClientSample> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut clients: Vec<App> = ["left", "right"]
+            .iter()
+            .map(|name| {
+                let mut client_app = build_minimal_client_app(client_args(port, name));
+                client_app.add_systems(Startup, spawn_test_submarine);
+                add_local_prediction(&mut client_app);
+                client_app.insert_resource(TestThrottleState::default());
+                client_app.add_systems(Update, drive_full_throttle);
+                client_app
+            })
+            .collect();
+
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            for client_app in clients.iter_mut() {
+                advance_app(client_app, HANDSHAKE_DT);
+            }
+            if clients.iter().all(|c| client_latest_position(c).is_some()) {
+                break;
+            }
+        }
+        assert!(
+            clients.iter().all(|c| client_latest_position(c).is_some()),
+            "a client never received its own state"
+        );
+        assert!(
+            clients.iter().all(|c| client_player_id(c).is_some()),
+            "a client never joined"
+        );
+
+        for _ in 0..WARMUP_STEPS {
+            advance_app(&mut server_app, SIM_DT);
+            for client_app in clients.iter_mut() {
+                advance_app(client_app, SIM_DT);
+            }
+        }
+
+        (0..TWO_CLIENT_STEPS)
+            .map(|_| {
+                advance_app(&mut server_app, SIM_DT);
+                for client_app in clients.iter_mut() {
+                    advance_app(client_app, SIM_DT);
+                }
+                let client_state = |i: usize| {
+                    let app = &clients[i];
+                    (
+                        client_latest_position(app).expect("client lost its state"),
+                        client_latest_rotation(app).expect("client lost its state"),
+                    )
+                };
+                TwoClientSample {
+                    clients: [client_state(0), client_state(1)],
+                    prediction_err: [
+                        client_prediction_error(&clients[0]),
+                        client_prediction_error(&clients[1]),
+                    ],
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn two_client_convergence() {
+        for (step, sample) in run_two_clients().iter().enumerate() {
+            let [(pos_a, rot_a), (pos_b, rot_b)] = sample.clients;
+            let between = distance(pos_a, pos_b);
+            assert!(
+                between < 2.0 * SOFT_THRESHOLD,
+                "clients diverged by {between:.5} m at step {step}"
+            );
+            assert!(
+                rot_a.angle_between(rot_b) < 1e-3,
+                "client orientations diverged at step {step}"
+            );
+        }
+    }
+
+    #[test]
+    fn two_client_prediction_tracks_server() {
+        for (step, sample) in run_two_clients().iter().enumerate() {
+            for err in sample.prediction_err.iter() {
+                let err = err.expect("no predicted state at the snapshot tick");
+                assert!(
+                    err < HARD_THRESHOLD,
+                    "prediction is {err:.5} m from the server at step {step}"
+                );
+            }
+        }
+    }
+
+    fn client_sees_player(app: &App, id: uuid::Uuid) -> bool {
+        app.world()
+            .resource::<LatestStateDelta>()
+            .0
+            .as_ref()
+            .is_some_and(|delta| delta.players.iter().any(|p| p.id == id))
+    }
+
+    #[test]
+    fn distant_players_are_culled_from_each_others_snapshots() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut clients: Vec<App> = ["near", "far"]
+            .iter()
+            .map(|name| build_minimal_client_app(client_args(port, name)))
+            .collect();
+
+        // Both spawn at the tunnel entrance, well within view of each other
+        let mut ids = Vec::new();
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            for client_app in clients.iter_mut() {
+                advance_app(client_app, HANDSHAKE_DT);
+            }
+            ids = clients.iter().filter_map(client_player_id).collect();
+            if ids.len() == 2
+                && client_sees_player(&clients[0], ids[1])
+                && client_sees_player(&clients[1], ids[0])
+            {
+                break;
+            }
+        }
+        assert_eq!(ids.len(), 2, "a client never joined");
+        assert!(client_sees_player(&clients[0], ids[1]));
+        assert!(client_sees_player(&clients[1], ids[0]));
+
+        // 200 m apart along the room and tunnel: 6 cells of 32 m, past the default radius of 3
+        for step in 0..60 {
+            for (id, x) in [(ids[0], 0.0), (ids[1], 200.0)] {
+                let mut q = server_app
+                    .world_mut()
+                    .query::<(&Player, &mut ServerSubStateComp)>();
+                for (player, mut state) in q.iter_mut(server_app.world_mut()) {
+                    if player.id == id {
+                        state.0.position = Vec3f::new(x, 4.0, 0.0);
+                        state.0.velocity = Vec3f::new(0.0, 0.0, 0.0);
+                    }
+                }
+            }
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            for client_app in clients.iter_mut() {
+                advance_app(client_app, HANDSHAKE_DT);
+            }
+            // Let snapshots sent before the move drain
+            if step < 30 {
+                continue;
+            }
+            for (i, client_app) in clients.iter().enumerate() {
+                assert!(
+                    client_sees_player(client_app, ids[i]),
+                    "client {i} lost its own entry"
+                );
+                assert!(
+                    !client_sees_player(client_app, ids[1 - i]),
+                    "client {i} still receives the other player 200 m away"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn client_received_state(app: &App) -> bool {
+        app.world()
+            .get_resource::<LatestStateDelta>()
+            .is_some_and(|latest| latest.0.is_some())
+    }
+
+    #[test]
+    fn udp_and_websocket_clients_share_a_server() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ws_port: Some(0),
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+        let ws_addr = server_app
+            .world()
+            .resource::<ServerAddresses>()
+            .ws
+            .expect("server started without its WebSocket proxy");
+
+        let mut clients: Vec<App> = [None, Some(ws_addr.to_string())]
+            .into_iter()
+            .map(|ws| {
+                build_minimal_client_app(ClientArgs {
+                    ws,
+                    ..client_args(port, "transport-test")
+                })
+            })
+            .collect();
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            for client_app in clients.iter_mut() {
+                advance_app(client_app, HANDSHAKE_DT);
+            }
+            if clients.iter().all(client_received_state) {
+                break;
+            }
+        }
+
+        assert!(
+            client_received_state(&clients[0]),
+            "UDP client never received a state delta"
+        );
+        assert!(
+            clients[1].world().contains_resource::<WsClientTransport>(),
+            "second client is not using the WebSocket transport"
+        );
+        assert!(
+            client_received_state(&clients[1]),
+            "WebSocket client never received a state delta"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn client_hole_punches_through_rendezvous() -> Result<()> {
+        let rendezvous_port = server::rendezvous::spawn_rendezvous(0)?.port();
+        let punch = HolePunchConfig {
+            rendezvous_addr: format!("127.0.0.1:{rendezvous_port}").parse()?,
+            session_token: [0x5a; 32],
+        };
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            rendezvous: Some(punch.to_url()),
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        // Only the rendezvous URL: the server address has to come from the rendezvous
+        let mut client_app = build_minimal_client_app(ClientArgs {
+            server: punch.to_url(),
+            ..client_args(port, "punch-test")
+        });
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            if client_received_state(&client_app) {
+                break;
+            }
+        }
+
+        assert!(
+            client_received_state(&client_app),
+            "hole-punched client never received a state delta"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn server_beacon_is_heard_on_the_lan() -> Result<()> {
+        // Listen before the server starts so its first beacon is not missed
+        let listener = UdpSocket::bind(("0.0.0.0", 0))?;
+        let discovery_port = listener.local_addr()?.port();
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            server_name: "beacon-test".to_string(),
+            discovery_port: Some(discovery_port),
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let found = collect_beacons(&listener, DISCOVERY_LISTEN)?;
+        let expected = ServerBeacon {
+            name: "beacon-test".to_string(),
+            addr: format!("127.0.0.1:{port}").parse()?,
+            player_count: 0,
+            max_players: Config::default().max_clients as u8,
+        };
+        assert_eq!(found, vec![expected]);
+        Ok(())
+    }
+
+    fn client_map_id(app: &App) -> usize {
+        app.world().resource::<CurrentLevel>().map_id
+    }
+
+    #[test]
+    fn campaign_advances_once_credits_unlock_the_next_level() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = connect_client(&mut server_app, port, "campaign-test");
+
+        // Short of the builtin unlock: still on the first level
+        let mut q_scores = server_app.world_mut().query::<&mut PlayerScore>();
+        for mut score in q_scores.iter_mut(server_app.world_mut()) {
+            score.credits = 4_999;
+        }
+        for _ in 0..10 {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+        }
+        assert_eq!(server_app.world().resource::<CampaignRes>().active, 0);
+        assert_eq!(client_map_id(&client_app), 0);
+
+        for mut score in q_scores.iter_mut(server_app.world_mut()) {
+            score.credits = 5_000;
+        }
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            if client_map_id(&client_app) == 1 {
+                break;
+            }
+        }
+
+        let campaign = server_app.world().resource::<CampaignRes>();
+        assert_eq!(campaign.active, 1);
+        assert_eq!(campaign.completed, vec![0]);
+        assert_eq!(
+            client_map_id(&client_app),
+            1,
+            "client never received LevelReload"
+        );
+        let tunnel = &campaign.spec.levels[1].level_spec.tunnel;
+        let pos = server_sub_position(&server_app).expect("server sub missing");
+        assert!(
+            (pos[0] - (tunnel.pos.x - tunnel.size.x * 0.5 + 6.0)).abs() < 5.0,
+            "sub was not moved to the new level's start: {pos:?}"
+        );
+        Ok(())
+    }
+
+    fn server_obstacle_travel(app: &mut App) -> Vec<f32> {
+        let mut q = app.world_mut().query::<&MovingObstacle>();
+        q.iter(app.world()).map(|o| o.traveled_m).collect()
+    }
+
+    #[test]
+    fn obstacles_move_on_the_physics_tick() {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            tick_hz: 30,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+        let start = server_obstacle_travel(&mut server_app);
+        assert!(!start.is_empty(), "greybox level has no moving obstacles");
+
+        let step_m: Vec<f32> = {
+            let mut q = server_app.world_mut().query::<&MovingObstacle>();
+            q.iter(server_app.world())
+                .map(|o| o.spec.speed_m_s / 30.0)
+                .collect()
+        };
+
+        // Frames shorter than a tick: obstacles only ever move by whole physics steps
+        for _ in 0..10 {
+            advance_app(&mut server_app, 0.37 / 30.0);
+            let travel = server_obstacle_travel(&mut server_app);
+            for ((after, before), step) in travel.iter().zip(&start).zip(&step_m) {
+                let steps = (after - before) / step;
+                assert!(
+                    (steps - steps.round()).abs() < 1e-2,
+                    "moved {steps} physics steps"
+                );
+            }
+        }
+        assert_ne!(server_obstacle_travel(&mut server_app), start);
+    }
+
+    fn server_torpedo_count(app: &mut App) -> usize {
+        let mut q = app.world_mut().query::<&Torpedo>();
+        q.iter(app.world()).count()
+    }
+
+    #[test]
+    fn fired_torpedo_flies_and_detonates() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = connect_client(&mut server_app, port, "torpedo-test");
+
+        // The second shot lands while the first tube reloads, the third during the player's
+        // cooldown even though tube 1 is loaded
+        for tube_id in [0, 0, 1] {
+            let fire = ClientToServer::FireTorpedo(protocol::FireTorpedo {
+                tube_id,
+                target_bearing_deg: 0.0,
+                speed_mps: 0.0,
+                heading_override: None,
+            });
+            client_app
+                .world_mut()
+                .resource_mut::<RenetClient>()
+                .send_message(Channel::Reliable, protocol::encode(&fire)?);
+        }
+        for _ in 0..60 {
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            if server_torpedo_count(&mut server_app) > 0 {
+                break;
+            }
+        }
+        assert_eq!(server_torpedo_count(&mut server_app), 1);
+        let mut q_tubes = server_app.world_mut().query::<&TorpedoTubes>();
+        let tubes = q_tubes.single(server_app.world())?;
+        assert!(tubes.0[0] > 0.0, "fired tube is not reloading");
+        assert_eq!(tubes.0[1], 0.0);
+        assert_eq!(
+            server_app.world().resource::<TorpedoCooldown>().0.len(),
+            1,
+            "launch did not start the player's cooldown"
+        );
+
+        // Every run ends within its 30 s lifetime; walls usually end it sooner
+        for _ in 0..(31.0 / HANDSHAKE_DT) as usize {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            if server_torpedo_count(&mut server_app) == 0 {
+                break;
+            }
+        }
+        assert_eq!(
+            server_torpedo_count(&mut server_app),
+            0,
+            "torpedo never detonated"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn starboard_bearing_launches_towards_minus_x() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = connect_client(&mut server_app, port, "torpedo-bearing-test");
+
+        let fire = ClientToServer::FireTorpedo(protocol::FireTorpedo {
+            tube_id: 0,
+            target_bearing_deg: 90.0,
+            speed_mps: 0.0,
+            heading_override: None,
+        });
+        client_app
+            .world_mut()
+            .resource_mut::<RenetClient>()
+            .send_message(Channel::Reliable, protocol::encode(&fire)?);
+        for _ in 0..60 {
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            if server_torpedo_count(&mut server_app) > 0 {
+                break;
+            }
+        }
+
+        let mut q_sub = server_app.world_mut().query::<&ServerSubStateComp>();
+        let orientation = q_sub.single(server_app.world())?.0.orientation;
+        let starboard = orientation * Vec3f::NEG_X;
+        let mut q_torpedo = server_app.world_mut().query::<&Torpedo>();
+        let torpedo = q_torpedo.single(server_app.world())?;
+        assert!(
+            torpedo.direction.dot(starboard) > 0.9,
+            "90 deg bearing launched along {:?}, starboard is {starboard:?}",
+            torpedo.direction
+        );
+        Ok(())
+    }
+
+    /// Run both apps for `seconds` of sim time with the given forward pump, returning server Y
+    /// once per step.
+    fn run_pump_phase(
+        server_app: &mut App,
+        client_app: &mut App,
+        pump_fwd: f32,
+        seconds: f32,
+    ) -> Vec<f32> {
+        client_app
+            .world_mut()
+            .resource_mut::<TestPumpState>()
+            .pump_fwd = pump_fwd;
+        (0..(seconds / SIM_DT) as usize)
+            .filter_map(|_| {
+                advance_app(server_app, SIM_DT);
+                advance_app(client_app, SIM_DT);
+                server_sub_position(server_app).map(|p| p[1])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ballast_depth_response() -> Result<()> {
+        const TOLERANCE_M: f32 = 0.5;
+        // Fill fraction per second at full pump in `step_submarine`
+        const PUMP_RATE_PER_S: f32 = 0.2;
+
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = build_minimal_client_app(client_args(port, "ballast-test"));
+        client_app.insert_resource(TestPumpState::default());
+        client_app.add_systems(Update, drive_pumps);
+        for _ in 0..HANDSHAKE_STEPS {
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            if client_player_id(&client_app).is_some() && server_sub_position(&server_app).is_some()
+            {
+                break;
+            }
+        }
+        assert!(
+            client_player_id(&client_app).is_some(),
+            "client never joined"
+        );
+        for _ in 0..WARMUP_STEPS {
+            advance_app(&mut server_app, SIM_DT);
+            advance_app(&mut client_app, SIM_DT);
+        }
+        let start_y = server_sub_position(&server_app).expect("server sub")[1];
+
+        // Spawn fill is 0.5 in every tank, which the physics treats as neutrally buoyant
+        let neutral = run_pump_phase(&mut server_app, &mut client_app, 0.0, 30.0);
+        let settled = *neutral.last().expect("neutral samples");
+        assert!(
+            (settled - start_y).abs() < TOLERANCE_M,
+            "neutral sub drifted from {start_y:.2} to {settled:.2}"
+        );
+
+        let filling = run_pump_phase(&mut server_app, &mut client_app, 1.0, 10.0);
+        assert_monotonic(&filling, -1.0, 0, TOLERANCE_M, "filling");
+        // The full forward tank stays heavier than neutral until it has pumped back down to 0.5,
+        // so the sub keeps sinking until then
+        let emptying = run_pump_phase(&mut server_app, &mut client_app, -1.0, 10.0);
+        let settle_steps = (0.5 / PUMP_RATE_PER_S / SIM_DT) as usize;
+        assert_monotonic(&emptying, 1.0, settle_steps, TOLERANCE_M, "emptying");
+        Ok(())
+    }
+
+    /// From `settle_steps` on, every sample stays within `tolerance` of the extreme reached so far
+    /// in direction `sign`, and the phase ends more than `tolerance` past its opposite extreme.
+    fn assert_monotonic(ys: &[f32], sign: f32, settle_steps: usize, tolerance: f32, phase: &str) {
+        let mut extreme = ys[settle_steps];
+        for (i, &y) in ys.iter().enumerate().skip(settle_steps) {
+            extreme = if sign > 0.0 {
+                extreme.max(y)
+            } else {
+                extreme.min(y)
+            };
+            assert!(
+                (extreme - y) * sign <= tolerance,
+                "{phase}: y {y:.2} at step {i} backtracked past {extreme:.2}"
+            );
+        }
+        let turnaround = ys
+            .iter()
+            .copied()
+            .fold(ys[0], |a, y| if (y - a) * sign < 0.0 { y } else { a });
+        let last = ys[ys.len() - 1];
+        assert!(
+            (last - turnaround) * sign > tolerance,
+            "{phase}: y only moved from {turnaround:.2} to {last:.2}"
+        );
+    }
+
+    /// Teleport the only server sub to `position` at rest.
+    fn place_server_sub(app: &mut App, position: Vec3f) {
+        let mut q = app.world_mut().query::<&mut ServerSubStateComp>();
+        let mut state = q.single_mut(app.world_mut()).expect("server sub");
+        state.0.position = position;
+        state.0.velocity = Vec3f::new(0.0, 0.0, 0.0);
+    }
+
+    fn set_server_credits(app: &mut App, credits: u64) {
+        let mut q = app.world_mut().query::<&mut PlayerScore>();
+        q.single_mut(app.world_mut()).expect("player score").credits = credits;
+    }
+
+    fn server_docks(app: &mut App) -> u32 {
+        let mut q = app.world_mut().query::<&PlayerScore>();
+        q.single(app.world()).expect("player score").docks
+    }
+
+    fn send_dock_request(client_app: &mut App) -> Result<()> {
+        let msg = ClientToServer::DockRequest(protocol::DockRequest);
+        client_app
+            .world_mut()
+            .resource_mut::<RenetClient>()
+            .send_message(Channel::Reliable, protocol::encode(&msg)?);
+        Ok(())
+    }
+
+    #[test]
+    fn dock_flow() -> Result<()> {
+        const POLL_STEPS: usize = 200;
+
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = connect_client(&mut server_app, port, "dock-test");
+
+        // Hover just above the pad, well inside the docking clearance
+        let room = greybox_level().room;
+        let above_pad = room.dock_pos + Vec3f::new(0.0, room.dock_size.y * 0.5 + 1.0, 0.0);
+        place_server_sub(&mut server_app, above_pad);
+        set_server_credits(&mut server_app, 500);
+        send_dock_request(&mut client_app)?;
+        for _ in 0..POLL_STEPS {
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            if client_app.world().resource::<Inventory>().credits == 500 {
+                break;
+            }
+        }
+        assert_eq!(
+            client_app.world().resource::<Inventory>().credits,
+            500,
+            "client never received a DockAck with its credits"
+        );
+        assert_eq!(server_docks(&mut server_app), 1);
+
+        // Away from the pad the request is dropped, so the changed balance never reaches the client
+        place_server_sub(&mut server_app, Vec3f::new(0.0, above_pad.y + 10.0, 0.0));
+        set_server_credits(&mut server_app, 900);
+        send_dock_request(&mut client_app)?;
+        for _ in 0..POLL_STEPS {
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            advance_app(&mut server_app, HANDSHAKE_DT);
+        }
+        assert_eq!(client_app.world().resource::<Inventory>().credits, 500);
+        assert_eq!(
+            server_docks(&mut server_app),
+            1,
+            "undocked request was accepted"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn docking_sells_cargo_and_wins_the_round() -> Result<()> {
+        const POLL_STEPS: usize = 200;
+
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            team_deathmatch: true,
+            team_score_limit: 100,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = connect_client(&mut server_app, port, "sell-test");
+
+        let room = greybox_level().room;
+        let above_pad = room.dock_pos + Vec3f::new(0.0, room.dock_size.y * 0.5 + 1.0, 0.0);
+        place_server_sub(&mut server_app, above_pad);
+        {
+            let mut q = server_app.world_mut().query::<&mut CargoHold>();
+            let mut hold = q.single_mut(server_app.world_mut()).expect("cargo hold");
+            hold.0.insert(ResourceType::Ore, 40);
+            hold.0.insert(ResourceType::Crystal, 20);
+        }
+        let expected =
+            40 * ResourceType::Ore.credits_per_kg() + 20 * ResourceType::Crystal.credits_per_kg();
+        assert!(expected >= 100, "cargo must be worth the score limit");
+
+        send_dock_request(&mut client_app)?;
+        for _ in 0..POLL_STEPS {
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            if client_app
+                .world()
+                .resource::<Leaderboard>()
+                .last_win
+                .is_some()
+            {
+                break;
+            }
+        }
+
+        let mut q = server_app.world_mut().query::<(&PlayerScore, &CargoHold)>();
+        let (score, hold) = q.single(server_app.world()).expect("player");
+        assert_eq!(score.credits, expected, "the hold was not sold");
+        assert!(hold.0.is_empty());
+        assert_eq!(client_app.world().resource::<Inventory>().credits, expected);
+        let win = client_app
+            .world()
+            .resource::<Leaderboard>()
+            .last_win
+            .expect("selling past the score limit did not end the round");
+        assert_eq!(win.score, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn mission_flow() -> Result<()> {
+        const POLL_STEPS: usize = 200;
+        // `builtin_missions()`: dock at the station 3 times
+        const DOCK_MISSION: u32 = 1;
+
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = connect_client(&mut server_app, port, "mission-test");
+
+        let room = greybox_level().room;
+        let above_pad = room.dock_pos + Vec3f::new(0.0, room.dock_size.y * 0.5 + 1.0, 0.0);
+        for docks in 1..=3 {
+            place_server_sub(&mut server_app, above_pad);
+            send_dock_request(&mut client_app)?;
+            for _ in 0..POLL_STEPS {
+                advance_app(&mut client_app, HANDSHAKE_DT);
+                advance_app(&mut server_app, HANDSHAKE_DT);
+                let progress = client_app
+                    .world()
+                    .resource::<MissionTracker>()
+                    .progress
+                    .get(&DOCK_MISSION)
+                    .copied();
+                if progress == Some((docks, 3)) {
+                    break;
+                }
+            }
+            assert_eq!(
+                client_app
+                    .world()
+                    .resource::<MissionTracker>()
+                    .progress
+                    .get(&DOCK_MISSION),
+                Some(&(docks, 3)),
+                "no MissionUpdate after dock {docks}"
+            );
+        }
+        // Let the MissionComplete that follows the last update land
+        for _ in 0..POLL_STEPS {
+            advance_app(&mut client_app, HANDSHAKE_DT);
+            advance_app(&mut server_app, HANDSHAKE_DT);
+            if client_app.world().resource::<Inventory>().credits > 0 {
+                break;
+            }
+        }
+
+        let mut q = server_app.world_mut().query::<&PlayerScore>();
+        let server_credits = q.single(server_app.world()).expect("player score").credits;
+        assert_eq!(server_credits, 100, "dock mission reward not paid");
+        assert_eq!(client_app.world().resource::<Inventory>().credits, 100);
+        Ok(())
+    }
+
+    fn server_cargo_kg(app: &mut App) -> u32 {
+        let mut q = app.world_mut().query::<&CargoHold>();
+        q.single(app.world()).expect("cargo hold").0.values().sum()
+    }
+
+    /// Send one `MineRequest` and give the server time to answer it.
+    fn mine(server_app: &mut App, client_app: &mut App, node_id: u32) -> Result<()> {
+        let msg = ClientToServer::MineRequest(protocol::MineRequest { node_id });
+        client_app
+            .world_mut()
+            .resource_mut::<RenetClient>()
+            .send_message(Channel::Reliable, protocol::encode(&msg)?);
+        for _ in 0..20 {
+            advance_app(client_app, HANDSHAKE_DT);
+            advance_app(server_app, HANDSHAKE_DT);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn mine_requests_need_a_known_node_in_range() -> Result<()> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        let mut client_app = connect_client(&mut server_app, port, "mining-test");
+        let node = greybox_level().ore_nodes[0].position;
+
+        place_server_sub(&mut server_app, node);
+        mine(&mut server_app, &mut client_app, 999)?;
+        assert_eq!(
+            server_cargo_kg(&mut server_app),
+            0,
+            "unknown node was mined"
+        );
+
+        place_server_sub(&mut server_app, node + Vec3f::new(0.0, 20.0, 0.0));
+        mine(&mut server_app, &mut client_app, 0)?;
+        assert_eq!(
+            server_cargo_kg(&mut server_app),
+            0,
+            "node out of range was mined"
+        );
+
+        place_server_sub(&mut server_app, node);
+        mine(&mut server_app, &mut client_app, 0)?;
+        assert!(
+            server_cargo_kg(&mut server_app) > 0,
+            "node in range refused"
+        );
+        Ok(())
+    }
+
+    fn server_player_count(app: &mut App) -> usize {
+        let mut q = app.world_mut().query::<&Player>();
+        q.iter(app.world()).count()
+    }
+
+    #[test]
+    fn protocol_version_mismatch() -> Result<()> {
+        const BAD_PROTOCOL: u16 = 255;
+        assert_ne!(BAD_PROTOCOL, PROTOCOL_VERSION);
+
+        let hello = ClientToServer::Hello(ClientHello {
+            protocol: BAD_PROTOCOL,
+            player_id: uuid::Uuid::new_v4(),
+            display_name: None,
+            requested_features: FeatureFlags::NONE,
+        });
+        assert_hello_rejected(protocol::encode(&hello)?, BAD_PROTOCOL)
+    }
+
+    #[test]
+    fn version_header_mismatch_is_rejected_before_decoding() -> Result<()> {
+        // Older than the legacy version the server still accepts
+        const OLD_PROTOCOL: u16 = LEGACY_PROTOCOL_VERSION - 1;
+
+        // A well-formed current Hello behind an older client's version header
+        let hello = ClientToServer::Hello(ClientHello {
+            protocol: PROTOCOL_VERSION,
+            player_id: uuid::Uuid::new_v4(),
+            display_name: None,
+            requested_features: FeatureFlags::NONE,
+        });
+        let mut payload = protocol::encode(&hello)?;
+        // The version leads the header
+        payload[..2].copy_from_slice(&OLD_PROTOCOL.to_le_bytes());
+        assert_hello_rejected(payload, OLD_PROTOCOL)
+    }
+
+    #[test]
+    fn legacy_protocol_client_is_accepted_during_migration() -> Result<()> {
+        // Bincode behind a bare version, as a client built before MessagePack sends it
+        let hello = protocol::legacy::ClientToServerV20::Hello(ClientHello {
+            protocol: LEGACY_PROTOCOL_VERSION,
+            player_id: uuid::Uuid::new_v4(),
+            display_name: None,
+            requested_features: FeatureFlags::NONE,
+        });
+        let payload = protocol::legacy::encode(&hello)?;
+
+        let (received, mut server_app) = send_bare_hello(payload)?;
+        assert!(
+            received
+                .iter()
+                .any(|(_, msg)| matches!(msg, ServerToClient::JoinAck(_))),
+            "server never acked the legacy client: {received:?}"
+        );
+        assert!(!received
+            .iter()
+            .any(|(_, msg)| matches!(msg, ServerToClient::Disconnect(_))));
+        // Every reply is in the client's own version
+        assert!(received
+            .iter()
+            .all(|&(version, _)| version == LEGACY_PROTOCOL_VERSION));
+        assert_eq!(server_player_count(&mut server_app), 1);
+        Ok(())
+    }
+
+    /// Send `hello_payload` from a bare netcode client and expect an `IncompatibleProtocol`
+    /// rejection naming `client_protocol`, with no player spawned.
+    fn assert_hello_rejected(hello_payload: Vec<u8>, client_protocol: u16) -> Result<()> {
+        let (received, mut server_app) = send_bare_hello(hello_payload)?;
+        let received: Vec<_> = received.into_iter().map(|(_, msg)| msg).collect();
+        assert!(
+            received.iter().any(|msg| matches!(
+                msg,
+                ServerToClient::Disconnect(DisconnectReason::IncompatibleProtocol {
+                    server: PROTOCOL_VERSION,
+                    client,
+                }) if *client == client_protocol
+            )),
+            "server never rejected the hello: {received:?}"
+        );
+        assert!(
+            !received
+                .iter()
+                .any(|msg| matches!(msg, ServerToClient::JoinAck(_))),
+            "server acked an incompatible client"
+        );
+        assert_eq!(server_player_count(&mut server_app), 0);
+        Ok(())
+    }
+
+    /// Send `hello_payload` from a bare netcode client, without the client app's Hello or
+    /// message handling, and collect the server's reliable replies with the version each was
+    /// encoded for.
+    fn send_bare_hello(hello_payload: Vec<u8>) -> Result<(Vec<(u16, ServerToClient)>, App)> {
+        let port = reserve_udp_port();
+        let mut server_app = build_server_app(Config {
+            port,
+            ..Config::default()
+        });
+        advance_app(&mut server_app, HANDSHAKE_DT);
+
+        // Bare renet client over netcode, without the client app's Hello or message handling
+        let socket = UdpSocket::bind(("127.0.0.1", 0))?;
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?;
+        let mut transport = NetcodeClientTransport::new(
+            now,
+            ClientAuthentication::Unsecure {
+                protocol_id: NETCODE_PROTOCOL_ID,
+                client_id: 0xBAD,
+                server_addr: format!("127.0.0.1:{port}").parse()?,
+                user_data: None,
+            },
+            socket,
+        )?;
+        let mut client = RenetClient::new(connection_config(1.0));
+        let dt = Duration::from_secs_f32(HANDSHAKE_DT);
+        let mut pump = |client: &mut RenetClient, server_app: &mut App| -> Result<()> {
+            client.update(dt);
+            transport.update(dt, client)?;
+            transport.send_packets(client)?;
+            advance_app(server_app, HANDSHAKE_DT);
+            Ok(())
+        };
+
+        for _ in 0..HANDSHAKE_STEPS {
+            pump(&mut client, &mut server_app)?;
+            if client.is_connected() {
+                break;
+            }
+        }
+        assert!(client.is_connected(), "netcode handshake never completed");
+
+        client.send_message(Channel::Reliable, hello_payload);
+
+        let mut received = Vec::new();
+        for _ in 0..60 {
+            // The transport errors out once the server has dropped the connection
+            let pumped = pump(&mut client, &mut server_app);
+            while let Some(bytes) = client.receive_message(Channel::Reliable) {
+                let version = protocol::wire_version(&bytes).unwrap_or_default();
+                let msg = if version == LEGACY_PROTOCOL_VERSION {
+                    protocol::legacy::decode(&bytes)?
+                } else {
+                    protocol::decode(&bytes)?
+                };
+                received.push((version, msg));
+            }
+            if pumped.is_err() || client.is_disconnected() {
+                break;
+            }
+        }
+        Ok((received, server_app))
+    }
 }
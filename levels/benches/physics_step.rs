@@ -0,0 +1,172 @@
+//! Throughput of `step_submarine_dbg`, reported in steps per second.
+//!
+//! `cargo bench -p levels --bench physics_step` runs the criterion groups and then fails if a
+//! plain single step of the skiff at 60 Hz runs slower than `PHYSICS_STEP_MIN_STEPS_PER_S`
+//! (default 5,000,000). Pass `-- --save-baseline <name>` / `--baseline <name>` to compare
+//! against an earlier run.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+use levels::builtins::greybox_level;
+use levels::{
+    step_submarine_dbg, subspecs, LevelSpec, Quatf, SubInputState, SubPhysicsSpec, SubState,
+    SubStepDebug, Vec3f,
+};
+
+const DEFAULT_MIN_STEPS_PER_S: f64 = 5_000_000.0;
+const DTS: [(&str, f32); 3] = [
+    ("30hz", 1.0 / 30.0),
+    ("60hz", 1.0 / 60.0),
+    ("120hz", 1.0 / 120.0),
+];
+const SEQUENCE_STEPS: u64 = 1000;
+
+fn specs() -> [(&'static str, SubPhysicsSpec); 2] {
+    [
+        ("small_skiff", subspecs::small_skiff_spec()),
+        ("large_cruiser", subspecs::large_cruiser_spec()),
+    ]
+}
+
+fn initial_state(level: &LevelSpec, spec: &SubPhysicsSpec) -> SubState {
+    SubState {
+        position: level.tunnel.pos,
+        velocity: Vec3f::new(1.0, 0.0, 0.0),
+        orientation: Quatf::IDENTITY,
+        ang_mom: Vec3f::ZERO,
+        ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+        pump: Default::default(),
+    }
+}
+
+fn cruising_inputs() -> SubInputState {
+    SubInputState {
+        thrust: 0.8,
+        yaw: 0.2,
+        pump_fwd: 0.1,
+        pump_aft: -0.1,
+        plane: 0.0,
+        roll_trim: 0.0,
+    }
+}
+
+fn bench_single_step(c: &mut Criterion, with_debug: bool) {
+    let level = greybox_level();
+    let name = if with_debug {
+        "step_submarine_dbg/single_debug"
+    } else {
+        "step_submarine_dbg/single"
+    };
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Elements(1));
+    for (spec_name, spec) in specs() {
+        for (dt_name, dt) in DTS {
+            let id = BenchmarkId::new(spec_name, dt_name);
+            let start = initial_state(&level, &spec);
+            let mut dbg = SubStepDebug::default();
+            group.bench_function(id, |b| {
+                b.iter_batched_ref(
+                    || start.clone(),
+                    |state| {
+                        let dbg = with_debug.then_some(&mut dbg);
+                        step_submarine_dbg(
+                            black_box(&level),
+                            black_box(&spec),
+                            cruising_inputs(),
+                            state,
+                            black_box(dt),
+                            0.0,
+                            dbg,
+                        );
+                    },
+                    criterion::BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_sequence(c: &mut Criterion) {
+    let level = greybox_level();
+    let mut group = c.benchmark_group("step_submarine_dbg/sequence_1000");
+    group.throughput(Throughput::Elements(SEQUENCE_STEPS));
+    for (spec_name, spec) in specs() {
+        for (dt_name, dt) in DTS {
+            let start = initial_state(&level, &spec);
+            group.bench_function(BenchmarkId::new(spec_name, dt_name), |b| {
+                b.iter_batched_ref(
+                    || start.clone(),
+                    |state| {
+                        for i in 0..SEQUENCE_STEPS {
+                            step_submarine_dbg(
+                                black_box(&level),
+                                black_box(&spec),
+                                cruising_inputs(),
+                                state,
+                                dt,
+                                i as f32 * dt,
+                                None,
+                            );
+                        }
+                    },
+                    criterion::BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Plain single-step throughput of the skiff at 60 Hz, timed directly since criterion does not
+/// expose its estimates to the bench binary.
+fn measure_gate_steps_per_s() -> f64 {
+    let level = greybox_level();
+    let spec = subspecs::small_skiff_spec();
+    let start = initial_state(&level, &spec);
+    let mut state = start.clone();
+    let mut steps = 0_u64;
+    let began = Instant::now();
+    while began.elapsed() < Duration::from_secs(2) {
+        for _ in 0..10_000 {
+            step_submarine_dbg(
+                black_box(&level),
+                black_box(&spec),
+                cruising_inputs(),
+                &mut state,
+                1.0 / 60.0,
+                0.0,
+                None,
+            );
+        }
+        steps += 10_000;
+        // Keep the sub near its start so the measurement stays a "single step" from there
+        state.clone_from(&start);
+    }
+    steps as f64 / began.elapsed().as_secs_f64()
+}
+
+fn main() {
+    let mut c = Criterion::default().configure_from_args();
+    bench_single_step(&mut c, false);
+    bench_single_step(&mut c, true);
+    bench_sequence(&mut c);
+    c.final_summary();
+
+    // `cargo test --benches` runs each benchmark once without `--bench`; only gate real runs
+    if !std::env::args().any(|arg| arg == "--bench") {
+        return;
+    }
+    let floor = std::env::var("PHYSICS_STEP_MIN_STEPS_PER_S")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(DEFAULT_MIN_STEPS_PER_S);
+    let measured = measure_gate_steps_per_s();
+    println!("step_submarine_dbg/single gate: {measured:.0} steps/s (floor {floor:.0})");
+    if measured < floor {
+        eprintln!("physics step throughput regressed below {floor:.0} steps/s");
+        std::process::exit(1);
+    }
+}
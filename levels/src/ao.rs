@@ -0,0 +1,341 @@
+//! Baked ambient occlusion for the static level shell.
+//!
+//! The `bake_ao` binary ray-casts every vertex of the tunnel and chamber planes against the
+//! level's static boxes and writes one [`AoMap`] per plane. The client multiplies the factors
+//! into the plane meshes as vertex colors, so baked planes must be built with the same vertex
+//! layout as [`StaticSurface::vertices`] (Bevy's `Plane3d` mesh with
+//! [`STATIC_PLANE_SUBDIVISIONS`]).
+
+use crate::{LevelSpec, Quatf, Vec3f};
+use bevy_math::bounding::{Aabb3d, RayCast3d};
+use bevy_math::Dir3A;
+use serde::{Deserialize, Serialize};
+
+/// Hemisphere rays per vertex.
+pub const AO_RAYS: usize = 64;
+/// Occluders further away than this do not darken a vertex.
+pub const AO_RADIUS_M: f32 = 8.0;
+/// Subdivisions of the baked plane meshes (33×33 vertices per plane).
+pub const STATIC_PLANE_SUBDIVISIONS: u32 = 31;
+
+/// Per-vertex AO factors (0 = fully occluded, 1 = open) for the mesh named `mesh_name`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AoMap {
+    pub mesh_name: String,
+    pub ao: Vec<f32>,
+}
+
+pub fn encode_ao_maps(maps: &[AoMap]) -> Vec<u8> {
+    bincode::serialize(maps).expect("AO maps serialize")
+}
+
+pub fn decode_ao_maps(bytes: &[u8]) -> Result<Vec<AoMap>, bincode::Error> {
+    bincode::deserialize(bytes)
+}
+
+/// Flat rectangular piece of the level shell. Mirrors the planes `spawn_greybox` creates:
+/// a `Plane3d` of `size` (local X × local Z) placed at `center` with `rotation`.
+#[derive(Debug, Clone)]
+pub struct StaticSurface {
+    pub name: &'static str,
+    pub center: Vec3f,
+    pub rotation: Quatf,
+    pub size: [f32; 2],
+    /// Side facing the open volume. Not always `rotation * Y`: double-sided chamber walls
+    /// may face outward.
+    pub inward: Vec3f,
+    /// Shell thickness behind the plane, used when it acts as an occluder.
+    pub thickness: f32,
+}
+
+impl StaticSurface {
+    /// World-space vertex positions in `Plane3d` mesh order: rows along local Z, columns along
+    /// local X, `subdivisions + 2` vertices per side.
+    pub fn vertices(&self, subdivisions: u32) -> Vec<Vec3f> {
+        let n = subdivisions + 2;
+        let mut out = Vec::with_capacity((n * n) as usize);
+        for z in 0..n {
+            for x in 0..n {
+                let tx = x as f32 / (n - 1) as f32;
+                let tz = z as f32 / (n - 1) as f32;
+                let local = Vec3f::new((-0.5 + tx) * self.size[0], 0.0, (-0.5 + tz) * self.size[1]);
+                out.push(self.center + self.rotation * local);
+            }
+        }
+        out
+    }
+
+    /// The plane extruded by `thickness` away from the open volume.
+    pub fn occluder(&self) -> Aabb3d {
+        let half_u = self.rotation * Vec3f::new(self.size[0] * 0.5, 0.0, 0.0);
+        let half_v = self.rotation * Vec3f::new(0.0, 0.0, self.size[1] * 0.5);
+        let back = -self.inward * self.thickness;
+        let corners = [
+            self.center + half_u + half_v,
+            self.center + half_u - half_v,
+            self.center - half_u + half_v,
+            self.center - half_u - half_v,
+        ];
+        let min = corners
+            .iter()
+            .flat_map(|&c| [c, c + back])
+            .fold(Vec3f::splat(f32::INFINITY), Vec3f::min);
+        let max = corners
+            .iter()
+            .flat_map(|&c| [c, c + back])
+            .fold(Vec3f::splat(f32::NEG_INFINITY), Vec3f::max);
+        Aabb3d {
+            min: min.into(),
+            max: max.into(),
+        }
+    }
+}
+
+/// Tunnel and chamber shell planes, named like the client entities they bake for.
+pub fn static_surfaces(level: &LevelSpec) -> Vec<StaticSurface> {
+    use std::f32::consts::{FRAC_PI_2, PI};
+    let mut out = Vec::new();
+
+    let t = &level.tunnel;
+    let half = t.size * 0.5;
+    let shell = t.shell_thickness;
+    let tunnel_faces = [
+        (
+            "Tunnel Floor",
+            Vec3f::new(0.0, -half.y, 0.0),
+            Quatf::IDENTITY,
+            [t.size.x, t.size.z],
+            Vec3f::Y,
+        ),
+        (
+            "Tunnel Ceiling",
+            Vec3f::new(0.0, half.y, 0.0),
+            Quatf::from_rotation_x(PI),
+            [t.size.x, t.size.z],
+            Vec3f::NEG_Y,
+        ),
+        (
+            "Tunnel Wall +Z",
+            Vec3f::new(0.0, 0.0, half.z),
+            Quatf::from_rotation_x(-FRAC_PI_2),
+            [t.size.x, t.size.y],
+            Vec3f::NEG_Z,
+        ),
+        (
+            "Tunnel Wall -Z",
+            Vec3f::new(0.0, 0.0, -half.z),
+            Quatf::from_rotation_x(FRAC_PI_2),
+            [t.size.x, t.size.y],
+            Vec3f::Z,
+        ),
+    ];
+    for (name, local, rotation, size, inward) in tunnel_faces {
+        out.push(StaticSurface {
+            name,
+            center: t.pos + local,
+            rotation,
+            size,
+            inward,
+            thickness: shell,
+        });
+    }
+
+    let c = &level.chamber;
+    let half = c.size * 0.5;
+    let shell = level.room.wall_thickness;
+    // No -X wall: the chamber opens onto the tunnel
+    let chamber_faces = [
+        (
+            "Chamber Floor",
+            Vec3f::new(0.0, -half.y, 0.0),
+            Quatf::IDENTITY,
+            [c.size.x, c.size.z],
+            Vec3f::Y,
+        ),
+        (
+            "Chamber Ceiling",
+            Vec3f::new(0.0, half.y, 0.0),
+            Quatf::from_rotation_x(PI),
+            [c.size.x, c.size.z],
+            Vec3f::NEG_Y,
+        ),
+        (
+            "Chamber Wall +Z",
+            Vec3f::new(0.0, 0.0, half.z),
+            Quatf::from_rotation_x(-FRAC_PI_2),
+            [c.size.x, c.size.y],
+            Vec3f::NEG_Z,
+        ),
+        (
+            "Chamber Wall -Z",
+            Vec3f::new(0.0, 0.0, -half.z),
+            Quatf::from_rotation_x(FRAC_PI_2),
+            [c.size.x, c.size.y],
+            Vec3f::Z,
+        ),
+        (
+            "Chamber Wall +X",
+            Vec3f::new(half.x, 0.0, 0.0),
+            Quatf::from_rotation_z(-FRAC_PI_2),
+            [c.size.y, c.size.z],
+            Vec3f::NEG_X,
+        ),
+    ];
+    for (name, local, rotation, size, inward) in chamber_faces {
+        out.push(StaticSurface {
+            name,
+            center: c.pos + local,
+            rotation,
+            size,
+            inward,
+            thickness: shell,
+        });
+    }
+    out
+}
+
+/// Every static box in the level: the station floor and walls, the dock pad and the
+/// extruded tunnel/chamber planes. Named so a surface can skip its own slab.
+pub fn static_geometry_aabbs(level: &LevelSpec) -> Vec<(&'static str, Aabb3d)> {
+    let r = &level.room;
+    let wt = r.wall_thickness;
+    let (w, h, d) = (r.size.x, r.size.y, r.size.z);
+    let boxed = |center: Vec3f, size: Vec3f| Aabb3d::new(center, size * 0.5);
+    let mut out = vec![
+        (
+            "Station Floor",
+            boxed(Vec3f::new(0.0, -wt * 0.5, 0.0), Vec3f::new(w, wt, d)),
+        ),
+        (
+            "Station Wall +X",
+            boxed(Vec3f::new(w * 0.5, h * 0.5 - wt, 0.0), Vec3f::new(wt, h, d)),
+        ),
+        (
+            "Station Wall -X",
+            boxed(
+                Vec3f::new(-w * 0.5, h * 0.5 - wt, 0.0),
+                Vec3f::new(wt, h, d),
+            ),
+        ),
+        (
+            "Station Wall +Z",
+            boxed(Vec3f::new(0.0, h * 0.5 - wt, d * 0.5), Vec3f::new(w, h, wt)),
+        ),
+        (
+            "Station Wall -Z",
+            boxed(
+                Vec3f::new(0.0, h * 0.5 - wt, -d * 0.5),
+                Vec3f::new(w, h, wt),
+            ),
+        ),
+        ("Dock Pad", boxed(r.dock_pos, r.dock_size)),
+    ];
+    out.extend(
+        static_surfaces(level)
+            .iter()
+            .map(|s| (s.name, s.occluder())),
+    );
+    out
+}
+
+/// Cosine-weighted directions around +Y on a Fibonacci spiral, so every vertex uses the same
+/// well-spread set and bakes are deterministic.
+pub fn hemisphere_directions(count: usize) -> Vec<Vec3f> {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    (0..count)
+        .map(|i| {
+            let r = ((i as f32 + 0.5) / count as f32).sqrt();
+            let phi = i as f32 * golden_angle;
+            Vec3f::new(r * phi.cos(), (1.0 - r * r).sqrt(), r * phi.sin())
+        })
+        .collect()
+}
+
+/// AO factor at `point` on a surface facing `normal`. Hits fade out linearly towards
+/// `AO_RADIUS_M` so distant walls only darken slightly.
+pub fn ambient_occlusion(
+    point: Vec3f,
+    normal: Vec3f,
+    directions: &[Vec3f],
+    occluders: &[Aabb3d],
+) -> f32 {
+    let to_normal = Quatf::from_rotation_arc(Vec3f::Y, normal);
+    let origin = point + normal * 1e-3;
+    let mut occlusion = 0.0;
+    for &dir in directions {
+        let Ok(dir) = Dir3A::new((to_normal * dir).into()) else {
+            continue;
+        };
+        let ray = RayCast3d::new(origin, dir, AO_RADIUS_M);
+        let nearest = occluders
+            .iter()
+            .filter_map(|aabb| {
+                let t = ray.aabb_intersection_at(aabb)?;
+                // Vertices on a crease sit on the neighbouring slab's face; rays leaving that
+                // face report a hit at t = 0 but never go inside, so do not count them.
+                let probe = ray.origin + *ray.direction * (t + 1e-3);
+                (probe.cmpge(aabb.min).all() && probe.cmple(aabb.max).all()).then_some(t)
+            })
+            .fold(f32::INFINITY, f32::min);
+        if nearest.is_finite() {
+            occlusion += 1.0 - nearest / AO_RADIUS_M;
+        }
+    }
+    (1.0 - occlusion / directions.len() as f32).clamp(0.0, 1.0)
+}
+
+/// Bake every static surface of `level` against all the other static geometry.
+pub fn bake_level_ao(level: &LevelSpec) -> Vec<AoMap> {
+    let directions = hemisphere_directions(AO_RAYS);
+    let geometry = static_geometry_aabbs(level);
+    static_surfaces(level)
+        .iter()
+        .map(|surface| {
+            let others: Vec<Aabb3d> = geometry
+                .iter()
+                .filter(|(name, _)| *name != surface.name)
+                .map(|(_, aabb)| *aabb)
+                .collect();
+            let ao = surface
+                .vertices(STATIC_PLANE_SUBDIVISIONS)
+                .into_iter()
+                .map(|p| ambient_occlusion(p, surface.inward, &directions, &others))
+                .collect();
+            AoMap {
+                mesh_name: surface.name.to_string(),
+                ao,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtins::greybox_level;
+
+    #[test]
+    fn tunnel_floor_is_darker_in_the_corners() {
+        let maps = bake_level_ao(&greybox_level());
+        let floor = maps.iter().find(|m| m.mesh_name == "Tunnel Floor").unwrap();
+        let n = (STATIC_PLANE_SUBDIVISIONS + 2) as usize;
+        assert_eq!(floor.ao.len(), n * n);
+        assert!(floor.ao.iter().all(|a| (0.0..=1.0).contains(a)));
+        // Halfway down the tunnel: centerline vs the first row (against the -Z wall)
+        let center = floor.ao[n / 2 * n + n / 2];
+        let edge = floor.ao[n / 2];
+        assert!(
+            edge < center - 0.2,
+            "wall crease should be darker: edge {edge}, center {center}"
+        );
+    }
+
+    #[test]
+    fn ao_maps_roundtrip_through_bincode() {
+        let maps = vec![AoMap {
+            mesh_name: "Tunnel Floor".into(),
+            ao: vec![1.0, 0.5, 0.25],
+        }];
+        assert_eq!(decode_ao_maps(&encode_ao_maps(&maps)).unwrap(), maps);
+    }
+}
@@ -0,0 +1,66 @@
+//! Bake per-vertex ambient occlusion for a level's static shell.
+//!
+//! Usage: `cargo run -p levels --release --bin bake_ao -- <level.ron | builtin name> [out_dir]`
+//!
+//! Writes `<out_dir>/<level name>.ao.bincode` (default `out_dir`: `client/assets/baked_ao`).
+//! The level name is the file stem up to the first dot, or the builtin's name
+//! (`greybox`, `torus_two_exit`).
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use levels::ao::{bake_level_ao, encode_ao_maps};
+use levels::builtins::{greybox_level, torus_two_exit_level};
+use levels::LevelSpec;
+
+const DEFAULT_OUT_DIR: &str = "client/assets/baked_ao";
+
+fn load_level(arg: &str) -> Result<(String, LevelSpec), String> {
+    match arg {
+        "greybox" => return Ok((arg.to_string(), greybox_level())),
+        "torus_two_exit" => return Ok((arg.to_string(), torus_two_exit_level())),
+        _ => {}
+    }
+    let path = Path::new(arg);
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{arg}: {e}"))?;
+    let level = LevelSpec::from_ron_str(&text).map_err(|e| format!("{arg}: {e}"))?;
+    // `greybox.level.ron` -> `greybox`
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .and_then(|n| n.split('.').next())
+        .ok_or_else(|| format!("{arg}: no file name"))?;
+    Ok((name.to_string(), level))
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(level_arg) = args.next() else {
+        eprintln!("usage: bake_ao <level.ron | greybox | torus_two_exit> [out_dir]");
+        return ExitCode::FAILURE;
+    };
+    let out_dir = PathBuf::from(args.next().unwrap_or_else(|| DEFAULT_OUT_DIR.to_string()));
+
+    let (name, level) = match load_level(&level_arg) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("bake_ao: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let maps = bake_level_ao(&level);
+    let out = out_dir.join(format!("{name}.ao.bincode"));
+    if let Err(err) =
+        std::fs::create_dir_all(&out_dir).and_then(|_| std::fs::write(&out, encode_ao_maps(&maps)))
+    {
+        eprintln!("bake_ao: {}: {err}", out.display());
+        return ExitCode::FAILURE;
+    }
+    let vertices: usize = maps.iter().map(|m| m.ao.len()).sum();
+    println!(
+        "baked {} meshes ({vertices} vertices) -> {}",
+        maps.len(),
+        out.display()
+    );
+    ExitCode::SUCCESS
+}
@@ -1,6 +1,7 @@
 use crate::{
-    ChamberSpec, FlowFieldSpec, LevelSpec, RoomSpec, TorusExitSpec, TorusTunnelSpec, TunnelSpec,
-    Vec3f,
+    ChamberSpec, DensityProfile, FlowFieldSpec, HoloIcon, HoloMarker, LevelSpec, LorePlaque,
+    MovingObstacleSpec, OreNodeSpec, ResourceType, RoomSpec, ThermalVentSpec, TorusExitSpec,
+    TorusTunnelSpec, TunnelSpec, Vec3f,
 };
 
 // Mirrors the current greybox layout used in the prototype.
@@ -42,6 +43,199 @@ pub fn greybox_level() -> LevelSpec {
             pos: chamber_pos,
         },
         torus_tunnel: None,
+        side_tunnels: Vec::new(),
+        // Vent on the chamber floor just past the tunnel mouth. The column top sits below the
+        // tunnel ceiling but above its centerline, so subs must pump out ballast to pass over it.
+        thermal_vents: vec![ThermalVentSpec {
+            position: Vec3f::new(
+                chamber_pos.x - chamber_size.x * 0.5 + 12.0,
+                chamber_pos.y - chamber_size.y * 0.5,
+                0.0,
+            ),
+            radius_m: 14.0,
+            height_m: 26.0,
+            upwelling_m_s: 2.5,
+            damage_per_s: 0.08,
+        }],
+        // Off-center in the chamber, clear of the vent column
+        ore_nodes: vec![OreNodeSpec::ore(
+            Vec3f::new(
+                chamber_pos.x + 6.0,
+                chamber_pos.y - 17.0,
+                chamber_pos.z + 5.0,
+            ),
+            1.0,
+        )],
+        lore_plaques: greybox_lore(tunnel_pos, tunnel_len, chamber_pos, chamber_size),
+        holo_markers: vec![
+            HoloMarker {
+                position: Vec3f::new(-16.0, 6.0, -16.0),
+                icon: HoloIcon::Poi,
+                color: [0.9, 0.9, 0.3],
+            },
+            HoloMarker {
+                position: Vec3f::new(chamber_pos.x - chamber_size.x * 0.5 + 12.0, 14.0, 0.0),
+                icon: HoloIcon::Warning,
+                color: [1.0, 0.4, 0.1],
+            },
+            HoloMarker {
+                position: Vec3f::new(
+                    chamber_pos.x + 6.0,
+                    chamber_pos.y - 10.0,
+                    chamber_pos.z + 5.0,
+                ),
+                icon: HoloIcon::Objective,
+                color: [0.2, 0.9, 1.0],
+            },
+        ],
+        moving_obstacles: greybox_gates(tunnel_pos, tunnel_h, tunnel_w),
+        density_profile: DensityProfile::default(),
+    }
+}
+
+/// Two gates across the tunnel's second half: one sliding side to side over half its width,
+/// then one rising and falling over half its height, so each leaves a gap that moves.
+fn greybox_gates(tunnel_pos: Vec3f, tunnel_h: f32, tunnel_w: f32) -> Vec<MovingObstacleSpec> {
+    let sliding_x = tunnel_pos.x - 24.0;
+    let rising_x = tunnel_pos.x + 56.0;
+    vec![
+        MovingObstacleSpec {
+            id: 0,
+            waypoints: vec![
+                Vec3f::new(sliding_x, tunnel_pos.y, tunnel_pos.z - tunnel_w * 0.25),
+                Vec3f::new(sliding_x, tunnel_pos.y, tunnel_pos.z + tunnel_w * 0.25),
+            ],
+            speed_m_s: 2.0,
+            mesh_size: Vec3f::new(2.0, tunnel_h, tunnel_w * 0.5),
+        },
+        MovingObstacleSpec {
+            id: 1,
+            waypoints: vec![
+                Vec3f::new(rising_x, tunnel_pos.y - tunnel_h * 0.25, tunnel_pos.z),
+                Vec3f::new(rising_x, tunnel_pos.y + tunnel_h * 0.25, tunnel_pos.z),
+            ],
+            speed_m_s: 1.5,
+            mesh_size: Vec3f::new(2.0, tunnel_h * 0.5, tunnel_w),
+        },
+    ]
+}
+
+/// Backstory along the greybox route: dock, tunnel mouth, mid tunnel, chamber mouth, ore seam.
+fn greybox_lore(
+    tunnel_pos: Vec3f,
+    tunnel_len: f32,
+    chamber_pos: Vec3f,
+    chamber_size: Vec3f,
+) -> Vec<LorePlaque> {
+    let plaque = |position: Vec3f, text: &str| LorePlaque {
+        position,
+        text: text.to_string(),
+        trigger_radius_m: 12.0,
+    };
+    vec![
+        plaque(
+            Vec3f::new(-8.0, 1.0, -16.0),
+            "Pelagic Station Thalassa, Dock 7. Commissioned in the ninth year of the Tide \
+             Compact, when the surface cities first sold their seas to the deep.",
+        ),
+        plaque(
+            Vec3f::new(tunnel_pos.x - tunnel_len * 0.5 + 12.0, 0.0, -10.0),
+            "Conduit A. The Compact cut these channels to carry ore up to the station; the \
+             current has run toward the mines ever since.",
+        ),
+        plaque(
+            Vec3f::new(tunnel_pos.x, 0.0, 10.0),
+            "In memory of the crew of the skiff Meridian, lost to a flow surge in this \
+             conduit. The Admiralty never recovered the hull.",
+        ),
+        plaque(
+            Vec3f::new(chamber_pos.x - chamber_size.x * 0.5 + 4.0, 12.0, -8.0),
+            "Hydrothermal activity ahead. The heat that makes these seams rich has boiled \
+             lesser hulls.",
+        ),
+        plaque(
+            Vec3f::new(
+                chamber_pos.x + 10.0,
+                chamber_pos.y - 17.0,
+                chamber_pos.z - 6.0,
+            ),
+            "Survey marker 12. Whoever holds the seams holds the sea: the Thalassocracy was \
+             built on this rock.",
+        ),
+    ]
+}
+
+/// Second campaign level: the greybox station with a longer, faster tunnel opening into a
+/// taller chamber whose floor sits well below the station. Two vents guard the drop and the
+/// ore lies on the deep floor.
+pub fn deep_trench_level() -> LevelSpec {
+    let room_w = 240.0;
+    let room_h = 48.0;
+    let room_d = 240.0;
+    let wall_thick = 2.0;
+
+    let tunnel_len = 384.0;
+    let tunnel_h = 24.0;
+    let tunnel_w = 32.0;
+    let tunnel_pos = Vec3f::new(room_w * 0.5 + tunnel_len * 0.5, 4.0, 0.0);
+
+    // Ceiling flush with the tunnel's, floor 56 m below the tunnel floor
+    let chamber_size = Vec3f::new(200.0, 80.0, 200.0);
+    let chamber_pos = Vec3f::new(room_w * 0.5 + tunnel_len + chamber_size.x * 0.5, -24.0, 0.0);
+    let chamber_floor = chamber_pos.y - chamber_size.y * 0.5;
+
+    LevelSpec {
+        room: RoomSpec {
+            size: Vec3f::new(room_w, room_h, room_d),
+            wall_thickness: wall_thick,
+            dock_size: Vec3f::new(12.0, 0.8, 12.0),
+            dock_pos: Vec3f::new(-16.0, 0.4, -16.0),
+        },
+        tunnel: TunnelSpec {
+            size: Vec3f::new(tunnel_len, tunnel_h, tunnel_w),
+            pos: tunnel_pos,
+            shell_thickness: wall_thick,
+            flow: FlowFieldSpec::Uniform {
+                flow: Vec3f::new(2.5, 0.0, 0.0),
+                variance: 0.35,
+            },
+        },
+        chamber: ChamberSpec {
+            size: chamber_size,
+            pos: chamber_pos,
+        },
+        torus_tunnel: None,
+        side_tunnels: Vec::new(),
+        thermal_vents: [-18.0, 18.0]
+            .into_iter()
+            .map(|z| ThermalVentSpec {
+                position: Vec3f::new(
+                    chamber_pos.x - chamber_size.x * 0.5 + 30.0,
+                    chamber_floor,
+                    z,
+                ),
+                radius_m: 12.0,
+                height_m: 40.0,
+                upwelling_m_s: 3.0,
+                damage_per_s: 0.1,
+            })
+            .collect(),
+        ore_nodes: [(20.0, 0.0), (50.0, -40.0), (60.0, 35.0)]
+            .into_iter()
+            // Trench seams hold three passes and some crystal
+            .map(|(x, z)| OreNodeSpec {
+                yields: vec![(ResourceType::Ore, 3.0), (ResourceType::Crystal, 1.0)],
+                supply_kg: Some(150),
+                ..OreNodeSpec::ore(
+                    Vec3f::new(chamber_pos.x + x, chamber_floor + 3.0, chamber_pos.z + z),
+                    1.0,
+                )
+            })
+            .collect(),
+        lore_plaques: Vec::new(),
+        holo_markers: Vec::new(),
+        moving_obstacles: Vec::new(),
+        density_profile: DensityProfile::default(),
     }
 }
 
@@ -123,5 +317,365 @@ pub fn torus_two_exit_level() -> LevelSpec {
             },
             exits: [exit_to_dock, exit_to_chamber],
         }),
+        side_tunnels: Vec::new(),
+        thermal_vents: Vec::new(),
+        ore_nodes: Vec::new(),
+        lore_plaques: Vec::new(),
+        holo_markers: Vec::new(),
+        moving_obstacles: Vec::new(),
+        density_profile: DensityProfile::default(),
+    }
+}
+
+/// SplitMix64 stream for procedural layouts; a given seed always yields the same sequence.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u32) -> Self {
+        Self(u64::from(seed))
+    }
+
+    pub(crate) fn from_u64(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[lo, hi)`.
+    pub(crate) fn range(&mut self, lo: f32, hi: f32) -> f32 {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        lo + (hi - lo) * unit
+    }
+
+    fn chance(&mut self, p: f32) -> bool {
+        self.range(0.0, 1.0) < p
+    }
+}
+
+/// Closest two ore nodes from `place_ore_nodes` may sit, centre to centre.
+pub const ORE_NODE_MIN_SEPARATION_M: f32 = 1.0;
+/// Spots `place_ore_nodes` tries for each node before leaving it out.
+const ORE_PLACEMENT_ATTEMPTS: usize = 10;
+
+/// Scatter `count` ore nodes through the chamber of `spec`, each a single resource holding
+/// two to five mining passes. Nodes stay `ORE_NODE_MIN_SEPARATION_M` apart from each other and
+/// from the level's own nodes; one that finds no free spot in ten tries is left out, so fewer
+/// may come back. Append the result to `LevelSpec::ore_nodes`, whose order sets `node_id`s.
+pub fn place_ore_nodes(spec: &LevelSpec, seed: u64, count: usize) -> Vec<OreNodeSpec> {
+    let mut rng = Rng::from_u64(seed);
+    // A metre clear of the chamber walls
+    let half = (spec.chamber.size * 0.5 - Vec3f::ONE).max(Vec3f::ZERO);
+    let (lo, hi) = (spec.chamber.pos - half, spec.chamber.pos + half);
+    let mut placed: Vec<OreNodeSpec> = Vec::with_capacity(count);
+    for _ in 0..count {
+        for _ in 0..ORE_PLACEMENT_ATTEMPTS {
+            let position = Vec3f::new(
+                rng.range(lo.x, hi.x),
+                rng.range(lo.y, hi.y),
+                rng.range(lo.z, hi.z),
+            );
+            let clear = spec
+                .ore_nodes
+                .iter()
+                .chain(&placed)
+                .all(|node| node.position.distance(position) >= ORE_NODE_MIN_SEPARATION_M);
+            if !clear {
+                continue;
+            }
+            let roll = rng.range(0.0, 10.0);
+            let resource_type = if roll < 6.0 {
+                ResourceType::Ore
+            } else if roll < 9.0 {
+                ResourceType::Crystal
+            } else {
+                ResourceType::RareMineral
+            };
+            let passes = 2 + (rng.next_u64() % 4) as u32;
+            let mut node = OreNodeSpec {
+                yields: vec![(resource_type, 1.0)],
+                ..OreNodeSpec::ore(position, 1.0)
+            };
+            node.supply_kg = Some(node.pass_kg() * passes);
+            placed.push(node);
+            break;
+        }
+    }
+    placed
+}
+
+/// Ore nodes `generate_cave_level` scatters through its chamber with `place_ore_nodes`.
+const CAVE_CHAMBER_ORE_NODES: usize = 6;
+
+/// Procedural cave behind the standard station room: a main tunnel with `complexity` junctions,
+/// each opening one or two dead-end side branches, ending in a large chamber whose floor lies
+/// `depth` metres below the station. Ore sits at every dead end and on the chamber floor, with
+/// yields proportional to depth, and `place_ore_nodes` scatters more through the chamber from
+/// the same seed; the dock pad is on the chamber floor and subs start at the tunnel entrance as
+/// usual.
+pub fn generate_cave_level(seed: u32, depth: f32, complexity: u32) -> LevelSpec {
+    let mut rng = Rng::new(seed);
+
+    let room_w = 240.0;
+    let room_h = 48.0;
+    let room_d = 240.0;
+    let wall_thick = 2.0;
+
+    let junction_spacing = 48.0;
+    let tunnel_len = junction_spacing * (complexity + 1) as f32;
+    let tunnel_h = 24.0;
+    let tunnel_w = 32.0;
+    let tunnel_pos = Vec3f::new(room_w * 0.5 + tunnel_len * 0.5, 4.0, 0.0);
+    let tunnel_floor = tunnel_pos.y - tunnel_h * 0.5;
+    let tunnel_ceiling = tunnel_pos.y + tunnel_h * 0.5;
+
+    // Ceiling flush with the tunnel's; the floor is the deepest point, at least 8 m below the
+    // tunnel floor
+    let depth = depth.max(8.0 - tunnel_floor);
+    let chamber_size = Vec3f::new(160.0, tunnel_ceiling + depth, 160.0);
+    let chamber_pos = Vec3f::new(
+        room_w * 0.5 + tunnel_len + chamber_size.x * 0.5,
+        tunnel_ceiling - chamber_size.y * 0.5,
+        0.0,
+    );
+    let chamber_floor = -depth;
+
+    // Deep deposits are richer and may turn up rare minerals
+    let ore_at = |position: Vec3f| {
+        let depth = (-position.y).max(1.0);
+        OreNodeSpec {
+            yields: vec![
+                (ResourceType::Ore, 4.0),
+                (ResourceType::Crystal, 1.0),
+                (ResourceType::RareMineral, depth / 100.0),
+            ],
+            ..OreNodeSpec::ore(position, depth / 10.0)
+        }
+    };
+    let mut side_tunnels = Vec::new();
+    let mut ore_nodes = Vec::new();
+    for j in 0..complexity {
+        let junction_x = room_w * 0.5 + junction_spacing * (j + 1) as f32 + rng.range(-8.0, 8.0);
+        let first_side = if rng.chance(0.5) { 1.0 } else { -1.0 };
+        let branches = if rng.chance(0.5) { 2 } else { 1 };
+        for side in [first_side, -first_side].into_iter().take(branches) {
+            let len = rng.range(8.0, 30.0);
+            let width = rng.range(3.0, 8.0);
+            // Up to 10° off perpendicular; the AABB widens to cover the slanted run
+            let drift = len * rng.range(-10f32.to_radians(), 10f32.to_radians()).tan();
+            // Reach a metre into the main tunnel so the two volumes join
+            let near_z = side * (tunnel_w * 0.5 - 1.0);
+            let far_z = side * (tunnel_w * 0.5 + len);
+            side_tunnels.push(TunnelSpec {
+                size: Vec3f::new(width + drift.abs(), width, len + 1.0),
+                pos: Vec3f::new(
+                    junction_x + drift * 0.5,
+                    tunnel_floor + width * 0.5,
+                    (near_z + far_z) * 0.5,
+                ),
+                shell_thickness: wall_thick,
+                flow: FlowFieldSpec::Uniform {
+                    flow: Vec3f::ZERO,
+                    variance: 0.0,
+                },
+            });
+            ore_nodes.push(ore_at(Vec3f::new(
+                junction_x + drift,
+                tunnel_floor + 1.0,
+                far_z - side * 1.5,
+            )));
+        }
+    }
+    ore_nodes.push(ore_at(Vec3f::new(
+        chamber_pos.x + 30.0,
+        chamber_floor + 3.0,
+        chamber_pos.z + rng.range(-40.0, 40.0),
+    )));
+
+    let mut level = LevelSpec {
+        room: RoomSpec {
+            size: Vec3f::new(room_w, room_h, room_d),
+            wall_thickness: wall_thick,
+            dock_size: Vec3f::new(12.0, 0.8, 12.0),
+            dock_pos: Vec3f::new(chamber_pos.x, chamber_floor + 0.4, chamber_pos.z),
+        },
+        tunnel: TunnelSpec {
+            size: Vec3f::new(tunnel_len, tunnel_h, tunnel_w),
+            pos: tunnel_pos,
+            shell_thickness: wall_thick,
+            flow: FlowFieldSpec::Uniform {
+                flow: Vec3f::new(rng.range(1.0, 2.5), 0.0, 0.0),
+                variance: 0.2,
+            },
+        },
+        chamber: ChamberSpec {
+            size: chamber_size,
+            pos: chamber_pos,
+        },
+        torus_tunnel: None,
+        side_tunnels,
+        thermal_vents: Vec::new(),
+        ore_nodes,
+        lore_plaques: Vec::new(),
+        holo_markers: Vec::new(),
+        moving_obstacles: Vec::new(),
+        density_profile: DensityProfile::default(),
+    };
+    let scattered = place_ore_nodes(&level, u64::from(seed), CAVE_CHAMBER_ORE_NODES);
+    level.ore_nodes.extend(scattered);
+    level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LevelSpecError;
+
+    #[test]
+    fn builtin_levels_validate() {
+        for level in [greybox_level(), deep_trench_level(), torus_two_exit_level()] {
+            level.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn greybox_level_is_valid() {
+        greybox_level().validate().unwrap();
+
+        let mut broken = greybox_level();
+        broken.tunnel.size.y = -1.0;
+        broken.tunnel.flow = FlowFieldSpec::Uniform {
+            flow: Vec3f::new(f32::NAN, 0.0, 0.0),
+            variance: 0.0,
+        };
+        let errors = broken.validate().unwrap_err();
+        assert!(errors.contains(&LevelSpecError::NegativeTunnelDimension {
+            volume: "tunnel".to_string()
+        }));
+        assert!(errors.contains(&LevelSpecError::InvalidFlowField {
+            volume: "tunnel".to_string()
+        }));
+
+        let mut still_eddies = greybox_level();
+        still_eddies.tunnel.flow = FlowFieldSpec::TurbulentUniform {
+            mean_flow: Vec3f::new(1.0, 0.0, 0.0),
+            intensity: 0.3,
+            correlation_length: 0.0,
+            seed: 1,
+        };
+        assert_eq!(
+            still_eddies.validate().unwrap_err(),
+            vec![LevelSpecError::InvalidFlowField {
+                volume: "tunnel".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn moving_obstacle_loops_and_pushes_subs_out() {
+        let gate = &greybox_level().moving_obstacles[0];
+        let [a, b] = [gate.waypoints[0], gate.waypoints[1]];
+        let lap = gate.path_length();
+        assert!((lap - 2.0 * a.distance(b)).abs() < 1e-4);
+        assert!(gate.position_at(0.0).distance(a) < 1e-4);
+        assert!(gate.position_at(lap * 0.25).distance(a.lerp(b, 0.5)) < 1e-4);
+        assert!(gate.position_at(lap * 0.5).distance(b) < 1e-4);
+        // Wraps onto the next lap
+        assert!(gate.position_at(lap * 1.25).distance(a.lerp(b, 0.5)) < 1e-4);
+
+        // A sphere just in front of the gate is pushed back along -X
+        let front = a - Vec3f::X * (gate.mesh_size.x * 0.5 + 0.5);
+        let (normal, depth) = gate.contact(a, front, 1.0).unwrap();
+        assert!(normal.distance(-Vec3f::X) < 1e-4);
+        assert!((depth - 0.5).abs() < 1e-4);
+        assert!(gate.contact(a, front - Vec3f::X, 1.0).is_none());
+        // Embedded just behind the front face: out through that face
+        let inside = a - Vec3f::X * (gate.mesh_size.x * 0.5 - 0.1);
+        let (normal, depth) = gate.contact(a, inside, 1.0).unwrap();
+        assert!(normal.distance(-Vec3f::X) < 1e-4);
+        assert!((depth - 1.1).abs() < 1e-4);
+    }
+
+    #[test]
+    fn ore_passes_draw_resources_by_weight_and_reproducibly() {
+        let plain = &greybox_level().ore_nodes[0];
+        assert_eq!(plain.pass_kg(), 50);
+        assert_eq!(plain.supply_kg(), plain.pass_kg());
+        assert!((0..50).all(|pass| plain.resource_for_pass(0, pass) == ResourceType::Ore));
+
+        let seam = &deep_trench_level().ore_nodes[0];
+        let draws: Vec<ResourceType> = (0..400).map(|p| seam.resource_for_pass(1, p)).collect();
+        let crystals = draws
+            .iter()
+            .filter(|&&r| r == ResourceType::Crystal)
+            .count();
+        // Weighted 3:1 ore to crystal
+        assert!((70..=130).contains(&crystals), "{crystals} crystals in 400");
+        assert!(!draws.contains(&ResourceType::RareMineral));
+        assert_eq!(seam.resource_for_pass(1, 17), draws[17]);
+    }
+
+    #[test]
+    fn cave_generation_is_deterministic_and_valid() {
+        let a = generate_cave_level(42, 60.0, 5);
+        let b = generate_cave_level(42, 60.0, 5);
+        assert_eq!(format!("{a:?}"), format!("{b:?}"));
+        a.validate().unwrap();
+        assert!((5..=10).contains(&a.side_tunnels.len()));
+        // A node per dead end, one on the chamber floor, then the scattered chamber nodes
+        let floor_node = a.side_tunnels.len();
+        assert!(a.ore_nodes.len() > floor_node + 1);
+        assert!(a.ore_nodes.len() <= floor_node + 1 + CAVE_CHAMBER_ORE_NODES);
+        let half = a.chamber.size * 0.5;
+        assert!(a.ore_nodes[floor_node..]
+            .iter()
+            .all(|o| (o.position - a.chamber.pos).abs().cmple(half).all()));
+
+        let other = generate_cave_level(43, 60.0, 5);
+        assert_ne!(format!("{a:?}"), format!("{other:?}"));
+        // The chamber floor node is the deepest fixed deposit and so the richest
+        let deepest = &a.ore_nodes[floor_node];
+        assert!(a
+            .ore_nodes
+            .iter()
+            .all(|o| o.yield_units <= deepest.yield_units));
+    }
+
+    #[test]
+    fn placed_ore_nodes_are_reproducible_apart_and_inside_the_chamber() {
+        let level = greybox_level();
+        let nodes = place_ore_nodes(&level, 7, 40);
+        assert_eq!(nodes.len(), 40);
+        assert_eq!(
+            format!("{nodes:?}"),
+            format!("{:?}", place_ore_nodes(&level, 7, 40))
+        );
+        assert_ne!(
+            format!("{nodes:?}"),
+            format!("{:?}", place_ore_nodes(&level, 8, 40))
+        );
+        let half = level.chamber.size * 0.5;
+        for (i, node) in nodes.iter().enumerate() {
+            let offset = (node.position - level.chamber.pos).abs();
+            assert!(offset.cmplt(half).all(), "{node:?} outside the chamber");
+            assert!(level
+                .ore_nodes
+                .iter()
+                .chain(&nodes[..i])
+                .all(|o| o.position.distance(node.position) >= ORE_NODE_MIN_SEPARATION_M));
+            assert_eq!(node.yields.len(), 1);
+            assert!((2..=5).contains(&(node.supply_kg() / node.pass_kg())));
+        }
+
+        // A chamber with room for only a few nodes leaves the rest out
+        let mut cramped = greybox_level();
+        cramped.chamber.size = Vec3f::new(3.0, 2.0, 2.0);
+        let few = place_ore_nodes(&cramped, 7, 20);
+        assert!(few.len() < 20, "{} nodes fit", few.len());
     }
 }
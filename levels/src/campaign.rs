@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::builtins::{deep_trench_level, greybox_level};
+use crate::LevelSpec;
+
+/// What it takes to enter a campaign level. The active level is won once the next entry's
+/// condition holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnlockCondition {
+    Always,
+    /// Credits banked by all connected players combined.
+    MinCredits(u64),
+    /// The campaign has already moved past the level at this index.
+    CompleteLevel(usize),
+}
+
+impl UnlockCondition {
+    pub fn is_met(&self, credits: u64, completed: &[usize]) -> bool {
+        match *self {
+            Self::Always => true,
+            Self::MinCredits(min) => credits >= min,
+            Self::CompleteLevel(index) => completed.contains(&index),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelEntry {
+    pub level_spec: LevelSpec,
+    pub unlock_condition: UnlockCondition,
+}
+
+/// Ordered sequence of levels played on one server. On the wire a level is its index here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignSpec {
+    pub levels: Vec<LevelEntry>,
+}
+
+#[derive(Debug)]
+pub enum CampaignLoadError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Empty,
+}
+
+impl std::fmt::Display for CampaignLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "reading campaign: {err}"),
+            Self::Parse(err) => write!(f, "parsing campaign: {err}"),
+            Self::Empty => write!(f, "campaign has no levels"),
+        }
+    }
+}
+
+impl std::error::Error for CampaignLoadError {}
+
+impl CampaignSpec {
+    pub fn from_ron_str(s: &str) -> Result<Self, CampaignLoadError> {
+        let campaign: Self = ron::from_str(s).map_err(CampaignLoadError::Parse)?;
+        if campaign.levels.is_empty() {
+            return Err(CampaignLoadError::Empty);
+        }
+        Ok(campaign)
+    }
+
+    pub fn from_ron_file(path: impl AsRef<Path>) -> Result<Self, CampaignLoadError> {
+        let s = std::fs::read_to_string(path).map_err(CampaignLoadError::Io)?;
+        Self::from_ron_str(&s)
+    }
+
+    /// Just `level`, with nothing to unlock after it.
+    pub fn single_level(level: LevelSpec) -> Self {
+        Self {
+            levels: vec![LevelEntry {
+                level_spec: level,
+                unlock_condition: UnlockCondition::Always,
+            }],
+        }
+    }
+
+    /// The greybox level, then the deep trench once the players have banked 5000 credits.
+    pub fn builtin_campaign() -> Self {
+        Self {
+            levels: vec![
+                LevelEntry {
+                    level_spec: greybox_level(),
+                    unlock_condition: UnlockCondition::Always,
+                },
+                LevelEntry {
+                    level_spec: deep_trench_level(),
+                    unlock_condition: UnlockCondition::MinCredits(5_000),
+                },
+            ],
+        }
+    }
+
+    /// Index of the level after `active` if its unlock condition is met, i.e. `active` is won.
+    pub fn next_unlocked(&self, active: usize, credits: u64, completed: &[usize]) -> Option<usize> {
+        let next = active + 1;
+        self.levels
+            .get(next)
+            .filter(|entry| entry.unlock_condition.is_met(credits, completed))
+            .map(|_| next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_campaign_round_trips_through_ron() {
+        let campaign = CampaignSpec::builtin_campaign();
+        let text = ron::to_string(&campaign).unwrap();
+        let parsed = CampaignSpec::from_ron_str(&text).unwrap();
+        assert_eq!(parsed.levels.len(), 2);
+        assert_eq!(
+            parsed.levels[1].unlock_condition,
+            UnlockCondition::MinCredits(5_000)
+        );
+    }
+
+    #[test]
+    fn next_level_unlocks_on_its_condition() {
+        let mut campaign = CampaignSpec::builtin_campaign();
+        assert_eq!(campaign.next_unlocked(0, 4_999, &[]), None);
+        assert_eq!(campaign.next_unlocked(0, 5_000, &[]), Some(1));
+        assert_eq!(campaign.next_unlocked(1, u64::MAX, &[0]), None);
+
+        campaign.levels.push(LevelEntry {
+            level_spec: greybox_level(),
+            unlock_condition: UnlockCondition::CompleteLevel(0),
+        });
+        assert_eq!(campaign.next_unlocked(1, 0, &[]), None);
+        assert_eq!(campaign.next_unlocked(1, 0, &[0]), Some(2));
+    }
+}
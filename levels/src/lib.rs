@@ -8,17 +8,37 @@
 pub use bevy_math::{Quat as Quatf, Vec3 as Vec3f};
 mod spec;
 pub use spec::{
-    ChamberSpec, FlowFieldSpec, LevelSpec, RoomSpec, TorusExitSpec, TorusTunnelSpec, TunnelSpec,
+    aabb_contains, load_level, save_level, ChamberSpec, FlowFieldSpec, HoloIcon, HoloMarker,
+    LevelLoadError, LevelSaveError, LevelSpec, LevelSpecError, LorePlaque, MovingObstacleSpec,
+    OreNodeSpec, RoomSpec, ThermalVentSpec, TorusExitSpec, TorusTunnelSpec, TunnelSpec,
 };
 
+pub mod ao;
 pub mod builtins;
+mod campaign;
+pub use campaign::{CampaignLoadError, CampaignSpec, LevelEntry, UnlockCondition};
+pub mod mesh;
+mod mission;
+pub use mission::{
+    builtin_missions, Mission, MissionEvent, MissionObjective, ResourceType, ORE_KG_PER_YIELD_UNIT,
+};
+
+mod sonar;
+pub use sonar::{
+    DensityProfile, SonarPropagation, REFERENCE_SOURCE_LEVEL_DB, REFRACTION_RANGE_LOSS_PER_RAD2,
+    SONAR_BEAM_HALF_ANGLE_RAD,
+};
 
 pub mod submarine_physics;
 pub use submarine_physics::{
-    sample_flow_at, step_submarine, step_submarine_dbg, SubInputState, SubInputs, SubState,
-    SubStepDebug,
+    sample_flow_at, step_submarine, step_submarine_dbg, DiveDepthEvent, PumpCooldownState,
+    SubInputState, SubInputs, SubState, SubStepDebug, TURBO_PUMP_BOOST_S, TURBO_PUMP_COOLDOWN_S,
+    TURBO_PUMP_MULTIPLIER,
 };
 
 mod sub_specs;
 pub use sub_specs::subspecs;
-pub use sub_specs::{BallastTankSpec, SubPhysicsSpec};
+pub use sub_specs::{
+    BallastTankSpec, DiveDepthLimit, PropellerSpec, PumpType, SpecLoadError, SpecValidationError,
+    SubPhysicsSpec, SubPhysicsSpecBuilder, MAX_TERMINAL_SPEED_M_S,
+};
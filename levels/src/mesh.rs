@@ -0,0 +1,230 @@
+//! Procedural meshes shared by tools and the client.
+//!
+//! Returned as plain vertex/index buffers so this crate does not depend on Bevy's renderer;
+//! the client wraps them in a `Mesh`.
+
+use crate::Vec3f;
+use std::collections::HashMap;
+
+/// Indexed triangle list with per-vertex normals.
+#[derive(Debug, Clone, Default)]
+pub struct RockMesh {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+}
+
+impl RockMesh {
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
+
+/// Highest subdivision level accepted by `procedural_rock` (5120 faces).
+pub const MAX_ROCK_LOD: u8 = 4;
+
+/// Lumpy boulder of roughly unit radius. `lod` is the icosphere subdivision count
+/// (0 = 20 faces, 1 = 80, 2 = 320, ...); `seed` picks the shape.
+pub fn procedural_rock(seed: u32, lod: u8) -> RockMesh {
+    let (mut positions, indices) = icosphere(lod.min(MAX_ROCK_LOD));
+    let offset = Vec3f::new(seed as f32 * 0.01, 0.0, 0.0);
+    // (frequency, amplitude): two coarse octaves for the silhouette, one fine for roughness
+    const OCTAVES: [(f32, f32); 3] = [(1.1, 0.22), (2.3, 0.10), (7.5, 0.03)];
+    for p in positions.iter_mut() {
+        let v = Vec3f::from_array(*p);
+        let displacement: f32 = OCTAVES
+            .iter()
+            .map(|&(freq, amp)| amp * simplex_noise3(v * freq + offset))
+            .sum();
+        *p = (v * (1.0 + displacement).max(0.5)).to_array();
+    }
+    let normals = vertex_normals(&positions, &indices);
+    RockMesh {
+        positions,
+        normals,
+        indices,
+    }
+}
+
+/// Unit icosphere with `subdivisions` rounds of 4:1 triangle splitting.
+fn icosphere(subdivisions: u8) -> (Vec<[f32; 3]>, Vec<u32>) {
+    let t = (1.0 + 5.0f32.sqrt()) * 0.5;
+    let mut verts: Vec<Vec3f> = [
+        (-1.0, t, 0.0),
+        (1.0, t, 0.0),
+        (-1.0, -t, 0.0),
+        (1.0, -t, 0.0),
+        (0.0, -1.0, t),
+        (0.0, 1.0, t),
+        (0.0, -1.0, -t),
+        (0.0, 1.0, -t),
+        (t, 0.0, -1.0),
+        (t, 0.0, 1.0),
+        (-t, 0.0, -1.0),
+        (-t, 0.0, 1.0),
+    ]
+    .iter()
+    .map(|&(x, y, z)| Vec3f::new(x, y, z).normalize())
+    .collect();
+    let mut faces: Vec<[u32; 3]> = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut midpoint = |a: u32, b: u32, verts: &mut Vec<Vec3f>| -> u32 {
+            let key = (a.min(b), a.max(b));
+            *midpoints.entry(key).or_insert_with(|| {
+                verts.push(((verts[a as usize] + verts[b as usize]) * 0.5).normalize());
+                (verts.len() - 1) as u32
+            })
+        };
+        let mut next = Vec::with_capacity(faces.len() * 4);
+        for [a, b, c] in faces {
+            let ab = midpoint(a, b, &mut verts);
+            let bc = midpoint(b, c, &mut verts);
+            let ca = midpoint(c, a, &mut verts);
+            next.extend([[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]);
+        }
+        faces = next;
+    }
+    (
+        verts.iter().map(|v| v.to_array()).collect(),
+        faces.into_iter().flatten().collect(),
+    )
+}
+
+/// Area-weighted vertex normals from the cross product of each triangle's edges.
+fn vertex_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut acc = vec![Vec3f::ZERO; positions.len()];
+    for tri in indices.chunks_exact(3) {
+        let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| Vec3f::from_array(positions[i as usize]));
+        let n = (b - a).cross(c - a);
+        for &i in tri {
+            acc[i as usize] += n;
+        }
+    }
+    acc.iter()
+        .map(|n| n.normalize_or(Vec3f::Y).to_array())
+        .collect()
+}
+
+/// 3D simplex noise in roughly [-1, 1] (Gustavson's formulation with a hashed gradient set).
+pub fn simplex_noise3(p: Vec3f) -> f32 {
+    const F3: f32 = 1.0 / 3.0;
+    const G3: f32 = 1.0 / 6.0;
+    const GRAD3: [[f32; 3]; 12] = [
+        [1.0, 1.0, 0.0],
+        [-1.0, 1.0, 0.0],
+        [1.0, -1.0, 0.0],
+        [-1.0, -1.0, 0.0],
+        [1.0, 0.0, 1.0],
+        [-1.0, 0.0, 1.0],
+        [1.0, 0.0, -1.0],
+        [-1.0, 0.0, -1.0],
+        [0.0, 1.0, 1.0],
+        [0.0, -1.0, 1.0],
+        [0.0, 1.0, -1.0],
+        [0.0, -1.0, -1.0],
+    ];
+    fn hash(i: i32, j: i32, k: i32) -> usize {
+        let mut h = (i as u32).wrapping_mul(0x8da6_b343)
+            ^ (j as u32).wrapping_mul(0xd816_3841)
+            ^ (k as u32).wrapping_mul(0xcb1a_b31f);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x2c1b_3c6d);
+        h ^= h >> 12;
+        (h % 12) as usize
+    }
+
+    // Skew into simplex cell space
+    let s = (p.x + p.y + p.z) * F3;
+    let (i, j, k) = (
+        (p.x + s).floor() as i32,
+        (p.y + s).floor() as i32,
+        (p.z + s).floor() as i32,
+    );
+    let t = (i + j + k) as f32 * G3;
+    let x0 = p - Vec3f::new(i as f32 - t, j as f32 - t, k as f32 - t);
+
+    // Which of the six tetrahedra we are in
+    let (o1, o2) = if x0.x >= x0.y {
+        if x0.y >= x0.z {
+            ([1, 0, 0], [1, 1, 0])
+        } else if x0.x >= x0.z {
+            ([1, 0, 0], [1, 0, 1])
+        } else {
+            ([0, 0, 1], [1, 0, 1])
+        }
+    } else if x0.y < x0.z {
+        ([0, 0, 1], [0, 1, 1])
+    } else if x0.x < x0.z {
+        ([0, 1, 0], [0, 1, 1])
+    } else {
+        ([0, 1, 0], [1, 1, 0])
+    };
+    let offsets = [[0, 0, 0], o1, o2, [1, 1, 1]];
+
+    let mut n = 0.0;
+    for (corner, o) in offsets.iter().enumerate() {
+        let d = x0 - Vec3f::new(o[0] as f32, o[1] as f32, o[2] as f32)
+            + Vec3f::splat(corner as f32 * G3);
+        let falloff = 0.6 - d.length_squared();
+        if falloff > 0.0 {
+            let g = GRAD3[hash(i + o[0], j + o[1], k + o[2])];
+            let f2 = falloff * falloff;
+            n += f2 * f2 * (g[0] * d.x + g[1] * d.y + g[2] * d.z);
+        }
+    }
+    32.0 * n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icosphere_face_counts_follow_lod() {
+        for (lod, faces) in [(0, 20), (1, 80), (2, 320)] {
+            assert_eq!(procedural_rock(7, lod).triangle_count(), faces);
+        }
+    }
+
+    #[test]
+    fn rock_has_no_degenerate_triangles() {
+        for seed in [0, 1, 42, 1234] {
+            for lod in 0..=MAX_ROCK_LOD {
+                let rock = procedural_rock(seed, lod);
+                assert_eq!(rock.positions.len(), rock.normals.len());
+                for tri in rock.indices.chunks_exact(3) {
+                    let [a, b, c] = [tri[0], tri[1], tri[2]]
+                        .map(|i| Vec3f::from_array(rock.positions[i as usize]));
+                    let area = 0.5 * (b - a).cross(c - a).length();
+                    assert!(
+                        area > 1e-6,
+                        "seed {seed} lod {lod}: degenerate triangle {tri:?} (area {area})"
+                    );
+                }
+                assert!(rock.normals.iter().all(|n| n.iter().all(|c| c.is_finite())));
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+
+/// Ore hauled per unit of `OreNodeSpec::yield_units` mined.
+pub const ORE_KG_PER_YIELD_UNIT: f32 = 50.0;
+
+/// Cargo a sub can haul back to the station. Ore nodes yield one of these per mining pass,
+/// drawn from `OreNodeSpec::yields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResourceType {
+    Ore,
+    Crystal,
+    RareMineral,
+}
+
+impl ResourceType {
+    /// Credits paid per kg when the station buys this resource from a docking sub.
+    pub fn credits_per_kg(self) -> u64 {
+        match self {
+            ResourceType::Ore => 1,
+            ResourceType::Crystal => 3,
+            ResourceType::RareMineral => 8,
+        }
+    }
+}
+
+/// What a player has to do to finish a mission. Progress is counted in whole units: kilograms
+/// delivered, docks, or meters of depth.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MissionObjective {
+    /// Unload this much of a resource at the station dock, over any number of trips.
+    DeliverResource {
+        resource_type: ResourceType,
+        amount_kg: u32,
+    },
+    Dock {
+        times: u32,
+    },
+    /// Dive this far below y = 0, the station floor.
+    ReachDepth {
+        depth_m: f32,
+    },
+}
+
+/// Something a player did that may count toward a mission.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MissionEvent {
+    Delivered {
+        resource_type: ResourceType,
+        amount_kg: u32,
+    },
+    Docked,
+    /// Current sub position's height; depth is measured below y = 0.
+    AtHeight(f32),
+}
+
+impl MissionObjective {
+    /// Progress at which the mission is complete.
+    pub fn total(&self) -> u32 {
+        match *self {
+            Self::DeliverResource { amount_kg, .. } => amount_kg,
+            Self::Dock { times } => times,
+            Self::ReachDepth { depth_m } => depth_m.max(0.0).ceil() as u32,
+        }
+    }
+
+    /// Progress after `event`, capped at `total`, or `None` if the event does not move it.
+    pub fn advance(&self, progress: u32, event: &MissionEvent) -> Option<u32> {
+        let next = match (self, event) {
+            (
+                Self::DeliverResource { resource_type, .. },
+                MissionEvent::Delivered {
+                    resource_type: delivered,
+                    amount_kg,
+                },
+            ) if resource_type == delivered => progress.saturating_add(*amount_kg),
+            (Self::Dock { .. }, MissionEvent::Docked) => progress.saturating_add(1),
+            // Depth is a high-water mark: surfacing again loses nothing
+            (Self::ReachDepth { .. }, MissionEvent::AtHeight(y)) => {
+                progress.max((-y).max(0.0).floor() as u32)
+            }
+            _ => return None,
+        };
+        let next = next.min(self.total());
+        (next > progress).then_some(next)
+    }
+}
+
+/// Objective offered to every player; its `id` names it on the wire.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mission {
+    pub id: u32,
+    pub description: String,
+    pub objective: MissionObjective,
+    /// Credits paid once on completion; they count toward `UnlockCondition::MinCredits`.
+    pub reward_credits: u64,
+}
+
+/// Missions the server offers and the client describes, matched by id.
+pub fn builtin_missions() -> Vec<Mission> {
+    vec![
+        Mission {
+            id: 0,
+            description: "Deliver 100 kg of ore to the station".to_string(),
+            objective: MissionObjective::DeliverResource {
+                resource_type: ResourceType::Ore,
+                amount_kg: 100,
+            },
+            reward_credits: 500,
+        },
+        Mission {
+            id: 1,
+            description: "Dock at the station 3 times".to_string(),
+            objective: MissionObjective::Dock { times: 3 },
+            reward_credits: 100,
+        },
+        Mission {
+            id: 2,
+            description: "Dive 10 m below the station floor".to_string(),
+            objective: MissionObjective::ReachDepth { depth_m: 10.0 },
+            reward_credits: 200,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn objectives_advance_only_on_matching_events_and_cap_at_total() {
+        let deliver = MissionObjective::DeliverResource {
+            resource_type: ResourceType::Ore,
+            amount_kg: 100,
+        };
+        let ore = |amount_kg| MissionEvent::Delivered {
+            resource_type: ResourceType::Ore,
+            amount_kg,
+        };
+        assert_eq!(deliver.advance(0, &ore(60)), Some(60));
+        assert_eq!(deliver.advance(60, &ore(60)), Some(100));
+        assert_eq!(deliver.advance(100, &ore(60)), None);
+        assert_eq!(deliver.advance(0, &MissionEvent::Docked), None);
+
+        let dock = MissionObjective::Dock { times: 3 };
+        assert_eq!(dock.advance(2, &MissionEvent::Docked), Some(3));
+        assert_eq!(dock.advance(3, &MissionEvent::Docked), None);
+
+        let dive = MissionObjective::ReachDepth { depth_m: 10.0 };
+        assert_eq!(dive.total(), 10);
+        assert_eq!(dive.advance(0, &MissionEvent::AtHeight(4.0)), None);
+        assert_eq!(dive.advance(0, &MissionEvent::AtHeight(-6.5)), Some(6));
+        // Rising back up keeps the deepest point reached
+        assert_eq!(dive.advance(6, &MissionEvent::AtHeight(-2.0)), None);
+        assert_eq!(dive.advance(6, &MissionEvent::AtHeight(-14.0)), Some(10));
+    }
+
+    #[test]
+    fn builtin_mission_ids_are_unique() {
+        let missions = builtin_missions();
+        let mut ids: Vec<_> = missions.iter().map(|m| m.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), missions.len());
+        assert!(missions.iter().all(|m| m.objective.total() > 0));
+    }
+}
@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Vec3f;
+
+/// Half-width of a ping's beam. Rays steeper than this still leave the emitter at the beam
+/// edge, so a ping straight down refracts like its outermost ray.
+pub const SONAR_BEAM_HALF_ANGLE_RAD: f32 = std::f32::consts::FRAC_PI_6;
+
+/// Detection range scales by `exp(-k * bend^2)` for a ray bent by `bend` radians in total.
+///
+/// A gameplay tuning, not a physical constant. A strong thermocline (a 10% density step, as in
+/// the tests) bends the beam-edge ray by about 0.027 rad, so 1000 halves the range to a target
+/// directly below it (`exp(-1000 * 0.027^2) ≈ 0.49`). Ocean-like gradients of a few hundredths
+/// of a kg/m³ per metre bend rays by under a milliradian and cost well under 1% of the range.
+pub const REFRACTION_RANGE_LOSS_PER_RAD2: f32 = 1000.0;
+
+/// Source level (dB) at which a contact is heard at exactly the base range; louder sources
+/// carry proportionally further, quieter ones down to a tenth of it.
+pub const REFERENCE_SOURCE_LEVEL_DB: f32 = 120.0;
+
+/// Floor for the density used as a refraction index, so a bad profile cannot divide by zero.
+const MIN_DENSITY_KG_M3: f32 = 1.0;
+
+/// Seawater density (kg/m³) by height. Layers are horizontal, so sound bends only when it
+/// travels up or down through them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DensityProfile {
+    /// Density changes at a constant rate with height; negative gradients are lighter above.
+    Linear {
+        density_at_zero: f32,
+        gradient_per_m: f32,
+    },
+    /// Two well-mixed layers meeting in a sharp thermocline at `height`.
+    Thermocline {
+        height: f32,
+        upper_density: f32,
+        lower_density: f32,
+    },
+}
+
+/// Uniform seawater: no layers, so pings travel straight.
+impl Default for DensityProfile {
+    fn default() -> Self {
+        Self::Linear {
+            density_at_zero: 1025.0,
+            gradient_per_m: 0.0,
+        }
+    }
+}
+
+impl DensityProfile {
+    pub fn density_at(&self, y: f32) -> f32 {
+        match *self {
+            Self::Linear {
+                density_at_zero,
+                gradient_per_m,
+            } => density_at_zero + gradient_per_m * y,
+            Self::Thermocline {
+                height,
+                upper_density,
+                lower_density,
+            } => {
+                if y >= height {
+                    upper_density
+                } else {
+                    lower_density
+                }
+            }
+        }
+    }
+
+    /// Acoustic refraction index; only ratios between heights matter.
+    fn index_at(&self, y: f32) -> f32 {
+        self.density_at(y).max(MIN_DENSITY_KG_M3).sqrt()
+    }
+
+    /// Total bending (radians) of a ray leaving `from_y` at `incidence` from the vertical on
+    /// its way to `to_y`, or `None` if it turns back before getting there (a shadow zone).
+    fn cumulative_refraction(&self, from_y: f32, to_y: f32, incidence: f32) -> Option<f32> {
+        match *self {
+            // `n * sin(i)` is conserved along a ray through horizontal layers, and the index is
+            // monotonic in height, so the turn is just the difference of the end angles
+            Self::Linear { .. } => {
+                snell_turn(self.index_at(from_y), self.index_at(to_y), incidence)
+            }
+            Self::Thermocline { height, .. } => {
+                if (from_y - height) * (to_y - height) >= 0.0 {
+                    return Some(0.0);
+                }
+                snell_turn(self.index_at(from_y), self.index_at(to_y), incidence)
+            }
+        }
+    }
+}
+
+/// Change of direction crossing from index `n1` to `n2`, or `None` on total internal reflection.
+fn snell_turn(n1: f32, n2: f32, incidence: f32) -> Option<f32> {
+    let sin_out = n1 / n2 * incidence.sin();
+    (sin_out <= 1.0).then(|| (sin_out.asin() - incidence).abs())
+}
+
+/// Sonar range through stratified water, approximating the ping as one straight ray that
+/// picks up bending where it crosses density gradients.
+pub struct SonarPropagation;
+
+impl SonarPropagation {
+    /// How far `base_range` reaches from `source` toward `target` once refraction through
+    /// `density_profile` is accounted for; zero if the target sits in a shadow zone.
+    ///
+    /// `source_level_db` is the sound source's radiated noise
+    /// (`SubPhysicsSpec::acoustic_level_db`); the range is scaled by
+    /// `(source_level_db / REFERENCE_SOURCE_LEVEL_DB).max(0.1)`.
+    pub fn compute_detection_range(
+        source: Vec3f,
+        target: Vec3f,
+        density_profile: &DensityProfile,
+        base_range: f32,
+        source_level_db: f32,
+    ) -> f32 {
+        let base_range = base_range * (source_level_db / REFERENCE_SOURCE_LEVEL_DB).max(0.1);
+        let delta = target - source;
+        let distance = delta.length();
+        if !distance.is_finite() || distance < 1e-3 {
+            return base_range;
+        }
+        let incidence = (delta.y.abs() / distance)
+            .clamp(0.0, 1.0)
+            .acos()
+            .max(SONAR_BEAM_HALF_ANGLE_RAD);
+        let Some(bend) = density_profile.cumulative_refraction(source.y, target.y, incidence)
+        else {
+            return 0.0;
+        };
+        base_range * (-REFRACTION_RANGE_LOSS_PER_RAD2 * bend * bend).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRONG_THERMOCLINE: DensityProfile = DensityProfile::Thermocline {
+        height: -20.0,
+        upper_density: 1000.0,
+        lower_density: 1100.0,
+    };
+
+    #[test]
+    fn strong_thermocline_cuts_range_to_target_directly_below() {
+        let source = Vec3f::ZERO;
+        let below = Vec3f::new(0.0, -40.0, 0.0);
+        let range = SonarPropagation::compute_detection_range(
+            source,
+            below,
+            &STRONG_THERMOCLINE,
+            100.0,
+            REFERENCE_SOURCE_LEVEL_DB,
+        );
+        assert!(range <= 70.0, "range {range}");
+        assert!(range > 0.0);
+
+        // Same layer: nothing to bend through
+        let above_layer = Vec3f::new(0.0, -10.0, 0.0);
+        let range = SonarPropagation::compute_detection_range(
+            source,
+            above_layer,
+            &STRONG_THERMOCLINE,
+            100.0,
+            REFERENCE_SOURCE_LEVEL_DB,
+        );
+        assert_eq!(range, 100.0);
+    }
+
+    #[test]
+    fn grazing_ping_out_of_dense_water_hits_shadow_zone() {
+        let source = Vec3f::new(0.0, -25.0, 0.0);
+        let target = Vec3f::new(80.0, -15.0, 0.0);
+        let range = SonarPropagation::compute_detection_range(
+            source,
+            target,
+            &STRONG_THERMOCLINE,
+            100.0,
+            REFERENCE_SOURCE_LEVEL_DB,
+        );
+        assert_eq!(range, 0.0);
+    }
+
+    #[test]
+    fn linear_gradient_bends_by_end_densities() {
+        let gentle = DensityProfile::Linear {
+            density_at_zero: 1025.0,
+            gradient_per_m: -0.05,
+        };
+        let steep = DensityProfile::Linear {
+            density_at_zero: 1025.0,
+            gradient_per_m: -2.0,
+        };
+        let (source, target) = (Vec3f::ZERO, Vec3f::new(0.0, -40.0, 0.0));
+        let gentle_range = SonarPropagation::compute_detection_range(
+            source,
+            target,
+            &gentle,
+            100.0,
+            REFERENCE_SOURCE_LEVEL_DB,
+        );
+        let steep_range = SonarPropagation::compute_detection_range(
+            source,
+            target,
+            &steep,
+            100.0,
+            REFERENCE_SOURCE_LEVEL_DB,
+        );
+        assert!(gentle_range > 99.0, "gentle {gentle_range}");
+        assert!(steep_range < gentle_range, "steep {steep_range}");
+
+        // Level with the source the gradient is not crossed at all
+        let level = Vec3f::new(60.0, 0.0, 0.0);
+        assert_eq!(
+            SonarPropagation::compute_detection_range(
+                source,
+                level,
+                &steep,
+                100.0,
+                REFERENCE_SOURCE_LEVEL_DB
+            ),
+            100.0
+        );
+    }
+
+    #[test]
+    fn full_thrust_is_heard_further_than_idle() {
+        use crate::builtins::greybox_level;
+        use crate::subspecs::small_skiff_spec;
+        use crate::{step_submarine_dbg, Quatf, SubInputState, SubState, SubStepDebug};
+
+        let level = greybox_level();
+        let spec = small_skiff_spec();
+        let level_db = |thrust: f32| {
+            let mut state = SubState {
+                position: level.tunnel.pos,
+                velocity: Vec3f::ZERO,
+                orientation: Quatf::IDENTITY,
+                ang_mom: Vec3f::ZERO,
+                ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+                pump: Default::default(),
+            };
+            let inputs = SubInputState {
+                thrust,
+                ..Default::default()
+            };
+            let mut dbg = SubStepDebug::default();
+            step_submarine_dbg(&level, &spec, inputs, &mut state, 0.01, 0.0, Some(&mut dbg));
+            dbg.acoustic_level_db
+        };
+        let (idle, full) = (level_db(0.0), level_db(1.0));
+        // Only turning adds noise at idle; full thrust adds 20·log10(2) ≈ 6 dB on top
+        assert!((idle - spec.acoustic_noise_db).abs() < 0.5, "idle {idle}");
+        assert!(full - idle > 5.0, "{idle} -> {full}");
+
+        let profile = DensityProfile::Linear {
+            density_at_zero: 1025.0,
+            gradient_per_m: 0.0,
+        };
+        let (sub, listener) = (Vec3f::ZERO, Vec3f::new(50.0, 0.0, 0.0));
+        let range =
+            |db| SonarPropagation::compute_detection_range(sub, listener, &profile, 100.0, db);
+        assert!(range(full) > range(idle));
+        assert!((range(idle) - 100.0 * idle / REFERENCE_SOURCE_LEVEL_DB).abs() < 1e-3);
+        // Near-silent sources are still heard at a tenth of the base range
+        assert_eq!(range(0.0), 10.0);
+    }
+}
@@ -1,9 +1,24 @@
-use crate::Vec3f;
+use crate::builtins::Rng;
+use crate::{DensityProfile, ResourceType, Vec3f, ORE_KG_PER_YIELD_UNIT};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FlowFieldSpec {
-    Uniform { flow: Vec3f, variance: f32 },
+    Uniform {
+        flow: Vec3f,
+        variance: f32,
+    },
+    /// `mean_flow` plus an Ornstein-Uhlenbeck perturbation with standard deviation
+    /// `intensity` (m/s) per axis, decorrelating over `correlation_length / |mean_flow|`
+    /// seconds. Seeded, and a function of time only, so client and server sample the same
+    /// eddies at the same time.
+    TurbulentUniform {
+        mean_flow: Vec3f,
+        intensity: f32,
+        correlation_length: f32,
+        seed: u64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +75,186 @@ pub struct TorusExitSpec {
     pub label: String,
 }
 
+/// Upwelling column of hot water rising from `position` (its base).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThermalVentSpec {
+    pub position: Vec3f,
+    pub radius_m: f32,
+    /// Column height above `position`; subs passing over the top are unaffected.
+    pub height_m: f32,
+    pub upwelling_m_s: f32,
+    /// Hull integrity lost per second inside the column (integrity is 0..1).
+    pub damage_per_s: f32,
+}
+
+impl ThermalVentSpec {
+    pub fn contains(&self, p: Vec3f) -> bool {
+        let dx = p.x - self.position.x;
+        let dz = p.z - self.position.z;
+        let dy = p.y - self.position.y;
+        (dx * dx + dz * dz).sqrt() < self.radius_m && dy >= 0.0 && dy <= self.height_m
+    }
+}
+
+/// Mineable ore deposit. Its node id on the wire is its index in `LevelSpec::ore_nodes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OreNodeSpec {
+    pub position: Vec3f,
+    /// Ore per mining relative to a standard node; deeper deposits are richer.
+    #[serde(default = "default_ore_yield")]
+    pub yield_units: f32,
+    /// Relative chances of what one mining pass brings up; empty means plain ore.
+    #[serde(default)]
+    pub yields: Vec<(ResourceType, f32)>,
+    /// Kilograms the node holds before it is mined out and starts to respawn; unset, a single
+    /// pass (`pass_kg`) empties it.
+    #[serde(default)]
+    pub supply_kg: Option<u32>,
+}
+
+fn default_ore_yield() -> f32 {
+    1.0
+}
+
+impl OreNodeSpec {
+    /// Plain ore node that one pass mines out.
+    pub fn ore(position: Vec3f, yield_units: f32) -> Self {
+        Self {
+            position,
+            yield_units,
+            yields: Vec::new(),
+            supply_kg: None,
+        }
+    }
+
+    /// Kilograms one mining pass takes, before the remaining supply caps it.
+    pub fn pass_kg(&self) -> u32 {
+        (self.yield_units * ORE_KG_PER_YIELD_UNIT).round() as u32
+    }
+
+    pub fn supply_kg(&self) -> u32 {
+        self.supply_kg.unwrap_or_else(|| self.pass_kg())
+    }
+
+    /// Resource brought up by mining pass `pass` of node `node_id`, drawn from `yields`. The
+    /// draw is a hash of both, so it is reproducible without keeping an RNG per node.
+    pub fn resource_for_pass(&self, node_id: u32, pass: u32) -> ResourceType {
+        let total: f32 = self.yields.iter().map(|&(_, w)| w).sum();
+        if total <= 0.0 {
+            return ResourceType::Ore;
+        }
+        let mut rng = Rng::new(node_id.wrapping_mul(0x9e37_79b9) ^ pass);
+        let mut pick = rng.range(0.0, total);
+        for &(resource_type, weight) in &self.yields {
+            if pick < weight {
+                return resource_type;
+            }
+            pick -= weight;
+        }
+        self.yields.last().map_or(ResourceType::Ore, |&(r, _)| r)
+    }
+
+    /// Resource with the largest weight in `yields`, or plain ore for an empty list.
+    pub fn primary_resource(&self) -> ResourceType {
+        self.yields
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(ResourceType::Ore, |&(r, _)| r)
+    }
+}
+
+/// Readable slab of backstory; its text is shown once a sub comes within `trigger_radius_m`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LorePlaque {
+    pub position: Vec3f,
+    pub text: String,
+    pub trigger_radius_m: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HoloIcon {
+    Warning,
+    Objective,
+    Poi,
+}
+
+/// Floating holographic icon drawn facing the camera; `color` is linear RGB.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoloMarker {
+    pub position: Vec3f,
+    pub icon: HoloIcon,
+    pub color: [f32; 3],
+}
+
+/// Solid box that loops along `waypoints` at a constant speed, returning from the last waypoint
+/// to the first. The server moves it and broadcasts its position; `id` names it on the wire.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovingObstacleSpec {
+    pub id: u32,
+    /// Box centers along the path; a single waypoint is a stationary obstacle.
+    pub waypoints: Vec<Vec3f>,
+    pub speed_m_s: f32,
+    /// Full box extents.
+    pub mesh_size: Vec3f,
+}
+
+impl MovingObstacleSpec {
+    /// Length of one lap, including the closing leg back to the first waypoint.
+    pub fn path_length(&self) -> f32 {
+        self.legs().map(|(a, b)| a.distance(b)).sum()
+    }
+
+    /// Box center after traveling `distance_m` from the first waypoint, wrapping every lap.
+    pub fn position_at(&self, distance_m: f32) -> Vec3f {
+        let Some(&first) = self.waypoints.first() else {
+            return Vec3f::ZERO;
+        };
+        let lap = self.path_length();
+        if lap <= f32::EPSILON {
+            return first;
+        }
+        let mut remaining = distance_m.rem_euclid(lap);
+        for (a, b) in self.legs() {
+            let leg = a.distance(b);
+            if remaining <= leg {
+                return a.lerp(b, remaining / leg.max(f32::EPSILON));
+            }
+            remaining -= leg;
+        }
+        first
+    }
+
+    /// Contact between a sphere at `p` and this box centered at `center`: the outward normal
+    /// and how deep the sphere reaches in, or `None` if they do not touch.
+    pub fn contact(&self, center: Vec3f, p: Vec3f, radius: f32) -> Option<(Vec3f, f32)> {
+        let half = self.mesh_size * 0.5;
+        let local = p - center;
+        let closest = local.clamp(-half, half);
+        let offset = local - closest;
+        let dist = offset.length();
+        if dist > f32::EPSILON {
+            return (dist < radius).then(|| (offset / dist, radius - dist));
+        }
+        // Center inside the box: leave through the nearest face
+        let gaps = half - local.abs();
+        let axis = if gaps.x <= gaps.y && gaps.x <= gaps.z {
+            Vec3f::X
+        } else if gaps.y <= gaps.z {
+            Vec3f::Y
+        } else {
+            Vec3f::Z
+        };
+        let sign = if local.dot(axis) < 0.0 { -1.0 } else { 1.0 };
+        Some((axis * sign, gaps.dot(axis) + radius))
+    }
+
+    fn legs(&self) -> impl Iterator<Item = (Vec3f, Vec3f)> + '_ {
+        let n = self.waypoints.len();
+        (0..if n > 1 { n } else { 0 })
+            .map(move |i| (self.waypoints[i], self.waypoints[(i + 1) % n]))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LevelSpec {
     pub room: RoomSpec,
@@ -69,4 +264,370 @@ pub struct LevelSpec {
     /// with labelled exits. Client can render if present; physics can sample
     /// its flow field separately from the axis‑aligned `tunnel`.
     pub torus_tunnel: Option<TorusTunnelSpec>,
+    /// Dead-end branches off the main tunnel; open water, but without current.
+    #[serde(default)]
+    pub side_tunnels: Vec<TunnelSpec>,
+    #[serde(default)]
+    pub thermal_vents: Vec<ThermalVentSpec>,
+    #[serde(default)]
+    pub ore_nodes: Vec<OreNodeSpec>,
+    #[serde(default)]
+    pub lore_plaques: Vec<LorePlaque>,
+    #[serde(default)]
+    pub holo_markers: Vec<HoloMarker>,
+    #[serde(default)]
+    pub moving_obstacles: Vec<MovingObstacleSpec>,
+    /// Water layering sonar pings refract through; uniform seawater when unset.
+    #[serde(default)]
+    pub density_profile: DensityProfile,
+}
+
+/// A problem found by `LevelSpec::validate`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelSpecError {
+    /// A room, tunnel or chamber has a zero, negative or non-finite size or position.
+    NegativeTunnelDimension {
+        volume: String,
+    },
+    /// A flow field with a non-finite velocity or a negative variance.
+    InvalidFlowField {
+        volume: String,
+    },
+    /// A direction vector that cannot be normalized.
+    InvalidOrientationNorm {
+        what: String,
+    },
+    DockOutsideOpenWater,
+    OreOutsideOpenWater {
+        index: usize,
+    },
+    InvalidOreYield {
+        index: usize,
+    },
+    InvalidThermalVent {
+        index: usize,
+    },
+    /// No waypoints, a waypoint in rock, a negative speed or a box without extent.
+    InvalidMovingObstacle {
+        id: u32,
+    },
+}
+
+impl std::fmt::Display for LevelSpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NegativeTunnelDimension { volume } => {
+                write!(f, "{volume} needs a finite position and a size > 0")
+            }
+            Self::InvalidFlowField { volume } => {
+                write!(f, "{volume} flow needs a finite velocity and variance >= 0")
+            }
+            Self::InvalidOrientationNorm { what } => write!(f, "{what} is not a usable direction"),
+            Self::DockOutsideOpenWater => write!(f, "dock pad is outside open water"),
+            Self::OreOutsideOpenWater { index } => {
+                write!(f, "ore_nodes[{index}] is outside open water")
+            }
+            Self::InvalidOreYield { index } => {
+                write!(
+                    f,
+                    "ore_nodes[{index}] needs finite yield_units and yield weights >= 0"
+                )
+            }
+            Self::InvalidThermalVent { index } => {
+                write!(f, "thermal_vents[{index}] needs a radius and height > 0")
+            }
+            Self::InvalidMovingObstacle { id } => write!(
+                f,
+                "moving obstacle {id} needs waypoints in open water, a speed >= 0 and a size > 0"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LevelSpecError {}
+
+#[derive(Debug)]
+pub enum LevelLoadError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Json(serde_json::Error),
+    Invalid(Vec<LevelSpecError>),
+}
+
+impl std::fmt::Display for LevelLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "reading level: {err}"),
+            Self::Parse(err) => write!(f, "parsing level: {err}"),
+            Self::Json(err) => write!(f, "parsing level JSON: {err}"),
+            Self::Invalid(errors) => {
+                write!(f, "invalid level:")?;
+                for err in errors {
+                    write!(f, " {err};")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LevelLoadError {}
+
+/// Why `save_level` couldn't write a level.
+#[derive(Debug, thiserror::Error)]
+pub enum LevelSaveError {
+    #[error("serializing level: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("writing level: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Read, parse and validate a JSON level file, as written by `save_level`.
+pub fn load_level(path: &Path) -> Result<LevelSpec, LevelLoadError> {
+    let s = std::fs::read_to_string(path).map_err(LevelLoadError::Io)?;
+    let level: LevelSpec = serde_json::from_str(&s).map_err(LevelLoadError::Json)?;
+    level.validate().map_err(LevelLoadError::Invalid)?;
+    Ok(level)
+}
+
+/// Write `spec` as pretty-printed JSON.
+pub fn save_level(spec: &LevelSpec, path: &Path) -> Result<(), LevelSaveError> {
+    let json = serde_json::to_string_pretty(spec)?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// Whether `p` lies in the axis-aligned box of `size` centered at `center`, faces included.
+pub fn aabb_contains(center: Vec3f, size: Vec3f, p: Vec3f) -> bool {
+    (p - center).abs().cmple(size * 0.5).all()
+}
+
+impl FlowFieldSpec {
+    fn is_valid(&self) -> bool {
+        match self {
+            Self::Uniform { flow, variance } => {
+                flow.is_finite() && variance.is_finite() && *variance >= 0.0
+            }
+            Self::TurbulentUniform {
+                mean_flow,
+                intensity,
+                correlation_length,
+                ..
+            } => {
+                mean_flow.is_finite()
+                    && intensity.is_finite()
+                    && *intensity >= 0.0
+                    && correlation_length.is_finite()
+                    && *correlation_length > 0.0
+            }
+        }
+    }
+
+    /// Time-averaged flow and its variance, for visuals that don't animate turbulence.
+    pub fn mean(&self) -> (Vec3f, f32) {
+        match *self {
+            Self::Uniform { flow, variance } => (flow, variance),
+            Self::TurbulentUniform {
+                mean_flow,
+                intensity,
+                ..
+            } => (mean_flow, intensity * intensity),
+        }
+    }
+}
+
+impl LevelSpec {
+    /// Parse a level from RON text, e.g. the client's `assets/levels/*.level.ron` files.
+    pub fn from_ron_str(s: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(s)
+    }
+
+    /// Read, parse and validate a level file.
+    pub fn from_ron_file(path: impl AsRef<std::path::Path>) -> Result<Self, LevelLoadError> {
+        let s = std::fs::read_to_string(path).map_err(LevelLoadError::Io)?;
+        let level = Self::from_ron_str(&s).map_err(LevelLoadError::Parse)?;
+        level.validate().map_err(LevelLoadError::Invalid)?;
+        Ok(level)
+    }
+
+    /// Inside the station room, the tunnel, a side tunnel or the chamber. Everything outside
+    /// these AABBs is rock, so the server treats leaving them as a wall collision.
+    pub fn in_open_water(&self, p: Vec3f) -> bool {
+        let inside = |center: Vec3f, size: Vec3f| aabb_contains(center, size, p);
+        // The room is centered on the origin in XZ, standing on its floor slab
+        let room_center = Vec3f::new(0.0, self.room.size.y * 0.5 - self.room.wall_thickness, 0.0);
+        inside(room_center, self.room.size)
+            || inside(self.tunnel.pos, self.tunnel.size)
+            || inside(self.chamber.pos, self.chamber.size)
+            || self.side_tunnels.iter().any(|t| inside(t.pos, t.size))
+    }
+
+    /// Every problem that would break physics or leave content unreachable: degenerate
+    /// volumes, unusable flow fields, a zero torus axis, and a dock pad, ore, vents or moving
+    /// obstacles buried in rock or without extent.
+    pub fn validate(&self) -> Result<(), Vec<LevelSpecError>> {
+        let mut errors = Vec::new();
+        let volumes = [
+            ("room".to_string(), Vec3f::ZERO, self.room.size, None),
+            (
+                "tunnel".to_string(),
+                self.tunnel.pos,
+                self.tunnel.size,
+                Some(&self.tunnel.flow),
+            ),
+            (
+                "chamber".to_string(),
+                self.chamber.pos,
+                self.chamber.size,
+                None,
+            ),
+        ]
+        .into_iter()
+        .chain(
+            self.side_tunnels
+                .iter()
+                .enumerate()
+                .map(|(i, t)| (format!("side_tunnels[{i}]"), t.pos, t.size, Some(&t.flow))),
+        );
+        for (volume, pos, size, flow) in volumes {
+            if !(pos.is_finite() && size.is_finite() && size.min_element() > 0.0) {
+                errors.push(LevelSpecError::NegativeTunnelDimension {
+                    volume: volume.clone(),
+                });
+            }
+            if flow.is_some_and(|f| !f.is_valid()) {
+                errors.push(LevelSpecError::InvalidFlowField { volume });
+            }
+        }
+        if let Some(torus) = &self.torus_tunnel {
+            let volume = "torus_tunnel".to_string();
+            if !(torus.center.is_finite()
+                && torus.minor_radius.is_finite()
+                && torus.minor_radius > 0.0
+                && torus.major_radius.is_finite()
+                && torus.major_radius > torus.minor_radius)
+            {
+                errors.push(LevelSpecError::NegativeTunnelDimension {
+                    volume: volume.clone(),
+                });
+            }
+            if torus.axis.try_normalize().is_none() {
+                errors.push(LevelSpecError::InvalidOrientationNorm {
+                    what: "torus_tunnel.axis".to_string(),
+                });
+            }
+            if !torus.flow.is_valid() {
+                errors.push(LevelSpecError::InvalidFlowField { volume });
+            }
+        }
+        if !self.in_open_water(self.room.dock_pos) {
+            errors.push(LevelSpecError::DockOutsideOpenWater);
+        }
+        for (index, ore) in self.ore_nodes.iter().enumerate() {
+            if !self.in_open_water(ore.position) {
+                errors.push(LevelSpecError::OreOutsideOpenWater { index });
+            }
+            if !(ore.yield_units.is_finite() && ore.yield_units >= 0.0)
+                || ore
+                    .yields
+                    .iter()
+                    .any(|&(_, w)| !(w.is_finite() && w >= 0.0))
+            {
+                errors.push(LevelSpecError::InvalidOreYield { index });
+            }
+        }
+        for (index, vent) in self.thermal_vents.iter().enumerate() {
+            if !(vent.radius_m.is_finite() && vent.radius_m > 0.0)
+                || !(vent.height_m.is_finite() && vent.height_m > 0.0)
+            {
+                errors.push(LevelSpecError::InvalidThermalVent { index });
+            }
+        }
+        for obstacle in &self.moving_obstacles {
+            let waypoints_ok = !obstacle.waypoints.is_empty()
+                && obstacle
+                    .waypoints
+                    .iter()
+                    .all(|&w| w.is_finite() && self.in_open_water(w));
+            let speed_ok = obstacle.speed_m_s.is_finite() && obstacle.speed_m_s >= 0.0;
+            let size_ok =
+                obstacle.mesh_size.is_finite() && obstacle.mesh_size.cmpgt(Vec3f::ZERO).all();
+            if !(waypoints_ok && speed_ok && size_ok) {
+                errors.push(LevelSpecError::InvalidMovingObstacle { id: obstacle.id });
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rock_below_the_cave_floor_is_not_open_water() {
+        let level = crate::builtins::generate_cave_level(7, 80.0, 3);
+        let chamber = &level.chamber;
+        let floor_y = chamber.pos.y - chamber.size.y * 0.5;
+        let in_chamber = Vec3f::new(chamber.pos.x, floor_y + 1.0, chamber.pos.z);
+        assert!(level.in_open_water(in_chamber));
+        let below_floor = Vec3f::new(chamber.pos.x, floor_y - 10.0, chamber.pos.z);
+        assert!(!level.in_open_water(below_floor));
+        // The dock pad sits on the chamber floor, in open water
+        assert!(level.in_open_water(level.room.dock_pos));
+        assert!(!level.in_open_water(level.room.dock_pos - Vec3f::new(0.0, 2.0, 0.0)));
+    }
+
+    /// Same shape, and every number within `f32::EPSILON * 10.0`.
+    fn assert_json_close(a: &serde_json::Value, b: &serde_json::Value, at: &str) {
+        use serde_json::Value;
+        match (a, b) {
+            (Value::Number(x), Value::Number(y)) => {
+                let (x, y) = (x.as_f64().unwrap(), y.as_f64().unwrap());
+                assert!(
+                    (x - y).abs() <= (f32::EPSILON * 10.0) as f64,
+                    "{at}: {x} vs {y}"
+                );
+            }
+            (Value::Array(xs), Value::Array(ys)) => {
+                assert_eq!(xs.len(), ys.len(), "{at}");
+                for (i, (x, y)) in xs.iter().zip(ys).enumerate() {
+                    assert_json_close(x, y, &format!("{at}[{i}]"));
+                }
+            }
+            (Value::Object(xs), Value::Object(ys)) => {
+                assert_eq!(xs.len(), ys.len(), "{at}");
+                for (key, x) in xs {
+                    assert_json_close(x, &ys[key], &format!("{at}.{key}"));
+                }
+            }
+            _ => assert_eq!(a, b, "{at}"),
+        }
+    }
+
+    #[test]
+    fn greybox_level_round_trips_through_json() {
+        let dir = std::env::temp_dir().join(format!("thalasso-level-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("greybox.json");
+        let level = crate::builtins::greybox_level();
+        save_level(&level, &path).unwrap();
+        let loaded = load_level(&path).unwrap();
+        assert_json_close(
+            &serde_json::to_value(&level).unwrap(),
+            &serde_json::to_value(&loaded).unwrap(),
+            "level",
+        );
+
+        std::fs::write(&path, "{").unwrap();
+        let err = load_level(&path).unwrap_err();
+        assert!(matches!(err, LevelLoadError::Json(_)), "{err}");
+        let err = load_level(&dir.join("missing.json")).unwrap_err();
+        assert!(matches!(err, LevelLoadError::Io(_)), "{err}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,13 +1,26 @@
-use crate::Vec3f;
+use std::path::Path;
+
+use crate::{DiveDepthEvent, Vec3f};
 use serde::{Deserialize, Serialize};
 
 /// Precomputed physics parameters for a specific submarine hull class.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubPhysicsSpec {
     pub m: f32,
     pub ixx: f32,
     pub iyy: f32,
     pub izz: f32,
+    /// Added (entrained-water) moment of inertia about body +X, the pitch axis (kg·m²).
+    /// Physics divides angular momentum by `ixx + added_ixx`; see `rotational_inertia`.
+    #[serde(default)]
+    pub added_ixx: f32,
+    /// Added moment of inertia about body +Y, the yaw axis (kg·m²).
+    #[serde(default)]
+    pub added_iyy: f32,
+    /// Added moment of inertia about body +Z, the roll axis (kg·m²). Near zero for a hull of
+    /// revolution, which drags little water with it when it rolls.
+    #[serde(default)]
+    pub added_izz: f32,
     pub cxd: f32,
     pub cyd: f32,
     pub czd: f32,
@@ -23,8 +36,30 @@ pub struct SubPhysicsSpec {
     pub volume_m3: f32,
     pub t_max: f32,
     pub tau_thr: f32,
+    /// Time constant (s) for ramping commanded thrust toward the raw input.
+    pub thrust_tau_s: f32,
+    /// Time constant (s) for ramping commanded rudder toward the raw input.
+    pub yaw_tau_s: f32,
+    /// Time constant (s) for ramping commanded ballast pump speeds toward the raw input.
+    #[serde(default = "default_pump_tau_s")]
+    pub pump_tau_s: f32,
+    /// Fraction of a ballast tank's capacity one pump moves per second at full speed.
+    #[serde(default = "default_pump_rate_frac_per_s")]
+    pub pump_rate_frac_per_s: f32,
+    /// Whether the pumps can boost past `pump_rate_frac_per_s`; see `PumpType`.
+    #[serde(default)]
+    pub pump_type: PumpType,
     pub n_delta_r: f32,
     pub n_beta: f32,
+    /// Dive plane pitch moment coefficient: plane input `δ` gives
+    /// `δ · m_delta_s · q · s_top · length` about body right. Zero (the default) leaves the
+    /// planes cosmetic.
+    #[serde(default)]
+    pub m_delta_s: f32,
+    /// Differential plane roll moment coefficient: roll trim `δ` gives
+    /// `-δ · k_delta_s · q · s_top · diameter` about body forward.
+    #[serde(default)]
+    pub k_delta_s: f32,
     pub m_delta_b: f32,
     pub delta_r_max: f32,
     pub delta_b_max: f32,
@@ -34,19 +69,505 @@ pub struct SubPhysicsSpec {
     pub s_side: f32,
     pub s_top: f32,
     pub ballast_tanks: Vec<BallastTankSpec>,
+    /// Thrusters; each delivers `thrust_share * t_max` at full input.
+    pub propellers: Vec<PropellerSpec>,
     pub n_ws: f32,
     pub y_delta_r: f32,
     /// Center of buoyancy offset from center of mass in body space (meters).
     /// Positive Y means COB above COM, creating a restoring torque toward level.
     pub cb_offset_body: Vec3f,
+    /// Speed (m/s) above which hull flow noise masks short-range passive sonar. Faster than
+    /// this, contacts nearer than `noise_masking_range(speed)` are lost in self-noise; the masked
+    /// radius is `threshold * speed / max_speed_m_s() * 50` m, so it grows linearly with speed
+    /// and a quieter hull (lower threshold) starts masking sooner but masks less.
+    #[serde(default = "default_sonar_self_noise_threshold_m_s")]
+    pub sonar_self_noise_threshold_m_s: f32,
+    /// Radiated noise of the idle hull in dB (re 1 µPa at 1 m). Thrust and turning add to it
+    /// (see `acoustic_level_db`); around 110 dB is a quiet boat, 130 dB a loud one.
+    #[serde(default = "default_acoustic_noise_db")]
+    pub acoustic_noise_db: f32,
+    /// Number of torpedo tubes; each fires independently.
+    #[serde(default = "default_torpedo_tubes")]
+    pub torpedo_tubes: u8,
+    /// Time (s) before a tube that just fired can fire again.
+    #[serde(default = "default_torpedo_reload_s")]
+    pub torpedo_reload_s: f32,
+    /// Depths past which the hull warns and then blows its ballast; see `DiveDepthLimit`.
+    #[serde(default)]
+    pub dive_depth_limit: DiveDepthLimit,
+}
+
+/// Ballast pump hardware. `Turbo` pumps at `TURBO_PUMP_MULTIPLIER` times
+/// `pump_rate_frac_per_s` for `TURBO_PUMP_BOOST_S` seconds of pumping, then needs
+/// `TURBO_PUMP_COOLDOWN_S` at the standard rate before it can boost again; the budget lives in
+/// `SubState::pump`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PumpType {
+    #[default]
+    Standard,
+    Turbo,
+}
+
+/// Safe diving envelope, in metres below y = 0. Between `warning_depth_m` and `max_depth_m`
+/// each physics step reports `DiveDepthEvent::Warning`; deeper than `max_depth_m` it reports
+/// `Exceeded` and performs an emergency blow: tanks are emptied whatever the pumps command,
+/// and any downward velocity is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DiveDepthLimit {
+    pub max_depth_m: f32,
+    pub warning_depth_m: f32,
+}
+
+impl Default for DiveDepthLimit {
+    /// Well below the deepest built-in level (the trench floor at 64 m).
+    fn default() -> Self {
+        Self {
+            max_depth_m: 200.0,
+            warning_depth_m: 150.0,
+        }
+    }
+}
+
+impl DiveDepthLimit {
+    pub fn classify(&self, depth_m: f32) -> Option<DiveDepthEvent> {
+        if depth_m > self.max_depth_m {
+            Some(DiveDepthEvent::Exceeded { depth_m })
+        } else if depth_m > self.warning_depth_m {
+            Some(DiveDepthEvent::Warning { depth_m })
+        } else {
+            None
+        }
+    }
+}
+
+fn default_pump_tau_s() -> f32 {
+    0.2
+}
+
+fn default_pump_rate_frac_per_s() -> f32 {
+    0.2
+}
+
+fn default_sonar_self_noise_threshold_m_s() -> f32 {
+    1.5
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_acoustic_noise_db() -> f32 {
+    110.0
+}
+
+fn default_torpedo_tubes() -> u8 {
+    2
+}
+
+fn default_torpedo_reload_s() -> f32 {
+    8.0
+}
+
+#[derive(Debug)]
+pub enum SpecLoadError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Invalid(String),
+}
+
+impl std::fmt::Display for SpecLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "reading submarine spec: {err}"),
+            Self::Parse(err) => write!(f, "parsing submarine spec: {err}"),
+            Self::Invalid(err) => write!(f, "invalid submarine spec: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SpecLoadError {}
+
+impl SubPhysicsSpec {
+    /// Parse a spec from RON text, e.g. the client's `assets/specs/*.ron` tuning files.
+    pub fn from_ron_str(s: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(s)
+    }
+
+    /// Pretty-printed RON in the layout of the `assets/specs` files.
+    pub fn to_ron_str(&self) -> String {
+        ron::ser::to_string_pretty(self, Default::default())
+            .expect("spec has only plain numeric fields")
+    }
+
+    /// Read, parse and validate a spec file.
+    pub fn from_ron_file(path: impl AsRef<Path>) -> Result<Self, SpecLoadError> {
+        let s = std::fs::read_to_string(path).map_err(SpecLoadError::Io)?;
+        let spec = Self::from_ron_str(&s).map_err(SpecLoadError::Parse)?;
+        spec.validate().map_err(SpecLoadError::Invalid)?;
+        Ok(spec)
+    }
+
+    pub fn to_ron_file(&self, path: impl AsRef<Path>) -> Result<(), SpecLoadError> {
+        std::fs::write(path, self.to_ron_str()).map_err(SpecLoadError::Io)
+    }
+
+    /// Rigid-body plus added moments of inertia per body axis (kg·m²).
+    pub fn rotational_inertia(&self) -> Vec3f {
+        Vec3f::new(
+            self.ixx + self.added_ixx,
+            self.iyy + self.added_iyy,
+            self.izz + self.added_izz,
+        )
+    }
+
+    /// Strip-theory estimate of the added inertia for a slender hull of revolution:
+    /// `0.1 · m · L²` about the pitch and yaw axes and none about the roll axis.
+    pub fn slender_hull_added_inertia(m: f32, length: f32) -> Vec3f {
+        let transverse = 0.1 * m * length * length;
+        Vec3f::new(transverse, transverse, 0.0)
+    }
+
+    /// Top surge speed in still water: where full thrust balances quadratic plus linear drag.
+    pub fn max_speed_m_s(&self) -> f32 {
+        let rho = 1025.0_f32;
+        let a = 0.5 * rho * self.cxd * self.s_forward;
+        let b = self.xu;
+        if a <= 0.0 {
+            return if b > 0.0 {
+                self.t_max / b
+            } else {
+                f32::INFINITY
+            };
+        }
+        (-b + (b * b + 4.0 * a * self.t_max).sqrt()) / (2.0 * a)
+    }
+
+    /// Radius (m) inside which the sub's own noise hides sonar contacts at `speed`; zero below
+    /// `sonar_self_noise_threshold_m_s`.
+    pub fn noise_masking_range(&self, speed: f32) -> f32 {
+        let threshold = self.sonar_self_noise_threshold_m_s;
+        let max_speed = self.max_speed_m_s();
+        if speed < threshold || !max_speed.is_finite() || max_speed <= 0.0 {
+            return 0.0;
+        }
+        threshold * speed / max_speed * 50.0
+    }
+
+    /// Radiated noise (dB) at `thrust` in [-1, 1] and body yaw rate `yaw_rate` (rad/s):
+    /// `acoustic_noise_db + 20·log10(1 + |thrust|) + 10·log10(1 + |yaw_rate|)`. Full thrust
+    /// adds about 6 dB and a 1 rad/s turn about 3 dB.
+    pub fn acoustic_level_db(&self, thrust: f32, yaw_rate: f32) -> f32 {
+        self.acoustic_noise_db
+            + 20.0 * (1.0 + thrust.abs()).log10()
+            + 10.0 * (1.0 + yaw_rate.abs()).log10()
+    }
+
+    /// Reject specs that would make the integrator blow up (non-finite or non-positive mass
+    /// properties, negative time constants, zero-length thrust axes). Returns the first problem.
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = [
+            ("m", self.m),
+            ("ixx", self.ixx),
+            ("iyy", self.iyy),
+            ("izz", self.izz),
+            ("volume_m3", self.volume_m3),
+            ("length", self.length),
+            ("diameter", self.diameter),
+            ("s_forward", self.s_forward),
+            ("s_side", self.s_side),
+            ("s_top", self.s_top),
+        ];
+        for (name, v) in positive {
+            if !(v.is_finite() && v > 0.0) {
+                return Err(format!("{name} must be finite and > 0 (got {v})"));
+            }
+        }
+        let non_negative = [
+            ("cxd", self.cxd),
+            ("cyd", self.cyd),
+            ("czd", self.czd),
+            ("xu", self.xu),
+            ("yv", self.yv),
+            ("zw", self.zw),
+            ("kr", self.kr),
+            ("kr2", self.kr2),
+            ("kq", self.kq),
+            ("kp", self.kp),
+            ("added_ixx", self.added_ixx),
+            ("added_iyy", self.added_iyy),
+            ("added_izz", self.added_izz),
+            ("t_max", self.t_max),
+            ("tau_thr", self.tau_thr),
+            ("thrust_tau_s", self.thrust_tau_s),
+            ("yaw_tau_s", self.yaw_tau_s),
+            ("pump_tau_s", self.pump_tau_s),
+            ("delta_r_max", self.delta_r_max),
+            ("delta_b_max", self.delta_b_max),
+            (
+                "sonar_self_noise_threshold_m_s",
+                self.sonar_self_noise_threshold_m_s,
+            ),
+            ("acoustic_noise_db", self.acoustic_noise_db),
+            ("torpedo_reload_s", self.torpedo_reload_s),
+        ];
+        for (name, v) in non_negative {
+            if !(v.is_finite() && v >= 0.0) {
+                return Err(format!("{name} must be finite and >= 0 (got {v})"));
+            }
+        }
+        let finite = [
+            ("nr_v", self.nr_v),
+            ("n_delta_r", self.n_delta_r),
+            ("n_beta", self.n_beta),
+            ("m_delta_s", self.m_delta_s),
+            ("k_delta_s", self.k_delta_s),
+            ("m_delta_b", self.m_delta_b),
+            ("n_ws", self.n_ws),
+            ("y_delta_r", self.y_delta_r),
+        ];
+        for (name, v) in finite {
+            if !v.is_finite() {
+                return Err(format!("{name} must be finite (got {v})"));
+            }
+        }
+        if !(0.01..=10.0).contains(&self.pump_rate_frac_per_s) {
+            return Err(format!(
+                "pump_rate_frac_per_s must be within [0.01, 10] (got {})",
+                self.pump_rate_frac_per_s
+            ));
+        }
+        if !self.cb_offset_body.is_finite() {
+            return Err("cb_offset_body must be finite".to_string());
+        }
+        let limit = self.dive_depth_limit;
+        if !(limit.warning_depth_m.is_finite()
+            && limit.max_depth_m.is_finite()
+            && (0.0..=limit.max_depth_m).contains(&limit.warning_depth_m))
+        {
+            return Err(format!(
+                "dive_depth_limit needs 0 <= warning_depth_m <= max_depth_m (got {} and {})",
+                limit.warning_depth_m, limit.max_depth_m
+            ));
+        }
+        for (i, tank) in self.ballast_tanks.iter().enumerate() {
+            if !tank.pos_body.is_finite()
+                || !(tank.capacity_kg.is_finite() && tank.capacity_kg >= 0.0)
+            {
+                return Err(format!(
+                    "ballast_tanks[{i}] has a non-finite position or negative capacity"
+                ));
+            }
+        }
+        for (i, prop) in self.propellers.iter().enumerate() {
+            if !prop.pos_body.is_finite()
+                || !(prop.thrust_direction_body.is_finite()
+                    && prop.thrust_direction_body.length_squared() > 1e-6)
+                || !(prop.thrust_share.is_finite() && prop.thrust_share >= 0.0)
+            {
+                return Err(format!("propellers[{i}] needs a finite position, a non-zero thrust direction and thrust_share >= 0"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BallastTankSpec {
     pub pos_body: Vec3f,
     pub capacity_kg: f32,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropellerSpec {
+    /// Thruster mount point relative to the hull origin in body frame (meters).
+    pub pos_body: Vec3f,
+    /// Unit thrust direction in body frame at positive input.
+    pub thrust_direction_body: Vec3f,
+    /// Fraction of `SubPhysicsSpec::t_max` produced by this thruster.
+    pub thrust_share: f32,
+}
+
+impl Default for PropellerSpec {
+    fn default() -> Self {
+        Self {
+            pos_body: Vec3f::new(0.0, 0.0, 0.0),
+            thrust_direction_body: Vec3f::new(0.0, 0.0, 1.0),
+            thrust_share: 1.0,
+        }
+    }
+}
+
+/// Fastest surge speed `SubPhysicsSpecBuilder::build` accepts; anything quicker is a typo.
+pub const MAX_TERMINAL_SPEED_M_S: f32 = 100.0;
+
+/// Which parameter `SubPhysicsSpecBuilder::build` rejected.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SpecValidationError {
+    #[error("mass must be finite and > 0 (got {0})")]
+    Mass(f32),
+    #[error("drag coefficient {name} must be finite and >= 0 (got {value})")]
+    Drag { name: &'static str, value: f32 },
+    #[error("inertia {name} must be finite and > 0 (got {value})")]
+    Inertia { name: &'static str, value: f32 },
+    #[error("ballast_tanks[{index}] capacity must be finite and >= 0 (got {capacity_kg})")]
+    BallastCapacity { index: usize, capacity_kg: f32 },
+    #[error("terminal speed must be finite and < {MAX_TERMINAL_SPEED_M_S} m/s (got {0})")]
+    TerminalSpeed(f32),
+    /// Anything else `SubPhysicsSpec::validate` rejects.
+    #[error("{0}")]
+    Other(String),
+}
+
+/// Fluent edits on top of a base spec, checked by `build`:
+///
+/// ```
+/// # use levels::{SubPhysicsSpecBuilder, Vec3f};
+/// let spec = SubPhysicsSpecBuilder::new()
+///     .mass(1500.0)
+///     .thrust_max(1800.0)
+///     .drag_forward(0.3, 0.8)
+///     .ballast_tank(Vec3f::new(0.0, 0.0, 1.0), 40.0)
+///     .ballast_tank(Vec3f::new(0.0, 0.0, -1.0), 40.0)
+///     .build()
+///     .unwrap();
+/// assert_eq!(spec.ballast_tanks.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SubPhysicsSpecBuilder {
+    spec: SubPhysicsSpec,
+    /// Tanks from `ballast_tank`; once any is added they replace the base spec's tanks.
+    ballast_tanks: Option<Vec<BallastTankSpec>>,
+}
+
+impl Default for SubPhysicsSpecBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubPhysicsSpecBuilder {
+    /// Start from `small_skiff_spec`.
+    pub fn new() -> Self {
+        Self::from_spec(subspecs::small_skiff_spec())
+    }
+
+    pub fn from_spec(spec: SubPhysicsSpec) -> Self {
+        Self {
+            spec,
+            ballast_tanks: None,
+        }
+    }
+
+    pub fn mass(mut self, kg: f32) -> Self {
+        self.spec.m = kg;
+        self
+    }
+
+    /// Full-input thrust of all propellers together (N).
+    pub fn thrust_max(mut self, n: f32) -> Self {
+        self.spec.t_max = n;
+        self
+    }
+
+    /// Quadratic surge drag coefficient and frontal area (m²).
+    pub fn drag_forward(mut self, cd: f32, area: f32) -> Self {
+        self.spec.cxd = cd;
+        self.spec.s_forward = area;
+        self
+    }
+
+    /// Quadratic sway drag coefficient and side area (m²).
+    pub fn drag_side(mut self, cd: f32, area: f32) -> Self {
+        self.spec.cyd = cd;
+        self.spec.s_side = area;
+        self
+    }
+
+    /// Quadratic heave drag coefficient and top area (m²).
+    pub fn drag_vertical(mut self, cd: f32, area: f32) -> Self {
+        self.spec.czd = cd;
+        self.spec.s_top = area;
+        self
+    }
+
+    /// Add a tank at `pos` in body space; the first call drops the base spec's tanks.
+    pub fn ballast_tank(mut self, pos: Vec3f, capacity_kg: f32) -> Self {
+        self.ballast_tanks
+            .get_or_insert_with(Vec::new)
+            .push(BallastTankSpec {
+                pos_body: pos,
+                capacity_kg,
+            });
+        self
+    }
+
+    pub fn pump_type(mut self, pump_type: PumpType) -> Self {
+        self.spec.pump_type = pump_type;
+        self
+    }
+
+    /// Moment of inertia about body +X (kg·m²).
+    pub fn inertia_pitch(mut self, ixx: f32) -> Self {
+        self.spec.ixx = ixx;
+        self
+    }
+
+    /// Moment of inertia about body +Y, the up axis (kg·m²).
+    pub fn inertia_yaw(mut self, iyy: f32) -> Self {
+        self.spec.iyy = iyy;
+        self
+    }
+
+    /// Moment of inertia about body +Z, the forward axis (kg·m²).
+    pub fn inertia_roll(mut self, izz: f32) -> Self {
+        self.spec.izz = izz;
+        self
+    }
+
+    /// Check the edited parameters, then everything `SubPhysicsSpec::validate` checks.
+    pub fn build(self) -> Result<SubPhysicsSpec, SpecValidationError> {
+        let mut spec = self.spec;
+        if let Some(tanks) = self.ballast_tanks {
+            spec.ballast_tanks = tanks;
+        }
+        if !(spec.m.is_finite() && spec.m > 0.0) {
+            return Err(SpecValidationError::Mass(spec.m));
+        }
+        let drag = [
+            ("cxd", spec.cxd),
+            ("cyd", spec.cyd),
+            ("czd", spec.czd),
+            ("xu", spec.xu),
+            ("yv", spec.yv),
+            ("zw", spec.zw),
+            ("kr", spec.kr),
+            ("kr2", spec.kr2),
+            ("kq", spec.kq),
+            ("kp", spec.kp),
+        ];
+        for (name, value) in drag {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(SpecValidationError::Drag { name, value });
+            }
+        }
+        for (name, value) in [("ixx", spec.ixx), ("iyy", spec.iyy), ("izz", spec.izz)] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(SpecValidationError::Inertia { name, value });
+            }
+        }
+        for (index, tank) in spec.ballast_tanks.iter().enumerate() {
+            if !(tank.capacity_kg.is_finite() && tank.capacity_kg >= 0.0) {
+                return Err(SpecValidationError::BallastCapacity {
+                    index,
+                    capacity_kg: tank.capacity_kg,
+                });
+            }
+        }
+        let terminal = spec.max_speed_m_s();
+        if !(terminal.is_finite() && terminal < MAX_TERMINAL_SPEED_M_S) {
+            return Err(SpecValidationError::TerminalSpeed(terminal));
+        }
+        spec.validate().map_err(SpecValidationError::Other)?;
+        Ok(spec)
+    }
+}
+
 pub mod subspecs {
     use super::*;
 
@@ -65,12 +586,16 @@ pub mod subspecs {
         let ixx = 0.5 * m * radius * radius; // roll
         let iyy = (1.0 / 12.0) * m * (3.0 * radius * radius + length * length); // pitch
         let izz = iyy; // yaw ~ pitch
+        let added = SubPhysicsSpec::slender_hull_added_inertia(m, length);
 
         SubPhysicsSpec {
             m,
             ixx,
             iyy,
             izz,
+            added_ixx: added.x,
+            added_iyy: added.y,
+            added_izz: added.z,
             // Quadratic drag coefficients (dimensionless, tuned)
             cxd: 0.35,
             cyd: 3.0,
@@ -89,10 +614,18 @@ pub mod subspecs {
             // Controls
             t_max: 1200.0, // N
             tau_thr: 2.5,  // s
+            thrust_tau_s: 0.15,
+            yaw_tau_s: 0.10,
+            pump_tau_s: 0.2,
+            pump_rate_frac_per_s: 0.2,
+            pump_type: PumpType::Standard,
             // Rudder effectiveness
             n_delta_r: 0.02,
             // Weathervane effectiveness
             n_beta: 0.015,
+            // Dive plane effectiveness: ~9° of steady pitch at full plane and 2 m/s
+            m_delta_s: 0.01,
+            k_delta_s: 0.005,
             m_delta_b: 1200.0,
             delta_r_max: 1.0,
             delta_b_max: 1.0,
@@ -112,9 +645,234 @@ pub mod subspecs {
                     capacity_kg: 30.0,
                 }, // aft
             ],
+            // Single stern propeller on the centerline, pushing forward
+            propellers: vec![PropellerSpec {
+                pos_body: Vec3f::new(0.0, 0.0, -0.5 * length),
+                ..Default::default()
+            }],
             n_ws: 0.16,
             y_delta_r: 0.0,
             cb_offset_body: Vec3f::new(0.0, 0.12, 0.0),
+            sonar_self_noise_threshold_m_s: default_sonar_self_noise_threshold_m_s(),
+            acoustic_noise_db: default_acoustic_noise_db(),
+            torpedo_tubes: default_torpedo_tubes(),
+            torpedo_reload_s: default_torpedo_reload_s(),
+            dive_depth_limit: DiveDepthLimit::default(),
+        }
+    }
+
+    /// Bigger hull with four tanks and twin screws, so per-tank and per-propeller loops cost
+    /// more than on the skiff. Only the physics benchmarks use it; no such hull ships yet.
+    pub fn large_cruiser_spec() -> SubPhysicsSpec {
+        let skiff = small_skiff_spec();
+        let (length, diameter) = (12.0_f32, 3.0_f32);
+        let radius = diameter * 0.5;
+        let m = 40_000.0;
+        let iyy = (1.0 / 12.0) * m * (3.0 * radius * radius + length * length);
+        let added = SubPhysicsSpec::slender_hull_added_inertia(m, length);
+        let tank = |x: f32| BallastTankSpec {
+            pos_body: Vec3f::new(x, 0.0, 0.0),
+            capacity_kg: 1000.0,
+        };
+        let screw = |x: f32| PropellerSpec {
+            pos_body: Vec3f::new(x, 0.0, -0.5 * length),
+            thrust_share: 0.5,
+            ..Default::default()
+        };
+        SubPhysicsSpec {
+            m,
+            ixx: 0.5 * m * radius * radius,
+            iyy,
+            izz: iyy,
+            added_ixx: added.x,
+            added_iyy: added.y,
+            added_izz: added.z,
+            t_max: 40_000.0,
+            length,
+            diameter,
+            s_forward: std::f32::consts::PI * radius * radius,
+            s_side: length * diameter,
+            s_top: length * diameter,
+            volume_m3: std::f32::consts::PI * radius * radius * length,
+            ballast_tanks: vec![tank(4.0), tank(1.5), tank(-1.5), tank(-4.0)],
+            propellers: vec![screw(1.0), screw(-1.0)],
+            ..skiff
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::subspecs::{large_cruiser_spec, small_skiff_spec};
+    use super::*;
+
+    #[test]
+    fn spec_roundtrips_through_ron_and_validates() {
+        let spec = small_skiff_spec();
+        let parsed = SubPhysicsSpec::from_ron_str(&spec.to_ron_str()).unwrap();
+        assert_eq!(parsed, spec);
+        parsed.validate().unwrap();
+    }
+
+    #[test]
+    fn large_cruiser_spec_validates() {
+        let spec = large_cruiser_spec();
+        spec.validate().unwrap();
+        assert_eq!(spec.ballast_tanks.len(), 4);
+        assert_eq!(spec.propellers.len(), 2);
+    }
+
+    #[test]
+    fn spec_file_roundtrips_and_rejects_invalid_specs() {
+        let dir = std::env::temp_dir().join(format!("thalasso-spec-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("skiff.ron");
+        small_skiff_spec().to_ron_file(&path).unwrap();
+        assert_eq!(
+            SubPhysicsSpec::from_ron_file(&path).unwrap(),
+            small_skiff_spec()
+        );
+
+        let broken = SubPhysicsSpec {
+            m: -1.0,
+            ..small_skiff_spec()
+        };
+        broken.to_ron_file(&path).unwrap();
+        let err = SubPhysicsSpec::from_ron_file(&path).unwrap_err();
+        assert!(matches!(err, SpecLoadError::Invalid(_)), "{err}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn noise_masking_range_scales_with_speed_above_threshold() {
+        let spec = small_skiff_spec();
+        let threshold = spec.sonar_self_noise_threshold_m_s;
+        assert_eq!(spec.noise_masking_range(0.0), 0.0);
+        let expected = threshold * threshold / spec.max_speed_m_s() * 50.0;
+        assert!((spec.noise_masking_range(threshold) - expected).abs() < 1e-4);
+        assert!(spec.noise_masking_range(spec.max_speed_m_s()) > expected);
+    }
+
+    #[test]
+    fn validate_rejects_non_positive_mass() {
+        let spec = SubPhysicsSpec {
+            m: 0.0,
+            ..small_skiff_spec()
+        };
+        let err = spec.validate().unwrap_err();
+        assert!(err.starts_with("m "), "{err}");
+    }
+
+    #[test]
+    fn builder_edits_a_base_spec_and_names_the_bad_parameter() {
+        let spec = SubPhysicsSpecBuilder::new()
+            .mass(1500.0)
+            .inertia_yaw(900.0)
+            .ballast_tank(Vec3f::new(0.0, 0.0, 1.2), 45.0)
+            .build()
+            .unwrap();
+        assert_eq!((spec.m, spec.iyy), (1500.0, 900.0));
+        assert_eq!(spec.ballast_tanks.len(), 1);
+        assert_eq!(spec.ixx, small_skiff_spec().ixx);
+
+        let err = |b: SubPhysicsSpecBuilder| b.build().unwrap_err();
+        assert_eq!(
+            err(SubPhysicsSpecBuilder::new().mass(0.0)),
+            SpecValidationError::Mass(0.0)
+        );
+        assert_eq!(
+            err(SubPhysicsSpecBuilder::new().drag_forward(-0.1, 0.8)),
+            SpecValidationError::Drag {
+                name: "cxd",
+                value: -0.1
+            }
+        );
+        assert_eq!(
+            err(SubPhysicsSpecBuilder::new().inertia_roll(f32::NAN)).to_string(),
+            "inertia izz must be finite and > 0 (got NaN)"
+        );
+        assert_eq!(
+            err(SubPhysicsSpecBuilder::new().ballast_tank(Vec3f::ZERO, -5.0)),
+            SpecValidationError::BallastCapacity {
+                index: 0,
+                capacity_kg: -5.0
+            }
+        );
+        // Torpedo-grade thrust on the skiff's hull
+        assert!(matches!(
+            err(SubPhysicsSpecBuilder::new().thrust_max(1.0e7)),
+            SpecValidationError::TerminalSpeed(v) if v > MAX_TERMINAL_SPEED_M_S
+        ));
+        assert!(matches!(
+            err(SubPhysicsSpecBuilder::new().drag_forward(0.35, 0.0)),
+            SpecValidationError::Other(msg) if msg.starts_with("s_forward")
+        ));
+    }
+
+    #[test]
+    fn dive_depth_limit_bands_and_validation() {
+        let limit = DiveDepthLimit {
+            max_depth_m: 50.0,
+            warning_depth_m: 40.0,
+        };
+        assert_eq!(limit.classify(-3.0), None);
+        assert_eq!(limit.classify(40.0), None);
+        assert_eq!(
+            limit.classify(45.0),
+            Some(DiveDepthEvent::Warning { depth_m: 45.0 })
+        );
+        assert_eq!(
+            limit.classify(51.0),
+            Some(DiveDepthEvent::Exceeded { depth_m: 51.0 })
+        );
+
+        let spec = SubPhysicsSpec {
+            dive_depth_limit: DiveDepthLimit {
+                max_depth_m: 30.0,
+                warning_depth_m: 40.0,
+            },
+            ..small_skiff_spec()
+        };
+        let err = spec.validate().unwrap_err();
+        assert!(err.starts_with("dive_depth_limit"), "{err}");
+    }
+
+    #[test]
+    fn pump_rate_sets_fill_speed_and_is_range_checked() {
+        for rate in [0.0, 0.005, 10.5, f32::NAN] {
+            let spec = SubPhysicsSpec {
+                pump_rate_frac_per_s: rate,
+                ..small_skiff_spec()
+            };
+            let err = spec.validate().unwrap_err();
+            assert!(err.starts_with("pump_rate_frac_per_s"), "{err}");
         }
+
+        let level = crate::builtins::greybox_level();
+        let fill_after_1s = |rate: f32| {
+            let spec = SubPhysicsSpec {
+                pump_rate_frac_per_s: rate,
+                ..small_skiff_spec()
+            };
+            spec.validate().unwrap();
+            let mut state = crate::SubState {
+                position: level.tunnel.pos,
+                velocity: Vec3f::ZERO,
+                orientation: crate::Quatf::IDENTITY,
+                ang_mom: Vec3f::ZERO,
+                ballast_fill: vec![0.0; spec.ballast_tanks.len()],
+                pump: Default::default(),
+            };
+            let inputs = crate::SubInputState {
+                pump_fwd: 1.0,
+                ..Default::default()
+            };
+            for _ in 0..10 {
+                crate::step_submarine(&level, &spec, inputs, &mut state, 0.1, 0.0);
+            }
+            state.ballast_fill[0]
+        };
+        assert!((fill_after_1s(0.05) - 0.05).abs() < 1e-4);
+        assert!((fill_after_1s(0.8) - 0.8).abs() < 1e-4);
     }
 }
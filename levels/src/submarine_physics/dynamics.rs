@@ -1,10 +1,10 @@
 use super::flow::sample_flow_at;
 use super::terms::*;
-use super::types::{SubInputState, SubState, SubStepDebug};
+use super::types::{DiveDepthEvent, SubInputState, SubState, SubStepDebug};
 use super::util::{
     quat_rotate_vec3, quat_to_yaw, vadd, vscale, vsub, BODY_FWD, BODY_RIGHT, BODY_UP,
 };
-use crate::{LevelSpec, Quatf, SubPhysicsSpec, Vec3f};
+use crate::{LevelSpec, PumpType, Quatf, SubPhysicsSpec, Vec3f};
 
 /// Simple submarine dynamics step honoring thrust and rudder in a flow field.
 /// See `step_submarine_dbg` for full details and telemetry.
@@ -15,11 +15,14 @@ pub fn step_submarine(
     state: &mut SubState,
     dt: f32,
     time: f32,
-) {
-    step_submarine_dbg(level, spec, inputs, state, dt, time, None);
+) -> Option<DiveDepthEvent> {
+    step_submarine_dbg(level, spec, inputs, state, dt, time, None)
 }
 
 /// Variant of `step_submarine` that fills out an optional debug telemetry struct.
+///
+/// Returns the sub's `DiveDepthLimit` state at the start of the step. Past `max_depth_m` the
+/// step overrides the pumps with an emergency blow.
 pub fn step_submarine_dbg(
     level: &LevelSpec,
     spec: &SubPhysicsSpec,
@@ -28,14 +31,20 @@ pub fn step_submarine_dbg(
     dt: f32,
     time: f32,
     mut dbg: Option<&mut SubStepDebug>,
-) {
+) -> Option<DiveDepthEvent> {
     if dt <= 0.0 {
-        return;
+        return None;
     }
+    let depth_event = spec.dive_depth_limit.classify(state.depth_m());
 
     let (flow, _variance) = sample_flow_at(level, state.position, time);
     // Integrate ballast pumps and compute effective mass + buoyancy.
-    let pump_rate_per_s = 0.2_f32;
+    let mut pump_rate_per_s = spec.pump_rate_frac_per_s;
+    if spec.pump_type == PumpType::Turbo {
+        // Ramped pump commands only decay toward zero, so treat a near-idle pump as off
+        let pumping = inputs.pump_fwd.abs() > 0.05 || inputs.pump_aft.abs() > 0.05;
+        pump_rate_per_s *= state.pump.step(pumping, dt);
+    }
     if state.ballast_fill.len() >= 2 {
         state.ballast_fill[0] = (state.ballast_fill[0]
             + inputs.pump_fwd.clamp(-1.0, 1.0) * pump_rate_per_s * dt)
@@ -44,6 +53,11 @@ pub fn step_submarine_dbg(
             + inputs.pump_aft.clamp(-1.0, 1.0) * pump_rate_per_s * dt)
             .clamp(0.0, 1.0);
     }
+    if let Some(DiveDepthEvent::Exceeded { .. }) = depth_event {
+        // Emergency blow: empty every tank and stop the descent
+        state.ballast_fill.fill(0.0);
+        state.velocity.y = state.velocity.y.max(0.0);
+    }
     let mut ballast_mass = 0.0_f32;
     let mut total_capacity = 0.0_f32;
     for (i, tank) in spec.ballast_tanks.iter().enumerate() {
@@ -60,14 +74,12 @@ pub fn step_submarine_dbg(
     let weight = m_eff * g; // N downward (increases with ballast)
     let buoy_net = buoyancy - weight; // + up
 
-    // Body axes in world (standard basis: +Z fwd, +X right, +Y up)
+    // Body axes in world (standard basis: +Z fwd, +X port, +Y up)
     let forward = quat_rotate_vec3(state.orientation, BODY_FWD);
     let right = quat_rotate_vec3(state.orientation, BODY_RIGHT);
     let up_b = quat_rotate_vec3(state.orientation, BODY_UP);
 
-    // Thrust force along forward
     let thrust_force = spec.t_max * inputs.thrust.clamp(-1.0, 1.0);
-    let a_thrust = vscale(forward, thrust_force / m_eff);
 
     // Yaw dynamics
     let rel = vsub(state.velocity, flow); // water-relative velocity (world)
@@ -78,6 +90,8 @@ pub fn step_submarine_dbg(
     let front_mount_gain = if u_rel < 0.0 { 2.0 } else { 1.0 };
     let yaw_in = inputs.yaw.clamp(-1.0, 1.0);
     let tau_control = torque_yaw_control(spec, yaw_in, sign_u, front_mount_gain, q);
+    let tau_plane = torque_pitch_control(spec, inputs.plane.clamp(-1.0, 1.0), sign_u, q);
+    let tau_roll_trim = torque_roll_control(spec, inputs.roll_trim.clamp(-1.0, 1.0), sign_u, q);
 
     debug_assert!(
         q >= 0.0 && spec.n_delta_r >= 0.0 && spec.s_side >= 0.0 && spec.length >= 0.0,
@@ -96,10 +110,24 @@ pub fn step_submarine_dbg(
         }
     }
 
-    // Derive body angular velocity from stored body angular momentum
-    let inv_ixx = if spec.ixx > 0.0 { 1.0 / spec.ixx } else { 0.0 };
-    let inv_iyy = if spec.iyy > 0.0 { 1.0 / spec.iyy } else { 0.0 };
-    let inv_izz = if spec.izz > 0.0 { 1.0 / spec.izz } else { 0.0 };
+    // Derive body angular velocity from stored body angular momentum, including the
+    // inertia of the water the hull drags along
+    let inertia = spec.rotational_inertia();
+    let inv_ixx = if inertia.x > 0.0 {
+        1.0 / inertia.x
+    } else {
+        0.0
+    };
+    let inv_iyy = if inertia.y > 0.0 {
+        1.0 / inertia.y
+    } else {
+        0.0
+    };
+    let inv_izz = if inertia.z > 0.0 {
+        1.0 / inertia.z
+    } else {
+        0.0
+    };
     let mut omega_body = Vec3f::new(
         state.ang_mom.x * inv_ixx,
         state.ang_mom.y * inv_iyy,
@@ -143,9 +171,16 @@ pub fn step_submarine_dbg(
 
     let (cg_body_current, _m) = compute_cg_body_current(spec, state);
 
+    // Propeller thrust: summed force in body frame, rotated to world; moments r × F about the CG
+    let (thrust_body, tau_thrust) =
+        propeller_force_and_moment_body(spec, inputs.thrust.clamp(-1.0, 1.0), cg_body_current);
+    let thrust_world = quat_rotate_vec3(state.orientation, thrust_body);
+    let a_thrust = vscale(thrust_world, 1.0 / m_eff);
+    tau_b = vadd(tau_b, tau_thrust);
+
     // Pitch and roll torque due to ballast distribution and COB offset
     let g = 9.81_f32;
-    let mut tau_pitch = torque_from_ballast_gravity_about_axis(
+    let tau_pitch_ballast = torque_from_ballast_gravity_about_axis(
         spec,
         state,
         cg_body_current,
@@ -153,7 +188,7 @@ pub fn step_submarine_dbg(
         right,
         g,
     );
-    let mut tau_roll = torque_from_ballast_gravity_about_axis(
+    let tau_roll_ballast = torque_from_ballast_gravity_about_axis(
         spec,
         state,
         cg_body_current,
@@ -161,18 +196,23 @@ pub fn step_submarine_dbg(
         forward,
         g,
     );
-    tau_pitch += torque_from_cob_buoyancy_about_axis(spec, state.orientation, right, buoyancy);
-    tau_roll += torque_from_cob_buoyancy_about_axis(spec, state.orientation, forward, buoyancy);
+    let tau_pitch_cob =
+        torque_from_cob_buoyancy_about_axis(spec, state.orientation, right, buoyancy);
+    let tau_roll_cob =
+        torque_from_cob_buoyancy_about_axis(spec, state.orientation, forward, buoyancy);
+    let tau_pitch = tau_pitch_ballast + tau_pitch_cob + tau_plane;
+    let tau_roll = tau_roll_ballast + tau_roll_cob + tau_roll_trim;
 
     // Linear pitch damping uses current omega.x
     let q_pitch = omega_body.x;
     let tau_pitch_damp = torque_pitch_linear_damping(spec, q_pitch);
     let tau_pitch_total = tau_pitch + tau_pitch_damp;
     // Add pitch and roll torque components and integrate full L with gyroscopic coupling
-    tau_b.x = tau_pitch_total;
+    tau_b.x += tau_pitch_total;
     // Tiny linear roll damping (no clamp): τ_roll += -kp * ωz
     let tau_roll_damp = torque_roll_linear_damping(spec, omega_body.z);
-    tau_b.z = tau_roll + tau_roll_damp;
+    let tau_roll_total = tau_roll + tau_roll_damp;
+    tau_b.z += tau_roll_total;
     // Ldot = tau_b - omega × L
     let cross = Vec3f::new(
         omega_body.y * l.z - omega_body.z * l.y,
@@ -185,8 +225,8 @@ pub fn step_submarine_dbg(
     // Clamp pitch and yaw rates by limiting momentum
     let q_max = 0.5; // ~29 deg/s
     let r_max = 0.6; // ~34 deg/s
-    let l_x_max = spec.ixx * q_max;
-    let l_y_max = spec.iyy * r_max;
+    let l_x_max = inertia.x * q_max;
+    let l_y_max = inertia.y * r_max;
     if state.ang_mom.x > l_x_max {
         state.ang_mom.x = l_x_max;
     }
@@ -207,7 +247,7 @@ pub fn step_submarine_dbg(
         state.ang_mom.z * inv_izz,
     );
     // Debug yaw acceleration from Euler equation: omega_dot_y = Ldot_y / Iyy
-    let yaw_acc = if spec.iyy > 0.0 {
+    let yaw_acc = if inertia.y > 0.0 {
         ldot.y * inv_iyy
     } else {
         0.0
@@ -220,9 +260,9 @@ pub fn step_submarine_dbg(
     state.orientation = (state.orientation * delta_pitch * delta_yaw * delta_roll).normalize();
 
     // Rudder sideforce tied to yaw rate: approximate centripetal acceleration ~ u * r
-    // Positive yaw rate (left turn) should create acceleration to the left (−right axis)
+    // Positive yaw rate swings the nose towards body +X, so the centre of curvature lies along +X
     // Ideal centripetal acceleration to track yaw rate: a_c = v * r toward the center of curvature
-    let a_rudder = vscale(right, -u_rel * r);
+    let a_rudder = vscale(right, u_rel * r);
 
     // Hydrodynamic drag in body axes
     let u = rel.x * forward.x + rel.y * forward.y + rel.z * forward.z;
@@ -264,6 +304,8 @@ pub fn step_submarine_dbg(
         d.sign_u = sign_u;
         d.front_mount_gain = front_mount_gain;
         d.thrust_force = thrust_force;
+        d.thrust_world = thrust_world;
+        d.tau_thrust = tau_thrust;
         d.fx = fx;
         d.fy = fy;
         d.fz = fz;
@@ -287,8 +329,28 @@ pub fn step_submarine_dbg(
         d.weight_n = weight;
         d.buoy_net_n = buoy_net;
         d.tau_pitch = tau_pitch;
+        d.tau_pitch_ballast = tau_pitch_ballast;
+        d.tau_pitch_cob = tau_pitch_cob;
+        d.tau_pitch_damp = tau_pitch_damp;
+        d.tau_pitch_total = tau_pitch_total;
+        d.tau_plane = tau_plane;
+        d.tau_roll_ballast = tau_roll_ballast;
+        d.tau_roll_cob = tau_roll_cob;
+        d.tau_roll_damp = tau_roll_damp;
+        d.tau_roll_total = tau_roll_total;
+        d.tau_roll_trim = tau_roll_trim;
+        d.a_buoy = a_buoy;
+        d.a_drag_world = a_drag;
+        d.a_thrust_world = a_thrust;
+        d.a_centripetal = a_rudder;
+        d.a_net = a;
         d.up_b = up_b;
+        d.noise_floor = 20.0 * (1.0 + state.velocity.length()).log10();
+        d.acoustic_level_db = spec.acoustic_level_db(inputs.thrust, omega_body.y);
+        d.depth_event = depth_event;
+        d.pump_turbo = (spec.pump_type == PumpType::Turbo).then_some(state.pump);
     }
+    depth_event
 }
 
 fn compute_cg_body_current(spec: &SubPhysicsSpec, state: &SubState) -> (Vec3f, f32) {
@@ -324,6 +386,7 @@ fn compute_cg_body_current(spec: &SubPhysicsSpec, state: &SubState) -> (Vec3f, f
 mod tests {
     use super::*;
     use crate::BallastTankSpec;
+    use crate::PumpCooldownState;
 
     fn spec_with_two_tanks() -> SubPhysicsSpec {
         let mut s = crate::subspecs::small_skiff_spec();
@@ -349,6 +412,7 @@ mod tests {
             orientation: Quatf::from_rotation_y(0.0),
             ang_mom: Vec3f::new(0.0, 0.0, 0.0),
             ballast_fill: vec![0.0, 0.0],
+            pump: Default::default(),
         }
     }
 
@@ -381,4 +445,264 @@ mod tests {
         assert!((m_total - (spec.m + 40.0)).abs() < 1e-6);
         assert!(cg.length() < 1e-6, "cg should be at origin when symmetric");
     }
+
+    #[test]
+    fn sideways_thruster_gives_no_forward_acceleration() {
+        let level = crate::builtins::greybox_level();
+        let mut spec = crate::subspecs::small_skiff_spec();
+        // Rotate the single thruster 90° about +Y so it pushes along body +X, and mount it at the CG.
+        spec.propellers[0].thrust_direction_body =
+            Quatf::from_rotation_y(std::f32::consts::FRAC_PI_2) * Vec3f::new(0.0, 0.0, 1.0);
+        spec.propellers[0].pos_body = Vec3f::new(0.0, 0.0, 0.0);
+        let mut state = base_state();
+        state.ballast_fill = vec![0.5; spec.ballast_tanks.len()];
+        let inputs = SubInputState {
+            thrust: 1.0,
+            ..Default::default()
+        };
+        let dt = 0.01;
+        let mut dbg = SubStepDebug::default();
+        step_submarine_dbg(&level, &spec, inputs, &mut state, dt, 0.0, Some(&mut dbg));
+
+        let m_eff = dbg.mass_eff;
+        let a = state.velocity / dt;
+        assert!(
+            a.z.abs() < 1e-4,
+            "forward acceleration should vanish: {a:?}"
+        );
+        assert!(
+            (a.x - spec.t_max / m_eff).abs() < 1e-3,
+            "full thrust should go sideways: {a:?}"
+        );
+        assert!(dbg.tau_thrust.length() < 1e-3);
+    }
+
+    #[test]
+    fn debug_breakdown_sums_to_totals() {
+        let level = crate::builtins::greybox_level();
+        let spec = crate::subspecs::small_skiff_spec();
+        let mut state = base_state();
+        state.ballast_fill = vec![0.8, 0.2];
+        state.orientation = Quatf::from_rotation_x(0.2) * Quatf::from_rotation_z(0.1);
+        state.velocity = Vec3f::new(0.5, 0.0, 1.5);
+        let inputs = SubInputState {
+            thrust: 0.6,
+            yaw: 0.3,
+            plane: 0.5,
+            roll_trim: -0.4,
+            ..Default::default()
+        };
+        let mut dbg = SubStepDebug::default();
+        step_submarine_dbg(&level, &spec, inputs, &mut state, 0.01, 0.0, Some(&mut dbg));
+
+        assert!(
+            (dbg.tau_pitch_ballast + dbg.tau_pitch_cob + dbg.tau_plane + dbg.tau_pitch_damp
+                - dbg.tau_pitch_total)
+                .abs()
+                < 1e-3
+        );
+        assert!(
+            (dbg.tau_roll_ballast + dbg.tau_roll_cob + dbg.tau_roll_trim + dbg.tau_roll_damp
+                - dbg.tau_roll_total)
+                .abs()
+                < 1e-3
+        );
+        let sum = dbg.a_buoy + dbg.a_drag_world + dbg.a_thrust_world + dbg.a_centripetal;
+        assert!(
+            (sum - dbg.a_net).length() < 1e-4,
+            "{sum:?} vs {:?}",
+            dbg.a_net
+        );
+    }
+
+    #[test]
+    fn pumps_ramp_with_pump_tau() {
+        let mut spec = crate::subspecs::small_skiff_spec();
+        let target = crate::SubInputs {
+            pump_fwd: 1.0,
+            pump_aft: -1.0,
+            ..Default::default()
+        };
+        let mut state = SubInputState::default();
+        state.ramp_toward(target, spec.pump_tau_s, &spec);
+        let expected = 1.0 - (-1.0f32).exp();
+        assert!((state.pump_fwd - expected).abs() < 1e-5);
+        assert!((state.pump_aft + expected).abs() < 1e-5);
+
+        spec.pump_tau_s = 0.0;
+        state.ramp_toward(target, 0.01, &spec);
+        assert_eq!((state.pump_fwd, state.pump_aft), (1.0, -1.0));
+    }
+
+    #[test]
+    fn turbo_pumps_boost_then_cool_down_at_the_standard_rate() {
+        let level = crate::builtins::greybox_level();
+        let spec = SubPhysicsSpec {
+            pump_type: PumpType::Turbo,
+            pump_rate_frac_per_s: 0.02,
+            ..crate::subspecs::small_skiff_spec()
+        };
+        let mut state = base_state();
+        let inputs = SubInputState {
+            pump_fwd: 1.0,
+            ..Default::default()
+        };
+        // Quarter-second steps count the budget down exactly
+        let dt = 0.25;
+        let run = |state: &mut SubState, seconds: f32| {
+            let mut dbg = SubStepDebug::default();
+            for _ in 0..(seconds / dt) as usize {
+                step_submarine_dbg(&level, &spec, inputs, state, dt, 0.0, Some(&mut dbg));
+            }
+            dbg.pump_turbo
+        };
+
+        let pump = run(&mut state, 5.0).unwrap();
+        assert!((state.ballast_fill[0] - 0.3).abs() < 1e-4);
+        assert!(pump.is_cooling_down());
+        assert_eq!(pump.cooldown_remaining_s, crate::TURBO_PUMP_COOLDOWN_S);
+
+        let pump = run(&mut state, 15.0).unwrap();
+        assert!((state.ballast_fill[0] - 0.6).abs() < 1e-4);
+        assert_eq!(pump, PumpCooldownState::default());
+
+        run(&mut state, 1.0);
+        assert!((state.ballast_fill[0] - 0.66).abs() < 1e-4);
+
+        // Standard pumps never boost and report no budget
+        let mut state = base_state();
+        let spec = SubPhysicsSpec {
+            pump_type: PumpType::Standard,
+            ..spec
+        };
+        let mut dbg = SubStepDebug::default();
+        step_submarine_dbg(&level, &spec, inputs, &mut state, dt, 0.0, Some(&mut dbg));
+        assert!((state.ballast_fill[0] - 0.005).abs() < 1e-6);
+        assert_eq!(dbg.pump_turbo, None);
+    }
+
+    #[test]
+    fn exceeding_max_depth_blows_ballast_and_stops_the_dive() {
+        let level = crate::builtins::greybox_level();
+        let mut spec = crate::subspecs::small_skiff_spec();
+        spec.dive_depth_limit = crate::DiveDepthLimit {
+            max_depth_m: 20.0,
+            warning_depth_m: 10.0,
+        };
+        let mut state = base_state();
+        state.position.y = -5.0;
+        state.ballast_fill = vec![1.0, 1.0];
+        // Keep flooding the tanks all the way down
+        let inputs = SubInputState {
+            pump_fwd: 1.0,
+            pump_aft: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(spec.dive_depth_limit.classify(state.depth_m()), None);
+
+        let mut deepest = 0.0_f32;
+        let mut kinds = Vec::new();
+        for _ in 0..20_000 {
+            let event = step_submarine(&level, &spec, inputs, &mut state, 0.01, 0.0);
+            if let Some(DiveDepthEvent::Exceeded { .. }) = event {
+                assert_eq!(state.ballast_fill, vec![0.0, 0.0]);
+                assert!(
+                    state.velocity.y >= 0.0,
+                    "still sinking: {:?}",
+                    state.velocity
+                );
+            }
+            let kind = match event {
+                None => "clear",
+                Some(DiveDepthEvent::Warning { .. }) => "warning",
+                Some(DiveDepthEvent::Exceeded { .. }) => "exceeded",
+            };
+            if kinds.last() != Some(&kind) {
+                kinds.push(kind);
+            }
+            deepest = deepest.max(state.depth_m());
+        }
+        assert_eq!(&kinds[..3], ["clear", "warning", "exceeded"]);
+        // Blown back up into the warning band; the pumps can't drag it much past the limit
+        assert_eq!(kinds[3], "warning");
+        assert!(deepest < 20.5, "sank to {deepest} m");
+    }
+
+    #[test]
+    fn added_yaw_inertia_reduces_yaw_acceleration() {
+        let level = crate::builtins::greybox_level();
+        let yaw_acc = |added_iyy: f32| {
+            let spec = SubPhysicsSpec {
+                added_iyy,
+                ..crate::subspecs::small_skiff_spec()
+            };
+            let mut state = base_state();
+            state.velocity = Vec3f::new(0.0, 0.0, 1.5);
+            state.ballast_fill = vec![0.5, 0.5];
+            let inputs = SubInputState {
+                yaw: 1.0,
+                ..Default::default()
+            };
+            let mut dbg = SubStepDebug::default();
+            step_submarine_dbg(
+                &level,
+                &spec,
+                inputs,
+                &mut state,
+                1.0 / 60.0,
+                0.0,
+                Some(&mut dbg),
+            );
+            dbg.yaw_acc
+        };
+        let bare = yaw_acc(0.0);
+        let loaded = yaw_acc(2000.0);
+        assert!(bare.abs() > 1e-3, "rudder must produce a yaw acceleration");
+        assert_eq!(bare.signum(), loaded.signum());
+        assert!(
+            loaded.abs() < bare.abs(),
+            "bare {bare}, with added inertia {loaded}"
+        );
+    }
+
+    #[test]
+    fn dive_planes_pitch_nose_down_and_roll_trim_rolls_starboard() {
+        let level = crate::builtins::greybox_level();
+        let spec = crate::subspecs::small_skiff_spec();
+        let step = |speed: f32, plane: f32, roll_trim: f32| {
+            let mut state = base_state();
+            state.velocity = Vec3f::new(0.0, 0.0, speed);
+            state.ballast_fill = vec![0.5, 0.5];
+            let inputs = SubInputState {
+                plane,
+                roll_trim,
+                ..Default::default()
+            };
+            let mut dbg = SubStepDebug::default();
+            step_submarine_dbg(
+                &level,
+                &spec,
+                inputs,
+                &mut state,
+                1.0 / 60.0,
+                0.0,
+                Some(&mut dbg),
+            );
+            (state.ang_mom, dbg)
+        };
+        let (neutral, _) = step(2.0, 0.0, 0.0);
+        let (dive, dbg) = step(2.0, 1.0, 1.0);
+        assert!(dbg.tau_plane > 0.0, "dive torque {}", dbg.tau_plane);
+        assert!(
+            dbg.tau_roll_trim > 0.0,
+            "roll trim torque {}",
+            dbg.tau_roll_trim
+        );
+        assert!(dive.x > neutral.x, "dive planes must pitch the nose down");
+        assert!(dive.z > neutral.z, "roll trim must roll to starboard");
+
+        // Like the rudder, the surfaces act the other way when going astern
+        let (_, astern) = step(-2.0, 1.0, 1.0);
+        assert!(astern.tau_plane < 0.0 && astern.tau_roll_trim < 0.0);
+    }
 }
@@ -1,38 +1,94 @@
 use super::util::{vadd, vscale, vsub};
-use crate::{FlowFieldSpec, LevelSpec, Vec3f};
+use crate::{aabb_contains, FlowFieldSpec, LevelSpec, Vec3f};
+
+/// Ornstein-Uhlenbeck kicks summed per turbulence sample; older ones have decayed below e^-8.
+const TURBULENCE_MEMORY_STEPS: i32 = 8;
+/// Mean speed the correlation time is computed from at or below this, so near-still water
+/// still stirs instead of freezing its eddies.
+const MIN_TURBULENCE_SPEED: f32 = 0.1;
+
+/// Flow and variance of one volume's field at `time`.
+fn sample_field(field: &FlowFieldSpec, time: f32) -> (Vec3f, f32) {
+    match *field {
+        FlowFieldSpec::Uniform { flow, variance } => (flow, variance),
+        FlowFieldSpec::TurbulentUniform {
+            mean_flow,
+            intensity,
+            correlation_length,
+            seed,
+        } => (
+            mean_flow
+                + turbulent_perturbation(mean_flow, intensity, correlation_length, seed, time),
+            intensity * intensity,
+        ),
+    }
+}
+
+/// Ornstein-Uhlenbeck perturbation of a `TurbulentUniform` field at `time`.
+///
+/// The process is stepped once per correlation time `T`:
+/// `p_k = p_(k-1) * exp(-1) + intensity * sqrt(1 - exp(-2)) * n_k`, with each kick `n_k`
+/// hashed from `(seed, k)`. Unrolling it lets any caller evaluate `p` at any time without
+/// carrying state, so prediction, replay and the server agree; between steps `p` is
+/// interpolated linearly so the flow has no jumps.
+pub fn turbulent_perturbation(
+    mean_flow: Vec3f,
+    intensity: f32,
+    correlation_length: f32,
+    seed: u64,
+    time: f32,
+) -> Vec3f {
+    if intensity <= 0.0 || correlation_length <= 0.0 {
+        return Vec3f::ZERO;
+    }
+    let t_corr = correlation_length / mean_flow.length().max(MIN_TURBULENCE_SPEED);
+    let steps = time / t_corr;
+    let k = steps.floor();
+    let frac = steps - k;
+    let k = k as i64;
+
+    let decay = (-1.0f32).exp();
+    let kick = intensity * (1.0 - decay * decay).sqrt();
+    let at = |k: i64| {
+        (0..TURBULENCE_MEMORY_STEPS).fold(Vec3f::ZERO, |p, j| {
+            p + turbulence_kick(seed, k - j as i64) * decay.powi(j)
+        }) * kick
+    };
+    at(k).lerp(at(k + 1), frac)
+}
+
+/// Zero-mean, unit-variance noise per axis for step `k`: uniform in [-sqrt(3), sqrt(3)].
+fn turbulence_kick(seed: u64, k: i64) -> Vec3f {
+    let mut h = seed ^ (k as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    let mut unit = || {
+        h = splitmix64(h);
+        // Top 24 bits to [0, 1)
+        (h >> 40) as f32 / (1u64 << 24) as f32
+    };
+    let half_width = 3.0f32.sqrt();
+    Vec3f::new(unit(), unit(), unit()) * (2.0 * half_width) - Vec3f::splat(half_width)
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
 
 /// Sample the flow field and variance at a world position.
-/// Currently only the tunnel contributes; extend later for multiple fields.
+/// Tunnel and torus flows are averaged where they overlap; thermal vent
+/// upwelling is added on top.
 pub fn sample_flow_at(level: &LevelSpec, pos: Vec3f, time: f32) -> (Vec3f, f32) {
     let mut flow = Vec3f::new(0.0, 0.0, 0.0);
     let mut variance = 0.0f32;
     let mut count = 0.0f32;
 
-    // Tunnel AABB check
-    let half = Vec3f::new(
-        level.tunnel.size.x * 0.5,
-        level.tunnel.size.y * 0.5,
-        level.tunnel.size.z * 0.5,
-    );
-    let min = vsub(level.tunnel.pos, half);
-    let max = vadd(level.tunnel.pos, half);
-    if pos.x >= min.x
-        && pos.x <= max.x
-        && pos.y >= min.y
-        && pos.y <= max.y
-        && pos.z >= min.z
-        && pos.z <= max.z
-    {
-        match level.tunnel.flow {
-            FlowFieldSpec::Uniform {
-                flow: f,
-                variance: var,
-            } => {
-                flow = vadd(flow, f);
-                variance += var;
-                count += 1.0;
-            }
-        }
+    if aabb_contains(level.tunnel.pos, level.tunnel.size, pos) {
+        let (f, var) = sample_field(&level.tunnel.flow, time);
+        flow = vadd(flow, f);
+        variance += var;
+        count += 1.0;
     }
 
     // Torus tunnel interior check (if present). See original file for geometry notes.
@@ -51,16 +107,10 @@ pub fn sample_flow_at(level: &LevelSpec, pos: Vec3f, time: f32) -> (Vec3f, f32)
             let p_len = (p.x * p.x + p.y * p.y + p.z * p.z).sqrt();
             let tube = ((p_len - t.major_radius).abs().powi(2) + h * h).sqrt();
             if tube <= t.minor_radius {
-                match t.flow {
-                    FlowFieldSpec::Uniform {
-                        flow: f,
-                        variance: var,
-                    } => {
-                        flow = vadd(flow, f);
-                        variance += var;
-                        count += 1.0;
-                    }
-                }
+                let (f, var) = sample_field(&t.flow, time);
+                flow = vadd(flow, f);
+                variance += var;
+                count += 1.0;
             }
         }
     }
@@ -70,7 +120,12 @@ pub fn sample_flow_at(level: &LevelSpec, pos: Vec3f, time: f32) -> (Vec3f, f32)
         variance /= count;
     }
 
-    let _ = time;
+    for vent in &level.thermal_vents {
+        if vent.contains(pos) {
+            flow = vadd(flow, Vec3f::new(0.0, vent.upwelling_m_s, 0.0));
+        }
+    }
+
     (flow, variance)
 }
 
@@ -84,17 +139,11 @@ mod tests {
         let level = greybox_level();
         let center = level.tunnel.pos;
         let (flow, var) = sample_flow_at(&level, center, 0.0);
-        match level.tunnel.flow {
-            FlowFieldSpec::Uniform {
-                flow: f,
-                variance: v,
-            } => {
-                assert!((flow.x - f.x).abs() < 1e-6);
-                assert!((flow.y - f.y).abs() < 1e-6);
-                assert!((flow.z - f.z).abs() < 1e-6);
-                assert!((var - v).abs() < 1e-6);
-            }
-        }
+        let (f, v) = level.tunnel.flow.mean();
+        assert!((flow.x - f.x).abs() < 1e-6);
+        assert!((flow.y - f.y).abs() < 1e-6);
+        assert!((flow.z - f.z).abs() < 1e-6);
+        assert!((var - v).abs() < 1e-6);
         // Outside the tunnel bounds: offset in Z beyond half-width
         let half_w = level.tunnel.size.z * 0.5;
         let outside = Vec3f::new(center.x, center.y, center.z + half_w + 10.0);
@@ -112,12 +161,8 @@ mod tests {
         let (flow, var) = sample_flow_at(&level, pos_on_ring, 0.0);
 
         // Expect average of tunnel and torus uniform flows/variances
-        let (tunnel_flow, tunnel_var) = match level.tunnel.flow {
-            FlowFieldSpec::Uniform { flow, variance } => (flow, variance),
-        };
-        let (ring_flow, ring_var) = match t.flow {
-            FlowFieldSpec::Uniform { flow, variance } => (flow, variance),
-        };
+        let (tunnel_flow, tunnel_var) = level.tunnel.flow.mean();
+        let (ring_flow, ring_var) = t.flow.mean();
         let expected = Vec3f::new(
             0.5 * (tunnel_flow.x + ring_flow.x),
             0.5 * (tunnel_flow.y + ring_flow.y),
@@ -129,4 +174,74 @@ mod tests {
         assert!((flow.z - expected.z).abs() < 1e-5);
         assert!((var - expected_var).abs() < 1e-5);
     }
+
+    #[test]
+    fn thermal_vent_adds_upwelling_inside_column_only() {
+        let level = greybox_level();
+        let vent = &level.thermal_vents[0];
+        let base = vent.position + Vec3f::new(0.0, 1.0, 0.0);
+        let (f_in, _) = sample_flow_at(&level, base, 0.0);
+        assert!(
+            (f_in.y - vent.upwelling_m_s).abs() < 1e-6,
+            "inside: {f_in:?}"
+        );
+
+        let beside = base + Vec3f::new(0.0, 0.0, vent.radius_m + 0.5);
+        let (f_side, _) = sample_flow_at(&level, beside, 0.0);
+        assert!(f_side.y.abs() < 1e-6, "beside: {f_side:?}");
+
+        let above = vent.position + Vec3f::new(0.0, vent.height_m + 0.5, 0.0);
+        let (f_above, _) = sample_flow_at(&level, above, 0.0);
+        assert!(f_above.y.abs() < 1e-6, "above: {f_above:?}");
+    }
+
+    fn turbulent_tunnel(intensity: f32) -> LevelSpec {
+        let mut level = greybox_level();
+        level.tunnel.flow = FlowFieldSpec::TurbulentUniform {
+            mean_flow: Vec3f::new(1.5, 0.0, 0.0),
+            intensity,
+            correlation_length: 3.0,
+            seed: 7,
+        };
+        level
+    }
+
+    #[test]
+    fn turbulence_is_seeded_continuous_and_zero_mean() {
+        let level = turbulent_tunnel(0.4);
+        let center = level.tunnel.pos;
+        let mean = Vec3f::new(1.5, 0.0, 0.0);
+
+        // Same time, same flow: nothing depends on who samples or how often
+        let (a, var) = sample_flow_at(&level, center, 12.34);
+        let (b, _) = sample_flow_at(&level, center, 12.34);
+        assert_eq!(a, b);
+        assert!((var - 0.16).abs() < 1e-6);
+        assert!((a - mean).length() > 1e-4, "no perturbation at all");
+
+        // T = 3 m / 1.5 m/s = 2 s; a 1/120 s step barely moves it
+        let (c, _) = sample_flow_at(&level, center, 12.34 + 1.0 / 120.0);
+        assert!((c - a).length() < 0.05, "jumped from {a:?} to {c:?}");
+
+        // A different seed stirs differently
+        let mut reseeded = level.clone();
+        if let FlowFieldSpec::TurbulentUniform { seed, .. } = &mut reseeded.tunnel.flow {
+            *seed = 8;
+        }
+        assert_ne!(sample_flow_at(&reseeded, center, 12.34).0, a);
+
+        // Over many correlation times the perturbation averages out with about `intensity` spread
+        let samples: Vec<Vec3f> = (0..4000)
+            .map(|i| sample_flow_at(&level, center, i as f32 * 2.0).0 - mean)
+            .collect();
+        let n = samples.len() as f32;
+        let avg = samples.iter().copied().sum::<Vec3f>() / n;
+        let rms_x = (samples.iter().map(|p| p.x * p.x).sum::<f32>() / n).sqrt();
+        assert!(avg.length() < 0.05, "biased: {avg:?}");
+        assert!((rms_x - 0.4).abs() < 0.06, "rms {rms_x}");
+
+        // No intensity is the plain mean flow
+        let calm = turbulent_tunnel(0.0);
+        assert_eq!(sample_flow_at(&calm, center, 12.34).0, mean);
+    }
 }
@@ -6,4 +6,7 @@ mod util;
 
 pub use dynamics::{step_submarine, step_submarine_dbg};
 pub use flow::sample_flow_at;
-pub use types::{SubInputState, SubInputs, SubState, SubStepDebug};
+pub use types::{
+    DiveDepthEvent, PumpCooldownState, SubInputState, SubInputs, SubState, SubStepDebug,
+    TURBO_PUMP_BOOST_S, TURBO_PUMP_COOLDOWN_S, TURBO_PUMP_MULTIPLIER,
+};
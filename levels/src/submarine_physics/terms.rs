@@ -36,6 +36,48 @@ pub(super) fn torque_weathervane_beta(spec: &SubPhysicsSpec, q_dyn: f32, yaw_err
     spec.n_beta * q_dyn * spec.s_side * spec.length * yaw_err
 }
 
+// ----- Dive plane torques -----
+
+pub(super) fn torque_pitch_control(
+    spec: &SubPhysicsSpec,
+    plane_in: f32,
+    sign_u: f32,
+    q_dyn: f32,
+) -> f32 {
+    // Positive plane input = dive; positive torque about body right pitches the nose down.
+    plane_in * sign_u * spec.m_delta_s * q_dyn * spec.s_top * spec.length
+}
+
+pub(super) fn torque_roll_control(
+    spec: &SubPhysicsSpec,
+    roll_in: f32,
+    sign_u: f32,
+    q_dyn: f32,
+) -> f32 {
+    // Positive roll trim = starboard side down. Body +X is to port, so that is a positive torque
+    // about body forward, which lifts +X.
+    roll_in * sign_u * spec.k_delta_s * q_dyn * spec.s_top * spec.diameter
+}
+
+// ----- Propulsion -----
+
+/// Sum of all propeller forces and their moments about the current CG, both in body frame.
+pub(super) fn propeller_force_and_moment_body(
+    spec: &SubPhysicsSpec,
+    thrust_in: f32,
+    cg_body_current: Vec3f,
+) -> (Vec3f, Vec3f) {
+    let mut force = Vec3f::new(0.0, 0.0, 0.0);
+    let mut moment = Vec3f::new(0.0, 0.0, 0.0);
+    for prop in &spec.propellers {
+        let dir = prop.thrust_direction_body.normalize_or_zero();
+        let f = dir * (spec.t_max * prop.thrust_share * thrust_in);
+        force += f;
+        moment += (prop.pos_body - cg_body_current).cross(f);
+    }
+    (force, moment)
+}
+
 // ----- Pitch / Roll torques from ballast and COB -----
 
 pub(super) fn torque_from_ballast_gravity_about_axis(
@@ -113,9 +155,31 @@ mod tests {
             orientation: Quatf::from_rotation_y(0.0),
             ang_mom: Vec3f::new(0.0, 0.0, 0.0),
             ballast_fill: fill.to_vec(),
+            pump: Default::default(),
         }
     }
 
+    #[test]
+    fn centerline_propeller_has_no_moment() {
+        let spec = small_skiff_spec();
+        let (f, m) = propeller_force_and_moment_body(&spec, 1.0, Vec3f::new(0.0, 0.0, 0.0));
+        assert!((f.z - spec.t_max).abs() < 1e-3);
+        assert!(f.x.abs() < 1e-6 && f.y.abs() < 1e-6);
+        assert!(m.length() < 1e-3, "moment = {m:?}");
+    }
+
+    #[test]
+    fn side_thruster_at_stern_produces_yaw_moment() {
+        let mut spec = small_skiff_spec();
+        spec.propellers[0].thrust_direction_body = Vec3f::new(1.0, 0.0, 0.0);
+        let (f, m) = propeller_force_and_moment_body(&spec, 1.0, Vec3f::new(0.0, 0.0, 0.0));
+        assert!((f.x - spec.t_max).abs() < 1e-3);
+        // r = (0,0,-L/2), F = (T,0,0) => r x F = (0, -L/2 * T, 0)
+        let expected = -0.5 * spec.length * spec.t_max;
+        assert!((m.y - expected).abs() < 1e-2, "moment = {m:?}");
+        assert!(m.x.abs() < 1e-6 && m.z.abs() < 1e-6);
+    }
+
     #[test]
     fn yaw_control_torque_scales_and_signs() {
         let mut spec = small_skiff_spec();
@@ -1,25 +1,36 @@
-use crate::{Quatf, Vec3f};
+use bevy_reflect::Reflect;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Default)]
+use crate::{Quatf, SubPhysicsSpec, Vec3f};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Reflect)]
 pub struct SubInputs {
     pub thrust: f32, // -1..1 (forward/back)
     /// Rudder input in [-1, 1].
     /// Convention: +1 = right rudder (nose yaws right when moving forward),
-    /// -1 = left rudder. The physics maps this to yaw torque so that forward
-    /// motion with positive input decreases heading_yaw (right turn).
+    /// -1 = left rudder. The physics maps this to a negative yaw torque so that
+    /// forward motion with positive input swings the nose towards -X (right turn).
     pub yaw: f32, // -1..1 (right rudder positive)
     /// Forward ballast pump speed in [-1,1]. +1 pumps water in (fill), -1 pumps out.
     pub pump_fwd: f32,
     /// Aft ballast pump speed in [-1,1]. +1 pumps water in (fill), -1 pumps out.
     pub pump_aft: f32,
+    /// Dive plane deflection in [-1,1]. +1 = dive (fore planes trailing edge up, aft planes
+    /// trailing edge down); pitches the nose down under forward motion.
+    pub plane: f32,
+    /// Differential plane trim in [-1,1]. +1 rolls to starboard (right side down) under
+    /// forward motion.
+    pub roll_trim: f32,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Reflect)]
 pub struct SubInputState {
     pub thrust: f32,
     pub yaw: f32,
     pub pump_fwd: f32,
     pub pump_aft: f32,
+    pub plane: f32,
+    pub roll_trim: f32,
 }
 
 impl SubInputState {
@@ -29,15 +40,55 @@ impl SubInputState {
             yaw: inputs.yaw,
             pump_fwd: inputs.pump_fwd,
             pump_aft: inputs.pump_aft,
+            plane: inputs.plane,
+            roll_trim: inputs.roll_trim,
         }
     }
 
     pub fn apply_inputs(&mut self, inputs: SubInputs) {
         *self = Self::from_inputs(inputs);
     }
+
+    /// First-order ramp from `prev` toward the raw `target` over `dt`, using the
+    /// spec's `thrust_tau_s`/`yaw_tau_s`/`pump_tau_s`. Plane and roll trim commands pass through
+    /// unchanged.
+    pub fn ramped(prev: &Self, target: SubInputs, dt: f32, spec: &SubPhysicsSpec) -> Self {
+        let blend = |tau: f32| {
+            if tau <= 0.0 {
+                1.0
+            } else {
+                1.0 - (-dt.max(0.0) / tau).exp()
+            }
+        };
+        let a_thr = blend(spec.thrust_tau_s);
+        let a_yaw = blend(spec.yaw_tau_s);
+        let a_pump = blend(spec.pump_tau_s);
+        Self {
+            thrust: prev.thrust + (target.thrust - prev.thrust) * a_thr,
+            yaw: prev.yaw + (target.yaw - prev.yaw) * a_yaw,
+            pump_fwd: prev.pump_fwd + (target.pump_fwd - prev.pump_fwd) * a_pump,
+            pump_aft: prev.pump_aft + (target.pump_aft - prev.pump_aft) * a_pump,
+            plane: target.plane,
+            roll_trim: target.roll_trim,
+        }
+    }
+
+    pub fn ramp_toward(&mut self, target: SubInputs, dt: f32, spec: &SubPhysicsSpec) {
+        *self = Self::ramped(self, target, dt, spec);
+    }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+/// Reported by a physics step taken past the spec's `DiveDepthLimit::warning_depth_m`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Reflect)]
+pub enum DiveDepthEvent {
+    /// Inside the warning band, above `max_depth_m`.
+    Warning { depth_m: f32 },
+    /// Below `max_depth_m`; the step blew the ballast tanks.
+    Exceeded { depth_m: f32 },
+}
+
+/// Breakdown of one physics step; reflected so the client's inspector can show it live.
+#[derive(Debug, Clone, Copy, Default, Serialize, Reflect)]
 pub struct SubStepDebug {
     pub dt: f32,
     pub time: f32,
@@ -58,6 +109,10 @@ pub struct SubStepDebug {
     pub front_mount_gain: f32,
     // Forces (body components) and world recompose
     pub thrust_force: f32,
+    /// Summed propeller force in world frame (N).
+    pub thrust_world: Vec3f,
+    /// Propeller moment about the CG in body frame (N·m).
+    pub tau_thrust: Vec3f,
     pub fx: f32,
     pub fy: f32,
     pub fz: f32,
@@ -84,21 +139,411 @@ pub struct SubStepDebug {
     pub buoy_net_n: f32,
     // Pitch diagnostics
     pub tau_pitch: f32,
+    // Pitch and roll torques (breakdown, N·m about body right / forward)
+    pub tau_pitch_ballast: f32,
+    pub tau_pitch_cob: f32,
+    pub tau_pitch_damp: f32,
+    pub tau_pitch_total: f32,
+    /// Dive plane share of `tau_pitch`.
+    pub tau_plane: f32,
+    pub tau_roll_ballast: f32,
+    pub tau_roll_cob: f32,
+    pub tau_roll_damp: f32,
+    pub tau_roll_total: f32,
+    /// Roll trim share of the roll torque.
+    pub tau_roll_trim: f32,
+    // Linear accelerations (world, m/s²); `a_net` is their sum
+    pub a_buoy: Vec3f,
+    pub a_drag_world: Vec3f,
+    pub a_thrust_world: Vec3f,
+    pub a_centripetal: Vec3f,
+    pub a_net: Vec3f,
+    /// Self-noise level in dB above a stationary hull, `20·log10(1 + speed)`.
+    pub noise_floor: f32,
+    /// Radiated noise (dB) others hear, `SubPhysicsSpec::acoustic_level_db` at this step's
+    /// thrust and yaw rate.
+    pub acoustic_level_db: f32,
+    /// Dive-depth state at the start of this step.
+    pub depth_event: Option<DiveDepthEvent>,
+    /// Turbo pump budget after this step; `None` for `PumpType::Standard`.
+    pub pump_turbo: Option<PumpCooldownState>,
+}
+
+/// Seconds of triple-rate pumping a `PumpType::Turbo` sub gets before its cooldown.
+pub const TURBO_PUMP_BOOST_S: f32 = 5.0;
+/// Seconds a spent turbo pump runs at the standard rate before the boost refills.
+pub const TURBO_PUMP_COOLDOWN_S: f32 = 15.0;
+/// Pump rate multiplier while the turbo boost lasts.
+pub const TURBO_PUMP_MULTIPLIER: f32 = 3.0;
+
+/// Turbo pump budget of a `PumpType::Turbo` sub: pumping drains `boost_remaining_s`, and once
+/// it's spent the pumps run at the standard rate until `cooldown_remaining_s` counts down to
+/// zero and the boost refills. Standard pumps leave it untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct PumpCooldownState {
+    pub boost_remaining_s: f32,
+    pub cooldown_remaining_s: f32,
+}
+
+impl Default for PumpCooldownState {
+    fn default() -> Self {
+        Self {
+            boost_remaining_s: TURBO_PUMP_BOOST_S,
+            cooldown_remaining_s: 0.0,
+        }
+    }
+}
+
+impl PumpCooldownState {
+    #[inline]
+    pub fn is_cooling_down(&self) -> bool {
+        self.cooldown_remaining_s > 0.0
+    }
+
+    /// Advance the budget by `dt` and return the pump rate multiplier for this step. Only
+    /// steps with a pump running (`pumping`) spend boost; the cooldown runs regardless.
+    pub fn step(&mut self, pumping: bool, dt: f32) -> f32 {
+        if self.is_cooling_down() {
+            self.cooldown_remaining_s = (self.cooldown_remaining_s - dt).max(0.0);
+            if !self.is_cooling_down() {
+                self.boost_remaining_s = TURBO_PUMP_BOOST_S;
+            }
+            return 1.0;
+        }
+        if !pumping {
+            return 1.0;
+        }
+        self.boost_remaining_s -= dt;
+        if self.boost_remaining_s <= 0.0 {
+            self.boost_remaining_s = 0.0;
+            self.cooldown_remaining_s = TURBO_PUMP_COOLDOWN_S;
+        }
+        TURBO_PUMP_MULTIPLIER
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubState {
     pub position: Vec3f,
     pub velocity: Vec3f,
     /// Orientation as quaternion (body->world).
     /// Frame conventions (see design/COORDINATES_AND_CONVENTIONS.md):
-    /// - Body axes: +Z forward, +Y up, +X right (starboard).
-    /// - World axes: +Z forward, +Y up, +X right.
+    /// - Body axes: +Z forward, +Y up, +X left (port).
+    /// - World axes: +Z forward, +Y up, +X left.
     /// - Positive yaw rate (omega_y) turns the nose to the left (CCW when looking down +Y).
     pub orientation: Quatf,
     /// Angular momentum in body frame (kg*m^2*rad/s). Use spec inertia to derive omega.
-    /// Convention: body axes are +Z forward, +Y up, +X right.
+    /// Convention: body axes are +Z forward, +Y up, +X left (port).
     pub ang_mom: Vec3f,
     /// Ballast tank fill state in [0,1] for each tank in spec.ballast_tanks (future use)
     pub ballast_fill: Vec<f32>,
+    /// Turbo pump boost and cooldown; see `PumpType::Turbo`.
+    #[serde(default)]
+    pub pump: PumpCooldownState,
+}
+
+impl SubState {
+    /// Bincode snapshot, e.g. for the server to keep a sub across a reconnect.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("sub state has only plain numeric fields")
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+
+    /// Blend from `a` (t = 0) to `b` (t = 1), `t` clamped to [0, 1]: position, velocity,
+    /// angular momentum and ballast fills linearly, orientation along the shortest arc. Tank
+    /// lists of different lengths can't be paired, so `b`'s fills are taken as-is; the pump
+    /// budget is always `b`'s.
+    pub fn interpolate(a: &SubState, b: &SubState, t: f32) -> SubState {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        if t == 0.0 {
+            return a.clone();
+        }
+        if t == 1.0 {
+            return b.clone();
+        }
+        let ballast_fill = if a.ballast_fill.len() == b.ballast_fill.len() {
+            a.ballast_fill
+                .iter()
+                .zip(&b.ballast_fill)
+                .map(|(fa, fb)| fa + (fb - fa) * t)
+                .collect()
+        } else {
+            b.ballast_fill.clone()
+        };
+        SubState {
+            position: a.position.lerp(b.position, t),
+            velocity: a.velocity.lerp(b.velocity, t),
+            orientation: a.orientation.slerp(b.orientation, t).normalize(),
+            ang_mom: a.ang_mom.lerp(b.ang_mom, t),
+            ballast_fill,
+            pump: b.pump,
+        }
+    }
+
+    /// World-frame speed (m/s).
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        self.velocity.length()
+    }
+
+    /// Heading of the body +Z axis in the horizontal plane (rad): 0 = world +Z, increasing as
+    /// the nose turns to port. Unaffected by pitch, unlike a YXZ Euler decomposition.
+    #[inline]
+    pub fn heading_yaw(&self) -> f32 {
+        let fwd = self.orientation * Vec3f::Z;
+        fwd.x.atan2(fwd.z)
+    }
+
+    /// Depth below the surface (m), positive downward.
+    #[inline]
+    pub fn depth_m(&self) -> f32 {
+        -self.position.y
+    }
+
+    /// Mean fill across all ballast tanks, 0 with no tanks.
+    #[inline]
+    pub fn ballast_avg(&self) -> f32 {
+        if self.ballast_fill.is_empty() {
+            0.0
+        } else {
+            self.ballast_fill.iter().sum::<f32>() / self.ballast_fill.len() as f32
+        }
+    }
+
+    /// Net buoyancy within 5 N of zero, computed as in `step_submarine` (neutral at 50% fill).
+    #[inline]
+    pub fn is_neutral_buoyancy_approx(&self, spec: &SubPhysicsSpec) -> bool {
+        let excess_kg: f32 = spec
+            .ballast_tanks
+            .iter()
+            .enumerate()
+            .map(|(i, tank)| {
+                let fill = self.ballast_fill.get(i).copied().unwrap_or(0.0).max(0.0);
+                tank.capacity_kg.max(0.0) * (0.5 - fill)
+            })
+            .sum();
+        (excess_kg * 9.81).abs() < 5.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thrust_ramps_smoothly_from_zero_to_full() {
+        let spec = crate::subspecs::small_skiff_spec();
+        let target = SubInputs {
+            thrust: 1.0,
+            ..Default::default()
+        };
+        let dt = 1.0 / 60.0;
+        let mut state = SubInputState::default();
+        let mut prev = 0.0f32;
+        let mut t = 0.0f32;
+        while t < 0.45 - 1e-4 {
+            state.ramp_toward(target, dt, &spec);
+            assert!(state.thrust > prev, "ramp must be monotonic");
+            assert!(
+                state.thrust - prev < 0.15,
+                "no step jumps: {}",
+                state.thrust - prev
+            );
+            prev = state.thrust;
+            t += dt;
+            if (t - 0.15).abs() < dt * 0.5 {
+                // One time constant in: ~63%
+                assert!(
+                    (state.thrust - 0.632).abs() < 0.03,
+                    "thrust at tau = {}",
+                    state.thrust
+                );
+            }
+        }
+        // Three time constants: ~95%
+        assert!(
+            state.thrust > 0.93 && state.thrust < 0.97,
+            "thrust at 0.45 s = {}",
+            state.thrust
+        );
+    }
+
+    #[test]
+    fn ramp_is_independent_of_step_size() {
+        let spec = crate::subspecs::small_skiff_spec();
+        let target = SubInputs {
+            thrust: 1.0,
+            yaw: -1.0,
+            ..Default::default()
+        };
+        let mut fine = SubInputState::default();
+        for _ in 0..120 {
+            fine.ramp_toward(target, 1.0 / 120.0, &spec);
+        }
+        let mut coarse = SubInputState::default();
+        for _ in 0..30 {
+            coarse.ramp_toward(target, 1.0 / 30.0, &spec);
+        }
+        assert!((fine.thrust - coarse.thrust).abs() < 1e-4);
+        assert!((fine.yaw - coarse.yaw).abs() < 1e-4);
+    }
+
+    #[test]
+    fn sub_state_roundtrips_through_bytes() {
+        let state = SubState {
+            position: Vec3f::new(12.5, -30.25, 7.0),
+            velocity: Vec3f::new(-1.5, 0.25, 3.75),
+            orientation: Quatf::from_rotation_y(0.7) * Quatf::from_rotation_x(-0.2),
+            ang_mom: Vec3f::new(4.0, -120.0, 0.5),
+            ballast_fill: vec![0.125, 0.875, 0.5],
+            pump: Default::default(),
+        };
+        let bytes = state.to_bytes();
+        assert_eq!(SubState::from_bytes(&bytes).unwrap(), state);
+        assert!(SubState::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn depth_is_negated_height_for_arbitrary_positions() {
+        // Deterministic LCG sweep; the crate has no property-testing dependency
+        let mut seed = 0x2545_f491_u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0e4 - 1.0e4
+        };
+        for _ in 0..1000 {
+            let state = SubState {
+                position: Vec3f::new(next(), next(), next()),
+                velocity: Vec3f::ZERO,
+                orientation: Quatf::IDENTITY,
+                ang_mom: Vec3f::ZERO,
+                ballast_fill: Vec::new(),
+                pump: Default::default(),
+            };
+            assert_eq!(state.depth_m(), -state.position.y);
+        }
+    }
+
+    #[test]
+    fn accessors_read_speed_heading_and_ballast() {
+        let spec = crate::subspecs::small_skiff_spec();
+        let mut state = SubState {
+            position: Vec3f::new(0.0, -12.0, 0.0),
+            velocity: Vec3f::new(3.0, 0.0, -4.0),
+            orientation: Quatf::from_rotation_y(0.6) * Quatf::from_rotation_x(-0.3),
+            ang_mom: Vec3f::ZERO,
+            ballast_fill: vec![0.25, 0.75],
+            pump: Default::default(),
+        };
+        assert_eq!(state.speed(), 5.0);
+        assert!(
+            (state.heading_yaw() - 0.6).abs() < 1e-5,
+            "pitch must not skew heading"
+        );
+        assert_eq!(state.ballast_avg(), 0.5);
+        assert!(state.is_neutral_buoyancy_approx(&spec));
+
+        state.ballast_fill = vec![0.52, 0.52];
+        assert!(!state.is_neutral_buoyancy_approx(&spec));
+        state.ballast_fill.clear();
+        assert_eq!(state.ballast_avg(), 0.0);
+    }
+
+    fn sample_state(seed: f32) -> SubState {
+        SubState {
+            position: Vec3f::new(seed * 3.0, -seed * 7.5, 1.0 - seed),
+            velocity: Vec3f::new(-seed, 0.5 * seed, 2.0),
+            orientation: Quatf::from_rotation_y(seed) * Quatf::from_rotation_x(-0.3 * seed),
+            ang_mom: Vec3f::new(10.0 * seed, -40.0, seed),
+            ballast_fill: vec![0.1 * seed.abs().min(10.0), 0.9],
+            pump: Default::default(),
+        }
+    }
+
+    fn assert_states_close(got: &SubState, want: &SubState) {
+        assert!(
+            got.position.abs_diff_eq(want.position, 1e-4),
+            "{got:?} vs {want:?}"
+        );
+        assert!(
+            got.velocity.abs_diff_eq(want.velocity, 1e-4),
+            "{got:?} vs {want:?}"
+        );
+        assert!(
+            got.ang_mom.abs_diff_eq(want.ang_mom, 1e-4),
+            "{got:?} vs {want:?}"
+        );
+        assert!(
+            got.orientation.abs_diff_eq(want.orientation, 1e-5),
+            "{got:?} vs {want:?}"
+        );
+        assert_eq!(got.ballast_fill.len(), want.ballast_fill.len());
+        for (g, w) in got.ballast_fill.iter().zip(&want.ballast_fill) {
+            assert!((g - w).abs() < 1e-6, "{got:?} vs {want:?}");
+        }
+    }
+
+    #[test]
+    fn interpolating_a_state_with_itself_is_identity() {
+        for i in 0..200 {
+            let s = sample_state(i as f32 * 0.37 - 20.0);
+            // Include out-of-range t, which clamps
+            let t = (i as f32 * 0.618).fract() * 1.4 - 0.2;
+            assert_states_close(&SubState::interpolate(&s, &s, t), &s);
+        }
+    }
+
+    #[test]
+    fn interpolate_hits_endpoints_and_blends_between() {
+        let a = sample_state(0.5);
+        let b = sample_state(2.0);
+        assert_eq!(SubState::interpolate(&a, &b, 0.0), a);
+        assert_eq!(SubState::interpolate(&a, &b, 1.0), b);
+        assert_eq!(SubState::interpolate(&a, &b, -3.0), a);
+        assert_eq!(SubState::interpolate(&a, &b, 7.0), b);
+
+        let mid = SubState::interpolate(&a, &b, 0.5);
+        assert!(mid
+            .position
+            .abs_diff_eq((a.position + b.position) * 0.5, 1e-5));
+        assert!((mid.ballast_fill[0] - 0.125).abs() < 1e-6);
+        let half_angle = a.orientation.angle_between(b.orientation) * 0.5;
+        assert!((a.orientation.angle_between(mid.orientation) - half_angle).abs() < 1e-4);
+
+        let mut one_tank = b.clone();
+        one_tank.ballast_fill = vec![0.7];
+        assert_eq!(
+            SubState::interpolate(&a, &one_tank, 0.5).ballast_fill,
+            vec![0.7]
+        );
+    }
+
+    #[test]
+    fn step_debug_round_trips_through_reflect() {
+        use bevy_reflect::PartialReflect;
+
+        let source = SubStepDebug {
+            dt: 1.0 / 60.0,
+            raw_inputs: Some(SubInputs {
+                thrust: 0.5,
+                ..Default::default()
+            }),
+            forward: Vec3f::Z,
+            depth_event: Some(DiveDepthEvent::Warning { depth_m: 90.0 }),
+            ..Default::default()
+        };
+        let mut target = SubStepDebug::default();
+        target.apply(source.as_partial_reflect());
+        assert_eq!(format!("{target:?}"), format!("{source:?}"));
+
+        let mut back = source;
+        back.apply(SubStepDebug::default().as_partial_reflect());
+        assert_eq!(
+            format!("{back:?}"),
+            format!("{:?}", SubStepDebug::default())
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{Quatf, Vec3f};
 
-// Basis: standard RHS with +Z forward, +Y up, +X right
+// Basis: standard RHS with +Z forward, +Y up, +X to port (left)
 pub(super) const BODY_FWD: Vec3f = Vec3f::new(0.0, 0.0, 1.0);
 pub(super) const BODY_RIGHT: Vec3f = Vec3f::new(1.0, 0.0, 0.0);
 pub(super) const BODY_UP: Vec3f = Vec3f::new(0.0, 1.0, 0.0);
@@ -13,7 +13,7 @@ pub(super) fn quat_rotate_vec3(q: Quatf, v: Vec3f) -> Vec3f {
 #[inline]
 pub(super) fn quat_to_yaw(q: Quatf) -> f32 {
     let fwd = q * BODY_FWD;
-    // Positive yaw turns left; project into XZ plane with +Z forward
+    // Positive yaw is a right turn (nose towards -X); project into XZ plane with +Z forward
     (-fwd.x).atan2(fwd.z)
 }
 
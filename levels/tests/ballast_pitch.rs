@@ -23,6 +23,7 @@ fn forward_full_aft_empty_pitches_nose_down() {
         orientation: Quatf::from_rotation_y(0.0),
         ang_mom: Vec3f::new(0.0, 0.0, 0.0),
         ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+        pump: Default::default(),
     };
 
     // Set forward tank to full (index 0), aft to empty (index 1)
@@ -37,6 +38,8 @@ fn forward_full_aft_empty_pitches_nose_down() {
         yaw: 0.0,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     let dt = 1.0 / 60.0;
     let mut t = 0.0f32;
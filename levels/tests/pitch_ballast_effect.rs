@@ -33,6 +33,7 @@ fn forward_heavy_ballast_pitches_nose_down() {
         ang_mom: Vec3f::new(0.0, 0.0, 0.0),
         // Heavier forward (1.0) vs aft (0.0) should create negative pitch torque (nose down)
         ballast_fill: vec![1.0, 0.0],
+        pump: Default::default(),
     };
 
     let dt = 1.0 / 60.0;
@@ -42,6 +43,8 @@ fn forward_heavy_ballast_pitches_nose_down() {
         yaw: 0.0,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     for _ in 0..600 {
         step_submarine(&level, &spec, inputs, &mut state, dt, t);
@@ -69,6 +72,7 @@ fn aft_heavy_ballast_pitches_nose_up() {
         ang_mom: Vec3f::new(0.0, 0.0, 0.0),
         // Heavier aft (1.0) vs forward (0.0) should create positive pitch torque (nose up)
         ballast_fill: vec![0.0, 1.0],
+        pump: Default::default(),
     };
 
     let dt = 1.0 / 60.0;
@@ -78,6 +82,8 @@ fn aft_heavy_ballast_pitches_nose_up() {
         yaw: 0.0,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     for _ in 0..600 {
         step_submarine(&level, &spec, inputs, &mut state, dt, t);
@@ -3,6 +3,8 @@ use levels::{
     SubState, Vec3f,
 };
 
+// Angle of the nose from +Z towards +X. With +Y up and +Z forward, +X is to port,
+// so a right (starboard) turn makes this decrease.
 #[inline]
 fn yaw_of(q: Quatf) -> f32 {
     let fwd = q * Vec3f::new(0.0, 0.0, 1.0);
@@ -29,6 +31,7 @@ fn right_rudder_decreases_yaw_when_moving_forward() {
         orientation: Quatf::from_rotation_y(0.0),
         ang_mom: Vec3f::new(0.0, 0.0, 0.0),
         ballast_fill: vec![0.0; spec.ballast_tanks.len()],
+        pump: Default::default(),
     };
 
     let dt = 1.0 / 60.0; // fine step; not critical
@@ -40,6 +43,8 @@ fn right_rudder_decreases_yaw_when_moving_forward() {
         yaw: 0.0,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     for _ in 0..600 {
         step_submarine(&level, &spec, warm, &mut state, dt, t);
@@ -53,6 +58,8 @@ fn right_rudder_decreases_yaw_when_moving_forward() {
         yaw: 0.3,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     for _ in 0..600 {
         step_submarine(&level, &spec, steer, &mut state, dt, t);
@@ -61,8 +68,8 @@ fn right_rudder_decreases_yaw_when_moving_forward() {
     let yaw1 = yaw_of(state.orientation);
 
     assert!(
-        yaw1 > yaw0 + 0.005,
-        "right rudder should increase yaw (right-turn) under forward motion (yaw0={}, yaw1={})",
+        yaw1 < yaw0 - 0.005,
+        "right rudder should decrease yaw (right-turn) under forward motion (yaw0={}, yaw1={})",
         yaw0,
         yaw1
     );
@@ -80,6 +87,7 @@ fn right_rudder_decreases_yaw_when_moving_backward() {
         orientation: Quatf::from_rotation_y(0.0),
         ang_mom: Vec3f::new(0.0, 0.0, 0.0),
         ballast_fill: vec![0.0; spec.ballast_tanks.len()],
+        pump: Default::default(),
     };
 
     let dt = 1.0 / 60.0;
@@ -91,6 +99,8 @@ fn right_rudder_decreases_yaw_when_moving_backward() {
         yaw: 0.0,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     for _ in 0..600 {
         step_submarine(&level, &spec, warm, &mut state, dt, t);
@@ -104,6 +114,8 @@ fn right_rudder_decreases_yaw_when_moving_backward() {
         yaw: 0.3,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     for _ in 0..600 {
         step_submarine(&level, &spec, steer, &mut state, dt, t);
@@ -112,8 +124,8 @@ fn right_rudder_decreases_yaw_when_moving_backward() {
     let yaw1 = yaw_of(state.orientation);
 
     assert!(
-        yaw1 > yaw0 + 0.005,
-        "right rudder should increase yaw (right-turn) under reverse motion (yaw0={}, yaw1={})",
+        yaw1 < yaw0 - 0.005,
+        "right rudder should decrease yaw (right-turn) under reverse motion (yaw0={}, yaw1={})",
         yaw0,
         yaw1
     );
@@ -30,6 +30,7 @@ fn yaw_stability_tailflow_dt_1ms() {
         orientation: Quatf::from_rotation_y(0.0),
         ang_mom: Vec3f::new(0.0, 0.0, 0.0),
         ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+        pump: Default::default(),
     };
 
     let dt = 0.001; // 1 ms
@@ -40,6 +41,8 @@ fn yaw_stability_tailflow_dt_1ms() {
         yaw: 0.0,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     for _ in 0..ticks {
         step_submarine(&level, &spec, inputs, &mut state, dt, t);
@@ -66,6 +69,7 @@ fn yaw_stability_tailflow_dt_10ms() {
         orientation: Quatf::from_rotation_y(0.0),
         ang_mom: Vec3f::new(0.0, 0.0, 0.0),
         ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+        pump: Default::default(),
     };
 
     let dt = 0.01; // 10 ms
@@ -76,6 +80,8 @@ fn yaw_stability_tailflow_dt_10ms() {
         yaw: 0.0,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     for _ in 0..ticks {
         step_submarine(&level, &spec, inputs, &mut state, dt, t);
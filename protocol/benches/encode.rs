@@ -0,0 +1,104 @@
+//! `encode` vs `encode_into` for an 8-player `StateDelta`, the server's per-tick broadcast.
+//! Run with `cargo bench -p protocol --features bench`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use protocol::{encode, encode_into, NetInputState, NetPlayer, ServerToClient, StateDelta};
+use uuid::Uuid;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ITERATIONS: usize = 100_000;
+
+fn state_delta(players: usize) -> ServerToClient {
+    ServerToClient::StateDelta(StateDelta {
+        tick: 1234,
+        server_ms: 41_000,
+        players: (0..players)
+            .map(|i| NetPlayer {
+                id: Uuid::from_u128(i as u128),
+                position: [i as f32, -20.0, 3.5],
+                velocity: [1.0, 0.0, 0.2],
+                orientation: [0.0, 0.38, 0.0, 0.92],
+                ang_mom: [0.0, 120.0, 0.0],
+                angular_velocity: [0.0, 0.15, 0.0],
+                ballast_fill: vec![0.5, 0.5],
+                input_state: NetInputState {
+                    thrust: 1.0,
+                    yaw: 0.0,
+                    pump_fwd: 0.0,
+                    pump_aft: 0.0,
+                    pitch: 0.0,
+                    roll_trim: 0.0,
+                },
+                hull_integrity: 1.0,
+                team_id: (i % 2) as u8,
+                pump: Default::default(),
+            })
+            .collect(),
+        compressed_players: Vec::new(),
+        snapshot_origin: [0.0; 3],
+        obstacles: Vec::new(),
+    })
+}
+
+/// Allocations per call and nanoseconds per call.
+fn measure(mut f: impl FnMut()) -> (f64, f64) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+    let allocs = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    (
+        allocs as f64 / ITERATIONS as f64,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64,
+    )
+}
+
+fn main() {
+    let msg = state_delta(8);
+
+    let (fresh_allocs, fresh_ns) = measure(|| {
+        black_box(encode(black_box(&msg)).unwrap());
+    });
+    let mut buf = Vec::new();
+    let (reused_allocs, reused_ns) = measure(|| {
+        encode_into(black_box(&msg), &mut buf).unwrap();
+        black_box(&buf);
+    });
+
+    println!("encode:      {fresh_allocs:.3} allocs/call  {fresh_ns:.0} ns/call");
+    println!("encode_into: {reused_allocs:.3} allocs/call  {reused_ns:.0} ns/call");
+    let reduction = 1.0 - reused_allocs / fresh_allocs;
+    println!("allocation reduction: {:.0}%", reduction * 100.0);
+    assert!(
+        reduction >= 0.3,
+        "encode_into should save at least 30% of allocations"
+    );
+}
@@ -0,0 +1,47 @@
+//! LAN discovery: servers broadcast a `ServerBeacon` so clients on the same network can list
+//! them without knowing an address.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// UDP port beacons are broadcast to and clients listen on.
+pub const DISCOVERY_PORT: u16 = 7788;
+pub const BEACON_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerBeacon {
+    pub name: String,
+    /// Netcode address to connect to; the server's advertised public address.
+    pub addr: SocketAddr,
+    pub player_count: u8,
+    pub max_players: u8,
+}
+
+impl ServerBeacon {
+    pub fn encode(&self) -> Vec<u8> {
+        rmp_serde::to_vec(self).expect("beacons always encode")
+    }
+
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        rmp_serde::from_slice(bytes).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beacon_round_trips_and_rejects_garbage() {
+        let beacon = ServerBeacon {
+            name: "Trench".into(),
+            addr: "192.168.1.20:61234".parse().unwrap(),
+            player_count: 3,
+            max_players: 64,
+        };
+        assert_eq!(ServerBeacon::decode(&beacon.encode()), Some(beacon));
+        assert_eq!(ServerBeacon::decode(b"\xff\xff"), None);
+    }
+}
@@ -0,0 +1,117 @@
+//! Messages as encoded under `LEGACY_PROTOCOL_VERSION`, before the MessagePack encoding and
+//! before `InputTickBatch` delta-encoded its ticks: bincode behind a bare 2-byte version. The
+//! server reads client messages through `decode_client_message` and answers those clients with
+//! `encode`, so they can upgrade without being disconnected; remove this module and the
+//! `legacy` feature with the next version bump.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BatchedInputTick, ClientHello, ClientToServer, DecodeError, DockRequest, FireTorpedo,
+    InputEvent, InputTick, InputTickDelta, MineRequest, PauseRequest, Ping,
+    LEGACY_PROTOCOL_VERSION,
+};
+
+/// Bytes of `LEGACY_PROTOCOL_VERSION` (little endian) in front of a legacy message.
+pub const LEGACY_HEADER_LEN: usize = 2;
+
+/// `msg` as bincode behind the legacy version, the way a version 20 peer reads it.
+pub fn encode<T: Serialize>(msg: &T) -> Result<Vec<u8>, bincode::Error> {
+    let mut buf = LEGACY_PROTOCOL_VERSION.to_le_bytes().to_vec();
+    bincode::serialize_into(&mut buf, msg)?;
+    Ok(buf)
+}
+
+/// Check the version written by `encode`, then decode the payload.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DecodeError> {
+    let Some(header) = bytes.first_chunk::<LEGACY_HEADER_LEN>() else {
+        return Err(DecodeError::Truncated(bytes.len()));
+    };
+    let found = u16::from_le_bytes(*header);
+    if found != LEGACY_PROTOCOL_VERSION {
+        return Err(DecodeError::VersionMismatch {
+            found,
+            expected: LEGACY_PROTOCOL_VERSION,
+        });
+    }
+    Ok(bincode::deserialize(&bytes[LEGACY_HEADER_LEN..])?)
+}
+
+/// Decode a version 20 client message, header included, and upgrade it.
+pub fn decode_v20(bytes: &[u8]) -> Result<ClientToServer, DecodeError> {
+    let msg: ClientToServerV20 = decode(bytes)?;
+    msg.try_into()
+}
+
+/// `ClientToServer` at version 20. Variant order must match, as bincode tags by index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClientToServerV20 {
+    Hello(ClientHello),
+    InputTick(InputTick),
+    InputEvent(InputEvent),
+    MineRequest(MineRequest),
+    DockRequest(DockRequest),
+    PauseRequest(PauseRequest),
+    Ping(PingV20),
+    FireTorpedo(FireTorpedoV20),
+    /// Never sent by a version 20 client; the server no longer keeps a base to apply it to.
+    InputTickDelta(InputTickDelta),
+    InputTickBatch(Vec<InputTick>),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PingV20 {
+    pub seq: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FireTorpedoV20 {
+    pub tube_id: u8,
+    pub target_bearing_deg: f32,
+}
+
+impl From<PingV20> for Ping {
+    fn from(p: PingV20) -> Self {
+        Self {
+            seq: p.seq,
+            client_send_ms: 0,
+        }
+    }
+}
+
+impl From<FireTorpedoV20> for FireTorpedo {
+    fn from(f: FireTorpedoV20) -> Self {
+        Self {
+            tube_id: f.tube_id,
+            target_bearing_deg: f.target_bearing_deg,
+            speed_mps: 0.0,
+            heading_override: None,
+        }
+    }
+}
+
+impl TryFrom<ClientToServerV20> for ClientToServer {
+    type Error = DecodeError;
+
+    fn try_from(msg: ClientToServerV20) -> Result<Self, DecodeError> {
+        Ok(match msg {
+            ClientToServerV20::Hello(hello) => Self::Hello(hello),
+            ClientToServerV20::InputTick(tick) => Self::InputTick(tick),
+            ClientToServerV20::InputEvent(ev) => Self::InputEvent(ev),
+            ClientToServerV20::MineRequest(req) => Self::MineRequest(req),
+            ClientToServerV20::DockRequest(req) => Self::DockRequest(req),
+            ClientToServerV20::PauseRequest(req) => Self::PauseRequest(req),
+            ClientToServerV20::Ping(ping) => Self::Ping(ping.into()),
+            ClientToServerV20::FireTorpedo(fire) => Self::FireTorpedo(fire.into()),
+            ClientToServerV20::InputTickDelta(_) => {
+                return Err(DecodeError::Legacy(Box::new(bincode::ErrorKind::Custom(
+                    "standalone InputTickDelta is no longer accepted".into(),
+                ))));
+            }
+            ClientToServerV20::InputTickBatch(batch) => {
+                Self::InputTickBatch(batch.into_iter().map(BatchedInputTick::Full).collect())
+            }
+        })
+    }
+}
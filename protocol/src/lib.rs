@@ -2,13 +2,39 @@
 //!
 //! Defines wire messages, channel ids, and simple (de)serialization helpers.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-pub const PROTOCOL_VERSION: u16 = 4;
+pub mod discovery;
+#[cfg(feature = "legacy")]
+pub mod legacy;
+pub mod rendezvous;
+#[cfg(feature = "renet")]
+pub mod renet_config;
+
+pub const PROTOCOL_VERSION: u16 = 21;
+/// Previous `PROTOCOL_VERSION`, still accepted from clients by `decode_client_message` while
+/// they migrate when built with the `legacy` feature. Its messages are bincode behind a bare
+/// version, and its `InputTickBatch` carries whole ticks.
+pub const LEGACY_PROTOCOL_VERSION: u16 = PROTOCOL_VERSION - 1;
 // Shared netcode protocol id used by client and server handshakes
 pub const NETCODE_PROTOCOL_ID: u64 = 7;
 
+/// Launch speed relative to the firing sub when `FireTorpedo::speed_mps` is left at 0.
+pub const TORPEDO_SPEED_M_S: f32 = 20.0;
+/// Fastest launch speed a `FireTorpedo` may ask for; faster requests are clamped.
+pub const TORPEDO_MAX_SPEED_M_S: f32 = 40.0;
+/// Quadratic drag on a running torpedo: it decelerates at `TORPEDO_DRAG_PER_M * speed²`.
+pub const TORPEDO_DRAG_PER_M: f32 = 0.005;
+
+/// Torpedo speed after `dt` seconds of drag, starting from `speed_m_s`. Exact for quadratic
+/// drag, so server and clients agree regardless of their step size.
+pub fn torpedo_drag(speed_m_s: f32, dt: f32) -> f32 {
+    speed_m_s / (1.0 + TORPEDO_DRAG_PER_M * speed_m_s * dt)
+}
+
 // Network channel layout (configurable at runtime; ids are defaults)
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
@@ -21,6 +47,81 @@ pub enum Channel {
     Input = 2,
 }
 
+impl From<Channel> for u8 {
+    fn from(channel: Channel) -> Self {
+        channel as u8
+    }
+}
+
+/// Which side of the connection sends on a channel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChannelKind {
+    Bidirectional,
+    ServerToClient,
+    ClientToServer,
+}
+
+/// Delivery guarantee; mirrors renet's `SendType` so this crate stays transport-agnostic.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChannelSendType {
+    Unreliable,
+    ReliableOrdered,
+    ReliableUnordered,
+}
+
+/// Per-channel transport settings. Client and server must agree on `id`, `kind` and `send_type`;
+/// `packet_budget_bytes` is local and may be scaled per side.
+///
+/// Translated into renet's `ChannelConfig` (`max_memory_usage_bytes`) by
+/// `renet_config::connection_config` under the `renet` feature, see
+/// https://docs.rs/renet/latest/renet/struct.ChannelConfig.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    pub id: u8,
+    pub kind: ChannelKind,
+    pub send_type: ChannelSendType,
+    /// Bytes the channel may hold unacknowledged. Unreliable channels drop new messages past
+    /// this; reliable channels disconnect.
+    pub packet_budget_bytes: u32,
+}
+
+impl ChannelConfig {
+    pub fn sent_by_server(&self) -> bool {
+        self.kind != ChannelKind::ClientToServer
+    }
+
+    pub fn sent_by_client(&self) -> bool {
+        self.kind != ChannelKind::ServerToClient
+    }
+}
+
+/// Default channel layout, in priority order: earlier channels get first claim on each
+/// packet's byte budget. Control traffic must never be starved by snapshots, so `Reliable`
+/// also gets twice the buffer of `State`.
+pub fn default_channel_configs() -> [ChannelConfig; 3] {
+    const STATE_BUDGET_BYTES: u32 = 5 * 1024 * 1024;
+    [
+        ChannelConfig {
+            id: Channel::Reliable.into(),
+            kind: ChannelKind::Bidirectional,
+            send_type: ChannelSendType::ReliableOrdered,
+            packet_budget_bytes: 2 * STATE_BUDGET_BYTES,
+        },
+        ChannelConfig {
+            id: Channel::State.into(),
+            kind: ChannelKind::ServerToClient,
+            send_type: ChannelSendType::Unreliable,
+            packet_budget_bytes: STATE_BUDGET_BYTES,
+        },
+        ChannelConfig {
+            id: Channel::Input.into(),
+            kind: ChannelKind::ClientToServer,
+            send_type: ChannelSendType::Unreliable,
+            packet_budget_bytes: STATE_BUDGET_BYTES / 4,
+        },
+    ]
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClientToServer {
     Hello(ClientHello),
@@ -30,6 +131,45 @@ pub enum ClientToServer {
     MineRequest(MineRequest),
     DockRequest(DockRequest),
     PauseRequest(PauseRequest),
+    /// Round-trip probe, sent on the unreliable `Input` channel.
+    Ping(Ping),
+    FireTorpedo(FireTorpedo),
+    /// Consecutive `InputTick`s, oldest first, sent together on the unreliable `Input` channel.
+    /// At most `MAX_INPUT_BATCH`; the server ignores any extra.
+    /// Entries come from `InputTick::encode_batch`, so most are deltas on the tick before.
+    InputTickBatch(Vec<BatchedInputTick>),
+    /// Text chat to every player, at most `MAX_CHAT_BYTES`.
+    SendChat(SendChat),
+    /// Active sonar ping from the sender's sub; needs `FeatureFlags::SONAR`.
+    SonarPing(SonarPingRequest),
+}
+
+impl WireMessage for ClientToServer {
+    fn msg_type(&self) -> u8 {
+        match self {
+            Self::Hello(_) => 0,
+            Self::InputTick(_) => 1,
+            Self::InputEvent(_) => 2,
+            Self::MineRequest(_) => 3,
+            Self::DockRequest(_) => 4,
+            Self::PauseRequest(_) => 5,
+            Self::Ping(_) => 6,
+            Self::FireTorpedo(_) => 7,
+            Self::InputTickBatch(_) => 8,
+            Self::SendChat(_) => 9,
+            Self::SonarPing(_) => 10,
+        }
+    }
+}
+
+impl ClientToServer {
+    /// Control input, counted against the server's per-client input rate limit.
+    pub fn is_input(&self) -> bool {
+        matches!(
+            self,
+            Self::InputTick(_) | Self::InputTickBatch(_) | Self::InputEvent(_)
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,12 +181,122 @@ pub enum ServerToClient {
     DockAck(DockAck),
     PauseState(PauseState),
     Disconnect(DisconnectReason),
+    /// Sent once when a player's hull integrity drops below the alert threshold.
+    HullAlert(HullAlert),
+    /// Periodic scoreboard snapshot for all connected players.
+    LeaderboardUpdate(LeaderboardUpdate),
+    /// Sent to everyone when a player joins or the server reassigns teams.
+    TeamAssignment(TeamAssignment),
+    /// Sent when a team reaches the round's score limit; team scores reset afterwards.
+    TeamWin(TeamWin),
+    /// An ore node is mineable again after its respawn timer ran out.
+    EntitySpawn(EntitySpawn),
+    /// An ore node was mined out; also sent to newcomers for nodes still respawning.
+    EntityDespawn(EntityDespawn),
+    /// Echo of a `Ping`, sent on the unreliable `State` channel.
+    PingResponse(PingResponse),
+    /// The campaign moved to another level; also sent to newcomers on join.
+    LevelReload(LevelReload),
+    /// Broadcast when any player fires; the torpedo coasts straight, slowed by `torpedo_drag`.
+    TorpedoSpawned(TorpedoSpawned),
+    /// Broadcast when a torpedo hits a wall or player or its run times out.
+    TorpedoDetonation(TorpedoDetonation),
+    /// The receiving player's progress on one mission moved; also sent on join for every
+    /// mission the player had already started.
+    MissionUpdate(MissionUpdate),
+    /// The receiving player finished a mission and was paid its reward.
+    MissionComplete(MissionComplete),
+    /// The client sent more inputs in the last second than the server accepts; the excess
+    /// was dropped.
+    RateLimit(RateLimit),
+    /// A player's `SendChat`, relayed to everyone including the sender.
+    ChatMessage(ChatMessage),
+    /// The receiving player's sub entered its dive-depth warning band, went past its maximum
+    /// depth, or was pulled back to a spawn point for staying there.
+    DepthWarning(DepthWarning),
+    /// The receiving player's full hold, after docking or on rejoin.
+    InventoryUpdate(InventoryUpdate),
+    /// The server changed its physics tick rate mid-session; replaces `JoinAck::tick_hz`.
+    TickRateChange(TickRateChange),
+    /// Answer to the receiving player's `SonarPing`.
+    SonarPingEcho(SonarPingEcho),
+}
+
+impl WireMessage for ServerToClient {
+    fn msg_type(&self) -> u8 {
+        match self {
+            Self::JoinAck(_) => 0,
+            Self::StateDelta(_) => 1,
+            Self::InputAck(_) => 2,
+            Self::MineAck(_) => 3,
+            Self::DockAck(_) => 4,
+            Self::PauseState(_) => 5,
+            Self::Disconnect(_) => 6,
+            Self::HullAlert(_) => 7,
+            Self::LeaderboardUpdate(_) => 8,
+            Self::TeamAssignment(_) => 9,
+            Self::TeamWin(_) => 10,
+            Self::EntitySpawn(_) => 11,
+            Self::EntityDespawn(_) => 12,
+            Self::PingResponse(_) => 13,
+            Self::LevelReload(_) => 14,
+            Self::TorpedoSpawned(_) => 15,
+            Self::TorpedoDetonation(_) => 16,
+            Self::MissionUpdate(_) => 17,
+            Self::MissionComplete(_) => 18,
+            Self::RateLimit(_) => 19,
+            Self::ChatMessage(_) => 20,
+            Self::DepthWarning(_) => 21,
+            Self::InventoryUpdate(_) => 22,
+            Self::TickRateChange(_) => 23,
+            Self::SonarPingEcho(_) => 24,
+        }
+    }
+}
+
+/// Optional gameplay features, negotiated in the handshake: the client requests a set in
+/// `ClientHello` and the server grants the part its deployment enables in `JoinAck`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FeatureFlags(pub u64);
+
+impl FeatureFlags {
+    pub const NONE: Self = Self(0);
+    pub const DELTA_STATE: Self = Self(1 << 0);
+    pub const SONAR: Self = Self(1 << 1);
+    pub const TORPEDO: Self = Self(1 << 2);
+    pub const TEAMS: Self = Self(1 << 3);
+    pub const VOICE: Self = Self(1 << 4);
+    pub const ALL: Self =
+        Self(Self::DELTA_STATE.0 | Self::SONAR.0 | Self::TORPEDO.0 | Self::TEAMS.0 | Self::VOICE.0);
+
+    /// Every flag in `other` is set here.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for FeatureFlags {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitAnd for FeatureFlags {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientHello {
     pub protocol: u16,
+    /// Stable per-install player id; the server reuses it so returning players keep their score.
+    pub player_id: Uuid,
     pub display_name: Option<String>,
+    pub requested_features: FeatureFlags,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +304,8 @@ pub struct JoinAck {
     pub player_id: Uuid,
     /// Server physics tick rate (Hz) for client fixed-step prediction.
     pub tick_hz: u32,
+    /// The client's `requested_features` that this server enables.
+    pub features: FeatureFlags,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,11 +320,142 @@ pub struct InputTick {
     pub pump_fwd: f32,
     /// Aft ballast pump speed in [-1,1]. +1 pumps water in (fill), -1 pumps out.
     pub pump_aft: f32,
+    /// Dive plane deflection in [-1,1]. +1 = dive (nose down under forward motion).
+    pub pitch: f32,
+    /// Differential plane trim in [-1,1]. +1 rolls to starboard under forward motion.
+    pub roll_trim: f32,
+}
+
+/// Most `InputTick`s one `InputTickBatch` may carry, bounding its packet size.
+pub const MAX_INPUT_BATCH: usize = 8;
+
+/// Input change encoded by `InputTickDelta`: one step is 1/100, so a delta spans ±1.27.
+pub const INPUT_DELTA_STEP: f32 = 0.01;
+
+/// `InputTick` relative to the entry before it in the same `InputTickBatch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputTickDelta {
+    pub tick: u64,
+    pub thrust_delta: i8,
+    pub yaw_delta: i8,
+    pub pump_fwd_delta: i8,
+    pub pump_aft_delta: i8,
+    pub pitch_delta: i8,
+    pub roll_trim_delta: i8,
+}
+
+/// One tick of an `InputTickBatch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BatchedInputTick {
+    Full(InputTick),
+    Delta(InputTickDelta),
+}
+
+impl InputTick {
+    /// Encode `curr` against `prev` as an `InputTickDelta` when every axis moved by at most
+    /// ±1.27, else as the full `InputTick`. Deltas are quantized, so the sender must keep
+    /// `prev.apply_delta(..)` of what it sent (not its exact input) as the next `prev`, or
+    /// the receiver's reconstruction drifts.
+    pub fn compress_delta(prev: &InputTick, curr: &InputTick) -> BatchedInputTick {
+        let step = |from: f32, to: f32| {
+            let steps = ((to - from) / INPUT_DELTA_STEP).round();
+            (steps.abs() <= i8::MAX as f32).then_some(steps as i8)
+        };
+        let deltas = (|| {
+            Some(InputTickDelta {
+                tick: curr.tick,
+                thrust_delta: step(prev.thrust, curr.thrust)?,
+                yaw_delta: step(prev.yaw, curr.yaw)?,
+                pump_fwd_delta: step(prev.pump_fwd, curr.pump_fwd)?,
+                pump_aft_delta: step(prev.pump_aft, curr.pump_aft)?,
+                pitch_delta: step(prev.pitch, curr.pitch)?,
+                roll_trim_delta: step(prev.roll_trim, curr.roll_trim)?,
+            })
+        })();
+        match deltas {
+            Some(delta) => BatchedInputTick::Delta(delta),
+            None => BatchedInputTick::Full(curr.clone()),
+        }
+    }
+
+    /// `ticks`, oldest first, as `InputTickBatch` entries: the first in full and each later
+    /// one compressed against the tick before it. Every batch stands alone, so a lost or late
+    /// datagram on the unreliable `Input` channel can't leave the server without a base.
+    pub fn encode_batch(ticks: &[InputTick]) -> Vec<BatchedInputTick> {
+        let mut prev: Option<InputTick> = None;
+        ticks
+            .iter()
+            .map(|curr| {
+                let entry = match &prev {
+                    Some(prev) => InputTick::compress_delta(prev, curr),
+                    None => BatchedInputTick::Full(curr.clone()),
+                };
+                // Chain from what the server will rebuild, not the exact input
+                prev = Some(match (&entry, &prev) {
+                    (BatchedInputTick::Delta(delta), Some(prev)) => prev.apply_delta(delta),
+                    _ => curr.clone(),
+                });
+                entry
+            })
+            .collect()
+    }
+
+    /// The ticks `encode_batch` packed into `entries`, oldest first. A delta with no entry
+    /// before it has no base and is dropped.
+    pub fn decode_batch(entries: &[BatchedInputTick]) -> Vec<InputTick> {
+        let mut ticks: Vec<InputTick> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let tick = match (entry, ticks.last()) {
+                (BatchedInputTick::Full(tick), _) => tick.clone(),
+                (BatchedInputTick::Delta(delta), Some(prev)) => prev.apply_delta(delta),
+                (BatchedInputTick::Delta(_), None) => continue,
+            };
+            ticks.push(tick);
+        }
+        ticks
+    }
+
+    /// The tick `delta` describes, reconstructed on top of this one.
+    pub fn apply_delta(&self, delta: &InputTickDelta) -> InputTick {
+        let apply = |base: f32, steps: i8| base + steps as f32 * INPUT_DELTA_STEP;
+        InputTick {
+            tick: delta.tick,
+            thrust: apply(self.thrust, delta.thrust_delta),
+            yaw: apply(self.yaw, delta.yaw_delta),
+            pump_fwd: apply(self.pump_fwd, delta.pump_fwd_delta),
+            pump_aft: apply(self.pump_aft, delta.pump_aft_delta),
+            pitch: apply(self.pitch, delta.pitch_delta),
+            roll_trim: apply(self.roll_trim, delta.roll_trim_delta),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InputAck {
     pub tick: u64,
+    /// The player's authoritative state at server tick `correction_tick`, attached to at most
+    /// one ack per snapshot so the client can check its prediction for that tick.
+    pub correction: Option<NetPlayer>,
+    pub correction_tick: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ping {
+    pub seq: u32,
+    /// Client clock (milliseconds, arbitrary origin) when sent; echoed in the response.
+    #[serde(default)]
+    pub client_send_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingResponse {
+    pub seq: u32,
+    /// `Ping::client_send_ms`, unchanged.
+    #[serde(default)]
+    pub client_send_ms: u64,
+    /// Server time (as in `StateDelta::server_ms`) when the ping arrived.
+    #[serde(default)]
+    pub server_recv_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,8 +463,28 @@ pub struct StateDelta {
     pub tick: u64,
     /// Server time in milliseconds since an arbitrary start (monotonic).
     pub server_ms: u64,
-    // Compact state for now; replace with snapshot diff when ready.
+    /// Players too far from `snapshot_origin` to quantize, at full precision.
     pub players: Vec<NetPlayer>,
+    /// Players within `SNAPSHOT_POSITION_RANGE_M` of `snapshot_origin` on every axis.
+    pub compressed_players: Vec<CompressedNetPlayer>,
+    /// World position `compressed_players` are quantized against.
+    pub snapshot_origin: [f32; 3],
+    /// Current centers of the level's moving obstacles.
+    pub obstacles: Vec<NetObstacle>,
+}
+
+impl StateDelta {
+    /// This snapshot with `compressed_players` expanded into `players`, for readers that only
+    /// want full-precision players.
+    pub fn into_decompressed(mut self) -> Self {
+        let origin = self.snapshot_origin;
+        self.players.extend(
+            self.compressed_players
+                .drain(..)
+                .map(|c| decompress_player(&c, origin)),
+        );
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +493,16 @@ pub struct NetInputState {
     pub yaw: f32,
     pub pump_fwd: f32,
     pub pump_aft: f32,
+    pub pitch: f32,
+    pub roll_trim: f32,
+}
+
+/// Turbo pump budget (`levels::PumpCooldownState`), so prediction restored from a snapshot
+/// pumps at the server's rate. Never spent by subs with standard pumps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetPumpState {
+    pub boost_remaining_s: f32,
+    pub cooldown_remaining_s: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,8 +513,142 @@ pub struct NetPlayer {
     /// Full body orientation as quaternion, [x, y, z, w] order.
     pub orientation: [f32; 4],
     pub ang_mom: [f32; 3],
+    /// Body-frame angular velocity (rad/s), so remotes can be turned between snapshots.
+    /// Adds 12 bytes per player per delta.
+    pub angular_velocity: [f32; 3],
     pub ballast_fill: Vec<f32>,
     pub input_state: NetInputState,
+    /// Remaining hull integrity in [0,1].
+    pub hull_integrity: f32,
+    pub team_id: u8,
+    #[serde(default)]
+    pub pump: NetPumpState,
+}
+
+/// Resolution of `CompressedNetPlayer::position`.
+pub const SNAPSHOT_POSITION_STEP_M: f32 = 0.001;
+/// Largest offset from `StateDelta::snapshot_origin`, per axis, an i16 position can hold.
+pub const SNAPSHOT_POSITION_RANGE_M: f32 = i16::MAX as f32 * SNAPSHOT_POSITION_STEP_M;
+
+/// `NetPlayer` with position and orientation quantized: 13 bytes instead of 28.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressedNetPlayer {
+    pub id: Uuid,
+    /// Offset from `StateDelta::snapshot_origin` in `SNAPSHOT_POSITION_STEP_M` units.
+    pub position: [i16; 3],
+    pub velocity: [f32; 3],
+    pub orientation: SmallestThreeQuat,
+    pub ang_mom: [f32; 3],
+    pub angular_velocity: [f32; 3],
+    pub ballast_fill: Vec<f32>,
+    pub input_state: NetInputState,
+    pub hull_integrity: f32,
+    pub team_id: u8,
+    #[serde(default)]
+    pub pump: NetPumpState,
+}
+
+/// Unit quaternion as its three smallest components; the largest is rebuilt from the unit
+/// norm. Each of the three lies in ±1/√2 and is stored as a u16 over that range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmallestThreeQuat {
+    /// Index into [x, y, z, w] of the dropped component, which is made non-negative.
+    pub largest: u8,
+    pub rest: [u16; 3],
+}
+
+impl SmallestThreeQuat {
+    pub fn from_xyzw(q: [f32; 4]) -> Self {
+        let norm = q.iter().map(|c| c * c).sum::<f32>().sqrt();
+        let q = if norm > 0.0 {
+            q.map(|c| c / norm)
+        } else {
+            [0.0, 0.0, 0.0, 1.0]
+        };
+        let largest = (0..4)
+            .max_by(|&a, &b| q[a].abs().total_cmp(&q[b].abs()))
+            .unwrap_or(3);
+        // q and -q are the same rotation; pick the sign that makes the dropped one positive
+        let sign = if q[largest] < 0.0 { -1.0 } else { 1.0 };
+        let mut rest = [0u16; 3];
+        for (slot, i) in (0..4).filter(|&i| i != largest).enumerate() {
+            let unit = (sign * q[i] * std::f32::consts::SQRT_2).clamp(-1.0, 1.0);
+            rest[slot] = ((unit + 1.0) * 0.5 * u16::MAX as f32).round() as u16;
+        }
+        Self {
+            largest: largest as u8,
+            rest,
+        }
+    }
+
+    pub fn to_xyzw(self) -> [f32; 4] {
+        let largest = (self.largest as usize).min(3);
+        let mut q = [0.0; 4];
+        let mut sum_sq = 0.0;
+        for (slot, i) in (0..4).filter(|&i| i != largest).enumerate() {
+            let unit = self.rest[slot] as f32 / u16::MAX as f32 * 2.0 - 1.0;
+            q[i] = unit * std::f32::consts::FRAC_1_SQRT_2;
+            sum_sq += q[i] * q[i];
+        }
+        q[largest] = (1.0 - sum_sq).max(0.0).sqrt();
+        q
+    }
+}
+
+/// Whether `position` is close enough to `origin` for `compress_player` to hold it.
+pub fn fits_snapshot_origin(position: [f32; 3], origin: [f32; 3]) -> bool {
+    position
+        .iter()
+        .zip(origin)
+        .all(|(p, o)| (p - o).abs() <= SNAPSHOT_POSITION_RANGE_M)
+}
+
+/// Quantize `p` against `origin`. Positions beyond `SNAPSHOT_POSITION_RANGE_M` on an axis clamp
+/// to it; check `fits_snapshot_origin` first.
+pub fn compress_player(p: &NetPlayer, origin: [f32; 3]) -> CompressedNetPlayer {
+    let quantize = |axis: usize| {
+        ((p.position[axis] - origin[axis]) / SNAPSHOT_POSITION_STEP_M)
+            .round()
+            .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+    };
+    CompressedNetPlayer {
+        id: p.id,
+        position: [quantize(0), quantize(1), quantize(2)],
+        velocity: p.velocity,
+        orientation: SmallestThreeQuat::from_xyzw(p.orientation),
+        ang_mom: p.ang_mom,
+        angular_velocity: p.angular_velocity,
+        ballast_fill: p.ballast_fill.clone(),
+        input_state: p.input_state.clone(),
+        hull_integrity: p.hull_integrity,
+        team_id: p.team_id,
+        pump: p.pump,
+    }
+}
+
+pub fn decompress_player(c: &CompressedNetPlayer, origin: [f32; 3]) -> NetPlayer {
+    NetPlayer {
+        id: c.id,
+        position: std::array::from_fn(|axis| {
+            origin[axis] + c.position[axis] as f32 * SNAPSHOT_POSITION_STEP_M
+        }),
+        velocity: c.velocity,
+        orientation: c.orientation.to_xyzw(),
+        ang_mom: c.ang_mom,
+        angular_velocity: c.angular_velocity,
+        ballast_fill: c.ballast_fill.clone(),
+        input_state: c.input_state.clone(),
+        hull_integrity: c.hull_integrity,
+        team_id: c.team_id,
+        pump: c.pump,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NetObstacle {
+    /// `MovingObstacleSpec::id` in the active level.
+    pub id: u32,
+    pub position: [f32; 3],
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -109,9 +656,30 @@ pub struct MineRequest {
     pub node_id: u32,
 }
 
+/// Wire mirror of `levels::ResourceType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResourceType {
+    Ore,
+    Crystal,
+    RareMineral,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MineAck {
     pub success: bool,
+    /// What the pass brought up; meaningless when `success` is false.
+    pub resource_type: ResourceType,
+    /// Kilograms added to the hold, possibly less than a full pass when the node ran low.
+    pub amount: u32,
+    /// The sub's hold, in kg per resource, after this pass.
+    pub inventory_after: HashMap<ResourceType, u32>,
+}
+
+/// The receiving player's hold changed other than by mining: unloaded at the dock, or restored
+/// on rejoin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InventoryUpdate {
+    pub inventory: HashMap<ResourceType, u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,11 +700,173 @@ pub struct PauseState {
     pub paused: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HullAlert {
+    pub integrity: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DepthWarning {
+    pub depth_m: f32,
+    /// The sub's `DiveDepthLimit::max_depth_m`.
+    pub max_depth_m: f32,
+    /// Deeper than `max_depth_m` rather than just inside the warning band.
+    pub exceeded: bool,
+    /// The server moved the sub back to a spawn point (`Config::eject_past_max_depth`).
+    pub ejected: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Input messages per second the server will apply; send no faster than this.
+    pub allowed_hz: u32,
+    pub excess_inputs_dropped: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickRateChange {
+    /// Server physics tick rate (Hz) from now on.
+    pub new_hz: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SonarPingRequest;
+
+/// Another player's sub a sonar ping reached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SonarContact {
+    pub player_id: Uuid,
+    pub position: [f32; 3],
+    pub distance_m: f32,
+}
+
+/// Every sub within the ping's detection range after refraction, nearest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SonarPingEcho {
+    pub contacts: Vec<SonarContact>,
+}
+
+/// Longest chat text, in UTF-8 bytes, the server relays.
+pub const MAX_CHAT_BYTES: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendChat {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub sender_id: Uuid,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub player_id: Uuid,
+    pub display_name: String,
+    pub credits: u64,
+    pub mines: u32,
+    pub docks: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardUpdate {
+    pub entries: Vec<LeaderboardEntry>,
+    pub team_scores: Vec<TeamScore>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamScore {
+    pub team_id: u8,
+    pub score: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamAssignment {
+    pub player_id: Uuid,
+    pub team_id: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamWin {
+    pub team_id: u8,
+    pub score: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntitySpawn {
+    /// Index into the level's `ore_nodes`.
+    pub node_id: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityDespawn {
+    /// Index into the level's `ore_nodes`.
+    pub node_id: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FireTorpedo {
+    /// Index into the sub's tubes (`SubPhysicsSpec::torpedo_tubes`).
+    pub tube_id: u8,
+    /// Launch direction in the horizontal plane relative to the bow; positive is to starboard.
+    pub target_bearing_deg: f32,
+    /// Launch speed relative to the sub, clamped to `TORPEDO_MAX_SPEED_M_S`; 0 picks
+    /// `TORPEDO_SPEED_M_S`.
+    #[serde(default)]
+    pub speed_mps: f32,
+    /// World yaw in degrees (`+yaw` to starboard, 0 along +Z) to launch along instead of
+    /// `target_bearing_deg` from the bow.
+    #[serde(default)]
+    pub heading_override: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorpedoSpawned {
+    pub id: u32,
+    pub origin: [f32; 3],
+    /// Unit travel direction.
+    pub direction: [f32; 3],
+    /// Launch speed including the firing sub's velocity; slowed by `torpedo_drag` from there.
+    #[serde(default)]
+    pub speed_m_s: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorpedoDetonation {
+    pub id: u32,
+    pub position: [f32; 3],
+    pub radius_m: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelReload {
+    /// Index into the campaign's `levels`.
+    pub map_id: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissionUpdate {
+    /// `Mission::id` in the shared mission list.
+    pub mission_id: u32,
+    pub progress: u32,
+    pub total: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissionComplete {
+    pub mission_id: u32,
+    /// Credits added to the player's score.
+    pub reward: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DisconnectReason {
     IncompatibleProtocol { server: u16, client: u16 },
     Kicked,
     ServerShutdown,
+    /// Another connected client already plays as the `ClientHello::player_id` sent.
+    IdentityInUse,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,21 +877,670 @@ pub struct InputEvent {
     pub yaw: f32,
     pub pump_fwd: f32,
     pub pump_aft: f32,
+    /// Dive plane deflection in [-1,1], as in `InputTick`.
+    pub pitch: f32,
+    /// Differential plane trim in [-1,1], as in `InputTick`.
+    pub roll_trim: f32,
+}
+
+/// Bytes of `MessageHeader` in front of every encoded message.
+pub const HEADER_LEN: usize = 4;
+
+/// `[version: u16 LE, msg_type: u8, reserved: u8]` in front of each MessagePack payload, so a
+/// peer built against another version is rejected before its payload is parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageHeader {
+    pub version: u16,
+    /// `WireMessage::msg_type` of the payload.
+    pub msg_type: u8,
+}
+
+/// A message `encode` can frame. Its `msg_type` goes in the `MessageHeader` and is checked
+/// against the decoded payload.
+pub trait WireMessage: Serialize + for<'de> Deserialize<'de> {
+    /// Fixed per variant; never renumber one, as peers of the same version compare it.
+    fn msg_type(&self) -> u8;
 }
 
-// Simple helpers for bincode encoding.
-pub fn encode<T: Serialize>(msg: &T) -> Result<Vec<u8>, bincode::Error> {
-    bincode::serialize(msg)
+impl MessageHeader {
+    /// Read the header of an encoded message without decoding its payload.
+    pub fn parse(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let Some([v0, v1, msg_type, _reserved]) = bytes.first_chunk::<HEADER_LEN>() else {
+            return Err(DecodeError::Truncated(bytes.len()));
+        };
+        Ok(Self {
+            version: u16::from_le_bytes([*v0, *v1]),
+            msg_type: *msg_type,
+        })
+    }
+
+    fn to_bytes(self) -> [u8; HEADER_LEN] {
+        let [v0, v1] = self.version.to_le_bytes();
+        [v0, v1, self.msg_type, 0]
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    /// The sender was built against a different `PROTOCOL_VERSION`.
+    #[error("protocol version mismatch: found {found}, expected {expected}")]
+    VersionMismatch { found: u16, expected: u16 },
+    #[error("message of {0} bytes is shorter than its header")]
+    Truncated(usize),
+    #[error("header says message type {header}, payload is type {payload}")]
+    MessageTypeMismatch { header: u8, payload: u8 },
+    #[error("malformed payload: {0}")]
+    Payload(#[from] rmp_serde::decode::Error),
+    #[error("{0} bytes left over after the payload")]
+    TrailingBytes(usize),
+    #[cfg(feature = "legacy")]
+    #[error("malformed legacy payload: {0}")]
+    Legacy(#[from] bincode::Error),
 }
 
-pub fn decode<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Result<T, bincode::Error> {
-    bincode::deserialize(bytes)
+#[derive(Debug, thiserror::Error)]
+pub enum ProtocolError {
+    #[error("failed to encode message: {0}")]
+    Encode(#[source] rmp_serde::encode::Error),
+    #[error(transparent)]
+    Decode(#[from] DecodeError),
 }
 
-/// AOI Note (not implemented):
-/// For underground 3D spaces, an octree spatial partition is the natural fit
-/// for culling StateDelta payloads; a quadtree only partitions 2D space. An
-/// octree allows pruning by 3D bounds and better matches cave volumes. We can
-/// start with a simple uniform grid (XYZ bins) and evolve to an octree when
-/// entity counts warrant. Leaving the specific structure undefined for now.
-pub struct Nothing {}
+/// `msg` as MessagePack behind a `MessageHeader` for `PROTOCOL_VERSION`.
+pub fn encode<T: WireMessage>(msg: &T) -> Result<Vec<u8>, ProtocolError> {
+    let mut buf = Vec::new();
+    encode_into(msg, &mut buf)?;
+    Ok(buf)
+}
+
+/// Like `encode`, but clears and refills `buf` so a hot path can keep one allocation alive.
+pub fn encode_into<T: WireMessage>(msg: &T, buf: &mut Vec<u8>) -> Result<(), ProtocolError> {
+    buf.clear();
+    buf.extend_from_slice(&[0; HEADER_LEN]);
+    rmp_serde::encode::write(buf, msg).map_err(ProtocolError::Encode)?;
+    let header = MessageHeader {
+        version: PROTOCOL_VERSION,
+        msg_type: msg.msg_type(),
+    };
+    buf[..HEADER_LEN].copy_from_slice(&header.to_bytes());
+    Ok(())
+}
+
+/// Check the header written by `encode`, then decode the payload.
+pub fn decode<T: WireMessage>(bytes: &[u8]) -> Result<T, ProtocolError> {
+    let header = MessageHeader::parse(bytes)?;
+    if header.version != PROTOCOL_VERSION {
+        return Err(DecodeError::VersionMismatch {
+            found: header.version,
+            expected: PROTOCOL_VERSION,
+        }
+        .into());
+    }
+    let mut payload = &bytes[HEADER_LEN..];
+    let msg = T::deserialize(&mut rmp_serde::Deserializer::new(&mut payload))
+        .map_err(DecodeError::from)?;
+    if !payload.is_empty() {
+        return Err(DecodeError::TrailingBytes(payload.len()).into());
+    }
+    let payload = msg.msg_type();
+    if payload != header.msg_type {
+        return Err(DecodeError::MessageTypeMismatch {
+            header: header.msg_type,
+            payload,
+        }
+        .into());
+    }
+    Ok(msg)
+}
+
+/// Counterpart to `encode_into`; identical to `decode`.
+pub fn decode_from_slice<T: WireMessage>(bytes: &[u8]) -> Result<T, ProtocolError> {
+    decode(bytes)
+}
+
+/// The version a message was sent under, read without decoding it. Both `MessageHeader` and
+/// the legacy header start with it.
+pub fn wire_version(bytes: &[u8]) -> Option<u16> {
+    bytes.first_chunk().copied().map(u16::from_le_bytes)
+}
+
+/// Decode a client message sent under `PROTOCOL_VERSION` or, with the `legacy` feature,
+/// `LEGACY_PROTOCOL_VERSION`; legacy messages are upgraded to the current types.
+pub fn decode_client_message(bytes: &[u8]) -> Result<ClientToServer, ProtocolError> {
+    #[cfg(feature = "legacy")]
+    if wire_version(bytes) == Some(LEGACY_PROTOCOL_VERSION) {
+        return Ok(legacy::decode_v20(bytes)?);
+    }
+    decode(bytes)
+}
+
+// AOI: the server culls each client's StateDelta with a uniform grid of cubic
+// cells (`server::aoi::AoiGrid`). For underground 3D spaces an octree is the
+// natural next step, pruning by 3D bounds to match cave volumes, once entity
+// counts warrant it.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaderboard_update_roundtrips() {
+        let entries: Vec<LeaderboardEntry> = ["alpha", "bravo", "charlie"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| LeaderboardEntry {
+                player_id: Uuid::new_v4(),
+                display_name: name.to_string(),
+                credits: 1000 * i as u64,
+                mines: i as u32,
+                docks: 2 * i as u32,
+            })
+            .collect();
+        let team_scores = vec![
+            TeamScore {
+                team_id: 0,
+                score: 1000,
+            },
+            TeamScore {
+                team_id: 1,
+                score: 2000,
+            },
+        ];
+        let msg = ServerToClient::LeaderboardUpdate(LeaderboardUpdate {
+            entries: entries.clone(),
+            team_scores: team_scores.clone(),
+        });
+        let bytes = encode(&msg).unwrap();
+        match decode::<ServerToClient>(&bytes).unwrap() {
+            ServerToClient::LeaderboardUpdate(update) => {
+                assert_eq!(update.entries, entries);
+                assert_eq!(update.team_scores, team_scores);
+            }
+            other => panic!("unexpected message {other:?}"),
+        }
+    }
+
+    #[test]
+    fn input_ticks_round_trip_as_deltas() {
+        let mut sent = InputTick {
+            tick: 0,
+            thrust: 0.5,
+            yaw: -0.2,
+            pump_fwd: 0.0,
+            pump_aft: 1.0,
+            pitch: 0.0,
+            roll_trim: 0.25,
+        };
+        for tick in 1..=100u64 {
+            let sign = if tick % 2 == 0 { 1.0 } else { -1.0 };
+            let curr = InputTick {
+                tick,
+                thrust: sent.thrust + 0.01,
+                yaw: sent.yaw - 0.01,
+                pump_fwd: sent.pump_fwd + sign * 0.01,
+                pump_aft: sent.pump_aft - sign * 0.01,
+                pitch: sent.pitch + sign * 0.01,
+                roll_trim: sent.roll_trim,
+            };
+            let entry = InputTick::compress_delta(&sent, &curr);
+            let BatchedInputTick::Delta(delta) = entry else {
+                panic!("tick {tick} was not delta encoded: {entry:?}");
+            };
+            assert_eq!(delta.thrust_delta, 1);
+            assert_eq!(delta.yaw_delta, -1);
+            let rebuilt = sent.apply_delta(&delta);
+            assert_eq!(rebuilt.tick, tick);
+            for (got, want) in [
+                (rebuilt.thrust, curr.thrust),
+                (rebuilt.yaw, curr.yaw),
+                (rebuilt.pump_fwd, curr.pump_fwd),
+                (rebuilt.pump_aft, curr.pump_aft),
+                (rebuilt.pitch, curr.pitch),
+                (rebuilt.roll_trim, curr.roll_trim),
+            ] {
+                assert!((got - want).abs() < 1e-4, "tick {tick}: {got} vs {want}");
+            }
+            sent = rebuilt;
+        }
+        // No drift after 100 quantized steps
+        assert!((sent.thrust - 1.5).abs() < 1e-3);
+
+        // Too large a jump for an i8 falls back to the full tick
+        let jump = InputTick {
+            tick: 101,
+            thrust: sent.thrust - 2.0,
+            ..sent.clone()
+        };
+        assert!(matches!(
+            InputTick::compress_delta(&sent, &jump),
+            BatchedInputTick::Full(InputTick { tick: 101, .. })
+        ));
+    }
+
+    #[test]
+    fn full_input_batch_round_trips_in_order_within_one_datagram() {
+        let ticks: Vec<_> = (1..=MAX_INPUT_BATCH as u64)
+            .map(|tick| InputTick {
+                tick,
+                thrust: tick as f32 / 10.0,
+                yaw: -0.5,
+                pump_fwd: 0.0,
+                pump_aft: 1.0,
+                pitch: 0.0,
+                roll_trim: 0.0,
+            })
+            .collect();
+        let entries = InputTick::encode_batch(&ticks);
+        assert!(matches!(entries[0], BatchedInputTick::Full(_)));
+        assert!(entries[1..]
+            .iter()
+            .all(|e| matches!(e, BatchedInputTick::Delta(_))));
+        let bytes = encode(&ClientToServer::InputTickBatch(entries)).unwrap();
+        // Well under a 1200 byte datagram, so renet never has to fragment a batch
+        assert!(bytes.len() < 1200, "{} bytes", bytes.len());
+        let full = ticks.iter().cloned().map(BatchedInputTick::Full).collect();
+        let full_bytes = encode(&ClientToServer::InputTickBatch(full)).unwrap();
+        assert!(
+            bytes.len() * 3 < full_bytes.len() * 2,
+            "{} bytes as deltas, {} in full",
+            bytes.len(),
+            full_bytes.len()
+        );
+        let ClientToServer::InputTickBatch(decoded) = decode(&bytes).unwrap() else {
+            panic!("batch decoded as another message");
+        };
+        let decoded = InputTick::decode_batch(&decoded);
+        assert_eq!(decoded.len(), ticks.len());
+        for (got, want) in decoded.iter().zip(&ticks) {
+            assert_eq!(got.tick, want.tick);
+            assert!(
+                (got.thrust - want.thrust).abs() < 1e-4,
+                "tick {}",
+                want.tick
+            );
+            assert_eq!((got.yaw, got.pump_aft), (want.yaw, want.pump_aft));
+        }
+    }
+
+    #[test]
+    fn input_batch_falls_back_to_full_ticks_on_large_jumps() {
+        let tick = |tick, thrust| InputTick {
+            tick,
+            thrust,
+            yaw: 0.0,
+            pump_fwd: 0.0,
+            pump_aft: 0.0,
+            pitch: 0.0,
+            roll_trim: 0.0,
+        };
+        // Full reverse to full ahead doesn't fit an i8 delta
+        let ticks = [tick(1, -1.0), tick(2, 1.0), tick(3, 0.99)];
+        let entries = InputTick::encode_batch(&ticks);
+        assert!(matches!(
+            entries[..],
+            [
+                BatchedInputTick::Full(_),
+                BatchedInputTick::Full(_),
+                BatchedInputTick::Delta(InputTickDelta {
+                    thrust_delta: -1,
+                    ..
+                })
+            ]
+        ));
+        let decoded = InputTick::decode_batch(&entries);
+        assert_eq!(decoded[1].thrust, 1.0);
+        assert!((decoded[2].thrust - 0.99).abs() < 1e-4);
+
+        // A delta with nothing before it can't be rebuilt
+        assert!(InputTick::decode_batch(&entries[2..]).is_empty());
+    }
+
+    #[test]
+    fn state_delta_round_trips_angular_velocity() {
+        for angular_velocity in [[0.0; 3], [0.1, -0.75, 2.5], [-3.0, 1e-6, f32::MAX]] {
+            let msg = ServerToClient::StateDelta(StateDelta {
+                tick: 7,
+                server_ms: 1234,
+                players: vec![NetPlayer {
+                    id: Uuid::new_v4(),
+                    position: [1.0, -2.0, 3.0],
+                    velocity: [0.5, 0.0, -0.5],
+                    orientation: [0.0, 0.0, 0.0, 1.0],
+                    ang_mom: [0.0, 4.0, 0.0],
+                    angular_velocity,
+                    ballast_fill: vec![0.5, 0.5],
+                    input_state: NetInputState {
+                        thrust: 0.0,
+                        yaw: 0.0,
+                        pump_fwd: 0.0,
+                        pump_aft: 0.0,
+                        pitch: 0.0,
+                        roll_trim: 0.0,
+                    },
+                    hull_integrity: 1.0,
+                    team_id: 0,
+                    pump: Default::default(),
+                }],
+                compressed_players: Vec::new(),
+                snapshot_origin: [0.0; 3],
+                obstacles: Vec::new(),
+            });
+            let ServerToClient::StateDelta(delta) =
+                decode::<ServerToClient>(&encode(&msg).unwrap()).unwrap()
+            else {
+                panic!("not a state delta");
+            };
+            assert_eq!(delta.players[0].angular_velocity, angular_velocity);
+        }
+    }
+
+    #[test]
+    fn encode_into_matches_encode_and_reuses_buffer() {
+        let msg = ServerToClient::InputAck(InputAck {
+            tick: 42,
+            correction: None,
+            correction_tick: 0,
+        });
+        let mut buf = vec![0xAA; 64];
+        let capacity = buf.capacity();
+        encode_into(&msg, &mut buf).unwrap();
+        assert_eq!(buf, encode(&msg).unwrap());
+        assert_eq!(buf.capacity(), capacity);
+        assert!(matches!(
+            decode_from_slice::<ServerToClient>(&buf).unwrap(),
+            ServerToClient::InputAck(InputAck { tick: 42, .. })
+        ));
+    }
+
+    #[test]
+    fn versioned_messages_reject_other_versions_and_truncation() {
+        let msg = ServerToClient::PingResponse(PingResponse {
+            seq: 7,
+            client_send_ms: 1500,
+            server_recv_ms: 900,
+        });
+        let bytes = encode(&msg).unwrap();
+        let header = MessageHeader::parse(&bytes).unwrap();
+        assert_eq!(header.version, PROTOCOL_VERSION);
+        assert_eq!(bytes[3], 0);
+        assert!(matches!(
+            decode::<ServerToClient>(&bytes),
+            Ok(ServerToClient::PingResponse(PingResponse {
+                seq: 7,
+                client_send_ms: 1500,
+                server_recv_ms: 900
+            }))
+        ));
+
+        let mut old = bytes.clone();
+        old[..2].copy_from_slice(&(PROTOCOL_VERSION - 1).to_le_bytes());
+        assert!(matches!(
+            decode::<ServerToClient>(&old),
+            Err(ProtocolError::Decode(DecodeError::VersionMismatch { found, expected }))
+                if found == PROTOCOL_VERSION - 1 && expected == PROTOCOL_VERSION
+        ));
+        for len in [0, 1, HEADER_LEN - 1] {
+            assert!(matches!(
+                decode::<ServerToClient>(&bytes[..len]),
+                Err(ProtocolError::Decode(DecodeError::Truncated(_)))
+            ));
+        }
+        for len in HEADER_LEN..bytes.len() {
+            assert!(matches!(
+                decode::<ServerToClient>(&bytes[..len]),
+                Err(ProtocolError::Decode(DecodeError::Payload(_)))
+            ));
+        }
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            decode::<ServerToClient>(&trailing),
+            Err(ProtocolError::Decode(DecodeError::TrailingBytes(1)))
+        ));
+    }
+
+    #[test]
+    fn older_messages_decode_without_trailing_default_fields() {
+        #[derive(Serialize, Deserialize)]
+        struct Old {
+            id: u64,
+        }
+        #[derive(Serialize, Deserialize)]
+        struct New {
+            id: u64,
+            #[serde(default)]
+            tags: Vec<String>,
+        }
+        impl WireMessage for Old {
+            fn msg_type(&self) -> u8 {
+                0
+            }
+        }
+        impl WireMessage for New {
+            fn msg_type(&self) -> u8 {
+                0
+            }
+        }
+        let new: New = decode(&encode(&Old { id: 3 }).unwrap()).unwrap();
+        assert_eq!((new.id, new.tags.len()), (3, 0));
+    }
+
+    #[test]
+    fn header_names_the_message_variant() {
+        let pong = encode(&ServerToClient::PingResponse(PingResponse {
+            seq: 7,
+            client_send_ms: 0,
+            server_recv_ms: 0,
+        }))
+        .unwrap();
+        let shutdown = encode(&ServerToClient::Disconnect(
+            DisconnectReason::ServerShutdown,
+        ))
+        .unwrap();
+        let pong_type = MessageHeader::parse(&pong).unwrap().msg_type;
+        let shutdown_type = MessageHeader::parse(&shutdown).unwrap().msg_type;
+        assert_eq!((pong_type, shutdown_type), (13, 6));
+        let hello = encode(&ClientToServer::Hello(ClientHello {
+            protocol: PROTOCOL_VERSION,
+            player_id: Uuid::nil(),
+            display_name: None,
+            requested_features: FeatureFlags::NONE,
+        }))
+        .unwrap();
+        assert_eq!(MessageHeader::parse(&hello).unwrap().msg_type, 0);
+
+        // A payload that does not match its header is corrupt
+        let mut relabelled = pong.clone();
+        relabelled[2] = shutdown_type;
+        assert!(matches!(
+            decode::<ServerToClient>(&relabelled),
+            Err(ProtocolError::Decode(DecodeError::MessageTypeMismatch { header, payload }))
+                if header == shutdown_type && payload == pong_type
+        ));
+    }
+
+    #[cfg(feature = "legacy")]
+    #[test]
+    fn client_messages_decode_under_both_current_and_legacy_versions() {
+        let tick = InputTick {
+            tick: 9,
+            thrust: 0.5,
+            yaw: -0.25,
+            pump_fwd: 1.0,
+            pump_aft: 0.0,
+            pitch: -1.0,
+            roll_trim: 0.0,
+        };
+        let legacy = legacy::ClientToServerV20::InputTickBatch(vec![tick.clone()]);
+        let mut bytes = legacy::encode(&legacy).unwrap();
+        let msg = decode_client_message(&bytes).unwrap();
+        let ClientToServer::InputTickBatch(batch) = msg else {
+            panic!("legacy batch decoded as {msg:?}");
+        };
+        let ticks = InputTick::decode_batch(&batch);
+        assert_eq!(ticks.len(), 1);
+        assert_eq!(
+            (ticks[0].tick, ticks[0].thrust, ticks[0].pitch),
+            (9, 0.5, -1.0)
+        );
+
+        let fire = legacy::ClientToServerV20::FireTorpedo(legacy::FireTorpedoV20 {
+            tube_id: 1,
+            target_bearing_deg: 15.0,
+        });
+        let msg = decode_client_message(&legacy::encode(&fire).unwrap()).unwrap();
+        assert!(matches!(
+            msg,
+            ClientToServer::FireTorpedo(FireTorpedo { tube_id: 1, speed_mps, heading_override: None, .. })
+                if speed_mps == 0.0
+        ));
+
+        let current = ClientToServer::InputTick(tick);
+        assert!(matches!(
+            decode_client_message(&encode(&current).unwrap()).unwrap(),
+            ClientToServer::InputTick(InputTick { tick: 9, pitch, .. }) if pitch == -1.0
+        ));
+
+        // Replies to a legacy client round-trip in its encoding
+        let reply = ServerToClient::Disconnect(DisconnectReason::ServerShutdown);
+        let reply = legacy::decode::<ServerToClient>(&legacy::encode(&reply).unwrap()).unwrap();
+        assert!(matches!(
+            reply,
+            ServerToClient::Disconnect(DisconnectReason::ServerShutdown)
+        ));
+
+        // Anything older than the migration window is still rejected
+        bytes[..legacy::LEGACY_HEADER_LEN]
+            .copy_from_slice(&(LEGACY_PROTOCOL_VERSION - 1).to_le_bytes());
+        assert!(matches!(
+            decode_client_message(&bytes),
+            Err(ProtocolError::Decode(DecodeError::VersionMismatch { found, .. }))
+                if found == LEGACY_PROTOCOL_VERSION - 1
+        ));
+    }
+
+    /// Rotation angle (rad) between two unit quaternions in [x, y, z, w] order.
+    fn quat_angle(a: [f32; 4], b: [f32; 4]) -> f32 {
+        // Vector part of conj(a) * b; its length is sin(angle / 2)
+        let [ax, ay, az, aw] = a;
+        let [bx, by, bz, bw] = b;
+        let x = aw * bx - ax * bw - ay * bz + az * by;
+        let y = aw * by - ay * bw - az * bx + ax * bz;
+        let z = aw * bz - az * bw - ax * by + ay * bx;
+        let w = aw * bw + ax * bx + ay * by + az * bz;
+        2.0 * (x * x + y * y + z * z).sqrt().atan2(w.abs())
+    }
+
+    #[test]
+    fn compressed_players_round_trip_within_tolerance() {
+        let origin = [120.0, -35.0, 260.0];
+        let mut seed = 0x2545_f491_u32;
+        let mut rand = || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0
+        };
+        let mut max_pos_err = 0.0f32;
+        let mut max_angle = 0.0f32;
+        for _ in 0..10_000 {
+            let offset = [rand(), rand(), rand()].map(|v| v * SNAPSHOT_POSITION_RANGE_M);
+            let q = [rand(), rand(), rand(), rand()];
+            let norm = q.iter().map(|c| c * c).sum::<f32>().sqrt().max(1e-3);
+            let player = NetPlayer {
+                id: Uuid::nil(),
+                position: std::array::from_fn(|i| origin[i] + offset[i]),
+                velocity: [1.0, 2.0, 3.0],
+                orientation: q.map(|c| c / norm),
+                ang_mom: [0.0; 3],
+                angular_velocity: [0.0; 3],
+                ballast_fill: vec![0.5, 0.5],
+                input_state: NetInputState {
+                    thrust: 0.0,
+                    yaw: 0.0,
+                    pump_fwd: 0.0,
+                    pump_aft: 0.0,
+                    pitch: 0.0,
+                    roll_trim: 0.0,
+                },
+                hull_integrity: 1.0,
+                team_id: 1,
+                pump: NetPumpState {
+                    boost_remaining_s: 2.5,
+                    cooldown_remaining_s: 0.75,
+                },
+            };
+            assert!(fits_snapshot_origin(player.position, origin));
+            let rebuilt = decompress_player(&compress_player(&player, origin), origin);
+            for (got, want) in rebuilt.position.iter().zip(player.position) {
+                max_pos_err = max_pos_err.max((got - want).abs());
+            }
+            max_angle = max_angle.max(quat_angle(rebuilt.orientation, player.orientation));
+            assert_eq!(rebuilt.velocity, player.velocity);
+            assert_eq!(rebuilt.pump, player.pump);
+        }
+        assert!(max_pos_err < 0.002, "position error {max_pos_err} m");
+        assert!(max_angle < 0.001, "orientation error {max_angle} rad");
+    }
+
+    #[test]
+    fn state_delta_expands_compressed_players_after_decoding() {
+        let player = |id: u128, position: [f32; 3]| NetPlayer {
+            id: Uuid::from_u128(id),
+            position,
+            velocity: [0.0; 3],
+            orientation: [0.0, 0.38268343, 0.0, 0.9238795],
+            ang_mom: [0.0; 3],
+            angular_velocity: [0.0; 3],
+            ballast_fill: vec![0.5, 0.5],
+            input_state: NetInputState {
+                thrust: 0.0,
+                yaw: 0.0,
+                pump_fwd: 0.0,
+                pump_aft: 0.0,
+                pitch: 0.0,
+                roll_trim: 0.0,
+            },
+            hull_integrity: 1.0,
+            team_id: 0,
+            pump: Default::default(),
+        };
+        let origin = [10.0, -20.0, 30.0];
+        let near = player(1, [12.5, -21.0, 29.0]);
+        let far = player(2, [10.0, -20.0, 30.0 + 2.0 * SNAPSHOT_POSITION_RANGE_M]);
+        assert!(!fits_snapshot_origin(far.position, origin));
+        let delta = StateDelta {
+            tick: 3,
+            server_ms: 100,
+            players: vec![far.clone()],
+            compressed_players: vec![compress_player(&near, origin)],
+            snapshot_origin: origin,
+            obstacles: Vec::new(),
+        };
+        let full = StateDelta {
+            players: vec![far, near.clone()],
+            compressed_players: Vec::new(),
+            ..delta.clone()
+        };
+        let bytes = encode(&ServerToClient::StateDelta(delta)).unwrap();
+        assert!(bytes.len() < encode(&ServerToClient::StateDelta(full)).unwrap().len());
+
+        let ServerToClient::StateDelta(decoded) = decode::<ServerToClient>(&bytes).unwrap() else {
+            panic!("not a state delta");
+        };
+        let decoded = decoded.into_decompressed();
+        assert!(decoded.compressed_players.is_empty());
+        assert_eq!(decoded.players.len(), 2);
+        let rebuilt = decoded.players.iter().find(|p| p.id == near.id).unwrap();
+        for (got, want) in rebuilt.position.iter().zip(near.position) {
+            assert!((got - want).abs() < 0.002);
+        }
+        assert!(quat_angle(rebuilt.orientation, near.orientation) < 0.001);
+    }
+
+    #[test]
+    fn torpedo_drag_is_independent_of_step_size() {
+        let once = torpedo_drag(TORPEDO_SPEED_M_S, 1.0);
+        let stepped = (0..60).fold(TORPEDO_SPEED_M_S, |v, _| torpedo_drag(v, 1.0 / 60.0));
+        assert!((once - stepped).abs() < 1e-4, "{once} vs {stepped}");
+        assert!(once < TORPEDO_SPEED_M_S && once > 0.0);
+    }
+}
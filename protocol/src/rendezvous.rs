@@ -0,0 +1,136 @@
+//! UDP rendezvous for NAT hole punching. A server and its clients register under a shared
+//! session token with a rendezvous service on a public address; each client then queries for
+//! the server's address as the rendezvous saw it and sends a few packets there before starting
+//! netcode, so its NAT lets the replies back in.
+
+use std::net::{SocketAddr, ToSocketAddrs};
+
+use serde::{Deserialize, Serialize};
+
+pub type SessionToken = [u8; 32];
+
+/// `--server` prefix selecting hole punching: `rendezvous://host:port/<64 hex digit token>`.
+pub const RENDEZVOUS_SCHEME: &str = "rendezvous://";
+
+/// Default UDP port of the rendezvous service.
+pub const DEFAULT_RENDEZVOUS_PORT: u16 = 61300;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RendezvousMessage {
+    /// Record the sender under `token`. Without `external_addr` the rendezvous stores the
+    /// address the packet came from, which is the sender's NAT mapping.
+    Register {
+        token: SessionToken,
+        external_addr: Option<SocketAddr>,
+    },
+    /// Ask for the other peer registered under `token`.
+    Query { token: SessionToken },
+    /// Reply to `Query`; `None` until the other peer has registered.
+    Peer {
+        token: SessionToken,
+        addr: Option<SocketAddr>,
+    },
+}
+
+impl RendezvousMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        rmp_serde::to_vec(self).expect("rendezvous messages always encode")
+    }
+
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        rmp_serde::from_slice(bytes).ok()
+    }
+}
+
+/// Where to meet peers and which session to meet them in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HolePunchConfig {
+    pub rendezvous_addr: SocketAddr,
+    pub session_token: SessionToken,
+}
+
+impl HolePunchConfig {
+    /// Parse `rendezvous://host[:port]/<token>`; `None` if `url` does not use the scheme.
+    pub fn parse(url: &str) -> Option<Result<Self, String>> {
+        let rest = url.strip_prefix(RENDEZVOUS_SCHEME)?;
+        Some(Self::parse_rest(rest))
+    }
+
+    fn parse_rest(rest: &str) -> Result<Self, String> {
+        let (host, token) = rest
+            .split_once('/')
+            .ok_or_else(|| format!("missing session token in {rest:?}"))?;
+        let session_token = parse_token(token)?;
+        let host = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{host}:{DEFAULT_RENDEZVOUS_PORT}")
+        };
+        let rendezvous_addr = host
+            .to_socket_addrs()
+            .map_err(|err| format!("resolving {host}: {err}"))?
+            .next()
+            .ok_or_else(|| format!("{host} resolved to no address"))?;
+        Ok(Self {
+            rendezvous_addr,
+            session_token,
+        })
+    }
+
+    pub fn to_url(&self) -> String {
+        let token: String = self
+            .session_token
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        format!("{RENDEZVOUS_SCHEME}{}/{token}", self.rendezvous_addr)
+    }
+}
+
+fn parse_token(hex: &str) -> Result<SessionToken, String> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(format!(
+            "session token must be 64 hex digits, got {}",
+            hex.len()
+        ));
+    }
+    let mut token = [0u8; 32];
+    for (i, byte) in token.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)
+            .map_err(|_| format!("session token is not hex: {hex:?}"))?;
+    }
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_round_trips_through_url() {
+        let cfg = HolePunchConfig {
+            rendezvous_addr: "127.0.0.1:4000".parse().unwrap(),
+            session_token: std::array::from_fn(|i| i as u8 * 7),
+        };
+        assert_eq!(HolePunchConfig::parse(&cfg.to_url()), Some(Ok(cfg)));
+        assert_eq!(HolePunchConfig::parse("127.0.0.1:61234"), None);
+    }
+
+    #[test]
+    fn port_defaults_and_bad_tokens_are_rejected() {
+        let url = format!("{RENDEZVOUS_SCHEME}127.0.0.1/{}", "ab".repeat(32));
+        let cfg = HolePunchConfig::parse(&url).unwrap().unwrap();
+        assert_eq!(cfg.rendezvous_addr.port(), DEFAULT_RENDEZVOUS_PORT);
+        assert_eq!(cfg.session_token, [0xab; 32]);
+
+        for bad in ["127.0.0.1:4000", "127.0.0.1:4000/abc", "127.0.0.1:4000/"] {
+            let url = format!("{RENDEZVOUS_SCHEME}{bad}");
+            assert!(
+                matches!(HolePunchConfig::parse(&url), Some(Err(_))),
+                "{bad}"
+            );
+        }
+        let non_hex = format!("{RENDEZVOUS_SCHEME}127.0.0.1:4000/{}", "zz".repeat(32));
+        assert!(matches!(HolePunchConfig::parse(&non_hex), Some(Err(_))));
+    }
+}
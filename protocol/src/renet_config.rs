@@ -0,0 +1,44 @@
+//! renet's `ConnectionConfig` built from `default_channel_configs`. Client and server both
+//! take it from here, so their channel ids and send types cannot drift apart.
+
+use std::time::Duration;
+
+use renet::{ChannelConfig as RenetChannelConfig, ConnectionConfig, SendType};
+
+use crate::{default_channel_configs, ChannelConfig, ChannelSendType};
+
+/// Resend interval for the reliable channels.
+const RELIABLE_RESEND: Duration = Duration::from_millis(300);
+
+/// The shared channel layout, each channel's byte budget scaled by `budget_multiplier`.
+/// Clients pass 1.0; the server passes its `channel_budget_multiplier`.
+pub fn connection_config(budget_multiplier: f32) -> ConnectionConfig {
+    let channels = default_channel_configs();
+    let to_renet = |c: &ChannelConfig| RenetChannelConfig {
+        channel_id: c.id,
+        max_memory_usage_bytes: ((c.packet_budget_bytes as f32 * budget_multiplier) as usize)
+            .max(1),
+        send_type: match c.send_type {
+            ChannelSendType::Unreliable => SendType::Unreliable,
+            ChannelSendType::ReliableOrdered => SendType::ReliableOrdered {
+                resend_time: RELIABLE_RESEND,
+            },
+            ChannelSendType::ReliableUnordered => SendType::ReliableUnordered {
+                resend_time: RELIABLE_RESEND,
+            },
+        },
+    };
+    ConnectionConfig {
+        server_channels_config: channels
+            .iter()
+            .filter(|c| c.sent_by_server())
+            .map(to_renet)
+            .collect(),
+        client_channels_config: channels
+            .iter()
+            .filter(|c| c.sent_by_client())
+            .map(to_renet)
+            .collect(),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,92 @@
+//! Physical sanity checks on each player's sub. A sub moving faster than twice its terminal
+//! speed is slowed back to that limit every physics tick, and its client is kicked after
+//! `VELOCITY_STRIKES_TO_KICK` such ticks in a row, then refused for `KICK_COOLDOWN`. Control
+//! inputs outside [-1, 1] are applied clamped but counted.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use levels::{SubPhysicsSpec, Vec3f};
+use protocol::InputTick;
+use uuid::Uuid;
+
+/// Multiple of the terminal speed a sub may reach before it is clamped.
+pub const TERMINAL_SPEED_MARGIN: f32 = 2.0;
+/// Consecutive over-speed physics ticks that get a client kicked.
+pub const VELOCITY_STRIKES_TO_KICK: u32 = 10;
+/// How long a kicked player's id is refused when it says `ClientHello` again.
+pub const KICK_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// Fastest speed (m/s) a sub with `spec` can plausibly reach: `TERMINAL_SPEED_MARGIN` times the
+/// terminal speed under linear surge drag alone, `t_max / xu`. Unlimited without linear drag.
+pub fn max_plausible_speed(spec: &SubPhysicsSpec) -> f32 {
+    if spec.xu > 0.0 {
+        spec.t_max.abs() / spec.xu * TERMINAL_SPEED_MARGIN
+    } else {
+        f32::INFINITY
+    }
+}
+
+/// Per-player violation counters.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AntiCheatState {
+    /// Physics ticks in a row that ended over `max_plausible_speed`.
+    pub velocity_strikes: u32,
+    /// Inputs received with a thrust, yaw or pump command outside [-1, 1]; never reset.
+    pub input_strikes: u32,
+}
+
+impl AntiCheatState {
+    /// Check a sub's velocity after a physics step. Over `max_speed` (or not finite) it is
+    /// clamped to that speed, or zeroed, and the measured speed is returned.
+    pub fn check_velocity(&mut self, velocity: &mut Vec3f, max_speed: f32) -> Option<f32> {
+        let speed = velocity.length();
+        if speed <= max_speed {
+            self.velocity_strikes = 0;
+            return None;
+        }
+        self.velocity_strikes += 1;
+        *velocity = if speed.is_finite() {
+            velocity.clamp_length_max(max_speed)
+        } else {
+            Vec3f::ZERO
+        };
+        Some(speed)
+    }
+
+    /// The sub has been too fast for long enough to kick its client.
+    pub fn should_kick(&self) -> bool {
+        self.velocity_strikes >= VELOCITY_STRIKES_TO_KICK
+    }
+
+    /// Count a strike if `input` commands thrust, yaw or either pump outside [-1, 1]; the
+    /// caller still applies it clamped. Returns whether it did.
+    pub fn check_input(&mut self, input: &InputTick) -> bool {
+        let in_range = [input.thrust, input.yaw, input.pump_fwd, input.pump_aft]
+            .iter()
+            .all(|v| v.abs() <= 1.0);
+        if !in_range {
+            self.input_strikes += 1;
+        }
+        !in_range
+    }
+}
+
+/// Players kicked within the last `KICK_COOLDOWN`, with the `Time::elapsed` they were kicked
+/// at. A kicked player leaves no `DepartedPlayer` behind and cannot rejoin until it expires.
+#[derive(Resource, Debug, Default)]
+pub struct KickedPlayers(pub HashMap<Uuid, Duration>);
+
+impl KickedPlayers {
+    pub fn kick(&mut self, player: Uuid, now: Duration) {
+        self.0.insert(player, now);
+    }
+
+    /// Whether `player` is still serving a kick at `now`. Expired kicks are forgotten.
+    pub fn is_barred(&mut self, player: &Uuid, now: Duration) -> bool {
+        self.0
+            .retain(|_, &mut at| now.saturating_sub(at) < KICK_COOLDOWN);
+        self.0.contains_key(player)
+    }
+}
@@ -0,0 +1,55 @@
+//! Area of interest: players are binned into a uniform grid of cubic cells every tick, and each
+//! client's `StateDelta` only carries the players within `Config::aoi_radius_cells` of its own
+//! cell (Chebyshev distance, so the visible region is a cube of cells).
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use uuid::Uuid;
+
+#[derive(Resource, Debug, Clone)]
+pub struct AoiGrid {
+    cell_size_m: f32,
+    radius_cells: u32,
+    cells: HashMap<Uuid, IVec3>,
+}
+
+impl AoiGrid {
+    pub fn new(cell_size_m: f32, radius_cells: u32) -> Self {
+        Self {
+            cell_size_m,
+            radius_cells,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn cell_of(&self, position: Vec3) -> IVec3 {
+        (position / self.cell_size_m).floor().as_ivec3()
+    }
+
+    /// Bin every player at its current position; players left out are forgotten.
+    pub fn rebuild(&mut self, players: impl IntoIterator<Item = (Uuid, Vec3)>) {
+        self.cells.clear();
+        for (id, position) in players {
+            let cell = self.cell_of(position);
+            self.cells.insert(id, cell);
+        }
+    }
+
+    pub fn cell(&self, player: Uuid) -> Option<IVec3> {
+        self.cells.get(&player).copied()
+    }
+
+    pub fn in_range(&self, a: IVec3, b: IVec3) -> bool {
+        (a - b).abs().max_element() as u32 <= self.radius_cells
+    }
+
+    /// Whether `target` belongs in the snapshot of a viewer in `viewer_cell`. A viewer or
+    /// target not binned yet, such as a client that has not sent `Hello`, is not culled.
+    pub fn is_visible(&self, viewer_cell: Option<IVec3>, target: Uuid) -> bool {
+        match (viewer_cell, self.cell(target)) {
+            (Some(viewer), Some(target)) => self.in_range(viewer, target),
+            _ => true,
+        }
+    }
+}
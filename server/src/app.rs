@@ -1,29 +1,49 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::net::UdpSocket;
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::Result;
+use bevy::ecs::query::QueryItem;
 use bevy::prelude::*;
+use bevy::utils::Parallel;
 use bevy_renet::{
     netcode::{NetcodeServerPlugin, NetcodeServerTransport, ServerAuthentication, ServerConfig},
-    renet::{ConnectionConfig, DefaultChannel, RenetServer, ServerEvent},
+    renet::{Bytes, RenetServer, ServerEvent},
     RenetServerPlugin,
 };
 use clap::Parser;
 use levels::subspecs::small_skiff_spec;
 use levels::SubPhysicsSpec;
 use levels::{
-    builtins::greybox_level, step_submarine, LevelSpec, Quatf, SubInputState, SubInputs, SubState,
-    Vec3f,
-};
-use protocol::{
-    ClientToServer, DisconnectReason, ServerToClient, NETCODE_PROTOCOL_ID, PROTOCOL_VERSION,
+    step_submarine, CampaignSpec, LevelSpec, Mission, MissionEvent, MovingObstacleSpec,
+    OreNodeSpec, Quatf, ResourceType, SubInputState, SubInputs, SubState, Vec3f,
 };
+use protocol::discovery::ServerBeacon;
+use protocol::rendezvous::HolePunchConfig;
+use protocol::{Channel, DisconnectReason, FeatureFlags, ServerToClient, NETCODE_PROTOCOL_ID};
 use serde::{Deserialize, Serialize};
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::anti_cheat::{max_plausible_speed, AntiCheatState, KickedPlayers};
+use crate::aoi::AoiGrid;
+use crate::chat::{server_relay_chat, ChatInbox};
+use crate::console::apply_console_commands;
+use crate::discovery::DiscoveryPlayerCount;
+use crate::dive_depth::DiveDepthAlarm;
+use crate::hello::{server_handle_hellos, DepartedPlayer, DepartedPlayers, HelloInbox};
+use crate::inputs::{server_handle_inputs, InputInbox};
+use crate::latency::LatencyHistogram;
+use crate::legacy::{LegacyClients, VersionedServer};
+use crate::messages::{server_receive_messages, ClientMessages};
+use crate::mining::{server_handle_cargo_requests, CargoInbox};
+use crate::rate_limit::ClientRateMonitor;
+use crate::shutdown::{broadcast_shutdown_warning, handle_shutdown_signals, ShutdownSignal};
+use crate::sonar::{server_answer_sonar_pings, SonarPingInbox};
+use crate::tick_rate::TickRateGovernor;
+
 #[derive(Parser, Debug, Resource)]
 #[command(name = "thalassocracy-server")]
 #[command(about = "Server for Thalassocracy prototype", long_about = None)]
@@ -31,6 +51,9 @@ pub struct Args {
     /// Path to config file
     #[arg(long, default_value = "server/config.toml")]
     pub config: PathBuf,
+    /// JSON level file (see `levels::save_level`) to play instead of the configured campaign
+    #[arg(long)]
+    pub level: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Resource)]
@@ -46,6 +69,65 @@ pub struct Config {
     /// Optional public address to advertise in netcode tokens
     #[serde(default)]
     pub public_addr: Option<String>,
+    /// Scales every channel's packet budget (see `protocol::default_channel_configs`)
+    #[serde(default = "default_channel_budget_multiplier")]
+    pub channel_budget_multiplier: f32,
+    /// Split players into two alternating teams on join and play rounds to `team_score_limit`
+    #[serde(default)]
+    pub team_deathmatch: bool,
+    /// Team credits that end a round (team deathmatch only)
+    #[serde(default = "default_team_score_limit")]
+    pub team_score_limit: u64,
+    /// TCP port for the WebSocket-to-UDP proxy used by browser clients; off when unset
+    #[serde(default)]
+    pub ws_port: Option<u16>,
+    /// RON campaign file (see `levels::CampaignSpec`); the builtin campaign when unset
+    #[serde(default)]
+    pub campaign: Option<PathBuf>,
+    /// RON `levels::SubPhysicsSpec` every player's sub is spawned with; the builtin small
+    /// skiff when unset
+    #[serde(default)]
+    pub submarine_spec_file: Option<PathBuf>,
+    /// `rendezvous://host:port/<token>` to keep registered with for NAT hole punching; clients
+    /// connect with the same URL as `--server`
+    #[serde(default)]
+    pub rendezvous: Option<String>,
+    /// Name shown to clients browsing with `--discover`
+    #[serde(default = "default_server_name")]
+    pub server_name: String,
+    /// UDP port to broadcast LAN discovery beacons to (see `protocol::discovery`); off when
+    /// unset
+    #[serde(default)]
+    pub discovery_port: Option<u16>,
+    /// Input messages per second applied from each client; the rest are dropped and the
+    /// client is sent `ServerToClient::RateLimit`
+    #[serde(default = "default_max_inputs_per_sec")]
+    pub max_inputs_per_sec: u32,
+    /// `protocol::FeatureFlags` bits this deployment grants; each client gets the part of its
+    /// requested features that is set here
+    #[serde(default = "default_enabled_features")]
+    pub enabled_features: FeatureFlags,
+    /// Ticks the physics step lags behind each client's newest `InputTick`, taking the
+    /// buffered input nearest that point (see `InputBuffer`) so late or reordered packets
+    /// still land in order
+    #[serde(default = "default_jitter_buffer_ticks")]
+    pub jitter_buffer_ticks: u32,
+    /// Edge of the cubic cells players are binned into for snapshot culling (see `AoiGrid`)
+    #[serde(default = "default_aoi_cell_size_m")]
+    pub aoi_cell_size_m: f32,
+    /// Cells, in each axis, another player may be from a client's own cell and still appear
+    /// in its `StateDelta`
+    #[serde(default = "default_aoi_radius_cells")]
+    pub aoi_radius_cells: u32,
+    /// Move a sub that sinks past its `DiveDepthLimit::max_depth_m` back to a spawn point,
+    /// rather than leaving it to the physics step's emergency blow
+    #[serde(default)]
+    pub eject_past_max_depth: bool,
+    /// How far (m) a sonar ping reaches through uniform water to a sub radiating
+    /// `levels::REFERENCE_SOURCE_LEVEL_DB`; louder subs are heard further and density layers
+    /// in the level shorten it (see `sonar::sonar_contacts`)
+    #[serde(default = "default_sonar_base_range_m")]
+    pub sonar_base_range_m: f32,
 }
 
 pub fn default_port() -> u16 {
@@ -60,6 +142,34 @@ pub fn default_tick_hz() -> u32 {
 pub fn default_snapshot_hz() -> u32 {
     20
 }
+pub fn default_channel_budget_multiplier() -> f32 {
+    1.0
+}
+pub fn default_team_score_limit() -> u64 {
+    10_000
+}
+pub fn default_server_name() -> String {
+    "Thalassocracy".to_string()
+}
+pub fn default_max_inputs_per_sec() -> u32 {
+    120
+}
+
+pub fn default_enabled_features() -> FeatureFlags {
+    FeatureFlags::ALL
+}
+pub fn default_jitter_buffer_ticks() -> u32 {
+    3
+}
+pub fn default_aoi_cell_size_m() -> f32 {
+    32.0
+}
+pub fn default_aoi_radius_cells() -> u32 {
+    3
+}
+pub fn default_sonar_base_range_m() -> f32 {
+    150.0
+}
 
 impl Default for Config {
     fn default() -> Self {
@@ -69,6 +179,22 @@ impl Default for Config {
             tick_hz: default_tick_hz(),
             snapshot_hz: default_snapshot_hz(),
             public_addr: None,
+            channel_budget_multiplier: default_channel_budget_multiplier(),
+            team_deathmatch: false,
+            team_score_limit: default_team_score_limit(),
+            ws_port: None,
+            campaign: None,
+            submarine_spec_file: None,
+            rendezvous: None,
+            server_name: default_server_name(),
+            discovery_port: None,
+            max_inputs_per_sec: default_max_inputs_per_sec(),
+            enabled_features: default_enabled_features(),
+            jitter_buffer_ticks: default_jitter_buffer_ticks(),
+            aoi_cell_size_m: default_aoi_cell_size_m(),
+            aoi_radius_cells: default_aoi_radius_cells(),
+            eject_past_max_depth: false,
+            sonar_base_range_m: default_sonar_base_range_m(),
         }
     }
 }
@@ -86,21 +212,70 @@ pub fn load_config(path: &PathBuf) -> Result<Config> {
 pub struct ServerAddresses {
     pub bound: std::net::SocketAddr,
     pub public: std::net::SocketAddr,
+    /// WebSocket proxy listener, when `Config::ws_port` is set.
+    pub ws: Option<std::net::SocketAddr>,
+}
+
+/// Like `build_server_app`, but plays only `level` instead of the configured campaign, so tests
+/// can run the server on a purpose-built layout. Clients still resolve map 0 against their own
+/// campaign.
+pub fn build_server_app_with_level(cfg: Config, level: LevelSpec) -> App {
+    let mut app = build_server_app(cfg);
+    app.insert_resource(CampaignOverride(CampaignSpec::single_level(level)));
+    app
 }
 
 pub fn build_server_app(cfg: Config) -> App {
     let mut app = App::new();
-    app.insert_resource(cfg)
+    app.insert_resource(AoiGrid::new(cfg.aoi_cell_size_m, cfg.aoi_radius_cells))
+        .insert_resource(cfg)
+        .init_resource::<ShutdownSignal>()
         .add_plugins(MinimalPlugins)
         .add_plugins((RenetServerPlugin, NetcodeServerPlugin))
         .add_systems(Startup, server_setup)
+        .add_systems(First, server_start_frame_timer)
+        .add_systems(Last, server_adapt_tick_rate)
         .add_systems(
             Update,
             (
                 server_handle_events,
-                server_handle_messages,
+                // Before anything that rejects a client, so its `Disconnect` goes out first
+                server_drop_rejected_clients
+                    .before(ClientMessages)
+                    .before(server_physics_tick),
+                (
+                    server_receive_messages,
+                    server_handle_hellos,
+                    server_handle_inputs,
+                    server_relay_chat,
+                    server_handle_cargo_requests,
+                )
+                    .chain()
+                    .in_set(ClientMessages),
                 server_physics_tick,
+                server_check_dive_depth
+                    .after(server_physics_tick)
+                    .before(server_bin_aoi_grid),
+                server_bin_aoi_grid
+                    .after(server_physics_tick)
+                    .before(server_broadcast_state),
                 server_broadcast_state,
+                server_broadcast_leaderboard,
+                server_respawn_ore,
+                server_advance_campaign,
+                server_torpedo_tick,
+                server_answer_sonar_pings
+                    .after(ClientMessages)
+                    .after(server_physics_tick),
+                server_update_missions.after(ClientMessages),
+                server_record_latency.after(server_physics_tick),
+                server_update_discovery_count,
+                (
+                    handle_shutdown_signals,
+                    apply_console_commands,
+                    broadcast_shutdown_warning.after(server_physics_tick),
+                )
+                    .chain(),
             ),
         );
     app
@@ -109,6 +284,23 @@ pub fn build_server_app(cfg: Config) -> App {
 #[derive(Resource)]
 pub struct LevelRes(pub LevelSpec);
 
+/// Campaign to play in place of `Config::campaign` (see `build_server_app_with_level`).
+#[derive(Resource)]
+struct CampaignOverride(CampaignSpec);
+
+/// Hull physics new subs are spawned with (`Config::submarine_spec_file`).
+#[derive(Resource)]
+pub struct SubSpecRes(pub SubPhysicsSpec);
+
+/// Campaign being played and how far along it is; `LevelRes` holds the active level's spec.
+#[derive(Resource, Debug)]
+pub struct CampaignRes {
+    pub spec: CampaignSpec,
+    pub active: usize,
+    /// Indices of levels already won, for `UnlockCondition::CompleteLevel`.
+    pub completed: Vec<usize>,
+}
+
 #[derive(Resource, Default)]
 pub struct ClientEntities(pub HashMap<u64, Entity>);
 
@@ -126,6 +318,216 @@ pub struct SubStateComp(pub SubState);
 #[derive(Component, Debug, Clone, Default)]
 pub struct SubInputStateComp(pub SubInputState);
 
+/// Remaining hull integrity in [0,1]; drained by environmental hazards.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct HullIntegrity(pub f32);
+
+impl Default for HullIntegrity {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Integrity below which the owning client receives a `HullAlert`.
+const HULL_ALERT_THRESHOLD: f32 = 0.3;
+
+/// Name the player joined with (from `ClientHello`), shown on the leaderboard.
+#[derive(Component, Debug, Clone)]
+pub struct DisplayName(pub String);
+
+/// Per-player economy totals reported on the leaderboard.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct PlayerScore {
+    pub credits: u64,
+    pub mines: u32,
+    pub docks: u32,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Team(pub u8);
+
+/// Each player's state from the latest snapshot, with its server tick, until an `InputAck`
+/// carries it back to them as a prediction check.
+#[derive(Resource, Debug, Default)]
+pub struct PendingCorrections(pub HashMap<Uuid, (u64, protocol::NetPlayer)>);
+
+impl PendingCorrections {
+    /// Ack `input_tick` for `player`, taking their pending correction if there is one.
+    pub(crate) fn ack(&mut self, player: Option<Uuid>, input_tick: u64) -> ServerToClient {
+        let (correction_tick, correction) = match player.and_then(|id| self.0.remove(&id)) {
+            Some((tick, state)) => (tick, Some(state)),
+            None => (0, None),
+        };
+        ServerToClient::InputAck(protocol::InputAck {
+            tick: input_tick,
+            correction,
+            correction_tick,
+        })
+    }
+}
+
+/// Credits banked per team in the current round.
+#[derive(Resource, Debug, Default)]
+pub struct TeamScores(pub HashMap<u8, u64>);
+
+/// Number of teams used when `team_deathmatch` is enabled.
+const TEAM_COUNT: u8 = 2;
+
+/// Hands out teams round-robin in join order.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct TeamAssigner {
+    joins: u64,
+}
+
+impl TeamAssigner {
+    pub(crate) fn next(&mut self, team_deathmatch: bool) -> u8 {
+        if !team_deathmatch {
+            return 0;
+        }
+        let team = (self.joins % TEAM_COUNT as u64) as u8;
+        self.joins += 1;
+        team
+    }
+}
+
+/// Max distance from a sub to an ore node for a `MineRequest` to succeed.
+pub const MINE_RANGE_M: f32 = 8.0;
+/// Time a mined-out ore node stays depleted before it respawns.
+pub const ORE_RESPAWN_S: f32 = 30.0;
+
+/// Ore deposit from `LevelSpec::ore_nodes`; depleted while `respawn_timer` is running.
+#[derive(Component, Debug)]
+pub struct OreNode {
+    pub node_id: u32,
+    pub position: Vec3f,
+    pub respawn_timer: Option<Timer>,
+    /// Kilograms left before the node is mined out; refilled to `OreNodeSpec::supply_kg` when
+    /// it respawns.
+    pub remaining_kg: u32,
+    /// Mining passes so far, which picks each pass's resource (`resource_for_pass`).
+    pub passes: u32,
+}
+
+impl OreNode {
+    /// Take one mining pass from the node: the resource it yields and the kilograms actually
+    /// taken, at most what is left. The pass that empties the node starts its respawn timer.
+    pub fn mine(&mut self, spec: &OreNodeSpec) -> (ResourceType, u32) {
+        let resource_type = spec.resource_for_pass(self.node_id, self.passes);
+        let amount = spec.pass_kg().min(self.remaining_kg);
+        self.remaining_kg -= amount;
+        self.passes = self.passes.wrapping_add(1);
+        if self.remaining_kg == 0 {
+            self.respawn_timer = Some(Timer::from_seconds(ORE_RESPAWN_S, TimerMode::Once));
+        }
+        (resource_type, amount)
+    }
+
+    /// Whether a sub at `sub_pos` may mine the node: it is not depleted and lies within
+    /// `MINE_RANGE_M`.
+    pub fn in_reach(&self, sub_pos: Vec3f) -> bool {
+        self.respawn_timer.is_none() && (self.position - sub_pos).length() <= MINE_RANGE_M
+    }
+
+    /// Run a depleted node's respawn timer for `delta`. Once it finishes the node is refilled
+    /// to `spec`'s full supply (empty if the level no longer has the node) and `true` returned.
+    pub fn tick_respawn(&mut self, delta: Duration, spec: Option<&OreNodeSpec>) -> bool {
+        let Some(timer) = self.respawn_timer.as_mut() else {
+            return false;
+        };
+        if !timer.tick(delta).finished() {
+            return false;
+        }
+        self.respawn_timer = None;
+        self.remaining_kg = spec.map_or(0, OreNodeSpec::supply_kg);
+        true
+    }
+}
+
+/// Ore and other resources aboard a sub, in kg, until it docks and unloads. This is the
+/// player's inventory as `MineAck` and `InventoryUpdate` report it.
+#[derive(Component, Debug, Clone, Default)]
+pub struct CargoHold(pub HashMap<ResourceType, u32>);
+
+impl CargoHold {
+    pub fn to_net(&self) -> HashMap<protocol::ResourceType, u32> {
+        self.0
+            .iter()
+            .map(|(&resource_type, &kg)| (net_resource_type(resource_type), kg))
+            .collect()
+    }
+}
+
+pub fn net_resource_type(resource_type: ResourceType) -> protocol::ResourceType {
+    match resource_type {
+        ResourceType::Ore => protocol::ResourceType::Ore,
+        ResourceType::Crystal => protocol::ResourceType::Crystal,
+        ResourceType::RareMineral => protocol::ResourceType::RareMineral,
+    }
+}
+
+/// Missions every player can work on; ids match the client's `levels::builtin_missions()`.
+#[derive(Resource, Debug)]
+pub struct ActiveMissions(pub Vec<Mission>);
+
+/// Per player, progress on each started mission by mission id. Kept across reconnects.
+#[derive(Resource, Debug, Default)]
+pub struct PlayerMissionProgress(pub HashMap<Uuid, HashMap<u32, u32>>);
+
+/// Docks and deliveries waiting for `server_update_missions`.
+#[derive(Resource, Default)]
+pub(crate) struct MissionEventInbox(pub Vec<(Entity, MissionEvent)>);
+
+/// Moving obstacle from `LevelSpec::moving_obstacles`, `traveled_m` along its looping path.
+#[derive(Component, Debug)]
+pub struct MovingObstacle {
+    pub spec: MovingObstacleSpec,
+    pub traveled_m: f32,
+    pub position: Vec3f,
+}
+
+/// Torpedoes detonate after this long even if they hit nothing.
+const TORPEDO_TTL_S: f32 = 30.0;
+/// A torpedo passing within this distance of another player's sub detonates.
+const TORPEDO_PROXIMITY_M: f32 = 1.0;
+/// Minimum time between one player's launches, whichever tubes are loaded.
+const TORPEDO_COOLDOWN_S: f32 = 8.0;
+const TORPEDO_BLAST_RADIUS_M: f32 = 6.0;
+
+/// Seconds until each tube can fire again; zero means loaded.
+#[derive(Component, Debug, Clone)]
+pub struct TorpedoTubes(pub Vec<f32>);
+
+#[derive(Component, Debug)]
+pub struct Torpedo {
+    pub id: u32,
+    /// Firing sub, which its own torpedo never proximity-detonates against.
+    pub owner: Entity,
+    pub position: Vec3f,
+    pub direction: Vec3f,
+    /// Current speed along `direction`, bled off by `protocol::torpedo_drag`.
+    pub speed_m_s: f32,
+    pub age_s: f32,
+}
+
+/// Seconds until each player may launch again, keyed by their sub; players without an
+/// entry are free to fire.
+#[derive(Resource, Debug, Default)]
+pub struct TorpedoCooldown(pub HashMap<Entity, f32>);
+
+/// Features the server granted this player in its `JoinAck`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrantedFeatures(pub FeatureFlags);
+
+/// `FireTorpedo` requests waiting for `server_torpedo_tick`.
+#[derive(Resource, Default)]
+pub(crate) struct TorpedoLaunchInbox(pub Vec<(Entity, protocol::FireTorpedo)>);
+
+/// Interval between `LeaderboardUpdate` broadcasts.
+const LEADERBOARD_INTERVAL_S: f32 = 5.0;
+
+#[derive(Resource)]
+struct LeaderboardTimer(Timer);
+
 #[allow(dead_code)]
 #[derive(Component, Clone)]
 pub struct SubPhysicsComp(pub SubPhysicsSpec);
@@ -143,36 +545,203 @@ struct SnapshotTiming {
 }
 
 #[derive(Resource)]
-struct Tick(pub u64);
+pub(crate) struct Tick(pub u64);
 #[derive(Resource)]
-struct ServerStart(pub std::time::Instant);
+pub(crate) struct ServerStart(pub std::time::Instant);
+
+/// `InputEvent`s this late when their time comes are dropped rather than applied.
+pub const SCHEDULED_INPUT_MAX_AGE_MS: u64 = 200;
+
+/// Client `InputEvent`s waiting for their `t_ms`, keyed by it. The physics tick applies
+/// whatever has come due, so inputs take effect at the time the client asked for rather than
+/// whenever the packet happens to arrive.
+#[derive(Resource, Default, Debug)]
+pub struct ScheduledInputQueue(pub BTreeMap<u64, Vec<(Entity, protocol::InputEvent)>>);
+
+impl ScheduledInputQueue {
+    pub fn push(&mut self, entity: Entity, ev: protocol::InputEvent) {
+        self.0.entry(ev.t_ms).or_default().push((entity, ev));
+    }
+
+    /// Remove every event with `t_ms <= now_ms`, oldest first. Events more than
+    /// `SCHEDULED_INPUT_MAX_AGE_MS` overdue are discarded with a warning.
+    pub fn drain_due(&mut self, now_ms: u64) -> Vec<(Entity, protocol::InputEvent)> {
+        let pending = self.0.split_off(&now_ms.saturating_add(1));
+        let due = std::mem::replace(&mut self.0, pending);
+        let mut out = Vec::new();
+        for (t_ms, events) in due {
+            let late_ms = now_ms - t_ms;
+            if late_ms > SCHEDULED_INPUT_MAX_AGE_MS {
+                for (entity, _) in events {
+                    warn!(?entity, t_ms, late_ms, "dropping stale input event");
+                }
+                continue;
+            }
+            out.extend(events);
+        }
+        out
+    }
+}
+
+/// Entries an `InputBuffer` holds by default.
+pub const INPUT_BUFFER_CAPACITY: usize = 32;
+
+/// A client's most recent `InputTick`s, keyed by their client tick. The physics step reads the
+/// entry nearest `server_tick - jitter_buffer_ticks` instead of the newest arrival, so inputs
+/// apply in tick order at a steady delay however the packets were spaced.
+///
+/// Client ticks count frames, not server ticks; they are mapped onto server time by the
+/// smallest `arrival server tick - client tick` seen, the offset of the quickest packet.
+///
+/// The newest tick ever inserted outlives `clear`, so a late `InputTickBatch` on the unordered
+/// `Input` channel can be told apart from fresh input.
+#[derive(Component, Debug, Clone)]
+pub struct InputBuffer {
+    capacity: usize,
+    entries: VecDeque<(u64, SubInputs)>,
+    tick_offset: Option<i64>,
+    newest: Option<(u64, SubInputs)>,
+}
+
+impl Default for InputBuffer {
+    fn default() -> Self {
+        Self::with_capacity(INPUT_BUFFER_CAPACITY)
+    }
+}
+
+impl InputBuffer {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: VecDeque::with_capacity(capacity.max(1)),
+            tick_offset: None,
+            newest: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.tick_offset = None;
+    }
+
+    /// The highest client tick inserted since the player joined, with its inputs.
+    pub fn newest(&self) -> Option<(u64, SubInputs)> {
+        self.newest
+    }
+
+    /// Store `inputs` for `client_tick`, received on `server_tick`. A repeated tick replaces
+    /// the earlier entry; past capacity the oldest tick is dropped, including this one when it
+    /// is older than everything held.
+    pub fn insert(&mut self, server_tick: u64, client_tick: u64, inputs: SubInputs) {
+        if self.newest.is_none_or(|(t, _)| t <= client_tick) {
+            self.newest = Some((client_tick, inputs));
+        }
+        let offset = server_tick as i64 - client_tick as i64;
+        self.tick_offset = Some(self.tick_offset.map_or(offset, |o| o.min(offset)));
+        match self.entries.binary_search_by_key(&client_tick, |&(t, _)| t) {
+            Ok(i) => self.entries[i].1 = inputs,
+            Err(i) => self.entries.insert(i, (client_tick, inputs)),
+        }
+        if self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Inputs whose client tick is nearest `server_tick - jitter_ticks` in server time; the
+    /// later one on a tie.
+    pub fn select(&self, server_tick: u64, jitter_ticks: u32) -> Option<SubInputs> {
+        let target = server_tick as i64 - jitter_ticks as i64 - self.tick_offset?;
+        self.entries
+            .iter()
+            .rev()
+            .min_by_key(|&&(t, _)| (t as i64 - target).unsigned_abs())
+            .map(|&(_, inputs)| inputs)
+    }
+}
 
 #[derive(Resource, Default)]
-struct InputEventInbox(Vec<(Entity, protocol::InputEvent)>);
+pub(crate) struct SimPaused(pub bool);
 
+/// Clients refused during the handshake or kicked. They are disconnected a frame later so the
+/// `Disconnect` message explaining why gets sent first.
 #[derive(Resource, Default)]
-struct SimPaused(pub bool);
+pub(crate) struct RejectedClients(pub Vec<u64>);
 
 #[allow(dead_code)]
 #[derive(Component, Default)]
-struct ControlInputComp {
+pub(crate) struct ControlInputComp {
     thrust: f32,
     yaw: f32,
     pump_fwd: f32,
     pump_aft: f32,
+    pitch: f32,
+    roll_trim: f32,
     last_tick: u64,
 }
 
-#[derive(Component, Default)]
-struct InputSchedule(VecDeque<protocol::InputEvent>);
+impl ControlInputComp {
+    pub(crate) fn sub_inputs(&self) -> SubInputs {
+        SubInputs {
+            thrust: self.thrust,
+            yaw: self.yaw,
+            pump_fwd: self.pump_fwd,
+            pump_aft: self.pump_aft,
+            plane: self.pitch,
+            roll_trim: self.roll_trim,
+        }
+    }
+
+    pub(crate) fn from_tick(input: &protocol::InputTick) -> Self {
+        Self {
+            thrust: input.thrust.clamp(-1.0, 1.0),
+            yaw: input.yaw.clamp(-1.0, 1.0),
+            pump_fwd: input.pump_fwd.clamp(-1.0, 1.0),
+            pump_aft: input.pump_aft.clamp(-1.0, 1.0),
+            pitch: input.pitch.clamp(-1.0, 1.0),
+            roll_trim: input.roll_trim.clamp(-1.0, 1.0),
+            last_tick: input.tick,
+        }
+    }
+}
 
-fn server_setup(mut commands: Commands, cfg: Res<Config>) {
+fn server_setup(
+    mut commands: Commands,
+    cfg: Res<Config>,
+    campaign_override: Option<Res<CampaignOverride>>,
+) {
     // Bind UDP socket
     let socket = UdpSocket::bind(("0.0.0.0", cfg.port)).expect("failed to bind UDP socket");
 
-    // Load/shared level spec
-    let level_spec = greybox_level();
+    // Campaign starts on its first level
+    let campaign = match (campaign_override, &cfg.campaign) {
+        (Some(campaign), _) => campaign.0.clone(),
+        (None, Some(path)) => CampaignSpec::from_ron_file(path).expect("failed to load campaign"),
+        (None, None) => CampaignSpec::builtin_campaign(),
+    };
+    let level_spec = campaign.levels[0].level_spec.clone();
+    log_level_errors(0, &level_spec);
+    spawn_ore_nodes(&mut commands, &level_spec);
+    spawn_moving_obstacles(&mut commands, &level_spec);
+    commands.insert_resource(LevelBounds::from_level(&level_spec));
     commands.insert_resource(LevelRes(level_spec));
+    commands.insert_resource(CampaignRes {
+        spec: campaign,
+        active: 0,
+        completed: Vec::new(),
+    });
+    let sub_spec = match &cfg.submarine_spec_file {
+        Some(path) => SubPhysicsSpec::from_ron_file(path).expect("failed to load submarine spec"),
+        None => small_skiff_spec(),
+    };
+    commands.insert_resource(SubSpecRes(sub_spec));
 
     // Timings
     let physics_dt = 1.0 / cfg.tick_hz.max(1) as f32;
@@ -185,11 +754,34 @@ fn server_setup(mut commands: Commands, cfg: Res<Config>) {
         acc: 0.0,
         dt: snapshot_dt,
     });
+    commands.insert_resource(TickRateGovernor::new(cfg.tick_hz));
     commands.insert_resource(Tick(0));
     commands.insert_resource(ClientEntities::default());
     commands.insert_resource(SimPaused(false));
     commands.insert_resource(ServerStart(std::time::Instant::now()));
-    commands.insert_resource(InputEventInbox::default());
+    commands.insert_resource(ScheduledInputQueue::default());
+    commands.insert_resource(RejectedClients::default());
+    commands.insert_resource(TorpedoLaunchInbox::default());
+    commands.insert_resource(SonarPingInbox::default());
+    commands.insert_resource(HelloInbox::default());
+    commands.insert_resource(LegacyClients::default());
+    commands.insert_resource(InputInbox::default());
+    commands.insert_resource(ChatInbox::default());
+    commands.insert_resource(CargoInbox::default());
+    commands.insert_resource(TorpedoCooldown::default());
+    commands.insert_resource(ActiveMissions(levels::builtin_missions()));
+    commands.insert_resource(PlayerMissionProgress::default());
+    commands.insert_resource(MissionEventInbox::default());
+    commands.insert_resource(TeamScores::default());
+    commands.insert_resource(TeamAssigner::default());
+    commands.insert_resource(DepartedPlayers::default());
+    commands.insert_resource(KickedPlayers::default());
+    commands.insert_resource(PendingCorrections::default());
+    commands.insert_resource(ClientRateMonitor::default());
+    commands.insert_resource(LeaderboardTimer(Timer::from_seconds(
+        LEADERBOARD_INTERVAL_S,
+        TimerMode::Repeating,
+    )));
 
     // Netcode transport (renet)
     let bound_addr = socket.local_addr().expect("udp local_addr");
@@ -207,35 +799,263 @@ fn server_setup(mut commands: Commands, cfg: Res<Config>) {
         public_addresses: vec![public_addr],
         authentication: ServerAuthentication::Unsecure,
     };
+    // Registration goes out from the netcode socket so the rendezvous sees its NAT mapping
+    if let Some(url) = &cfg.rendezvous {
+        let punch = HolePunchConfig::parse(url)
+            .unwrap_or_else(|| Err(format!("not a rendezvous:// URL: {url}")))
+            .expect("invalid rendezvous in server config");
+        let external = cfg.public_addr.as_ref().map(|_| public_addr);
+        let register_socket = socket.try_clone().expect("failed to clone UDP socket");
+        crate::rendezvous::spawn_registration(register_socket, punch, external)
+            .expect("failed to start rendezvous registration");
+    }
     let transport = NetcodeServerTransport::new(server_config, socket)
         .expect("failed to create server transport");
 
+    // Browser clients reach the netcode socket through the proxy over loopback
+    let ws_addr = cfg.ws_port.map(|ws_port| {
+        let netcode_addr = std::net::SocketAddr::from(([127, 0, 0, 1], bound_addr.port()));
+        crate::ws_proxy::spawn_ws_proxy(ws_port, netcode_addr)
+            .expect("failed to start WebSocket proxy")
+    });
+
+    let player_count = DiscoveryPlayerCount::default();
+    if let Some(port) = cfg.discovery_port {
+        let beacon = ServerBeacon {
+            name: cfg.server_name.clone(),
+            addr: public_addr,
+            player_count: 0,
+            max_players: cfg.max_clients.min(u8::MAX as usize) as u8,
+        };
+        let target = std::net::SocketAddr::from((std::net::Ipv4Addr::BROADCAST, port));
+        crate::discovery::spawn_discovery_beacon(beacon, player_count.0.clone(), target)
+            .expect("failed to start discovery beacon");
+    }
+    commands.insert_resource(player_count);
+
     commands.insert_resource(ServerAddresses {
         bound: bound_addr,
         public: public_addr,
+        ws: ws_addr,
     });
 
     // Reliable server (renet)
-    let server = RenetServer::new(ConnectionConfig::default());
+    let server = RenetServer::new(protocol::renet_config::connection_config(
+        cfg.channel_budget_multiplier,
+    ));
 
     commands.insert_resource(server);
     commands.insert_resource(transport);
     info!(port = bound_addr.port(), "Server running");
 }
 
+fn spawn_ore_nodes(commands: &mut Commands, level: &LevelSpec) {
+    for (node_id, ore) in level.ore_nodes.iter().enumerate() {
+        commands.spawn((
+            OreNode {
+                node_id: node_id as u32,
+                position: ore.position,
+                respawn_timer: None,
+                remaining_kg: ore.supply_kg(),
+                passes: 0,
+            },
+            Name::new(format!("Ore Node {node_id}")),
+        ));
+    }
+}
+
+fn spawn_moving_obstacles(commands: &mut Commands, level: &LevelSpec) {
+    for spec in &level.moving_obstacles {
+        commands.spawn((
+            MovingObstacle {
+                spec: spec.clone(),
+                traveled_m: 0.0,
+                position: spec.position_at(0.0),
+            },
+            Name::new(format!("Moving Obstacle {}", spec.id)),
+        ));
+    }
+}
+
+/// Report a broken campaign level without taking the server down; play continues on it as is.
+fn log_level_errors(map_id: usize, level: &LevelSpec) {
+    level.validate().unwrap_or_else(|errors| {
+        for err in errors {
+            error!(map_id, %err, "invalid level spec");
+        }
+    });
+}
+
+/// Spawn state near the tunnel entrance, nose pointing with the local flow in XZ.
+pub(crate) fn start_state(level: &LevelSpec, spec: &SubPhysicsSpec) -> SubState {
+    let start = tunnel_entrance(level);
+    let (flow, _) = levels::sample_flow_at(level, start, 0.0);
+    let mut yaw = 0.0f32;
+    let fxz = (flow.x * flow.x + flow.z * flow.z).sqrt();
+    if fxz > 1e-3 {
+        yaw = flow.x.atan2(flow.z);
+    }
+    SubState {
+        position: start,
+        velocity: Vec3f::new(0.0, 0.0, 0.0),
+        orientation: Quatf::from_rotation_y(yaw),
+        ang_mom: Vec3f::new(0.0, 0.0, 0.0),
+        ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+        pump: Default::default(),
+    }
+}
+
+fn tunnel_entrance(level: &LevelSpec) -> Vec3f {
+    let t = &level.tunnel;
+    Vec3f::new(t.pos.x - t.size.x * 0.5 + 6.0, t.pos.y, t.pos.z)
+}
+
+/// Slack around the level's volumes before a sub counts as escaped rather than crashed.
+const LEVEL_BOUNDS_MARGIN_M: f32 = 50.0;
+
+/// Union of the active level's room, tunnel, chamber, side tunnel and torus AABBs, grown by
+/// `LEVEL_BOUNDS_MARGIN_M`. Walls already disconnect subs that leave open water; a sub outside
+/// these bounds (or with a NaN position) means the simulation itself blew up.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct LevelBounds {
+    pub min: Vec3f,
+    pub max: Vec3f,
+    /// Open-water points an escaped sub is returned to; the tunnel entrance comes first.
+    pub spawn_points: Vec<Vec3f>,
+}
+
+impl LevelBounds {
+    pub fn from_level(level: &LevelSpec) -> Self {
+        let room_center = Vec3f::new(
+            0.0,
+            level.room.size.y * 0.5 - level.room.wall_thickness,
+            0.0,
+        );
+        let mut boxes = vec![
+            (room_center, level.room.size * 0.5),
+            (level.tunnel.pos, level.tunnel.size * 0.5),
+            (level.chamber.pos, level.chamber.size * 0.5),
+        ];
+        boxes.extend(level.side_tunnels.iter().map(|t| (t.pos, t.size * 0.5)));
+        if let Some(torus) = &level.torus_tunnel {
+            let reach = torus.major_radius + torus.minor_radius + torus.wall_thickness;
+            boxes.push((torus.center, Vec3f::splat(reach)));
+        }
+        let (min, max) = boxes.iter().fold(
+            (Vec3f::splat(f32::INFINITY), Vec3f::splat(f32::NEG_INFINITY)),
+            |(min, max), &(center, half)| (min.min(center - half), max.max(center + half)),
+        );
+        Self {
+            min: min - Vec3f::splat(LEVEL_BOUNDS_MARGIN_M),
+            max: max + Vec3f::splat(LEVEL_BOUNDS_MARGIN_M),
+            spawn_points: vec![tunnel_entrance(level), room_center, level.chamber.pos],
+        }
+    }
+
+    /// False for NaN positions as well as ones outside the bounds.
+    pub fn contains(&self, p: Vec3f) -> bool {
+        p.cmpge(self.min).all() && p.cmple(self.max).all()
+    }
+
+    fn nearest_spawn(&self, p: Vec3f) -> Vec3f {
+        let first = self.spawn_points.first().copied().unwrap_or_default();
+        if !p.is_finite() {
+            return first;
+        }
+        self.spawn_points
+            .iter()
+            .copied()
+            .min_by(|a, b| a.distance_squared(p).total_cmp(&b.distance_squared(p)))
+            .unwrap_or(first)
+    }
+}
+
+/// Put a sub that left `bounds` (or went NaN) back at the nearest spawn point, at rest.
+/// Returns whether it had to; the caller reports it to the player.
+pub fn clamp_sub_state(state: &mut SubState, bounds: &LevelBounds) -> bool {
+    if bounds.contains(state.position) {
+        return false;
+    }
+    state.position = bounds.nearest_spawn(state.position);
+    state.velocity = Vec3f::ZERO;
+    state.ang_mom = Vec3f::ZERO;
+    if !state.orientation.is_finite() {
+        state.orientation = Quatf::IDENTITY;
+    }
+    for fill in &mut state.ballast_fill {
+        if !fill.is_finite() {
+            *fill = 0.5;
+        }
+    }
+    true
+}
+
+/// Integrity reported (and left) after a sub is pulled back in bounds.
+const OUT_OF_BOUNDS_INTEGRITY: f32 = 0.1;
+
+/// Connection events arrive through `Events<ServerEvent>`: `RenetServerPlugin` drains
+/// `RenetServer::get_event` in `PreUpdate`, so polling the server here would see nothing.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn server_handle_events(
-    mut server: ResMut<RenetServer>,
+    mut events: EventReader<ServerEvent>,
     mut commands: Commands,
     mut clients: ResMut<ClientEntities>,
+    mut departed: ResMut<DepartedPlayers>,
+    kicked: Res<KickedPlayers>,
+    mut rate_monitor: ResMut<ClientRateMonitor>,
+    mut legacy: ResMut<LegacyClients>,
+    time: Res<Time>,
+    q_players: Query<(
+        &Player,
+        &PlayerScore,
+        &Team,
+        &SubStateComp,
+        Option<&HullIntegrity>,
+        Option<&LatencyHistogram>,
+        Option<&CargoHold>,
+    )>,
 ) {
-    while let Some(event) = server.get_event() {
+    for event in events.read() {
         match event {
             ServerEvent::ClientConnected { client_id } => {
                 info!(?client_id, "client connected");
             }
             ServerEvent::ClientDisconnected { client_id, reason } => {
                 info!(?client_id, ?reason, "client disconnected");
-                if let Some(entity) = clients.0.remove(&client_id) {
+                legacy.0.remove(client_id);
+                if let Some(entity) = clients.0.remove(client_id) {
+                    if let Ok((player, score, team, state, hull, latency, cargo)) =
+                        q_players.get(entity)
+                    {
+                        rate_monitor.forget(&player.id);
+                        // A kicked player doesn't get to pick up where they left off
+                        if kicked.0.contains_key(&player.id) {
+                            info!(player_id = %player.id, "dropping kicked player's state");
+                        } else {
+                            departed.remember(
+                                *client_id,
+                                DepartedPlayer {
+                                    player_id: player.id,
+                                    score: *score,
+                                    team: *team,
+                                    sub_state: Some(state.0.clone()),
+                                    hull: hull.copied().unwrap_or_default(),
+                                    cargo: cargo.cloned().unwrap_or_default(),
+                                    left_at: time.elapsed(),
+                                },
+                            );
+                        }
+                        if let Some(latency) = latency.filter(|l| l.count() > 0) {
+                            info!(
+                                ?client_id,
+                                samples = latency.count(),
+                                p50_ms = latency.percentile_ms(0.50),
+                                p95_ms = latency.percentile_ms(0.95),
+                                p99_ms = latency.percentile_ms(0.99),
+                                "client latency"
+                            );
+                        }
+                    }
                     commands.entity(entity).despawn();
                 }
             }
@@ -243,132 +1063,109 @@ fn server_handle_events(
     }
 }
 
-fn server_handle_messages(
-    mut server: ResMut<RenetServer>,
-    mut commands: Commands,
-    level: Res<LevelRes>,
-    mut clients: ResMut<ClientEntities>,
-    mut paused: ResMut<SimPaused>,
-    cfg: Res<Config>,
-    mut inbox: ResMut<InputEventInbox>,
+fn server_drop_rejected_clients(
+    mut server: VersionedServer,
+    mut rejected: ResMut<RejectedClients>,
 ) {
-    for client_id in server.clients_id() {
-        while let Some(payload) = server.receive_message(client_id, DefaultChannel::ReliableOrdered)
-        {
-            match protocol::decode::<ClientToServer>(payload.as_ref()) {
-                Ok(ClientToServer::Hello(hello)) => {
-                    if hello.protocol != PROTOCOL_VERSION {
-                        let msg =
-                            ServerToClient::Disconnect(DisconnectReason::IncompatibleProtocol {
-                                server: PROTOCOL_VERSION,
-                                client: hello.protocol,
-                            });
-                        server.send_message(
-                            client_id,
-                            DefaultChannel::ReliableOrdered,
-                            protocol::encode(&msg).unwrap(),
-                        );
-                        server.disconnect(client_id);
-                        continue;
-                    }
-                    // Assign a UUID and ack, and spawn a server-side player + submarine state
-                    let player_uuid = Uuid::new_v4();
-                    let ack = ServerToClient::JoinAck(protocol::JoinAck {
-                        player_id: player_uuid,
-                        tick_hz: cfg.tick_hz.max(1),
-                    });
-                    server.send_message(
-                        client_id,
-                        DefaultChannel::ReliableOrdered,
-                        protocol::encode(&ack).unwrap(),
-                    );
-                    info!(
-                        ?client_id,
-                        name = hello.display_name.as_deref().unwrap_or("(anon)"),
-                        "sent JoinAck"
-                    );
+    for client_id in rejected.0.drain(..) {
+        server.disconnect(client_id);
+    }
+}
 
-                    // Avoid double-spawn on repeated Hello
-                    if clients.0.contains_key(&client_id) {
-                        continue;
-                    }
+type PhysicsSubData = (
+    Entity,
+    &'static mut SubStateComp,
+    &'static SubPhysicsComp,
+    Option<&'static mut ControlInputComp>,
+    &'static mut SubInputStateComp,
+    Option<&'static mut HullIntegrity>,
+    Option<&'static Player>,
+    Option<&'static mut InputBuffer>,
+    Option<&'static mut AntiCheatState>,
+);
+type PhysicsSub<'a> = QueryItem<'a, PhysicsSubData>;
 
-                    // Compute a start position near tunnel entrance
-                    let t = &level.0.tunnel;
-                    let half_x = t.size.x * 0.5;
-                    let start = Vec3f::new(t.pos.x - half_x + 6.0, t.pos.y, t.pos.z);
-                    // Align spawn orientation to the local flow direction in XZ (nose points with the flow)
-                    let (flow, _) = levels::sample_flow_at(&level.0, start, 0.0);
-                    let mut yaw = 0.0f32;
-                    let fxz = (flow.x * flow.x + flow.z * flow.z).sqrt();
-                    if fxz > 1e-3 {
-                        yaw = flow.x.atan2(flow.z);
-                    }
-                    let spec = small_skiff_spec();
-                    let entity = commands
-                        .spawn((
-                            Player { id: player_uuid },
-                            Submarine,
-                            SubStateComp(SubState {
-                                position: start,
-                                velocity: Vec3f::new(0.0, 0.0, 0.0),
-                                orientation: Quatf::from_rotation_y(yaw),
-                                ang_mom: Vec3f::new(0.0, 0.0, 0.0),
-                                ballast_fill: vec![0.5; spec.ballast_tanks.len()],
-                            }),
-                            SubPhysicsComp(spec),
-                            Name::new(format!("Player {player_uuid}")),
-                        ))
-                        .id();
-                    clients.0.insert(client_id, entity);
-                }
-                Ok(ClientToServer::InputTick(input)) => {
-                    // For now ignore in physics; acknowledge receipt only.
-                    let ack = ServerToClient::InputAck(protocol::InputAck { tick: input.tick });
-                    let payload = protocol::encode(&ack).unwrap();
-                    server.send_message(client_id, DefaultChannel::ReliableOrdered, payload);
-                    // Update or insert control input on the client's entity
-                    if let Some(&entity) = clients.0.get(&client_id) {
-                        let thrust = input.thrust.clamp(-1.0, 1.0);
-                        let yaw = input.yaw.clamp(-1.0, 1.0);
-                        let pump_fwd = input.pump_fwd.clamp(-1.0, 1.0);
-                        let pump_aft = input.pump_aft.clamp(-1.0, 1.0);
-                        commands.entity(entity).insert(ControlInputComp {
-                            thrust,
-                            yaw,
-                            pump_fwd,
-                            pump_aft,
-                            last_tick: input.tick,
-                        });
-                    }
-                }
-                Ok(ClientToServer::InputEvent(ev)) => {
-                    // Queue future-dated input; apply in physics tick when t_ms has passed
-                    if let Some(&entity) = clients.0.get(&client_id) {
-                        let evc = protocol::InputEvent {
-                            t_ms: ev.t_ms,
-                            thrust: ev.thrust.clamp(-1.0, 1.0),
-                            yaw: ev.yaw.clamp(-1.0, 1.0),
-                            pump_fwd: ev.pump_fwd.clamp(-1.0, 1.0),
-                            pump_aft: ev.pump_aft.clamp(-1.0, 1.0),
-                        };
-                        inbox.0.push((entity, evc));
-                    }
-                }
-                Ok(ClientToServer::PauseRequest(req)) => {
-                    paused.0 = req.paused;
-                    let msg = ServerToClient::PauseState(protocol::PauseState { paused: paused.0 });
-                    let payload = protocol::encode(&msg).unwrap();
-                    for id in server.clients_id() {
-                        server.send_message(id, DefaultChannel::ReliableOrdered, payload.clone());
-                    }
-                }
-                Ok(other) => {
-                    warn!(?client_id, ?other, "unexpected message before world init");
+/// A clamped implausible velocity, for the warning logged after the step.
+struct SpeedClamp {
+    speed: f32,
+    max_speed: f32,
+    strikes: u32,
+}
+
+/// What one sub's physics step left for `server_physics_tick` to act on once every sub has
+/// stepped: messages to its client, a kick, or a disconnect.
+struct SubTickOutcome {
+    entity: Entity,
+    player: Option<Uuid>,
+    /// Where the sub was before being put back inside the level bounds.
+    escaped_at: Option<Vec3f>,
+    speed_clamp: Option<SpeedClamp>,
+    kick: bool,
+    /// Integrity after the step, when it fell below `HULL_ALERT_THRESHOLD`.
+    hull_alert: Option<f32>,
+    /// Hit the tunnel wall.
+    collided: bool,
+}
+
+impl SubTickOutcome {
+    /// Bounds, anti-cheat and hull checks on a freshly stepped sub. Only touches the sub's own
+    /// components, so subs can run it in parallel.
+    #[allow(clippy::too_many_arguments)]
+    fn step_sub(
+        &mut self,
+        level: &LevelSpec,
+        bounds: &LevelBounds,
+        dt: f32,
+        spec: &SubPhysicsSpec,
+        state: &mut SubState,
+        mut hull: Option<Mut<HullIntegrity>>,
+        anti_cheat: Option<Mut<AntiCheatState>>,
+    ) {
+        let escaped_at = state.position;
+        if clamp_sub_state(state, bounds) {
+            self.escaped_at = Some(escaped_at);
+            if let Some(hull) = hull.as_mut() {
+                hull.0 = hull.0.min(OUT_OF_BOUNDS_INTEGRITY);
+            }
+        }
+
+        if let Some(mut anti_cheat) = anti_cheat {
+            let max_speed = max_plausible_speed(spec);
+            if let Some(speed) = anti_cheat.check_velocity(&mut state.velocity, max_speed) {
+                self.speed_clamp = Some(SpeedClamp {
+                    speed,
+                    max_speed,
+                    strikes: anti_cheat.velocity_strikes,
+                });
+            }
+            if anti_cheat.should_kick() {
+                self.kick = true;
+                anti_cheat.velocity_strikes = 0;
+            }
+        }
+
+        if let Some(mut hull) = hull {
+            let before = hull.0;
+            for vent in &level.thermal_vents {
+                if vent.contains(state.position) {
+                    hull.0 -= vent.damage_per_s * dt;
                 }
-                Err(err) => warn!(?client_id, ?err, "failed to decode client message"),
+            }
+            hull.0 = hull.0.max(0.0);
+            if before >= HULL_ALERT_THRESHOLD && hull.0 < HULL_ALERT_THRESHOLD {
+                self.hull_alert = Some(hull.0);
             }
         }
+
+        self.collided = !level.in_open_water(state.position);
+    }
+
+    fn needs_follow_up(&self) -> bool {
+        self.escaped_at.is_some()
+            || self.speed_clamp.is_some()
+            || self.kick
+            || self.hull_alert.is_some()
+            || self.collided
     }
 }
 
@@ -378,20 +1175,17 @@ fn server_physics_tick(
     mut timing: ResMut<PhysicsTiming>,
     level: Res<LevelRes>,
     mut tick: ResMut<Tick>,
-    mut server: ResMut<RenetServer>,
+    mut server: VersionedServer,
     mut clients: ResMut<ClientEntities>,
     mut commands: Commands,
-    mut q: Query<(
-        Entity,
-        &mut SubStateComp,
-        &SubPhysicsComp,
-        Option<&ControlInputComp>,
-        Option<&mut InputSchedule>,
-        &mut SubInputStateComp,
-    )>,
+    mut q: Query<PhysicsSubData>,
     paused: Res<SimPaused>,
+    cfg: Res<Config>,
     start: Res<ServerStart>,
-    mut inbox: ResMut<InputEventInbox>,
+    mut scheduled: ResMut<ScheduledInputQueue>,
+    mut q_obstacles: Query<&mut MovingObstacle>,
+    bounds: Res<LevelBounds>,
+    (mut rejected, mut kicked): (ResMut<RejectedClients>, ResMut<KickedPlayers>),
 ) {
     if paused.0 {
         // Drop accumulated dt to avoid huge catch-up on resume.
@@ -400,51 +1194,51 @@ fn server_physics_tick(
     }
     timing.acc += time.delta_secs();
     while timing.acc >= timing.dt {
+        advance_obstacles(&mut q_obstacles, timing.dt);
         let now_ms = start.0.elapsed().as_millis() as u64;
-        if !inbox.0.is_empty() {
-            for (entity, evc) in inbox.0.drain(..) {
-                if let Ok((_e, _s, _sp, _ci, Some(mut sched), _input_state)) = q.get_mut(entity) {
-                    let pos = sched
-                        .0
-                        .iter()
-                        .position(|e| e.t_ms > evc.t_ms)
-                        .unwrap_or(sched.0.len());
-                    sched.0.insert(pos, evc);
-                } else {
-                    commands
-                        .entity(entity)
-                        .insert(InputSchedule(VecDeque::from([evc])));
-                }
+        for (entity, ev) in scheduled.drain_due(now_ms) {
+            let Ok((_e, _s, _sp, input, _input_state, _hull, _player, buffer, _anti_cheat)) =
+                q.get_mut(entity)
+            else {
+                continue;
+            };
+            // The client has moved on to time-stamped events; its buffered ticks are stale
+            if let Some(mut buffer) = buffer {
+                buffer.clear();
             }
-        }
-        for (entity, mut s, spec, input, schedule, mut input_state) in &mut q {
-            // Apply any scheduled inputs whose time has arrived
-            if let Some(mut sched) = schedule {
-                while let Some(front) = sched.0.front() {
-                    if front.t_ms > now_ms {
-                        break;
-                    }
-                    let ev = sched.0.pop_front().unwrap();
-                    commands.entity(entity).insert(ControlInputComp {
-                        thrust: ev.thrust,
-                        yaw: ev.yaw,
-                        pump_fwd: ev.pump_fwd,
-                        pump_aft: ev.pump_aft,
-                        last_tick: tick.0,
-                    });
+            let applied = ControlInputComp {
+                thrust: ev.thrust,
+                yaw: ev.yaw,
+                pump_fwd: ev.pump_fwd,
+                pump_aft: ev.pump_aft,
+                pitch: ev.pitch,
+                roll_trim: ev.roll_trim,
+                last_tick: tick.0,
+            };
+            match input {
+                Some(mut input) => *input = applied,
+                None => {
+                    commands.entity(entity).insert(applied);
                 }
             }
-            let raw_inputs = if let Some(ci) = input {
-                SubInputs {
-                    thrust: ci.thrust,
-                    yaw: ci.yaw,
-                    pump_fwd: ci.pump_fwd,
-                    pump_aft: ci.pump_aft,
-                }
-            } else {
-                SubInputs::default()
-            };
-            input_state.0.apply_inputs(raw_inputs);
+        }
+        let mut outcomes = Parallel::<Vec<SubTickOutcome>>::default();
+        let step = |(
+            entity,
+            mut s,
+            spec,
+            input,
+            mut input_state,
+            hull,
+            player,
+            buffer,
+            anti_cheat,
+        ): PhysicsSub| {
+            let raw_inputs = buffer
+                .and_then(|b| b.select(tick.0, cfg.jitter_buffer_ticks))
+                .or_else(|| input.as_deref().map(ControlInputComp::sub_inputs))
+                .unwrap_or_default();
+            input_state.0.ramp_toward(raw_inputs, timing.dt, &spec.0);
             let commanded = input_state.0;
             step_submarine(
                 &level.0,
@@ -454,40 +1248,107 @@ fn server_physics_tick(
                 timing.dt,
                 time.elapsed_secs(),
             );
-
-            // Allowed space: inside the station room, the tunnel, or the chamber.
-            // If outside all three interior AABBs, treat as a wall collision.
-            let p = s.0.position;
-            let inside = |center: Vec3f, size: Vec3f| -> bool {
-                let hx = size.x * 0.5;
-                let hy = size.y * 0.5;
-                let hz = size.z * 0.5;
-                p.x >= center.x - hx
-                    && p.x <= center.x + hx
-                    && p.y >= center.y - hy
-                    && p.y <= center.y + hy
-                    && p.z >= center.z - hz
-                    && p.z <= center.z + hz
+            push_out_of_obstacles(&mut s.0, spec.0.diameter * 0.5, &q_obstacles);
+            let mut outcome = SubTickOutcome {
+                entity,
+                player: player.map(|p| p.id),
+                escaped_at: None,
+                speed_clamp: None,
+                kick: false,
+                hull_alert: None,
+                collided: false,
             };
-            let in_room = inside(
-                level.0.room.dock_pos + Vec3f::new(16.0, -0.4, 16.0),
-                level.0.room.size,
-            );
-            // The room spec uses wall thickness and sizes; center is at origin in our greybox
-            // so prefer (0, room_h/2 - wall_thickness, 0) as approximate center. Fall back to (0,0,0).
-            let room_center = Vec3f::new(
-                0.0,
-                level.0.room.size.y * 0.5 - level.0.room.wall_thickness,
-                0.0,
+            outcome.step_sub(
+                &level.0, &bounds, timing.dt, &spec.0, &mut s.0, hull, anti_cheat,
             );
-            let in_room2 = inside(room_center, level.0.room.size);
-            let in_tunnel = inside(level.0.tunnel.pos, level.0.tunnel.size);
-            let in_chamber = inside(level.0.chamber.pos, level.0.chamber.size);
-            let allowed = in_room || in_room2 || in_tunnel || in_chamber;
-            let collide = !allowed;
-            if collide {
-                // Find client_id for this entity and disconnect once; also cleanup entity & mapping immediately
-                if let Some((&client_id, _)) = clients.0.iter().find(|(_, &e)| e == entity) {
+            if outcome.needs_follow_up() {
+                outcomes.borrow_local_mut().push(outcome);
+            }
+        };
+        #[cfg(feature = "parallel_physics")]
+        q.par_iter_mut().for_each(step);
+        #[cfg(not(feature = "parallel_physics"))]
+        q.iter_mut().for_each(step);
+
+        // Sends, kicks and despawns touch shared state; apply them in the order a sequential
+        // pass would have
+        let mut merged = Vec::new();
+        outcomes.drain_into(&mut merged);
+        merged.sort_unstable_by_key(|o| o.entity);
+        for outcome in merged {
+            let entity = outcome.entity;
+            let player_id = || {
+                outcome
+                    .player
+                    .map_or_else(|| format!("{entity:?}"), |id| id.to_string())
+            };
+            let client_id = clients
+                .0
+                .iter()
+                .find(|(_, &e)| e == entity)
+                .map(|(&client_id, _)| client_id);
+
+            if let Some(escaped_at) = outcome.escaped_at {
+                error!(
+                    "Submarine {} out of bounds at {:?}",
+                    player_id(),
+                    escaped_at
+                );
+                if let Some(client_id) = client_id {
+                    let alert = ServerToClient::HullAlert(protocol::HullAlert {
+                        integrity: OUT_OF_BOUNDS_INTEGRITY,
+                    });
+                    server.send_message(
+                        client_id,
+                        Channel::Reliable,
+                        protocol::encode(&alert).unwrap(),
+                    );
+                }
+            }
+
+            if let Some(clamp) = outcome.speed_clamp {
+                warn!(
+                    player_id = %player_id(),
+                    speed = clamp.speed,
+                    max_speed = clamp.max_speed,
+                    strikes = clamp.strikes,
+                    "Clamped implausible sub velocity"
+                );
+            }
+            if outcome.kick {
+                if let Some(client_id) = client_id {
+                    warn!(
+                        client_id,
+                        "Kicking client for sustained implausible velocity"
+                    );
+                    let msg = ServerToClient::Disconnect(DisconnectReason::Kicked);
+                    server.send_message(
+                        client_id,
+                        Channel::Reliable,
+                        protocol::encode(&msg).unwrap(),
+                    );
+                    rejected.0.push(client_id);
+                }
+                if let Some(player) = outcome.player {
+                    kicked.kick(player, time.elapsed());
+                }
+            }
+
+            if let Some(integrity) = outcome.hull_alert {
+                if let Some(client_id) = client_id {
+                    tracing::warn!(?client_id, integrity, "Hull integrity critical");
+                    let alert = ServerToClient::HullAlert(protocol::HullAlert { integrity });
+                    server.send_message(
+                        client_id,
+                        Channel::Reliable,
+                        protocol::encode(&alert).unwrap(),
+                    );
+                }
+            }
+
+            if outcome.collided {
+                // Disconnect the sub's client once; also cleanup entity & mapping immediately
+                if let Some(client_id) = client_id {
                     tracing::warn!(
                         ?client_id,
                         ?entity,
@@ -498,7 +1359,6 @@ fn server_physics_tick(
                         commands.entity(entity).despawn();
                     }
                 }
-                continue; // skip further processing on collided entity this tick
             }
         }
         timing.acc -= timing.dt;
@@ -506,13 +1366,461 @@ fn server_physics_tick(
     }
 }
 
+/// Sample each client's round-trip time whenever a new input of theirs took effect. The RTT
+/// is renet's, measured from packet acks, since `InputTick::tick` is a client frame counter
+/// rather than a timestamp.
+fn server_record_latency(
+    server: Res<RenetServer>,
+    clients: Res<ClientEntities>,
+    mut q: Query<(Ref<ControlInputComp>, &mut LatencyHistogram)>,
+) {
+    for (&client_id, &entity) in &clients.0 {
+        let Ok((input, mut latency)) = q.get_mut(entity) else {
+            continue;
+        };
+        if !input.is_changed() {
+            continue;
+        }
+        let Ok(info) = server.network_info(client_id) else {
+            continue;
+        };
+        // Zero until the first ack has been timed
+        if info.rtt > 0.0 {
+            latency.record((info.rtt * 1000.0) as f32);
+        }
+    }
+}
+
+/// Keep the discovery beacon's player count in step with the connected clients.
+fn server_update_discovery_count(clients: Res<ClientEntities>, count: Res<DiscoveryPlayerCount>) {
+    if clients.is_changed() {
+        let players = clients.0.len().min(u8::MAX as usize) as u8;
+        count.0.store(players, Ordering::Relaxed);
+    }
+}
+
+/// Apply queued docks and deliveries plus every sub's current depth to its player's missions.
+/// Progress changes go to that player as `MissionUpdate`; a finished mission pays its reward
+/// and sends `MissionComplete`. Players joining (or rejoining) get their standing progress.
+fn server_update_missions(
+    mut server: VersionedServer,
+    clients: Res<ClientEntities>,
+    missions: Res<ActiveMissions>,
+    mut progress: ResMut<PlayerMissionProgress>,
+    mut inbox: ResMut<MissionEventInbox>,
+    mut q_players: Query<(Entity, Ref<Player>, &SubStateComp, &mut PlayerScore)>,
+) {
+    let mut events = std::mem::take(&mut inbox.0);
+    for (entity, player, state, _) in &q_players {
+        events.push((entity, MissionEvent::AtHeight(state.0.position.y)));
+        if !player.is_added() {
+            continue;
+        }
+        let Some((&client_id, _)) = clients.0.iter().find(|(_, &e)| e == entity) else {
+            continue;
+        };
+        let Some(standing) = progress.0.get(&player.id) else {
+            continue;
+        };
+        for mission in &missions.0 {
+            let Some(&done) = standing.get(&mission.id).filter(|&&done| done > 0) else {
+                continue;
+            };
+            let msg = ServerToClient::MissionUpdate(protocol::MissionUpdate {
+                mission_id: mission.id,
+                progress: done,
+                total: mission.objective.total(),
+            });
+            server.send_message(
+                client_id,
+                Channel::Reliable,
+                protocol::encode(&msg).unwrap(),
+            );
+        }
+    }
+
+    for (entity, event) in events {
+        let Ok((_, player, _, mut score)) = q_players.get_mut(entity) else {
+            continue;
+        };
+        let Some((&client_id, _)) = clients.0.iter().find(|(_, &e)| e == entity) else {
+            continue;
+        };
+        let standing = progress.0.entry(player.id).or_default();
+        for mission in &missions.0 {
+            let done = standing.entry(mission.id).or_default();
+            let Some(next) = mission.objective.advance(*done, &event) else {
+                continue;
+            };
+            *done = next;
+            let total = mission.objective.total();
+            let msg = ServerToClient::MissionUpdate(protocol::MissionUpdate {
+                mission_id: mission.id,
+                progress: next,
+                total,
+            });
+            server.send_message(
+                client_id,
+                Channel::Reliable,
+                protocol::encode(&msg).unwrap(),
+            );
+            if next < total {
+                continue;
+            }
+            score.credits += mission.reward_credits;
+            info!(?client_id, mission_id = mission.id, "mission complete");
+            let msg = ServerToClient::MissionComplete(protocol::MissionComplete {
+                mission_id: mission.id,
+                reward: mission.reward_credits,
+            });
+            server.send_message(
+                client_id,
+                Channel::Reliable,
+                protocol::encode(&msg).unwrap(),
+            );
+        }
+    }
+}
+
+/// Obstacles are solid boxes: move a sub overlapping one back out through the nearest face and
+/// drop its velocity into that face. A gate moving into a sub thus shoves it along.
+fn push_out_of_obstacles(
+    state: &mut SubState,
+    radius: f32,
+    obstacles: &Query<&mut MovingObstacle>,
+) {
+    for obstacle in obstacles {
+        let Some((normal, depth)) =
+            obstacle
+                .spec
+                .contact(obstacle.position, state.position, radius)
+        else {
+            continue;
+        };
+        state.position += normal * depth;
+        let into = state.velocity.dot(normal);
+        if into < 0.0 {
+            state.velocity -= normal * into;
+        }
+    }
+}
+
+/// Advance every moving obstacle along its path by one physics step, so subs collide with
+/// them at the positions they had on that tick.
+fn advance_obstacles(obstacles: &mut Query<&mut MovingObstacle>, dt: f32) {
+    for mut obstacle in obstacles {
+        obstacle.traveled_m += obstacle.spec.speed_m_s * dt;
+        // Keep the odometer small so f32 precision holds on long-running servers
+        let lap = obstacle.spec.path_length();
+        if lap > 0.0 {
+            obstacle.traveled_m %= lap;
+        }
+        obstacle.position = obstacle.spec.position_at(obstacle.traveled_m);
+    }
+}
+
+/// Reload tubes, launch queued `FireTorpedo` requests from loaded tubes of players off
+/// cooldown, and fly torpedoes straight under drag until they hit a wall, pass near another
+/// sub or time out. Frozen while paused; requests made while paused are dropped.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn server_torpedo_tick(
+    time: Res<Time>,
+    paused: Res<SimPaused>,
+    level: Res<LevelRes>,
+    mut commands: Commands,
+    mut server: VersionedServer,
+    mut launches: ResMut<TorpedoLaunchInbox>,
+    mut cooldown: ResMut<TorpedoCooldown>,
+    mut next_id: Local<u32>,
+    mut q_subs: Query<(
+        Entity,
+        &SubStateComp,
+        &SubPhysicsComp,
+        &mut TorpedoTubes,
+        Option<&GrantedFeatures>,
+    )>,
+    mut q_torpedoes: Query<(Entity, &mut Torpedo)>,
+) {
+    if paused.0 {
+        launches.0.clear();
+        return;
+    }
+    let dt = time.delta_secs();
+    for (_, _, _, mut tubes, _) in &mut q_subs {
+        for reload in &mut tubes.0 {
+            *reload = (*reload - dt).max(0.0);
+        }
+    }
+    cooldown.0.retain(|_, remaining| {
+        *remaining -= dt;
+        *remaining > 0.0
+    });
+
+    for (entity, fire) in launches.0.drain(..) {
+        let Ok((_, state, spec, mut tubes, granted)) = q_subs.get_mut(entity) else {
+            continue;
+        };
+        if granted.is_some_and(|g| !g.0.contains(FeatureFlags::TORPEDO)) {
+            continue;
+        }
+        let Some(reload) = tubes.0.get_mut(fire.tube_id as usize) else {
+            continue;
+        };
+        if *reload > 0.0 || cooldown.0.contains_key(&entity) {
+            continue;
+        }
+        *reload = spec.0.torpedo_reload_s;
+        cooldown.0.insert(entity, TORPEDO_COOLDOWN_S);
+        let heading = match fire.heading_override {
+            Some(yaw_deg) => {
+                let yaw = yaw_deg.to_radians();
+                Vec3f::new(-yaw.sin(), 0.0, yaw.cos())
+            }
+            None => {
+                // Positive bearing is to starboard: a negative rotation about +Y swings the
+                // bow towards -X, the same sense as a right turn (positive heading yaw)
+                let bearing = Quatf::from_rotation_y(-fire.target_bearing_deg.to_radians());
+                let mut heading = state.0.orientation * bearing * Vec3f::Z;
+                heading.y = 0.0;
+                heading.normalize_or(Vec3f::Z)
+            }
+        };
+        let launch_speed = if fire.speed_mps > 0.0 {
+            fire.speed_mps.min(protocol::TORPEDO_MAX_SPEED_M_S)
+        } else {
+            protocol::TORPEDO_SPEED_M_S
+        };
+        let velocity = state.0.velocity + heading * launch_speed;
+        let speed_m_s = velocity.length();
+        let direction = velocity.normalize_or(heading);
+        let origin = state.0.position + heading * (spec.0.length * 0.5 + 0.5);
+        let id = *next_id;
+        *next_id = next_id.wrapping_add(1);
+        commands.spawn((
+            Torpedo {
+                id,
+                owner: entity,
+                position: origin,
+                direction,
+                speed_m_s,
+                age_s: 0.0,
+            },
+            Name::new(format!("Torpedo {id}")),
+        ));
+        let msg = ServerToClient::TorpedoSpawned(protocol::TorpedoSpawned {
+            id,
+            origin: origin.to_array(),
+            direction: direction.to_array(),
+            speed_m_s,
+        });
+        let payload = protocol::encode(&msg).unwrap();
+        for client_id in server.clients_id() {
+            server.send_message(client_id, Channel::Reliable, payload.clone());
+        }
+    }
+
+    for (entity, mut torpedo) in &mut q_torpedoes {
+        let speed = protocol::torpedo_drag(torpedo.speed_m_s, dt);
+        let step = torpedo.direction * speed * dt;
+        torpedo.position += step;
+        torpedo.speed_m_s = speed;
+        torpedo.age_s += dt;
+        let hit_sub = q_subs.iter().any(|(sub, state, ..)| {
+            sub != torpedo.owner
+                && (state.0.position - torpedo.position).length() <= TORPEDO_PROXIMITY_M
+        });
+        if !hit_sub && torpedo.age_s < TORPEDO_TTL_S && level.0.in_open_water(torpedo.position) {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        let msg = ServerToClient::TorpedoDetonation(protocol::TorpedoDetonation {
+            id: torpedo.id,
+            position: torpedo.position.to_array(),
+            radius_m: TORPEDO_BLAST_RADIUS_M,
+        });
+        let payload = protocol::encode(&msg).unwrap();
+        for client_id in server.clients_id() {
+            server.send_message(client_id, Channel::Reliable, payload.clone());
+        }
+    }
+}
+
+/// Count down depleted ore nodes (frozen while paused) and announce each one that respawns,
+/// refilled to its full supply.
+fn server_respawn_ore(
+    time: Res<Time>,
+    paused: Res<SimPaused>,
+    level: Res<LevelRes>,
+    mut server: VersionedServer,
+    mut q_ore: Query<&mut OreNode>,
+) {
+    if paused.0 {
+        return;
+    }
+    for mut ore in &mut q_ore {
+        let spec = level.0.ore_nodes.get(ore.node_id as usize);
+        if !ore.tick_respawn(time.delta(), spec) {
+            continue;
+        }
+        let msg = ServerToClient::EntitySpawn(protocol::EntitySpawn {
+            node_id: ore.node_id,
+        });
+        let payload = protocol::encode(&msg).unwrap();
+        for id in server.clients_id() {
+            server.send_message(id, Channel::Reliable, payload.clone());
+        }
+    }
+}
+
+/// Move to the next campaign level once its unlock condition holds: swap the level, replace its
+/// ore nodes and moving obstacles, put every sub back at the start and tell clients to reload.
+#[allow(clippy::type_complexity)]
+fn server_advance_campaign(
+    mut commands: Commands,
+    mut campaign: ResMut<CampaignRes>,
+    mut level: ResMut<LevelRes>,
+    mut server: VersionedServer,
+    mut departed: ResMut<DepartedPlayers>,
+    q_level_entities: Query<Entity, Or<(With<OreNode>, With<MovingObstacle>)>>,
+    mut q_players: Query<(
+        &PlayerScore,
+        &SubPhysicsComp,
+        &mut SubStateComp,
+        &mut SubInputStateComp,
+        Option<&mut HullIntegrity>,
+    )>,
+) {
+    let credits = q_players.iter().map(|(score, ..)| score.credits).sum();
+    let Some(next) = campaign
+        .spec
+        .next_unlocked(campaign.active, credits, &campaign.completed)
+    else {
+        return;
+    };
+    info!(
+        from = campaign.active,
+        to = next,
+        credits,
+        "campaign level won"
+    );
+    let won = campaign.active;
+    campaign.completed.push(won);
+    campaign.active = next;
+    level.0 = campaign.spec.levels[next].level_spec.clone();
+    log_level_errors(next, &level.0);
+
+    for entity in &q_level_entities {
+        commands.entity(entity).despawn();
+    }
+    spawn_ore_nodes(&mut commands, &level.0);
+    spawn_moving_obstacles(&mut commands, &level.0);
+    commands.insert_resource(LevelBounds::from_level(&level.0));
+    for (_, spec, mut state, mut input_state, hull) in &mut q_players {
+        state.0 = start_state(&level.0, &spec.0);
+        input_state.0 = SubInputState::default();
+        if let Some(mut hull) = hull {
+            *hull = HullIntegrity::default();
+        }
+    }
+    for back in departed.0.values_mut() {
+        back.sub_state = None;
+        back.hull = HullIntegrity::default();
+    }
+
+    let msg = ServerToClient::LevelReload(protocol::LevelReload {
+        map_id: next as u32,
+    });
+    let payload = protocol::encode(&msg).unwrap();
+    for id in server.clients_id() {
+        server.send_message(id, Channel::Reliable, payload.clone());
+    }
+}
+
+fn server_start_frame_timer(mut tick_rate: ResMut<TickRateGovernor>) {
+    tick_rate.start_frame();
+}
+
+/// Feed this frame's work time to the `TickRateGovernor`; when it changes the rate, retime the
+/// physics step and tell every client.
+fn server_adapt_tick_rate(
+    time: Res<Time>,
+    mut tick_rate: ResMut<TickRateGovernor>,
+    mut timing: ResMut<PhysicsTiming>,
+    mut server: VersionedServer,
+) {
+    let busy_s = tick_rate.frame_busy_s();
+    let Some(new_hz) = tick_rate.record_frame(busy_s, time.delta_secs()) else {
+        return;
+    };
+    info!(
+        new_hz,
+        max_hz = tick_rate.max_hz,
+        "Changing physics tick rate for server load"
+    );
+    timing.dt = 1.0 / new_hz as f32;
+    let msg = ServerToClient::TickRateChange(protocol::TickRateChange { new_hz });
+    let payload = protocol::encode(&msg).unwrap();
+    for client_id in server.clients_id() {
+        server.send_message(client_id, Channel::Reliable, payload.clone());
+    }
+}
+
+fn server_broadcast_leaderboard(
+    time: Res<Time>,
+    mut timer: ResMut<LeaderboardTimer>,
+    mut server: VersionedServer,
+    team_scores: Res<TeamScores>,
+    q: Query<(&Player, &DisplayName, &PlayerScore)>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let entries = q
+        .iter()
+        .map(|(player, name, score)| protocol::LeaderboardEntry {
+            player_id: player.id,
+            display_name: name.0.clone(),
+            credits: score.credits,
+            mines: score.mines,
+            docks: score.docks,
+        })
+        .collect();
+    let mut team_scores: Vec<_> = team_scores
+        .0
+        .iter()
+        .map(|(&team_id, &score)| protocol::TeamScore { team_id, score })
+        .collect();
+    team_scores.sort_by_key(|t| t.team_id);
+    let msg = ServerToClient::LeaderboardUpdate(protocol::LeaderboardUpdate {
+        entries,
+        team_scores,
+    });
+    let payload = protocol::encode(&msg).unwrap();
+    for client_id in server.clients_id() {
+        server.send_message(client_id, Channel::Reliable, payload.clone());
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn server_broadcast_state(
     time: Res<Time>,
     mut timing: ResMut<SnapshotTiming>,
     tick: Res<Tick>,
     start: Res<ServerStart>,
-    mut server: ResMut<RenetServer>,
-    q: Query<(&Player, &SubStateComp, &SubInputStateComp)>,
+    mut server: VersionedServer,
+    clients: Res<ClientEntities>,
+    grid: Res<AoiGrid>,
+    mut corrections: ResMut<PendingCorrections>,
+    q: Query<(
+        &Player,
+        &SubStateComp,
+        &SubPhysicsComp,
+        &SubInputStateComp,
+        Option<&HullIntegrity>,
+        Option<&Team>,
+    )>,
+    q_obstacles: Query<&MovingObstacle>,
+    mut encode_buf: Local<Vec<u8>>,
 ) {
     timing.acc += time.delta_secs();
     if timing.acc < timing.dt {
@@ -521,7 +1829,9 @@ fn server_broadcast_state(
     timing.acc -= timing.dt;
 
     let mut players = Vec::new();
-    for (player, state, input_state) in &q {
+    for (player, state, spec, input_state, hull, team) in &q {
+        let rate = |l: f32, i: f32| if i > 0.0 { l / i } else { 0.0 };
+        let inertia = spec.0.rotational_inertia();
         players.push(protocol::NetPlayer {
             id: player.id,
             position: [state.0.position.x, state.0.position.y, state.0.position.z],
@@ -533,24 +1843,171 @@ fn server_broadcast_state(
                 state.0.orientation.w,
             ],
             ang_mom: [state.0.ang_mom.x, state.0.ang_mom.y, state.0.ang_mom.z],
+            angular_velocity: [
+                rate(state.0.ang_mom.x, inertia.x),
+                rate(state.0.ang_mom.y, inertia.y),
+                rate(state.0.ang_mom.z, inertia.z),
+            ],
             ballast_fill: state.0.ballast_fill.clone(),
             input_state: protocol::NetInputState {
                 thrust: input_state.0.thrust,
                 yaw: input_state.0.yaw,
                 pump_fwd: input_state.0.pump_fwd,
                 pump_aft: input_state.0.pump_aft,
+                pitch: input_state.0.plane,
+                roll_trim: input_state.0.roll_trim,
+            },
+            hull_integrity: hull.map(|h| h.0).unwrap_or(1.0),
+            team_id: team.map(|t| t.0).unwrap_or(0),
+            pump: protocol::NetPumpState {
+                boost_remaining_s: state.0.pump.boost_remaining_s,
+                cooldown_remaining_s: state.0.pump.cooldown_remaining_s,
             },
         });
     }
+    let mut obstacles: Vec<_> = q_obstacles
+        .iter()
+        .map(|o| protocol::NetObstacle {
+            id: o.spec.id,
+            position: o.position.to_array(),
+        })
+        .collect();
+    obstacles.sort_by_key(|o| o.id);
+    corrections.0 = players
+        .iter()
+        .map(|p| (p.id, (tick.0, p.clone())))
+        .collect();
     let server_ms = start.0.elapsed().as_millis() as u64;
-    let delta = protocol::StateDelta {
-        tick: tick.0,
-        server_ms,
-        players,
-    };
-    let payload = protocol::encode(&protocol::ServerToClient::StateDelta(delta)).unwrap();
+    // Clients in the same cell see the same players: encode once per viewer cell into a buffer
+    // reused across ticks, copy it into a shared `Bytes`, and hand out reference-counted clones
+    let mut payloads: HashMap<Option<IVec3>, Bytes> = HashMap::new();
     for client_id in server.clients_id() {
+        let viewer_cell = clients
+            .0
+            .get(&client_id)
+            .and_then(|&entity| q.get(entity).ok())
+            .and_then(|(player, ..)| grid.cell(player.id));
+        let payload = payloads.entry(viewer_cell).or_insert_with(|| {
+            let visible = players
+                .iter()
+                .filter(|p| grid.is_visible(viewer_cell, p.id))
+                .cloned()
+                .collect();
+            let delta = build_state_delta(tick.0, server_ms, visible, obstacles.clone());
+            protocol::encode_into(
+                &protocol::ServerToClient::StateDelta(delta),
+                &mut encode_buf,
+            )
+            .unwrap();
+            Bytes::copy_from_slice(&encode_buf)
+        });
         // Use unreliable channel for snapshots to avoid HOL blocking.
-        server.send_message(client_id, DefaultChannel::Unreliable, payload.clone());
+        server.send_message(client_id, Channel::State, payload.clone());
+    }
+}
+
+/// Snapshot of `players`, quantized against the center of their bounding box; anyone out of
+/// i16 range of it goes at full precision.
+fn build_state_delta(
+    tick: u64,
+    server_ms: u64,
+    players: Vec<protocol::NetPlayer>,
+    obstacles: Vec<protocol::NetObstacle>,
+) -> protocol::StateDelta {
+    let snapshot_origin = players
+        .iter()
+        .map(|p| (p.position, p.position))
+        .reduce(|(lo, hi), (p, _)| {
+            (
+                std::array::from_fn(|i| lo[i].min(p[i])),
+                std::array::from_fn(|i| hi[i].max(p[i])),
+            )
+        })
+        .map(|(lo, hi)| std::array::from_fn(|i| (lo[i] + hi[i]) * 0.5))
+        .unwrap_or([0.0; 3]);
+    let (near, far): (Vec<_>, Vec<_>) = players
+        .into_iter()
+        .partition(|p| protocol::fits_snapshot_origin(p.position, snapshot_origin));
+    let compressed_players = near
+        .iter()
+        .map(|p| protocol::compress_player(p, snapshot_origin))
+        .collect();
+    protocol::StateDelta {
+        tick,
+        server_ms,
+        players: far,
+        compressed_players,
+        snapshot_origin,
+        obstacles,
+    }
+}
+
+/// Send `DepthWarning` to players whose sub went a dive-depth band deeper this frame, and
+/// eject subs past their maximum depth when `Config::eject_past_max_depth` is set.
+#[allow(clippy::type_complexity)]
+fn server_check_dive_depth(
+    mut commands: Commands,
+    mut server: VersionedServer,
+    clients: Res<ClientEntities>,
+    cfg: Res<Config>,
+    bounds: Res<LevelBounds>,
+    mut q: Query<(
+        &mut SubStateComp,
+        &SubPhysicsComp,
+        Option<&mut DiveDepthAlarm>,
+    )>,
+) {
+    for (&client_id, &entity) in &clients.0 {
+        let Ok((mut state, spec, alarm)) = q.get_mut(entity) else {
+            continue;
+        };
+        let limit = spec.0.dive_depth_limit;
+        let depth_m = state.0.depth_m();
+        let band = DiveDepthAlarm::from_event(limit.classify(depth_m));
+        let ejected = cfg.eject_past_max_depth && band == DiveDepthAlarm::Exceeded;
+        if ejected {
+            state.0.position = bounds.nearest_spawn(state.0.position);
+            state.0.velocity = Vec3f::ZERO;
+            state.0.ang_mom = Vec3f::ZERO;
+            // Undo the emergency blow so the sub doesn't shoot up from the spawn point
+            state.0.ballast_fill.fill(0.5);
+            warn!(client_id, depth_m, "Ejected sub past its maximum depth");
+        }
+        let mut current = alarm.as_deref().copied().unwrap_or_default();
+        let deeper = current.escalate(band);
+        if ejected {
+            // Back in safe water; the next dive warns afresh
+            current = DiveDepthAlarm::Clear;
+        }
+        match alarm {
+            Some(mut alarm) => {
+                alarm.set_if_neq(current);
+            }
+            None => {
+                commands.entity(entity).insert(current);
+            }
+        }
+        if !deeper && !ejected {
+            continue;
+        }
+        let msg = ServerToClient::DepthWarning(protocol::DepthWarning {
+            depth_m,
+            max_depth_m: limit.max_depth_m,
+            exceeded: depth_m > limit.max_depth_m,
+            ejected,
+        });
+        server.send_message(
+            client_id,
+            Channel::Reliable,
+            protocol::encode(&msg).unwrap(),
+        );
     }
 }
+
+/// Rebin every player's sub for `server_broadcast_state`'s culling.
+fn server_bin_aoi_grid(mut grid: ResMut<AoiGrid>, q: Query<(&Player, &SubStateComp)>) {
+    grid.rebuild(
+        q.iter()
+            .map(|(player, state)| (player.id, state.0.position)),
+    );
+}
@@ -0,0 +1,28 @@
+use std::net::{Ipv4Addr, UdpSocket};
+
+use anyhow::Result;
+use clap::Parser;
+use protocol::rendezvous::DEFAULT_RENDEZVOUS_PORT;
+use tracing::info;
+
+/// Standalone rendezvous service for NAT hole punching; run it somewhere both the game server
+/// and its players can reach.
+#[derive(Parser, Debug)]
+#[command(name = "thalassocracy-rendezvous")]
+struct Args {
+    /// UDP port to listen on
+    #[arg(long, default_value_t = DEFAULT_RENDEZVOUS_PORT)]
+    port: u16,
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let args = Args::parse();
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, args.port))?;
+    info!(addr = ?socket.local_addr()?, "Rendezvous listening");
+    server::rendezvous::serve(socket)?;
+    Ok(())
+}
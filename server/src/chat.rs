@@ -0,0 +1,96 @@
+//! Player text chat: `SendChat` text is checked and rate limited per player before it is
+//! relayed to everyone as `ChatMessage`.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_renet::renet::ClientId;
+use protocol::{Channel, SendChat, ServerToClient, MAX_CHAT_BYTES};
+use tracing::debug;
+
+use crate::app::{ClientEntities, Player};
+use crate::legacy::VersionedServer;
+
+/// Chat messages one player may send per `CHAT_WINDOW`.
+pub const CHAT_MESSAGES_PER_WINDOW: usize = 4;
+pub const CHAT_WINDOW: Duration = Duration::from_secs(5);
+
+/// Why a `SendChat` was not relayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRejection {
+    TooLong,
+    ContainsNul,
+    RateLimited,
+}
+
+/// Reject text over `MAX_CHAT_BYTES` or with an embedded NUL.
+pub fn validate_chat_text(text: &str) -> Result<(), ChatRejection> {
+    if text.len() > MAX_CHAT_BYTES {
+        return Err(ChatRejection::TooLong);
+    }
+    if text.contains('\0') {
+        return Err(ChatRejection::ContainsNul);
+    }
+    Ok(())
+}
+
+/// Send times of a player's recent chat messages, on the player's entity.
+#[derive(Component, Debug, Clone, Default)]
+pub struct ChatRateLimit {
+    sent: VecDeque<Duration>,
+}
+
+impl ChatRateLimit {
+    /// Record a message at `now`, the app's elapsed time, unless the player already sent
+    /// `CHAT_MESSAGES_PER_WINDOW` in the `CHAT_WINDOW` before it.
+    pub fn try_send(&mut self, now: Duration) -> Result<(), ChatRejection> {
+        while self
+            .sent
+            .front()
+            .is_some_and(|&t| now.saturating_sub(t) >= CHAT_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= CHAT_MESSAGES_PER_WINDOW {
+            return Err(ChatRejection::RateLimited);
+        }
+        self.sent.push_back(now);
+        Ok(())
+    }
+}
+
+/// `SendChat`s received this frame, in arrival order.
+#[derive(Resource, Default)]
+pub(crate) struct ChatInbox(pub Vec<(ClientId, SendChat)>);
+
+pub(crate) fn server_relay_chat(
+    mut server: VersionedServer,
+    mut inbox: ResMut<ChatInbox>,
+    time: Res<Time>,
+    clients: Res<ClientEntities>,
+    mut q_players: Query<(&Player, &mut ChatRateLimit)>,
+) {
+    for (client_id, chat) in inbox.0.drain(..) {
+        let Some(&entity) = clients.0.get(&client_id) else {
+            continue;
+        };
+        let Ok((player, mut limit)) = q_players.get_mut(entity) else {
+            continue;
+        };
+        if let Err(reason) =
+            validate_chat_text(&chat.text).and_then(|()| limit.try_send(time.elapsed()))
+        {
+            debug!(client_id, ?reason, "chat message dropped");
+            continue;
+        }
+        let msg = ServerToClient::ChatMessage(protocol::ChatMessage {
+            sender_id: player.id,
+            text: chat.text,
+        });
+        let payload = protocol::encode(&msg).unwrap();
+        for id in server.clients_id() {
+            server.send_message(id, Channel::Reliable, payload.clone());
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Admin console: commands typed on the server's stdin, one per line.
+
+use std::io::BufRead;
+use std::sync::mpsc::{channel, Receiver};
+
+use bevy::prelude::*;
+use parking_lot::Mutex;
+use tracing::{info, warn};
+
+use crate::shutdown::ShutdownSignal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleCommand {
+    /// `stop [ticks]`: shut down after `ticks` physics ticks, or the configured countdown.
+    Stop { ticks: Option<u32> },
+}
+
+impl ConsoleCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("stop") => {
+                let ticks = words
+                    .next()
+                    .map(|t| t.parse().map_err(|_| format!("not a tick count: {t}")))
+                    .transpose()?;
+                Ok(Self::Stop { ticks })
+            }
+            Some(other) => Err(format!("unknown command: {other}")),
+            None => Err("empty command".to_string()),
+        }
+    }
+}
+
+/// Commands read from stdin by `spawn_stdin_console`, applied each frame.
+#[derive(Resource)]
+pub struct ConsoleInbox(pub Mutex<Receiver<ConsoleCommand>>);
+
+/// Read commands from stdin on a background thread until it closes.
+pub fn spawn_stdin_console() -> std::io::Result<ConsoleInbox> {
+    let (tx, rx) = channel();
+    std::thread::Builder::new()
+        .name("console".into())
+        .spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match ConsoleCommand::parse(&line) {
+                    Ok(cmd) => {
+                        if tx.send(cmd).is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => warn!(%err, "console"),
+                }
+            }
+        })?;
+    Ok(ConsoleInbox(Mutex::new(rx)))
+}
+
+pub(crate) fn apply_console_commands(
+    inbox: Option<Res<ConsoleInbox>>,
+    mut shutdown: ResMut<ShutdownSignal>,
+) {
+    let Some(inbox) = inbox else {
+        return;
+    };
+    for cmd in inbox.0.lock().try_iter() {
+        match cmd {
+            ConsoleCommand::Stop { ticks } => {
+                if let Some(ticks) = ticks {
+                    shutdown.countdown_ticks = ticks;
+                }
+                shutdown.requested = true;
+                info!(
+                    countdown_ticks = shutdown.countdown_ticks,
+                    "Shutdown requested from console"
+                );
+            }
+        }
+    }
+}
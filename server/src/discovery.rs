@@ -0,0 +1,46 @@
+//! LAN discovery beacon (see `protocol::discovery`).
+
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use protocol::discovery::{ServerBeacon, BEACON_INTERVAL};
+use tracing::{info, warn};
+
+/// Player count the beacon thread reports, kept current by `server_update_discovery_count`.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct DiscoveryPlayerCount(pub Arc<AtomicU8>);
+
+/// Broadcast `beacon` to `target` every `BEACON_INTERVAL` on a background thread for the life
+/// of the process, with `player_count` read fresh for each send.
+pub fn spawn_discovery_beacon(
+    beacon: ServerBeacon,
+    player_count: Arc<AtomicU8>,
+    target: SocketAddr,
+) -> io::Result<()> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.set_broadcast(true)?;
+    info!(?target, name = %beacon.name, "Broadcasting discovery beacon");
+    std::thread::Builder::new()
+        .name("discovery-beacon".into())
+        .spawn(move || {
+            let mut beacon = beacon;
+            let mut warned = false;
+            loop {
+                beacon.player_count = player_count.load(Ordering::Relaxed);
+                match socket.send_to(&beacon.encode(), target) {
+                    Ok(_) => warned = false,
+                    // e.g. no broadcast route; keep trying quietly until it comes back
+                    Err(err) if !warned => {
+                        warn!(?err, ?target, "discovery beacon send failed");
+                        warned = true;
+                    }
+                    Err(_) => {}
+                }
+                std::thread::sleep(BEACON_INTERVAL);
+            }
+        })?;
+    Ok(())
+}
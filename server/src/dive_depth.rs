@@ -0,0 +1,33 @@
+//! Dive-depth alarms. The physics step itself blows ballast past `DiveDepthLimit::max_depth_m`;
+//! the server tells the owning player each time their sub goes a band deeper, and with
+//! `Config::eject_past_max_depth` pulls a sub past the limit back to a spawn point instead.
+
+use bevy::prelude::*;
+use levels::DiveDepthEvent;
+
+/// Dive-depth band a player's sub was in at the last check.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DiveDepthAlarm {
+    #[default]
+    Clear,
+    Warning,
+    Exceeded,
+}
+
+impl DiveDepthAlarm {
+    pub fn from_event(event: Option<DiveDepthEvent>) -> Self {
+        match event {
+            None => Self::Clear,
+            Some(DiveDepthEvent::Warning { .. }) => Self::Warning,
+            Some(DiveDepthEvent::Exceeded { .. }) => Self::Exceeded,
+        }
+    }
+
+    /// Move to `band`; true if it is deeper than before, so the player should be warned.
+    /// Climbing back out is silent.
+    pub fn escalate(&mut self, band: Self) -> bool {
+        let deeper = band > *self;
+        *self = band;
+        deeper
+    }
+}
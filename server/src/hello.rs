@@ -0,0 +1,308 @@
+//! Joining: a `ClientHello` is checked against the protocol version and the kick list, acked
+//! with the features this server grants, and spawns the player's sub. A player who left within
+//! `DEPARTED_PLAYER_TTL` gets their score, team, hold and sub back.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_renet::renet::ClientId;
+use levels::{SubInputState, SubState};
+use protocol::{
+    Channel, ClientHello, DisconnectReason, ServerToClient, LEGACY_PROTOCOL_VERSION,
+    PROTOCOL_VERSION,
+};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::anti_cheat::{AntiCheatState, KickedPlayers};
+use crate::app::{
+    start_state, CampaignRes, CargoHold, ClientEntities, Config, DisplayName, GrantedFeatures,
+    HullIntegrity, InputBuffer, LevelRes, OreNode, Player, PlayerScore, RejectedClients,
+    SubInputStateComp, SubPhysicsComp, SubSpecRes, SubStateComp, Submarine, Team, TeamAssigner,
+    TorpedoTubes,
+};
+use crate::chat::ChatRateLimit;
+use crate::latency::LatencyHistogram;
+use crate::legacy::VersionedServer;
+use crate::sonar::SonarPingCooldown;
+use crate::tick_rate::TickRateGovernor;
+
+/// What a disconnected player left behind, restored when they rejoin.
+#[derive(Debug, Clone)]
+pub struct DepartedPlayer {
+    pub player_id: Uuid,
+    pub score: PlayerScore,
+    pub team: Team,
+    /// The sub as it was on disconnect; cleared when the campaign moves to another level, so
+    /// the player then starts at the new level's spawn.
+    pub sub_state: Option<SubState>,
+    pub hull: HullIntegrity,
+    pub cargo: CargoHold,
+    /// `Time::elapsed` at the disconnect.
+    pub left_at: Duration,
+}
+
+/// How long a departed player's state is kept for their return.
+pub const DEPARTED_PLAYER_TTL: Duration = Duration::from_secs(30 * 60);
+/// Most departed players kept at once; the ids are chosen by clients, so without a cap anyone
+/// could grow the map by connecting with fresh ones.
+pub const MAX_DEPARTED_PLAYERS: usize = 1024;
+
+/// Players who disconnected, keyed by their netcode client id, so a returning player picks up
+/// where they left off. The client id is the client's private secret: unlike the
+/// `ClientHello::player_id` every other player sees, it is never sent to anyone, so nobody
+/// else can claim the state. Bounded by `DEPARTED_PLAYER_TTL` and `MAX_DEPARTED_PLAYERS`.
+#[derive(Resource, Debug, Default)]
+pub struct DepartedPlayers(pub HashMap<ClientId, DepartedPlayer>);
+
+impl DepartedPlayers {
+    /// Keep `player` for `id`, first forgetting anyone gone longer than `DEPARTED_PLAYER_TTL`
+    /// and then, while still full, whoever left first.
+    pub fn remember(&mut self, id: ClientId, player: DepartedPlayer) {
+        let now = player.left_at;
+        self.0
+            .retain(|_, back| now.saturating_sub(back.left_at) < DEPARTED_PLAYER_TTL);
+        while self.0.len() >= MAX_DEPARTED_PLAYERS && !self.0.contains_key(&id) {
+            let Some(oldest) = self
+                .0
+                .iter()
+                .min_by_key(|(_, back)| back.left_at)
+                .map(|(&id, _)| id)
+            else {
+                break;
+            };
+            self.0.remove(&oldest);
+        }
+        self.0.insert(id, player);
+    }
+
+    /// The state `player_id` left behind on client `id`, unless it is older than
+    /// `DEPARTED_PLAYER_TTL` at `now`.
+    pub fn take(
+        &mut self,
+        id: ClientId,
+        player_id: &Uuid,
+        now: Duration,
+    ) -> Option<DepartedPlayer> {
+        self.0.remove(&id).filter(|back| {
+            back.player_id == *player_id && now.saturating_sub(back.left_at) < DEPARTED_PLAYER_TTL
+        })
+    }
+}
+
+/// `ClientHello`s received this frame, in arrival order.
+#[derive(Resource, Default)]
+pub(crate) struct HelloInbox(pub Vec<(ClientId, ClientHello)>);
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn server_handle_hellos(
+    mut server: VersionedServer,
+    mut commands: Commands,
+    mut inbox: ResMut<HelloInbox>,
+    mut clients: ResMut<ClientEntities>,
+    mut rejected: ResMut<RejectedClients>,
+    (cfg, level, campaign, sub_spec, tick_rate, time): (
+        Res<Config>,
+        Res<LevelRes>,
+        Res<CampaignRes>,
+        Res<SubSpecRes>,
+        Res<TickRateGovernor>,
+        Res<Time>,
+    ),
+    mut kicked: ResMut<KickedPlayers>,
+    mut departed: ResMut<DepartedPlayers>,
+    mut team_assigner: ResMut<TeamAssigner>,
+    q_players: Query<(&Player, &Team)>,
+    q_ore: Query<&OreNode>,
+) {
+    for (client_id, hello) in inbox.0.drain(..) {
+        if rejected.0.contains(&client_id) {
+            continue;
+        }
+        if hello.protocol == LEGACY_PROTOCOL_VERSION {
+            // Still joined during the migration window, answered in its own encoding
+            warn!(
+                client_id,
+                protocol = hello.protocol,
+                "Client is on the previous protocol version"
+            );
+            server.answer_in_legacy(client_id);
+        } else if hello.protocol != PROTOCOL_VERSION {
+            let msg = ServerToClient::Disconnect(DisconnectReason::IncompatibleProtocol {
+                server: PROTOCOL_VERSION,
+                client: hello.protocol,
+            });
+            server.send_message(
+                client_id,
+                Channel::Reliable,
+                protocol::encode(&msg).unwrap(),
+            );
+            rejected.0.push(client_id);
+            continue;
+        }
+        if kicked.is_barred(&hello.player_id, time.elapsed()) {
+            warn!(client_id, player_id = %hello.player_id, "Refusing kicked player");
+            let msg = ServerToClient::Disconnect(DisconnectReason::Kicked);
+            server.send_message(
+                client_id,
+                Channel::Reliable,
+                protocol::encode(&msg).unwrap(),
+            );
+            rejected.0.push(client_id);
+            continue;
+        }
+        // Keep the client's stable UUID unless it is missing. A repeated Hello on this
+        // connection (or one netcode replaced in place after a reconnect) keeps the player it
+        // already has.
+        let existing = clients
+            .0
+            .get(&client_id)
+            .and_then(|&e| q_players.get(e).ok())
+            .map(|(p, _)| p.id);
+        let in_use = clients.0.iter().any(|(&id, &e)| {
+            id != client_id && q_players.get(e).is_ok_and(|(p, _)| p.id == hello.player_id)
+        });
+        if existing.is_none() && in_use {
+            warn!(client_id, player_id = %hello.player_id, "Refusing a second session for a player");
+            let msg = ServerToClient::Disconnect(DisconnectReason::IdentityInUse);
+            server.send_message(
+                client_id,
+                Channel::Reliable,
+                protocol::encode(&msg).unwrap(),
+            );
+            rejected.0.push(client_id);
+            continue;
+        }
+        let player_uuid = match existing {
+            Some(id) => id,
+            None if hello.player_id.is_nil() => Uuid::new_v4(),
+            None => hello.player_id,
+        };
+        let granted = hello.requested_features & cfg.enabled_features;
+        let ack = ServerToClient::JoinAck(protocol::JoinAck {
+            player_id: player_uuid,
+            tick_hz: tick_rate.current_hz,
+            features: granted,
+        });
+        server.send_message(
+            client_id,
+            Channel::Reliable,
+            protocol::encode(&ack).unwrap(),
+        );
+        info!(
+            ?client_id,
+            name = hello.display_name.as_deref().unwrap_or("(anon)"),
+            "sent JoinAck"
+        );
+
+        // Avoid double-spawn on repeated Hello
+        if let Some(&entity) = clients.0.get(&client_id) {
+            commands.entity(entity).insert(GrantedFeatures(granted));
+            continue;
+        }
+
+        // Load the campaign's current level before any state for it arrives
+        let msg = ServerToClient::LevelReload(protocol::LevelReload {
+            map_id: campaign.active as u32,
+        });
+        server.send_message(
+            client_id,
+            Channel::Reliable,
+            protocol::encode(&msg).unwrap(),
+        );
+
+        let spec = sub_spec.0.clone();
+        let returning = departed.take(client_id, &player_uuid, time.elapsed());
+        if returning.is_some() {
+            info!(?player_uuid, "returning player restored");
+        }
+        let (score, team_id) = match &returning {
+            Some(back) => (back.score, back.team.0),
+            None => (
+                PlayerScore::default(),
+                team_assigner.next(cfg.team_deathmatch),
+            ),
+        };
+        // Resume where the sub was left unless it no longer fits the spawned hull
+        let sub_state = returning
+            .as_ref()
+            .and_then(|back| back.sub_state.clone())
+            .filter(|s| s.ballast_fill.len() == spec.ballast_tanks.len())
+            .unwrap_or_else(|| start_state(&level.0, &spec));
+        let (hull, cargo) = returning.map_or_else(Default::default, |back| (back.hull, back.cargo));
+        if !cargo.0.is_empty() {
+            let msg = ServerToClient::InventoryUpdate(protocol::InventoryUpdate {
+                inventory: cargo.to_net(),
+            });
+            server.send_message(
+                client_id,
+                Channel::Reliable,
+                protocol::encode(&msg).unwrap(),
+            );
+        }
+        let entity = commands
+            .spawn((
+                Player { id: player_uuid },
+                Submarine,
+                SubStateComp(sub_state),
+                TorpedoTubes(vec![0.0; spec.torpedo_tubes as usize]),
+                SubPhysicsComp(spec),
+                SubInputStateComp(SubInputState::default()),
+                hull,
+                DisplayName(
+                    hello
+                        .display_name
+                        .clone()
+                        .unwrap_or_else(|| "(anon)".to_string()),
+                ),
+                score,
+                Team(team_id),
+                cargo,
+                (
+                    LatencyHistogram::default(),
+                    InputBuffer::default(),
+                    ChatRateLimit::default(),
+                    AntiCheatState::default(),
+                    SonarPingCooldown::default(),
+                ),
+                GrantedFeatures(granted),
+                Name::new(format!("Player {player_uuid}")),
+            ))
+            .id();
+        clients.0.insert(client_id, entity);
+
+        // Tell the newcomer about existing players, then everyone about the newcomer
+        for (player, team) in &q_players {
+            let msg = ServerToClient::TeamAssignment(protocol::TeamAssignment {
+                player_id: player.id,
+                team_id: team.0,
+            });
+            server.send_message(
+                client_id,
+                Channel::Reliable,
+                protocol::encode(&msg).unwrap(),
+            );
+        }
+        let msg = ServerToClient::TeamAssignment(protocol::TeamAssignment {
+            player_id: player_uuid,
+            team_id,
+        });
+        let payload = protocol::encode(&msg).unwrap();
+        for id in server.clients_id() {
+            server.send_message(id, Channel::Reliable, payload.clone());
+        }
+
+        // Nodes still respawning are hidden on the newcomer's side too
+        for ore in q_ore.iter().filter(|o| o.respawn_timer.is_some()) {
+            let msg = ServerToClient::EntityDespawn(protocol::EntityDespawn {
+                node_id: ore.node_id,
+            });
+            server.send_message(
+                client_id,
+                Channel::Reliable,
+                protocol::encode(&msg).unwrap(),
+            );
+        }
+    }
+}
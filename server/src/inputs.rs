@@ -0,0 +1,193 @@
+//! Control input from clients: `InputTick`s on the reliable channel, `InputTickBatch`es on the
+//! unreliable `Input` channel and future-dated `InputEvent`s. Every tick is rate limited, acked
+//! with the player's pending prediction check and buffered for the physics tick.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_renet::renet::ClientId;
+use protocol::{BatchedInputTick, Channel, InputEvent, InputTick, ServerToClient, MAX_INPUT_BATCH};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::anti_cheat::AntiCheatState;
+use crate::app::{
+    ClientEntities, Config, ControlInputComp, InputBuffer, PendingCorrections, Player,
+    ScheduledInputQueue, Tick,
+};
+use crate::legacy::VersionedServer;
+use crate::rate_limit::ClientRateMonitor;
+
+/// One control message from a client.
+pub(crate) enum ClientInput {
+    Tick(InputTick),
+    Event(InputEvent),
+    Batch(Vec<BatchedInputTick>),
+}
+
+/// Control messages received this frame, in arrival order.
+#[derive(Resource, Default)]
+pub(crate) struct InputInbox(pub Vec<(ClientId, ClientInput)>);
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn server_handle_inputs(
+    mut server: VersionedServer,
+    mut commands: Commands,
+    mut inbox: ResMut<InputInbox>,
+    (cfg, time, tick, clients): (Res<Config>, Res<Time>, Res<Tick>, Res<ClientEntities>),
+    mut corrections: ResMut<PendingCorrections>,
+    mut rate_monitor: ResMut<ClientRateMonitor>,
+    mut scheduled: ResMut<ScheduledInputQueue>,
+    mut q_anti_cheat: Query<&mut AntiCheatState>,
+    mut q_players: Query<(&Player, Option<&mut InputBuffer>)>,
+) {
+    for (client_id, input) in inbox.0.drain(..) {
+        match input {
+            ClientInput::Tick(input) => {
+                let player_id = clients
+                    .0
+                    .get(&client_id)
+                    .and_then(|&entity| q_players.get(entity).ok())
+                    .map(|(player, _)| player.id);
+                let ack = corrections.ack(player_id, input.tick);
+                let payload = protocol::encode(&ack).unwrap();
+                server.send_message(client_id, Channel::Reliable, payload);
+                // Update or insert control input on the client's entity
+                if let Some(&entity) = clients.0.get(&client_id) {
+                    check_input_ranges(&mut q_anti_cheat, client_id, entity, &input);
+                    let control = ControlInputComp::from_tick(&input);
+                    if let Ok((_, Some(mut buffer))) = q_players.get_mut(entity) {
+                        buffer.insert(tick.0, input.tick, control.sub_inputs());
+                    }
+                    commands.entity(entity).insert(control);
+                }
+            }
+            ClientInput::Event(ev) => {
+                // Queue future-dated input; the physics tick applies it once t_ms has passed
+                if let Some(&entity) = clients.0.get(&client_id) {
+                    let evc = InputEvent {
+                        t_ms: ev.t_ms,
+                        thrust: ev.thrust.clamp(-1.0, 1.0),
+                        yaw: ev.yaw.clamp(-1.0, 1.0),
+                        pump_fwd: ev.pump_fwd.clamp(-1.0, 1.0),
+                        pump_aft: ev.pump_aft.clamp(-1.0, 1.0),
+                        pitch: ev.pitch.clamp(-1.0, 1.0),
+                        roll_trim: ev.roll_trim.clamp(-1.0, 1.0),
+                    };
+                    scheduled.push(entity, evc);
+                }
+            }
+            ClientInput::Batch(mut batch) => {
+                let Some(&entity) = clients.0.get(&client_id) else {
+                    continue;
+                };
+                let Ok((player, _)) = q_players.get(entity) else {
+                    continue;
+                };
+                let player_id = player.id;
+                if batch.len() > MAX_INPUT_BATCH {
+                    debug!(
+                        client_id,
+                        len = batch.len(),
+                        "oversized input batch truncated"
+                    );
+                }
+                batch.truncate(MAX_INPUT_BATCH);
+                for input in InputTick::decode_batch(&batch) {
+                    // Every tick counts against the rate limit, batched or not
+                    if !admit_input(
+                        &mut server,
+                        &mut commands,
+                        &mut rate_monitor,
+                        cfg.max_inputs_per_sec,
+                        time.elapsed(),
+                        (client_id, entity, player_id),
+                    ) {
+                        continue;
+                    }
+                    // The channel is unordered; a late batch must not roll inputs back
+                    let newest = q_players
+                        .get(entity)
+                        .ok()
+                        .and_then(|(_, buffer)| buffer?.newest());
+                    if newest.is_some_and(|(t, _)| t >= input.tick) {
+                        continue;
+                    }
+                    let ack = corrections.ack(Some(player_id), input.tick);
+                    server.send_message(
+                        client_id,
+                        Channel::Reliable,
+                        protocol::encode(&ack).unwrap(),
+                    );
+                    check_input_ranges(&mut q_anti_cheat, client_id, entity, &input);
+                    let control = ControlInputComp::from_tick(&input);
+                    if let Ok((_, Some(mut buffer))) = q_players.get_mut(entity) {
+                        buffer.insert(tick.0, input.tick, control.sub_inputs());
+                    }
+                    commands.entity(entity).insert(control);
+                }
+            }
+        }
+    }
+}
+
+/// Count one input from a joined client against its rate limit. Sends the client the
+/// `RateLimit` notice for the window that just closed and raises its `SuspicionLevel` when
+/// due; false when this input is over the limit and must be dropped.
+pub(crate) fn admit_input(
+    server: &mut VersionedServer,
+    commands: &mut Commands,
+    rate_monitor: &mut ClientRateMonitor,
+    max_inputs_per_sec: u32,
+    now: Duration,
+    (client_id, entity, player_id): (ClientId, Entity, Uuid),
+) -> bool {
+    let verdict = rate_monitor.record(player_id, now, max_inputs_per_sec);
+    if let Some(notice) = verdict.notice {
+        warn!(
+            client_id,
+            dropped = notice.excess_inputs_dropped,
+            "Client exceeded the input rate limit"
+        );
+        let msg = ServerToClient::RateLimit(notice);
+        server.send_message(
+            client_id,
+            Channel::Reliable,
+            protocol::encode(&msg).unwrap(),
+        );
+    }
+    if let Some(suspicion) = verdict.suspicion {
+        warn!(
+            client_id,
+            %player_id,
+            level = suspicion.0,
+            "Raised suspicion level for sustained input flooding"
+        );
+        commands.entity(entity).insert(suspicion);
+    }
+    verdict.accept
+}
+
+/// Count an `InputTick` with thrust, yaw or a pump outside [-1, 1] against its player. The
+/// input is still applied, clamped by `ControlInputComp::from_tick`.
+fn check_input_ranges(
+    q_anti_cheat: &mut Query<&mut AntiCheatState>,
+    client_id: ClientId,
+    entity: Entity,
+    input: &InputTick,
+) {
+    let Ok(mut anti_cheat) = q_anti_cheat.get_mut(entity) else {
+        return;
+    };
+    if anti_cheat.check_input(input) {
+        warn!(
+            client_id,
+            strikes = anti_cheat.input_strikes,
+            thrust = input.thrust,
+            yaw = input.yaw,
+            pump_fwd = input.pump_fwd,
+            pump_aft = input.pump_aft,
+            "Clamped out-of-range input"
+        );
+    }
+}
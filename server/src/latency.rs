@@ -0,0 +1,108 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+pub const LATENCY_BUCKETS: usize = 16;
+const LATENCY_MIN_MS: f32 = 1.0;
+const LATENCY_MAX_MS: f32 = 2000.0;
+
+/// Round-trip times seen for one client, in buckets spaced logarithmically from 1 to 2000 ms.
+/// Samples below 1 ms land in the first bucket and samples above 2000 ms in the last, so the
+/// last bucket is open-ended.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct LatencyHistogram {
+    pub buckets: [u64; LATENCY_BUCKETS],
+    /// Bucket `i` covers `bounds_ms[i]..bounds_ms[i + 1]`.
+    pub bounds_ms: [f32; LATENCY_BUCKETS + 1],
+    pub sum_ms: f64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        let ratio = LATENCY_MAX_MS / LATENCY_MIN_MS;
+        let bounds_ms = std::array::from_fn(|i| {
+            if i == LATENCY_BUCKETS {
+                LATENCY_MAX_MS
+            } else {
+                LATENCY_MIN_MS * ratio.powf(i as f32 / LATENCY_BUCKETS as f32)
+            }
+        });
+        Self {
+            buckets: [0; LATENCY_BUCKETS],
+            bounds_ms,
+            sum_ms: 0.0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Index of the bucket a sample of `ms` is counted in.
+    pub fn bucket_of(&self, ms: f32) -> usize {
+        self.bounds_ms[1..LATENCY_BUCKETS]
+            .iter()
+            .position(|&upper| ms < upper)
+            .unwrap_or(LATENCY_BUCKETS - 1)
+    }
+
+    pub fn record(&mut self, ms: f32) {
+        if !ms.is_finite() || ms < 0.0 {
+            return;
+        }
+        let bucket = self.bucket_of(ms);
+        self.buckets[bucket] += 1;
+        self.sum_ms += ms as f64;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Latency below which a fraction `q` of samples fall, interpolated linearly inside its
+    /// bucket; `None` before the first sample.
+    pub fn percentile_ms(&self, q: f32) -> Option<f32> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let rank = ((q.clamp(0.0, 1.0) * count as f32).ceil() as u64).max(1);
+        let mut below = 0;
+        for (i, &n) in self.buckets.iter().enumerate() {
+            if n > 0 && below + n >= rank {
+                let t = (rank - below) as f32 / n as f32;
+                let (lo, hi) = (self.bounds_ms[i], self.bounds_ms[i + 1]);
+                return Some(lo + t * (hi - lo));
+            }
+            below += n;
+        }
+        Some(self.bounds_ms[LATENCY_BUCKETS])
+    }
+
+    /// Append this histogram in the Prometheus text format as
+    /// `thalassocracy_client_latency_{bucket,sum,count}` labelled with `client`. The open-ended
+    /// last bucket is folded into `le="+Inf"`.
+    pub fn write_prometheus(&self, out: &mut String, client: &str) {
+        let mut cumulative = 0;
+        for (i, &n) in self.buckets[..LATENCY_BUCKETS - 1].iter().enumerate() {
+            cumulative += n;
+            let le = self.bounds_ms[i + 1];
+            let _ = writeln!(
+                out,
+                "thalassocracy_client_latency_bucket{{client=\"{client}\",le=\"{le:.3}\"}} {cumulative}"
+            );
+        }
+        let count = self.count();
+        let _ = writeln!(
+            out,
+            "thalassocracy_client_latency_bucket{{client=\"{client}\",le=\"+Inf\"}} {count}"
+        );
+        let _ = writeln!(
+            out,
+            "thalassocracy_client_latency_sum{{client=\"{client}\"}} {:.3}",
+            self.sum_ms
+        );
+        let _ = writeln!(
+            out,
+            "thalassocracy_client_latency_count{{client=\"{client}\"}} {count}"
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Clients still on `LEGACY_PROTOCOL_VERSION`. Their messages are upgraded on decode
+//! (`protocol::decode_client_message`) and everything sent to them goes through
+//! `VersionedServer`, which re-encodes it in their version.
+
+use std::collections::HashSet;
+use std::ops::{Deref, DerefMut};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_renet::renet::{Bytes, ClientId, RenetServer};
+use protocol::ServerToClient;
+use tracing::warn;
+
+/// Clients whose `ClientHello` named `LEGACY_PROTOCOL_VERSION`, until they disconnect.
+#[derive(Resource, Debug, Default)]
+pub struct LegacyClients(pub HashSet<ClientId>);
+
+/// `RenetServer` for systems that send: `send_message` takes a message encoded for
+/// `PROTOCOL_VERSION` and hands legacy clients the same message in theirs.
+#[derive(SystemParam)]
+pub struct VersionedServer<'w> {
+    server: ResMut<'w, RenetServer>,
+    legacy: ResMut<'w, LegacyClients>,
+}
+
+impl VersionedServer<'_> {
+    /// Encode everything sent to `client_id` from now on for `LEGACY_PROTOCOL_VERSION`.
+    pub fn answer_in_legacy(&mut self, client_id: ClientId) {
+        self.legacy.0.insert(client_id);
+    }
+
+    pub fn send_message<C: Into<u8>, B: Into<Bytes>>(
+        &mut self,
+        client_id: ClientId,
+        channel: C,
+        payload: B,
+    ) {
+        let payload = payload.into();
+        if !self.legacy.0.contains(&client_id) {
+            self.server.send_message(client_id, channel, payload);
+            return;
+        }
+        let downgraded = protocol::decode::<ServerToClient>(&payload)
+            .map_err(|err| err.to_string())
+            .and_then(|msg| protocol::legacy::encode(&msg).map_err(|err| err.to_string()));
+        match downgraded {
+            Ok(bytes) => self.server.send_message(client_id, channel, bytes),
+            Err(err) => warn!(client_id, %err, "dropped a message for a legacy client"),
+        }
+    }
+}
+
+impl Deref for VersionedServer<'_> {
+    type Target = RenetServer;
+
+    fn deref(&self) -> &RenetServer {
+        &self.server
+    }
+}
+
+impl DerefMut for VersionedServer<'_> {
+    fn deref_mut(&mut self) -> &mut RenetServer {
+        &mut self.server
+    }
+}
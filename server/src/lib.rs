@@ -1,6 +1,34 @@
+pub mod anti_cheat;
+pub mod aoi;
 pub mod app;
+pub mod chat;
+pub mod console;
+pub mod discovery;
+pub mod dive_depth;
+pub mod hello;
+pub mod inputs;
+pub mod latency;
+pub mod legacy;
+pub mod messages;
+pub mod mining;
+pub mod rate_limit;
+pub mod rendezvous;
+pub mod shutdown;
+pub mod sonar;
+pub mod tick_rate;
+pub mod ws_proxy;
 
+pub use anti_cheat::AntiCheatState;
+pub use aoi::AoiGrid;
 pub use app::{
-    build_server_app, load_config, Args, ClientEntities, Config, Player, ServerAddresses,
-    SubInputStateComp, SubStateComp,
+    build_server_app, build_server_app_with_level, clamp_sub_state, load_config, ActiveMissions,
+    Args, CampaignRes, CargoHold, ClientEntities, Config, DisplayName, GrantedFeatures,
+    HullIntegrity, InputBuffer, LevelBounds, MovingObstacle, OreNode, Player,
+    PlayerMissionProgress, PlayerScore, ScheduledInputQueue, ServerAddresses, SubInputStateComp,
+    SubSpecRes, SubStateComp, Team, TeamScores, Torpedo, TorpedoCooldown, TorpedoTubes,
+    SCHEDULED_INPUT_MAX_AGE_MS,
 };
+pub use hello::{DepartedPlayer, DepartedPlayers};
+pub use latency::LatencyHistogram;
+pub use shutdown::ShutdownSignal;
+pub use tick_rate::TickRateGovernor;
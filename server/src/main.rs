@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use tracing::info;
 
-use server::{build_server_app, load_config, Args};
+use server::{build_server_app, build_server_app_with_level, load_config, Args};
 
 fn main() -> Result<()> {
     tracing_subscriber::fmt()
@@ -13,8 +13,17 @@ fn main() -> Result<()> {
     let cfg = load_config(&args.config)?;
     info!(?cfg, "Server config loaded");
 
-    let mut app = build_server_app(cfg);
+    server::shutdown::install_signal_handler()?;
+    let mut app = match &args.level {
+        Some(path) => {
+            let level = levels::load_level(path)?;
+            info!(path = %path.display(), "Playing custom level");
+            build_server_app_with_level(cfg, level)
+        }
+        None => build_server_app(cfg),
+    };
     app.insert_resource(args);
+    app.insert_resource(server::console::spawn_stdin_console()?);
     app.run();
     Ok(())
 }
@@ -0,0 +1,144 @@
+//! Client message intake. `server_receive_messages` drains both channels of every client,
+//! answers what needs no game state (pings, pause requests, protocol mismatches) and queues
+//! the rest in the inbox of the system that owns it: `hello`, `inputs`, `chat`, `mining`,
+//! `sonar` and the torpedo tick. All of them run in `ClientMessages`, intake first.
+
+use bevy::prelude::*;
+use protocol::{
+    Channel, ClientToServer, DecodeError, DisconnectReason, ProtocolError, ServerToClient,
+    PROTOCOL_VERSION,
+};
+use tracing::warn;
+
+use crate::app::{
+    ClientEntities, Config, Player, RejectedClients, ServerStart, SimPaused, TorpedoLaunchInbox,
+};
+use crate::chat::ChatInbox;
+use crate::hello::HelloInbox;
+use crate::inputs::{admit_input, ClientInput, InputInbox};
+use crate::legacy::VersionedServer;
+use crate::mining::{CargoInbox, CargoRequest};
+use crate::rate_limit::ClientRateMonitor;
+use crate::sonar::SonarPingInbox;
+
+/// Intake and the per-message systems it feeds.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClientMessages;
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn server_receive_messages(
+    mut server: VersionedServer,
+    mut commands: Commands,
+    (cfg, time, start): (Res<Config>, Res<Time>, Res<ServerStart>),
+    clients: Res<ClientEntities>,
+    q_players: Query<&Player>,
+    mut paused: ResMut<SimPaused>,
+    mut rate_monitor: ResMut<ClientRateMonitor>,
+    mut rejected: ResMut<RejectedClients>,
+    (mut hellos, mut inputs, mut chat, mut cargo): (
+        ResMut<HelloInbox>,
+        ResMut<InputInbox>,
+        ResMut<ChatInbox>,
+        ResMut<CargoInbox>,
+    ),
+    (mut launches, mut sonar_pings): (ResMut<TorpedoLaunchInbox>, ResMut<SonarPingInbox>),
+) {
+    for client_id in server.clients_id() {
+        while let Some(payload) = server.receive_message(client_id, Channel::Reliable) {
+            let decoded = protocol::decode_client_message(payload.as_ref());
+            let player = clients
+                .0
+                .get(&client_id)
+                .and_then(|&e| q_players.get(e).ok().map(|p| (e, p.id)));
+            if let (Ok(true), Some((entity, player_id))) =
+                (decoded.as_ref().map(ClientToServer::is_input), player)
+            {
+                if !admit_input(
+                    &mut server,
+                    &mut commands,
+                    &mut rate_monitor,
+                    cfg.max_inputs_per_sec,
+                    time.elapsed(),
+                    (client_id, entity, player_id),
+                ) {
+                    continue;
+                }
+            }
+            match decoded {
+                Ok(ClientToServer::Hello(hello)) => hellos.0.push((client_id, hello)),
+                Ok(ClientToServer::InputTick(input)) => {
+                    inputs.0.push((client_id, ClientInput::Tick(input)));
+                }
+                Ok(ClientToServer::InputEvent(ev)) => {
+                    inputs.0.push((client_id, ClientInput::Event(ev)));
+                }
+                Ok(ClientToServer::FireTorpedo(fire)) => {
+                    if let Some(&entity) = clients.0.get(&client_id) {
+                        launches.0.push((entity, fire));
+                    }
+                }
+                Ok(ClientToServer::SonarPing(_)) => {
+                    if let Some(&entity) = clients.0.get(&client_id) {
+                        sonar_pings.0.push(entity);
+                    }
+                }
+                Ok(ClientToServer::SendChat(msg)) => chat.0.push((client_id, msg)),
+                Ok(ClientToServer::PauseRequest(req)) => {
+                    paused.0 = req.paused;
+                    let msg = ServerToClient::PauseState(protocol::PauseState { paused: paused.0 });
+                    let payload = protocol::encode(&msg).unwrap();
+                    for id in server.clients_id() {
+                        server.send_message(id, Channel::Reliable, payload.clone());
+                    }
+                }
+                Ok(ClientToServer::MineRequest(req)) => {
+                    cargo.0.push((client_id, CargoRequest::Mine(req)));
+                }
+                Ok(ClientToServer::DockRequest(_)) => {
+                    cargo.0.push((client_id, CargoRequest::Dock));
+                }
+                Ok(other) => {
+                    warn!(?client_id, ?other, "unexpected message on reliable channel");
+                }
+                Err(ProtocolError::Decode(DecodeError::VersionMismatch { found, .. })) => {
+                    // Built against another protocol; none of its messages can be trusted
+                    warn!(
+                        client_id,
+                        found, "Rejecting client with a different protocol version"
+                    );
+                    let msg = ServerToClient::Disconnect(DisconnectReason::IncompatibleProtocol {
+                        server: PROTOCOL_VERSION,
+                        client: found,
+                    });
+                    server.send_message(
+                        client_id,
+                        Channel::Reliable,
+                        protocol::encode(&msg).unwrap(),
+                    );
+                    rejected.0.push(client_id);
+                    break;
+                }
+                Err(err) => warn!(?client_id, ?err, "failed to decode client message"),
+            }
+        }
+
+        // Unreliable channel: latency probes and batched input ticks
+        while let Some(payload) = server.receive_message(client_id, Channel::Input) {
+            match protocol::decode_client_message(payload.as_ref()) {
+                Ok(ClientToServer::InputTickBatch(batch)) => {
+                    inputs.0.push((client_id, ClientInput::Batch(batch)));
+                }
+                Ok(ClientToServer::Ping(ping)) => {
+                    let msg = ServerToClient::PingResponse(protocol::PingResponse {
+                        seq: ping.seq,
+                        client_send_ms: ping.client_send_ms,
+                        server_recv_ms: start.0.elapsed().as_millis() as u64,
+                    });
+                    server.send_message(client_id, Channel::State, protocol::encode(&msg).unwrap());
+                }
+                Ok(other) => warn!(?client_id, ?other, "unexpected message on input channel"),
+                Err(err) => warn!(?client_id, ?err, "failed to decode client message"),
+            }
+        }
+    }
+}
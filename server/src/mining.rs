@@ -0,0 +1,157 @@
+//! Mining and docking: a `MineRequest` takes one pass from an ore node within reach into the
+//! player's hold, and a `DockRequest` inside the dock volume sells the whole hold for credits
+//! toward the player's score, their team's round score and their missions.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_renet::renet::ClientId;
+use levels::{MissionEvent, Vec3f};
+use protocol::{Channel, MineRequest, ServerToClient};
+use tracing::info;
+
+use crate::app::{
+    net_resource_type, CargoHold, ClientEntities, Config, LevelRes, MissionEventInbox, OreNode,
+    PlayerScore, SubStateComp, Team, TeamScores,
+};
+use crate::legacy::VersionedServer;
+
+/// Height above the dock pad that still counts as docked.
+const DOCK_CLEARANCE_M: f32 = 3.0;
+
+fn in_dock_volume(room: &levels::RoomSpec, p: Vec3f) -> bool {
+    let half = room.dock_size * 0.5;
+    let d = p - room.dock_pos;
+    d.x.abs() <= half.x && d.z.abs() <= half.z && d.y >= -half.y && d.y <= half.y + DOCK_CLEARANCE_M
+}
+
+/// A request that fills or empties a player's hold.
+pub(crate) enum CargoRequest {
+    Mine(MineRequest),
+    Dock,
+}
+
+/// Mining and docking requests received this frame, in arrival order.
+#[derive(Resource, Default)]
+pub(crate) struct CargoInbox(pub Vec<(ClientId, CargoRequest)>);
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn server_handle_cargo_requests(
+    mut server: VersionedServer,
+    mut inbox: ResMut<CargoInbox>,
+    cfg: Res<Config>,
+    level: Res<LevelRes>,
+    clients: Res<ClientEntities>,
+    mut team_scores: ResMut<TeamScores>,
+    mut mission_events: ResMut<MissionEventInbox>,
+    mut q_players: Query<(&SubStateComp, &Team, &mut PlayerScore, &mut CargoHold)>,
+    mut q_ore: Query<&mut OreNode>,
+) {
+    for (client_id, request) in inbox.0.drain(..) {
+        let Some(&entity) = clients.0.get(&client_id) else {
+            continue;
+        };
+        let Ok((state, team, mut score, mut cargo)) = q_players.get_mut(entity) else {
+            continue;
+        };
+        match request {
+            CargoRequest::Mine(req) => {
+                let ore = q_ore
+                    .iter_mut()
+                    .find(|o| o.node_id == req.node_id && o.in_reach(state.0.position));
+                let node_spec = ore
+                    .as_ref()
+                    .and_then(|o| level.0.ore_nodes.get(o.node_id as usize));
+                let (Some(mut ore), Some(node_spec)) = (ore, node_spec) else {
+                    let ack = ServerToClient::MineAck(protocol::MineAck {
+                        success: false,
+                        resource_type: protocol::ResourceType::Ore,
+                        amount: 0,
+                        inventory_after: cargo.to_net(),
+                    });
+                    server.send_message(
+                        client_id,
+                        Channel::Reliable,
+                        protocol::encode(&ack).unwrap(),
+                    );
+                    continue;
+                };
+                score.mines += 1;
+                let (resource_type, amount) = ore.mine(node_spec);
+                *cargo.0.entry(resource_type).or_default() += amount;
+                let ack = ServerToClient::MineAck(protocol::MineAck {
+                    success: true,
+                    resource_type: net_resource_type(resource_type),
+                    amount,
+                    inventory_after: cargo.to_net(),
+                });
+                server.send_message(
+                    client_id,
+                    Channel::Reliable,
+                    protocol::encode(&ack).unwrap(),
+                );
+                if ore.remaining_kg > 0 {
+                    continue;
+                }
+                let msg = ServerToClient::EntityDespawn(protocol::EntityDespawn {
+                    node_id: ore.node_id,
+                });
+                let payload = protocol::encode(&msg).unwrap();
+                for id in server.clients_id() {
+                    server.send_message(id, Channel::Reliable, payload.clone());
+                }
+            }
+            CargoRequest::Dock => {
+                if !in_dock_volume(&level.0.room, state.0.position) {
+                    continue;
+                }
+                // The station buys the whole hold; deliveries also count toward missions
+                let unloaded = !cargo.0.is_empty();
+                let mut earned = 0u64;
+                for (resource_type, amount_kg) in cargo.0.drain() {
+                    earned += resource_type.credits_per_kg() * u64::from(amount_kg);
+                    let delivered = MissionEvent::Delivered {
+                        resource_type,
+                        amount_kg,
+                    };
+                    mission_events.0.push((entity, delivered));
+                }
+                if unloaded {
+                    let msg = ServerToClient::InventoryUpdate(protocol::InventoryUpdate {
+                        inventory: HashMap::new(),
+                    });
+                    server.send_message(
+                        client_id,
+                        Channel::Reliable,
+                        protocol::encode(&msg).unwrap(),
+                    );
+                }
+                mission_events.0.push((entity, MissionEvent::Docked));
+                score.docks += 1;
+                score.credits += earned;
+                let ack = ServerToClient::DockAck(protocol::DockAck {
+                    credits_after: score.credits,
+                });
+                server.send_message(
+                    client_id,
+                    Channel::Reliable,
+                    protocol::encode(&ack).unwrap(),
+                );
+                let team_score = team_scores.0.entry(team.0).or_default();
+                *team_score += earned;
+                if cfg.team_deathmatch && *team_score >= cfg.team_score_limit {
+                    let msg = ServerToClient::TeamWin(protocol::TeamWin {
+                        team_id: team.0,
+                        score: *team_score,
+                    });
+                    info!(team_id = team.0, score = *team_score, "round won");
+                    let payload = protocol::encode(&msg).unwrap();
+                    for id in server.clients_id() {
+                        server.send_message(id, Channel::Reliable, payload.clone());
+                    }
+                    team_scores.0.clear();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Per-client input rate limiting: inputs past `Config::max_inputs_per_sec` in a one second
+//! window are dropped, and the client is told how fast it may send.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use protocol::RateLimit;
+use uuid::Uuid;
+
+pub const RATE_WINDOW: Duration = Duration::from_secs(1);
+/// Consecutive over-limit windows that raise a player's `SuspicionLevel`.
+pub const RATE_LIMITS_PER_SUSPICION: u32 = 10;
+
+/// Anti-cheat score on a player's entity; raised once per `RATE_LIMITS_PER_SUSPICION`
+/// consecutive rate-limited seconds.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SuspicionLevel(pub u32);
+
+#[derive(Debug, Clone, Copy)]
+struct InputWindow {
+    started: Duration,
+    count: u32,
+    dropped: u32,
+    /// Windows in a row that ended with dropped inputs.
+    consecutive_limits: u32,
+    suspicion: u32,
+}
+
+/// What to do with one input, and whether the previous window needs reporting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputVerdict {
+    pub accept: bool,
+    /// The window that just closed dropped inputs; send this to the client.
+    pub notice: Option<RateLimit>,
+    /// Set when this notice raised the player's suspicion level.
+    pub suspicion: Option<SuspicionLevel>,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct ClientRateMonitor(HashMap<Uuid, InputWindow>);
+
+impl ClientRateMonitor {
+    /// Count one input message from `player` arriving at `now`, the app's elapsed time.
+    pub fn record(&mut self, player: Uuid, now: Duration, max_per_sec: u32) -> InputVerdict {
+        let window = self.0.entry(player).or_insert(InputWindow {
+            started: now,
+            count: 0,
+            dropped: 0,
+            consecutive_limits: 0,
+            suspicion: 0,
+        });
+        let mut verdict = InputVerdict {
+            accept: true,
+            notice: None,
+            suspicion: None,
+        };
+        if now.saturating_sub(window.started) >= RATE_WINDOW {
+            if window.dropped > 0 {
+                verdict.notice = Some(RateLimit {
+                    allowed_hz: max_per_sec,
+                    excess_inputs_dropped: window.dropped,
+                });
+                window.consecutive_limits += 1;
+                if window
+                    .consecutive_limits
+                    .is_multiple_of(RATE_LIMITS_PER_SUSPICION)
+                {
+                    window.suspicion += 1;
+                    verdict.suspicion = Some(SuspicionLevel(window.suspicion));
+                }
+            } else {
+                window.consecutive_limits = 0;
+            }
+            window.started = now;
+            window.count = 0;
+            window.dropped = 0;
+        }
+        window.count += 1;
+        if window.count > max_per_sec {
+            window.dropped += 1;
+            verdict.accept = false;
+        }
+        verdict
+    }
+
+    pub fn forget(&mut self, player: &Uuid) {
+        self.0.remove(player);
+    }
+}
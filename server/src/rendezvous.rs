@@ -0,0 +1,147 @@
+//! Rendezvous service for UDP hole punching (see `protocol::rendezvous`). Peers register under
+//! a session token; a query returns the longest-registered other peer, which is the game
+//! server as long as it registers before its clients and keeps refreshing.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use protocol::rendezvous::{HolePunchConfig, RendezvousMessage, SessionToken};
+use tracing::{info, warn};
+
+/// Registrations not refreshed within this long are forgotten.
+pub const REGISTRATION_TTL: Duration = Duration::from_secs(60);
+/// How often a game server re-registers, well inside `REGISTRATION_TTL`.
+pub const REGISTER_INTERVAL: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Copy)]
+struct Registration {
+    /// Where the peer registered from; identifies it on refresh and query.
+    source: SocketAddr,
+    /// Address handed to other peers: the advertised external address, or `source`.
+    addr: SocketAddr,
+    last_seen: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct RendezvousServer {
+    sessions: HashMap<SessionToken, Vec<Registration>>,
+}
+
+impl RendezvousServer {
+    /// Apply one message received from `from`, returning the reply to send back, if any.
+    pub fn handle(
+        &mut self,
+        msg: RendezvousMessage,
+        from: SocketAddr,
+        now: Instant,
+    ) -> Option<RendezvousMessage> {
+        self.expire(now);
+        match msg {
+            RendezvousMessage::Register {
+                token,
+                external_addr,
+            } => {
+                let addr = external_addr.unwrap_or(from);
+                let peers = self.sessions.entry(token).or_default();
+                match peers.iter_mut().find(|r| r.source == from) {
+                    Some(reg) => {
+                        reg.addr = addr;
+                        reg.last_seen = now;
+                    }
+                    None => peers.push(Registration {
+                        source: from,
+                        addr,
+                        last_seen: now,
+                    }),
+                }
+                None
+            }
+            RendezvousMessage::Query { token } => {
+                let addr = self
+                    .sessions
+                    .get(&token)
+                    .and_then(|peers| peers.iter().find(|r| r.source != from))
+                    .map(|r| r.addr);
+                Some(RendezvousMessage::Peer { token, addr })
+            }
+            RendezvousMessage::Peer { .. } => None,
+        }
+    }
+
+    /// Number of live registrations under `token`.
+    pub fn peer_count(&self, token: &SessionToken) -> usize {
+        self.sessions.get(token).map_or(0, Vec::len)
+    }
+
+    fn expire(&mut self, now: Instant) {
+        self.sessions.retain(|_, peers| {
+            peers.retain(|r| now.duration_since(r.last_seen) < REGISTRATION_TTL);
+            !peers.is_empty()
+        });
+    }
+}
+
+/// Answer rendezvous messages on `socket` until it fails.
+pub fn serve(socket: UdpSocket) -> io::Result<()> {
+    let mut server = RendezvousServer::default();
+    let mut buf = [0u8; 512];
+    loop {
+        let (n, from) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            // ICMP port unreachable from a peer that went away
+            Err(err) if err.kind() == io::ErrorKind::ConnectionReset => continue,
+            Err(err) => return Err(err),
+        };
+        let Some(msg) = RendezvousMessage::decode(&buf[..n]) else {
+            continue;
+        };
+        if let Some(reply) = server.handle(msg, from, Instant::now()) {
+            if let Err(err) = socket.send_to(&reply.encode(), from) {
+                warn!(?err, ?from, "rendezvous reply failed");
+            }
+        }
+    }
+}
+
+/// Run a rendezvous service on UDP `port` on a background thread for the life of the process;
+/// returns the bound address.
+pub fn spawn_rendezvous(port: u16) -> io::Result<SocketAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port))?;
+    let bound = socket.local_addr()?;
+    std::thread::Builder::new()
+        .name("rendezvous".into())
+        .spawn(move || {
+            if let Err(err) = serve(socket) {
+                warn!(?err, "rendezvous service stopped");
+            }
+        })?;
+    info!(port = bound.port(), "Rendezvous listening");
+    Ok(bound)
+}
+
+/// Keep the game server registered with the rendezvous in `cfg`, sending from the netcode
+/// `socket` so the rendezvous records the NAT mapping clients must reach. Registration gets
+/// no reply, so nothing but netcode traffic ever arrives on that socket.
+pub fn spawn_registration(
+    socket: UdpSocket,
+    cfg: HolePunchConfig,
+    external_addr: Option<SocketAddr>,
+) -> io::Result<()> {
+    let register = RendezvousMessage::Register {
+        token: cfg.session_token,
+        external_addr,
+    }
+    .encode();
+    std::thread::Builder::new()
+        .name("rendezvous-register".into())
+        .spawn(move || loop {
+            if let Err(err) = socket.send_to(&register, cfg.rendezvous_addr) {
+                warn!(?err, rendezvous = ?cfg.rendezvous_addr, "rendezvous register failed");
+            }
+            std::thread::sleep(REGISTER_INTERVAL);
+        })?;
+    info!(rendezvous = ?cfg.rendezvous_addr, "Registering with rendezvous");
+    Ok(())
+}
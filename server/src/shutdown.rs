@@ -0,0 +1,91 @@
+//! Graceful shutdown: on SIGINT/SIGTERM or a console `stop`, count down, tell every client the
+//! server is going away, then exit.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bevy::prelude::*;
+use protocol::{Channel, DisconnectReason, ServerToClient};
+use tracing::info;
+
+use crate::app::Tick;
+use crate::legacy::VersionedServer;
+
+/// Physics ticks between the disconnect notice and exiting, so the notice reaches clients
+/// before netcode's own disconnect packets do.
+pub const SHUTDOWN_GRACE_TICKS: u32 = 3;
+
+/// Set from the signal handler; polled by `handle_shutdown_signals`.
+static SIGNALLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownSignal {
+    pub requested: bool,
+    /// Physics ticks left before clients are told to disconnect.
+    pub countdown_ticks: u32,
+}
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        Self {
+            requested: false,
+            // 2 s at the default 30 Hz tick
+            countdown_ticks: 60,
+        }
+    }
+}
+
+/// Route SIGINT and SIGTERM to `ShutdownSignal`. Call once per process.
+pub fn install_signal_handler() -> Result<(), ctrlc::Error> {
+    ctrlc::set_handler(|| SIGNALLED.store(true, Ordering::Relaxed))
+}
+
+pub(crate) fn handle_shutdown_signals(mut signal: ResMut<ShutdownSignal>) {
+    if SIGNALLED.load(Ordering::Relaxed) && !signal.requested {
+        info!(
+            countdown_ticks = signal.countdown_ticks,
+            "Shutdown signal received"
+        );
+        signal.requested = true;
+    }
+}
+
+/// Count a requested shutdown down by physics ticks, broadcast
+/// `Disconnect(ServerShutdown)` when it reaches zero, and exit `SHUTDOWN_GRACE_TICKS` later.
+pub(crate) fn broadcast_shutdown_warning(
+    mut signal: ResMut<ShutdownSignal>,
+    tick: Res<Tick>,
+    mut server: VersionedServer,
+    mut last_tick: Local<Option<u64>>,
+    mut exit_at: Local<Option<u64>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if !signal.requested {
+        *last_tick = None;
+        return;
+    }
+    let elapsed = last_tick.map_or(0, |last| tick.0.saturating_sub(last));
+    *last_tick = Some(tick.0);
+    if let Some(at) = *exit_at {
+        if tick.0 >= at {
+            info!("Shutting down");
+            exit.write(AppExit::Success);
+        }
+        return;
+    }
+    signal.countdown_ticks = signal
+        .countdown_ticks
+        .saturating_sub(elapsed.min(u32::MAX as u64) as u32);
+    if signal.countdown_ticks > 0 {
+        return;
+    }
+    let msg = ServerToClient::Disconnect(DisconnectReason::ServerShutdown);
+    let payload = protocol::encode(&msg).unwrap();
+    for client_id in server.clients_id() {
+        server.send_message(client_id, Channel::Reliable, payload.clone());
+    }
+    info!(
+        clients = server.clients_id().len(),
+        "Sent shutdown notice to clients"
+    );
+    *exit_at = Some(tick.0 + SHUTDOWN_GRACE_TICKS as u64);
+}
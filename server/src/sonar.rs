@@ -0,0 +1,161 @@
+//! Sonar pings. A `SonarPing` is answered after the physics tick with every other sub the ping
+//! still reaches once it has refracted through the level's density layers
+//! (`SonarPropagation::compute_detection_range`). Louder subs are heard further. Each player
+//! may ping once per `SONAR_PING_COOLDOWN`.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use levels::{DensityProfile, SonarPropagation, SubInputState, SubPhysicsSpec, SubState, Vec3f};
+use protocol::{Channel, FeatureFlags, ServerToClient, SonarContact};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::app::{
+    ClientEntities, Config, GrantedFeatures, LevelRes, Player, SubInputStateComp, SubPhysicsComp,
+    SubStateComp,
+};
+use crate::legacy::VersionedServer;
+
+/// Shortest time between two pings from one player; pings sent sooner are dropped unanswered.
+pub const SONAR_PING_COOLDOWN: Duration = Duration::from_secs(2);
+
+/// When a player's last answered ping went out, on the player's entity.
+#[derive(Component, Debug, Clone, Default)]
+pub struct SonarPingCooldown {
+    last_ping: Option<Duration>,
+}
+
+impl SonarPingCooldown {
+    /// Record a ping at `now`, the app's elapsed time, unless the last one was less than
+    /// `SONAR_PING_COOLDOWN` before it.
+    pub fn try_ping(&mut self, now: Duration) -> bool {
+        if self
+            .last_ping
+            .is_some_and(|last| now.saturating_sub(last) < SONAR_PING_COOLDOWN)
+        {
+            return false;
+        }
+        self.last_ping = Some(now);
+        true
+    }
+}
+
+/// Another player's sub a ping may pick up.
+#[derive(Debug, Clone, Copy)]
+pub struct SonarTarget {
+    pub player_id: Uuid,
+    pub position: Vec3f,
+    /// Its radiated noise (dB), from `acoustic_level_db`.
+    pub source_level_db: f32,
+}
+
+/// A sub's radiated noise this tick: `SubPhysicsSpec::acoustic_level_db` at its commanded
+/// thrust and body yaw rate, the value `step_submarine_dbg` reports in `SubStepDebug`.
+pub fn acoustic_level_db(spec: &SubPhysicsSpec, state: &SubState, inputs: &SubInputState) -> f32 {
+    let iyy = spec.rotational_inertia().y;
+    let yaw_rate = if iyy > 0.0 {
+        state.ang_mom.y / iyy
+    } else {
+        0.0
+    };
+    spec.acoustic_level_db(inputs.thrust, yaw_rate)
+}
+
+/// The subs in `targets` that a ping from `origin` reaches, nearest first. Each is heard out to
+/// `base_range_m` scaled by its source level, shortened by refraction through `density`.
+/// Contacts nearer than
+/// `masking_range_m` are lost in the pinging sub's own flow noise
+/// (`SubPhysicsSpec::noise_masking_range`).
+pub fn sonar_contacts(
+    origin: Vec3f,
+    targets: impl IntoIterator<Item = SonarTarget>,
+    density: &DensityProfile,
+    base_range_m: f32,
+    masking_range_m: f32,
+) -> Vec<SonarContact> {
+    let mut contacts: Vec<_> = targets
+        .into_iter()
+        .filter_map(|target| {
+            let distance_m = origin.distance(target.position);
+            let range_m = SonarPropagation::compute_detection_range(
+                origin,
+                target.position,
+                density,
+                base_range_m,
+                target.source_level_db,
+            );
+            (masking_range_m <= distance_m && distance_m <= range_m).then(|| SonarContact {
+                player_id: target.player_id,
+                position: target.position.to_array(),
+                distance_m,
+            })
+        })
+        .collect();
+    contacts.sort_by(|a, b| a.distance_m.total_cmp(&b.distance_m));
+    contacts
+}
+
+/// Subs whose `SonarPing` waits for `server_answer_sonar_pings`.
+#[derive(Resource, Default)]
+pub(crate) struct SonarPingInbox(pub Vec<Entity>);
+
+/// Answer queued `SonarPing`s from players granted `FeatureFlags::SONAR` and off their
+/// `SonarPingCooldown` with the other subs their ping reaches, minus those masked by the
+/// pinging sub's speed. Each contact's own noise sets how far it is heard.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn server_answer_sonar_pings(
+    cfg: Res<Config>,
+    level: Res<LevelRes>,
+    time: Res<Time>,
+    clients: Res<ClientEntities>,
+    mut server: VersionedServer,
+    mut pings: ResMut<SonarPingInbox>,
+    mut q_cooldowns: Query<&mut SonarPingCooldown>,
+    q_subs: Query<(
+        Entity,
+        &Player,
+        &SubStateComp,
+        &SubPhysicsComp,
+        &SubInputStateComp,
+        Option<&GrantedFeatures>,
+    )>,
+) {
+    for entity in pings.0.drain(..) {
+        let Ok((_, _, state, spec, _, granted)) = q_subs.get(entity) else {
+            continue;
+        };
+        if granted.is_some_and(|g| !g.0.contains(FeatureFlags::SONAR)) {
+            continue;
+        }
+        let Some((&client_id, _)) = clients.0.iter().find(|(_, &e)| e == entity) else {
+            continue;
+        };
+        if let Ok(mut cooldown) = q_cooldowns.get_mut(entity) {
+            if !cooldown.try_ping(time.elapsed()) {
+                debug!(client_id, "sonar ping dropped during cooldown");
+                continue;
+            }
+        }
+        let targets = q_subs.iter().filter(|&(other, ..)| other != entity).map(
+            |(_, player, other, other_spec, inputs, _)| SonarTarget {
+                player_id: player.id,
+                position: other.0.position,
+                source_level_db: acoustic_level_db(&other_spec.0, &other.0, &inputs.0),
+            },
+        );
+        let contacts = sonar_contacts(
+            state.0.position,
+            targets,
+            &level.0.density_profile,
+            cfg.sonar_base_range_m,
+            spec.0.noise_masking_range(state.0.speed()),
+        );
+        let msg = ServerToClient::SonarPingEcho(protocol::SonarPingEcho { contacts });
+        server.send_message(
+            client_id,
+            Channel::Reliable,
+            protocol::encode(&msg).unwrap(),
+        );
+    }
+}
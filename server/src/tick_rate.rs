@@ -0,0 +1,102 @@
+use std::time::Instant;
+
+use bevy::prelude::*;
+
+/// Frame times are averaged over windows of this much game time.
+pub const TICK_RATE_WINDOW_S: f32 = 5.0;
+/// Load shedding never halves the tick rate below this.
+pub const MIN_TICK_HZ: u32 = 10;
+/// A window is slow when frames averaged more than this many physics steps of work.
+const SLOW_FRAME_STEPS: f32 = 1.5;
+/// A window is fast when frames averaged less than this many physics steps of work.
+const FAST_FRAME_STEPS: f32 = 0.8;
+const SLOW_WINDOWS_TO_HALVE: u32 = 3;
+const FAST_WINDOWS_TO_DOUBLE: u32 = 5;
+
+/// Adapts the physics tick rate to server load: three slow windows in a row halve it, down to
+/// `MIN_TICK_HZ`, and five fast windows in a row double it, back up to the configured rate.
+/// Frame time is wall-clock time spent running the schedule, excluding the runner's sleep.
+#[derive(Resource, Debug, Clone)]
+pub struct TickRateGovernor {
+    pub current_hz: u32,
+    /// `Config::tick_hz`, which the rate never exceeds.
+    pub max_hz: u32,
+    window_s: f32,
+    window_busy_s: f32,
+    window_frames: u32,
+    slow_windows: u32,
+    fast_windows: u32,
+    frame_start: Option<Instant>,
+}
+
+impl TickRateGovernor {
+    pub fn new(max_hz: u32) -> Self {
+        let max_hz = max_hz.max(1);
+        Self {
+            current_hz: max_hz,
+            max_hz,
+            window_s: 0.0,
+            window_busy_s: 0.0,
+            window_frames: 0,
+            slow_windows: 0,
+            fast_windows: 0,
+            frame_start: None,
+        }
+    }
+
+    /// Count one frame that took `busy_s` of work over `dt_s` of game time. Returns the new
+    /// tick rate when this frame closed a window and the rate changed.
+    pub fn record_frame(&mut self, busy_s: f32, dt_s: f32) -> Option<u32> {
+        self.window_s += dt_s;
+        self.window_busy_s += busy_s;
+        self.window_frames += 1;
+        if self.window_s < TICK_RATE_WINDOW_S {
+            return None;
+        }
+        let average_s = self.window_busy_s / self.window_frames as f32;
+        self.window_s = 0.0;
+        self.window_busy_s = 0.0;
+        self.window_frames = 0;
+        self.close_window(average_s)
+    }
+
+    /// Judge a window whose frames averaged `average_s` of work.
+    pub fn close_window(&mut self, average_s: f32) -> Option<u32> {
+        let step_s = 1.0 / self.current_hz as f32;
+        if average_s > SLOW_FRAME_STEPS * step_s {
+            self.slow_windows += 1;
+            self.fast_windows = 0;
+        } else if average_s < FAST_FRAME_STEPS * step_s {
+            self.fast_windows += 1;
+            self.slow_windows = 0;
+        } else {
+            self.slow_windows = 0;
+            self.fast_windows = 0;
+        }
+        let new_hz = if self.slow_windows >= SLOW_WINDOWS_TO_HALVE {
+            (self.current_hz / 2).max(MIN_TICK_HZ.min(self.max_hz))
+        } else if self.fast_windows >= FAST_WINDOWS_TO_DOUBLE {
+            (self.current_hz * 2).min(self.max_hz)
+        } else {
+            return None;
+        };
+        self.slow_windows = 0;
+        self.fast_windows = 0;
+        if new_hz == self.current_hz {
+            return None;
+        }
+        self.current_hz = new_hz;
+        Some(new_hz)
+    }
+
+    pub(crate) fn start_frame(&mut self) {
+        self.frame_start = Some(Instant::now());
+    }
+
+    /// Wall-clock time since `start_frame`, or zero if no frame was started.
+    pub(crate) fn frame_busy_s(&mut self) -> f32 {
+        self.frame_start
+            .take()
+            .map_or(0.0, |start| start.elapsed().as_secs_f32())
+    }
+}
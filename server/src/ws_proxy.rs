@@ -0,0 +1,100 @@
+//! WebSocket-to-UDP proxy for browser clients. Each WebSocket connection gets its own UDP
+//! socket, so the netcode server sees it as an ordinary client at that socket's address; every
+//! binary message is forwarded as one datagram and every datagram back as one message.
+//!
+//! All connections are multiplexed as tasks on one single-threaded tokio runtime.
+
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::Semaphore;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tracing::{info, warn};
+
+/// How long a new connection may take to send its upgrade request.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Most WebSocket clients proxied at once; further connections are refused until one closes.
+pub const MAX_WS_CONNECTIONS: usize = 256;
+
+/// Listen for WebSocket connections on `ws_port` and relay them to the netcode socket at
+/// `netcode_addr`. Runs on a background thread for the life of the process; returns the bound
+/// listener address.
+pub fn spawn_ws_proxy(ws_port: u16, netcode_addr: SocketAddr) -> io::Result<SocketAddr> {
+    let listener = std::net::TcpListener::bind((Ipv4Addr::UNSPECIFIED, ws_port))?;
+    listener.set_nonblocking(true)?;
+    let bound = listener.local_addr()?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .enable_time()
+        .build()?;
+    std::thread::Builder::new()
+        .name("ws-proxy".into())
+        .spawn(move || {
+            runtime.block_on(async move {
+                match TcpListener::from_std(listener) {
+                    Ok(listener) => serve(listener, netcode_addr).await,
+                    Err(err) => warn!(?err, "ws proxy could not register its listener"),
+                }
+            })
+        })?;
+    info!(port = bound.port(), "WebSocket proxy listening");
+    Ok(bound)
+}
+
+async fn serve(listener: TcpListener, netcode_addr: SocketAddr) {
+    let slots = Arc::new(Semaphore::new(MAX_WS_CONNECTIONS));
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(err) => {
+                warn!(?err, "ws proxy accept failed");
+                continue;
+            }
+        };
+        let Ok(slot) = slots.clone().try_acquire_owned() else {
+            warn!(?peer, "ws proxy full; refusing connection");
+            continue;
+        };
+        tokio::spawn(async move {
+            match relay(stream, netcode_addr).await {
+                Ok(()) => info!(?peer, "ws client closed"),
+                Err(err) => info!(?peer, ?err, "ws client dropped"),
+            }
+            drop(slot);
+        });
+    }
+}
+
+async fn relay(stream: TcpStream, netcode_addr: SocketAddr) -> Result<(), WsError> {
+    let peer = stream.peer_addr().ok();
+    let mut ws = tokio::time::timeout(HANDSHAKE_TIMEOUT, tokio_tungstenite::accept_async(stream))
+        .await
+        .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))??;
+    let udp = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+    udp.connect(netcode_addr).await?;
+    info!(?peer, udp = ?udp.local_addr().ok(), "ws client connected");
+    let mut buf = [0u8; 2048];
+    loop {
+        tokio::select! {
+            msg = ws.next() => match msg {
+                Some(Ok(Message::Binary(data))) => {
+                    udp.send(&data).await?;
+                }
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                // Pings are answered by tungstenite; text is not part of the tunnel
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(err),
+            },
+            received = udp.recv(&mut buf) => match received {
+                Ok(n) => ws.send(Message::binary(buf[..n].to_vec())).await?,
+                // ICMP port unreachable while the server restarts; keep the browser connected
+                Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => {}
+                Err(err) => return Err(err.into()),
+            },
+        }
+    }
+}
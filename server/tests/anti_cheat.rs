@@ -0,0 +1,80 @@
+use levels::{subspecs::small_skiff_spec, Vec3f};
+use protocol::InputTick;
+use std::time::Duration;
+
+use server::anti_cheat::{
+    max_plausible_speed, AntiCheatState, KickedPlayers, KICK_COOLDOWN, VELOCITY_STRIKES_TO_KICK,
+};
+use uuid::Uuid;
+
+fn input(thrust: f32, yaw: f32, pump_fwd: f32, pump_aft: f32) -> InputTick {
+    InputTick {
+        tick: 1,
+        thrust,
+        yaw,
+        pump_fwd,
+        pump_aft,
+        pitch: 0.0,
+        roll_trim: 0.0,
+    }
+}
+
+#[test]
+fn speed_limit_is_twice_the_linear_drag_terminal_speed() {
+    let spec = small_skiff_spec();
+    assert_eq!(max_plausible_speed(&spec), spec.t_max / spec.xu * 2.0);
+    let no_drag = levels::SubPhysicsSpec { xu: 0.0, ..spec };
+    assert_eq!(max_plausible_speed(&no_drag), f32::INFINITY);
+}
+
+#[test]
+fn sustained_overspeed_is_clamped_then_kicked() {
+    let mut state = AntiCheatState::default();
+    let max_speed = 10.0;
+    for strike in 1..VELOCITY_STRIKES_TO_KICK {
+        let mut velocity = Vec3f::new(0.0, 0.0, 30.0);
+        assert_eq!(state.check_velocity(&mut velocity, max_speed), Some(30.0));
+        assert!((velocity.length() - max_speed).abs() < 1e-4);
+        assert_eq!(state.velocity_strikes, strike);
+        assert!(!state.should_kick());
+    }
+
+    // One plausible tick restarts the count
+    let mut velocity = Vec3f::new(0.0, 0.0, 5.0);
+    assert_eq!(state.check_velocity(&mut velocity, max_speed), None);
+    assert_eq!(velocity, Vec3f::new(0.0, 0.0, 5.0));
+    assert_eq!(state.velocity_strikes, 0);
+
+    for _ in 0..VELOCITY_STRIKES_TO_KICK {
+        let mut velocity = Vec3f::new(f32::NAN, 0.0, 0.0);
+        assert!(state.check_velocity(&mut velocity, max_speed).is_some());
+        assert_eq!(velocity, Vec3f::ZERO);
+    }
+    assert!(state.should_kick());
+}
+
+#[test]
+fn out_of_range_inputs_count_as_strikes() {
+    let mut state = AntiCheatState::default();
+    assert!(!state.check_input(&input(1.0, -1.0, 0.5, -0.5)));
+    assert!(state.check_input(&input(1.5, 0.0, 0.0, 0.0)));
+    assert!(state.check_input(&input(0.0, -3.0, 0.0, 0.0)));
+    assert!(state.check_input(&input(0.0, 0.0, 0.0, f32::NAN)));
+    assert_eq!(state.input_strikes, 3);
+    // Strikes don't kick on their own
+    assert!(!state.should_kick());
+}
+
+#[test]
+fn kicked_players_are_barred_until_the_cooldown_ends() {
+    let mut kicked = KickedPlayers::default();
+    let player = Uuid::new_v4();
+    let kicked_at = Duration::from_secs(10);
+    kicked.kick(player, kicked_at);
+
+    assert!(kicked.is_barred(&player, kicked_at));
+    assert!(kicked.is_barred(&player, kicked_at + KICK_COOLDOWN - Duration::from_secs(1)));
+    assert!(!kicked.is_barred(&Uuid::new_v4(), kicked_at));
+    assert!(!kicked.is_barred(&player, kicked_at + KICK_COOLDOWN));
+    assert!(kicked.0.is_empty(), "expired kicks should be forgotten");
+}
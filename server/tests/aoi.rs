@@ -0,0 +1,35 @@
+use bevy::math::{IVec3, Vec3};
+use server::AoiGrid;
+use uuid::Uuid;
+
+#[test]
+fn players_see_each_other_within_the_chebyshev_radius() {
+    let mut grid = AoiGrid::new(32.0, 3);
+    assert_eq!(grid.cell_of(Vec3::new(-0.5, 31.9, 64.0)), IVec3::new(-1, 0, 2));
+
+    let (me, near, diagonal, far) = (
+        Uuid::from_u128(1),
+        Uuid::from_u128(2),
+        Uuid::from_u128(3),
+        Uuid::from_u128(4),
+    );
+    let unbinned = Uuid::from_u128(5);
+    grid.rebuild([
+        (me, Vec3::ZERO),
+        (near, Vec3::new(-90.0, 0.0, 0.0)),
+        // Three cells out on every axis is still in range
+        (diagonal, Vec3::new(100.0, 100.0, 100.0)),
+        (far, Vec3::new(200.0, 0.0, 0.0)),
+    ]);
+    let viewer = grid.cell(me);
+    assert!(grid.is_visible(viewer, me));
+    assert!(grid.is_visible(viewer, near));
+    assert!(grid.is_visible(viewer, diagonal));
+    assert!(!grid.is_visible(viewer, far));
+    assert!(grid.is_visible(viewer, unbinned));
+    assert!(grid.is_visible(None, far));
+
+    // Rebinning forgets players that left
+    grid.rebuild([(me, Vec3::ZERO)]);
+    assert_eq!(grid.cell(far), None);
+}
@@ -0,0 +1,21 @@
+use bevy_renet::renet::ConnectionConfig;
+use protocol::renet_config::connection_config;
+
+#[test]
+fn connection_config_scales_every_channel_budget() {
+    let base = connection_config(1.0);
+    let doubled = connection_config(2.0);
+    let budgets = |c: &ConnectionConfig| {
+        c.server_channels_config
+            .iter()
+            .chain(&c.client_channels_config)
+            .map(|ch| (ch.channel_id, ch.max_memory_usage_bytes))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(base.server_channels_config.len(), 2);
+    assert_eq!(base.client_channels_config.len(), 2);
+    for ((id, b), (id2, d)) in budgets(&base).into_iter().zip(budgets(&doubled)) {
+        assert_eq!(id, id2);
+        assert_eq!(d, 2 * b);
+    }
+}
@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use protocol::MAX_CHAT_BYTES;
+use server::chat::{validate_chat_text, ChatRateLimit, ChatRejection, CHAT_WINDOW};
+
+#[test]
+fn chat_text_is_limited_by_utf8_bytes_and_rejects_nul() {
+    assert_eq!(validate_chat_text("hello"), Ok(()));
+    assert_eq!(validate_chat_text(&"a".repeat(MAX_CHAT_BYTES)), Ok(()));
+    assert_eq!(
+        validate_chat_text(&"a".repeat(MAX_CHAT_BYTES + 1)),
+        Err(ChatRejection::TooLong)
+    );
+    // 86 three-byte characters are only 86 chars but 258 bytes
+    assert_eq!(
+        validate_chat_text(&"€".repeat(86)),
+        Err(ChatRejection::TooLong)
+    );
+    assert_eq!(
+        validate_chat_text("before\0after"),
+        Err(ChatRejection::ContainsNul)
+    );
+}
+
+#[test]
+fn four_messages_per_five_seconds_in_a_sliding_window() {
+    let mut limit = ChatRateLimit::default();
+    let at = |ms: u64| Duration::from_millis(ms);
+    for ms in [0, 1000, 2000, 3000] {
+        assert_eq!(limit.try_send(at(ms)), Ok(()));
+    }
+    assert_eq!(limit.try_send(at(4999)), Err(ChatRejection::RateLimited));
+    // The first message leaves the window exactly `CHAT_WINDOW` after it was sent
+    assert_eq!(limit.try_send(CHAT_WINDOW), Ok(()));
+    assert_eq!(limit.try_send(at(5500)), Err(ChatRejection::RateLimited));
+    assert_eq!(limit.try_send(at(6000)), Ok(()));
+}
@@ -0,0 +1,16 @@
+use server::console::ConsoleCommand;
+
+#[test]
+fn stop_takes_an_optional_tick_count() {
+    assert_eq!(
+        ConsoleCommand::parse("stop"),
+        Ok(ConsoleCommand::Stop { ticks: None })
+    );
+    assert_eq!(
+        ConsoleCommand::parse("  stop 90 "),
+        Ok(ConsoleCommand::Stop { ticks: Some(90) })
+    );
+    assert!(ConsoleCommand::parse("stop soon").is_err());
+    assert!(ConsoleCommand::parse("restart").is_err());
+    assert!(ConsoleCommand::parse("").is_err());
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use server::hello::{DEPARTED_PLAYER_TTL, MAX_DEPARTED_PLAYERS};
+use server::{DepartedPlayer, DepartedPlayers, PlayerScore, Team};
+use uuid::Uuid;
+
+fn departed(credits: u64, left_at: Duration) -> DepartedPlayer {
+    DepartedPlayer {
+        player_id: Uuid::from_u128(credits as u128),
+        score: PlayerScore {
+            credits,
+            ..Default::default()
+        },
+        team: Team(0),
+        sub_state: None,
+        hull: Default::default(),
+        cargo: Default::default(),
+        left_at,
+    }
+}
+
+#[test]
+fn departed_players_expire_after_the_ttl() {
+    let mut departed_players = DepartedPlayers::default();
+    let (early, late) = (1, 2);
+    departed_players.remember(early, departed(100, Duration::ZERO));
+    departed_players.remember(late, departed(200, DEPARTED_PLAYER_TTL / 2));
+
+    // Remembering someone new sweeps out whoever has been gone a full TTL
+    departed_players.remember(3, departed(300, DEPARTED_PLAYER_TTL));
+    assert!(!departed_players.0.contains_key(&early));
+    assert_eq!(departed_players.0.len(), 2);
+
+    let back = departed_players.take(late, &Uuid::from_u128(200), DEPARTED_PLAYER_TTL);
+    assert_eq!(back.map(|b| b.score.credits), Some(200));
+    assert!(departed_players
+        .take(3, &Uuid::from_u128(300), DEPARTED_PLAYER_TTL * 2)
+        .is_none());
+}
+
+#[test]
+fn departed_players_are_capped_by_evicting_the_oldest() {
+    let mut departed_players = DepartedPlayers::default();
+    for i in 0..MAX_DEPARTED_PLAYERS as u64 + 10 {
+        departed_players.remember(i, departed(i, Duration::from_millis(i)));
+    }
+    assert_eq!(departed_players.0.len(), MAX_DEPARTED_PLAYERS);
+    for i in 0..10 {
+        assert!(!departed_players.0.contains_key(&i));
+    }
+    assert!(departed_players
+        .0
+        .contains_key(&(MAX_DEPARTED_PLAYERS as u64 + 9)));
+}
+
+#[test]
+fn departed_state_is_only_restored_to_its_client_secret() {
+    let mut departed_players = DepartedPlayers::default();
+    let player_id = Uuid::from_u128(500);
+    departed_players.remember(7, departed(500, Duration::ZERO));
+
+    // Someone who only knows the public player id gets nothing
+    assert!(departed_players.take(8, &player_id, Duration::ZERO).is_none());
+    assert!(departed_players.0.contains_key(&7));
+
+    let back = departed_players.take(7, &player_id, Duration::ZERO);
+    assert_eq!(back.map(|b| b.score.credits), Some(500));
+}
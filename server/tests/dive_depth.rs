@@ -0,0 +1,21 @@
+use levels::DiveDepthEvent;
+use server::dive_depth::DiveDepthAlarm;
+
+#[test]
+fn players_are_warned_only_when_going_a_band_deeper() {
+    let mut alarm = DiveDepthAlarm::default();
+    let band = |event| DiveDepthAlarm::from_event(event);
+    let warning = band(Some(DiveDepthEvent::Warning { depth_m: 160.0 }));
+    let exceeded = band(Some(DiveDepthEvent::Exceeded { depth_m: 210.0 }));
+
+    assert!(!alarm.escalate(band(None)));
+    assert!(alarm.escalate(warning));
+    assert!(!alarm.escalate(warning));
+    assert!(alarm.escalate(exceeded));
+    // Blown back up into the warning band, then down past the limit again
+    assert!(!alarm.escalate(warning));
+    assert!(alarm.escalate(exceeded));
+    // Surfacing resets, so the next dive warns from scratch
+    assert!(!alarm.escalate(band(None)));
+    assert!(alarm.escalate(exceeded));
+}
@@ -0,0 +1,63 @@
+use levels::SubInputs;
+use server::InputBuffer;
+
+fn thrust(value: f32) -> SubInputs {
+    SubInputs {
+        thrust: value,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn out_of_order_ticks_are_replayed_in_tick_order() {
+    let mut buffer = InputBuffer::default();
+    // Client ticks 10..=14 map onto server ticks 100..=104; 12 and 13 arrive swapped and late
+    buffer.insert(100, 10, thrust(0.1));
+    buffer.insert(101, 11, thrust(0.2));
+    buffer.insert(104, 13, thrust(0.4));
+    buffer.insert(104, 12, thrust(0.3));
+    buffer.insert(104, 14, thrust(0.5));
+
+    let jitter = 3;
+    let applied: Vec<f32> = (103..=107)
+        .map(|server_tick| buffer.select(server_tick, jitter).unwrap().thrust)
+        .collect();
+    assert_eq!(applied, [0.1, 0.2, 0.3, 0.4, 0.5]);
+    // Holds the newest input once the client goes quiet
+    assert_eq!(buffer.select(200, jitter).unwrap().thrust, 0.5);
+}
+
+#[test]
+fn buffer_keeps_only_the_newest_ticks() {
+    let mut buffer = InputBuffer::with_capacity(4);
+    assert!(buffer.select(0, 0).is_none());
+    for tick in 0..10 {
+        buffer.insert(tick, tick, thrust(tick as f32));
+    }
+    assert_eq!(buffer.len(), 4);
+    // A straggler older than everything held is dropped rather than evicting newer input
+    buffer.insert(10, 2, thrust(-1.0));
+    assert_eq!(buffer.len(), 4);
+    assert_eq!(buffer.select(0, 0).unwrap().thrust, 6.0);
+
+    // A repeated tick replaces its entry
+    buffer.insert(10, 9, thrust(0.9));
+    assert_eq!(buffer.select(9, 0).unwrap().thrust, 0.9);
+
+    buffer.clear();
+    assert!(buffer.is_empty() && buffer.select(9, 0).is_none());
+}
+
+#[test]
+fn newest_tick_survives_clear() {
+    let mut buffer = InputBuffer::default();
+    assert!(buffer.newest().is_none());
+    buffer.insert(100, 20, thrust(0.2));
+    // A straggler from a late batch doesn't replace the newest tick
+    buffer.insert(101, 18, thrust(-0.5));
+    let (tick, inputs) = buffer.newest().unwrap();
+    assert_eq!((tick, inputs.thrust), (20, 0.2));
+
+    buffer.clear();
+    assert_eq!(buffer.newest().map(|(tick, _)| tick), Some(20));
+}
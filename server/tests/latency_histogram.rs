@@ -0,0 +1,46 @@
+use server::LatencyHistogram;
+
+#[test]
+fn p95_of_1000_samples_lands_in_its_bucket() {
+    let mut hist = LatencyHistogram::default();
+    // 1..=1000 ms: the 950th sample is 950 ms
+    for ms in 1..=1000 {
+        hist.record(ms as f32);
+    }
+    assert_eq!(hist.count(), 1000);
+
+    let p95 = hist.percentile_ms(0.95).unwrap();
+    let bucket = hist.bucket_of(950.0);
+    assert!(
+        hist.bounds_ms[bucket] <= p95 && p95 <= hist.bounds_ms[bucket + 1],
+        "p95 {p95} outside bucket {bucket} {:?}",
+        &hist.bounds_ms[bucket..=bucket + 1]
+    );
+    assert!(hist.percentile_ms(0.5).unwrap() < p95);
+    assert!(p95 <= hist.percentile_ms(0.99).unwrap());
+}
+
+#[test]
+fn prometheus_buckets_are_cumulative_and_end_at_inf() {
+    let mut hist = LatencyHistogram::default();
+    assert_eq!(hist.percentile_ms(0.5), None);
+    for ms in [0.5, 30.0, 30.0, 5000.0] {
+        hist.record(ms);
+    }
+    assert_eq!(hist.buckets[0], 1);
+    assert_eq!(hist.buckets[15], 1);
+
+    let mut out = String::new();
+    hist.write_prometheus(&mut out, "7");
+    let buckets: Vec<u64> = out
+        .lines()
+        .filter(|l| l.starts_with("thalassocracy_client_latency_bucket{client=\"7\""))
+        .map(|l| l.rsplit(' ').next().unwrap().parse().unwrap())
+        .collect();
+    assert_eq!(buckets.len(), 16);
+    assert!(buckets.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(buckets[0], 1);
+    assert_eq!(buckets[14], 3);
+    assert!(out.contains("le=\"+Inf\"} 4\n"));
+    assert!(out.contains("thalassocracy_client_latency_count{client=\"7\"} 4\n"));
+}
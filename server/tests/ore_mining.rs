@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use levels::{builtins::deep_trench_level, OreNodeSpec, ResourceType, Vec3f};
+use server::app::{MINE_RANGE_M, ORE_RESPAWN_S};
+use server::OreNode;
+
+fn fresh_node(spec: &OreNodeSpec) -> OreNode {
+    OreNode {
+        node_id: 0,
+        position: spec.position,
+        respawn_timer: None,
+        remaining_kg: spec.supply_kg(),
+        passes: 0,
+    }
+}
+
+#[test]
+fn mining_deducts_supply_and_the_last_pass_takes_what_is_left() {
+    let mut spec = deep_trench_level().ore_nodes[0].clone();
+    spec.supply_kg = Some(120);
+    let mut node = fresh_node(&spec);
+
+    let mut mined = Vec::new();
+    while node.remaining_kg > 0 {
+        mined.push(node.mine(&spec));
+    }
+    let amounts: Vec<u32> = mined.iter().map(|&(_, kg)| kg).collect();
+    assert_eq!(amounts, [50, 50, 20]);
+    assert_eq!(node.passes, 3);
+    for (pass, &(resource_type, _)) in mined.iter().enumerate() {
+        assert_eq!(resource_type, spec.resource_for_pass(0, pass as u32));
+        assert_ne!(resource_type, ResourceType::RareMineral);
+    }
+    // A mined-out node yields nothing until it respawns
+    assert_eq!(node.mine(&spec).1, 0);
+}
+
+#[test]
+fn depleting_a_node_starts_its_respawn_timer_and_refuses_mining() {
+    let mut spec = deep_trench_level().ore_nodes[0].clone();
+    spec.supply_kg = Some(100);
+    let mut node = fresh_node(&spec);
+
+    node.mine(&spec);
+    assert!(node.respawn_timer.is_none());
+    assert!(node.in_reach(spec.position));
+    node.mine(&spec);
+    assert_eq!(node.remaining_kg, 0);
+    assert!(node.respawn_timer.is_some());
+    assert!(!node.in_reach(spec.position));
+}
+
+#[test]
+fn depleted_node_respawns_full_once_the_timer_runs_out() {
+    let mut spec = deep_trench_level().ore_nodes[0].clone();
+    spec.supply_kg = Some(50);
+    let mut node = fresh_node(&spec);
+    node.mine(&spec);
+
+    let almost = Duration::from_secs_f32(ORE_RESPAWN_S - 1.0);
+    assert!(!node.tick_respawn(almost, Some(&spec)));
+    assert_eq!(node.remaining_kg, 0);
+    assert!(node.tick_respawn(Duration::from_secs(1), Some(&spec)));
+    assert!(node.respawn_timer.is_none());
+    assert_eq!(node.remaining_kg, 50);
+    assert!(node.in_reach(spec.position));
+
+    // A node that is not depleted has no timer to run
+    assert!(!node.tick_respawn(Duration::from_secs(3600), Some(&spec)));
+    assert_eq!(node.remaining_kg, 50);
+}
+
+#[test]
+fn nodes_out_of_range_cannot_be_mined() {
+    let spec = deep_trench_level().ore_nodes[0].clone();
+    let node = fresh_node(&spec);
+    assert!(node.in_reach(spec.position + Vec3f::new(MINE_RANGE_M, 0.0, 0.0)));
+    assert!(!node.in_reach(spec.position + Vec3f::new(MINE_RANGE_M + 0.1, 0.0, 0.0)));
+}
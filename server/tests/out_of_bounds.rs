@@ -0,0 +1,95 @@
+use levels::{builtins::greybox_level, step_submarine, Quatf, SubInputState, SubState, Vec3f};
+use protocol::{NetInputState, NetPlayer, ServerToClient, StateDelta};
+use server::{clamp_sub_state, LevelBounds};
+
+#[test]
+fn zero_mass_spec_is_clamped_back_to_spawn_instead_of_sending_nan() {
+    let level = greybox_level();
+    let bounds = LevelBounds::from_level(&level);
+    // Massless hull with empty tanks: forces are divided by the 1e-3 kg mass floor, so thrust
+    // flings the sub out of the level within a few ticks
+    let mut spec = levels::subspecs::small_skiff_spec();
+    spec.m = 0.0;
+
+    let mut state = SubState {
+        position: level.tunnel.pos,
+        velocity: Vec3f::ZERO,
+        orientation: Quatf::IDENTITY,
+        ang_mom: Vec3f::ZERO,
+        ballast_fill: vec![0.0; spec.ballast_tanks.len()],
+        pump: Default::default(),
+    };
+    let inputs = SubInputState {
+        thrust: 1.0,
+        ..Default::default()
+    };
+    let dt = 1.0 / 30.0;
+    let mut clamped = 0;
+    for tick in 0..300 {
+        step_submarine(&level, &spec, inputs, &mut state, dt, tick as f32 * dt);
+        if clamp_sub_state(&mut state, &bounds) {
+            clamped += 1;
+            assert_eq!(state.velocity, Vec3f::ZERO);
+            assert!(bounds.spawn_points.contains(&state.position));
+            assert!(level.in_open_water(state.position));
+        }
+        assert!(
+            bounds.contains(state.position),
+            "tick {tick}: {:?}",
+            state.position
+        );
+    }
+    assert!(clamped > 0, "degenerate spec never left the level");
+
+    let delta = ServerToClient::StateDelta(StateDelta {
+        tick: 1,
+        server_ms: 0,
+        players: vec![NetPlayer {
+            id: uuid::Uuid::nil(),
+            position: state.position.to_array(),
+            velocity: state.velocity.to_array(),
+            orientation: state.orientation.to_array(),
+            ang_mom: state.ang_mom.to_array(),
+            angular_velocity: [0.0; 3],
+            ballast_fill: state.ballast_fill.clone(),
+            input_state: NetInputState {
+                thrust: inputs.thrust,
+                yaw: inputs.yaw,
+                pump_fwd: inputs.pump_fwd,
+                pump_aft: inputs.pump_aft,
+                pitch: 0.0,
+                roll_trim: 0.0,
+            },
+            hull_integrity: 0.1,
+            team_id: 0,
+            pump: Default::default(),
+        }],
+        compressed_players: Vec::new(),
+        snapshot_origin: [0.0; 3],
+        obstacles: Vec::new(),
+    });
+    let ServerToClient::StateDelta(decoded) =
+        protocol::decode::<ServerToClient>(&protocol::encode(&delta).unwrap()).unwrap()
+    else {
+        panic!("expected a StateDelta");
+    };
+    let sub = &decoded.players[0];
+    assert!(sub
+        .position
+        .iter()
+        .chain(&sub.velocity)
+        .chain(&sub.orientation)
+        .chain(&sub.ballast_fill)
+        .all(|v| v.is_finite()));
+}
+
+#[test]
+fn bounds_cover_every_volume_with_margin() {
+    let level = greybox_level();
+    let bounds = LevelBounds::from_level(&level);
+    assert!(bounds.contains(level.chamber.pos));
+    assert!(bounds.contains(level.tunnel.pos + Vec3f::Y * (level.tunnel.size.y * 0.5 + 40.0)));
+    assert!(!bounds.contains(Vec3f::splat(f32::NAN)));
+    assert!(!bounds.contains(level.chamber.pos + Vec3f::X * 1.0e4));
+    assert!(bounds.spawn_points.iter().all(|&p| level.in_open_water(p)));
+}
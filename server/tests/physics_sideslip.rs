@@ -29,6 +29,7 @@ fn run_sideslip_scenario(flow: Vec3f, ticks: usize, thrust: f32) {
         orientation: Quatf::from_rotation_y(0.0),
         ang_mom: Vec3f::new(0.0, 0.0, 0.0),
         ballast_fill: vec![0.0; spec.ballast_tanks.len()],
+        pump: Default::default(),
     };
 
     // Simulate
@@ -39,6 +40,8 @@ fn run_sideslip_scenario(flow: Vec3f, ticks: usize, thrust: f32) {
         yaw: 0.0,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     let mut tick_counter = 0;
     for _ in 0..ticks {
@@ -95,6 +98,7 @@ fn run_rudder_sign_scenario(thrust: f32, _rudder: f32, warm_ticks: usize, steer_
         orientation: Quatf::from_rotation_y(0.0),
         ang_mom: Vec3f::new(0.0, 0.0, 0.0),
         ballast_fill: vec![0.0; spec.ballast_tanks.len()],
+        pump: Default::default(),
     };
     let dt = 1.0 / 30.0;
     let mut t = 0.0f32;
@@ -103,23 +107,33 @@ fn run_rudder_sign_scenario(thrust: f32, _rudder: f32, warm_ticks: usize, steer_
         yaw: 0.0,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     for _ in 0..warm_ticks {
         step_submarine(&level, &spec, warm_inputs, &mut state, dt, t);
         t += dt;
     }
-    let yaw0 = yaw_of(state.orientation);
+    // A steady turn wraps past ±π within the steer window, so sum the per-step change
+    let mut yaw_prev = yaw_of(state.orientation);
+    let mut yaw_turned = 0.0f32;
     // Steer to the right with positive slider
     let steer_inputs = SubInputState {
         thrust,
         yaw: 0.2,
         pump_fwd: 0.0,
         pump_aft: 0.0,
+        plane: 0.0,
+        roll_trim: 0.0,
     };
     let mut w_sum = 0.0f32;
     for i in 0..steer_ticks {
         step_submarine(&level, &spec, steer_inputs, &mut state, dt, t);
         t += dt;
+        let yaw = yaw_of(state.orientation);
+        yaw_turned += (yaw - yaw_prev + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+            - std::f32::consts::PI;
+        yaw_prev = yaw;
         if i + 100 >= steer_ticks {
             let forward = state.orientation * Vec3f::new(0.0, 0.0, 1.0);
             let up = Vec3f {
@@ -137,12 +151,10 @@ fn run_rudder_sign_scenario(thrust: f32, _rudder: f32, warm_ticks: usize, steer_
             w_sum += w;
         }
     }
-    let yaw1 = yaw_of(state.orientation);
     assert!(
-        yaw1 > yaw0 + 0.01,
-        "rudder right did not increase yaw (right-turn): yaw0={}, yaw1={}",
-        yaw0,
-        yaw1
+        yaw_turned < -0.01,
+        "rudder right did not decrease yaw (right-turn): turned {}",
+        yaw_turned
     );
     // Sideslip magnitude should not blow up during a gentle right turn
     assert!(
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use protocol::RateLimit;
+use server::rate_limit::{ClientRateMonitor, SuspicionLevel, RATE_LIMITS_PER_SUSPICION};
+use uuid::Uuid;
+
+/// Send `count` inputs spread evenly over the second starting at `second`; returns how many
+/// were accepted and the verdicts' notices and suspicion changes.
+fn flood_second(
+    monitor: &mut ClientRateMonitor,
+    player: Uuid,
+    second: u64,
+    count: u32,
+) -> (u32, Vec<RateLimit>, Vec<SuspicionLevel>) {
+    let mut accepted = 0;
+    let mut notices = Vec::new();
+    let mut suspicion = Vec::new();
+    for i in 0..count {
+        let now = Duration::from_secs(second) + Duration::from_secs(1) * i / count;
+        let verdict = monitor.record(player, now, 120);
+        accepted += verdict.accept as u32;
+        notices.extend(verdict.notice);
+        suspicion.extend(verdict.suspicion);
+    }
+    (accepted, notices, suspicion)
+}
+
+#[test]
+fn inputs_past_the_limit_are_dropped_and_reported_next_window() {
+    let mut monitor = ClientRateMonitor::default();
+    let player = Uuid::new_v4();
+
+    let (accepted, notices, _) = flood_second(&mut monitor, player, 0, 200);
+    assert_eq!(accepted, 120);
+    assert!(notices.is_empty());
+
+    let (accepted, notices, _) = flood_second(&mut monitor, player, 1, 60);
+    assert_eq!(accepted, 60);
+    assert_eq!(
+        notices,
+        vec![RateLimit {
+            allowed_hz: 120,
+            excess_inputs_dropped: 80,
+        }]
+    );
+
+    // Other players have their own window
+    let (accepted, notices, _) = flood_second(&mut monitor, Uuid::new_v4(), 1, 100);
+    assert_eq!((accepted, notices.len()), (100, 0));
+}
+
+#[test]
+fn sustained_flooding_raises_suspicion() {
+    let mut monitor = ClientRateMonitor::default();
+    let player = Uuid::new_v4();
+    let mut raised = Vec::new();
+    for second in 0..=2 * RATE_LIMITS_PER_SUSPICION as u64 {
+        raised.extend(flood_second(&mut monitor, player, second, 200).2);
+    }
+    assert_eq!(raised, vec![SuspicionLevel(1), SuspicionLevel(2)]);
+
+    // A clean second resets the streak
+    let mut monitor = ClientRateMonitor::default();
+    let mut raised = Vec::new();
+    for second in 0..=RATE_LIMITS_PER_SUSPICION as u64 {
+        let count = if second == 5 { 10 } else { 200 };
+        raised.extend(flood_second(&mut monitor, player, second, count).2);
+    }
+    assert!(raised.is_empty());
+}
@@ -0,0 +1,92 @@
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use protocol::rendezvous::RendezvousMessage;
+use server::rendezvous::{RendezvousServer, REGISTRATION_TTL};
+
+fn addr(s: &str) -> SocketAddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn query_returns_the_first_registered_other_peer() {
+    let token = [7; 32];
+    let (host, client, other) = (
+        addr("1.2.3.4:5000"),
+        addr("5.6.7.8:40000"),
+        addr("9.9.9.9:1"),
+    );
+    let now = Instant::now();
+    let mut rv = RendezvousServer::default();
+    let query = RendezvousMessage::Query { token };
+
+    assert_eq!(
+        rv.handle(query.clone(), client, now),
+        Some(RendezvousMessage::Peer { token, addr: None })
+    );
+    let register = RendezvousMessage::Register {
+        token,
+        external_addr: None,
+    };
+    assert_eq!(rv.handle(register.clone(), host, now), None);
+    assert_eq!(rv.handle(register.clone(), client, now), None);
+    assert_eq!(rv.handle(register, client, now), None);
+    assert_eq!(rv.peer_count(&token), 2);
+
+    assert_eq!(
+        rv.handle(query.clone(), client, now),
+        Some(RendezvousMessage::Peer {
+            token,
+            addr: Some(host)
+        })
+    );
+    // The host itself is pointed at the client, and other sessions see nothing
+    assert_eq!(
+        rv.handle(query, host, now),
+        Some(RendezvousMessage::Peer {
+            token,
+            addr: Some(client)
+        })
+    );
+    assert_eq!(
+        rv.handle(RendezvousMessage::Query { token: [8; 32] }, other, now),
+        Some(RendezvousMessage::Peer {
+            token: [8; 32],
+            addr: None
+        })
+    );
+}
+
+#[test]
+fn advertised_address_is_used_and_stale_registrations_expire() {
+    let token = [1; 32];
+    let (host, public, client) = (
+        addr("10.0.0.2:61234"),
+        addr("203.0.113.9:61234"),
+        addr("5.6.7.8:40000"),
+    );
+    let start = Instant::now();
+    let mut rv = RendezvousServer::default();
+    rv.handle(
+        RendezvousMessage::Register {
+            token,
+            external_addr: Some(public),
+        },
+        host,
+        start,
+    );
+    let query = RendezvousMessage::Query { token };
+    assert_eq!(
+        rv.handle(query.clone(), client, start),
+        Some(RendezvousMessage::Peer {
+            token,
+            addr: Some(public)
+        })
+    );
+
+    assert_eq!(
+        rv.handle(query, client, start + REGISTRATION_TTL),
+        Some(RendezvousMessage::Peer { token, addr: None })
+    );
+    assert_eq!(rv.peer_count(&token), 0);
+}
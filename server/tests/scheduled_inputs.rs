@@ -0,0 +1,65 @@
+use bevy::prelude::Entity;
+use protocol::InputEvent;
+use server::{ScheduledInputQueue, SCHEDULED_INPUT_MAX_AGE_MS};
+
+fn event(t_ms: u64, thrust: f32) -> InputEvent {
+    InputEvent {
+        t_ms,
+        thrust,
+        yaw: 0.0,
+        pump_fwd: 0.0,
+        pump_aft: 0.0,
+        pitch: 0.0,
+        roll_trim: 0.0,
+    }
+}
+
+#[test]
+fn event_is_applied_within_10ms_of_its_time() {
+    let sub = Entity::from_raw(7);
+    // Ticks at 120 Hz from an arbitrary server clock offset
+    let server_ms = 12_345;
+    let target = server_ms + 100;
+    let mut queue = ScheduledInputQueue::default();
+    queue.push(sub, event(target, 0.0));
+
+    let mut applied_at = None;
+    for tick in 0..60 {
+        let now_ms = server_ms + tick * 1000 / 120;
+        let due = queue.drain_due(now_ms);
+        if !due.is_empty() {
+            assert_eq!(due.len(), 1);
+            assert_eq!(due[0].0, sub);
+            applied_at = Some(now_ms);
+            break;
+        }
+    }
+    let applied_at = applied_at.expect("event never came due");
+    assert!(
+        (target..=target + 10).contains(&applied_at),
+        "applied at {applied_at}, target {target}"
+    );
+    assert!(queue.0.is_empty());
+}
+
+#[test]
+fn due_events_drain_in_time_order_and_stale_ones_are_dropped() {
+    let sub = Entity::from_raw(3);
+    let now_ms = 10_000;
+    let mut queue = ScheduledInputQueue::default();
+    queue.push(sub, event(now_ms + 5, 0.5));
+    queue.push(sub, event(now_ms - 20, 0.2));
+    queue.push(sub, event(now_ms - SCHEDULED_INPUT_MAX_AGE_MS - 1, 0.1));
+    queue.push(sub, event(now_ms, 0.3));
+
+    let due: Vec<f32> = queue
+        .drain_due(now_ms)
+        .into_iter()
+        .map(|(_, ev)| ev.thrust)
+        .collect();
+    assert_eq!(due, vec![0.2, 0.3]);
+    // The future event stays queued
+    assert_eq!(queue.0.len(), 1);
+    assert!(queue.drain_due(now_ms + 4).is_empty());
+    assert_eq!(queue.drain_due(now_ms + 5).len(), 1);
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use levels::{DensityProfile, Quatf, SubInputState, SubState, Vec3f, REFERENCE_SOURCE_LEVEL_DB};
+use server::sonar::{
+    acoustic_level_db, sonar_contacts, SonarPingCooldown, SonarTarget, SONAR_PING_COOLDOWN,
+};
+use uuid::Uuid;
+
+const UNIFORM: DensityProfile = DensityProfile::Linear {
+    density_at_zero: 1025.0,
+    gradient_per_m: 0.0,
+};
+
+fn target(id: u128, position: Vec3f) -> SonarTarget {
+    SonarTarget {
+        player_id: Uuid::from_u128(id),
+        position,
+        source_level_db: REFERENCE_SOURCE_LEVEL_DB,
+    }
+}
+
+#[test]
+fn ping_reports_subs_in_range_nearest_first() {
+    let targets = [
+        target(1, Vec3f::new(120.0, 0.0, 0.0)),
+        target(2, Vec3f::new(0.0, 0.0, -40.0)),
+        target(3, Vec3f::new(200.0, 0.0, 0.0)),
+    ];
+    let contacts = sonar_contacts(Vec3f::ZERO, targets, &UNIFORM, 150.0, 0.0);
+    let ids: Vec<_> = contacts.iter().map(|c| c.player_id).collect();
+    assert_eq!(ids, [Uuid::from_u128(2), Uuid::from_u128(1)]);
+    assert_eq!(contacts[0].distance_m, 40.0);
+    assert_eq!(contacts[0].position, [0.0, 0.0, -40.0]);
+}
+
+#[test]
+fn thermocline_hides_a_sub_heard_through_uniform_water() {
+    let thermocline = DensityProfile::Thermocline {
+        height: -20.0,
+        upper_density: 1000.0,
+        lower_density: 1100.0,
+    };
+    let below = [target(1, Vec3f::new(0.0, -100.0, 0.0))];
+    assert_eq!(
+        sonar_contacts(Vec3f::ZERO, below, &UNIFORM, 150.0, 0.0).len(),
+        1
+    );
+    assert!(sonar_contacts(Vec3f::ZERO, below, &thermocline, 150.0, 0.0).is_empty());
+}
+
+#[test]
+fn self_noise_at_speed_masks_near_contacts() {
+    let spec = levels::subspecs::small_skiff_spec();
+    let targets = [
+        target(1, Vec3f::new(5.0, 0.0, 0.0)),
+        target(2, Vec3f::new(100.0, 0.0, 0.0)),
+    ];
+    let heard = |speed| {
+        let masking = spec.noise_masking_range(speed);
+        sonar_contacts(Vec3f::ZERO, targets, &UNIFORM, 150.0, masking)
+            .iter()
+            .map(|c| c.player_id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(heard(0.0), [Uuid::from_u128(1), Uuid::from_u128(2)]);
+    let flat_out = spec.max_speed_m_s();
+    assert!(spec.noise_masking_range(flat_out) > 5.0);
+    assert_eq!(heard(flat_out), [Uuid::from_u128(2)]);
+}
+
+#[test]
+fn full_thrust_sub_is_heard_further_than_an_idle_one() {
+    let spec = levels::subspecs::small_skiff_spec();
+    let position = Vec3f::new(140.0, 0.0, 0.0);
+    let state = SubState {
+        position,
+        velocity: Vec3f::ZERO,
+        orientation: Quatf::IDENTITY,
+        ang_mom: Vec3f::ZERO,
+        ballast_fill: vec![0.5; spec.ballast_tanks.len()],
+        pump: Default::default(),
+    };
+    let heard = |thrust| {
+        let inputs = SubInputState {
+            thrust,
+            ..Default::default()
+        };
+        let loud = SonarTarget {
+            source_level_db: acoustic_level_db(&spec, &state, &inputs),
+            ..target(1, position)
+        };
+        !sonar_contacts(Vec3f::ZERO, [loud], &UNIFORM, 150.0, 0.0).is_empty()
+    };
+    assert!(!heard(0.0), "idle skiff heard at 140 m");
+    assert!(heard(1.0), "full-thrust skiff not heard at 140 m");
+}
+
+#[test]
+fn pings_inside_the_cooldown_are_dropped() {
+    let mut cooldown = SonarPingCooldown::default();
+    let start = Duration::from_secs(10);
+    assert!(cooldown.try_ping(start));
+    assert!(!cooldown.try_ping(start + SONAR_PING_COOLDOWN / 2));
+    // A dropped ping doesn't push the next one back
+    assert!(cooldown.try_ping(start + SONAR_PING_COOLDOWN));
+    assert!(!cooldown.try_ping(start + SONAR_PING_COOLDOWN));
+}
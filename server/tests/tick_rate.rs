@@ -0,0 +1,62 @@
+use server::tick_rate::{TickRateGovernor, MIN_TICK_HZ, TICK_RATE_WINDOW_S};
+
+/// Run one full window of frames that each took `busy_s`, returning any rate change.
+fn run_window(governor: &mut TickRateGovernor, busy_s: f32) -> Option<u32> {
+    // A quarter second sums to the window length exactly in f32
+    let dt = 0.25;
+    let frames = (TICK_RATE_WINDOW_S / dt).ceil() as usize;
+    let changes: Vec<u32> = (0..frames)
+        .filter_map(|_| governor.record_frame(busy_s, dt))
+        .collect();
+    assert!(changes.len() <= 1, "{changes:?}");
+    changes.first().copied()
+}
+
+#[test]
+fn sustained_load_halves_down_to_the_minimum() {
+    let mut governor = TickRateGovernor::new(60);
+    // 40 ms frames are over 1.5 steps at 60 Hz (25 ms) but not at 30 Hz (50 ms)
+    assert_eq!(run_window(&mut governor, 0.040), None);
+    assert_eq!(run_window(&mut governor, 0.040), None);
+    assert_eq!(run_window(&mut governor, 0.040), Some(30));
+    assert_eq!(governor.current_hz, 30);
+    for _ in 0..5 {
+        assert_eq!(run_window(&mut governor, 0.040), None);
+    }
+
+    for _ in 0..2 {
+        assert_eq!(run_window(&mut governor, 1.0), None);
+    }
+    assert_eq!(run_window(&mut governor, 1.0), Some(15));
+    for _ in 0..2 {
+        assert_eq!(run_window(&mut governor, 1.0), None);
+    }
+    assert_eq!(run_window(&mut governor, 1.0), Some(MIN_TICK_HZ));
+    for _ in 0..6 {
+        assert_eq!(run_window(&mut governor, 1.0), None);
+    }
+    assert_eq!(governor.current_hz, MIN_TICK_HZ);
+}
+
+#[test]
+fn recovery_doubles_up_to_the_configured_rate() {
+    let mut governor = TickRateGovernor::new(60);
+    for _ in 0..3 {
+        run_window(&mut governor, 0.1);
+    }
+    assert_eq!(governor.current_hz, 30);
+
+    // A normal window in between restarts the count
+    for _ in 0..4 {
+        assert_eq!(run_window(&mut governor, 0.001), None);
+    }
+    assert_eq!(run_window(&mut governor, 0.030), None);
+    for _ in 0..4 {
+        assert_eq!(run_window(&mut governor, 0.001), None);
+    }
+    assert_eq!(run_window(&mut governor, 0.001), Some(60));
+    for _ in 0..10 {
+        assert_eq!(run_window(&mut governor, 0.001), None);
+    }
+    assert_eq!(governor.current_hz, 60);
+}